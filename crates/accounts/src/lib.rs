@@ -32,7 +32,11 @@ use std::{
     time::{Duration, Instant},
 };
 
-use cfxkey::{Address, Generator, Message, Password, Public, Random, Secret};
+use cfx_types::AddressUtil;
+use cfxkey::{
+    public_to_address, recover, Address, Generator, Message, Password, Public,
+    Random, Secret,
+};
 use cfxstore::{
     accounts_dir::MemoryDirectory, random_string, CfxMultiStore, CfxStore,
     OpaqueSecret, SecretStore, SecretVaultRef, SimpleSecretStore,
@@ -494,6 +498,26 @@ impl AccountProvider {
         }
     }
 
+    /// Signs the message with the account of the eSpace address. If password
+    /// is not provided the account must be unlocked.
+    ///
+    /// The accounts are indexed by their Core Space addresses, which only
+    /// differ from the eSpace addresses in the type bits, so the signer is
+    /// recovered to check that it owns the eSpace address.
+    pub fn sign_evm(
+        &self, address: Address, password: Option<Password>, message: Message,
+    ) -> Result<Signature, SignError> {
+        let mut core_address = address;
+        core_address.set_user_account_type_bits();
+        let signature = self.sign(core_address, password, message)?;
+        match recover(&signature, &message) {
+            Ok(public) if public_to_address(&public, false) == address => {
+                Ok(signature)
+            }
+            _ => Err(SignError::NotFound),
+        }
+    }
+
     /// Signs message using the derived secret. If password is not provided the
     /// account must be unlocked.
     pub fn sign_derived(
@@ -708,6 +732,26 @@ mod tests {
         assert!(ap.sign(kp.address(), None, Default::default()).is_err());
     }
 
+    #[test]
+    fn sign_evm() {
+        let kp = Random.generate().unwrap();
+        let ap = AccountProvider::transient_provider();
+        assert!(ap
+            .insert_account(kp.secret().clone(), &"test".into())
+            .is_ok());
+        assert!(ap
+            .unlock_account_permanently(kp.address(), "test".into())
+            .is_ok());
+
+        let message = H256::from_low_u64_be(1);
+        let signature = ap.sign_evm(kp.evm_address(), None, message).unwrap();
+        assert_eq!(signature, ap.sign(kp.address(), None, message).unwrap());
+
+        let mut other = kp.evm_address();
+        other.0[0] ^= 0x20;
+        assert!(ap.sign_evm(other, None, message).is_err());
+    }
+
     #[test]
     fn derived_account_nosave() {
         let kp = Random.generate().unwrap();
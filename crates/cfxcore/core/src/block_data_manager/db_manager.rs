@@ -1,14 +1,16 @@
 use crate::{
     block_data_manager::{
-        db_decode_list, db_encode_list, BlamedHeaderVerifiedRoots,
+        db_decode_list, db_encode_list, AddressTxIndexEntry,
+        AddressTxIndexRange, BlamedHeaderVerifiedRoots,
         BlockExecutionResultWithEpoch, BlockRewardResult, BlockTracesWithEpoch,
-        CheckpointHashes, DataVersionTuple, EpochExecutionContext,
-        LocalBlockInfo, PosRewardInfo,
+        CheckpointHashes, DataVersionTuple, EpochAddressTxIndex,
+        EpochExecutionContext, EpochLogIndex, LocalBlockInfo, LogIndexEntry,
+        LogIndexRange, PosRewardInfo,
     },
     db::{
-        COL_BLAMED_HEADER_VERIFIED_ROOTS, COL_BLOCKS, COL_BLOCK_TRACES,
-        COL_EPOCH_NUMBER, COL_HASH_BY_BLOCK_NUMBER, COL_MISC,
-        COL_REWARD_BY_POS_EPOCH, COL_TX_INDEX,
+        COL_ADDRESS_TX_INDEX, COL_BLAMED_HEADER_VERIFIED_ROOTS, COL_BLOCKS,
+        COL_BLOCK_TRACES, COL_EPOCH_NUMBER, COL_HASH_BY_BLOCK_NUMBER,
+        COL_LOG_INDEX, COL_MISC, COL_REWARD_BY_POS_EPOCH, COL_TX_INDEX,
     },
     pow::PowComputer,
     verification::VerificationConfig,
@@ -18,9 +20,10 @@ use cfx_internal_common::{
     DatabaseDecodable, DatabaseEncodable, EpochExecutionCommitment,
 };
 use cfx_storage::{
-    storage_db::KeyValueDbTrait, KvdbRocksdb, KvdbSqlite, KvdbSqliteStatements,
+    storage_db::{KeyValueDbTrait, KeyValueDbTraitTransactionalDyn},
+    KvdbRocksdb, KvdbSqlite, KvdbSqliteStatements,
 };
-use cfx_types::H256;
+use cfx_types::{AddressWithSpace, Space, H256};
 use db::SystemDB;
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use primitives::{Block, BlockHeader, SignedTransaction, TransactionIndex};
@@ -39,6 +42,10 @@ const EPOCH_SKIPPED_BLOCK_SET_SUFFIX_BYTE: u8 = 7;
 const BLOCK_REWARD_RESULT_SUFFIX_BYTE: u8 = 8;
 const BLOCK_TERMINAL_KEY: &[u8] = b"block_terminals";
 const GC_PROGRESS_KEY: &[u8] = b"gc_progress";
+/// The bounds of the epochs covered by the log index, kept in the same table
+/// as the index so that they are updated in the same transaction.
+const LOG_INDEX_EARLIEST_EPOCH_KEY: &[u8] = b"log_index_earliest_epoch";
+const LOG_INDEX_LATEST_EPOCH_KEY: &[u8] = b"log_index_latest_epoch";
 
 #[derive(Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq, EnumIter)]
 enum DBTable {
@@ -50,6 +57,8 @@ enum DBTable {
     BlockTraces,
     HashByBlockNumber,
     RewardByPosEpoch,
+    AddressTxIndex,
+    LogIndex,
 }
 
 fn rocks_db_col(table: DBTable) -> u32 {
@@ -62,6 +71,8 @@ fn rocks_db_col(table: DBTable) -> u32 {
         DBTable::BlockTraces => COL_BLOCK_TRACES,
         DBTable::HashByBlockNumber => COL_HASH_BY_BLOCK_NUMBER,
         DBTable::RewardByPosEpoch => COL_REWARD_BY_POS_EPOCH,
+        DBTable::AddressTxIndex => COL_ADDRESS_TX_INDEX,
+        DBTable::LogIndex => COL_LOG_INDEX,
     }
 }
 
@@ -75,15 +86,35 @@ fn sqlite_db_table(table: DBTable) -> String {
         DBTable::BlockTraces => "block_traces",
         DBTable::HashByBlockNumber => "hash_by_block_number",
         DBTable::RewardByPosEpoch => "reward_by_pos_epoch",
+        DBTable::AddressTxIndex => "address_tx_index",
+        DBTable::LogIndex => "log_index",
     }
     .into()
 }
 
+/// The database of a table, which can also write a [`WriteBatch`] in one
+/// transaction.
+trait TableDb:
+    KeyValueDbTrait<ValueType = Box<[u8]>> + KeyValueDbTraitTransactionalDyn
+{
+}
+
+impl<T> TableDb for T where T: KeyValueDbTrait<ValueType = Box<[u8]>> + KeyValueDbTraitTransactionalDyn
+{}
+
 pub struct DBManager {
-    table_db: HashMap<DBTable, Box<dyn KeyValueDbTrait<ValueType = Box<[u8]>>>>,
+    table_db: HashMap<DBTable, Box<dyn TableDb>>,
     pow: Arc<PowComputer>,
 }
 
+/// The changes written by [`DBManager::write_batch`], so a reader never sees
+/// a part of them.
+#[derive(Default)]
+pub struct WriteBatch {
+    /// The values to write, or `None` for the keys to remove.
+    changes: Vec<(DBTable, Vec<u8>, Option<Vec<u8>>)>,
+}
+
 impl DBManager {
     pub fn new_from_rocksdb(db: Arc<SystemDB>, pow: Arc<PowComputer>) -> Self {
         let mut table_db = HashMap::new();
@@ -94,8 +125,7 @@ impl DBManager {
                 Box::new(KvdbRocksdb {
                     kvdb: db.key_value().clone(),
                     col: rocks_db_col(table),
-                })
-                    as Box<dyn KeyValueDbTrait<ValueType = Box<[u8]>>>,
+                }) as Box<dyn TableDb>,
             );
         }
         Self { table_db, pow }
@@ -125,11 +155,7 @@ impl DBManager {
                 false, /* unsafe_mode */
             )
             .expect("Open sqlite failure");
-            table_db.insert(
-                table,
-                Box::new(sqlite_db)
-                    as Box<dyn KeyValueDbTrait<ValueType = Box<[u8]>>>,
-            );
+            table_db.insert(table, Box::new(sqlite_db) as Box<dyn TableDb>);
         }
         Self { table_db, pow }
     }
@@ -505,6 +531,82 @@ impl DBManager {
         )
     }
 
+    pub fn address_tx_index_range_from_db(
+        &self, address: &AddressWithSpace,
+    ) -> Option<AddressTxIndexRange> {
+        self.load_decodable_val(
+            DBTable::AddressTxIndex,
+            &address_tx_index_range_key(address),
+        )
+    }
+
+    pub fn address_tx_index_entry_from_db(
+        &self, address: &AddressWithSpace, position: u64,
+    ) -> Option<AddressTxIndexEntry> {
+        self.load_decodable_val(
+            DBTable::AddressTxIndex,
+            &address_tx_index_entry_key(address, position),
+        )
+    }
+
+    pub fn epoch_address_tx_index_from_db(
+        &self, epoch: u64,
+    ) -> Option<EpochAddressTxIndex> {
+        self.load_decodable_val(DBTable::AddressTxIndex, &epoch.to_be_bytes())
+    }
+
+    pub fn log_index_range_from_db(&self, key: &[u8]) -> Option<LogIndexRange> {
+        self.load_decodable_val(DBTable::LogIndex, key)
+    }
+
+    pub fn log_index_entry_from_db(
+        &self, key: &[u8], position: u64,
+    ) -> Option<LogIndexEntry> {
+        self.load_decodable_val(
+            DBTable::LogIndex,
+            &log_index_entry_key(key, position),
+        )
+    }
+
+    pub fn epoch_log_index_from_db(&self, epoch: u64) -> Option<EpochLogIndex> {
+        self.load_decodable_val(DBTable::LogIndex, &epoch.to_be_bytes())
+    }
+
+    pub fn log_index_earliest_epoch_from_db(&self) -> Option<u64> {
+        self.load_decodable_val(DBTable::LogIndex, LOG_INDEX_EARLIEST_EPOCH_KEY)
+    }
+
+    pub fn log_index_latest_epoch_from_db(&self) -> Option<u64> {
+        self.load_decodable_val(DBTable::LogIndex, LOG_INDEX_LATEST_EPOCH_KEY)
+    }
+
+    /// Writes the changes of the batch, in one transaction for each table.
+    pub fn write_batch(&self, batch: WriteBatch) {
+        let mut changes_by_table: HashMap<DBTable, Vec<_>> = HashMap::new();
+        for (table, key, value) in batch.changes {
+            changes_by_table
+                .entry(table)
+                .or_default()
+                .push((key, value));
+        }
+        for (table, changes) in changes_by_table {
+            let db = self.table_db.get(&table).unwrap();
+            let mut transaction = db
+                .start_transaction_dyn(true /* immediate_write */)
+                .expect("db transaction failure");
+            for (key, value) in changes {
+                match value {
+                    Some(value) => transaction.put(&key, &value),
+                    None => transaction.delete(&key),
+                }
+                .expect("db batch write failure");
+            }
+            transaction
+                .commit(db.as_any())
+                .expect("db transaction commit failure");
+        }
+    }
+
     /// The functions below are private utils used by the DBManager to access
     /// database
     fn insert_to_db(&self, table: DBTable, db_key: &[u8], value: Vec<u8>) {
@@ -603,6 +705,140 @@ fn skipped_epoch_set_key(epoch_number: u64) -> [u8; 9] {
     epoch_key
 }
 
+impl WriteBatch {
+    pub fn insert_address_tx_index_range(
+        &mut self, address: &AddressWithSpace, range: &AddressTxIndexRange,
+    ) {
+        self.insert_encodable_val(
+            DBTable::AddressTxIndex,
+            address_tx_index_range_key(address),
+            range,
+        )
+    }
+
+    pub fn insert_address_tx_index_entry(
+        &mut self, address: &AddressWithSpace, position: u64,
+        entry: &AddressTxIndexEntry,
+    ) {
+        self.insert_encodable_val(
+            DBTable::AddressTxIndex,
+            address_tx_index_entry_key(address, position),
+            entry,
+        )
+    }
+
+    pub fn remove_address_tx_index_entry(
+        &mut self, address: &AddressWithSpace, position: u64,
+    ) {
+        self.remove(
+            DBTable::AddressTxIndex,
+            address_tx_index_entry_key(address, position),
+        )
+    }
+
+    pub fn insert_epoch_address_tx_index(
+        &mut self, epoch: u64, value: &EpochAddressTxIndex,
+    ) {
+        self.insert_encodable_val(
+            DBTable::AddressTxIndex,
+            epoch.to_be_bytes().to_vec(),
+            value,
+        )
+    }
+
+    pub fn remove_epoch_address_tx_index(&mut self, epoch: u64) {
+        self.remove(DBTable::AddressTxIndex, epoch.to_be_bytes().to_vec())
+    }
+
+    pub fn insert_log_index_range(
+        &mut self, key: &[u8], range: &LogIndexRange,
+    ) {
+        self.insert_encodable_val(DBTable::LogIndex, key.to_vec(), range)
+    }
+
+    pub fn insert_log_index_entry(
+        &mut self, key: &[u8], position: u64, entry: &LogIndexEntry,
+    ) {
+        self.insert_encodable_val(
+            DBTable::LogIndex,
+            log_index_entry_key(key, position),
+            entry,
+        )
+    }
+
+    pub fn remove_log_index_entry(&mut self, key: &[u8], position: u64) {
+        self.remove(DBTable::LogIndex, log_index_entry_key(key, position))
+    }
+
+    pub fn insert_epoch_log_index(
+        &mut self, epoch: u64, value: &EpochLogIndex,
+    ) {
+        self.insert_encodable_val(
+            DBTable::LogIndex,
+            epoch.to_be_bytes().to_vec(),
+            value,
+        )
+    }
+
+    pub fn remove_epoch_log_index(&mut self, epoch: u64) {
+        self.remove(DBTable::LogIndex, epoch.to_be_bytes().to_vec())
+    }
+
+    pub fn insert_log_index_earliest_epoch(&mut self, epoch: u64) {
+        self.insert_encodable_val(
+            DBTable::LogIndex,
+            LOG_INDEX_EARLIEST_EPOCH_KEY.to_vec(),
+            &epoch,
+        )
+    }
+
+    pub fn insert_log_index_latest_epoch(&mut self, epoch: u64) {
+        self.insert_encodable_val(
+            DBTable::LogIndex,
+            LOG_INDEX_LATEST_EPOCH_KEY.to_vec(),
+            &epoch,
+        )
+    }
+
+    fn insert_encodable_val<V>(
+        &mut self, table: DBTable, db_key: Vec<u8>, value: &V,
+    ) where V: DatabaseEncodable {
+        self.changes.push((table, db_key, Some(value.db_encode())))
+    }
+
+    fn remove(&mut self, table: DBTable, db_key: Vec<u8>) {
+        self.changes.push((table, db_key, None))
+    }
+}
+
+/// The key of the entry range of an address, which is the space byte followed
+/// by the address. The epoch records of the same table use 8-byte keys, so
+/// they never collide with the address keys.
+fn address_tx_index_range_key(address: &AddressWithSpace) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + 20 + 8);
+    key.push(match address.space {
+        Space::Native => 0,
+        Space::Ethereum => 1,
+    });
+    key.extend_from_slice(address.address.as_bytes());
+    key
+}
+
+fn address_tx_index_entry_key(
+    address: &AddressWithSpace, position: u64,
+) -> Vec<u8> {
+    let mut key = address_tx_index_range_key(address);
+    key.extend_from_slice(&position.to_be_bytes());
+    key
+}
+
+fn log_index_entry_key(key: &[u8], position: u64) -> Vec<u8> {
+    let mut entry_key = Vec::with_capacity(key.len() + 8);
+    entry_key.extend_from_slice(key);
+    entry_key.extend_from_slice(&position.to_be_bytes());
+    entry_key
+}
+
 fn block_execution_result_key(hash: &H256) -> Vec<u8> {
     append_suffix(hash, BLOCK_EXECUTION_RESULT_SUFFIX_BYTE)
 }
@@ -0,0 +1,679 @@
+use super::{
+    db_manager::{DBManager, WriteBatch},
+    BlockDataManager, EpochLogIndex, LogIndexEntry, LogIndexRange,
+};
+use cfx_types::{Address, Space, H256};
+use primitives::BlockReceipts;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+/// The number of epochs indexed by the backfill before it releases the data
+/// manager and sleeps for `LOG_INDEX_BACKFILL_INTERVAL`.
+const LOG_INDEX_BACKFILL_BATCH_SIZE: usize = 100;
+const LOG_INDEX_BACKFILL_INTERVAL: Duration = Duration::from_millis(100);
+/// The interval to check again if no epoch has been indexed yet.
+const LOG_INDEX_BACKFILL_IDLE_INTERVAL: Duration = Duration::from_secs(5);
+
+impl BlockDataManager {
+    /// Replaces the log index of the epoch `epoch_number` with the logs in
+    /// `epoch_receipts`, which are the receipts of the blocks of the epoch in
+    /// execution order. As in `insert_address_tx_index`, the entries of this
+    /// epoch and the later epochs left by a previous pivot chain are removed
+    /// first.
+    ///
+    /// The index covers the epochs from the earliest indexed epoch to this
+    /// epoch. If the epoch is before the earliest one or not right after the
+    /// latest one, e.g., the epochs in between were executed with the index
+    /// disabled, the index starts again from this epoch.
+    pub fn insert_log_index(
+        &self, epoch_number: u64, epoch_receipts: &[Arc<BlockReceipts>],
+    ) {
+        if !self.config.persist_log_index {
+            return;
+        }
+        let keys = log_index_keys(epoch_receipts);
+        let _guard = self.log_index_lock.lock();
+
+        let mut batch = WriteBatch::default();
+        let mut ranges = BTreeMap::new();
+        let stale_keys = self
+            .db_manager
+            .epoch_log_index_from_db(epoch_number)
+            .map_or(vec![], |record| record.keys);
+        for key in stale_keys.into_iter().chain(keys.iter().cloned()) {
+            if ranges.contains_key(&key) {
+                continue;
+            }
+            let mut range = self
+                .db_manager
+                .log_index_range_from_db(&key)
+                .unwrap_or_default();
+            while range.end > range.start {
+                match self
+                    .db_manager
+                    .log_index_entry_from_db(&key, range.end - 1)
+                {
+                    Some(entry) if entry.epoch_number < epoch_number => break,
+                    _ => {
+                        range.end -= 1;
+                        batch.remove_log_index_entry(&key, range.end);
+                    }
+                }
+            }
+            ranges.insert(key, range);
+        }
+
+        for key in &keys {
+            let range: &mut LogIndexRange =
+                ranges.get_mut(key).expect("inserted above");
+            batch.insert_log_index_entry(
+                key,
+                range.end,
+                &LogIndexEntry { epoch_number },
+            );
+            range.end += 1;
+        }
+        for (key, range) in &ranges {
+            batch.insert_log_index_range(key, range);
+        }
+        insert_epoch_log_index(&mut batch, epoch_number, keys);
+
+        let continuous = match (
+            self.db_manager.log_index_earliest_epoch_from_db(),
+            self.db_manager.log_index_latest_epoch_from_db(),
+        ) {
+            (Some(earliest), Some(latest)) => {
+                earliest <= epoch_number && epoch_number <= latest + 1
+            }
+            _ => false,
+        };
+        if !continuous {
+            batch.insert_log_index_earliest_epoch(epoch_number);
+        }
+        batch.insert_log_index_latest_epoch(epoch_number);
+        self.db_manager.write_batch(batch);
+    }
+
+    /// Returns the epochs in `[from_epoch, to_epoch]` that have logs of
+    /// `space` matching `address` or one of the `topics`, from the newest.
+    /// Only the addresses or the topics of one position are looked up, so the
+    /// epochs may still have no matching logs, and the logs must be filtered
+    /// as without the index.
+    ///
+    /// `None` is returned if the index is disabled, the index does not cover
+    /// `from_epoch` yet, or neither an address nor a topic is given.
+    pub fn log_index_epochs(
+        &self, space: Space, address: &Option<Vec<Address>>,
+        topics: &Vec<Option<Vec<H256>>>, from_epoch: u64, to_epoch: u64,
+    ) -> Option<LogIndexEpochs<'_>> {
+        if !self.config.persist_log_index {
+            return None;
+        }
+        match self.db_manager.log_index_earliest_epoch_from_db() {
+            Some(earliest) if earliest <= from_epoch => {}
+            _ => return None,
+        }
+
+        let mut candidates: Vec<Vec<Vec<u8>>> = Vec::new();
+        if let Some(addresses) = address.as_ref().filter(|a| !a.is_empty()) {
+            candidates.push(
+                addresses
+                    .iter()
+                    .map(|address| log_index_key(space, 0, address.as_bytes()))
+                    .collect(),
+            );
+        }
+        for (i, topics) in topics.iter().enumerate() {
+            if let Some(topics) = topics.as_ref().filter(|t| !t.is_empty()) {
+                candidates.push(
+                    topics
+                        .iter()
+                        .map(|topic| {
+                            log_index_key(space, i as u8 + 1, topic.as_bytes())
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        // Look up the addresses or the topics with the fewest entries in the
+        // range, which are counted by the positions of the range bounds.
+        candidates
+            .into_iter()
+            .map(|keys| {
+                let cursors: Vec<_> = keys
+                    .into_iter()
+                    .map(|key| self.log_index_cursor(key, from_epoch, to_epoch))
+                    .collect();
+                let count: u64 = cursors.iter().map(|c| c.end - c.start).sum();
+                (count, cursors)
+            })
+            .min_by_key(|(count, _)| *count)
+            .map(|(_, cursors)| {
+                LogIndexEpochs::new(&self.db_manager, cursors, from_epoch)
+            })
+    }
+
+    fn log_index_cursor(
+        &self, key: Vec<u8>, from_epoch: u64, to_epoch: u64,
+    ) -> LogIndexCursor {
+        let range = self
+            .db_manager
+            .log_index_range_from_db(&key)
+            .unwrap_or_default();
+        let start = match from_epoch.checked_sub(1) {
+            Some(before_from) => {
+                self.log_index_position_after(&key, range, before_from)
+            }
+            None => range.start,
+        };
+        let end = self.log_index_position_after(&key, range, to_epoch);
+        LogIndexCursor {
+            key,
+            start,
+            end: end.max(start),
+            head: None,
+        }
+    }
+
+    /// Returns the first position in `range` whose entry is in an epoch after
+    /// `epoch_number`.
+    fn log_index_position_after(
+        &self, key: &[u8], range: LogIndexRange, epoch_number: u64,
+    ) -> u64 {
+        let (mut low, mut high) = (range.start, range.end);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            // A missing entry has been removed by GC after reading the range,
+            // so it is in an earlier epoch than the entries left.
+            let mid_epoch = self
+                .db_manager
+                .log_index_entry_from_db(key, mid)
+                .map_or(0, |entry| entry.epoch_number);
+            if mid_epoch > epoch_number {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        low
+    }
+
+    /// Removes the log index entries of the epoch `epoch_number` and the
+    /// epochs before it in one batch.
+    pub(super) fn remove_log_index(&self, epoch_number: u64) {
+        if !self.config.persist_log_index {
+            return;
+        }
+        let _guard = self.log_index_lock.lock();
+
+        let record = match self.db_manager.epoch_log_index_from_db(epoch_number)
+        {
+            Some(record) => record,
+            None => return,
+        };
+        let mut batch = WriteBatch::default();
+        for key in record.keys {
+            let mut range = match self.db_manager.log_index_range_from_db(&key)
+            {
+                Some(range) => range,
+                None => continue,
+            };
+            while range.start < range.end {
+                match self.db_manager.log_index_entry_from_db(&key, range.start)
+                {
+                    Some(entry) if entry.epoch_number > epoch_number => break,
+                    _ => {
+                        batch.remove_log_index_entry(&key, range.start);
+                        range.start += 1;
+                    }
+                }
+            }
+            batch.insert_log_index_range(&key, &range);
+        }
+        batch.remove_epoch_log_index(epoch_number);
+        self.db_manager.write_batch(batch);
+    }
+
+    /// Starts a thread to index the logs of the epochs executed before the log
+    /// index was enabled. It goes backwards from the earliest indexed epoch
+    /// until the genesis or an epoch whose receipts have been removed.
+    pub fn start_log_index_backfill(data_man: &Arc<BlockDataManager>) {
+        if !data_man.config.persist_log_index {
+            return;
+        }
+        // Only keep a weak reference so that the data manager can be released
+        // on shutdown.
+        let data_man = Arc::downgrade(data_man);
+        thread::Builder::new()
+            .name("log_index_backfill".into())
+            .spawn(move || loop {
+                let interval = match data_man.upgrade() {
+                    Some(data_man) => {
+                        match data_man
+                            .backfill_log_index(LOG_INDEX_BACKFILL_BATCH_SIZE)
+                        {
+                            Some(true) => break,
+                            Some(false) => LOG_INDEX_BACKFILL_INTERVAL,
+                            None => LOG_INDEX_BACKFILL_IDLE_INTERVAL,
+                        }
+                    }
+                    None => break,
+                };
+                thread::sleep(interval);
+            })
+            .expect("Log index backfill thread start fails");
+    }
+
+    /// Indexes at most `max_epochs` epochs before the earliest indexed epoch.
+    /// Returns `Some(true)` if the backfill is finished, and `None` if no epoch
+    /// is indexed yet.
+    fn backfill_log_index(&self, max_epochs: usize) -> Option<bool> {
+        for _ in 0..max_epochs {
+            let earliest =
+                self.db_manager.log_index_earliest_epoch_from_db()?;
+            if earliest == 0 {
+                info!("Log index backfill finished");
+                return Some(true);
+            }
+            let epoch_number = earliest - 1;
+            // The true genesis has no logs.
+            if epoch_number == 0 {
+                let mut batch = WriteBatch::default();
+                batch.insert_log_index_earliest_epoch(0);
+                self.db_manager.write_batch(batch);
+                continue;
+            }
+            if epoch_number < self.earliest_epoch_with_execution_result() {
+                info!(
+                    "Log index backfill stopped at the pruned epoch {}",
+                    epoch_number
+                );
+                return Some(true);
+            }
+
+            let epoch_receipts = match self
+                .executed_epoch_set_hashes_from_db(epoch_number)
+                .and_then(|epoch_hashes| {
+                    let pivot_hash = *epoch_hashes.last()?;
+                    epoch_hashes
+                        .iter()
+                        .map(|hash| {
+                            self.block_execution_result_by_hash_with_epoch(
+                                hash,
+                                &pivot_hash,
+                                false, /* update_pivot_assumption */
+                                false, /* update_cache */
+                            )
+                            .map(|result| result.block_receipts)
+                        })
+                        .collect::<Option<Vec<_>>>()
+                }) {
+                Some(epoch_receipts) => epoch_receipts,
+                None => {
+                    warn!(
+                        "Log index backfill stopped at epoch {}: receipts are \
+                         missing",
+                        epoch_number
+                    );
+                    return Some(true);
+                }
+            };
+            if !self.prepend_log_index(epoch_number, &epoch_receipts) {
+                info!(
+                    "Log index backfill stopped at epoch {}: it reaches the \
+                     entries indexed before the index restarted",
+                    epoch_number
+                );
+                return Some(true);
+            }
+        }
+        Some(false)
+    }
+
+    /// Adds the log index of the epoch `epoch_number`, which is right before
+    /// the earliest indexed epoch, at the start of the entry ranges. Returns
+    /// `false` if an entry range already starts with this epoch or an earlier
+    /// one, which is left from before the index restarted, since the entries
+    /// would no longer be ordered by epoch.
+    fn prepend_log_index(
+        &self, epoch_number: u64, epoch_receipts: &[Arc<BlockReceipts>],
+    ) -> bool {
+        let keys = log_index_keys(epoch_receipts);
+        let _guard = self.log_index_lock.lock();
+        // The epoch may have been indexed again or garbage collected since the
+        // earliest epoch was read.
+        if self.db_manager.log_index_earliest_epoch_from_db()
+            != Some(epoch_number + 1)
+            || epoch_number < self.earliest_epoch_with_execution_result()
+        {
+            return true;
+        }
+
+        let mut batch = WriteBatch::default();
+        for key in &keys {
+            let mut range = self
+                .db_manager
+                .log_index_range_from_db(key)
+                .unwrap_or_default();
+            if range.start < range.end
+                && self
+                    .db_manager
+                    .log_index_entry_from_db(key, range.start)
+                    .map_or(true, |entry| entry.epoch_number <= epoch_number)
+            {
+                return false;
+            }
+            range.start -= 1;
+            batch.insert_log_index_entry(
+                key,
+                range.start,
+                &LogIndexEntry { epoch_number },
+            );
+            batch.insert_log_index_range(key, &range);
+        }
+        insert_epoch_log_index(&mut batch, epoch_number, keys);
+        batch.insert_log_index_earliest_epoch(epoch_number);
+        self.db_manager.write_batch(batch);
+        true
+    }
+}
+
+fn insert_epoch_log_index(
+    batch: &mut WriteBatch, epoch_number: u64, keys: BTreeSet<Vec<u8>>,
+) {
+    if keys.is_empty() {
+        batch.remove_epoch_log_index(epoch_number);
+    } else {
+        batch.insert_epoch_log_index(
+            epoch_number,
+            &EpochLogIndex {
+                keys: keys.into_iter().collect(),
+            },
+        );
+    }
+}
+
+/// Iterates the epochs of the log index entries of some addresses or topics,
+/// from the newest and without duplicates. The entries are read lazily, so
+/// that a query can stop once it has enough logs.
+pub struct LogIndexEpochs<'a> {
+    db_manager: &'a DBManager,
+    cursors: Vec<LogIndexCursor>,
+    from_epoch: u64,
+}
+
+/// The positions `[start, end)` of the entries of a key that are not read yet,
+/// and the epoch of the last entry read.
+struct LogIndexCursor {
+    key: Vec<u8>,
+    start: u64,
+    end: u64,
+    head: Option<u64>,
+}
+
+impl<'a> LogIndexEpochs<'a> {
+    fn new(
+        db_manager: &'a DBManager, mut cursors: Vec<LogIndexCursor>,
+        from_epoch: u64,
+    ) -> Self {
+        for cursor in &mut cursors {
+            cursor.advance(db_manager, from_epoch);
+        }
+        LogIndexEpochs {
+            db_manager,
+            cursors,
+            from_epoch,
+        }
+    }
+}
+
+impl LogIndexCursor {
+    fn advance(&mut self, db_manager: &DBManager, from_epoch: u64) {
+        self.head = None;
+        while self.end > self.start {
+            self.end -= 1;
+            if let Some(entry) =
+                db_manager.log_index_entry_from_db(&self.key, self.end)
+            {
+                if entry.epoch_number >= from_epoch {
+                    self.head = Some(entry.epoch_number);
+                }
+                return;
+            }
+        }
+    }
+}
+
+impl Iterator for LogIndexEpochs<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let epoch = self.cursors.iter().filter_map(|c| c.head).max()?;
+        for cursor in &mut self.cursors {
+            while cursor.head.map_or(false, |head| head >= epoch) {
+                cursor.advance(self.db_manager, self.from_epoch);
+            }
+        }
+        Some(epoch)
+    }
+}
+
+/// The key of the entry range of an address (`position` 0) or a topic at
+/// `position - 1` of the logs in `space`. Epoch records of the same table use
+/// 8-byte keys and the bounds of the index start with a letter, so they never
+/// collide with these keys.
+fn log_index_key(space: Space, position: u8, value: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(2 + value.len());
+    key.push(match space {
+        Space::Native => 0,
+        Space::Ethereum => 1,
+    });
+    key.push(position);
+    key.extend_from_slice(value);
+    key
+}
+
+/// Returns the keys of the addresses and the topics of the logs in an epoch.
+/// Each key has one entry in the epoch however many logs it has.
+fn log_index_keys(epoch_receipts: &[Arc<BlockReceipts>]) -> BTreeSet<Vec<u8>> {
+    let mut keys = BTreeSet::new();
+    for block_receipts in epoch_receipts {
+        for receipt in &block_receipts.receipts {
+            for log in &receipt.logs {
+                keys.insert(log_index_key(
+                    log.space,
+                    0,
+                    log.address.as_bytes(),
+                ));
+                for (i, topic) in log.topics.iter().enumerate() {
+                    keys.insert(log_index_key(
+                        log.space,
+                        i as u8 + 1,
+                        topic.as_bytes(),
+                    ));
+                }
+            }
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::tests::with_data_manager, *};
+    use primitives::{LogEntry, Receipt};
+
+    fn with_log_index(config: &mut super::super::DataManagerConfiguration) {
+        config.persist_log_index = true;
+        config.additional_maintained_execution_result_epoch_count = Some(1);
+    }
+
+    /// The receipts of an epoch with one block, whose transactions emit logs
+    /// of the addresses `logs` with topic `T`.
+    fn epoch_receipts(logs: &[&[u64]]) -> Vec<Arc<BlockReceipts>> {
+        let receipts = logs
+            .iter()
+            .map(|addresses| Receipt {
+                logs: addresses
+                    .iter()
+                    .map(|address| LogEntry {
+                        address: Address::from_low_u64_be(*address),
+                        topics: vec![topic()],
+                        data: vec![],
+                        space: Space::Native,
+                    })
+                    .collect(),
+                ..Default::default()
+            })
+            .collect();
+        vec![Arc::new(BlockReceipts {
+            receipts,
+            block_number: 0,
+            secondary_reward: Default::default(),
+            tx_execution_error_messages: vec![],
+        })]
+    }
+
+    fn topic() -> H256 { H256::from_low_u64_be(100) }
+
+    fn indexed_epochs(
+        data_man: &BlockDataManager, address: u64, from_epoch: u64,
+        to_epoch: u64,
+    ) -> Option<Vec<u64>> {
+        data_man
+            .log_index_epochs(
+                Space::Native,
+                &Some(vec![Address::from_low_u64_be(address)]),
+                &vec![],
+                from_epoch,
+                to_epoch,
+            )
+            .map(|epochs| epochs.collect())
+    }
+
+    fn entry_count(data_man: &BlockDataManager, address: u64) -> u64 {
+        let key = log_index_key(
+            Space::Native,
+            0,
+            Address::from_low_u64_be(address).as_bytes(),
+        );
+        let range = data_man.db_manager.log_index_range_from_db(&key).unwrap();
+        range.end - range.start
+    }
+
+    #[test]
+    fn test_log_index_reorg() {
+        with_data_manager(
+            "./log_index_reorg.db/",
+            with_log_index,
+            |data_man| {
+                // An address with several logs in an epoch has one entry.
+                data_man
+                    .insert_log_index(1, &epoch_receipts(&[&[1, 1], &[1, 2]]));
+                data_man.insert_log_index(2, &epoch_receipts(&[&[1]]));
+                data_man.insert_log_index(3, &epoch_receipts(&[&[1, 2]]));
+                assert_eq!(entry_count(data_man, 1), 3);
+                assert_eq!(
+                    indexed_epochs(data_man, 1, 1, 3),
+                    Some(vec![3, 2, 1])
+                );
+                assert_eq!(indexed_epochs(data_man, 1, 2, 2), Some(vec![2]));
+                assert_eq!(indexed_epochs(data_man, 2, 1, 3), Some(vec![3, 1]));
+
+                // Executing epoch 2 again removes the entries of epoch 2 and
+                // the later epochs.
+                data_man.insert_log_index(2, &epoch_receipts(&[&[2]]));
+                assert_eq!(indexed_epochs(data_man, 1, 1, 3), Some(vec![1]));
+                assert_eq!(indexed_epochs(data_man, 2, 1, 3), Some(vec![2, 1]));
+                assert_eq!(
+                    data_man.db_manager.log_index_latest_epoch_from_db(),
+                    Some(2)
+                );
+            },
+        );
+    }
+
+    #[test]
+    fn test_log_index_earliest_epoch() {
+        with_data_manager(
+            "./log_index_earliest_epoch.db/",
+            with_log_index,
+            |data_man| {
+                let earliest =
+                    || data_man.db_manager.log_index_earliest_epoch_from_db();
+                data_man.insert_log_index(5, &epoch_receipts(&[&[1]]));
+                data_man.insert_log_index(6, &epoch_receipts(&[&[1]]));
+                assert_eq!(earliest(), Some(5));
+                assert_eq!(indexed_epochs(data_man, 1, 4, 6), None);
+
+                // An epoch before the earliest one removes all the indexed
+                // epochs.
+                data_man.insert_log_index(3, &epoch_receipts(&[&[1]]));
+                assert_eq!(earliest(), Some(3));
+                assert_eq!(indexed_epochs(data_man, 1, 3, 6), Some(vec![3]));
+                data_man.insert_log_index(4, &epoch_receipts(&[]));
+                assert_eq!(earliest(), Some(3));
+
+                // The index starts again after a gap.
+                data_man.insert_log_index(7, &epoch_receipts(&[&[1]]));
+                assert_eq!(earliest(), Some(7));
+                assert_eq!(indexed_epochs(data_man, 1, 3, 7), None);
+                assert_eq!(indexed_epochs(data_man, 1, 7, 7), Some(vec![7]));
+            },
+        );
+    }
+
+    #[test]
+    fn test_log_index_backfill() {
+        with_data_manager(
+            "./log_index_backfill.db/",
+            |config| config.persist_log_index = true,
+            |data_man| {
+                let earliest =
+                    || data_man.db_manager.log_index_earliest_epoch_from_db();
+                data_man.insert_log_index(5, &epoch_receipts(&[&[1]]));
+                assert!(data_man.prepend_log_index(4, &epoch_receipts(&[&[1]])));
+                assert!(data_man.prepend_log_index(3, &epoch_receipts(&[&[2]])));
+                // An epoch other than the one before the earliest is skipped.
+                assert!(data_man.prepend_log_index(1, &epoch_receipts(&[&[1]])));
+                assert_eq!(earliest(), Some(3));
+                assert_eq!(indexed_epochs(data_man, 1, 3, 5), Some(vec![5, 4]));
+                assert_eq!(indexed_epochs(data_man, 2, 3, 5), Some(vec![3]));
+
+                // After a gap, the backfill stops at the entries indexed
+                // before the gap.
+                data_man.insert_log_index(8, &epoch_receipts(&[&[1]]));
+                assert!(data_man.prepend_log_index(7, &epoch_receipts(&[])));
+                assert!(
+                    !data_man.prepend_log_index(6, &epoch_receipts(&[&[2]]))
+                );
+                assert_eq!(earliest(), Some(7));
+                assert_eq!(indexed_epochs(data_man, 1, 7, 8), Some(vec![8]));
+                assert_eq!(indexed_epochs(data_man, 2, 7, 8), Some(vec![]));
+            },
+        );
+    }
+
+    #[test]
+    fn test_log_index_gc() {
+        with_data_manager("./log_index_gc.db/", with_log_index, |data_man| {
+            data_man.insert_log_index(1, &epoch_receipts(&[&[1]]));
+            data_man.insert_log_index(2, &epoch_receipts(&[&[1, 2]]));
+            data_man.insert_log_index(3, &epoch_receipts(&[&[1]]));
+
+            // With one additional maintained epoch of execution results, the
+            // GC of base epoch 3 removes the entries of epoch 2 and before.
+            data_man.gc_base_epoch(3);
+            assert_eq!(entry_count(data_man, 1), 1);
+            assert_eq!(entry_count(data_man, 2), 0);
+            assert_eq!(indexed_epochs(data_man, 1, 1, 3), Some(vec![3]));
+            assert!(data_man.db_manager.epoch_log_index_from_db(2).is_none());
+            assert!(data_man.db_manager.epoch_log_index_from_db(3).is_some());
+        });
+    }
+}
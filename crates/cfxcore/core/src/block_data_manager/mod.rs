@@ -13,7 +13,7 @@ use cfx_storage::{
     state_manager::StateIndex, utils::guarded_value::*, StorageManager,
     StorageManagerTrait,
 };
-use cfx_types::{Bloom, Space, H256};
+use cfx_types::{AddressSpaceUtil, AddressWithSpace, Bloom, Space, H256};
 pub use cfxcore_types::block_data_manager::block_data_types;
 use db::SystemDB;
 use malloc_size_of::{new_malloc_size_ops, MallocSizeOf, MallocSizeOfOps};
@@ -22,21 +22,25 @@ use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockUpgradableReadGuard};
 use primitives::{
     block::CompactBlock,
     receipt::{BlockReceipts, TransactionStatus},
-    Block, BlockHeader, EpochId, Receipt, SignedTransaction, TransactionIndex,
-    TransactionWithSignature, NULL_EPOCH,
+    Action, Block, BlockHeader, EpochId, Receipt, SignedTransaction,
+    TransactionIndex, TransactionWithSignature, NULL_EPOCH,
 };
 use rlp::DecoderError;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     sync::Arc,
 };
 use threadpool::ThreadPool;
 pub mod db_gc_manager;
 pub mod db_manager;
+mod log_index;
+#[cfg(test)]
+mod tests;
 pub mod tx_data_manager;
 use crate::{
     block_data_manager::{
-        db_manager::DBManager, tx_data_manager::TransactionDataManager,
+        db_manager::{DBManager, WriteBatch},
+        tx_data_manager::TransactionDataManager,
     },
     consensus::pos_handler::PosVerifier,
 };
@@ -49,6 +53,7 @@ use cfx_internal_common::{
     EpochExecutionCommitment, StateAvailabilityBoundary, StateRootWithAuxInfo,
 };
 use db_gc_manager::GCProgress;
+pub use log_index::LogIndexEpochs;
 use metrics::{register_meter_with_group, Meter, MeterTimer};
 use primitives::pos::PosBlockId;
 use std::{hash::Hash, path::Path, time::Duration};
@@ -144,6 +149,12 @@ pub struct BlockDataManager {
     cache_man: Arc<Mutex<CacheManager<CacheId>>>,
     pub target_difficulty_manager: TargetDifficultyManager,
     gc_progress: Arc<Mutex<GCProgress>>,
+    /// Serializes the updates of the address transaction index, which are
+    /// made by both epoch execution and database GC.
+    address_tx_index_lock: Mutex<()>,
+    /// Serializes the updates of the log index, which are made by epoch
+    /// execution, database GC and the backfill.
+    log_index_lock: Mutex<()>,
 
     /// This maintains the boundary height of available state and commitments
     /// (executed but not deleted or in `ExecutionTaskQueue`).
@@ -237,6 +248,8 @@ impl BlockDataManager {
             gc_progress: Arc::new(Mutex::new(GCProgress::new(
                 previous_db_progress,
             ))),
+            address_tx_index_lock: Default::default(),
+            log_index_lock: Default::default(),
         };
 
         data_man.initialize_instance_id();
@@ -852,6 +865,169 @@ impl BlockDataManager {
         }
     }
 
+    /// Replaces the address transaction index of the epoch `epoch_number`
+    /// with `entries`, which are in execution order. The entries of this
+    /// epoch and the later epochs left by a previous pivot chain are removed
+    /// first, so the index stays ordered by epoch. All the changes of the
+    /// epoch are written in one batch.
+    pub fn insert_address_tx_index(
+        &self, epoch_number: u64, entries: Vec<(AddressWithSpace, H256)>,
+    ) {
+        if !self.config.persist_address_tx_index {
+            return;
+        }
+        let _guard = self.address_tx_index_lock.lock();
+
+        let mut batch = WriteBatch::default();
+        let mut ranges = BTreeMap::new();
+        let stale_addresses = self
+            .db_manager
+            .epoch_address_tx_index_from_db(epoch_number)
+            .map_or(vec![], |record| record.addresses);
+        for address in stale_addresses
+            .into_iter()
+            .chain(entries.iter().map(|(address, _)| *address))
+        {
+            ranges.entry(address).or_insert_with(|| {
+                let mut range = self
+                    .db_manager
+                    .address_tx_index_range_from_db(&address)
+                    .unwrap_or_default();
+                while range.end > range.start {
+                    match self
+                        .db_manager
+                        .address_tx_index_entry_from_db(&address, range.end - 1)
+                    {
+                        Some(entry) if entry.epoch_number < epoch_number => {
+                            break
+                        }
+                        _ => {
+                            range.end -= 1;
+                            batch.remove_address_tx_index_entry(
+                                &address, range.end,
+                            );
+                        }
+                    }
+                }
+                range
+            });
+        }
+
+        let mut addresses = Vec::new();
+        for (address, tx_hash) in entries {
+            let range: &mut AddressTxIndexRange =
+                ranges.get_mut(&address).expect("inserted above");
+            addresses.push(address);
+            batch.insert_address_tx_index_entry(
+                &address,
+                range.end,
+                &AddressTxIndexEntry {
+                    epoch_number,
+                    tx_hash,
+                },
+            );
+            range.end += 1;
+        }
+        for (address, range) in &ranges {
+            batch.insert_address_tx_index_range(address, range);
+        }
+
+        addresses.sort();
+        addresses.dedup();
+        if addresses.is_empty() {
+            batch.remove_epoch_address_tx_index(epoch_number);
+        } else {
+            batch.insert_epoch_address_tx_index(
+                epoch_number,
+                &EpochAddressTxIndex { addresses },
+            );
+        }
+        self.db_manager.write_batch(batch);
+    }
+
+    /// Returns at most `limit` index entries of `address` from the newest
+    /// one, starting at the position `cursor`, and the position of the next
+    /// entry if there are more. `None` is returned if the index is disabled.
+    pub fn address_tx_index(
+        &self, address: &AddressWithSpace, cursor: Option<u64>, limit: usize,
+    ) -> Option<(Vec<AddressTxIndexEntry>, Option<u64>)> {
+        if !self.config.persist_address_tx_index {
+            return None;
+        }
+        let range =
+            match self.db_manager.address_tx_index_range_from_db(address) {
+                Some(range) => range,
+                None => return Some((vec![], None)),
+            };
+
+        // `next` is the position after the next entry to read.
+        let mut next = cursor
+            .map_or(range.end, |cursor| cursor.saturating_add(1))
+            .min(range.end);
+        let mut entries = Vec::new();
+        while next > range.start && entries.len() < limit {
+            next -= 1;
+            // The entry may have been removed by GC after reading the range.
+            if let Some(entry) = self
+                .db_manager
+                .address_tx_index_entry_from_db(address, next)
+            {
+                entries.push(entry);
+            }
+        }
+        let next_cursor = if next > range.start {
+            Some(next - 1)
+        } else {
+            None
+        };
+        Some((entries, next_cursor))
+    }
+
+    /// Removes the address transaction index entries of the epoch
+    /// `epoch_number` and the epochs before it in one batch.
+    fn remove_address_tx_index(&self, epoch_number: u64) {
+        if !self.config.persist_address_tx_index {
+            return;
+        }
+        let _guard = self.address_tx_index_lock.lock();
+
+        let record = match self
+            .db_manager
+            .epoch_address_tx_index_from_db(epoch_number)
+        {
+            Some(record) => record,
+            None => return,
+        };
+        let mut batch = WriteBatch::default();
+        for address in record.addresses {
+            let mut range = match self
+                .db_manager
+                .address_tx_index_range_from_db(&address)
+            {
+                Some(range) => range,
+                None => continue,
+            };
+            while range.start < range.end {
+                match self
+                    .db_manager
+                    .address_tx_index_entry_from_db(&address, range.start)
+                {
+                    Some(entry) if entry.epoch_number > epoch_number => break,
+                    _ => {
+                        batch.remove_address_tx_index_entry(
+                            &address,
+                            range.start,
+                        );
+                        range.start += 1;
+                    }
+                }
+            }
+            batch.insert_address_tx_index_range(&address, &range);
+        }
+        batch.remove_epoch_address_tx_index(epoch_number);
+        self.db_manager.write_batch(batch);
+    }
+
     pub fn insert_local_block_info(&self, hash: &H256, info: LocalBlockInfo) {
         self.insert(
             *hash,
@@ -1261,7 +1437,9 @@ impl BlockDataManager {
             }
         }
 
+        let me_height = self.block_height_by_hash(epoch_hash).unwrap();
         let mut evm_tx_index = 0;
+        let mut address_tx_index = Vec::new();
 
         // Recover tx address if we will skip pivot chain execution
         for (block_idx, block_hash) in epoch_block_hashes.iter().enumerate() {
@@ -1308,10 +1486,18 @@ impl BlockDataManager {
                                 rpc_index: Some(rpc_index),
                             },
                         );
+                        for address in
+                            tx_index_addresses(tx.sender(), &tx.action())
+                        {
+                            address_tx_index.push((address, tx.hash));
+                        }
 
                         for ptx in phantom_txs {
+                            let from = ptx.from.with_evm_space();
+                            let action = ptx.action.clone();
+                            let hash = ptx.into_eip155(evm_chain_id).hash();
                             self.insert_transaction_index(
-                                &ptx.into_eip155(evm_chain_id).hash(),
+                                &hash,
                                 &TransactionIndex {
                                     block_hash: *block_hash,
                                     real_index: tx_idx,
@@ -1319,6 +1505,9 @@ impl BlockDataManager {
                                     rpc_index: Some(evm_tx_index),
                                 },
                             );
+                            for address in tx_index_addresses(from, &action) {
+                                address_tx_index.push((address, hash));
+                            }
 
                             evm_tx_index += 1;
                         }
@@ -1329,6 +1518,8 @@ impl BlockDataManager {
                 }
             }
         }
+        self.insert_address_tx_index(me_height, address_tx_index);
+        self.insert_log_index(me_height, &epoch_receipts);
         if let Some(reward_execution_info) = reward_execution_info {
             for block in &reward_execution_info.epoch_blocks {
                 let h = block.as_ref().hash();
@@ -1342,7 +1533,6 @@ impl BlockDataManager {
                 }
             }
         }
-        let me_height = self.block_height_by_hash(epoch_hash).unwrap();
         if pos_verifier.pos_option().is_some() && me_height != 0 {
             // Check if stored pos reward is on pivot.
             let pivot_block_header = self
@@ -1775,6 +1965,24 @@ impl BlockDataManager {
         };
 
         gc_tx_index();
+        if let Some(defer_epochs) = self
+            .config
+            .additional_maintained_transaction_index_epoch_count
+        {
+            if base_epoch > defer_epochs as u64 {
+                self.remove_address_tx_index(base_epoch - defer_epochs as u64);
+            }
+        }
+        // The log index is only used with the receipts, so it is kept as long
+        // as the execution results.
+        if let Some(defer_epochs) = self
+            .config
+            .additional_maintained_execution_result_epoch_count
+        {
+            if base_epoch > defer_epochs as u64 {
+                self.remove_log_index(base_epoch - defer_epochs as u64);
+            }
+        }
 
         self.gc_epoch_with_defer(
             base_epoch,
@@ -1821,6 +2029,19 @@ impl BlockDataManager {
     }
 }
 
+/// Returns the addresses indexed for a transaction, i.e., the sender and the
+/// receiver of a call.
+pub fn tx_index_addresses(
+    from: AddressWithSpace, action: &Action,
+) -> Vec<AddressWithSpace> {
+    match action {
+        Action::Call(to) if *to != from.address => {
+            vec![from, to.with_space(from.space)]
+        }
+        _ => vec![from],
+    }
+}
+
 #[derive(Copy, Clone)]
 pub enum DbType {
     Rocksdb,
@@ -1829,6 +2050,13 @@ pub enum DbType {
 
 pub struct DataManagerConfiguration {
     pub persist_tx_index: bool,
+    /// Whether to keep the transactions sent from or to each address. It is
+    /// garbage collected with the transaction index.
+    pub persist_address_tx_index: bool,
+    /// Whether to keep the epochs of the logs of each address and topic to
+    /// speed up log filters. It is garbage collected with the execution
+    /// results.
+    pub persist_log_index: bool,
     pub persist_block_number_index: bool,
     pub tx_cache_index_maintain_timeout: Duration,
     pub db_type: DbType,
@@ -1852,6 +2080,8 @@ impl DataManagerConfiguration {
     ) -> Self {
         Self {
             persist_tx_index,
+            persist_address_tx_index: false,
+            persist_log_index: false,
             persist_block_number_index,
             tx_cache_index_maintain_timeout,
             db_type,
@@ -0,0 +1,155 @@
+// Copyright 2019 Conflux Foundation. All rights reserved.
+// Conflux is free software and distributed under GNU General Public License.
+// See http://www.gnu.org/licenses/
+
+use super::{BlockDataManager, DataManagerConfiguration, DbType};
+use crate::{
+    pow::PowComputer, sync::utils::initialize_data_manager_with_config,
+};
+use cfx_executor::machine::VmFactory;
+use cfx_types::{Address, AddressSpaceUtil, AddressWithSpace, H256};
+use std::{fs, sync::Arc, time::Duration};
+
+pub(super) fn with_data_manager<C, F>(db_dir: &str, configure: C, f: F)
+where
+    C: FnOnce(&mut DataManagerConfiguration),
+    F: FnOnce(&BlockDataManager),
+{
+    {
+        let mut config = DataManagerConfiguration::new(
+            false, /* persist_tx_index */
+            false, /* persist_block_number_index */
+            Duration::from_millis(300_000),
+            DbType::Rocksdb,
+        );
+        configure(&mut config);
+        let (data_man, _) = initialize_data_manager_with_config(
+            db_dir,
+            config,
+            Arc::new(PowComputer::new(true)),
+            VmFactory::new(1024 * 32),
+            &Default::default(),
+            &Default::default(),
+        );
+        f(&data_man);
+    }
+    fs::remove_dir_all(db_dir).unwrap();
+}
+
+fn with_address_tx_index(config: &mut DataManagerConfiguration) {
+    config.persist_address_tx_index = true;
+    config.additional_maintained_transaction_index_epoch_count = Some(1);
+}
+
+fn address(n: u64) -> AddressWithSpace {
+    Address::from_low_u64_be(n).with_native_space()
+}
+
+fn indexed_hashes(
+    data_man: &BlockDataManager, address: &AddressWithSpace,
+) -> Vec<(u64, H256)> {
+    let (entries, next) = data_man
+        .address_tx_index(address, None, usize::MAX)
+        .unwrap();
+    assert_eq!(next, None);
+    entries
+        .into_iter()
+        .map(|entry| (entry.epoch_number, entry.tx_hash))
+        .collect()
+}
+
+#[test]
+fn test_address_tx_index_pagination() {
+    with_data_manager(
+        "./address_tx_index_pagination.db/",
+        with_address_tx_index,
+        |data_man| {
+            let (a, b) = (address(1), address(2));
+            let h = H256::from_low_u64_be;
+            data_man.insert_address_tx_index(1, vec![(a, h(1)), (b, h(1))]);
+            data_man.insert_address_tx_index(2, vec![(a, h(2)), (a, h(3))]);
+            data_man.insert_address_tx_index(3, vec![(a, h(4))]);
+
+            // The entries are returned from the newest one.
+            let (entries, next) =
+                data_man.address_tx_index(&a, None, 2).unwrap();
+            assert_eq!(
+                entries.iter().map(|e| e.tx_hash).collect::<Vec<_>>(),
+                vec![h(4), h(3)]
+            );
+            assert_eq!(next, Some(1));
+            let (entries, next) =
+                data_man.address_tx_index(&a, next, 2).unwrap();
+            assert_eq!(
+                entries.iter().map(|e| e.tx_hash).collect::<Vec<_>>(),
+                vec![h(2), h(1)]
+            );
+            assert_eq!(next, None);
+
+            // A cursor beyond the end starts from the newest entry.
+            let (entries, _) =
+                data_man.address_tx_index(&a, Some(100), 1).unwrap();
+            assert_eq!(entries[0].tx_hash, h(4));
+
+            assert_eq!(indexed_hashes(data_man, &b), vec![(1, h(1))]);
+            assert_eq!(indexed_hashes(data_man, &address(3)), vec![]);
+        },
+    );
+}
+
+#[test]
+fn test_address_tx_index_reorg() {
+    with_data_manager(
+        "./address_tx_index_reorg.db/",
+        with_address_tx_index,
+        |data_man| {
+            let (a, b) = (address(1), address(2));
+            let h = H256::from_low_u64_be;
+            data_man.insert_address_tx_index(1, vec![(a, h(1))]);
+            data_man.insert_address_tx_index(2, vec![(a, h(2))]);
+            data_man.insert_address_tx_index(3, vec![(a, h(3)), (b, h(3))]);
+
+            // Executing epoch 2 again on a new pivot chain removes the entries
+            // of epoch 2 and the later epochs.
+            data_man.insert_address_tx_index(2, vec![(b, h(4))]);
+            assert_eq!(indexed_hashes(data_man, &a), vec![(1, h(1))]);
+            assert_eq!(indexed_hashes(data_man, &b), vec![(2, h(4))]);
+
+            data_man.insert_address_tx_index(3, vec![(a, h(5))]);
+            assert_eq!(
+                indexed_hashes(data_man, &a),
+                vec![(3, h(5)), (1, h(1))]
+            );
+            assert_eq!(indexed_hashes(data_man, &b), vec![(2, h(4))]);
+        },
+    );
+}
+
+#[test]
+fn test_address_tx_index_gc() {
+    with_data_manager(
+        "./address_tx_index_gc.db/",
+        with_address_tx_index,
+        |data_man| {
+            let (a, b) = (address(1), address(2));
+            let h = H256::from_low_u64_be;
+            data_man.insert_address_tx_index(1, vec![(a, h(1))]);
+            data_man.insert_address_tx_index(2, vec![(a, h(2)), (b, h(2))]);
+            data_man.insert_address_tx_index(3, vec![(a, h(3))]);
+
+            // With one additional maintained epoch, the GC of base epoch 3
+            // removes the entries of epoch 2 and before.
+            data_man.gc_base_epoch(3);
+            assert_eq!(indexed_hashes(data_man, &a), vec![(3, h(3))]);
+            assert_eq!(indexed_hashes(data_man, &b), vec![]);
+            assert!(data_man
+                .db_manager
+                .epoch_address_tx_index_from_db(2)
+                .is_none());
+
+            // The positions of the remaining entries are kept.
+            let (_, next) = data_man.address_tx_index(&a, Some(2), 1).unwrap();
+            assert_eq!(next, None);
+        },
+    );
+}
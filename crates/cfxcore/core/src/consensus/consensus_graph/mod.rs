@@ -4,7 +4,10 @@ pub(super) mod onchain_blocks_provider;
 pub(super) mod rpc_api;
 pub(super) mod sync_graph_api;
 
-use self::best_info_provider::BestInformation;
+use self::{
+    best_info_provider::BestInformation,
+    rpc_api::pending_block_provider::PendingBlock,
+};
 
 use super::{
     consensus_inner::{
@@ -76,6 +79,12 @@ pub struct ConsensusGraph {
     pub synced_epoch_id: Mutex<Option<EpochId>>,
     pub config: ConsensusConfig,
     pub params: CommonParams,
+
+    /// The block the node would pack next, built on request for the RPCs.
+    pending_block: Mutex<Option<Arc<PendingBlock>>>,
+    /// Held while the pending block is built, so that concurrent requests
+    /// build it only once.
+    pending_block_build: Mutex<()>,
 }
 
 impl MallocSizeOf for ConsensusGraph {
@@ -147,6 +156,8 @@ impl ConsensusGraph {
             synced_epoch_id: Default::default(),
             config: conf,
             params,
+            pending_block: Default::default(),
+            pending_block_build: Default::default(),
         };
         graph.update_best_info(false /* ready_for_mining */);
         graph
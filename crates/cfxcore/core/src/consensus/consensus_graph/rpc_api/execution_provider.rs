@@ -11,6 +11,7 @@ use cfx_parameters::rpc::{
     GAS_PRICE_TRANSACTION_SAMPLE_SIZE,
 };
 use cfx_rpc_eth_types::EvmOverrides;
+use cfx_statedb::StateDb;
 use cfx_types::{Space, H256, U256};
 use primitives::{EpochNumber, SignedTransaction};
 
@@ -147,4 +148,29 @@ impl ConsensusGraph {
             evm_overrides,
         )
     }
+
+    /// Execute `tx` virtually on `statedb`, which holds the state after
+    /// executing the epoch `epoch_height`. Unlike `call_virtual`, the epoch
+    /// does not need to be executed locally, so that light nodes can call
+    /// against a state retrieved from peers.
+    pub fn call_virtual_on_statedb(
+        &self, tx: &SignedTransaction, epoch_height: u64, statedb: StateDb,
+        request: EstimateRequest, evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        let (epoch_id, epoch_size) = if let Ok(v) =
+            self.get_block_hashes_by_epoch(EpochNumber::Number(epoch_height))
+        {
+            (v.last().expect("pivot block always exist").clone(), v.len())
+        } else {
+            bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
+        };
+        self.executor.call_virtual_on_statedb(
+            tx,
+            &epoch_id,
+            epoch_size,
+            statedb,
+            request,
+            evm_overrides,
+        )
+    }
 }
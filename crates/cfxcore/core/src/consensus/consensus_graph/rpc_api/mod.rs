@@ -1,4 +1,5 @@
 pub mod execution_provider;
+pub mod pending_block_provider;
 pub mod phantom_block_provider;
 pub mod state_provider;
 pub mod trace_provider;
@@ -0,0 +1,176 @@
+use super::super::ConsensusGraph;
+
+use crate::errors::Result as CoreResult;
+use cfx_parameters::block::{
+    MAX_BLOCK_SIZE_IN_BYTES, MAX_TRANSACTION_COUNT_PER_BLOCK,
+};
+use cfx_parity_trace_types::{AccountDiff, Diff, StateDiff};
+use cfx_rpc_cfx_types::PhantomBlock;
+use cfx_types::{AddressWithSpace, H256, U256};
+use primitives::{Block, BlockHeaderBuilder, EpochNumber};
+use std::{
+    cmp::max,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The block the node would pack next, executed on top of the best executed
+/// state.
+pub struct PendingBlock {
+    /// The packed block, whose parent is the pivot block of the best executed
+    /// epoch.
+    pub block: Arc<Block>,
+    /// The eSpace view of the block, built in the same way as the phantom
+    /// blocks of the executed epochs.
+    pub phantom_block: PhantomBlock,
+    /// The changes of the accounts made by all the transactions in the block.
+    pub state_diff: StateDiff,
+    /// The `best_info_version` of the transaction pool when the block is
+    /// packed.
+    best_info_version: u64,
+}
+
+impl PendingBlock {
+    pub fn hash(&self) -> H256 { self.block.hash() }
+
+    /// The epoch number of the block, one more than the best executed epoch.
+    pub fn height(&self) -> u64 { self.block.block_header.height() }
+
+    pub fn timestamp(&self) -> u64 { self.block.block_header.timestamp() }
+
+    /// The balance of the account after the block, or `None` if the block
+    /// does not change it.
+    pub fn balance(&self, address: &AddressWithSpace) -> Option<U256> {
+        self.account_diff(address)
+            .and_then(|diff| value_after(&diff.balance))
+    }
+
+    /// The nonce of the account after the block, or `None` if the block does
+    /// not change it.
+    pub fn nonce(&self, address: &AddressWithSpace) -> Option<U256> {
+        self.account_diff(address)
+            .and_then(|diff| value_after(&diff.nonce))
+    }
+
+    fn account_diff(&self, address: &AddressWithSpace) -> Option<&AccountDiff> {
+        self.state_diff.0.get(address)
+    }
+}
+
+fn value_after(diff: &Diff<U256>) -> Option<U256> {
+    match diff {
+        Diff::Same => None,
+        Diff::Born(to) | Diff::Changed { to, .. } => Some(*to),
+        Diff::Died(_) => Some(U256::zero()),
+    }
+}
+
+impl ConsensusGraph {
+    /// Returns the block the node would pack next. The block is built on the
+    /// first request after the transaction pool is notified of a new best
+    /// information, and reused until the next notification.
+    ///
+    /// The block is executed without holding the `pending_block` lock, so a
+    /// slow execution does not block the requests served by the cached
+    /// block. Only one block is built at a time: concurrent requests after a
+    /// notification wait for that build and share its result.
+    pub fn pending_block(&self) -> CoreResult<Arc<PendingBlock>> {
+        if let Some(block) = self.cached_pending_block() {
+            return Ok(block);
+        }
+
+        let _build = self.pending_block_build.lock();
+        // Another request may have built the block while we were waiting.
+        if let Some(block) = self.cached_pending_block() {
+            return Ok(block);
+        }
+
+        let version = self.txpool.best_info_version();
+        let block = Arc::new(self.build_pending_block(version)?);
+        let mut pending_block = self.pending_block.lock();
+        match pending_block.as_ref() {
+            Some(cached) if cached.best_info_version >= version => {}
+            _ => *pending_block = Some(block.clone()),
+        }
+        Ok(block)
+    }
+
+    /// Returns the cached block if it is built for the current best
+    /// information of the transaction pool.
+    fn cached_pending_block(&self) -> Option<Arc<PendingBlock>> {
+        let version = self.txpool.best_info_version();
+        self.pending_block
+            .lock()
+            .as_ref()
+            .filter(|block| block.best_info_version == version)
+            .cloned()
+    }
+
+    fn build_pending_block(
+        &self, best_info_version: u64,
+    ) -> CoreResult<PendingBlock> {
+        let epoch_height = self.best_executed_state_epoch_number();
+        let hashes =
+            self.get_block_hashes_by_epoch(EpochNumber::Number(epoch_height))?;
+        let epoch_id = *hashes.last().expect("pivot block always exist");
+        let parent = self
+            .data_man
+            .block_header_by_hash(&epoch_id)
+            .ok_or("pivot block of the best executed epoch not found")?;
+
+        let (_, gas_limit, transactions, base_price) =
+            self.txpool.get_best_info_with_packed_transactions(
+                MAX_TRANSACTION_COUNT_PER_BLOCK,
+                MAX_BLOCK_SIZE_IN_BYTES,
+                vec![],
+            );
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let height = epoch_height + 1;
+        let header = BlockHeaderBuilder::new()
+            .with_parent_hash(epoch_id)
+            .with_height(height)
+            .with_timestamp(max(now, parent.timestamp() + 1))
+            .with_gas_limit(gas_limit)
+            .with_base_price(base_price)
+            .build();
+        let block = Arc::new(Block::new(header, transactions));
+
+        let (receipts, state_diff) = self.executor.execute_pending_block(
+            &epoch_id,
+            epoch_height,
+            hashes.len(),
+            block.clone(),
+        )?;
+
+        let mut phantom_block = PhantomBlock {
+            pivot_header: block.block_header.clone(),
+            transactions: vec![],
+            receipts: vec![],
+            errors: vec![],
+            bloom: Default::default(),
+            traces: vec![],
+            total_gas_limit: block
+                .block_header
+                .espace_gas_limit(self.params.can_pack_evm_transaction(height)),
+        };
+        self.append_phantom_transactions(
+            &mut phantom_block,
+            &block,
+            &receipts.receipts,
+            &receipts.tx_execution_error_messages,
+            None,
+            &mut U256::zero(),
+        )?;
+
+        Ok(PendingBlock {
+            block,
+            phantom_block,
+            state_diff,
+            best_info_version,
+        })
+    }
+}
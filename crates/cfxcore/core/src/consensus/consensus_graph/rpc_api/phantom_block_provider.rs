@@ -2,10 +2,11 @@ use cfx_execute_helper::{
     exec_tracer::recover_phantom_traces,
     phantom_tx::build_bloom_and_recover_phantom,
 };
+use cfx_parity_trace_types::TransactionExecTraces;
 use cfx_rpc_cfx_types::PhantomBlock;
 use cfx_types::{Bloom, Space, H256, U256};
 use cfxcore_errors::ProviderBlockError;
-use primitives::{receipt::Receipt, EpochNumber, TransactionStatus};
+use primitives::{receipt::Receipt, Block, EpochNumber, TransactionStatus};
 use std::sync::Arc;
 
 use super::super::ConsensusGraph;
@@ -138,7 +139,6 @@ impl ConsensusGraph {
         };
 
         let mut accumulated_gas_used = U256::from(0);
-        let mut gas_used_offset;
         let mut total_gas_limit = U256::from(0);
 
         let iter_blocks = if only_pivot {
@@ -148,7 +148,6 @@ impl ConsensusGraph {
         };
 
         for b in iter_blocks {
-            gas_used_offset = accumulated_gas_used;
             // note: we need the receipts to reconstruct a phantom block.
             // as a result, we cannot return unexecuted blocks in eth_* RPCs.
             let exec_info = match self
@@ -199,101 +198,129 @@ impl ConsensusGraph {
                 vec![]
             };
 
-            // sanity check: transaction and receipt length
-            if b.transactions.len() != block_receipts.len() {
-                return Err("Inconsistent state: transactions and receipts length mismatch".into());
-            }
+            self.append_phantom_transactions(
+                &mut phantom_block,
+                b,
+                block_receipts,
+                errors,
+                include_traces.then_some(&block_traces[..]),
+                &mut accumulated_gas_used,
+            )?;
+        }
 
-            let evm_chain_id = self.best_chain_id().in_evm_space();
+        phantom_block.total_gas_limit = total_gas_limit;
+        Ok(Some(phantom_block))
+    }
 
-            for (id, tx) in b.transactions.iter().enumerate() {
-                match tx.space() {
-                    Space::Ethereum => {
-                        let receipt = &block_receipts[id];
+    /// Appends the eSpace transactions of the executed block to the phantom
+    /// block, including the phantom transactions of the successful Core Space
+    /// transactions.
+    pub(super) fn append_phantom_transactions(
+        &self, phantom_block: &mut PhantomBlock, b: &Block,
+        block_receipts: &[Receipt], errors: &[String],
+        block_traces: Option<&[TransactionExecTraces]>,
+        accumulated_gas_used: &mut U256,
+    ) -> Result<(), ProviderBlockError> {
+        let gas_used_offset = *accumulated_gas_used;
+
+        // sanity check: transaction and receipt length
+        if b.transactions.len() != block_receipts.len() {
+            return Err(
+                "Inconsistent state: transactions and receipts length mismatch"
+                    .into(),
+            );
+        }
 
-                        // we do not return non-executed transaction
-                        if receipt.outcome_status == TransactionStatus::Skipped
-                        {
-                            continue;
-                        }
+        let evm_chain_id = self.best_chain_id().in_evm_space();
 
-                        phantom_block.transactions.push(tx.clone());
+        for (id, tx) in b.transactions.iter().enumerate() {
+            match tx.space() {
+                Space::Ethereum => {
+                    let receipt = &block_receipts[id];
 
-                        // sanity check: gas price must be positive
-                        if *tx.gas_price() == 0.into() {
-                            return Err("Inconsistent state: zero transaction gas price".into());
-                        }
+                    // we do not return non-executed transaction
+                    if receipt.outcome_status == TransactionStatus::Skipped {
+                        continue;
+                    }
 
-                        accumulated_gas_used =
-                            gas_used_offset + receipt.accumulated_gas_used;
+                    phantom_block.transactions.push(tx.clone());
 
-                        phantom_block.receipts.push(Receipt {
-                            accumulated_gas_used,
-                            outcome_status: receipt.outcome_status,
-                            ..receipt.clone()
-                        });
+                    // sanity check: gas price must be positive
+                    if *tx.gas_price() == 0.into() {
+                        return Err(
+                            "Inconsistent state: zero transaction gas price"
+                                .into(),
+                        );
+                    }
 
-                        phantom_block.errors.push(errors[id].clone());
-                        phantom_block.bloom.accrue_bloom(&receipt.log_bloom);
+                    *accumulated_gas_used =
+                        gas_used_offset + receipt.accumulated_gas_used;
 
-                        if include_traces {
-                            phantom_block.traces.push(block_traces[id].clone());
-                        }
-                    }
-                    Space::Native => {
-                        // note: failing transactions will not produce any
-                        // phantom txs or traces
-                        if block_receipts[id].outcome_status
-                            != TransactionStatus::Success
-                        {
-                            continue;
-                        }
+                    phantom_block.receipts.push(Receipt {
+                        accumulated_gas_used: *accumulated_gas_used,
+                        outcome_status: receipt.outcome_status,
+                        ..receipt.clone()
+                    });
 
-                        let (phantom_txs, _) = build_bloom_and_recover_phantom(
-                            &block_receipts[id].logs[..],
-                            tx.hash(),
-                        );
+                    phantom_block.errors.push(errors[id].clone());
+                    phantom_block.bloom.accrue_bloom(&receipt.log_bloom);
+
+                    if let Some(block_traces) = block_traces {
+                        phantom_block.traces.push(block_traces[id].clone());
+                    }
+                }
+                Space::Native => {
+                    // note: failing transactions will not produce any
+                    // phantom txs or traces
+                    if block_receipts[id].outcome_status
+                        != TransactionStatus::Success
+                    {
+                        continue;
+                    }
 
-                        if include_traces {
-                            let tx_traces = block_traces[id].clone();
+                    let (phantom_txs, _) = build_bloom_and_recover_phantom(
+                        &block_receipts[id].logs[..],
+                        tx.hash(),
+                    );
 
-                            let phantom_traces =
-                                recover_phantom_traces(tx_traces, tx.hash())?;
+                    if let Some(block_traces) = block_traces {
+                        let tx_traces = block_traces[id].clone();
 
-                            // sanity check: one trace for each phantom tx
-                            if phantom_txs.len() != phantom_traces.len() {
-                                error!("Inconsistent state: phantom tx and trace length mismatch, txs.len = {:?}, traces.len = {:?}", phantom_txs.len(), phantom_traces.len());
-                                return Err("Inconsistent state: phantom tx and trace length mismatch".into());
-                            }
+                        let phantom_traces =
+                            recover_phantom_traces(tx_traces, tx.hash())?;
 
-                            phantom_block.traces.extend(phantom_traces);
+                        // sanity check: one trace for each phantom tx
+                        if phantom_txs.len() != phantom_traces.len() {
+                            error!("Inconsistent state: phantom tx and trace length mismatch, txs.len = {:?}, traces.len = {:?}", phantom_txs.len(), phantom_traces.len());
+                            return Err("Inconsistent state: phantom tx and trace length mismatch".into());
                         }
 
-                        for p in phantom_txs {
-                            phantom_block.transactions.push(Arc::new(
-                                p.clone().into_eip155(evm_chain_id),
-                            ));
+                        phantom_block.traces.extend(phantom_traces);
+                    }
 
-                            // note: phantom txs consume no gas
-                            let phantom_receipt =
-                                p.into_receipt(accumulated_gas_used);
+                    for p in phantom_txs {
+                        phantom_block.transactions.push(Arc::new(
+                            p.clone().into_eip155(evm_chain_id),
+                        ));
 
-                            phantom_block
-                                .bloom
-                                .accrue_bloom(&phantom_receipt.log_bloom);
+                        // note: phantom txs consume no gas
+                        let phantom_receipt =
+                            p.into_receipt(*accumulated_gas_used);
 
-                            phantom_block.receipts.push(phantom_receipt);
+                        phantom_block
+                            .bloom
+                            .accrue_bloom(&phantom_receipt.log_bloom);
 
-                            // note: phantom txs never fail
-                            phantom_block.errors.push("".into());
-                        }
+                        phantom_block.receipts.push(phantom_receipt);
+
+                        // note: phantom txs never fail
+                        phantom_block.errors.push("".into());
                     }
                 }
             }
         }
 
-        phantom_block.total_gas_limit = total_gas_limit;
-        Ok(Some(phantom_block))
+        Ok(())
     }
 
     pub fn get_phantom_block_by_hash(
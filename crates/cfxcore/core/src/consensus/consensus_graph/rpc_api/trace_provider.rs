@@ -2,10 +2,12 @@ use crate::{
     block_data_manager::DataVersionTuple, errors::Result as CoreResult,
 };
 use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
-use cfx_execute_helper::exec_tracer::{
-    ActionType, BlockExecTraces, LocalizedTrace, TraceFilter,
+use cfx_execute_helper::{
+    estimation::EstimateRequest,
+    exec_tracer::{ActionType, BlockExecTraces, LocalizedTrace, TraceFilter},
 };
-use cfx_executor::state::State;
+use cfx_executor::{executive::TransactSettings, state::State};
+use cfx_parity_trace_types::{TraceOptions, TransactionReplayTraces};
 use cfx_types::{AddressWithSpace, Bloom, Space, H256, U256};
 use either::Either;
 use geth_tracer::GethTraceWithHash;
@@ -16,7 +18,7 @@ use primitives::{
     filter::{FilterError, LogFilter},
     log_entry::LocalizedLogEntry,
     receipt::Receipt,
-    Block, EpochNumber, SignedTransaction,
+    Block, BlockHeaderBuilder, EpochNumber, SignedTransaction,
 };
 use rayon::prelude::*;
 use std::{cmp::max, collections::HashSet, sync::Arc};
@@ -320,6 +322,19 @@ impl ConsensusGraph {
         &self, from_epoch: EpochNumber, to_epoch: EpochNumber,
         check_range: bool,
     ) -> Result<impl Iterator<Item = u64>, FilterError> {
+        let (from_epoch, to_epoch) =
+            self.get_log_filter_epoch_bounds(from_epoch, to_epoch)?;
+
+        if check_range {
+            self.check_log_filter_epoch_gap(from_epoch, to_epoch)?;
+        }
+
+        return Ok((from_epoch..=to_epoch).rev());
+    }
+
+    fn get_log_filter_epoch_bounds(
+        &self, from_epoch: EpochNumber, to_epoch: EpochNumber,
+    ) -> Result<(u64, u64), FilterError> {
         // lock so that we have a consistent view
         let _inner = self.inner.read_recursive();
 
@@ -341,20 +356,68 @@ impl ConsensusGraph {
             });
         }
 
-        if check_range {
-            if let Some(max_gap) = self.config.get_logs_filter_max_epoch_range {
-                // The range includes both ends.
-                if to_epoch - from_epoch + 1 > max_gap {
-                    return Err(FilterError::EpochNumberGapTooLarge {
-                        from_epoch,
-                        to_epoch,
-                        max_gap,
-                    });
-                }
+        Ok((from_epoch, to_epoch))
+    }
+
+    fn check_log_filter_epoch_gap(
+        &self, from_epoch: u64, to_epoch: u64,
+    ) -> Result<(), FilterError> {
+        if let Some(max_gap) = self.config.get_logs_filter_max_epoch_range {
+            // The range includes both ends.
+            if to_epoch - from_epoch + 1 > max_gap {
+                return Err(FilterError::EpochNumberGapTooLarge {
+                    from_epoch,
+                    to_epoch,
+                    max_gap,
+                });
             }
         }
+        Ok(())
+    }
+
+    /// Returns the epochs to filter in reverse order. If the log index covers
+    /// the range, only the epochs with indexed logs of the filter are
+    /// returned, and it is their number rather than the range that is limited
+    /// by `get_logs_filter_max_epoch_range`: an error is returned in place of
+    /// the epoch after the limit.
+    fn get_log_filter_epochs<'a>(
+        &'a self, from_epoch: EpochNumber, to_epoch: EpochNumber,
+        filter: &LogFilter, check_range: bool,
+    ) -> Result<impl Iterator<Item = Result<u64, FilterError>> + 'a, FilterError>
+    {
+        let (from_epoch, to_epoch) =
+            self.get_log_filter_epoch_bounds(from_epoch, to_epoch)?;
+
+        if let Some(epochs) = self.data_man.log_index_epochs(
+            filter.space,
+            &filter.address,
+            &filter.topics,
+            from_epoch,
+            to_epoch,
+        ) {
+            let max_epochs = self
+                .config
+                .get_logs_filter_max_epoch_range
+                .filter(|_| check_range);
+            return Ok(Either::Left(epochs.enumerate().map(
+                move |(i, epoch)| match max_epochs {
+                    Some(max_epochs) if i as u64 >= max_epochs => {
+                        Err(FilterError::TooManyIndexedEpochs {
+                            from_epoch,
+                            to_epoch,
+                            max_epochs,
+                        })
+                    }
+                    _ => Ok(epoch),
+                },
+            )));
+        }
 
-        return Ok((from_epoch..=to_epoch).rev());
+        if check_range {
+            self.check_log_filter_epoch_gap(from_epoch, to_epoch)?;
+        }
+
+        Ok(Either::Right((from_epoch..=to_epoch).rev().map(Ok)))
     }
 
     pub fn get_trace_filter_epoch_range(
@@ -396,7 +459,7 @@ impl ConsensusGraph {
 
         let mut logs = self
             // iterate over epochs in reverse order
-            .get_log_filter_epoch_range(from_epoch, to_epoch, check_range)?
+            .get_log_filter_epochs(from_epoch, to_epoch, filter, check_range)?
             // we process epochs in each batch in parallel
             // but batches are processed one-by-one
             .chunks(self.config.get_logs_epoch_batch_size)
@@ -405,7 +468,7 @@ impl ConsensusGraph {
                 self.filter_epoch_batch(
                     &filter,
                     &bloom_possibilities,
-                    epochs.into_iter().collect(),
+                    epochs.into_iter().collect::<Result<_, _>>()?,
                     &mut consistency_check_data,
                 )
             })
@@ -723,6 +786,79 @@ impl ConsensusGraph {
         &self, epoch_num: u64, tx_hash: Option<H256>,
         opts: GethDebugTracingOptions,
     ) -> CoreResult<Vec<GethTraceWithHash>> {
+        let blocks = self.get_stated_epoch_blocks(epoch_num)?;
+
+        let pivot_block = blocks.last().expect("Not empty");
+        let parent_pivot_block_hash = pivot_block.block_header.parent_hash();
+        let parent_epoch_num = pivot_block.block_header.height() - 1;
+
+        self.collect_blocks_geth_trace(
+            *parent_pivot_block_hash,
+            parent_epoch_num,
+            &blocks,
+            opts,
+            tx_hash,
+        )
+    }
+
+    /// Replays the transactions in the epoch (or only the transaction
+    /// `tx_hash`) and collects the parity traces.
+    pub fn collect_epoch_parity_trace(
+        &self, epoch_num: u64, tx_hash: Option<H256>, options: TraceOptions,
+    ) -> CoreResult<Vec<TransactionReplayTraces>> {
+        let blocks = self.get_stated_epoch_blocks(epoch_num)?;
+
+        let pivot_block = blocks.last().expect("Not empty");
+        let parent_pivot_block_hash = pivot_block.block_header.parent_hash();
+        let parent_epoch_num = pivot_block.block_header.height() - 1;
+
+        self.executor.collect_blocks_parity_trace(
+            *parent_pivot_block_hash,
+            parent_epoch_num,
+            &blocks,
+            options,
+            TransactSettings::all_checks(),
+            tx_hash,
+        )
+    }
+
+    /// Executes a transaction on the state after the epoch, in a block
+    /// following the pivot block of the epoch, and collects the parity
+    /// traces. The transaction is checked in the same way as `eth_call`
+    /// according to `request`.
+    pub fn collect_call_parity_trace(
+        &self, epoch_num: u64, tx: SignedTransaction, request: EstimateRequest,
+        options: TraceOptions,
+    ) -> CoreResult<TransactionReplayTraces> {
+        let blocks = self.get_stated_epoch_blocks(epoch_num)?;
+        let pivot_block = blocks.last().expect("Not empty");
+
+        let header = BlockHeaderBuilder::new()
+            .with_base_price(pivot_block.block_header.base_price())
+            .with_parent_hash(pivot_block.block_header.hash())
+            .with_height(epoch_num + 1)
+            .with_timestamp(pivot_block.block_header.timestamp() + 1)
+            .with_gas_limit(*pivot_block.block_header.gas_limit())
+            .build();
+        let block = Block::new(header, vec![Arc::new(tx)]);
+
+        let mut traces = self.executor.collect_blocks_parity_trace(
+            pivot_block.hash(),
+            epoch_num,
+            &vec![Arc::new(block)],
+            options,
+            request.virtual_call_settings(),
+            None,
+        )?;
+        match traces.pop() {
+            Some(trace) => Ok(trace),
+            None => bail!("trace generation failed"),
+        }
+    }
+
+    fn get_stated_epoch_blocks(
+        &self, epoch_num: u64,
+    ) -> CoreResult<Vec<Arc<Block>>> {
         let epoch = EpochNumber::Number(epoch_num);
         self.validate_stated_epoch(&epoch)?;
 
@@ -734,25 +870,13 @@ impl ConsensusGraph {
             bail!("cannot get block hashes in the specified epoch, maybe it does not exist?");
         };
 
-        let blocks = self
+        Ok(self
             .data_man
             .blocks_by_hash_list(
                 &epoch_block_hashes,
                 true, /* update_cache */
             )
-            .expect("blocks exist");
-
-        let pivot_block = blocks.last().expect("Not empty");
-        let parent_pivot_block_hash = pivot_block.block_header.parent_hash();
-        let parent_epoch_num = pivot_block.block_header.height() - 1;
-
-        self.collect_blocks_geth_trace(
-            *parent_pivot_block_hash,
-            parent_epoch_num,
-            &blocks,
-            opts,
-            tx_hash,
-        )
+            .expect("blocks exist"))
     }
 
     pub fn collect_blocks_geth_trace(
@@ -1,4 +1,6 @@
-use cfx_types::{H256, U256};
+use crate::errors::Result as CoreResult;
+
+use cfx_types::{AddressWithSpace, H256, U256};
 use primitives::{
     receipt::Receipt, SignedTransaction, TransactionIndex, TransactionStatus,
 };
@@ -41,6 +43,36 @@ impl ConsensusGraph {
         Some((transaction, tx_info))
     }
 
+    /// Returns the hashes of the transactions sent from or to `address` from
+    /// the newest one, starting at `cursor`, and the cursor of the next page.
+    /// Transactions of the epochs reverted by a pivot chain switch are left
+    /// out, so a page may have fewer than `limit` transactions.
+    pub fn get_transactions_by_address(
+        &self, address: &AddressWithSpace, cursor: Option<u64>, limit: usize,
+    ) -> CoreResult<(Vec<H256>, Option<u64>)> {
+        let (entries, next_cursor) = self
+            .data_man
+            .address_tx_index(address, cursor, limit)
+            .ok_or("address transaction index is not enabled, please set `persist_address_tx_index`")?;
+
+        let tx_hashes = entries
+            .into_iter()
+            .filter(|entry| {
+                self.data_man
+                    .transaction_index_by_hash(
+                        &entry.tx_hash,
+                        false, /* update_cache */
+                    )
+                    .and_then(|tx_index| {
+                        self.get_block_epoch_number(&tx_index.block_hash)
+                    })
+                    == Some(entry.epoch_number)
+            })
+            .map(|entry| entry.tx_hash)
+            .collect();
+        Ok((tx_hashes, next_cursor))
+    }
+
     fn get_transaction_info(&self, tx_hash: &H256) -> Option<TransactionInfo> {
         let inner = self.inner.read();
 
@@ -1,3 +1,5 @@
+mod parallel;
+
 use super::ConsensusExecutionHandler;
 use std::{collections::BTreeSet, convert::From, sync::Arc};
 
@@ -7,23 +9,27 @@ use geth_tracer::{GethTraceWithHash, GethTracer, TxExecContext};
 use pow_types::StakingEvent;
 
 use cfx_statedb::{Error as DbErrorKind, Result as DbResult};
-use cfx_types::{AddressSpaceUtil, Space, SpaceMap, H256, U256};
+use cfx_types::{
+    AddressSpaceUtil, AddressWithSpace, Space, SpaceMap, H256, U256,
+};
 use primitives::{
     receipt::BlockReceipts, AccessListItem, Action, Block, BlockNumber,
     Receipt, SignedTransaction, TransactionIndex,
 };
 
 use crate::{
-    block_data_manager::BlockDataManager,
+    block_data_manager::{tx_index_addresses, BlockDataManager},
     consensus::consensus_inner::consensus_executor::GOOD_TPS_METER,
 };
 use cfx_execute_helper::{
-    exec_tracer::TransactionExecTraces,
-    observer::Observer,
+    exec_tracer::{ExecTracer, TransactionExecTraces},
+    observer::{vm_tracer::VmTracer, Observer},
     tx_outcome::{make_process_tx_outcome, ProcessTxOutcome},
 };
 use cfx_executor::{
-    executive::{ExecutiveContext, TransactOptions, TransactSettings},
+    executive::{
+        ExecutionOutcome, ExecutiveContext, TransactOptions, TransactSettings,
+    },
     internal_contract::{
         block_hash_slot, epoch_hash_slot, initialize_internal_contract_accounts,
     },
@@ -32,10 +38,15 @@ use cfx_executor::{
         initialize_or_update_dao_voted_params, State,
     },
 };
-use cfx_vm_types::Env;
+use cfx_parity_trace_types::{
+    StateDiff, TraceOptions, TransactionReplayTraces,
+};
+use cfx_vm_types::{Env, Spec};
 
 pub enum VirtualCall<'a> {
     GethTrace(GethTask<'a>),
+    ParityTrace(ParityTask<'a>),
+    Pending(PendingTask<'a>),
 }
 
 pub struct GethTask<'a> {
@@ -44,6 +55,20 @@ pub struct GethTask<'a> {
     pub(super) answer: &'a mut Vec<GethTraceWithHash>,
 }
 
+pub struct ParityTask<'a> {
+    pub(super) tx_hash: Option<H256>,
+    pub(super) options: TraceOptions,
+    /// The settings to execute the traced transactions, which are relaxed
+    /// for a virtual call.
+    pub(super) settings: TransactSettings,
+    pub(super) answer: &'a mut Vec<TransactionReplayTraces>,
+}
+
+/// Collects the changes of the accounts made by all the transactions.
+pub struct PendingTask<'a> {
+    pub(super) answer: &'a mut StateDiff,
+}
+
 impl ConsensusExecutionHandler {
     pub(super) fn process_epoch_transactions<'a>(
         &self, state: &mut State, epoch_blocks: &Vec<Arc<Block>>,
@@ -93,8 +118,17 @@ impl ConsensusExecutionHandler {
             )?;
         }
 
-        if let Some(VirtualCall::GethTrace(task)) = context.virtual_call {
-            std::mem::swap(&mut epoch_recorder.geth_traces, task.answer);
+        match context.virtual_call {
+            Some(VirtualCall::GethTrace(task)) => {
+                std::mem::swap(&mut epoch_recorder.geth_traces, task.answer);
+            }
+            Some(VirtualCall::ParityTrace(task)) => {
+                std::mem::swap(&mut epoch_recorder.replay_traces, task.answer);
+            }
+            Some(VirtualCall::Pending(task)) => {
+                std::mem::swap(&mut epoch_recorder.state_diff, task.answer);
+            }
+            None => {}
         }
 
         if !dry_run && self.pos_verifier.pos_option().is_some() {
@@ -120,6 +154,14 @@ impl ConsensusExecutionHandler {
 
         if !dry_run && on_local_pivot {
             self.tx_pool.recycle_transactions(epoch_recorder.repack_tx);
+            self.data_man.insert_address_tx_index(
+                pivot_block.block_header.height(),
+                epoch_recorder.address_tx_index,
+            );
+            self.data_man.insert_log_index(
+                pivot_block.block_header.height(),
+                &epoch_recorder.receipts,
+            );
         }
 
         debug!("Finish processing tx for epoch");
@@ -221,7 +263,6 @@ impl ConsensusExecutionHandler {
         epoch_recorder: &mut EpochProcessRecorder,
     ) -> DbResult<()> {
         let BlockProcessContext {
-            epoch_context: &EpochProcessContext { on_local_pivot, .. },
             block,
             block_number,
             ..
@@ -246,16 +287,25 @@ impl ConsensusExecutionHandler {
         let mut block_recorder =
             BlockProcessRecorder::new(epoch_recorder.evm_tx_idx);
 
-        for (idx, transaction) in block.transactions.iter().enumerate() {
-            self.process_transaction(
-                idx,
-                transaction,
+        if let Some(pool) = self.parallel_execution_pool(block_context) {
+            self.process_transactions_in_parallel(
+                pool,
                 block_context,
                 state,
                 &mut env,
-                on_local_pivot,
                 &mut block_recorder,
             )?;
+        } else {
+            for (idx, transaction) in block.transactions.iter().enumerate() {
+                self.process_transaction(
+                    idx,
+                    transaction,
+                    block_context,
+                    state,
+                    &mut env,
+                    &mut block_recorder,
+                )?;
+            }
         }
 
         block_recorder.finish_block(
@@ -271,26 +321,73 @@ impl ConsensusExecutionHandler {
     fn process_transaction(
         &self, idx: usize, transaction: &Arc<SignedTransaction>,
         block_context: &BlockProcessContext, state: &mut State, env: &mut Env,
-        on_local_pivot: bool, recorder: &mut BlockProcessRecorder,
+        recorder: &mut BlockProcessRecorder,
     ) -> DbResult<()> {
         let rpc_index = recorder.tx_idx[transaction.space()];
 
-        let block = &block_context.block;
-        let dry_run = block_context.epoch_context.dry_run;
+        let spec = self.machine.spec(env.number, env.epoch_height);
 
-        let machine = self.machine.as_ref();
+        let observer =
+            self.make_observer(transaction, rpc_index, block_context, &spec);
 
-        let spec = machine.spec(env.number, env.epoch_height);
+        env.transaction_hash = transaction.hash();
+        let settings =
+            block_context.epoch_context.transact_settings(transaction);
+        let execution_outcome = self.execute_transaction(
+            transaction,
+            state,
+            env,
+            &spec,
+            observer,
+            settings,
+        )?;
+        // The state diff must be read before the cache is committed.
+        let state_diff =
+            if block_context.epoch_context.needs_state_diff(transaction) {
+                Some(state.state_diff()?)
+            } else {
+                None
+            };
+
+        self.finish_transaction(
+            idx,
+            transaction,
+            block_context,
+            state,
+            env,
+            &spec,
+            execution_outcome,
+            state_diff,
+            recorder,
+        )
+    }
 
-        let options = TransactOptions {
-            observer: self.make_observer(transaction, block_context),
-            settings: TransactSettings::all_checks(),
-        };
+    fn execute_transaction(
+        &self, transaction: &SignedTransaction, state: &mut State, env: &Env,
+        spec: &Spec, observer: Observer, settings: TransactSettings,
+    ) -> DbResult<ExecutionOutcome> {
+        let options = TransactOptions { observer, settings };
+        ExecutiveContext::new(state, env, self.machine.as_ref(), spec)
+            .transact(transaction, options)
+    }
+
+    /// Commits the changes of an executed transaction in the cache, and
+    /// records its outcome.
+    fn finish_transaction(
+        &self, idx: usize, transaction: &Arc<SignedTransaction>,
+        block_context: &BlockProcessContext, state: &mut State, env: &mut Env,
+        spec: &Spec, execution_outcome: ExecutionOutcome,
+        state_diff: Option<StateDiff>, recorder: &mut BlockProcessRecorder,
+    ) -> DbResult<()> {
+        let rpc_index = recorder.tx_idx[transaction.space()];
+
+        let block = &block_context.block;
+        let EpochProcessContext {
+            on_local_pivot,
+            dry_run,
+            ..
+        } = *block_context.epoch_context;
 
-        env.transaction_hash = transaction.hash();
-        let execution_outcome =
-            ExecutiveContext::new(state, env, machine, &spec)
-                .transact(transaction, options)?;
         state.update_state_post_tx_execution(!spec.cip645.fix_eip1153);
         execution_outcome.log(transaction, &block_context.block.hash());
 
@@ -301,12 +398,13 @@ impl ConsensusExecutionHandler {
             state.burn_by_cip1559(burnt_fee);
         };
 
-        let r = make_process_tx_outcome(
+        let mut r = make_process_tx_outcome(
             execution_outcome,
             &mut env.accumulated_gas_used,
             transaction.hash,
-            &spec,
+            spec,
         );
+        r.state_diff = state_diff;
 
         if r.receipt.tx_success() {
             GOOD_TPS_METER.mark(1);
@@ -333,6 +431,11 @@ impl ConsensusExecutionHandler {
                 rpc_index: Some(rpc_index),
             },
         );
+        for address in
+            tx_index_addresses(transaction.sender(), &transaction.action())
+        {
+            recorder.address_tx_index.push((address, hash));
+        }
 
         // persist tx index for phantom transactions.
         // note: in some cases, pivot chain reorgs will result in
@@ -344,8 +447,11 @@ impl ConsensusExecutionHandler {
         let evm_tx_index = &mut recorder.tx_idx[Space::Ethereum];
 
         for ptx in phantom_txs {
+            let from = ptx.from.with_evm_space();
+            let action = ptx.action.clone();
+            let hash = ptx.into_eip155(evm_chain_id).hash();
             self.data_man.insert_transaction_index(
-                &ptx.into_eip155(evm_chain_id).hash(),
+                &hash,
                 &TransactionIndex {
                     block_hash: block.hash(),
                     real_index: idx,
@@ -353,6 +459,9 @@ impl ConsensusExecutionHandler {
                     rpc_index: Some(*evm_tx_index),
                 },
             );
+            for address in tx_index_addresses(from, &action) {
+                recorder.address_tx_index.push((address, hash));
+            }
 
             *evm_tx_index += 1;
         }
@@ -361,11 +470,12 @@ impl ConsensusExecutionHandler {
     }
 
     fn make_observer(
-        &self, transaction: &Arc<SignedTransaction>,
-        block_context: &BlockProcessContext,
+        &self, transaction: &Arc<SignedTransaction>, rpc_index: usize,
+        block_context: &BlockProcessContext, spec: &Spec,
     ) -> Observer {
         use alloy_rpc_types_trace::geth::{
-            GethDebugBuiltInTracerType::*, GethDebugTracerType::BuiltInTracer,
+            GethDebugBuiltInTracerType::*,
+            GethDebugTracerType::{BuiltInTracer, JsTracer},
         };
 
         let mut observer = if self.config.executive_trace {
@@ -382,8 +492,14 @@ impl ConsensusExecutionHandler {
             let support_tracer = matches!(
                 task.opts.tracer,
                 Some(BuiltInTracer(
-                    FourByteTracer | CallTracer | PreStateTracer | NoopTracer
-                )) | None
+                    FourByteTracer
+                        | CallTracer
+                        | PreStateTracer
+                        | NoopTracer
+                        | MuxTracer
+                        | FlatCallTracer
+                )) | Some(JsTracer(_))
+                    | None
             );
             let tx_gas_limit = transaction.gas_limit().as_u64();
 
@@ -397,12 +513,28 @@ impl ConsensusExecutionHandler {
                             .block_header
                             .height(),
                         block_number: block_context.block_number,
+                        tx_hash: transaction.hash(),
+                        tx_index: rpc_index,
+                        block_hash: block_context
+                            .epoch_context
+                            .pivot_block
+                            .hash(),
                     },
                     Arc::clone(&self.machine),
                     task.opts.clone(),
                 ))
             }
         }
+
+        if let Some(task) = block_context.epoch_context.parity_task(transaction)
+        {
+            if task.options.trace && observer.tracer.is_none() {
+                observer.tracer = Some(ExecTracer::default());
+            }
+            if task.options.vm_trace {
+                observer.vm_tracer = Some(VmTracer::new(spec.clone()));
+            }
+        }
         observer
     }
 
@@ -497,6 +629,42 @@ struct EpochProcessContext<'a> {
     burnt_gas_price: SpaceMap<U256>,
 }
 
+impl<'a> EpochProcessContext<'a> {
+    /// Returns the parity trace task if the transaction should be traced.
+    fn parity_task(
+        &self, transaction: &SignedTransaction,
+    ) -> Option<&ParityTask<'a>> {
+        match &self.virtual_call {
+            Some(VirtualCall::ParityTrace(task))
+                if task
+                    .tx_hash
+                    .map_or(true, |hash| transaction.hash() == hash) =>
+            {
+                Some(task)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the settings to execute the transaction.
+    fn transact_settings(
+        &self, transaction: &SignedTransaction,
+    ) -> TransactSettings {
+        self.parity_task(transaction)
+            .map_or_else(TransactSettings::all_checks, |task| task.settings)
+    }
+
+    /// Returns whether the state diff of the transaction should be collected.
+    fn needs_state_diff(&self, transaction: &SignedTransaction) -> bool {
+        match &self.virtual_call {
+            Some(VirtualCall::Pending(_)) => true,
+            _ => self
+                .parity_task(transaction)
+                .map_or(false, |task| task.options.state_diff),
+        }
+    }
+}
+
 struct BlockProcessContext<'a, 'b> {
     epoch_context: &'b EpochProcessContext<'a>,
     block: &'b Block,
@@ -532,6 +700,9 @@ struct EpochProcessRecorder {
     staking_events: Vec<StakingEvent>,
     repack_tx: Vec<Arc<SignedTransaction>>,
     geth_traces: Vec<GethTraceWithHash>,
+    replay_traces: Vec<TransactionReplayTraces>,
+    state_diff: StateDiff,
+    address_tx_index: Vec<(AddressWithSpace, H256)>,
 
     evm_tx_idx: usize,
 }
@@ -545,8 +716,11 @@ struct BlockProcessRecorder {
     tx_error_msg: Vec<String>,
     traces: Vec<TransactionExecTraces>,
     geth_traces: Vec<GethTraceWithHash>,
+    replay_traces: Vec<TransactionReplayTraces>,
+    state_diff: StateDiff,
     repack_tx: Vec<Arc<SignedTransaction>>,
     staking_events: Vec<StakingEvent>,
+    address_tx_index: Vec<(AddressWithSpace, H256)>,
 
     tx_idx: SpaceMap<usize>,
 }
@@ -560,14 +734,17 @@ impl BlockProcessRecorder {
             tx_error_msg: vec![],
             traces: vec![],
             geth_traces: vec![],
+            replay_traces: vec![],
+            state_diff: Default::default(),
             repack_tx: vec![],
             staking_events: vec![],
+            address_tx_index: vec![],
             tx_idx,
         }
     }
 
     fn receive_tx_outcome(
-        &mut self, r: ProcessTxOutcome, tx: &Arc<SignedTransaction>,
+        &mut self, mut r: ProcessTxOutcome, tx: &Arc<SignedTransaction>,
         block_context: &BlockProcessContext,
     ) {
         let EpochProcessContext {
@@ -582,6 +759,31 @@ impl BlockProcessRecorder {
 
         let not_skipped = !r.receipt.tx_skipped();
 
+        if let Some(VirtualCall::Pending(_)) =
+            block_context.epoch_context.virtual_call
+        {
+            if let Some(state_diff) = r.state_diff.take() {
+                self.state_diff.merge(state_diff);
+            }
+        }
+
+        // The skipped transactions are not executed, so they are not
+        // replayed.
+        let parity_task = block_context
+            .epoch_context
+            .parity_task(tx)
+            .filter(|_| not_skipped);
+        if let Some(task) = parity_task {
+            self.replay_traces.push(TransactionReplayTraces {
+                tx_hash: tx.hash(),
+                space: tx.space(),
+                output: r.output,
+                trace: task.options.trace.then(|| r.tx_traces.clone()),
+                vm_trace: r.vm_trace,
+                state_diff: r.state_diff,
+            });
+        }
+
         if executive_trace {
             self.traces.push(r.tx_traces.into());
         }
@@ -641,6 +843,11 @@ impl BlockProcessRecorder {
         epoch_recorder.staking_events.extend(self.staking_events);
         epoch_recorder.repack_tx.extend(self.repack_tx);
         epoch_recorder.geth_traces.extend(self.geth_traces);
+        epoch_recorder.replay_traces.extend(self.replay_traces);
+        epoch_recorder.state_diff.merge(self.state_diff);
+        epoch_recorder
+            .address_tx_index
+            .extend(self.address_tx_index);
 
         epoch_recorder.evm_tx_idx = self.tx_idx[Space::Ethereum];
 
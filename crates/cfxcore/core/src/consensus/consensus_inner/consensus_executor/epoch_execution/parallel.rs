@@ -0,0 +1,582 @@
+//! Optimistic parallel execution of the transactions in a block.
+//!
+//! The transactions are first executed speculatively in parallel, each on a
+//! view of the state at the beginning of the block. The results are then
+//! replayed on the canonical state in the block order. A result is accepted
+//! only if none of the accounts it has loaded was modified by the preceding
+//! transactions in the block, and the global statistic variables it depends
+//! on are still consistent. Otherwise, the transaction is executed again on
+//! the canonical state. Thus the receipts and the state root are identical to
+//! the serial execution.
+//!
+//! Before CIP-645, the transient storage is kept across the transactions. It
+//! is cached with the account, so it goes through the same conflict check as
+//! the other fields of the account, and the historical blocks can be
+//! executed in parallel as well.
+//!
+//! A speculative state deeply copies the accounts it loads from the committed
+//! cache, including their cached storage entries, so the parallel execution
+//! only pays off for blocks with many independent transactions.
+
+use super::{BlockProcessContext, BlockProcessRecorder};
+use crate::consensus::consensus_inner::consensus_executor::ConsensusExecutionHandler;
+use cfx_execute_helper::observer::Observer;
+use cfx_executor::{
+    executive::{ExecutionOutcome, TransactSettings},
+    state::{SpeculativeResult, State},
+};
+use cfx_statedb::{Result as DbResult, StateDb};
+use cfx_vm_types::{Env, Spec};
+use parking_lot::Mutex;
+use primitives::SignedTransaction;
+use rayon::{prelude::*, ThreadPool};
+use std::{collections::HashSet, sync::Arc};
+
+impl ConsensusExecutionHandler {
+    /// Returns the thread pool to execute the transactions of the block in
+    /// parallel, or `None` if they should be executed serially.
+    pub(super) fn parallel_execution_pool(
+        &self, block_context: &BlockProcessContext,
+    ) -> Option<&ThreadPool> {
+        let pool = self.config.parallel_executor.as_deref()?;
+
+        // Virtual calls collect traces and state diffs per transaction.
+        if block_context.epoch_context.virtual_call.is_some()
+            || block_context.block.transactions.len() < 2
+        {
+            return None;
+        }
+        Some(pool)
+    }
+
+    pub(super) fn process_transactions_in_parallel(
+        &self, pool: &ThreadPool, block_context: &BlockProcessContext,
+        state: &mut State, env: &mut Env, recorder: &mut BlockProcessRecorder,
+    ) -> DbResult<()> {
+        let block = block_context.block;
+        let pivot_block = block_context.epoch_context.pivot_block;
+        let spec = self.machine.spec(env.number, env.epoch_height);
+
+        let block_env = &*env;
+        let speculative_results = execute_speculatively(
+            pool,
+            &block.transactions,
+            state,
+            || self.new_speculative_state_db(pivot_block),
+            |transaction, speculative_state| {
+                let mut env = block_env.clone();
+                env.transaction_hash = transaction.hash();
+                // Virtual calls are never executed in parallel, so only the
+                // executive traces are needed.
+                let observer = if self.config.executive_trace {
+                    Observer::with_tracing()
+                } else {
+                    Observer::with_no_tracing()
+                };
+                self.execute_transaction(
+                    transaction,
+                    speculative_state,
+                    &env,
+                    &spec,
+                    observer,
+                    TransactSettings::all_checks(),
+                )
+            },
+        );
+
+        let mut executor = BlockExecutor {
+            handler: self,
+            block_context,
+            env,
+            spec: &spec,
+            recorder,
+        };
+        let reexecuted = replay_speculative_results(
+            state,
+            &block.transactions,
+            speculative_results,
+            &mut executor,
+        )?;
+
+        debug!(
+            "Parallel execution of block {:?}: {} txs, {} re-executed",
+            block.hash(),
+            block.transactions.len(),
+            reexecuted
+        );
+        Ok(())
+    }
+}
+
+/// Executes the transactions on the canonical state, in the block order.
+trait SerialExecutor {
+    /// Executes a transaction whose speculative result is invalid.
+    fn execute(
+        &mut self, transaction: &Arc<SignedTransaction>, state: &mut State,
+    ) -> DbResult<ExecutionOutcome>;
+
+    /// Commits the changes of the transaction at `idx` in the cache, and
+    /// records its outcome.
+    fn finish(
+        &mut self, idx: usize, transaction: &Arc<SignedTransaction>,
+        state: &mut State, outcome: ExecutionOutcome,
+    ) -> DbResult<()>;
+}
+
+struct BlockExecutor<'a, 'b, 'c> {
+    handler: &'a ConsensusExecutionHandler,
+    block_context: &'a BlockProcessContext<'b, 'c>,
+    env: &'a mut Env,
+    spec: &'a Spec,
+    recorder: &'a mut BlockProcessRecorder,
+}
+
+impl SerialExecutor for BlockExecutor<'_, '_, '_> {
+    fn execute(
+        &mut self, transaction: &Arc<SignedTransaction>, state: &mut State,
+    ) -> DbResult<ExecutionOutcome> {
+        self.env.transaction_hash = transaction.hash();
+        let rpc_index = self.recorder.tx_idx[transaction.space()];
+        let observer = self.handler.make_observer(
+            transaction,
+            rpc_index,
+            self.block_context,
+            self.spec,
+        );
+        self.handler.execute_transaction(
+            transaction,
+            state,
+            self.env,
+            self.spec,
+            observer,
+            TransactSettings::all_checks(),
+        )
+    }
+
+    fn finish(
+        &mut self, idx: usize, transaction: &Arc<SignedTransaction>,
+        state: &mut State, outcome: ExecutionOutcome,
+    ) -> DbResult<()> {
+        self.env.transaction_hash = transaction.hash();
+        self.handler.finish_transaction(
+            idx,
+            transaction,
+            self.block_context,
+            state,
+            self.env,
+            self.spec,
+            outcome,
+            /* state_diff */ None,
+            self.recorder,
+        )
+    }
+}
+
+/// Executes the transactions speculatively on `pool`, on top of the committed
+/// cache of `state`. Each worker thread opens its speculative state once with
+/// `new_state_db`, and reuses it for all the transactions it executes. The
+/// result of a transaction is `None` if the speculative execution fails.
+fn execute_speculatively<N, E>(
+    pool: &ThreadPool, transactions: &[Arc<SignedTransaction>],
+    state: &mut State, new_state_db: N, execute: E,
+) -> Vec<Option<(ExecutionOutcome, SpeculativeResult)>>
+where
+    N: Fn() -> Option<StateDb> + Sync,
+    E: Fn(&SignedTransaction, &mut State) -> DbResult<ExecutionOutcome> + Sync,
+{
+    let base = state.take_speculative_base();
+    let workers: Vec<Mutex<Option<State>>> = (0..pool.current_num_threads())
+        .map(|_| Mutex::new(None))
+        .collect();
+
+    let results = pool.install(|| {
+        transactions
+            .par_iter()
+            .map(|transaction| {
+                // Only the worker thread itself locks its state.
+                let mut worker = workers[rayon::current_thread_index()?].lock();
+                if worker.is_none() {
+                    *worker = Some(State::new_speculative(
+                        new_state_db()?,
+                        base.clone(),
+                    ));
+                }
+                let speculative_state = worker.as_mut()?;
+
+                match execute(transaction, speculative_state) {
+                    Ok(outcome) => Some((
+                        outcome,
+                        speculative_state.take_speculative_result(),
+                    )),
+                    Err(e) => {
+                        debug!(
+                            "Speculative execution of tx {:?} fails: {:?}",
+                            transaction.hash(),
+                            e
+                        );
+                        // The state may be left in the middle of the
+                        // transaction, so the worker opens a new one.
+                        *worker = None;
+                        None
+                    }
+                }
+            })
+            .collect()
+    });
+
+    drop(workers);
+    state.restore_speculative_base(base);
+    results
+}
+
+/// Replays the speculative results on `state` in the block order, executing
+/// the transactions with invalid results again. Returns the number of the
+/// transactions executed again.
+fn replay_speculative_results<S: SerialExecutor>(
+    state: &mut State, transactions: &[Arc<SignedTransaction>],
+    speculative_results: Vec<Option<(ExecutionOutcome, SpeculativeResult)>>,
+    executor: &mut S,
+) -> DbResult<usize> {
+    let mut changed_accounts = HashSet::new();
+    let mut reexecuted = 0;
+    for (idx, (transaction, speculative_result)) in
+        transactions.iter().zip(speculative_results).enumerate()
+    {
+        let outcome = match speculative_result {
+            Some((outcome, result))
+                if state.validate_speculative_result(
+                    &result,
+                    &changed_accounts,
+                ) =>
+            {
+                changed_accounts.extend(state.apply_speculative_result(result));
+                outcome
+            }
+            _ => {
+                reexecuted += 1;
+                let outcome = executor.execute(transaction, state)?;
+                // Conservatively, it includes the accounts only loaded in
+                // this transaction but changed in the previous blocks.
+                changed_accounts.extend(state.dirty_cached_accounts());
+                outcome
+            }
+        };
+        executor.finish(idx, transaction, state, outcome)?;
+    }
+    Ok(reexecuted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_executor::{
+        executive::{ExecutiveContext, TransactOptions},
+        machine::{Machine, VmFactory},
+        spec::CommonParams,
+    };
+    use cfx_parameters::internal_contract_addresses::STORAGE_INTEREST_STAKING_CONTRACT_ADDRESS;
+    use cfx_types::{
+        address_util::AddressUtil, Address, AddressSpaceUtil, H256, U256,
+    };
+    use cfxkey::{Generator, KeyPair, Random};
+    use primitives::{
+        storage::STORAGE_LAYOUT_REGULAR_V0,
+        transaction::native_transaction::NativeTransaction, Action, EpochId,
+        Receipt, Transaction, TransactionStatus,
+    };
+    use rand::{Rng, SeedableRng};
+    use rand_xorshift::XorShiftRng;
+    use rayon::ThreadPoolBuilder;
+    use rustc_hex::FromHex;
+
+    /// The code of a contract which emits its call data as a log.
+    const LOG_CALL_DATA: &str = "366000600037366000a000";
+    /// The code of a contract which stores the first word of its call data
+    /// in slot 0 and increments the counter in slot 1.
+    const STORE_CALL_DATA: &str = "60003560005560015460010160015500";
+    /// The code of a contract which always reverts.
+    const REVERT: &str = "60006000fd";
+    /// The call data of `deposit(uint256)` of the staking contract, with 1
+    /// CFX.
+    const DEPOSIT_ONE_CFX: &str = "b6b55f250000000000000000000000000000000000000000000000000de0b6b3a7640000";
+
+    struct TestExecutor<'a> {
+        machine: &'a Machine,
+        env: Env,
+        spec: Spec,
+        receipts: Vec<Receipt>,
+        reexecuted: Vec<H256>,
+    }
+
+    impl<'a> TestExecutor<'a> {
+        fn new(machine: &'a Machine, env: &Env) -> Self {
+            TestExecutor {
+                machine,
+                env: env.clone(),
+                spec: machine.spec(env.number, env.epoch_height),
+                receipts: Vec::new(),
+                reexecuted: Vec::new(),
+            }
+        }
+    }
+
+    impl SerialExecutor for TestExecutor<'_> {
+        fn execute(
+            &mut self, transaction: &Arc<SignedTransaction>, state: &mut State,
+        ) -> DbResult<ExecutionOutcome> {
+            self.reexecuted.push(transaction.hash());
+            execute(self.machine, &self.env, &self.spec, transaction, state)
+        }
+
+        fn finish(
+            &mut self, _idx: usize, _transaction: &Arc<SignedTransaction>,
+            state: &mut State, outcome: ExecutionOutcome,
+        ) -> DbResult<()> {
+            state.update_state_post_tx_execution(!self.spec.cip645.fix_eip1153);
+            self.receipts.push(
+                outcome.make_receipt(
+                    &mut self.env.accumulated_gas_used,
+                    &self.spec,
+                ),
+            );
+            Ok(())
+        }
+    }
+
+    fn execute(
+        machine: &Machine, env: &Env, spec: &Spec,
+        transaction: &SignedTransaction, state: &mut State,
+    ) -> DbResult<ExecutionOutcome> {
+        ExecutiveContext::new(state, env, machine, spec)
+            .transact(transaction, TransactOptions::default())
+    }
+
+    fn contract(n: u64) -> Address {
+        let mut contract = Address::from_low_u64_be(n);
+        contract.set_contract_type_bits();
+        contract
+    }
+
+    fn new_state(senders: &[KeyPair]) -> State {
+        let mut state = State::new(StateDb::new_for_unit_test()).unwrap();
+        let balance = U256::exp10(21);
+        for sender in senders {
+            state
+                .add_balance(&sender.address().with_native_space(), &balance)
+                .unwrap();
+            state.add_total_issued(balance);
+        }
+        for (n, code) in [(1, LOG_CALL_DATA), (2, STORE_CALL_DATA), (3, REVERT)]
+        {
+            let contract = contract(n).with_native_space();
+            state
+                .new_contract_with_admin(
+                    &contract,
+                    &Address::zero(),
+                    U256::zero(),
+                    Some(STORAGE_LAYOUT_REGULAR_V0),
+                    /* cip107 */ false,
+                )
+                .unwrap();
+            state
+                .init_code(
+                    &contract,
+                    code.from_hex().unwrap(),
+                    Address::zero(),
+                    H256::zero(),
+                )
+                .unwrap();
+        }
+        state.commit_cache(false);
+        state
+    }
+
+    fn transaction(
+        sender: &KeyPair, nonce: u64, action: Action, value: u64, data: &str,
+    ) -> Arc<SignedTransaction> {
+        Arc::new(
+            Transaction::from(NativeTransaction {
+                nonce: nonce.into(),
+                gas_price: U256::one(),
+                gas: U256::from(200_000),
+                action,
+                value: value.into(),
+                storage_limit: 1024,
+                epoch_height: 0,
+                chain_id: 1,
+                data: data.from_hex().unwrap(),
+            })
+            .sign(sender.secret()),
+        )
+    }
+
+    fn call(
+        sender: &KeyPair, nonce: u64, to: Address, value: u64, data: &str,
+    ) -> Arc<SignedTransaction> {
+        transaction(sender, nonce, Action::Call(to), value, data)
+    }
+
+    /// Executes the transactions serially and in parallel on the same
+    /// initial state, and checks that the receipts and the state roots are
+    /// identical. Returns the receipts and the hashes of the transactions
+    /// executed again in the parallel execution.
+    fn check_parallel_execution(
+        senders: &[KeyPair], transactions: &[Arc<SignedTransaction>],
+    ) -> (Vec<Receipt>, Vec<H256>) {
+        let machine = Machine::new_with_builtin(
+            CommonParams::default(),
+            VmFactory::new(1024 * 32),
+            &Default::default(),
+            &Default::default(),
+        );
+        let mut env = Env::default();
+        env.gas_limit = U256::from(10_000_000);
+        let spec = machine.spec(env.number, env.epoch_height);
+
+        let mut serial_state = new_state(senders);
+        let mut serial = TestExecutor::new(&machine, &env);
+        for (idx, transaction) in transactions.iter().enumerate() {
+            let outcome =
+                execute(&machine, &env, &spec, transaction, &mut serial_state)
+                    .unwrap();
+            serial
+                .finish(idx, transaction, &mut serial_state, outcome)
+                .unwrap();
+        }
+
+        let pool = ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+        let mut parallel_state = new_state(senders);
+        let mut parallel = TestExecutor::new(&machine, &env);
+        let speculative_results = execute_speculatively(
+            &pool,
+            transactions,
+            &mut parallel_state,
+            || Some(StateDb::new_for_unit_test()),
+            |transaction, state| {
+                execute(&machine, &env, &spec, transaction, state)
+            },
+        );
+        let reexecuted = replay_speculative_results(
+            &mut parallel_state,
+            transactions,
+            speculative_results,
+            &mut parallel,
+        )
+        .unwrap();
+
+        assert_eq!(parallel.receipts, serial.receipts);
+        assert_eq!(reexecuted, parallel.reexecuted.len());
+        let epoch_id = EpochId::default();
+        assert_eq!(
+            parallel_state.commit(epoch_id, None).unwrap().state_root,
+            serial_state.commit(epoch_id, None).unwrap().state_root,
+        );
+        (parallel.receipts, parallel.reexecuted)
+    }
+
+    #[test]
+    fn test_parallel_execution_matches_serial_execution() {
+        let senders: Vec<_> =
+            (0..3).map(|_| Random.generate().unwrap()).collect();
+        let mut recipient = Address::from_low_u64_be(4);
+        recipient.set_user_account_type_bits();
+
+        let transactions = vec![
+            call(&senders[0], 0, senders[1].address(), 1000, ""),
+            // Conflicts with the first one on the balance of the sender.
+            call(&senders[1], 0, recipient, 500, ""),
+            call(&senders[2], 0, contract(1), 0, "01"),
+            // The speculative execution sees a stale nonce.
+            call(&senders[0], 1, contract(1), 0, "02"),
+            call(&senders[2], 1, recipient, 300, ""),
+        ];
+        let (receipts, reexecuted) =
+            check_parallel_execution(&senders, &transactions);
+
+        assert!(receipts.iter().all(
+            |receipt| receipt.outcome_status == TransactionStatus::Success
+        ));
+        assert_eq!(receipts[2].logs[0].data, vec![0x01]);
+        assert_eq!(receipts[3].logs[0].data, vec![0x02]);
+
+        assert!(!reexecuted.contains(&transactions[0].hash()));
+        for idx in [1, 3, 4] {
+            assert!(reexecuted.contains(&transactions[idx].hash()));
+        }
+    }
+
+    #[test]
+    fn test_parallel_execution_with_conflicts() {
+        let senders: Vec<_> =
+            (0..3).map(|_| Random.generate().unwrap()).collect();
+        let staking = STORAGE_INTEREST_STAKING_CONTRACT_ADDRESS;
+
+        let transactions = vec![
+            // The same slots are written by all the senders.
+            call(&senders[0], 0, contract(2), 0, "01"),
+            call(&senders[1], 0, contract(2), 0, "02"),
+            call(&senders[2], 0, contract(2), 0, "03"),
+            // A chain of transactions of the same sender, creating contracts.
+            transaction(&senders[0], 1, Action::Create, 0, "00"),
+            transaction(&senders[0], 2, Action::Create, 10, "00"),
+            call(&senders[0], 3, contract(2), 0, "04"),
+            // The reverted transactions still pay the gas.
+            call(&senders[1], 1, contract(3), 100, ""),
+            call(&senders[2], 1, contract(3), 0, ""),
+            // The deposits change the total staking tokens.
+            call(&senders[1], 2, staking, 0, DEPOSIT_ONE_CFX),
+            call(&senders[2], 2, staking, 0, DEPOSIT_ONE_CFX),
+            call(&senders[1], 3, contract(2), 0, "05"),
+        ];
+        let (receipts, reexecuted) =
+            check_parallel_execution(&senders, &transactions);
+
+        for (idx, receipt) in receipts.iter().enumerate() {
+            let expected = if idx == 6 || idx == 7 {
+                TransactionStatus::Failure
+            } else {
+                TransactionStatus::Success
+            };
+            assert_eq!(receipt.outcome_status, expected);
+        }
+        // All the transactions after the first one conflict with a preceding
+        // one.
+        assert!(!reexecuted.contains(&transactions[0].hash()));
+        assert_eq!(reexecuted.len(), transactions.len() - 1);
+    }
+
+    #[test]
+    fn test_parallel_execution_random_blocks() {
+        let staking = STORAGE_INTEREST_STAKING_CONTRACT_ADDRESS;
+        for seed in 0..8 {
+            let mut rng = XorShiftRng::seed_from_u64(seed);
+            let senders: Vec<_> =
+                (0..4).map(|_| Random.generate().unwrap()).collect();
+            let mut nonces = vec![0; senders.len()];
+
+            let transactions: Vec<_> = (0..40)
+                .map(|_| {
+                    let idx = rng.random_range(0..senders.len());
+                    let sender = &senders[idx];
+                    let nonce = nonces[idx];
+                    nonces[idx] += 1;
+                    let data = format!("{:02x}", rng.random::<u8>());
+                    match rng.random_range(0..6) {
+                        0 => {
+                            let to = &senders[rng.random_range(0..4)];
+                            call(sender, nonce, to.address(), 1000, "")
+                        }
+                        1 => call(sender, nonce, contract(1), 0, &data),
+                        2 => call(sender, nonce, contract(2), 0, &data),
+                        3 => call(sender, nonce, contract(3), 0, ""),
+                        4 => {
+                            transaction(sender, nonce, Action::Create, 0, "00")
+                        }
+                        _ => call(sender, nonce, staking, 0, DEPOSIT_ONE_CFX),
+                    }
+                })
+                .collect();
+            check_parallel_execution(&senders, &transactions);
+        }
+    }
+}
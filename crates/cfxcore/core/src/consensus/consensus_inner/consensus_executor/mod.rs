@@ -62,19 +62,22 @@ use cfx_execute_helper::estimation::{
     EstimateExt, EstimateRequest, EstimationContext,
 };
 use cfx_executor::{
-    executive::{ExecutionOutcome, ExecutiveContext},
+    executive::{ExecutionOutcome, ExecutiveContext, TransactSettings},
     machine::Machine,
     state::{
         distribute_pos_interest, update_pos_status, State, StateCommitResult,
     },
 };
+use cfx_parity_trace_types::{
+    StateDiff, TraceOptions, TransactionReplayTraces,
+};
 use cfx_vm_types::{Env, Spec};
 use geth_tracer::GethTraceWithHash;
 
 use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
 use cfx_rpc_eth_types::EvmOverrides;
 
-use self::epoch_execution::{GethTask, VirtualCall};
+use self::epoch_execution::{GethTask, ParityTask, PendingTask, VirtualCall};
 
 lazy_static! {
     static ref CONSENSIS_EXECUTION_TIMER: Arc<dyn Meter> =
@@ -653,6 +656,21 @@ impl ConsensusExecutor {
         )
     }
 
+    pub fn call_virtual_on_statedb(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+        statedb: StateDb, request: EstimateRequest,
+        evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        self.handler.call_virtual_on_statedb(
+            tx,
+            epoch_id,
+            epoch_size,
+            statedb,
+            request,
+            evm_overrides,
+        )
+    }
+
     pub fn collect_blocks_geth_trace(
         &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
         opts: GethDebugTracingOptions, tx_hash: Option<H256>,
@@ -662,6 +680,28 @@ impl ConsensusExecutor {
         )
     }
 
+    pub fn execute_pending_block(
+        &self, epoch_id: &H256, epoch_height: u64, epoch_size: usize,
+        block: Arc<Block>,
+    ) -> CoreResult<(Arc<BlockReceipts>, StateDiff)> {
+        self.handler.execute_pending_block(
+            epoch_id,
+            epoch_height,
+            epoch_size,
+            block,
+        )
+    }
+
+    pub fn collect_blocks_parity_trace(
+        &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
+        options: TraceOptions, settings: TransactSettings,
+        tx_hash: Option<H256>,
+    ) -> CoreResult<Vec<TransactionReplayTraces>> {
+        self.handler.collect_blocks_parity_trace(
+            epoch_id, epoch_num, blocks, options, settings, tx_hash,
+        )
+    }
+
     pub fn stop(&self) {
         // `stopped` is used to allow the execution thread to stopped even the
         // queue is not empty and `ExecutionTask::Stop` has not been
@@ -924,6 +964,37 @@ impl ConsensusExecutionHandler {
         &self, pivot_block: &Block,
         recover_mpt_during_construct_pivot_state: bool,
     ) -> DbResult<State> {
+        let storage = self
+            .data_man
+            .storage_manager
+            .get_state_for_next_epoch(
+                self.state_index_for_next_epoch(pivot_block),
+                recover_mpt_during_construct_pivot_state,
+            )
+            .expect("No db error")
+            // Unwrapping is safe because the state exists.
+            .expect("State exists");
+
+        let state_db = StateDb::new(storage);
+        State::new(state_db)
+    }
+
+    /// Opens another view of the state which the epoch of `pivot_block` is
+    /// executed on, for the workers of the parallel execution.
+    fn new_speculative_state_db(&self, pivot_block: &Block) -> Option<StateDb> {
+        let storage = self
+            .data_man
+            .storage_manager
+            .get_state_for_next_epoch(
+                self.state_index_for_next_epoch(pivot_block),
+                /* recover_mpt_during_construct_pivot_state = */ false,
+            )
+            .ok()
+            .flatten()?;
+        Some(StateDb::new(storage))
+    }
+
+    fn state_index_for_next_epoch(&self, pivot_block: &Block) -> StateIndex {
         let state_root_with_aux_info = &self
             .data_man
             .get_epoch_execution_commitment(
@@ -933,26 +1004,12 @@ impl ConsensusExecutionHandler {
             .unwrap()
             .state_root_with_aux_info;
 
-        let state_index = StateIndex::new_for_next_epoch(
+        StateIndex::new_for_next_epoch(
             pivot_block.block_header.parent_hash(),
             &state_root_with_aux_info,
             pivot_block.block_header.height() - 1,
             self.data_man.get_snapshot_epoch_count(),
-        );
-
-        let storage = self
-            .data_man
-            .storage_manager
-            .get_state_for_next_epoch(
-                state_index,
-                recover_mpt_during_construct_pivot_state,
-            )
-            .expect("No db error")
-            // Unwrapping is safe because the state exists.
-            .expect("State exists");
-
-        let state_db = StateDb::new(storage);
-        State::new(state_db)
+        )
     }
 
     pub fn epoch_executed_and_recovered(
@@ -1584,6 +1641,36 @@ impl ConsensusExecutionHandler {
     pub fn call_virtual(
         &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
         request: EstimateRequest, evm_overrides: EvmOverrides,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        let epoch_height = match self.data_man.block_header_by_hash(epoch_id) {
+            Some(header) => header.height(),
+            None => bail!("invalid epoch id"),
+        };
+        let state_space = match tx.space() {
+            Space::Native => None,
+            Space::Ethereum => Some(Space::Ethereum),
+        };
+        let statedb = self.get_statedb_by_epoch_id_and_space(
+            epoch_id,
+            epoch_height,
+            state_space,
+        )?;
+        self.call_virtual_on_statedb(
+            tx,
+            epoch_id,
+            epoch_size,
+            statedb,
+            request,
+            evm_overrides,
+        )
+    }
+
+    /// Execute `tx` virtually on `statedb`, which must hold the state after
+    /// executing the epoch `epoch_id`.
+    pub fn call_virtual_on_statedb(
+        &self, tx: &SignedTransaction, epoch_id: &H256, epoch_size: usize,
+        statedb: StateDb, request: EstimateRequest,
+        evm_overrides: EvmOverrides,
     ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
         let best_block_header = self.data_man.block_header_by_hash(epoch_id);
         if best_block_header.is_none() {
@@ -1618,15 +1705,6 @@ impl ConsensusExecutionHandler {
             ),
         )?;
 
-        let state_space = match tx.space() {
-            Space::Native => None,
-            Space::Ethereum => Some(Space::Ethereum),
-        };
-        let statedb = self.get_statedb_by_epoch_id_and_space(
-            epoch_id,
-            best_block_header.height(),
-            state_space,
-        )?;
         let mut state = if evm_overrides.has_state() {
             State::new_with_override(
                 statedb,
@@ -1688,6 +1766,85 @@ impl ConsensusExecutionHandler {
         &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
         opts: GethDebugTracingOptions, tx_hash: Option<H256>,
     ) -> CoreResult<Vec<GethTraceWithHash>> {
+        let mut answer = vec![];
+        let virtual_call = VirtualCall::GethTrace(GethTask {
+            tx_hash,
+            opts,
+            answer: &mut answer,
+        });
+        self.execute_blocks_virtually(
+            epoch_id,
+            epoch_num,
+            blocks,
+            virtual_call,
+        )?;
+
+        Ok(answer)
+    }
+
+    /// Execute transactions in the blocks to collect parity's replay traces.
+    pub fn collect_blocks_parity_trace(
+        &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
+        options: TraceOptions, settings: TransactSettings,
+        tx_hash: Option<H256>,
+    ) -> CoreResult<Vec<TransactionReplayTraces>> {
+        let mut answer = vec![];
+        let virtual_call = VirtualCall::ParityTrace(ParityTask {
+            tx_hash,
+            options,
+            settings,
+            answer: &mut answer,
+        });
+        self.execute_blocks_virtually(
+            epoch_id,
+            epoch_num,
+            blocks,
+            virtual_call,
+        )?;
+
+        Ok(answer)
+    }
+
+    /// Execute the block which would be packed next on the state of the given
+    /// epoch without committing the result, and collect the changes of the
+    /// accounts made by its transactions.
+    pub fn execute_pending_block(
+        &self, epoch_id: &H256, epoch_height: u64, epoch_size: usize,
+        block: Arc<Block>,
+    ) -> CoreResult<(Arc<BlockReceipts>, StateDiff)> {
+        let mut state = self.get_state_by_epoch_id_and_space(
+            epoch_id,
+            epoch_height,
+            /* state_space */ None,
+        )?;
+
+        let start_block_number = match self.data_man.get_epoch_execution_context(epoch_id) {
+            Some(v) => v.start_block_number + epoch_size as u64,
+            None => bail!("cannot obtain the execution context. Database is potentially corrupted!"),
+        };
+
+        let mut state_diff = StateDiff::default();
+        let virtual_call = VirtualCall::Pending(PendingTask {
+            answer: &mut state_diff,
+        });
+        let mut receipts = self.process_epoch_transactions(
+            &mut state,
+            &vec![block],
+            start_block_number,
+            false,
+            Some(virtual_call),
+        )?;
+        let receipts = receipts.pop().expect("one block executed");
+
+        Ok((receipts, state_diff))
+    }
+
+    /// Execute the blocks on the state of the given epoch without committing
+    /// the result.
+    fn execute_blocks_virtually(
+        &self, epoch_id: H256, epoch_num: u64, blocks: &Vec<Arc<Block>>,
+        virtual_call: VirtualCall<'_>,
+    ) -> CoreResult<()> {
         let state_space = None;
         let mut state = self.get_state_by_epoch_id_and_space(
             &epoch_id,
@@ -1701,12 +1858,6 @@ impl ConsensusExecutionHandler {
             .map(|v| v.start_block_number)
             .expect("should exist");
 
-        let mut answer = vec![];
-        let virtual_call = VirtualCall::GethTrace(GethTask {
-            tx_hash,
-            opts,
-            answer: &mut answer,
-        });
         self.process_epoch_transactions(
             &mut state,
             blocks,
@@ -1714,8 +1865,7 @@ impl ConsensusExecutionHandler {
             false,
             Some(virtual_call),
         )?;
-
-        Ok(answer)
+        Ok(())
     }
 
     fn get_state_by_epoch_id_and_space(
@@ -1771,4 +1921,7 @@ impl ConsensusExecutionHandler {
 
 pub struct ConsensusExecutionConfiguration {
     pub executive_trace: bool,
+    /// The thread pool executing the transactions of a block speculatively.
+    /// `None` disables parallel execution.
+    pub parallel_executor: Option<Arc<ThreadPool>>,
 }
@@ -19,8 +19,9 @@ pub use crate::consensus::consensus_inner::{
 pub use config::ConsensusConfig;
 pub use consensus_graph::{
     best_info_provider::BestInformation,
-    rpc_api::transaction_provider::{
-        MaybeExecutedTxExtraInfo, TransactionInfo,
+    rpc_api::{
+        pending_block_provider::PendingBlock,
+        transaction_provider::{MaybeExecutedTxExtraInfo, TransactionInfo},
     },
     ConsensusGraph,
 };
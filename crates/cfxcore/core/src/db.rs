@@ -43,8 +43,12 @@ pub const COL_BLOCK_TRACES: u32 = 5;
 pub const COL_HASH_BY_BLOCK_NUMBER: u32 = 6;
 /// Column for PoS interest reward info.
 pub const COL_REWARD_BY_POS_EPOCH: u32 = 7;
+/// Column for the transactions sent from or to an address
+pub const COL_ADDRESS_TX_INDEX: u32 = 8;
+/// Column for the epochs of the logs of an address or a topic
+pub const COL_LOG_INDEX: u32 = 9;
 /// Number of columns in DB
-pub const NUM_COLUMNS: u32 = 8;
+pub const NUM_COLUMNS: u32 = 10;
 
 /// Modes for updating caches.
 #[derive(Clone, Copy)]
@@ -7,6 +7,7 @@ mod config;
 mod error;
 mod handler;
 mod message;
+mod proven_state;
 mod provider;
 pub mod query_service;
 
@@ -25,5 +26,6 @@ use error::handle as handle_error;
 pub use config::Configuration as LightNodeConfiguration;
 pub use error::Error;
 pub use handler::Handler;
+pub use proven_state::{ProvenStateStorage, StateEntryProvider};
 pub use provider::Provider;
 pub use query_service::QueryService;
@@ -0,0 +1,324 @@
+use crate::light_protocol::{Error as LightError, QueryService};
+use cfx_internal_common::StateRootWithAuxInfo;
+use cfx_statedb::{for_all_global_param_keys, global_params::GlobalParamKey};
+use cfx_storage::{
+    state::StateTrait as StorageStateTrait, Error, MptKeyValue, Result,
+};
+use futures::{stream, StreamExt, TryStreamExt};
+use parking_lot::Mutex;
+use primitives::{EpochId, SkipInputCheck, StorageKeyWithSpace};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use tokio::runtime::Handle;
+
+/// The number of state entries requested at the same time by `prefetch`.
+const PREFETCH_BATCH_SIZE: usize = 64;
+
+/// The source of the verified state entries of a `ProvenStateStorage`.
+#[async_trait::async_trait]
+pub trait StateEntryProvider: Send + Sync {
+    /// Returns the value of `key` in the state after executing `epoch`.
+    async fn state_entry(
+        &self, epoch: u64, key: Vec<u8>,
+    ) -> std::result::Result<Option<Vec<u8>>, LightError>;
+}
+
+#[async_trait::async_trait]
+impl StateEntryProvider for QueryService {
+    async fn state_entry(
+        &self, epoch: u64, key: Vec<u8>,
+    ) -> std::result::Result<Option<Vec<u8>>, LightError> {
+        self.retrieve_state_entry_raw(epoch, key).await
+    }
+}
+
+/// Read-only storage of the state after executing `epoch`, whose entries are
+/// retrieved from peers on first access.
+///
+/// Every entry is verified by the light protocol handler against the state
+/// root of `epoch` before it is returned. Retrieved entries, including the
+/// absent ones, are cached so that repeated reads within one execution are
+/// requested only once.
+///
+/// Reads block on `runtime` until the entry is retrieved, so the storage must
+/// not be used from within an async task (e.g. use `spawn_blocking`). Each
+/// such read waits for a round trip, so the entries known to be read should be
+/// retrieved together by `prefetch` first.
+pub struct ProvenStateStorage {
+    provider: Arc<dyn StateEntryProvider>,
+    epoch: u64,
+    runtime: Handle,
+    cache: Mutex<HashMap<Vec<u8>, Option<Box<[u8]>>>>,
+}
+
+impl ProvenStateStorage {
+    pub fn new(
+        provider: Arc<dyn StateEntryProvider>, epoch: u64, runtime: Handle,
+    ) -> Self {
+        ProvenStateStorage {
+            provider,
+            epoch,
+            runtime,
+            cache: Default::default(),
+        }
+    }
+
+    /// Retrieves the entries of `keys` that are not cached yet, at most
+    /// `PREFETCH_BATCH_SIZE` at the same time.
+    pub async fn prefetch(&self, keys: Vec<Vec<u8>>) -> Result<()> {
+        let missing: HashSet<_> = {
+            let cache = self.cache.lock();
+            keys.into_iter()
+                .filter(|key| !cache.contains_key(key))
+                .collect()
+        };
+        let entries: Vec<_> = stream::iter(missing)
+            .map(|key| async move {
+                let value = self.retrieve(&key).await.map_err(|e| {
+                    self.retrieval_error(
+                        &StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(
+                            &key,
+                        ),
+                        e,
+                    )
+                })?;
+                Ok::<_, Error>((key, value))
+            })
+            .buffer_unordered(PREFETCH_BATCH_SIZE)
+            .try_collect()
+            .await?;
+        self.cache.lock().extend(entries);
+        Ok(())
+    }
+
+    /// The keys of the global parameters, which are all read when a `State`
+    /// is built on the storage.
+    pub fn global_param_keys() -> Vec<Vec<u8>> {
+        fn push_key<T: GlobalParamKey>(keys: &mut Vec<Vec<u8>>) {
+            keys.push(T::STORAGE_KEY.to_key_bytes());
+        }
+        let mut keys = Vec::new();
+        use cfx_statedb::global_params::*;
+        for_all_global_param_keys! {
+            push_key::<Key>(&mut keys);
+        }
+        keys
+    }
+
+    async fn retrieve(
+        &self, key: &[u8],
+    ) -> std::result::Result<Option<Box<[u8]>>, LightError> {
+        let value = self.provider.state_entry(self.epoch, key.to_vec()).await?;
+        Ok(value.map(Vec::into_boxed_slice))
+    }
+
+    fn retrieval_error(
+        &self, access_key: &StorageKeyWithSpace, e: LightError,
+    ) -> Error {
+        Error::Msg(format!(
+            "Unable to retrieve state entry {:?} in epoch {}: {}",
+            access_key, self.epoch, e
+        ))
+    }
+
+    fn read_only<T>(&self) -> Result<T> {
+        Err(Error::Msg(
+            "Proven state on light nodes is read-only".into(),
+        ))
+    }
+}
+
+impl StorageStateTrait for ProvenStateStorage {
+    fn get(
+        &self, access_key: StorageKeyWithSpace,
+    ) -> Result<Option<Box<[u8]>>> {
+        let key = access_key.to_key_bytes();
+
+        if let Some(value) = self.cache.lock().get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = self
+            .runtime
+            .block_on(self.retrieve(&key))
+            .map_err(|e| self.retrieval_error(&access_key, e))?;
+        self.cache.lock().insert(key, value.clone());
+        Ok(value)
+    }
+
+    fn set(
+        &mut self, _access_key: StorageKeyWithSpace, _value: Box<[u8]>,
+    ) -> Result<()> {
+        self.read_only()
+    }
+
+    fn delete(&mut self, _access_key: StorageKeyWithSpace) -> Result<()> {
+        self.read_only()
+    }
+
+    fn delete_test_only(
+        &mut self, _access_key: StorageKeyWithSpace,
+    ) -> Result<Option<Box<[u8]>>> {
+        self.read_only()
+    }
+
+    fn delete_all(
+        &mut self, _access_key_prefix: StorageKeyWithSpace,
+    ) -> Result<Option<Vec<MptKeyValue>>> {
+        self.read_only()
+    }
+
+    // Peers cannot prove the completeness of a key range, so no entry is
+    // returned besides those already read, which the state db keeps itself.
+    // When a contract is killed, the execution is the same, except that the
+    // storage released by the entries it has not read is not counted, so the
+    // estimated storage collateral can only be higher than on a full node.
+    fn read_all(
+        &mut self, _access_key_prefix: StorageKeyWithSpace,
+    ) -> Result<Option<Vec<MptKeyValue>>> {
+        Ok(None)
+    }
+
+    fn compute_state_root(&mut self) -> Result<StateRootWithAuxInfo> {
+        self.read_only()
+    }
+
+    fn get_state_root(&self) -> Result<StateRootWithAuxInfo> {
+        self.read_only()
+    }
+
+    fn commit(&mut self, _epoch: EpochId) -> Result<StateRootWithAuxInfo> {
+        self.read_only()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_statedb::global_params::TOTAL_GLOBAL_PARAMS;
+    use cfx_types::Address;
+    use primitives::StorageKey;
+    use tokio::runtime::Runtime;
+
+    /// Serves the entries of `state` and records the requested keys. Keys
+    /// starting with `0xff` cannot be retrieved.
+    #[derive(Default)]
+    struct MockProvider {
+        state: HashMap<Vec<u8>, Vec<u8>>,
+        requests: Mutex<Vec<Vec<u8>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl StateEntryProvider for MockProvider {
+        async fn state_entry(
+            &self, epoch: u64, key: Vec<u8>,
+        ) -> std::result::Result<Option<Vec<u8>>, LightError> {
+            assert_eq!(epoch, 10);
+            self.requests.lock().push(key.clone());
+            if key.first() == Some(&0xff) {
+                return Err(LightError::InternalError("no peer".into()));
+            }
+            Ok(self.state.get(&key).cloned())
+        }
+    }
+
+    fn key(address: &Address) -> StorageKeyWithSpace<'_> {
+        StorageKey::new_account_key(address).with_native_space()
+    }
+
+    fn storage(
+        runtime: &Runtime, addresses: &[Address],
+    ) -> (Arc<MockProvider>, ProvenStateStorage) {
+        let mut provider = MockProvider::default();
+        for (i, address) in addresses.iter().enumerate() {
+            provider
+                .state
+                .insert(key(address).to_key_bytes(), vec![i as u8]);
+        }
+        let provider = Arc::new(provider);
+        let storage = ProvenStateStorage::new(
+            provider.clone(),
+            10,
+            runtime.handle().clone(),
+        );
+        (provider, storage)
+    }
+
+    #[test]
+    fn test_get_caches_entries() {
+        let runtime = Runtime::new().unwrap();
+        let (a, b) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+        let (provider, storage) = storage(&runtime, &[a]);
+
+        assert_eq!(storage.get(key(&a)).unwrap(), Some(vec![0].into()));
+        assert_eq!(storage.get(key(&b)).unwrap(), None);
+        // Both the present and the absent entries are cached.
+        assert_eq!(storage.get(key(&a)).unwrap(), Some(vec![0].into()));
+        assert_eq!(storage.get(key(&b)).unwrap(), None);
+        assert_eq!(provider.requests.lock().len(), 2);
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let runtime = Runtime::new().unwrap();
+        let addresses: Vec<_> = (0..3).map(Address::from_low_u64_be).collect();
+        let (provider, storage) = storage(&runtime, &addresses[..2]);
+
+        storage.get(key(&addresses[0])).unwrap();
+        let keys: Vec<_> = addresses
+            .iter()
+            .chain(&addresses)
+            .map(|address| key(address).to_key_bytes())
+            .collect();
+        runtime.block_on(storage.prefetch(keys.clone())).unwrap();
+        // Only the keys not cached are requested, once each.
+        let mut requests = provider.requests.lock().clone();
+        requests.sort();
+        assert_eq!(requests, keys[..3].to_vec());
+
+        assert_eq!(
+            storage.get(key(&addresses[1])).unwrap(),
+            Some(vec![1].into())
+        );
+        assert_eq!(storage.get(key(&addresses[2])).unwrap(), None);
+        assert_eq!(provider.requests.lock().len(), 3);
+    }
+
+    #[test]
+    fn test_retrieval_error() {
+        let runtime = Runtime::new().unwrap();
+        let (_, storage) = storage(&runtime, &[]);
+
+        let unavailable = Address::repeat_byte(0xff);
+        assert!(runtime
+            .block_on(storage.prefetch(vec![key(&unavailable).to_key_bytes()]))
+            .is_err());
+        assert!(storage.get(key(&unavailable)).is_err());
+    }
+
+    #[test]
+    fn test_read_only() {
+        let runtime = Runtime::new().unwrap();
+        let address = Address::from_low_u64_be(1);
+        let (provider, mut storage) = storage(&runtime, &[address]);
+
+        assert!(storage.set(key(&address), vec![0].into()).is_err());
+        assert!(storage.delete(key(&address)).is_err());
+        assert!(storage.delete_all(key(&address)).is_err());
+        assert!(storage.commit(Default::default()).is_err());
+        // Reading a range does not fail, so that contracts can be killed.
+        let prefix =
+            StorageKey::new_storage_root_key(&address).with_native_space();
+        assert_eq!(storage.read_all(prefix).unwrap(), None);
+        assert!(provider.requests.lock().is_empty());
+    }
+
+    #[test]
+    fn test_global_param_keys() {
+        let keys = ProvenStateStorage::global_param_keys();
+        assert_eq!(keys.len(), TOTAL_GLOBAL_PARAMS);
+        assert_eq!(keys.iter().collect::<HashSet<_>>().len(), keys.len());
+    }
+}
@@ -127,7 +127,7 @@ impl QueryService {
             .expect("Unable to access network service")
     }
 
-    async fn retrieve_state_entry_raw(
+    pub(super) async fn retrieve_state_entry_raw(
         &self, epoch: u64, key: Vec<u8>,
     ) -> Result<Option<Vec<u8>>, LightError> {
         trace!(
@@ -6,7 +6,6 @@ use crate::sync::{
     message::{Context, Handleable},
     Error,
 };
-use cfx_types::H256;
 use primitives::Block;
 use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 
@@ -60,45 +59,10 @@ impl Handleable for NewBlock {
             true, /* ignore_db */
         );
 
-        let need_to_relay = on_new_decoded_block(ctx, block, true, true)?;
+        let need_to_relay =
+            ctx.manager.insert_decoded_block(block, true, true)?;
 
         // broadcast the hash of the newly got block
         ctx.manager.relay_blocks(ctx.io, need_to_relay)
     }
 }
-
-fn on_new_decoded_block(
-    ctx: &Context, mut block: Block, need_to_verify: bool, persistent: bool,
-) -> Result<Vec<H256>, Error> {
-    let hash = block.block_header.hash();
-    let mut need_to_relay = Vec::new();
-    match ctx.manager.graph.block_header_by_hash(&hash) {
-        Some(header) => block.block_header = header,
-        None => {
-            let (insert_result, to_relay) =
-                ctx.manager.graph.insert_block_header(
-                    &mut block.block_header,
-                    need_to_verify,
-                    false,
-                    false,
-                    true,
-                );
-            if insert_result.is_new_valid() {
-                need_to_relay.extend(to_relay);
-            } else {
-                return Err(Error::InvalidBlock);
-            }
-        }
-    }
-
-    let insert_result = ctx.manager.graph.insert_block(
-        block,
-        need_to_verify,
-        persistent,
-        false, // recover_from_db
-    );
-    if insert_result.should_relay() {
-        need_to_relay.push(hash);
-    }
-    Ok(need_to_relay)
-}
@@ -1182,6 +1182,73 @@ impl SynchronizationProtocolHandler {
         );
     }
 
+    /// Insert a block that is not received from peers, e.g. one read from an
+    /// exported chain file. The block is verified as a newly received one, and
+    /// its parent and referees must be already known.
+    ///
+    /// Returns the hashes of the blocks to relay.
+    pub fn on_imported_block(
+        &self, mut block: Block,
+    ) -> Result<Vec<H256>, Error> {
+        if self.in_recover_from_db_phase() {
+            return Err(Error::InCatchUpMode(
+                "cannot import blocks while recovering from db".into(),
+            ));
+        }
+        self.graph.data_man.recover_block(&mut block)?;
+
+        if let Some(missing) = std::iter::once(block.block_header.parent_hash())
+            .chain(block.block_header.referee_hashes())
+            .find(|hash| !self.graph.contains_block_header(hash))
+        {
+            return Err(Error::Msg(format!(
+                "block {:?} depends on unknown block {:?}",
+                block.hash(),
+                missing
+            )));
+        }
+
+        self.insert_decoded_block(block, true, true)
+    }
+
+    /// Insert a decoded block with its header into the synchronization graph.
+    ///
+    /// Returns the hashes of the blocks to relay.
+    pub(super) fn insert_decoded_block(
+        &self, mut block: Block, need_to_verify: bool, persistent: bool,
+    ) -> Result<Vec<H256>, Error> {
+        let hash = block.block_header.hash();
+        let mut need_to_relay = Vec::new();
+        match self.graph.block_header_by_hash(&hash) {
+            Some(header) => block.block_header = header,
+            None => {
+                let (insert_result, to_relay) = self.graph.insert_block_header(
+                    &mut block.block_header,
+                    need_to_verify,
+                    false,
+                    false,
+                    true,
+                );
+                if insert_result.is_new_valid() {
+                    need_to_relay.extend(to_relay);
+                } else {
+                    return Err(Error::InvalidBlock);
+                }
+            }
+        }
+
+        let insert_result = self.graph.insert_block(
+            block,
+            need_to_verify,
+            persistent,
+            false, // recover_from_db
+        );
+        if insert_result.should_relay() {
+            need_to_relay.push(hash);
+        }
+        Ok(need_to_relay)
+    }
+
     fn broadcast_message(
         &self, io: &dyn NetworkContext, skip_id: &NodeId, msg: &dyn Message,
     ) -> Result<(), NetworkError> {
@@ -103,6 +103,13 @@ impl SynchronizationService {
         self.relay_blocks(vec![hash])
     }
 
+    /// Import a block from outside the network, e.g. from a chain file, and
+    /// relay it to peers if it is new.
+    pub fn import_block(&self, block: Block) -> Result<(), Error> {
+        let need_to_relay = self.protocol_handler.on_imported_block(block)?;
+        self.relay_blocks(need_to_relay)
+    }
+
     pub fn expire_block_gc(&self, timeout: u64) {
         let _res = self.network.with_context(
             self.protocol_handler.clone(),
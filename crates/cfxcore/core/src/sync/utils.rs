@@ -45,6 +45,7 @@ use crate::{
 use cfx_executor::{
     machine::{Machine, VmFactory},
     spec::CommonParams,
+    InternalContractPlugins, PrecompilePlugins,
 };
 
 pub fn create_simple_block_impl(
@@ -109,6 +110,28 @@ pub fn create_simple_block(
 
 pub fn initialize_data_manager(
     db_dir: &str, dbtype: DbType, pow: Arc<PowComputer>, vm: VmFactory,
+    plugins: &InternalContractPlugins, precompiles: &PrecompilePlugins,
+) -> (Arc<BlockDataManager>, Arc<Block>) {
+    initialize_data_manager_with_config(
+        db_dir,
+        DataManagerConfiguration::new(
+            false,                          /* do not persist transaction
+                                             * address */
+            false, /* do not persist block number index */
+            Duration::from_millis(300_000), /* max cached tx count */
+            dbtype,
+        ),
+        pow,
+        vm,
+        plugins,
+        precompiles,
+    )
+}
+
+pub fn initialize_data_manager_with_config(
+    db_dir: &str, config: DataManagerConfiguration, pow: Arc<PowComputer>,
+    vm: VmFactory, plugins: &InternalContractPlugins,
+    precompiles: &PrecompilePlugins,
 ) -> (Arc<BlockDataManager>, Arc<Block>) {
     let ledger_db = db::open_database(
         db_dir,
@@ -145,7 +168,12 @@ pub fn initialize_data_manager(
         U256::from(0),
     );
 
-    let machine = Arc::new(Machine::new_with_builtin(Default::default(), vm));
+    let machine = Arc::new(Machine::new_with_builtin(
+        Default::default(),
+        vm,
+        plugins,
+        precompiles,
+    ));
 
     let genesis_block = Arc::new(genesis_block(
         &storage_manager,
@@ -168,13 +196,7 @@ pub fn initialize_data_manager(
         ledger_db.clone(),
         storage_manager,
         worker_thread_pool,
-        DataManagerConfiguration::new(
-            false,                          /* do not persist transaction
-                                             * address */
-            false, /* do not persist block number index */
-            Duration::from_millis(300_000), /* max cached tx count */
-            dbtype,
-        ),
+        config,
         pow,
     ));
     (data_man, genesis_block)
@@ -183,10 +205,16 @@ pub fn initialize_data_manager(
 pub fn initialize_synchronization_graph_with_data_manager(
     data_man: Arc<BlockDataManager>, beta: u64, h: u64, tcr: u64, tcb: u64,
     era_epoch_count: u64, pow: Arc<PowComputer>, vm: VmFactory,
+    plugins: &InternalContractPlugins, precompiles: &PrecompilePlugins,
 ) -> (Arc<SynchronizationGraph>, Arc<ConsensusGraph>) {
     let mut params = CommonParams::default();
     params.transition_heights.cip1559 = u64::MAX;
-    let machine = Arc::new(Machine::new_with_builtin(params.clone(), vm));
+    let machine = Arc::new(Machine::new_with_builtin(
+        params.clone(),
+        vm,
+        plugins,
+        precompiles,
+    ));
     let mut rng = StdRng::from_seed([0u8; 32]);
     let pos_verifier = Arc::new(PosVerifier::new(
         None,
@@ -213,11 +241,13 @@ pub fn initialize_synchronization_graph_with_data_manager(
         machine.clone(),
     );
 
+    let notifications = Notifications::init();
     let txpool = Arc::new(TransactionPool::new(
         TxPoolConfig::default(),
         verification_config.clone(),
         data_man.clone(),
         machine.clone(),
+        notifications.clone(),
     ));
     let statistics = Arc::new(Statistics::new());
 
@@ -237,7 +267,6 @@ pub fn initialize_synchronization_graph_with_data_manager(
         enable_state_expose: false,
         is_consortium: false,
     };
-    let notifications = Notifications::init();
     let consensus = Arc::new(ConsensusGraph::new(
         ConsensusConfig {
             chain_id: ChainIdParamsInner::new_simple(AllChainID::new(1, 1)),
@@ -278,6 +307,7 @@ pub fn initialize_synchronization_graph_with_data_manager(
         notifications.clone(),
         ConsensusExecutionConfiguration {
             executive_trace: false,
+            parallel_executor: None,
         },
         verification_config.clone(),
         NodeType::Archive,
@@ -313,10 +343,18 @@ pub fn initialize_synchronization_graph(
     Arc<Block>,
 ) {
     let vm = VmFactory::new(1024 * 32);
+    let plugins = InternalContractPlugins::default();
+    let precompiles = PrecompilePlugins::default();
     let pow = Arc::new(PowComputer::new(true));
 
-    let (data_man, genesis_block) =
-        initialize_data_manager(db_dir, dbtype, pow.clone(), vm.clone());
+    let (data_man, genesis_block) = initialize_data_manager(
+        db_dir,
+        dbtype,
+        pow.clone(),
+        vm.clone(),
+        &plugins,
+        &precompiles,
+    );
 
     let (sync, consensus) = initialize_synchronization_graph_with_data_manager(
         data_man.clone(),
@@ -327,6 +365,8 @@ pub fn initialize_synchronization_graph(
         era_epoch_count,
         pow,
         vm,
+        &plugins,
+        &precompiles,
     );
 
     (sync, consensus, data_man, genesis_block)
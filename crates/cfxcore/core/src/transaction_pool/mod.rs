@@ -14,6 +14,7 @@ pub use error::TransactionPoolError;
 
 use crate::{
     block_data_manager::BlockDataManager,
+    channel::Notifications,
     consensus::BestInformation,
     transaction_pool::{nonce_pool::TxWithReadyInfo, pool_metrics::*},
     verification::{VerificationConfig, VerifyTxLocalMode, VerifyTxMode},
@@ -52,7 +53,7 @@ use std::{
     mem,
     ops::DerefMut,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
 };
@@ -176,9 +177,13 @@ pub struct TransactionPool {
     pub data_man: Arc<BlockDataManager>,
     best_executed_state: Mutex<Arc<State>>,
     consensus_best_info: Mutex<Arc<BestInformation>>,
+    /// Bumped on every `notify_new_best_info`, so the views built on the
+    /// previous best information (e.g. the pending block) can be refreshed.
+    best_info_version: AtomicU64,
     set_tx_requests: Mutex<Vec<Arc<SignedTransaction>>>,
     recycle_tx_requests: Mutex<Vec<Arc<SignedTransaction>>>,
     machine: Arc<Machine>,
+    notifications: Arc<Notifications>,
 
     /// If it's `false`, operations on the tx pool will be ignored to save
     /// memory/CPU cost.
@@ -212,6 +217,7 @@ impl TransactionPool {
     pub fn new(
         config: TxPoolConfig, verification_config: VerificationConfig,
         data_man: Arc<BlockDataManager>, machine: Arc<Machine>,
+        notifications: Arc<Notifications>,
     ) -> Self {
         let genesis_hash = data_man.true_genesis.hash();
         let inner = TransactionPoolInner::new(
@@ -238,9 +244,11 @@ impl TransactionPool {
             data_man: data_man.clone(),
             best_executed_state,
             consensus_best_info: Mutex::new(Arc::new(Default::default())),
+            best_info_version: AtomicU64::new(0),
             set_tx_requests: Mutex::new(Default::default()),
             recycle_tx_requests: Mutex::new(Default::default()),
             machine,
+            notifications,
             ready_for_mining: AtomicBool::new(false),
         }
     }
@@ -492,6 +500,7 @@ impl TransactionPool {
         TX_POOL_DEFERRED_GAUGE.update(self.total_deferred(None));
         TX_POOL_UNPACKED_GAUGE.update(self.total_unpacked());
         TX_POOL_READY_GAUGE.update(self.total_ready_accounts());
+        self.notify_new_pending_transactions(&passed_transactions);
 
         INSERT_TXS_SUCCESS_TPS.mark(passed_transactions.len());
         INSERT_TXS_FAILURE_TPS.mark(failure.len());
@@ -606,6 +615,7 @@ impl TransactionPool {
         TX_POOL_DEFERRED_GAUGE.update(self.total_deferred(None));
         TX_POOL_UNPACKED_GAUGE.update(self.total_unpacked());
         TX_POOL_READY_GAUGE.update(self.total_ready_accounts());
+        self.notify_new_pending_transactions(&passed_transactions);
 
         INSERT_TXS_SUCCESS_TPS.mark(passed_transactions.len());
         INSERT_TXS_FAILURE_TPS.mark(failure.len());
@@ -613,6 +623,25 @@ impl TransactionPool {
         (passed_transactions, failure)
     }
 
+    /// Publishes the eSpace transactions newly inserted into the pool to the
+    /// pending transaction subscriptions, as one message per insertion.
+    fn notify_new_pending_transactions(
+        &self, transactions: &[Arc<SignedTransaction>],
+    ) {
+        let channel = &self.notifications.new_pending_transactions;
+        if channel.num_subscriptions() == 0 {
+            return;
+        }
+        let transactions: Vec<_> = transactions
+            .iter()
+            .filter(|tx| tx.space() == Space::Ethereum)
+            .cloned()
+            .collect();
+        if !transactions.is_empty() {
+            channel.send(Arc::new(transactions));
+        }
+    }
+
     /// verify transactions based on the rules that have nothing to do with
     /// readiness
     fn verify_transaction_tx_pool(
@@ -933,6 +962,7 @@ impl TransactionPool {
         {
             let mut consensus_best_info = self.consensus_best_info.lock();
             *consensus_best_info = best_info.clone();
+            self.best_info_version.fetch_add(1, Ordering::SeqCst);
         }
         if let Some(half_block_gas_limit) = self.calc_half_block_gas_limit() {
             *self.config.half_block_gas_limit.write() = half_block_gas_limit;
@@ -999,6 +1029,11 @@ impl TransactionPool {
         Ok(())
     }
 
+    /// The number of `notify_new_best_info` calls so far.
+    pub fn best_info_version(&self) -> u64 {
+        self.best_info_version.load(Ordering::SeqCst)
+    }
+
     // For RPC use only
     pub fn get_best_info_with_parent_base_price(
         &self,
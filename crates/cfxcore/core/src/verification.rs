@@ -784,6 +784,7 @@ impl VerificationConfig {
         let cip7702 = height >= transitions.cip7702;
         let cip645 = height >= transitions.cip645;
         let eip7623 = height >= transitions.eip7623;
+        let eip7825 = height >= transitions.eip7825;
 
         if let Transaction::Native(ref tx) = tx.unsigned {
             Self::verify_transaction_epoch_height(
@@ -827,6 +828,7 @@ impl VerificationConfig {
 
         Self::check_gas_limit(tx, cip76, eip7623, &mode)?;
         Self::check_gas_limit_with_calldata(tx, cip130)?;
+        Self::check_eip7825(tx, eip7825)?;
 
         Ok(())
     }
@@ -975,6 +977,23 @@ impl VerificationConfig {
         Ok(())
     }
 
+    /// EIP-7825: Caps the gas limit of EVM space transactions.
+    fn check_eip7825(
+        tx: &TransactionWithSignature, eip7825: bool,
+    ) -> Result<(), TransactionError> {
+        if !eip7825 || tx.space() != Space::Ethereum {
+            return Ok(());
+        }
+        let limit = U256::from(EIP7825_TRANSACTION_GAS_LIMIT_CAP);
+        if *tx.gas() > limit {
+            bail!(TransactionError::GasLimitExceeded {
+                limit,
+                got: *tx.gas()
+            });
+        }
+        Ok(())
+    }
+
     pub fn check_tx_size(
         &self, tx: &TransactionWithSignature,
     ) -> Result<(), TransactionError> {
@@ -1025,10 +1044,24 @@ impl<'a> VerifyTxMode<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::verification::EpochReceiptProof;
+    use crate::verification::{
+        compute_epoch_receipt_proof, compute_receipts_root, EpochReceiptProof,
+        VerificationConfig,
+    };
+    use cfx_parameters::block::EIP7825_TRANSACTION_GAS_LIMIT_CAP;
     use cfx_storage::{
         CompressedPathRaw, TrieProof, TrieProofNode, VanillaChildrenTable,
     };
+    use cfx_types::{Address, AddressSpaceUtil, U256};
+    use primitives::{
+        transaction::{
+            eth_transaction::Eip155Transaction,
+            native_transaction::NativeTransaction, TransactionError,
+        },
+        Action, BlockReceipts, Receipt, TransactionWithSignature,
+    };
+    use rlp::Encodable;
+    use std::sync::Arc;
 
     #[test]
     fn test_rlp_epoch_receipt_proof() {
@@ -1087,4 +1120,140 @@ mod tests {
             serde_json::from_str(&serialized).unwrap();
         assert_eq!(epoch_proof, deserialized);
     }
+
+    fn encode_nodes(proof: &TrieProof) -> Vec<Vec<u8>> {
+        proof
+            .get_proof_nodes()
+            .iter()
+            .map(|node| rlp::encode(node))
+            .collect()
+    }
+
+    #[test]
+    fn test_verify_epoch_receipt_proof() {
+        // Cover single-key tries, tries whose root is removed because all
+        // keys share the first nibble, and multi-byte keys.
+        let epoch_receipts: Vec<Arc<BlockReceipts>> = [1, 2, 16, 17, 300]
+            .iter()
+            .enumerate()
+            .map(|(block_index, receipt_count)| {
+                let receipts = (0..*receipt_count)
+                    .map(|i| Receipt {
+                        accumulated_gas_used: U256::from(
+                            block_index * 1000 + i + 1,
+                        ),
+                        ..Default::default()
+                    })
+                    .collect();
+                Arc::new(BlockReceipts {
+                    receipts,
+                    block_number: 0,
+                    secondary_reward: U256::zero(),
+                    tx_execution_error_messages: vec![],
+                })
+            })
+            .collect();
+        let root = compute_receipts_root(&epoch_receipts);
+
+        for (block_index, block_receipts) in epoch_receipts.iter().enumerate() {
+            let receipt_count = block_receipts.receipts.len();
+            for tx_index in [0, receipt_count / 2, receipt_count - 1] {
+                let proof = compute_epoch_receipt_proof(
+                    &epoch_receipts,
+                    block_index,
+                    tx_index,
+                );
+                let block_index_proof = encode_nodes(&proof.block_index_proof);
+                let block_receipt_proof =
+                    encode_nodes(&proof.block_receipt_proof);
+                let receipt = block_receipts.receipts[tx_index].rlp_bytes();
+
+                let verify = |receipt: &[u8], tx_index: usize| {
+                    primitives::verify_epoch_receipt_proof(
+                        &root,
+                        block_index,
+                        epoch_receipts.len(),
+                        &block_index_proof,
+                        tx_index,
+                        receipt_count,
+                        receipt,
+                        &block_receipt_proof,
+                    )
+                };
+
+                assert!(verify(&receipt, tx_index));
+
+                let other_receipt = Receipt {
+                    accumulated_gas_used: U256::max_value(),
+                    ..Default::default()
+                };
+                assert!(!verify(&other_receipt.rlp_bytes(), tx_index));
+                if receipt_count > 1 {
+                    let other_index = (tx_index + 1) % receipt_count;
+                    assert!(!verify(&receipt, other_index));
+                }
+                assert!(!primitives::verify_epoch_receipt_proof(
+                    &root,
+                    block_index,
+                    epoch_receipts.len(),
+                    &block_index_proof,
+                    tx_index,
+                    receipt_count,
+                    &receipt,
+                    &block_receipt_proof[..block_receipt_proof.len() - 1],
+                ));
+            }
+        }
+    }
+
+    fn evm_transaction(gas: U256) -> TransactionWithSignature {
+        Eip155Transaction {
+            nonce: U256::zero(),
+            gas_price: U256::one(),
+            gas,
+            action: Action::Call(Address::zero()),
+            value: U256::zero(),
+            chain_id: Some(1),
+            data: vec![],
+        }
+        .fake_sign_rpc(Address::zero().with_evm_space())
+        .transaction
+    }
+
+    #[test]
+    fn test_eip7825_gas_limit_cap() {
+        let cap = U256::from(EIP7825_TRANSACTION_GAS_LIMIT_CAP);
+        assert_eq!(cap, U256::from(1) << 24);
+        let over_cap = cap + 1;
+
+        let tx = evm_transaction(cap);
+        assert_eq!(VerificationConfig::check_eip7825(&tx, true), Ok(()));
+
+        let tx = evm_transaction(over_cap);
+        assert_eq!(
+            VerificationConfig::check_eip7825(&tx, true),
+            Err(TransactionError::GasLimitExceeded {
+                limit: cap,
+                got: over_cap,
+            })
+        );
+        // The cap applies only after EIP-7825 is enabled.
+        assert_eq!(VerificationConfig::check_eip7825(&tx, false), Ok(()));
+
+        // The Core Space transactions are not capped.
+        let tx = NativeTransaction {
+            nonce: U256::zero(),
+            gas_price: U256::one(),
+            gas: over_cap,
+            action: Action::Call(Address::zero()),
+            value: U256::zero(),
+            storage_limit: 0,
+            epoch_height: 0,
+            chain_id: 1,
+            data: vec![],
+        }
+        .fake_sign(Address::zero().with_native_space())
+        .transaction;
+        assert_eq!(VerificationConfig::check_eip7825(&tx, true), Ok(()));
+    }
 }
@@ -2,7 +2,7 @@ use cfx_execute_helper::exec_tracer::BlockExecTraces;
 use cfx_internal_common::{
     impl_db_encoding_as_rlp, DatabaseDecodable, DatabaseEncodable,
 };
-use cfx_types::{Address, Bloom, H256, U256};
+use cfx_types::{Address, AddressWithSpace, Bloom, Space, H256, U256};
 use malloc_size_of::{MallocSizeOf, MallocSizeOfOps};
 use malloc_size_of_derive::MallocSizeOf as DeriveMallocSizeOf;
 use primitives::BlockReceipts;
@@ -319,6 +319,111 @@ impl PosRewardInfo {
     }
 }
 
+/// A transaction sent from or to an address, kept in the address transaction
+/// index.
+#[derive(Clone, Debug, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct AddressTxIndexEntry {
+    pub epoch_number: u64,
+    pub tx_hash: H256,
+}
+
+/// The positions `[start, end)` of the index entries kept for an address.
+/// Entries are appended in execution order, and removed from the front when
+/// their epochs are garbage collected.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, RlpEncodable, RlpDecodable,
+)]
+pub struct AddressTxIndexRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The addresses whose index entries are added in an epoch. They are used to
+/// revert the entries when the epoch is executed again after a pivot chain
+/// switch, and to remove the entries in garbage collection.
+#[derive(Clone, Debug, Default)]
+pub struct EpochAddressTxIndex {
+    pub addresses: Vec<AddressWithSpace>,
+}
+
+impl Encodable for EpochAddressTxIndex {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(self.addresses.len());
+        for address in &self.addresses {
+            s.begin_list(2)
+                .append(&address.address)
+                .append(&address.space);
+        }
+    }
+}
+
+impl Decodable for EpochAddressTxIndex {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        let mut addresses = Vec::with_capacity(rlp.item_count()?);
+        for item in rlp.iter() {
+            addresses.push(AddressWithSpace {
+                address: item.val_at(0)?,
+                space: item.val_at::<Space>(1)?,
+            });
+        }
+        Ok(EpochAddressTxIndex { addresses })
+    }
+}
+
+/// An epoch with logs of an address or a topic, kept in the log index. There
+/// is one entry for each epoch, however many logs match in it.
+#[derive(Clone, Debug, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct LogIndexEntry {
+    pub epoch_number: u64,
+}
+
+/// The positions `[start, end)` of the log index entries kept for an address
+/// or a topic. Entries of new epochs are appended at `end`, while the entries
+/// of older epochs are prepended at `start` by the backfill, so the first
+/// range of a value starts in the middle of the position space.
+#[derive(Clone, Copy, Debug, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct LogIndexRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Default for LogIndexRange {
+    fn default() -> Self {
+        LogIndexRange {
+            start: 1 << 63,
+            end: 1 << 63,
+        }
+    }
+}
+
+/// The keys of the addresses and topics whose log index entries are added in
+/// an epoch, used to revert and garbage collect the entries like
+/// `EpochAddressTxIndex`.
+#[derive(Clone, Debug, Default)]
+pub struct EpochLogIndex {
+    pub keys: Vec<Vec<u8>>,
+}
+
+impl Encodable for EpochLogIndex {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(self.keys.len());
+        for key in &self.keys {
+            s.append(key);
+        }
+    }
+}
+
+impl Decodable for EpochLogIndex {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        Ok(EpochLogIndex {
+            keys: rlp
+                .iter()
+                .map(|item| item.as_val())
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
 /// The validity status of a block. If a block's status among all honest nodes
 /// is guaranteed to have no conflict, which means if some honest nodes think a
 /// block is not `Pending`, their decision will be the same status.
@@ -390,3 +495,9 @@ impl_db_encoding_as_rlp!(EpochExecutionContext);
 impl_db_encoding_as_rlp!(BlamedHeaderVerifiedRoots);
 impl_db_encoding_as_rlp!(PosRewardInfo);
 impl_db_encoding_as_rlp!(BlockRewardResult);
+impl_db_encoding_as_rlp!(AddressTxIndexEntry);
+impl_db_encoding_as_rlp!(AddressTxIndexRange);
+impl_db_encoding_as_rlp!(EpochAddressTxIndex);
+impl_db_encoding_as_rlp!(LogIndexEntry);
+impl_db_encoding_as_rlp!(LogIndexRange);
+impl_db_encoding_as_rlp!(EpochLogIndex);
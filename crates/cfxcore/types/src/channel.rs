@@ -6,6 +6,7 @@ use crate::UniqueId;
 use cfx_types::H256;
 use log::warn;
 use parking_lot::RwLock;
+use primitives::SignedTransaction;
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
 use tokio::{runtime, sync::mpsc, time::timeout};
 
@@ -113,6 +114,10 @@ pub struct Notifications {
     pub new_block_hashes: Arc<Channel<H256>>,
     pub epochs_ordered: Arc<Channel<(u64, Vec<H256>)>>,
     pub blame_verification_results: Arc<Channel<(u64, Option<u64>)>>, /* <height, witness> */
+    /// The eSpace transactions newly inserted into the transaction pool, in
+    /// one message per insertion.
+    pub new_pending_transactions:
+        Arc<Channel<Arc<Vec<Arc<SignedTransaction>>>>>,
 }
 
 impl Notifications {
@@ -123,6 +128,9 @@ impl Notifications {
             blame_verification_results: Arc::new(Channel::new(
                 "blame-verification-results",
             )),
+            new_pending_transactions: Arc::new(Channel::new(
+                "new-pending-transactions",
+            )),
         })
     }
 }
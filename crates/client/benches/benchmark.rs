@@ -50,6 +50,8 @@ fn txexe_benchmark(c: &mut Criterion) {
     let machine = Machine::new_with_builtin(
         Default::default(),
         VmFactory::new(1024 * 32),
+        &Default::default(),
+        &Default::default(),
     );
     let env = Env {
         chain_id: machine.params().chain_id_map(0),
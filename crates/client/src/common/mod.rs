@@ -329,7 +329,12 @@ pub fn initialize_common_modules(
 
     let consensus_conf = conf.consensus_config();
     let vm = VmFactory::new(1024 * 32);
-    let machine = Arc::new(Machine::new_with_builtin(conf.common_params(), vm));
+    let machine = Arc::new(Machine::try_new_with_builtin(
+        conf.common_params(),
+        vm,
+        &conf.internal_contract_plugins,
+        &conf.precompile_plugins,
+    )?);
 
     let genesis_block = genesis_block(
         &storage_manager,
@@ -365,6 +370,7 @@ pub fn initialize_common_modules(
         conf.data_mananger_config(),
         pow.clone(),
     ));
+    BlockDataManager::start_log_index_backfill(&data_man);
 
     let network = {
         let mut rng = StdRng::from_rng(OsRng).unwrap();
@@ -397,15 +403,16 @@ pub fn initialize_common_modules(
         conf.raw_conf.pos_reference_enable_height,
     ));
     let verification_config = conf.verification_config(machine.clone());
+    let notifications = Notifications::init();
     let txpool = Arc::new(TransactionPool::new(
         conf.txpool_config(),
         verification_config.clone(),
         data_man.clone(),
         machine.clone(),
+        notifications.clone(),
     ));
 
     let statistics = Arc::new(Statistics::new());
-    let notifications = Notifications::init();
     let pivot_hint = if let Some(conf) = &consensus_conf.pivot_hint_conf {
         Some(Arc::new(PivotHint::new(conf)?))
     } else {
@@ -420,7 +427,7 @@ pub fn initialize_common_modules(
         pow_config.clone(),
         pow.clone(),
         notifications.clone(),
-        conf.execution_config(),
+        conf.execution_config()?,
         verification_config.clone(),
         node_type,
         pos_verifier.clone(),
@@ -482,6 +489,7 @@ pub fn initialize_common_modules(
         consensus.clone(),
         notifications.clone(),
         *network.get_network_type(),
+        pos_verifier.clone(),
     );
 
     Ok((
@@ -669,7 +677,7 @@ pub fn initialize_not_light_node_modules(
         maybe_txgen.clone(),
         maybe_direct_txgen,
         conf.rpc_impl_config(),
-        accounts,
+        accounts.clone(),
     ));
 
     let debug_rpc_http_server = super::rpc::start_http(
@@ -740,6 +748,9 @@ pub fn initialize_not_light_node_modules(
             sync.clone(),
             txpool.clone(),
             notifications.clone(),
+            accounts,
+            pos_verifier.clone(),
+            blockgen.test_api(),
             task_executor.clone(),
             conf,
         ))?;
@@ -23,6 +23,9 @@ use cfx_executor::{
     executive::{ExecutionError, ExecutionOutcome, TxDropError},
     internal_contract::storage_point_prop,
 };
+use cfx_rpc_cfx_impl::helpers::{
+    build_epoch_receipt_proof, check_accounts_limit, read_accounts,
+};
 use cfx_rpc_eth_types::Transaction as EthTransaction;
 use cfx_rpc_utils::error::jsonrpc_error_helpers::error_object_owned_to_jsonrpc_error;
 use cfx_statedb::{
@@ -31,8 +34,9 @@ use cfx_statedb::{
         InterestRate, LastDistributeBlock, PowBaseReward, TotalBurnt1559,
         TotalPosStaking,
     },
-    StateDbExt,
+    StateDb, StateDbExt,
 };
+use cfx_typed_data::TypedData;
 use cfx_types::{
     Address, AddressSpaceUtil, BigEndianHash, Space, H256, H520, U128, U256,
     U64,
@@ -40,13 +44,9 @@ use cfx_types::{
 use cfx_util_macros::bail;
 use cfx_vm_types::Error as VmError;
 use cfxcore::{
-    block_data_manager::BlockExecutionResult,
-    errors::account_result_to_rpc_result,
-    state_exposer::STATE_EXPOSER,
-    transaction_pool::TransactionPoolError,
-    verification::{compute_epoch_receipt_proof, EpochReceiptProof},
-    ConsensusGraph, PeerInfo, SharedConsensusGraph,
-    SharedSynchronizationService, SharedTransactionPool,
+    block_data_manager::BlockExecutionResult, state_exposer::STATE_EXPOSER,
+    transaction_pool::TransactionPoolError, ConsensusGraph, PeerInfo,
+    SharedConsensusGraph, SharedSynchronizationService, SharedTransactionPool,
 };
 use cfxcore_accounts::AccountProvider;
 use delegate::delegate;
@@ -59,7 +59,7 @@ use network::{
 };
 use parking_lot::Mutex;
 use primitives::{
-    filter::LogFilter, receipt::EVM_SPACE_SUCCESS, Account, Block, BlockHeader,
+    filter::LogFilter, receipt::EVM_SPACE_SUCCESS, Block, BlockHeader,
     BlockReceipts, DepositInfo, SignedTransaction, StorageKey, StorageRoot,
     StorageValue, TransactionIndex, TransactionStatus,
     TransactionWithSignature, VoteStakeInfo,
@@ -90,11 +90,12 @@ use crate::{
             BlockHashOrEpochNumber, Bytes, CfxRpcLogFilter,
             CheckBalanceAgainstTransactionResponse,
             ConsensusGraphBlockExecutionState, ConsensusGraphBlockState,
-            ConsensusGraphStates, EpochNumber,
+            ConsensusGraphStates, EpochNumber, EpochReceiptProof,
             EstimateGasAndCollateralResponse, Log as RpcLog, PackedOrExecuted,
             Receipt as RpcReceipt, RewardInfo as RpcRewardInfo,
             Status as RpcStatus, StorageCollateralInfo, SyncGraphBlockState,
             SyncGraphStates, Transaction as RpcTransaction, TransactionRequest,
+            TransactionsByAddress,
         },
         CoreResult,
     },
@@ -107,6 +108,10 @@ use cfx_parameters::{
     genesis::{
         genesis_contract_address_four_year, genesis_contract_address_two_year,
     },
+    rpc::{
+        TRANSACTIONS_BY_ADDRESS_DEFAULT_LIMIT,
+        TRANSACTIONS_BY_ADDRESS_MAX_LIMIT,
+    },
     staking::{BLOCKS_PER_YEAR, DRIPS_PER_STORAGE_COLLATERAL_UNIT},
 };
 use cfx_storage::state::StateDbGetOriginalMethods;
@@ -393,33 +398,39 @@ impl RpcImpl {
     ) -> CoreResult<RpcAccount> {
         self.check_address_network(address.network)?;
         let epoch_num = epoch_num.unwrap_or(EpochNumber::LatestState).into();
-        let network = address.network;
 
         info!(
             "RPC Request: cfx_getAccount address={:?} epoch_num={:?}",
             address, epoch_num
         );
 
-        let address = &address.hex_address;
-
         let state_db = self
             .consensus
             .get_state_db_by_epoch_number(epoch_num, "epoch_num")?;
 
-        let account =
-            match state_db.get_account(&address.with_native_space())? {
-                Some(t) => t,
-                None => account_result_to_rpc_result(
-                    "address",
-                    Ok(Account::new_empty_with_balance(
-                        &address.with_native_space(),
-                        &U256::zero(), /* balance */
-                        &U256::zero(), /* nonce */
-                    )),
-                )?,
-            };
+        Ok(read_rpc_accounts(&state_db, &[address])?.remove(0))
+    }
+
+    fn accounts(
+        &self, addresses: Vec<RpcAddress>, epoch_num: Option<EpochNumber>,
+    ) -> CoreResult<Vec<RpcAccount>> {
+        check_accounts_limit(addresses.len())?;
+        for address in &addresses {
+            self.check_address_network(address.network)?;
+        }
+        let epoch_num = epoch_num.unwrap_or(EpochNumber::LatestState).into();
+
+        info!(
+            "RPC Request: cfx_getAccounts addresses={} epoch_num={:?}",
+            addresses.len(),
+            epoch_num
+        );
+
+        let state_db = self
+            .consensus
+            .get_state_db_by_epoch_number(epoch_num, "epoch_num")?;
 
-        Ok(RpcAccount::try_from(account, network)?)
+        read_rpc_accounts(&state_db, &addresses)
     }
 
     /// Returns interest rate of the given epoch
@@ -784,6 +795,48 @@ impl RpcImpl {
         Ok(None)
     }
 
+    fn transactions_by_address(
+        &self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>,
+    ) -> CoreResult<TransactionsByAddress> {
+        self.check_address_network(address.network)?;
+        info!(
+            "RPC Request: cfx_getTransactionsByAddress address={:?} cursor={:?} limit={:?}",
+            address, cursor, limit
+        );
+
+        let limit = limit
+            .map_or(TRANSACTIONS_BY_ADDRESS_DEFAULT_LIMIT, |limit| {
+                limit.as_usize()
+            });
+        if limit > TRANSACTIONS_BY_ADDRESS_MAX_LIMIT {
+            bail!(invalid_params(
+                "limit",
+                format!(
+                    "limit should not exceed {}",
+                    TRANSACTIONS_BY_ADDRESS_MAX_LIMIT
+                )
+            ));
+        }
+
+        let (tx_hashes, next_cursor) =
+            self.consensus.get_transactions_by_address(
+                &address.hex_address.with_native_space(),
+                cursor.map(|cursor| cursor.as_u64()),
+                limit,
+            )?;
+        let mut transactions = Vec::with_capacity(tx_hashes.len());
+        for hash in tx_hashes {
+            if let Some(tx) = self.transaction_by_hash(hash)? {
+                transactions.push(tx);
+            }
+        }
+
+        Ok(TransactionsByAddress {
+            transactions,
+            next_cursor: next_cursor.map(Into::into),
+        })
+    }
+
     fn get_block_execution_info(
         &self, block_hash: &H256,
     ) -> CoreResult<Option<BlockExecInfo>> {
@@ -1277,53 +1330,7 @@ impl RpcImpl {
         );
         let (execution_outcome, _estimation) =
             self.exec_transaction(request, epoch)?;
-        match execution_outcome {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
-                expected,
-                got,
-            )) => bail!(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"nonce is too old expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::InvalidRecipientAddress(recipient),
-            ) => bail!(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"invalid recipient address {:?}", recipient}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::NotEnoughGasLimit { expected, got },
-            ) => bail!(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
-                address,
-            )) => bail!(call_execution_error(
-                "Transaction can not be executed".into(),
-                format! {"tx sender has contract code: {:?}", address}
-            )),
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(call_execution_error(
-                    "Transaction can not be executed".into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(VmError::Reverted),
-                executed,
-            ) => bail!(call_execution_error(
-                "Transaction reverted".into(),
-                format!("0x{}", executed.output.to_hex::<String>())
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(call_execution_error(
-                    "Transaction execution failed".into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
-        }
+        call_output(execution_outcome)
     }
 
     fn estimate_gas_and_collateral(
@@ -1334,78 +1341,11 @@ impl RpcImpl {
         );
         let (execution_outcome, estimation) =
             self.exec_transaction(request, epoch)?;
-        match execution_outcome {
-            ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
-                expected,
-                got,
-            )) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"nonce is too old expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::InvalidRecipientAddress(recipient),
-            ) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"invalid recipient address {:?}", recipient}
-            )),
-            ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
-                address,
-            )) => bail!(call_execution_error(
-                "Can not estimate: transaction sender has code".into(),
-                format! {"transaction sender has code {:?}", address}
-            )),
-            ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
-                bail!(call_execution_error(
-                    "Can not estimate: transaction can not be executed".into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::NotExecutedDrop(
-                TxDropError::NotEnoughGasLimit { expected, got },
-            ) => bail!(call_execution_error(
-                "Can not estimate: transaction can not be executed".into(),
-                format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
-            )),
-            ExecutionOutcome::ExecutionErrorBumpNonce(
-                ExecutionError::VmError(VmError::Reverted),
-                executed,
-            ) => {
-                let network_type = *self.sync.network.get_network_type();
-                let (revert_error, innermost_error, errors) =
-                    decode_error(&executed, |addr| {
-                        RpcAddress::try_from_h160(addr.clone(), network_type)
-                            .unwrap()
-                            .base32_address
-                    });
-
-                bail!(call_execution_error(
-                    format!("Estimation isn't accurate: transaction is reverted{}{}",
-                        revert_error, innermost_error),
-                    errors.join("\n"),
-                ))
-            }
-            ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
-                bail!(call_execution_error(
-                    format! {"Can not estimate: transaction execution failed, \
-                    all gas will be charged (execution error: {:?})", e}
-                    .into(),
-                    format! {"{:?}", e}
-                ))
-            }
-            ExecutionOutcome::Finished(executed) => executed,
-        };
-        let storage_collateralized =
-            U64::from(estimation.estimated_storage_limit);
-        let estimated_gas_used = estimation.estimated_gas_limit;
-        let response = EstimateGasAndCollateralResponse {
-            gas_limit: estimated_gas_used, /* gas_limit used to be 4/3 of
-                                            * gas_used due to inaccuracy,
-                                            * currently it's the same as gas
-                                            * used as it's more accurate */
-            gas_used: estimated_gas_used,
-            storage_collateralized,
-        };
-        Ok(response)
+        estimation_response(
+            execution_outcome,
+            estimation,
+            *self.sync.network.get_network_type(),
+        )
     }
 
     fn check_balance_against_transaction(
@@ -1626,27 +1566,7 @@ impl RpcImpl {
             self.consensus
                 .get_state_db_by_epoch_number(epoch, "epoch")?,
         )?;
-        let total_issued = state.total_issued_tokens();
-        let total_staking = state.total_staking_tokens();
-        let total_collateral = state.total_storage_tokens();
-        let two_year_unlock_address = genesis_contract_address_two_year();
-        let four_year_unlock_address = genesis_contract_address_four_year();
-        let two_year_locked = state
-            .balance(&two_year_unlock_address)
-            .unwrap_or(U256::zero());
-        let four_year_locked = state
-            .balance(&four_year_unlock_address)
-            .unwrap_or(U256::zero());
-        let total_circulating =
-            total_issued - two_year_locked - four_year_locked;
-        let total_espace_tokens = state.total_espace_tokens();
-        Ok(TokenSupplyInfo {
-            total_circulating,
-            total_issued,
-            total_staking,
-            total_collateral,
-            total_espace_tokens,
-        })
+        Ok(supply_info(&state))
     }
 
     pub fn get_collateral_info(
@@ -1657,16 +1577,7 @@ impl RpcImpl {
             self.consensus
                 .get_state_db_by_epoch_number(epoch, "epoch")?,
         )?;
-        let total_storage_tokens = state.total_storage_tokens();
-        let converted_storage_points = state.converted_storage_points()
-            / *DRIPS_PER_STORAGE_COLLATERAL_UNIT;
-        let used_storage_points =
-            state.used_storage_points() / *DRIPS_PER_STORAGE_COLLATERAL_UNIT;
-        Ok(StorageCollateralInfo {
-            total_storage_tokens,
-            converted_storage_points,
-            used_storage_points,
-        })
+        Ok(collateral_info(&state))
     }
 
     pub fn get_vote_params(
@@ -1676,20 +1587,7 @@ impl RpcImpl {
         let state_db = self
             .consensus
             .get_state_db_by_epoch_number(epoch, "epoch_num")?;
-        let interest_rate = state_db.get_global_param::<InterestRate>()?
-            / U256::from(BLOCKS_PER_YEAR);
-        let pow_base_reward = state_db.get_global_param::<PowBaseReward>()?;
-
-        let storage_point_prop =
-            state_db.get_system_storage(&storage_point_prop())?;
-
-        let base_fee_share_prop = state_db.get_global_param::<BaseFeeProp>()?;
-        Ok(VoteParamsInfo {
-            pow_base_reward,
-            interest_rate,
-            storage_point_prop,
-            base_fee_share_prop,
-        })
+        vote_params(&state_db)
     }
 
     pub fn get_fee_burnt(
@@ -1788,94 +1686,9 @@ impl RpcImpl {
     fn epoch_receipt_proof_by_transaction(
         &self, tx_hash: H256,
     ) -> JsonRpcResult<Option<EpochReceiptProof>> {
-        let (block_hash, tx_index_in_block) =
-            match self.consensus.get_signed_tx_and_tx_info(&tx_hash) {
-                None => {
-                    bail!(invalid_params(
-                        "transactions hash",
-                        format!(
-                            "Unable to get transaction info for hash {:?}",
-                            tx_hash
-                        )
-                    ));
-                }
-                Some((_, TransactionInfo { tx_index, .. })) => {
-                    (tx_index.block_hash, tx_index.real_index)
-                }
-            };
-
-        let epoch = match self.consensus.get_block_epoch_number(&block_hash) {
-            Some(epoch) => epoch,
-            None => {
-                bail!(invalid_params(
-                    "block hash",
-                    format!(
-                        "Unable to get epoch number for block {:?}",
-                        block_hash
-                    )
-                ));
-            }
-        };
-
-        let epoch_number = primitives::EpochNumber::Number(epoch);
-        let epoch_hashes = match self
-            .consensus
-            .get_block_hashes_by_epoch(epoch_number.clone())
-        {
-            Ok(hs) => hs,
-            Err(e) => {
-                bail!(invalid_params(
-                    "block hash",
-                    format!("Unable to find epoch hashes for {}: {}", epoch, e)
-                ));
-            }
-        };
-
-        let block_index_in_epoch =
-            match epoch_hashes.iter().position(|h| *h == block_hash) {
-                Some(id) => id,
-                None => {
-                    bail!(invalid_params(
-                        "block hash",
-                        format!(
-                            "Unable to find block {:?} in epoch {}",
-                            block_hash, epoch
-                        )
-                    ));
-                }
-            };
-
-        let pivot = epoch_hashes
-            .last()
-            .expect("epoch hashes should be not empty")
-            .clone();
-
-        let epoch_receipts = epoch_hashes
-            .into_iter()
-            .map(|h| {
-                self.consensus
-                    .data_manager()
-                    .block_execution_result_by_hash_with_epoch(
-                        &h, &pivot, false, /* update_pivot_assumption */
-                        false, /* update_cache */
-                    )
-                    .map(|res| Arc::new((*res.block_receipts).clone()))
-                    .ok_or_else(|| {
-                        invalid_params(
-                            "block hash",
-                            format!("Unable to find recepits for {}", h),
-                        )
-                    })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let epoch_receipt_proof = compute_epoch_receipt_proof(
-            &epoch_receipts,
-            block_index_in_epoch,
-            tx_index_in_block,
-        );
-
-        Ok(Some(epoch_receipt_proof))
+        let proof = build_epoch_receipt_proof(&self.consensus, &tx_hash)
+            .map_err(|e| invalid_params("transaction hash", e))?;
+        Ok(Some(proof))
     }
 
     fn stat_on_gas_load(
@@ -2285,6 +2098,212 @@ impl RpcImpl {
     }
 }
 
+/// Convert the outcome of a virtual call into the result of `cfx_call`.
+pub(crate) fn call_output(
+    execution_outcome: ExecutionOutcome,
+) -> CoreResult<Bytes> {
+    match execution_outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => bail!(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"nonce is too old expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => bail!(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"invalid recipient address {:?}", recipient}
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::NotEnoughGasLimit {
+            expected,
+            got,
+        }) => bail!(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
+            address,
+        )) => bail!(call_execution_error(
+            "Transaction can not be executed".into(),
+            format! {"tx sender has contract code: {:?}", address}
+        )),
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(call_execution_error(
+                "Transaction can not be executed".into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(VmError::Reverted),
+            executed,
+        ) => bail!(call_execution_error(
+            "Transaction reverted".into(),
+            format!("0x{}", executed.output.to_hex::<String>())
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(call_execution_error(
+                "Transaction execution failed".into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::Finished(executed) => Ok(executed.output.into()),
+    }
+}
+
+/// Convert the outcome of a virtual call into the result of
+/// `cfx_estimateGasAndCollateral`. `network_type` is used to format the
+/// addresses in revert errors.
+pub(crate) fn estimation_response(
+    execution_outcome: ExecutionOutcome, estimation: EstimateExt,
+    network_type: Network,
+) -> CoreResult<EstimateGasAndCollateralResponse> {
+    match execution_outcome {
+        ExecutionOutcome::NotExecutedDrop(TxDropError::OldNonce(
+            expected,
+            got,
+        )) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"nonce is too old expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::NotExecutedDrop(
+            TxDropError::InvalidRecipientAddress(recipient),
+        ) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"invalid recipient address {:?}", recipient}
+        )),
+        ExecutionOutcome::NotExecutedDrop(TxDropError::SenderWithCode(
+            address,
+        )) => bail!(call_execution_error(
+            "Can not estimate: transaction sender has code".into(),
+            format! {"transaction sender has code {:?}", address}
+        )),
+        ExecutionOutcome::NotExecutedToReconsiderPacking(e) => {
+            bail!(call_execution_error(
+                "Can not estimate: transaction can not be executed".into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::NotExecutedDrop(TxDropError::NotEnoughGasLimit {
+            expected,
+            got,
+        }) => bail!(call_execution_error(
+            "Can not estimate: transaction can not be executed".into(),
+            format! {"not enough gas limit with respected to tx size: expected {:?} got {:?}", expected, got}
+        )),
+        ExecutionOutcome::ExecutionErrorBumpNonce(
+            ExecutionError::VmError(VmError::Reverted),
+            executed,
+        ) => {
+            let (revert_error, innermost_error, errors) =
+                decode_error(&executed, |addr| {
+                    RpcAddress::try_from_h160(addr.clone(), network_type)
+                        .unwrap()
+                        .base32_address
+                });
+
+            bail!(call_execution_error(
+                format!(
+                    "Estimation isn't accurate: transaction is reverted{}{}",
+                    revert_error, innermost_error
+                ),
+                errors.join("\n"),
+            ))
+        }
+        ExecutionOutcome::ExecutionErrorBumpNonce(e, _) => {
+            bail!(call_execution_error(
+                format! {"Can not estimate: transaction execution failed, \
+                all gas will be charged (execution error: {:?})", e}
+                .into(),
+                format! {"{:?}", e}
+            ))
+        }
+        ExecutionOutcome::Finished(executed) => executed,
+    };
+    let storage_collateralized = U64::from(estimation.estimated_storage_limit);
+    let estimated_gas_used = estimation.estimated_gas_limit;
+    let response = EstimateGasAndCollateralResponse {
+        gas_limit: estimated_gas_used, /* gas_limit used to be 4/3 of
+                                        * gas_used due to inaccuracy,
+                                        * currently it's the same as gas
+                                        * used as it's more accurate */
+        gas_used: estimated_gas_used,
+        storage_collateralized,
+    };
+    Ok(response)
+}
+
+pub(crate) fn supply_info(state: &State) -> TokenSupplyInfo {
+    let total_issued = state.total_issued_tokens();
+    let total_staking = state.total_staking_tokens();
+    let total_collateral = state.total_storage_tokens();
+    let two_year_unlock_address = genesis_contract_address_two_year();
+    let four_year_unlock_address = genesis_contract_address_four_year();
+    let two_year_locked = state
+        .balance(&two_year_unlock_address)
+        .unwrap_or(U256::zero());
+    let four_year_locked = state
+        .balance(&four_year_unlock_address)
+        .unwrap_or(U256::zero());
+    let total_circulating = total_issued - two_year_locked - four_year_locked;
+    let total_espace_tokens = state.total_espace_tokens();
+    TokenSupplyInfo {
+        total_circulating,
+        total_issued,
+        total_staking,
+        total_collateral,
+        total_espace_tokens,
+    }
+}
+
+pub(crate) fn collateral_info(state: &State) -> StorageCollateralInfo {
+    let total_storage_tokens = state.total_storage_tokens();
+    let converted_storage_points =
+        state.converted_storage_points() / *DRIPS_PER_STORAGE_COLLATERAL_UNIT;
+    let used_storage_points =
+        state.used_storage_points() / *DRIPS_PER_STORAGE_COLLATERAL_UNIT;
+    StorageCollateralInfo {
+        total_storage_tokens,
+        converted_storage_points,
+        used_storage_points,
+    }
+}
+
+pub(crate) fn vote_params(state_db: &StateDb) -> CoreResult<VoteParamsInfo> {
+    let interest_rate = state_db.get_global_param::<InterestRate>()?
+        / U256::from(BLOCKS_PER_YEAR);
+    let pow_base_reward = state_db.get_global_param::<PowBaseReward>()?;
+
+    let storage_point_prop =
+        state_db.get_system_storage(&storage_point_prop())?;
+
+    let base_fee_share_prop = state_db.get_global_param::<BaseFeeProp>()?;
+    Ok(VoteParamsInfo {
+        pow_base_reward,
+        interest_rate,
+        storage_point_prop,
+        base_fee_share_prop,
+    })
+}
+
+/// Reads the accounts of `addresses` from `state_db`, see [`read_accounts`].
+pub(crate) fn read_rpc_accounts(
+    state_db: &StateDb, addresses: &[RpcAddress],
+) -> CoreResult<Vec<RpcAccount>> {
+    let native_addresses: Vec<_> = addresses
+        .iter()
+        .map(|address| address.hex_address.with_native_space())
+        .collect();
+    read_accounts(state_db, &native_addresses)?
+        .into_iter()
+        .zip(addresses)
+        .map(|(account, address)| {
+            Ok(RpcAccount::try_from(account, address.network)?)
+        })
+        .collect()
+}
+
 #[allow(dead_code)]
 pub struct CfxHandler {
     common: Arc<CommonImpl>,
@@ -2328,6 +2347,7 @@ impl Cfx for CfxHandler {
         to self.rpc_impl {
             fn code(&self, addr: RpcAddress, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> BoxFuture<JsonRpcResult<Bytes>>;
             fn account(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<RpcAccount>>;
+            fn accounts(&self, addresses: Vec<RpcAddress>, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<Vec<RpcAccount>>>;
             fn interest_rate(&self, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<U256>>;
             fn accumulate_interest_rate(&self, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<U256>>;
             fn pos_economics(&self, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<PoSEconomics>>;
@@ -2343,10 +2363,10 @@ impl Cfx for CfxHandler {
             fn collateral_for_storage(&self, address: RpcAddress, num: Option<EpochNumber>)
                 -> BoxFuture<JsonRpcResult<U256>>;
             fn call(&self, request: TransactionRequest, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>)
-                -> JsonRpcResult<Bytes>;
+                -> BoxFuture<JsonRpcResult<Bytes>>;
             fn estimate_gas_and_collateral(
                 &self, request: TransactionRequest, epoch_number: Option<EpochNumber>)
-                -> JsonRpcResult<EstimateGasAndCollateralResponse>;
+                -> BoxFuture<JsonRpcResult<EstimateGasAndCollateralResponse>>;
             fn check_balance_against_transaction(
                 &self, account_addr: RpcAddress, contract_addr: RpcAddress, gas_limit: U256, gas_price: U256, storage_limit: U256, epoch: Option<EpochNumber>,
             ) -> BoxFuture<JsonRpcResult<CheckBalanceAgainstTransactionResponse>>;
@@ -2356,12 +2376,13 @@ impl Cfx for CfxHandler {
             fn storage_at(&self, addr: RpcAddress, pos: U256, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>)
                 -> BoxFuture<JsonRpcResult<Option<H256>>>;
             fn transaction_by_hash(&self, hash: H256) -> BoxFuture<JsonRpcResult<Option<RpcTransaction>>>;
+            fn transactions_by_address(&self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>) -> BoxFuture<JsonRpcResult<TransactionsByAddress>>;
             fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<JsonRpcResult<Option<RpcReceipt>>>;
             fn storage_root(&self, address: RpcAddress, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<Option<StorageRoot>>>;
-            fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<TokenSupplyInfo>;
-            fn get_collateral_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<StorageCollateralInfo>;
-            fn get_vote_params(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<VoteParamsInfo>;
-            fn get_fee_burnt(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<U256>;
+            fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<TokenSupplyInfo>>;
+            fn get_collateral_info(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<StorageCollateralInfo>>;
+            fn get_vote_params(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<VoteParamsInfo>>;
+            fn get_fee_burnt(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<U256>>;
         }
     }
 }
@@ -2471,6 +2492,8 @@ impl LocalRpc for LocalRpcImpl {
             fn lock_account(&self, address: RpcAddress) -> JsonRpcResult<bool>;
             fn sign(&self, data: Bytes, address: RpcAddress, password: Option<String>)
                 -> JsonRpcResult<H520>;
+            fn sign_typed_data(&self, address: RpcAddress, data: TypedData, password: Option<String>)
+                -> JsonRpcResult<H520>;
 
         }
 
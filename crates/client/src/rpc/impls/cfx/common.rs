@@ -39,7 +39,10 @@ use cfx_parameters::{
 };
 pub use cfx_rpc_cfx_impl::check_balance_against_transaction;
 use cfx_rpc_cfx_impl::eth_data_hash;
-use cfx_rpc_utils::error::jsonrpc_error_helpers::internal_rpc_err;
+use cfx_rpc_utils::error::jsonrpc_error_helpers::{
+    account_error, encoding_error, internal_rpc_err,
+};
+use cfx_typed_data::{Standard, TypedData};
 use cfx_types::{
     Address, AddressSpaceUtil, Space, H160, H256, H520, U128, U256, U64,
 };
@@ -1287,6 +1290,21 @@ impl RpcImpl {
         Ok(H520(signature.into()))
     }
 
+    pub fn sign_typed_data(
+        &self, address: RpcAddress, data: TypedData, password: Option<String>,
+    ) -> CoreResult<H520> {
+        self.check_address_network(address.network)?;
+
+        let message =
+            data.signing_hash(Standard::Cip23).map_err(encoding_error)?;
+        let password = password.map(Password::from);
+        let signature = self
+            .accounts
+            .sign(address.into(), password, message)
+            .map_err(account_error)?;
+        Ok(H520(signature.into()))
+    }
+
     pub fn save_node_db(&self) -> JsonRpcResult<()> {
         self.network.save_node_db();
         Ok(())
@@ -2,7 +2,11 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use cfx_execute_helper::estimation::{EstimateExt, EstimateRequest};
+use cfx_executor::{executive::ExecutionOutcome, state::State};
 use cfx_rpc_utils::error::jsonrpc_error_helpers::error_object_owned_to_jsonrpc_error;
+use cfx_statedb::{global_params::TotalBurnt1559, StateDb, StateDbExt};
+use cfx_typed_data::TypedData;
 use cfx_types::{
     AddressSpaceUtil, BigEndianHash, Space, H160, H256, H520, U128, U256, U64,
 };
@@ -10,8 +14,9 @@ use cfx_util_macros::bail;
 use cfxcore::{
     block_data_manager::BlockDataManager,
     errors::account_result_to_rpc_result,
-    light_protocol::{self, query_service::TxInfo, Error as LightError},
-    verification::EpochReceiptProof,
+    light_protocol::{
+        self, query_service::TxInfo, Error as LightError, ProvenStateStorage,
+    },
     ConsensusGraph, LightQueryService, PeerInfo, SharedConsensusGraph,
 };
 use cfxcore_accounts::AccountProvider;
@@ -25,37 +30,50 @@ use network::{
     throttling, SessionDetails, UpdateNodeOperation,
 };
 use primitives::{
-    Account, DepositInfo, StorageRoot, TransactionWithSignature, VoteStakeInfo,
+    Account, DepositInfo, StorageKey, StorageRoot, TransactionWithSignature,
+    VoteStakeInfo,
 };
 use rlp::Encodable;
 use std::{collections::BTreeMap, net::SocketAddr, sync::Arc};
+use tokio::{runtime::Handle, task::spawn_blocking};
 // To convert from CoreResult to BoxFuture by delegate! macro automatically.
 use crate::{
     common::delegate_convert,
     rpc::{
         errors::{self, invalid_params_check},
         helpers::{build_block, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT},
-        impls::common::{self, RpcImpl as CommonImpl},
+        impls::{
+            cfx::cfx_handler::{
+                call_output, collateral_info, estimation_response,
+                read_rpc_accounts, supply_info, vote_params,
+            },
+            common::{self, RpcImpl as CommonImpl},
+            RpcImplConfiguration,
+        },
         traits::{cfx::Cfx, debug::LocalRpc, test::TestRpc},
         types::{
-            cfx::check_rpc_address_network,
+            cfx::{
+                check_rpc_address_network, check_two_rpc_address_network_match,
+            },
             pos::{Block as PosBlock, PoSEpochReward},
             Account as RpcAccount, AccountPendingInfo,
             AccountPendingTransactions, BlameInfo, Block as RpcBlock,
             BlockHashOrEpochNumber, Bytes, CfxFeeHistory, CfxRpcLogFilter,
             CheckBalanceAgainstTransactionResponse, ConsensusGraphStates,
-            EpochNumber, EstimateGasAndCollateralResponse, FeeHistory,
-            Log as RpcLog, PoSEconomics, Receipt as RpcReceipt,
+            EpochNumber, EpochReceiptProof, EstimateGasAndCollateralResponse,
+            FeeHistory, Log as RpcLog, PoSEconomics, Receipt as RpcReceipt,
             RewardInfo as RpcRewardInfo, RpcAddress, SponsorInfo,
             StatOnGasLoad, Status as RpcStatus, StorageCollateralInfo,
             SyncGraphStates, TokenSupplyInfo, Transaction as RpcTransaction,
-            TransactionRequest, VoteParamsInfo, WrapTransaction, U64 as HexU64,
+            TransactionRequest, TransactionsByAddress, VoteParamsInfo,
+            WrapTransaction, U64 as HexU64,
         },
         CoreBoxFuture, CoreResult,
     },
 };
 use cfx_addr::Network;
 use cfx_parameters::rpc::GAS_PRICE_DEFAULT_VALUE;
+use cfx_rpc_cfx_impl::helpers::check_accounts_limit;
 use cfxcore::{errors::Error::LightProtocol, light_protocol::QueryService};
 use diem_types::account_address::AccountAddress;
 
@@ -94,18 +112,22 @@ pub struct RpcImpl {
 
     // helper API for retrieving verified information from peers
     light: Arc<LightQueryService>,
+
+    config: RpcImplConfiguration,
 }
 
 impl RpcImpl {
     pub fn new(
         light: Arc<LightQueryService>, accounts: Arc<AccountProvider>,
         consensus: SharedConsensusGraph, data_man: Arc<BlockDataManager>,
+        config: RpcImplConfiguration,
     ) -> Self {
         RpcImpl {
             accounts,
             consensus,
             data_man,
             light,
+            config,
         }
     }
 
@@ -119,6 +141,39 @@ impl RpcImpl {
         .map_err(|e| e.into())
     }
 
+    fn account_key_bytes(address: &H160) -> Vec<u8> {
+        StorageKey::new_account_key(address)
+            .with_native_space()
+            .to_key_bytes()
+    }
+
+    /// Run `f` on the state of `epoch`, retrieving the state entries it reads
+    /// from peers. The entries of `prefetch` are retrieved together first.
+    /// `f` is given the height of `epoch` and runs on a blocking thread, as
+    /// every other read waits for the corresponding entry.
+    async fn with_proven_state<T, F>(
+        light: Arc<QueryService>, epoch: primitives::EpochNumber,
+        prefetch: Vec<Vec<u8>>, f: F,
+    ) -> CoreResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(StateDb, u64) -> CoreResult<T> + Send + 'static,
+    {
+        let height = invalid_params_check(
+            "epoch",
+            light.get_height_from_epoch_number(epoch),
+        )?;
+        let storage = ProvenStateStorage::new(light, height, Handle::current());
+        storage
+            .prefetch(prefetch)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        spawn_blocking(move || f(StateDb::new(Box::new(storage)), height))
+            .await
+            .map_err(|e| format!("Proven state execution failed: {}", e))?
+    }
+
     fn get_epoch_number_with_pivot_check(
         consensus_graph: SharedConsensusGraph,
         block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
@@ -179,6 +234,39 @@ impl RpcImpl {
         fut.boxed()
     }
 
+    fn accounts(
+        &self, addresses: Vec<RpcAddress>, num: Option<EpochNumber>,
+    ) -> CoreBoxFuture<Vec<RpcAccount>> {
+        let epoch = num.unwrap_or(EpochNumber::LatestState).into();
+
+        info!(
+            "RPC Request: cfx_getAccounts addresses={} epoch={:?}",
+            addresses.len(),
+            epoch
+        );
+
+        let light = self.light.clone();
+
+        let fut = async move {
+            check_accounts_limit(addresses.len())?;
+            for address in &addresses {
+                Self::check_address_network(address.network, &light)?;
+            }
+
+            let keys = addresses
+                .iter()
+                .map(|address| Self::account_key_bytes(&address.hex_address))
+                .collect();
+            // All the accounts are read from one snapshot of the state.
+            Self::with_proven_state(light, epoch, keys, move |statedb, _| {
+                read_rpc_accounts(&statedb, &addresses)
+            })
+            .await
+        };
+
+        fut.boxed()
+    }
+
     fn balance(
         &self, address: RpcAddress,
         block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
@@ -1017,6 +1105,200 @@ impl RpcImpl {
         fut.boxed()
     }
 
+    async fn exec_transaction(
+        light: Arc<QueryService>, consensus: SharedConsensusGraph,
+        max_gas: Option<U256>, request: TransactionRequest,
+        epoch: primitives::EpochNumber,
+    ) -> CoreResult<(ExecutionOutcome, EstimateExt)> {
+        let rpc_request_network = invalid_params_check(
+            "request",
+            check_two_rpc_address_network_match(
+                request.from.as_ref(),
+                request.to.as_ref(),
+            ),
+        )?;
+        invalid_params_check(
+            "request",
+            check_rpc_address_network(
+                rpc_request_network,
+                light.get_network_type(),
+            ),
+        )?;
+
+        let estimate_request = EstimateRequest {
+            has_sender: request.from.is_some(),
+            has_gas_limit: request.gas.is_some(),
+            has_gas_price: request.has_gas_price(),
+            has_nonce: request.nonce.is_some(),
+            has_storage_limit: request.storage_limit.is_some(),
+        };
+        let chain_id = consensus.best_chain_id();
+
+        // Building the state reads all the global parameters.
+        let mut keys = ProvenStateStorage::global_param_keys();
+        keys.extend(
+            request
+                .from
+                .iter()
+                .chain(&request.to)
+                .map(|address| Self::account_key_bytes(&address.hex_address)),
+        );
+
+        Self::with_proven_state(light, epoch, keys, move |statedb, height| {
+            let signed_tx = request
+                .sign_call(height, chain_id.in_native_space(), max_gas)
+                .map_err(error_object_owned_to_jsonrpc_error)?;
+            debug!("call tx {:?}", signed_tx);
+
+            consensus.call_virtual_on_statedb(
+                &signed_tx,
+                height,
+                statedb,
+                estimate_request,
+                Default::default(),
+            )
+        })
+        .await
+    }
+
+    fn call(
+        &self, request: TransactionRequest,
+        block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
+    ) -> CoreBoxFuture<Bytes> {
+        info!(
+            "RPC Request: cfx_call request={:?} epoch={:?}",
+            request, block_hash_or_epoch_number
+        );
+
+        // clone to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+        let consensus_graph = self.consensus.clone();
+        let max_gas = self.config.max_estimation_gas_limit;
+
+        let fut = async move {
+            let epoch = Self::get_epoch_number_with_pivot_check(
+                consensus_graph.clone(),
+                block_hash_or_epoch_number,
+            )?
+            .into();
+            let (execution_outcome, _estimation) = Self::exec_transaction(
+                light,
+                consensus_graph,
+                max_gas,
+                request,
+                epoch,
+            )
+            .await?;
+            call_output(execution_outcome)
+        };
+
+        fut.boxed()
+    }
+
+    fn estimate_gas_and_collateral(
+        &self, request: TransactionRequest, epoch: Option<EpochNumber>,
+    ) -> CoreBoxFuture<EstimateGasAndCollateralResponse> {
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState).into();
+        info!(
+            "RPC Request: cfx_estimateGasAndCollateral request={:?}, epoch={:?}",
+            request, epoch
+        );
+
+        // clone to avoid lifetime issues due to capturing `self`
+        let light = self.light.clone();
+        let consensus_graph = self.consensus.clone();
+        let max_gas = self.config.max_estimation_gas_limit;
+
+        let fut = async move {
+            let network = *light.get_network_type();
+            let (execution_outcome, estimation) = Self::exec_transaction(
+                light,
+                consensus_graph,
+                max_gas,
+                request,
+                epoch,
+            )
+            .await?;
+            estimation_response(execution_outcome, estimation, network)
+        };
+
+        fut.boxed()
+    }
+
+    fn get_supply_info(
+        &self, epoch: Option<EpochNumber>,
+    ) -> CoreBoxFuture<TokenSupplyInfo> {
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState).into();
+        info!("RPC Request: cfx_getSupplyInfo epoch={:?}", epoch);
+
+        let light = self.light.clone();
+
+        let fut = async move {
+            let keys = ProvenStateStorage::global_param_keys();
+            Self::with_proven_state(light, epoch, keys, |statedb, _| {
+                Ok(supply_info(&State::new(statedb)?))
+            })
+            .await
+        };
+
+        fut.boxed()
+    }
+
+    fn get_collateral_info(
+        &self, epoch: Option<EpochNumber>,
+    ) -> CoreBoxFuture<StorageCollateralInfo> {
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState).into();
+        info!("RPC Request: cfx_getCollateralInfo epoch={:?}", epoch);
+
+        let light = self.light.clone();
+
+        let fut = async move {
+            let keys = ProvenStateStorage::global_param_keys();
+            Self::with_proven_state(light, epoch, keys, |statedb, _| {
+                Ok(collateral_info(&State::new(statedb)?))
+            })
+            .await
+        };
+
+        fut.boxed()
+    }
+
+    fn get_vote_params(
+        &self, epoch: Option<EpochNumber>,
+    ) -> CoreBoxFuture<VoteParamsInfo> {
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState).into();
+        info!("RPC Request: cfx_getParamsFromVote epoch={:?}", epoch);
+
+        let light = self.light.clone();
+
+        let fut = async move {
+            let keys = ProvenStateStorage::global_param_keys();
+            Self::with_proven_state(light, epoch, keys, |statedb, _| {
+                vote_params(&statedb)
+            })
+            .await
+        };
+
+        fut.boxed()
+    }
+
+    fn get_fee_burnt(&self, epoch: Option<EpochNumber>) -> CoreBoxFuture<U256> {
+        let epoch = epoch.unwrap_or(EpochNumber::LatestState).into();
+        info!("RPC Request: cfx_getFeeBurnt epoch={:?}", epoch);
+
+        let light = self.light.clone();
+
+        let fut = async move {
+            let keys = ProvenStateStorage::global_param_keys();
+            Self::with_proven_state(light, epoch, keys, |statedb, _| {
+                Ok(statedb.get_global_param::<TotalBurnt1559>()?)
+            })
+            .await
+        };
+
+        fut.boxed()
+    }
+
     fn check_balance_against_transaction(
         &self, account_addr: RpcAddress, contract_addr: RpcAddress,
         gas_limit: U256, gas_price: U256, storage_limit: U256,
@@ -1193,6 +1475,7 @@ impl Cfx for CfxHandler {
 
         to self.rpc_impl {
             fn account(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<RpcAccount>>;
+            fn accounts(&self, addresses: Vec<RpcAddress>, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<Vec<RpcAccount>>>;
             fn accumulate_interest_rate(&self, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<U256>>;
             fn admin(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<Option<RpcAddress>>>;
             fn balance(&self, address: RpcAddress, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> BoxFuture<JsonRpcResult<U256>>;
@@ -1219,6 +1502,12 @@ impl Cfx for CfxHandler {
             fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<JsonRpcResult<Option<RpcReceipt>>>;
             fn vote_list(&self, address: RpcAddress, num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<Vec<VoteStakeInfo>>>;
             fn fee_history(&self, block_count: HexU64, newest_block: EpochNumber, reward_percentiles: Option<Vec<f64>>) -> BoxFuture<JsonRpcResult<CfxFeeHistory>>;
+            fn call(&self, request: TransactionRequest, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> BoxFuture<JsonRpcResult<Bytes>>;
+            fn estimate_gas_and_collateral(&self, request: TransactionRequest, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<EstimateGasAndCollateralResponse>>;
+            fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<TokenSupplyInfo>>;
+            fn get_collateral_info(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<StorageCollateralInfo>>;
+            fn get_vote_params(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<VoteParamsInfo>>;
+            fn get_fee_burnt(&self, epoch_num: Option<EpochNumber>) -> BoxFuture<JsonRpcResult<U256>>;
         }
     }
 
@@ -1226,15 +1515,10 @@ impl Cfx for CfxHandler {
     not_supported! {
         fn account_pending_transactions(&self, address: RpcAddress, maybe_start_nonce: Option<U256>, maybe_limit: Option<U64>) -> BoxFuture<JsonRpcResult<AccountPendingTransactions>>;
         fn block_by_block_number(&self, block_number: U64, include_txs: bool) -> BoxFuture<JsonRpcResult<Option<RpcBlock>>>;
-        fn call(&self, request: TransactionRequest, block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>) -> JsonRpcResult<Bytes>;
-        fn estimate_gas_and_collateral(&self, request: TransactionRequest, epoch_num: Option<EpochNumber>) -> JsonRpcResult<EstimateGasAndCollateralResponse>;
         fn get_block_reward_info(&self, num: EpochNumber) -> JsonRpcResult<Vec<RpcRewardInfo>>;
-        fn get_supply_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<TokenSupplyInfo>;
-        fn get_collateral_info(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<StorageCollateralInfo>;
-        fn get_vote_params(&self, epoch_num: Option<EpochNumber>) -> JsonRpcResult<VoteParamsInfo>;
         fn get_pos_reward_by_epoch(&self, epoch: EpochNumber) -> JsonRpcResult<Option<PoSEpochReward>>;
-        fn get_fee_burnt(&self, epoch: Option<EpochNumber>) -> JsonRpcResult<U256>;
         fn max_priority_fee_per_gas(&self) -> BoxFuture<JsonRpcResult<U256>>;
+        fn transactions_by_address(&self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>) -> BoxFuture<JsonRpcResult<TransactionsByAddress>>;
     }
 }
 
@@ -1328,6 +1612,7 @@ impl LocalRpc for DebugRpcImpl {
             fn net_throttling(&self) -> JsonRpcResult<throttling::Service>;
             fn new_account(&self, password: String) -> JsonRpcResult<RpcAddress>;
             fn sign(&self, data: Bytes, address: RpcAddress, password: Option<String>) -> JsonRpcResult<H520>;
+            fn sign_typed_data(&self, address: RpcAddress, data: TypedData, password: Option<String>) -> JsonRpcResult<H520>;
             fn unlock_account(&self, address: RpcAddress, password: String, duration: Option<U128>) -> JsonRpcResult<bool>;
         }
 
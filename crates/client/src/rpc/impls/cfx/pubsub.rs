@@ -8,6 +8,10 @@ use crate::rpc::{
     metadata::Metadata,
     traits::pubsub::PubSub,
     types::{
+        pos::{
+            Account as PosAccount, Block as PosBlock,
+            BlockNumber as PosBlockNumber, NodeLockStatus, RpcCommittee,
+        },
         pubsub::{self, SubscriptionEpoch},
         Header as RpcHeader, Log as RpcLog,
     },
@@ -17,10 +21,13 @@ use cfx_parameters::{
     consensus::DEFERRED_STATE_EPOCH_COUNT,
     consensus_internal::REWARD_EPOCH_COUNT,
 };
+use cfx_rpc_cfx_api::PosRpcServer;
+use cfx_rpc_cfx_impl::PosHandler;
 use cfx_rpc_utils::error::jsonrpc_error_helpers::error_object_owned_to_jsonrpc_error;
-use cfx_types::{Space, H256};
+use cfx_types::{Space, H256, U64};
 use cfxcore::{
-    channel::Channel, BlockDataManager, Notifications, SharedConsensusGraph,
+    channel::Channel, consensus::pos_handler::PosVerifier, BlockDataManager,
+    Notifications, SharedConsensusGraph,
 };
 use futures::future::join_all;
 use itertools::zip;
@@ -32,7 +39,7 @@ use jsonrpc_pubsub::{
 use log::{debug, error, trace, warn};
 use parking_lot::RwLock;
 use primitives::{
-    filter::LogFilter, log_entry::LocalizedLogEntry, BlockReceipts,
+    filter::LogFilter, log_entry::LocalizedLogEntry, BlockReceipts, EpochNumber,
 };
 use std::{
     sync::{Arc, Weak},
@@ -49,6 +56,9 @@ pub struct PubSubClient {
     heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
     epochs_subscribers: Arc<RwLock<Subscribers<Client>>>,
     logs_subscribers: Arc<RwLock<Subscribers<(Client, LogFilter)>>>,
+    finalized_epochs_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    pos_blocks_subscribers: Arc<RwLock<Subscribers<Client>>>,
+    pos_account_subscribers: Arc<RwLock<Subscribers<Client>>>,
     heads_loop_started: Arc<RwLock<bool>>,
     notifications: Arc<Notifications>,
     pub executor: Arc<Runtime>,
@@ -59,15 +69,30 @@ impl PubSubClient {
     pub fn new(
         executor: Arc<Runtime>, consensus: SharedConsensusGraph,
         notifications: Arc<Notifications>, network: Network,
+        pos_verifier: Arc<PosVerifier>,
     ) -> Self {
         let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
         let epochs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
         let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
-
+        let finalized_epochs_subscribers =
+            Arc::new(RwLock::new(Subscribers::default()));
+        let pos_blocks_subscribers =
+            Arc::new(RwLock::new(Subscribers::default()));
+        let pos_account_subscribers =
+            Arc::new(RwLock::new(Subscribers::default()));
+
+        let pos = PosHandler::new(
+            pos_verifier.clone(),
+            consensus.data_manager().clone(),
+            network,
+            consensus.clone(),
+        );
         let handler = Arc::new(ChainNotificationHandler {
             consensus: consensus.clone(),
             data_man: consensus.data_manager().clone(),
             network,
+            pos_verifier,
+            pos,
         });
 
         PubSubClient {
@@ -75,6 +100,9 @@ impl PubSubClient {
             heads_subscribers,
             epochs_subscribers,
             logs_subscribers,
+            finalized_epochs_subscribers,
+            pos_blocks_subscribers,
+            pos_account_subscribers,
             heads_loop_started: Arc::new(RwLock::new(false)),
             notifications,
             executor,
@@ -291,6 +319,254 @@ impl PubSubClient {
 
         self.executor.spawn(fut);
     }
+
+    // Start an async loop that publishes the notifications returned by `poll`
+    // to subscriber `id`, first for the current state and then on every epoch
+    // notification. The loop terminates when subscriber `id` unsubscribes, or
+    // with an error notification when `poll` fails.
+    fn start_poll_loop(
+        &self, id: SubscriberId, subscribers: Arc<RwLock<Subscribers<Client>>>,
+        mut poll: impl FnMut(
+                &ChainNotificationHandler,
+            ) -> Result<Vec<pubsub::Result>, String>
+            + Send
+            + 'static,
+    ) {
+        trace!("start_poll_loop({:?})", id);
+
+        // clone everything we use in our async loop
+        let epochs_ordered = self.notifications.epochs_ordered.clone();
+        let handler = self.handler.clone();
+
+        // subscribe to the `epochs_ordered` channel
+        let mut receiver = epochs_ordered.subscribe();
+
+        // loop asynchronously
+        let fut = async move {
+            loop {
+                // retrieve subscriber
+                let sub = match subscribers.read().get(&id) {
+                    Some(sub) => sub.clone(),
+                    None => {
+                        // unsubscribed, terminate loop
+                        epochs_ordered.unsubscribe(receiver.id);
+                        return;
+                    }
+                };
+
+                let send_res = match poll(&*handler) {
+                    Ok(results) => results
+                        .into_iter()
+                        .try_for_each(|result| notify(&sub, result)),
+                    Err(e) => {
+                        warn!("Subscription {:?} failed: {}", id, e);
+                        let _ = sub.notify(Err(errors::internal_rpc_err(e)));
+                        epochs_ordered.unsubscribe(receiver.id);
+                        subscribers
+                            .write()
+                            .remove(&SubscriptionId::String(id.as_string()));
+                        return;
+                    }
+                };
+                if let Err(err) = send_res {
+                    if err.is_disconnected() {
+                        epochs_ordered.unsubscribe(receiver.id);
+                        subscribers
+                            .write()
+                            .remove(&SubscriptionId::String(id.as_string()));
+                        return;
+                    }
+                }
+
+                if receiver.recv().await.is_none() {
+                    return;
+                }
+            }
+        };
+
+        self.executor.spawn(fut);
+    }
+}
+
+/// The number of consecutive polls on which a finalized epoch, a PoS block or
+/// a PoS account which can not be retrieved is retried, before the
+/// subscription fails.
+const MAX_POLL_RETRIES: usize = 10;
+
+/// Counts the consecutive failures of a poller.
+#[derive(Default)]
+struct Retries(usize);
+
+impl Retries {
+    /// Returns `e` if the retries are exhausted.
+    fn fail(&mut self, e: String) -> Result<(), String> {
+        self.0 += 1;
+        if self.0 > MAX_POLL_RETRIES {
+            return Err(e);
+        }
+        debug!("{}, retry {}/{}", e, self.0, MAX_POLL_RETRIES);
+        Ok(())
+    }
+
+    fn reset(&mut self) { self.0 = 0; }
+}
+
+/// Retrieves the items numbered after `last` up to the latest one in order,
+/// e.g. the finalized epochs. An item which can not be retrieved is retried
+/// on the next poll instead of being skipped.
+struct OrderedPoller {
+    last: u64,
+    retries: Retries,
+}
+
+impl OrderedPoller {
+    fn new(last: u64) -> Self {
+        Self {
+            last,
+            retries: Retries::default(),
+        }
+    }
+
+    fn poll<T>(
+        &mut self, latest: u64,
+        mut retrieve: impl FnMut(u64) -> Result<Vec<T>, String>,
+    ) -> Result<Vec<T>, String> {
+        let mut items = vec![];
+        while self.last < latest {
+            match retrieve(self.last + 1) {
+                Ok(item) => {
+                    items.extend(item);
+                    self.last += 1;
+                    self.retries.reset();
+                }
+                Err(e) => {
+                    self.retries.fail(e)?;
+                    break;
+                }
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// The chain data published by the `finalizedEpochs`, `posBlocks` and
+/// `posAccount` subscriptions.
+trait PosChainData {
+    fn latest_finalized_epoch_number(&self) -> u64;
+
+    fn epoch_hashes(&self, epoch: u64) -> Result<Vec<H256>, String>;
+
+    fn latest_pos_block_number(&self) -> u64;
+
+    fn pos_block(&self, number: u64) -> Result<PosBlock, String>;
+
+    /// The committee of the PoS epoch of block `number`.
+    fn pos_committee(&self, number: u64) -> Result<RpcCommittee, String>;
+
+    fn pos_account(&self, address: H256) -> Result<PosAccount, String>;
+}
+
+/// Publishes the epochs finalized by PoS, in order.
+struct FinalizedEpochsPoller(OrderedPoller);
+
+impl FinalizedEpochsPoller {
+    fn new(chain: &impl PosChainData) -> Self {
+        Self(OrderedPoller::new(chain.latest_finalized_epoch_number()))
+    }
+
+    fn poll(
+        &mut self, chain: &impl PosChainData,
+    ) -> Result<Vec<pubsub::Result>, String> {
+        let latest = chain.latest_finalized_epoch_number();
+        self.0.poll(latest, |epoch| {
+            Ok(vec![pubsub::Result::Epoch {
+                epoch_number: epoch.into(),
+                epoch_hashes_ordered: chain.epoch_hashes(epoch)?,
+            }])
+        })
+    }
+}
+
+/// Publishes the committed PoS blocks in order, each block of a new PoS
+/// epoch preceded by its committee.
+struct PosBlocksPoller {
+    blocks: OrderedPoller,
+    epoch: Option<U64>,
+}
+
+impl PosBlocksPoller {
+    fn new(chain: &impl PosChainData) -> Self {
+        let last = chain.latest_pos_block_number();
+        Self {
+            blocks: OrderedPoller::new(last),
+            epoch: chain.pos_block(last).ok().map(|block| block.epoch),
+        }
+    }
+
+    fn poll(
+        &mut self, chain: &impl PosChainData,
+    ) -> Result<Vec<pubsub::Result>, String> {
+        let latest = chain.latest_pos_block_number();
+        let epoch = &mut self.epoch;
+        self.blocks.poll(latest, |number| {
+            let block = chain.pos_block(number)?;
+            let mut results = vec![];
+            if epoch.map_or(false, |epoch| epoch != block.epoch) {
+                results.push(pubsub::Result::PosCommitteeChange {
+                    committee: chain.pos_committee(number)?,
+                });
+            }
+            *epoch = Some(block.epoch);
+            results.push(pubsub::Result::PosBlock(block));
+            Ok(results)
+        })
+    }
+}
+
+/// Publishes the status of a PoS account, first its current status and then
+/// every change of it. The status is retrieved when a new PoS block is
+/// committed.
+struct PosAccountPoller {
+    address: H256,
+    last_committed: Option<u64>,
+    status: Option<NodeLockStatus>,
+    retries: Retries,
+}
+
+impl PosAccountPoller {
+    fn new(address: H256) -> Self {
+        Self {
+            address,
+            last_committed: None,
+            status: None,
+            retries: Retries::default(),
+        }
+    }
+
+    fn poll(
+        &mut self, chain: &impl PosChainData,
+    ) -> Result<Vec<pubsub::Result>, String> {
+        let committed = chain.latest_pos_block_number();
+        if self.last_committed == Some(committed) {
+            return Ok(vec![]);
+        }
+
+        let account = match chain.pos_account(self.address) {
+            Ok(account) => account,
+            Err(e) => {
+                self.retries.fail(e)?;
+                return Ok(vec![]);
+            }
+        };
+        self.retries.reset();
+        self.last_committed = Some(committed);
+
+        if self.status.as_ref() == Some(&account.status) {
+            return Ok(vec![]);
+        }
+        self.status = Some(account.status.clone());
+        Ok(vec![pubsub::Result::PosAccount(account)])
+    }
 }
 
 /// PubSub notification handler.
@@ -298,9 +574,15 @@ pub struct ChainNotificationHandler {
     consensus: SharedConsensusGraph,
     data_man: Arc<BlockDataManager>,
     pub network: Network,
+    pos_verifier: Arc<PosVerifier>,
+    pos: PosHandler,
 }
 
 impl ChainNotificationHandler {
+    fn is_pos_enabled(&self) -> bool {
+        self.pos_verifier.pos_option().is_some()
+    }
+
     fn get_header_by_hash(&self, hash: &H256) -> Result<RpcHeader, String> {
         let header = match self.data_man.block_header_by_hash(hash) {
             Some(h) => build_header(&*h, self.network, self.consensus.clone()),
@@ -505,6 +787,48 @@ impl ChainNotificationHandler {
     }
 }
 
+impl PosChainData for ChainNotificationHandler {
+    fn latest_finalized_epoch_number(&self) -> u64 {
+        self.consensus.latest_finalized_epoch_number()
+    }
+
+    fn epoch_hashes(&self, epoch: u64) -> Result<Vec<H256>, String> {
+        self.consensus
+            .get_block_hashes_by_epoch(EpochNumber::Number(epoch))
+            .map_err(|e| format!("Cannot retrieve epoch {}: {}", epoch, e))
+    }
+
+    fn latest_pos_block_number(&self) -> u64 {
+        self.pos
+            .pos_status()
+            .map(|status| status.latest_committed.as_u64())
+            .unwrap_or_default()
+    }
+
+    fn pos_block(&self, number: u64) -> Result<PosBlock, String> {
+        self.pos
+            .pos_block_by_number(PosBlockNumber::Num(number.into()))
+            .ok()
+            .flatten()
+            .ok_or_else(|| format!("Committed PoS block {} not found", number))
+    }
+
+    fn pos_committee(&self, number: u64) -> Result<RpcCommittee, String> {
+        self.pos
+            .pos_committee(Some(number.into()))
+            .map(|state| state.current_committee)
+            .map_err(|e| {
+                format!("Cannot retrieve PoS committee at {}: {}", number, e)
+            })
+    }
+
+    fn pos_account(&self, address: H256) -> Result<PosAccount, String> {
+        self.pos.pos_account(address, None).map_err(|e| {
+            format!("Cannot retrieve PoS account {:?}: {}", address, e)
+        })
+    }
+}
+
 impl PubSub for PubSubClient {
     type Metadata = Metadata;
 
@@ -563,6 +887,61 @@ impl PubSub for PubSubClient {
             (pubsub::Kind::Logs, _) => {
                 errors::invalid_params("logs", "Expected filter parameter.")
             }
+            // --------- finalizedEpochs ---------
+            (pubsub::Kind::FinalizedEpochs, None) => {
+                let id =
+                    self.finalized_epochs_subscribers.write().push(subscriber);
+                let mut poller = FinalizedEpochsPoller::new(&*self.handler);
+                self.start_poll_loop(
+                    id,
+                    self.finalized_epochs_subscribers.clone(),
+                    move |chain| poller.poll(chain),
+                );
+                return;
+            }
+            (pubsub::Kind::FinalizedEpochs, _) => errors::invalid_params(
+                "finalizedEpochs",
+                "Expected no parameters.",
+            ),
+            // --------- posBlocks / posAccount ---------
+            (pubsub::Kind::PosBlocks | pubsub::Kind::PosAccount, _)
+                if !self.handler.is_pos_enabled() =>
+            {
+                errors::build_rpc_server_error(
+                    errors::codes::POS_NOT_ENABLED,
+                    "PoS chain is not enabled".into(),
+                )
+            }
+            (pubsub::Kind::PosBlocks, None) => {
+                let id = self.pos_blocks_subscribers.write().push(subscriber);
+                let mut poller = PosBlocksPoller::new(&*self.handler);
+                self.start_poll_loop(
+                    id,
+                    self.pos_blocks_subscribers.clone(),
+                    move |chain| poller.poll(chain),
+                );
+                return;
+            }
+            (pubsub::Kind::PosBlocks, _) => {
+                errors::invalid_params("posBlocks", "Expected no parameters.")
+            }
+            (
+                pubsub::Kind::PosAccount,
+                Some(pubsub::Params::PosAccount(address)),
+            ) => {
+                let id = self.pos_account_subscribers.write().push(subscriber);
+                let mut poller = PosAccountPoller::new(address);
+                self.start_poll_loop(
+                    id,
+                    self.pos_account_subscribers.clone(),
+                    move |chain| poller.poll(chain),
+                );
+                return;
+            }
+            (pubsub::Kind::PosAccount, _) => errors::invalid_params(
+                "posAccount",
+                "Expected PoS account address.",
+            ),
             _ => errors::unimplemented(None),
         };
 
@@ -575,11 +954,236 @@ impl PubSub for PubSubClient {
         let res0 = self.heads_subscribers.write().remove(&id).is_some();
         let res1 = self.epochs_subscribers.write().remove(&id).is_some();
         let res2 = self.logs_subscribers.write().remove(&id).is_some();
-
-        Ok(res0 || res1 || res2)
+        let res3 = self
+            .finalized_epochs_subscribers
+            .write()
+            .remove(&id)
+            .is_some();
+        let res4 = self.pos_blocks_subscribers.write().remove(&id).is_some();
+        let res5 = self.pos_account_subscribers.write().remove(&id).is_some();
+
+        Ok(res0 || res1 || res2 || res3 || res4 || res5)
     }
 }
 
 fn notify(subscriber: &Client, result: pubsub::Result) -> SinkResult {
     subscriber.notify(Ok(result))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::types::pos::VotePowerState;
+    use std::collections::{HashMap, HashSet};
+
+    /// Serves the epochs and PoS blocks up to `finalized` and `committed`,
+    /// except for the `missing` ones. The PoS epoch of block `n` is `n / 3`.
+    #[derive(Default)]
+    struct MockChain {
+        finalized: u64,
+        committed: u64,
+        missing: HashSet<u64>,
+        accounts: HashMap<H256, NodeLockStatus>,
+    }
+
+    impl PosChainData for MockChain {
+        fn latest_finalized_epoch_number(&self) -> u64 { self.finalized }
+
+        fn epoch_hashes(&self, epoch: u64) -> Result<Vec<H256>, String> {
+            if epoch > self.finalized || self.missing.contains(&epoch) {
+                return Err(format!("epoch {} missing", epoch));
+            }
+            Ok(vec![H256::from_low_u64_be(epoch)])
+        }
+
+        fn latest_pos_block_number(&self) -> u64 { self.committed }
+
+        fn pos_block(&self, number: u64) -> Result<PosBlock, String> {
+            if number > self.committed || self.missing.contains(&number) {
+                return Err(format!("block {} missing", number));
+            }
+            Ok(PosBlock {
+                hash: H256::from_low_u64_be(number),
+                height: number.into(),
+                epoch: (number / 3).into(),
+                round: number.into(),
+                last_tx_number: number.into(),
+                miner: None,
+                parent_hash: H256::from_low_u64_be(number - 1),
+                timestamp: number.into(),
+                pivot_decision: None,
+                signatures: vec![],
+            })
+        }
+
+        fn pos_committee(&self, number: u64) -> Result<RpcCommittee, String> {
+            Ok(RpcCommittee {
+                epoch_number: (number / 3).into(),
+                ..Default::default()
+            })
+        }
+
+        fn pos_account(&self, address: H256) -> Result<PosAccount, String> {
+            let status = self
+                .accounts
+                .get(&address)
+                .ok_or_else(|| format!("account {:?} missing", address))?;
+            Ok(PosAccount {
+                address,
+                block_number: self.committed.into(),
+                status: status.clone(),
+            })
+        }
+    }
+
+    fn epoch_numbers(results: &[pubsub::Result]) -> Vec<u64> {
+        results
+            .iter()
+            .map(|result| match result {
+                pubsub::Result::Epoch {
+                    epoch_number,
+                    epoch_hashes_ordered,
+                } => {
+                    let epoch = epoch_number.as_u64();
+                    assert_eq!(
+                        epoch_hashes_ordered,
+                        &vec![H256::from_low_u64_be(epoch)]
+                    );
+                    epoch
+                }
+                result => panic!("unexpected result {:?}", result),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_finalized_epochs_poller() {
+        let mut chain = MockChain {
+            finalized: 5,
+            ..Default::default()
+        };
+        let mut poller = FinalizedEpochsPoller::new(&chain);
+        assert!(poller.poll(&chain).unwrap().is_empty());
+
+        chain.finalized = 8;
+        assert_eq!(epoch_numbers(&poller.poll(&chain).unwrap()), [6, 7, 8]);
+
+        // an epoch which can not be retrieved is not skipped
+        chain.finalized = 11;
+        chain.missing.insert(10);
+        assert_eq!(epoch_numbers(&poller.poll(&chain).unwrap()), [9]);
+        assert!(poller.poll(&chain).unwrap().is_empty());
+        chain.missing.clear();
+        assert_eq!(epoch_numbers(&poller.poll(&chain).unwrap()), [10, 11]);
+
+        // the subscription fails once the retries are exhausted
+        chain.finalized = 12;
+        chain.missing.insert(12);
+        for _ in 0..MAX_POLL_RETRIES {
+            assert!(poller.poll(&chain).unwrap().is_empty());
+        }
+        assert!(poller.poll(&chain).is_err());
+    }
+
+    #[test]
+    fn test_pos_blocks_poller() {
+        let mut chain = MockChain {
+            committed: 4,
+            ..Default::default()
+        };
+        let mut poller = PosBlocksPoller::new(&chain);
+        assert!(poller.poll(&chain).unwrap().is_empty());
+
+        // blocks 6 and 9 start the PoS epochs 2 and 3
+        chain.committed = 9;
+        let results: Vec<_> = poller
+            .poll(&chain)
+            .unwrap()
+            .into_iter()
+            .map(|result| match result {
+                pubsub::Result::PosBlock(block) => {
+                    format!("block {}", block.height)
+                }
+                pubsub::Result::PosCommitteeChange { committee } => {
+                    format!("committee {}", committee.epoch_number)
+                }
+                result => panic!("unexpected result {:?}", result),
+            })
+            .collect();
+        assert_eq!(
+            results,
+            [
+                "block 5",
+                "committee 2",
+                "block 6",
+                "block 7",
+                "block 8",
+                "committee 3",
+                "block 9"
+            ]
+        );
+
+        chain.committed = 11;
+        chain.missing.insert(10);
+        for _ in 0..MAX_POLL_RETRIES {
+            assert!(poller.poll(&chain).unwrap().is_empty());
+        }
+        assert!(poller.poll(&chain).is_err());
+    }
+
+    #[test]
+    fn test_pos_account_poller() {
+        let address = H256::from_low_u64_be(1);
+        let status = |forfeited: u64| NodeLockStatus {
+            in_queue: vec![VotePowerState {
+                end_block_number: 10.into(),
+                power: 1.into(),
+            }],
+            forfeited: forfeited.into(),
+            ..Default::default()
+        };
+        let mut chain = MockChain {
+            committed: 1,
+            ..Default::default()
+        };
+        chain.accounts.insert(address, status(0));
+        let mut poller = PosAccountPoller::new(address);
+
+        let forfeited = |results: Vec<pubsub::Result>| -> Vec<u64> {
+            results
+                .into_iter()
+                .map(|result| match result {
+                    pubsub::Result::PosAccount(account) => {
+                        assert_eq!(account.address, address);
+                        account.status.forfeited.as_u64()
+                    }
+                    result => panic!("unexpected result {:?}", result),
+                })
+                .collect()
+        };
+
+        // the current status first
+        assert_eq!(forfeited(poller.poll(&chain).unwrap()), [0]);
+        // then only its changes, checked once per committed block
+        chain.committed = 2;
+        assert!(poller.poll(&chain).unwrap().is_empty());
+        chain.accounts.insert(address, status(1));
+        assert!(poller.poll(&chain).unwrap().is_empty());
+        chain.committed = 3;
+        assert_eq!(forfeited(poller.poll(&chain).unwrap()), [1]);
+
+        // a status which can not be retrieved is retrieved again
+        chain.committed = 4;
+        chain.accounts.clear();
+        assert!(poller.poll(&chain).unwrap().is_empty());
+        chain.accounts.insert(address, status(2));
+        assert_eq!(forfeited(poller.poll(&chain).unwrap()), [2]);
+
+        chain.committed = 5;
+        chain.accounts.clear();
+        for _ in 0..MAX_POLL_RETRIES {
+            assert!(poller.poll(&chain).unwrap().is_empty());
+        }
+        assert!(poller.poll(&chain).is_err());
+    }
+}
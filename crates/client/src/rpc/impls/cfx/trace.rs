@@ -8,7 +8,8 @@ use crate::{
         traits::trace::Trace,
         types::{
             EpochNumber as RpcEpochNumber, LocalizedBlockTrace, LocalizedTrace,
-            TraceFilter as RpcTraceFilter,
+            TraceFilter as RpcTraceFilter, TraceResults,
+            TraceResultsWithTransactionHash, TraceType, TransactionRequest,
         },
     },
 };
@@ -16,7 +17,7 @@ use cfx_addr::Network;
 use cfx_rpc_cfx_impl::TraceHandler as CfxTraceHandler;
 use cfx_rpc_eth_types::trace::EpochTrace;
 use cfx_rpc_utils::error::jsonrpc_error_helpers::error_object_owned_to_jsonrpc_error;
-use cfx_types::H256;
+use cfx_types::{H256, U256};
 
 use cfxcore::SharedConsensusGraph;
 use jsonrpc_core::Result as JsonRpcResult;
@@ -27,9 +28,16 @@ pub struct TraceHandler {
 }
 
 impl TraceHandler {
-    pub fn new(network: Network, consensus: SharedConsensusGraph) -> Self {
+    pub fn new(
+        network: Network, consensus: SharedConsensusGraph,
+        max_estimation_gas_limit: Option<U256>,
+    ) -> Self {
         TraceHandler {
-            inner: CfxTraceHandler::new(network, consensus),
+            inner: CfxTraceHandler::new(
+                network,
+                consensus,
+                max_estimation_gas_limit,
+            ),
         }
     }
 }
@@ -61,4 +69,28 @@ impl Trace for TraceHandler {
     ) -> JsonRpcResult<Option<EpochTrace>> {
         into_jsonrpc_result(self.inner.epoch_trace_impl(epoch.into_primitive()))
     }
+
+    fn call(
+        &self, request: TransactionRequest, trace_types: Vec<TraceType>,
+        epoch: Option<RpcEpochNumber>,
+    ) -> JsonRpcResult<TraceResults> {
+        into_jsonrpc_result(self.inner.call_impl(request, trace_types, epoch))
+    }
+
+    fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> JsonRpcResult<Option<TraceResults>> {
+        into_jsonrpc_result(
+            self.inner.replay_transaction_impl(tx_hash, trace_types),
+        )
+    }
+
+    fn replay_block_transactions(
+        &self, epoch: RpcEpochNumber, trace_types: Vec<TraceType>,
+    ) -> JsonRpcResult<Vec<TraceResultsWithTransactionHash>> {
+        into_jsonrpc_result(
+            self.inner
+                .replay_epoch_transactions_impl(epoch, trace_types),
+        )
+    }
 }
@@ -2,18 +2,21 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
+use blockgen::BlockGeneratorTestApi;
 use cfx_rpc_builder::{
-    RpcModuleBuilder, RpcServerConfig, RpcServerHandle,
+    GraphQLServerConfig, HttpServerController, JwtSecret, RpcModuleBuilder,
+    RpcModuleSelection, RpcServerConfig, RpcServerHandle,
     TransportRpcModuleConfig,
 };
 use cfx_tasks::TaskExecutor;
 use cfxcore::{
-    Notifications, SharedConsensusGraph, SharedSynchronizationService,
-    SharedTransactionPool,
+    consensus::pos_handler::PosVerifier, Notifications, SharedConsensusGraph,
+    SharedSynchronizationService, SharedTransactionPool,
 };
+use cfxcore_accounts::AccountProvider;
 use jsonrpc_core::{MetaIoHandler, RemoteProcedure, Value};
 use jsonrpc_http_server::{
-    Server as HttpServer, ServerBuilder as HttpServerBuilder,
+    DomainsValidation, Server as HttpServer, ServerBuilder as HttpServerBuilder,
 };
 use jsonrpc_tcp_server::{
     MetaExtractor as TpcMetaExtractor, Server as TcpServer,
@@ -25,7 +28,7 @@ use jsonrpc_ws_server::{
 };
 pub use jsonrpsee::server::ServerBuilder;
 use log::{info, warn};
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 mod authcodes;
 pub mod errors;
@@ -175,6 +178,7 @@ fn setup_rpc_apis(
                 let trace = TraceHandler::new(
                     *rpc.sync.network.get_network_type(),
                     rpc.consensus.clone(),
+                    rpc.config.max_estimation_gas_limit,
                 )
                 .to_delegate();
                 extend_with_interceptor(
@@ -403,53 +407,137 @@ where
     }
 }
 
-// start espace rpc server v2(async)
+// start espace rpc server v2(async) and the core space graphql server
 pub async fn launch_async_rpc_servers(
     consensus: SharedConsensusGraph, sync: SharedSynchronizationService,
     tx_pool: SharedTransactionPool, notifications: Arc<Notifications>,
-    executor: TaskExecutor, conf: &Configuration,
+    accounts: Arc<AccountProvider>, pos_verifier: Arc<PosVerifier>,
+    block_gen: BlockGeneratorTestApi, executor: TaskExecutor,
+    conf: &Configuration,
 ) -> Result<Option<RpcServerHandle>, String> {
     let http_config = conf.eth_http_config();
     let ws_config = conf.eth_ws_config();
-    let apis = conf.raw_conf.public_evm_rpc_apis.clone();
-
-    let (transport_rpc_module_config, server_config) =
-        match (http_config.enabled, ws_config.enabled) {
-            (true, true) => {
-                let transport_rpc_module_config =
-                    TransportRpcModuleConfig::set_http(apis.clone())
-                        .with_ws(apis.clone());
-
-                let server_config =
-                    RpcServerConfig::http(conf.jsonrpsee_server_builder())
-                        .with_ws(conf.jsonrpsee_server_builder())
-                        .with_http_address(http_config.address)
-                        .with_ws_address(ws_config.address);
-                (transport_rpc_module_config, server_config)
-            }
-            (true, false) => {
-                let transport_rpc_module_config =
-                    TransportRpcModuleConfig::set_http(apis.clone());
-                let server_config =
-                    RpcServerConfig::http(conf.jsonrpsee_server_builder())
-                        .with_http_address(http_config.address);
-                (transport_rpc_module_config, server_config)
-            }
-            (false, true) => {
-                let transport_rpc_module_config =
-                    TransportRpcModuleConfig::set_ws(apis.clone());
-                let server_config =
-                    RpcServerConfig::ws(conf.jsonrpsee_server_builder())
-                        .with_ws_address(ws_config.address);
-                (transport_rpc_module_config, server_config)
-            }
-            _ => return Ok(None),
-        };
+    let ipc_config = conf.eth_ipc_config();
+    let graphql_config = conf.graphql_config();
+    let mut apis = conf.raw_conf.public_evm_rpc_apis.clone();
+
+    if !http_config.enabled
+        && !ws_config.enabled
+        && !ipc_config.enabled
+        && !graphql_config.enabled
+    {
+        return Ok(None);
+    }
+
+    let mut transport_rpc_module_config = TransportRpcModuleConfig::default();
+    let mut server_config = RpcServerConfig::default();
+    if let Some(auth_apis) = conf.raw_conf.auth_evm_rpc_apis.clone() {
+        // The modules requiring auth are served in addition to the public
+        // ones on http and ws.
+        info!(
+            "Evm rpc modules requiring JWT: {:?}",
+            auth_apis.to_selection()
+        );
+        apis = RpcModuleSelection::Selection(
+            apis.to_selection()
+                .union(&auth_apis.to_selection())
+                .copied()
+                .collect(),
+        );
+        transport_rpc_module_config =
+            transport_rpc_module_config.with_auth(auth_apis);
+        if conf.raw_conf.jsonrpc_jwt_secret_file.is_none() {
+            return Err("auth_evm_rpc_apis requires jsonrpc_jwt_secret_file"
+                .to_string());
+        }
+    }
+    if let Some(path) = &conf.raw_conf.jsonrpc_jwt_secret_file {
+        let secret =
+            JwtSecret::from_file(Path::new(path)).map_err(|e| e.to_string())?;
+        server_config = server_config.with_jwt_secret(secret);
+    }
+    if http_config.enabled {
+        transport_rpc_module_config =
+            transport_rpc_module_config.with_http(apis.clone());
+        server_config = server_config
+            .with_http(conf.jsonrpsee_server_builder())
+            .with_http_address(http_config.address);
+    }
+    if ws_config.enabled {
+        transport_rpc_module_config =
+            transport_rpc_module_config.with_ws(apis.clone());
+        server_config = server_config
+            .with_ws(conf.jsonrpsee_server_builder())
+            .with_ws_address(ws_config.address);
+    }
+    if ipc_config.enabled {
+        // The socket is only reachable by local users, so it serves its own
+        // module selection.
+        let ipc_apis = conf.raw_conf.ipc_evm_rpc_apis.clone();
+        info!("Enabled evm ipc rpc modules: {:?}", ipc_apis.to_selection());
+        transport_rpc_module_config =
+            transport_rpc_module_config.with_ipc(ipc_apis);
+        server_config = server_config
+            .with_ipc(conf.ipc_server_builder())
+            .with_ipc_endpoint(ipc_config.path);
+    }
 
     info!("Enabled evm async rpc modules: {:?}", apis.into_selection());
     let rpc_conf = conf.rpc_impl_config();
     let enable_metrics = rpc_conf.enable_metrics;
 
+    if graphql_config.enabled {
+        info!("Enabled core space graphql at {}", graphql_config.address);
+        let cfx = cfx_rpc_cfx_impl::CfxHandler::new(
+            rpc_conf.clone(),
+            consensus.clone(),
+            sync.clone(),
+            tx_pool.clone(),
+            accounts.clone(),
+            pos_verifier.clone(),
+            block_gen,
+        );
+        let pos = cfx_rpc_cfx_impl::PosHandler::new(
+            pos_verifier,
+            consensus.data_manager().clone(),
+            *sync.network.get_network_type(),
+            consensus.clone(),
+        );
+        let schema = cfx_rpc_graphql::build_schema(
+            Arc::new(cfx),
+            Arc::new(pos),
+            conf.raw_conf.jsonrpc_graphql_send_raw_transaction,
+        );
+        let mut config =
+            GraphQLServerConfig::new(graphql_config.address, schema)
+                .with_max_request_body_size(
+                    conf.raw_conf.jsonrpc_max_request_body_size as usize,
+                )
+                .with_keep_alive(graphql_config.keep_alive)
+                .with_require_jwt(conf.raw_conf.jsonrpc_graphql_require_jwt);
+        if let DomainsValidation::AllowOnly(domains) =
+            &graphql_config.cors_domains
+        {
+            config = config.with_cors_domains(
+                domains.iter().map(ToString::to_string).collect(),
+            );
+        }
+        if conf.raw_conf.jsonrpc_graphql_require_jwt
+            && conf.raw_conf.jsonrpc_jwt_secret_file.is_none()
+        {
+            return Err("jsonrpc_graphql_require_jwt requires \
+                        jsonrpc_jwt_secret_file"
+                .to_string());
+        }
+        server_config = server_config.with_graphql(config);
+    }
+
+    let throttling_conf_file = conf.raw_conf.throttling_conf.clone();
+    let http_controller = Arc::new(HttpServerController::new(
+        conf.jsonrpsee_server_builder(),
+        http_config.enabled.then_some(http_config.address),
+    ));
+
     let rpc_module_builder = RpcModuleBuilder::new(
         rpc_conf,
         consensus,
@@ -457,13 +545,15 @@ pub async fn launch_async_rpc_servers(
         tx_pool,
         executor,
         notifications,
-    );
+    )
+    .with_http_controller(http_controller.clone())
+    .with_accounts(accounts);
 
     let transport_rpc_modules =
         rpc_module_builder.build(transport_rpc_module_config);
 
-    let throttling_conf_file = conf.raw_conf.throttling_conf.clone();
     let server_handle = server_config
+        .with_http_controller(http_controller)
         .start(&transport_rpc_modules, throttling_conf_file, enable_metrics)
         .await
         .map_err(|e| e.to_string())?;
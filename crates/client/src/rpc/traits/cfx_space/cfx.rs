@@ -9,7 +9,8 @@ use crate::rpc::types::{
     EpochNumber, EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
     Receipt as RpcReceipt, RewardInfo as RpcRewardInfo, RpcAddress,
     SponsorInfo, Status as RpcStatus, StorageCollateralInfo, TokenSupplyInfo,
-    Transaction, TransactionRequest, VoteParamsInfo, U64 as HexU64,
+    Transaction, TransactionRequest, TransactionsByAddress, VoteParamsInfo,
+    U64 as HexU64,
 };
 use cfx_types::{H256, U256, U64};
 use jsonrpc_core::{BoxFuture, Result as JsonRpcResult};
@@ -169,7 +170,7 @@ pub trait Cfx {
     fn call(
         &self, tx: TransactionRequest,
         block_hash_or_epoch_number: Option<BlockHashOrEpochNumber>,
-    ) -> JsonRpcResult<Bytes>;
+    ) -> BoxFuture<JsonRpcResult<Bytes>>;
 
     /// Returns logs matching the filter provided.
     #[rpc(name = "cfx_getLogs")]
@@ -183,6 +184,14 @@ pub trait Cfx {
         &self, tx_hash: H256,
     ) -> BoxFuture<JsonRpcResult<Option<Transaction>>>;
 
+    /// Get the transactions sent from or to an address, from the newest one.
+    /// `cursor` is the `nextCursor` of the previous page. It requires
+    /// `persist_address_tx_index` to be enabled.
+    #[rpc(name = "cfx_getTransactionsByAddress")]
+    fn transactions_by_address(
+        &self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>,
+    ) -> BoxFuture<JsonRpcResult<TransactionsByAddress>>;
+
     /// Get transaction pending info by account address
     #[rpc(name = "cfx_getAccountPendingInfo")]
     fn account_pending_info(
@@ -200,7 +209,7 @@ pub trait Cfx {
     #[rpc(name = "cfx_estimateGasAndCollateral")]
     fn estimate_gas_and_collateral(
         &self, request: TransactionRequest, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<EstimateGasAndCollateralResponse>;
+    ) -> BoxFuture<JsonRpcResult<EstimateGasAndCollateralResponse>>;
 
     #[rpc(name = "cfx_feeHistory")]
     fn fee_history(
@@ -237,6 +246,13 @@ pub trait Cfx {
         &self, address: RpcAddress, epoch_num: Option<EpochNumber>,
     ) -> BoxFuture<JsonRpcResult<RpcAccount>>;
 
+    /// Return the accounts of the given addresses, in the same order. All of
+    /// them are read from the state of the same epoch.
+    #[rpc(name = "cfx_getAccounts")]
+    fn accounts(
+        &self, addresses: Vec<RpcAddress>, epoch_num: Option<EpochNumber>,
+    ) -> BoxFuture<JsonRpcResult<Vec<RpcAccount>>>;
+
     /// Returns interest rate of the given epoch
     #[rpc(name = "cfx_getInterestRate")]
     fn interest_rate(
@@ -277,18 +293,18 @@ pub trait Cfx {
     #[rpc(name = "cfx_getSupplyInfo")]
     fn get_supply_info(
         &self, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<TokenSupplyInfo>;
+    ) -> BoxFuture<JsonRpcResult<TokenSupplyInfo>>;
 
     /// Return information about total token supply.
     #[rpc(name = "cfx_getCollateralInfo")]
     fn get_collateral_info(
         &self, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<StorageCollateralInfo>;
+    ) -> BoxFuture<JsonRpcResult<StorageCollateralInfo>>;
 
     #[rpc(name = "cfx_getFeeBurnt")]
     fn get_fee_burnt(
         &self, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<U256>;
+    ) -> BoxFuture<JsonRpcResult<U256>>;
 
     #[rpc(name = "cfx_getPoSRewardByEpoch")]
     fn get_pos_reward_by_epoch(
@@ -298,7 +314,7 @@ pub trait Cfx {
     #[rpc(name = "cfx_getParamsFromVote")]
     fn get_vote_params(
         &self, epoch_number: Option<EpochNumber>,
-    ) -> JsonRpcResult<VoteParamsInfo>;
+    ) -> BoxFuture<JsonRpcResult<VoteParamsInfo>>;
 
     //        /// Returns transaction at given block hash and index.
     //        #[rpc(name = "cfx_getTransactionByBlockHashAndIndex")]
@@ -4,12 +4,12 @@
 
 use crate::rpc::types::{
     BlockHashOrEpochNumber, Bytes as RpcBytes, ConsensusGraphStates,
-    EpochNumber, Receipt as RpcReceipt, RpcAddress, StatOnGasLoad,
-    SyncGraphStates, Transaction as RpcTransaction, TransactionRequest,
-    WrapTransaction,
+    EpochNumber, EpochReceiptProof, Receipt as RpcReceipt, RpcAddress,
+    StatOnGasLoad, SyncGraphStates, Transaction as RpcTransaction,
+    TransactionRequest, WrapTransaction,
 };
+use cfx_typed_data::TypedData;
 use cfx_types::{H256, H520, U128, U64};
-use cfxcore::verification::EpochReceiptProof;
 use jsonrpc_core::{BoxFuture, Result as JsonRpcResult};
 use jsonrpc_derive::rpc;
 use network::{
@@ -102,6 +102,13 @@ pub trait LocalRpc {
         &self, data: RpcBytes, address: RpcAddress, password: Option<String>,
     ) -> JsonRpcResult<H520>;
 
+    /// Signs typed structured data via
+    /// [CIP-23](https://github.com/Conflux-Chain/CIPs/blob/master/CIPs/cip-23.md).
+    #[rpc(name = "cfx_signTypedData")]
+    fn sign_typed_data(
+        &self, address: RpcAddress, data: TypedData, password: Option<String>,
+    ) -> JsonRpcResult<H520>;
+
     #[rpc(name = "cfx_signTransaction")]
     fn sign_transaction(
         &self, tx: TransactionRequest, password: Option<String>,
@@ -4,6 +4,8 @@
 
 use crate::rpc::types::{
     EpochNumber, LocalizedBlockTrace, LocalizedTrace, TraceFilter,
+    TraceResults, TraceResultsWithTransactionHash, TraceType,
+    TransactionRequest,
 };
 use cfx_rpc_eth_types::trace::EpochTrace;
 use cfx_types::H256;
@@ -36,4 +38,25 @@ pub trait Trace {
     fn epoch_traces(
         &self, epoch: EpochNumber,
     ) -> JsonRpcResult<Option<EpochTrace>>;
+
+    /// Executes a transaction on the state of the epoch and returns the
+    /// requested traces.
+    #[rpc(name = "trace_call")]
+    fn call(
+        &self, request: TransactionRequest, trace_types: Vec<TraceType>,
+        epoch: Option<EpochNumber>,
+    ) -> JsonRpcResult<TraceResults>;
+
+    /// Replays a transaction and returns the requested traces.
+    #[rpc(name = "trace_replayTransaction")]
+    fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> JsonRpcResult<Option<TraceResults>>;
+
+    /// Replays the Core Space transactions in an epoch and returns the
+    /// requested traces.
+    #[rpc(name = "trace_replayBlockTransactions")]
+    fn replay_block_transactions(
+        &self, epoch: EpochNumber, trace_types: Vec<TraceType>,
+    ) -> JsonRpcResult<Vec<TraceResultsWithTransactionHash>>;
 }
@@ -7,7 +7,7 @@ pub use cfx_rpc_cfx_types::{
         RcpAddressNetworkInconsistent, RpcAddress, UnexpectedRpcAddressNetwork,
     },
     blame_info, block, consensus_graph_states, epoch_number, filter, log,
-    pos_economics, pubsub, receipt, reward_info, sponsor_info,
+    pos_economics, pubsub, receipt, receipt_proof, reward_info, sponsor_info,
     stat_on_gas_load, status, storage_collateral_info, sync_graph_states,
     token_supply_info, transaction, transaction_request, tx_pool,
     vote_params_info, CfxFeeHistory,
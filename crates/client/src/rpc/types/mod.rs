@@ -18,13 +18,14 @@ pub use self::cfx::{
     pos_economics::PoSEconomics,
     pubsub,
     receipt::Receipt,
+    receipt_proof::EpochReceiptProof,
     reward_info::RewardInfo,
     stat_on_gas_load::StatOnGasLoad,
     status::Status,
     storage_collateral_info::StorageCollateralInfo,
     sync_graph_states::{SyncGraphBlockState, SyncGraphStates},
     token_supply_info::TokenSupplyInfo,
-    transaction::{PackedOrExecuted, Transaction},
+    transaction::{PackedOrExecuted, Transaction, TransactionsByAddress},
     transaction_request::{
         self, CheckBalanceAgainstTransactionResponse,
         EstimateGasAndCollateralResponse, TransactionRequest,
@@ -42,8 +43,10 @@ pub use cfx_rpc_cfx_types::{
     provenance::Origin,
     trace::{
         Action, LocalizedBlockTrace, LocalizedTrace, LocalizedTransactionTrace,
+        TraceResults, TraceResultsWithTransactionHash,
     },
     trace_filter::TraceFilter,
+    trace_replay::TraceType,
 };
 pub use cfx_rpc_primitives::{Bytes, Index, U64};
 
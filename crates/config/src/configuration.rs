@@ -9,9 +9,14 @@ use lazy_static::*;
 use log::{error, warn};
 use parking_lot::RwLock;
 use rand::Rng;
+use rayon::ThreadPoolBuilder;
 
 use cfx_addr::{cfx_addr_decode, Network};
-use cfx_executor::{machine::Machine, spec::CommonParams};
+use cfx_executor::{
+    machine::Machine,
+    spec::{CommonParams, CustomPrecompileParams},
+    InternalContractPlugins, PrecompilePlugins,
+};
 use cfx_internal_common::{
     ChainIdParams, ChainIdParamsInner, ChainIdParamsOneChainInner,
 };
@@ -21,12 +26,14 @@ use cfx_parameters::{
 use cfx_rpc_cfx_types::{
     address::USE_SIMPLE_RPC_ADDRESS, apis::ApiSet, RpcImplConfiguration,
 };
+use cfx_rpc_ipc::IpcServerBuilder;
 use cfx_storage::{
     defaults::DEFAULT_DEBUG_SNAPSHOT_CHECKER_THREADS, storage_dir,
     ConsensusParam, ProvideExtraSnapshotSyncConfig, StorageConfiguration,
 };
 use cfx_types::{
-    parse_hex_string, Address, AllChainID, Space, SpaceMap, H256, U256,
+    address_util::AddressUtil, parse_hex_string, Address, AllChainID, Space,
+    SpaceMap, H256, U256,
 };
 use cfxcore::{
     block_data_manager::{DataManagerConfiguration, DbType},
@@ -58,7 +65,9 @@ use network::DiscoveryConfiguration;
 use primitives::block_header::CIP112_TRANSITION_HEIGHT;
 use txgen::TransactionGeneratorConfig;
 
-use crate::{HttpConfiguration, TcpConfiguration, WsConfiguration};
+use crate::{
+    HttpConfiguration, IpcConfiguration, TcpConfiguration, WsConfiguration,
+};
 
 lazy_static! {
     pub static ref CHAIN_ID: RwLock<Option<ChainIdParams>> = Default::default();
@@ -206,6 +215,8 @@ build_config! {
         // V3.1
         (cip166_transition_height, (Option<u64>), None)
         (osaka_opcode_transition_height, (Option<u64>), None)
+        (osaka_transition_height, (Option<u64>), None)
+        (internal_contract_plugins_transition_height, (Option<u64>), None)
 
 
 
@@ -221,6 +232,9 @@ build_config! {
         // Network section.
         (jsonrpc_local_tcp_port, (Option<u16>), None)
         (jsonrpc_local_http_port, (Option<u16>), None)
+        (jsonrpc_ipc_path, (Option<String>), None)
+        (jsonrpc_ipc_permissions, (u32), 0o600)
+        (jsonrpc_jwt_secret_file, (Option<String>), None)
         (jsonrpc_local_ws_port, (Option<u16>), None)
         (jsonrpc_ws_port, (Option<u16>), None)
         (jsonrpc_tcp_port, (Option<u16>), None)
@@ -231,6 +245,9 @@ build_config! {
         (jsonrpc_ws_max_payload_bytes, (usize), 30 * 1024 * 1024)
         (jsonrpc_http_eth_port, (Option<u16>), None)
         (jsonrpc_ws_eth_port, (Option<u16>), None)
+        (jsonrpc_graphql_port, (Option<u16>), None)
+        (jsonrpc_graphql_send_raw_transaction, (bool), false)
+        (jsonrpc_graphql_require_jwt, (bool), false)
         (jsonrpc_max_request_body_size, (u32), 10 * 1024 * 1024)
         (jsonrpc_max_response_body_size, (u32), 10 * 1024 * 1024)
         (jsonrpc_max_connections, (u32), 100)
@@ -247,6 +264,10 @@ build_config! {
         (public_address, (Option<String>), None)
         (udp_port, (Option<u16>), Some(32323))
         (max_estimation_gas_limit, (Option<u64>), None)
+        // The directory of the files exported and imported by
+        // `admin_exportChain` and `admin_importChain`. The two methods are
+        // disabled if it is not set.
+        (admin_chain_data_dir, (Option<String>), None)
         (rpc_address_simple_mode, (bool), false)
 
         // Network parameters section.
@@ -367,10 +388,19 @@ build_config! {
         (get_logs_epoch_batch_size, (usize), 32)
         (max_trans_count_received_in_catch_up, (u64), 60_000)
         (persist_tx_index, (bool), false)
+        // Keep the transactions sent from or to each address for
+        // `cfx_getTransactionsByAddress` and `eth_getTransactionsByAddress`.
+        (persist_address_tx_index, (bool), false)
+        // Index the logs by address and topic to speed up `cfx_getLogs` and
+        // `eth_getLogs`.
+        (persist_log_index, (bool), false)
         (persist_block_number_index, (bool), true)
         (print_memory_usage_period_s, (Option<u64>), None)
         (target_block_gas_limit, (u64), DEFAULT_TARGET_BLOCK_GAS_LIMIT)
         (executive_trace, (bool), false)
+        // Execute the transactions in a block speculatively in parallel with
+        // this number of threads. Zero disables parallel execution.
+        (parallel_execution_threads, (usize), 0)
         (check_status_genesis, (bool), true)
         (packing_gas_limit_block_count, (u64), 10)
         (poll_lifetime_in_seconds, (Option<u32>), None)
@@ -446,6 +476,10 @@ build_config! {
         // Usually this is needed to describe forks. This config overrides chain_id.
         (chain_id_params, (Option<ChainIdParamsOneChainInner>), None,
             ChainIdParamsOneChainInner::parse_config_str)
+        // The precompiles registered by the embedding binary to enable, see
+        // `parse_custom_precompiles`.
+        (custom_precompiles, (Vec<CustomPrecompileParams>), Vec::new(),
+            parse_custom_precompiles)
 
         // Storage section.
         (provide_more_snapshot_for_sync,
@@ -455,6 +489,8 @@ build_config! {
         (node_type, (Option<NodeType>), None, NodeType::from_str)
         (public_rpc_apis, (ApiSet), ApiSet::Safe, ApiSet::from_str)
         (public_evm_rpc_apis, (RpcModuleSelection), RpcModuleSelection::Evm, RpcModuleSelection::from_str)
+        (ipc_evm_rpc_apis, (RpcModuleSelection), RpcModuleSelection::Evm, RpcModuleSelection::from_str)
+        (auth_evm_rpc_apis, (Option<RpcModuleSelection>), None, RpcModuleSelection::from_str)
         (single_mpt_space, (Option<Space>), None, Space::from_str)
     }
 }
@@ -462,12 +498,20 @@ build_config! {
 #[derive(Debug, Clone)]
 pub struct Configuration {
     pub raw_conf: RawConfiguration,
+    /// The internal contracts supplied by the embedding binary, enabled after
+    /// `internal_contract_plugins_transition_height`.
+    pub internal_contract_plugins: InternalContractPlugins,
+    /// The precompiles supplied by the embedding binary, enabled at the
+    /// addresses of `custom_precompiles`.
+    pub precompile_plugins: PrecompilePlugins,
 }
 
 impl Default for Configuration {
     fn default() -> Self {
         Configuration {
             raw_conf: Default::default(),
+            internal_contract_plugins: Default::default(),
+            precompile_plugins: Default::default(),
         }
     }
 }
@@ -499,6 +543,8 @@ impl Configuration {
     pub fn from_file(config_path: &str) -> Result<Configuration, String> {
         Ok(Configuration {
             raw_conf: RawConfiguration::from_file(config_path)?,
+            internal_contract_plugins: Default::default(),
+            precompile_plugins: Default::default(),
         })
     }
 
@@ -994,6 +1040,8 @@ impl Configuration {
     pub fn data_mananger_config(&self) -> DataManagerConfiguration {
         let mut conf = DataManagerConfiguration {
             persist_tx_index: self.raw_conf.persist_tx_index,
+            persist_address_tx_index: self.raw_conf.persist_address_tx_index,
+            persist_log_index: self.raw_conf.persist_log_index,
             persist_block_number_index: self
                 .raw_conf
                 .persist_block_number_index,
@@ -1058,6 +1106,10 @@ impl Configuration {
         if conf.additional_maintained_transaction_index_epoch_count != Some(0) {
             conf.persist_tx_index = true;
         }
+        // The transactions of the address index are looked up by their hashes.
+        if conf.persist_address_tx_index {
+            conf.persist_tx_index = true;
+        }
         conf
     }
 
@@ -1145,6 +1197,11 @@ impl Configuration {
                 .raw_conf
                 .max_estimation_gas_limit
                 .map(U256::from),
+            admin_chain_data_dir: self
+                .raw_conf
+                .admin_chain_data_dir
+                .as_ref()
+                .map(PathBuf::from),
         }
     }
 
@@ -1186,6 +1243,23 @@ impl Configuration {
         )
     }
 
+    pub fn graphql_config(&self) -> HttpConfiguration {
+        HttpConfiguration::new(
+            None,
+            self.raw_conf.jsonrpc_graphql_port,
+            self.raw_conf.jsonrpc_cors.clone(),
+            self.raw_conf.jsonrpc_http_keep_alive,
+            self.raw_conf.jsonrpc_http_threads,
+        )
+    }
+
+    pub fn eth_ipc_config(&self) -> IpcConfiguration {
+        IpcConfiguration::new(
+            self.raw_conf.jsonrpc_ipc_path.clone(),
+            self.raw_conf.jsonrpc_ipc_permissions,
+        )
+    }
+
     pub fn local_tcp_config(&self) -> TcpConfiguration {
         TcpConfiguration::new(
             Some((127, 0, 0, 1)),
@@ -1214,6 +1288,22 @@ impl Configuration {
         builder
     }
 
+    pub fn ipc_server_builder(&self) -> IpcServerBuilder {
+        IpcServerBuilder::default()
+            .max_request_body_size(self.raw_conf.jsonrpc_max_request_body_size)
+            .max_response_body_size(
+                self.raw_conf.jsonrpc_max_response_body_size,
+            )
+            .max_connections(self.raw_conf.jsonrpc_max_connections)
+            .max_subscriptions_per_connection(
+                self.raw_conf.jsonrpc_max_subscriptions_per_connection,
+            )
+            .set_message_buffer_capacity(
+                self.raw_conf.jsonrpc_message_buffer_capacity,
+            )
+            .permissions(self.raw_conf.jsonrpc_ipc_permissions)
+    }
+
     pub fn local_ws_config(&self) -> WsConfiguration {
         WsConfiguration::new(
             Some((127, 0, 0, 1)),
@@ -1230,10 +1320,28 @@ impl Configuration {
         )
     }
 
-    pub fn execution_config(&self) -> ConsensusExecutionConfiguration {
-        ConsensusExecutionConfiguration {
+    pub fn execution_config(
+        &self,
+    ) -> Result<ConsensusExecutionConfiguration, String> {
+        let parallel_executor = match self.raw_conf.parallel_execution_threads
+        {
+            0 => None,
+            threads => Some(Arc::new(
+                ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|e| {
+                        format!(
+                            "Failed to build the parallel execution thread pool: {}",
+                            e
+                        )
+                    })?,
+            )),
+        };
+        Ok(ConsensusExecutionConfiguration {
             executive_trace: self.raw_conf.executive_trace,
-        }
+            parallel_executor,
+        })
     }
 
     pub fn discovery_protocol(&self) -> DiscoveryConfiguration {
@@ -1346,6 +1454,8 @@ impl Configuration {
 
         params.params_dao_vote_period = self.raw_conf.params_dao_vote_period;
 
+        params.custom_precompiles = self.raw_conf.custom_precompiles.clone();
+
         self.set_cips(&mut params);
 
         params
@@ -1571,6 +1681,15 @@ impl Configuration {
         if let Some(x) = self.raw_conf.cip166_transition_height {
             params.transition_heights.cip166 = x;
         }
+        set_conf!(
+            self.raw_conf.osaka_transition_height.unwrap_or(default_transition_time);
+            params.transition_heights => { eip7823, eip7825, eip7883, eip7951 }
+        );
+
+        params.transition_heights.internal_contract_plugins = self
+            .raw_conf
+            .internal_contract_plugins_transition_height
+            .unwrap_or(default_transition_time);
     }
 }
 
@@ -1598,6 +1717,72 @@ pub fn to_bootnodes(bootnodes: &Option<String>) -> Result<Vec<String>, String> {
     }
 }
 
+/// Parses the custom precompiles in the genesis config. The implementation and
+/// the pricing of each precompile are registered by the embedding binary, and
+/// the config only enables them, in the following format.
+///
+/// ```toml
+/// custom_precompiles = """
+/// [[precompile]]
+/// # The name registered in `Configuration::precompile_plugins`.
+/// name = "poseidon"
+/// address = "0x0000000000000000000000000000000000001000"
+/// # The block number from which the precompile is enabled.
+/// activate_at = 0
+/// """
+/// ```
+fn parse_custom_precompiles(
+    content: &str,
+) -> Result<Vec<CustomPrecompileParams>, String> {
+    let value = content
+        .parse::<toml::Value>()
+        .map_err(|e| format!("failed to parse custom_precompiles: {:?}", e))?;
+
+    let entries = match value.get("precompile") {
+        Some(toml::Value::Array(entries)) => entries.as_slice(),
+        Some(_) => return Err("precompile should be an array of tables".into()),
+        None => &[],
+    };
+
+    let mut precompiles: Vec<CustomPrecompileParams> = vec![];
+    for entry in entries {
+        let get_str = |key: &str| {
+            entry.get(key).and_then(toml::Value::as_str).ok_or_else(|| {
+                format!("precompile requires string field {}", key)
+            })
+        };
+        let get_u64 = |key: &str| {
+            entry
+                .get(key)
+                .and_then(toml::Value::as_integer)
+                .and_then(|x| u64::try_from(x).ok())
+                .ok_or_else(|| {
+                    format!("precompile requires integer field {}", key)
+                })
+        };
+
+        let name = get_str("name")?.to_string();
+        let address: Address = parse_hex_string(get_str("address")?)
+            .map_err(|e| format!("invalid address of {}: {:?}", name, e))?;
+        if !address.is_builtin_address() {
+            return Err(format!(
+                "address of {} should be a builtin address starting with 0x0",
+                name
+            ));
+        }
+        if precompiles.iter().any(|p| p.address == address) {
+            return Err(format!("duplicated precompile address {:?}", address));
+        }
+
+        precompiles.push(CustomPrecompileParams {
+            name,
+            address,
+            activate_at: get_u64("activate_at")?,
+        });
+    }
+    Ok(precompiles)
+}
+
 pub fn parse_config_address_string(
     addr: &str, network: &Network,
 ) -> Result<Address, String> {
@@ -1633,7 +1818,11 @@ pub fn parse_config_address_string(
 mod tests {
     use cfx_addr::Network;
 
-    use crate::configuration::parse_config_address_string;
+    use crate::configuration::{
+        parse_config_address_string, parse_custom_precompiles,
+    };
+    use cfx_types::Address;
+    use std::str::FromStr;
 
     #[test]
     fn test_config_address_string() {
@@ -1670,4 +1859,61 @@ mod tests {
             .unwrap()
         );
     }
+
+    #[test]
+    fn test_custom_precompiles() {
+        let precompiles = parse_custom_precompiles(
+            r#"
+            [[precompile]]
+            name = "poseidon"
+            address = "0x0000000000000000000000000000000000001000"
+            activate_at = 10
+            "#,
+        )
+        .unwrap();
+        assert_eq!(precompiles.len(), 1);
+        assert_eq!(precompiles[0].name, "poseidon");
+        assert_eq!(
+            precompiles[0].address,
+            Address::from_str("0000000000000000000000000000000000001000")
+                .unwrap()
+        );
+        assert_eq!(precompiles[0].activate_at, 10);
+
+        // Addresses must be builtin addresses.
+        assert!(parse_custom_precompiles(
+            r#"
+            [[precompile]]
+            name = "poseidon"
+            address = "0x1000000000000000000000000000000000000000"
+            activate_at = 0
+            "#,
+        )
+        .is_err());
+        // Duplicated addresses.
+        assert!(parse_custom_precompiles(
+            r#"
+            [[precompile]]
+            name = "poseidon"
+            address = "0x0000000000000000000000000000000000001000"
+            activate_at = 0
+
+            [[precompile]]
+            name = "sm3"
+            address = "0x0000000000000000000000000000000000001000"
+            activate_at = 0
+            "#,
+        )
+        .is_err());
+        // Missing fields.
+        assert!(parse_custom_precompiles(
+            r#"
+            [[precompile]]
+            name = "poseidon"
+            address = "0x0000000000000000000000000000000000001000"
+            "#,
+        )
+        .is_err());
+        assert!(parse_custom_precompiles("").unwrap().is_empty());
+    }
 }
@@ -84,3 +84,21 @@ impl WsConfiguration {
         }
     }
 }
+
+#[derive(Debug, PartialEq)]
+pub struct IpcConfiguration {
+    pub enabled: bool,
+    pub path: String,
+    // The unix file mode of the socket
+    pub permissions: u32,
+}
+
+impl IpcConfiguration {
+    pub fn new(path: Option<String>, permissions: u32) -> Self {
+        IpcConfiguration {
+            enabled: path.is_some(),
+            path: path.unwrap_or_default(),
+            permissions,
+        }
+    }
+}
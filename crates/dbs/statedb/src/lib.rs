@@ -197,6 +197,23 @@ mod impls {
                 .map_err(|err| err.into())
         }
 
+        /// Reads at most `limit` key/value pairs with the prefix in the order
+        /// of their keys, and the key to continue from if there are more. See
+        /// `StateTrait::read_range`.
+        pub fn read_range(
+            &mut self, access_key_prefix: StorageKeyWithSpace,
+            start_key: &[u8], limit: usize, only_account_key: bool,
+        ) -> Result<(Vec<MptKeyValue>, Option<Vec<u8>>)> {
+            self.storage
+                .read_range(
+                    access_key_prefix,
+                    start_key,
+                    limit,
+                    only_account_key,
+                )
+                .map_err(|err| err.into())
+        }
+
         pub fn delete_all<AM: access_mode::AccessMode>(
             &mut self, key_prefix: StorageKeyWithSpace,
             debug_record: Option<&mut ComputeEpochDebugRecord>,
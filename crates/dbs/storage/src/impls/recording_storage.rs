@@ -55,6 +55,7 @@ impl<Storage: StateTrait + StateTraitExt> StateTrait
             fn get_state_root(&self) -> Result<StateRootWithAuxInfo>;
             fn commit(&mut self, epoch_id: EpochId) -> Result<StateRootWithAuxInfo>;
             fn read_all_with_callback(&mut self, access_key_prefix: StorageKeyWithSpace, callback: &mut dyn FnMut(MptKeyValue), only_account_key: bool) -> Result<()>;
+            fn read_range(&mut self, access_key_prefix: StorageKeyWithSpace, start_key: &[u8], limit: usize, only_account_key: bool) -> Result<(Vec<MptKeyValue>, Option<Vec<u8>>)>;
         }
     }
 
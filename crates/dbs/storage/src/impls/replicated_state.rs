@@ -324,6 +324,18 @@ impl<Main: StateTrait> StateTrait for ReplicatedState<Main> {
         )
     }
 
+    fn read_range(
+        &mut self, access_key_prefix: StorageKeyWithSpace, start_key: &[u8],
+        limit: usize, only_account_key: bool,
+    ) -> Result<(Vec<MptKeyValue>, Option<Vec<u8>>)> {
+        self.state.read_range(
+            access_key_prefix,
+            start_key,
+            limit,
+            only_account_key,
+        )
+    }
+
     fn compute_state_root(&mut self) -> Result<StateRootWithAuxInfo> {
         self.replication_handler
             .send_op(StateOperation::ComputeStateRoot);
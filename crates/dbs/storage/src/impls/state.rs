@@ -320,6 +320,18 @@ impl StateTrait for State {
         )
     }
 
+    fn read_range(
+        &mut self, access_key_prefix: StorageKeyWithSpace, start_key: &[u8],
+        limit: usize, only_account_key: bool,
+    ) -> Result<(Vec<MptKeyValue>, Option<Vec<u8>>)> {
+        self.read_range_impl(
+            access_key_prefix,
+            start_key,
+            limit,
+            only_account_key,
+        )
+    }
+
     fn compute_state_root(&mut self) -> Result<StateRootWithAuxInfo> {
         self.ensure_temp_slab_for_db_load();
 
@@ -1105,6 +1117,194 @@ impl State {
 
         Ok(())
     }
+
+    pub fn read_range_impl(
+        &mut self, access_key_prefix: StorageKeyWithSpace, start_key: &[u8],
+        limit: usize, only_account_key: bool,
+    ) -> Result<(Vec<MptKeyValue>, Option<Vec<u8>>)> {
+        self.ensure_temp_slab_for_db_load();
+
+        let space = access_key_prefix.space;
+        let key_prefix = access_key_prefix.to_key_bytes();
+        let upper_bound_excl = to_key_prefix_iter_upper_bound(&key_prefix);
+        let mut cursor = max(key_prefix.as_slice(), start_key).to_vec();
+        // At most `limit + 1` pairs are read from the recent tries, so the
+        // read stops at `recent_end`, the first key not read, if any.
+        let (recent_kvs, recent_end) = self.read_recent_tries(
+            access_key_prefix,
+            &key_prefix,
+            &cursor,
+            limit.saturating_add(1),
+            only_account_key,
+        )?;
+
+        let mut result = Vec::new();
+        let mut kv_iterator = self.snapshot_db.snapshot_kv_iterator()?.take();
+        loop {
+            let mut snapshot_kvs = kv_iterator
+                .iter_range(
+                    cursor.as_slice(),
+                    upper_bound_excl.as_ref().map(|v| &**v),
+                )?
+                .take();
+            let mut snapshot_next = snapshot_kvs.next()?;
+            let mut recent_next = recent_kvs
+                .range::<[u8], _>((Included(cursor.as_slice()), Unbounded))
+                .peekable();
+
+            // Merge the sorted key/value pairs of the snapshot and the recent
+            // tries, until a key to skip is found when only the accounts of
+            // the space are requested.
+            let seek_key = loop {
+                let (take_recent, overridden) =
+                    match (&snapshot_next, recent_next.peek()) {
+                        (None, None) => return Ok((result, recent_end)),
+                        (None, Some(_)) => (true, false),
+                        (Some(_), None) => (false, false),
+                        (Some((k, _)), Some((recent_k, _))) => {
+                            (*recent_k <= k, *recent_k == k)
+                        }
+                    };
+                if overridden {
+                    // The value in the recent tries is the latest.
+                    snapshot_next = snapshot_kvs.next()?;
+                }
+                let (k, v) = if take_recent {
+                    let (k, v) = recent_next.next().unwrap();
+                    (k.clone(), v.clone())
+                } else {
+                    let kv = snapshot_next.take().unwrap();
+                    snapshot_next = snapshot_kvs.next()?;
+                    kv
+                };
+
+                // Empty values are tombstones of the deleted keys.
+                if v.len() == 0 {
+                    continue;
+                }
+                if only_account_key && !is_account_key_bytes(&k, space) {
+                    match next_account_key_bytes(&k, space) {
+                        Some(seek_key) => break seek_key,
+                        None => return Ok((result, None)),
+                    }
+                }
+                if let Some(recent_end) =
+                    recent_end.as_ref().filter(|recent_end| k >= **recent_end)
+                {
+                    return Ok((result, Some(recent_end.clone())));
+                }
+                if result.len() == limit {
+                    return Ok((result, Some(k)));
+                }
+                result.push((k, v));
+            };
+            cursor = seek_key;
+        }
+    }
+
+    /// Reads the first `max_count` key/value pairs with the prefix from the
+    /// delta trie and the intermediate trie, starting from `start_key`. The
+    /// values of the deleted keys are empty. Returns the pairs sorted by
+    /// their keys and, if there are more, the first key not returned.
+    ///
+    /// The keys in the tries are padded, so they are not in the order of the
+    /// keys and the tries are visited from the prefix instead of seeking to
+    /// `start_key`. Only `max_count` pairs are kept while visiting.
+    fn read_recent_tries(
+        &mut self, access_key_prefix: StorageKeyWithSpace, key_prefix: &[u8],
+        start_key: &[u8], max_count: usize, only_account_key: bool,
+    ) -> Result<(BTreeMap<Vec<u8>, Box<[u8]>>, Option<Vec<u8>>)> {
+        let space = access_key_prefix.space;
+        let mut recent_kvs = BTreeMap::new();
+        let mut recent_end: Option<Vec<u8>> = None;
+        let mut inner_callback = |(k, v): MptKeyValue| {
+            let k = StorageKeyWithSpace::from_delta_mpt_key(&k).to_key_bytes();
+            // The delta trie does not support address prefix key search.
+            if !k.starts_with(key_prefix)
+                || k.as_slice() < start_key
+                || only_account_key && !is_account_key_bytes(&k, space)
+            {
+                return;
+            }
+            // The keys after `recent_end` are dropped, including the ones of
+            // the intermediate trie which the dropped ones override.
+            if recent_end.as_ref().map_or(false, |end| k >= *end) {
+                return;
+            }
+            // The delta trie is visited first and overrides the intermediate
+            // trie.
+            recent_kvs.entry(k).or_insert(v);
+            if recent_kvs.len() > max_count {
+                recent_end = recent_kvs.pop_last().map(|(k, _)| k);
+            }
+        };
+
+        if let Some(root_node) = &self.delta_trie_root {
+            let delta_mpt_key_prefix = access_key_prefix
+                .to_delta_mpt_key_bytes(&self.delta_trie_key_padding);
+            SubTrieVisitor::new(
+                &self.delta_trie,
+                root_node.clone(),
+                &mut self.owned_node_set,
+            )?
+            .traversal_with_callback(
+                &delta_mpt_key_prefix,
+                &delta_mpt_key_prefix,
+                &mut inner_callback,
+                true,
+                only_account_key,
+            )?;
+        }
+
+        if let (Some(root_node), Some(trie), Some(key_padding)) = (
+            &self.intermediate_trie_root,
+            &self.maybe_intermediate_trie,
+            &self.maybe_intermediate_trie_key_padding,
+        ) {
+            let intermediate_mpt_key_prefix =
+                access_key_prefix.to_delta_mpt_key_bytes(key_padding);
+            SubTrieVisitor::new(
+                trie,
+                root_node.clone(),
+                &mut self.owned_node_set,
+            )?
+            .traversal_with_callback(
+                &intermediate_mpt_key_prefix,
+                &intermediate_mpt_key_prefix,
+                &mut inner_callback,
+                true,
+                only_account_key,
+            )?;
+        }
+
+        Ok((recent_kvs, recent_end))
+    }
+}
+
+/// Account keys are the address, followed by the space type in eSpace.
+fn is_account_key_bytes(key: &[u8], space: Space) -> bool {
+    let account_bytes = StorageKeyWithSpace::ACCOUNT_BYTES;
+    match space {
+        Space::Native => key.len() == account_bytes,
+        Space::Ethereum => {
+            key.len() == account_bytes + 1
+                && key[account_bytes..] == *StorageKeyWithSpace::EVM_SPACE_TYPE
+        }
+    }
+}
+
+/// The smallest key after `key` which may be an account key of the space. The
+/// Core Space keys of an address sort before its eSpace account key, which
+/// sorts before the other eSpace keys of the address.
+fn next_account_key_bytes(key: &[u8], space: Space) -> Option<Vec<u8>> {
+    let address_bytes = &key[..StorageKeyWithSpace::ACCOUNT_BYTES];
+    let espace_account_key =
+        [address_bytes, StorageKeyWithSpace::EVM_SPACE_TYPE].concat();
+    if space == Space::Ethereum && key < espace_account_key.as_slice() {
+        Some(espace_account_key)
+    } else {
+        to_key_prefix_iter_upper_bound(address_bytes)
+    }
 }
 
 use crate::{
@@ -1125,7 +1325,7 @@ use crate::{
     StorageRootProof,
 };
 use cfx_internal_common::{StateRootAuxInfo, StateRootWithAuxInfo};
-use cfx_types::AddressWithSpace;
+use cfx_types::{AddressWithSpace, Space};
 use fallible_iterator::FallibleIterator;
 use primitives::{
     DeltaMptKeyPadding, EpochId, MerkleHash, MptValue, NodeMerkleTriplet,
@@ -1135,6 +1335,8 @@ use primitives::{
 use rustc_hex::ToHex;
 use std::{
     cell::UnsafeCell,
+    cmp::max,
     collections::{BTreeMap, HashSet},
+    ops::Bound::{Included, Unbounded},
     sync::{atomic::Ordering, Arc},
 };
@@ -46,6 +46,18 @@ pub trait StateTrait: Sync + Send {
         Err(Error::Msg("Not implemented".into()))
     }
 
+    /// Reads at most `limit` key/value pairs with `access_key_prefix` as
+    /// prefix in the order of their keys, starting from `start_key`. Returns
+    /// the pairs and the key to start the next read from if there are more.
+    /// With `only_account_key` set, only the accounts of the space of the
+    /// prefix are read.
+    fn read_range(
+        &mut self, _access_key_prefix: StorageKeyWithSpace, _start_key: &[u8],
+        _limit: usize, _only_account_key: bool,
+    ) -> Result<(Vec<MptKeyValue>, Option<Vec<u8>>)> {
+        Err(Error::Msg("Not implemented".into()))
+    }
+
     // Finalize
     /// It's costly to compute state root however it's only necessary to compute
     /// state root once before committing.
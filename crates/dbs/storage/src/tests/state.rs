@@ -567,6 +567,151 @@ fn test_set_delete_all() {
     assert_eq!(state_root, empty_state_root);
 }
 
+#[test]
+fn test_read_range() {
+    let state_manager = new_state_manager_for_unit_test();
+    let mut state = state_manager.get_state_for_genesis_write();
+
+    let addresses: Vec<Address> =
+        (1..=5).map(Address::from_low_u64_be).collect();
+    let slots: Vec<H256> = (1..=5).map(H256::from_low_u64_be).collect();
+    for address in &addresses {
+        state
+            .set(
+                StorageKey::new_account_key(address).with_native_space(),
+                vec![1].into(),
+            )
+            .unwrap();
+        state
+            .set(
+                StorageKey::new_account_key(address).with_evm_space(),
+                vec![2].into(),
+            )
+            .unwrap();
+        for slot in &slots {
+            state
+                .set(
+                    StorageKey::new_storage_key(address, slot.as_ref())
+                        .with_evm_space(),
+                    vec![3].into(),
+                )
+                .unwrap();
+        }
+    }
+    let mut epoch_id = H256::default();
+    epoch_id.as_bytes_mut()[0] = 1;
+    state.compute_state_root().unwrap();
+    state.commit(epoch_id).unwrap();
+
+    let mut state = state_manager
+        .get_state_for_next_epoch(
+            StateIndex::new_for_test_only_delta_mpt(&epoch_id),
+            false,
+        )
+        .unwrap()
+        .unwrap();
+    state
+        .delete(
+            StorageKey::new_storage_key(&addresses[0], slots[1].as_ref())
+                .with_evm_space(),
+        )
+        .unwrap();
+    state
+        .delete(StorageKey::new_account_key(&addresses[2]).with_evm_space())
+        .unwrap();
+
+    // Read the storage of an account in pages of two slots.
+    let mut read_slots = vec![];
+    let mut start_key = vec![];
+    loop {
+        let (kvs, next_key) = state
+            .read_range(
+                StorageKey::new_storage_root_key(&addresses[0])
+                    .with_evm_space(),
+                &start_key,
+                2,
+                false,
+            )
+            .unwrap();
+        assert!(kvs.len() <= 2);
+        for (key, _) in kvs {
+            match StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(&key)
+                .key
+            {
+                StorageKey::StorageKey { storage_key, .. } => {
+                    read_slots.push(H256::from_slice(storage_key))
+                }
+                key => panic!("unexpected key {:?}", key),
+            }
+        }
+        match next_key {
+            Some(next_key) => start_key = next_key,
+            None => break,
+        }
+    }
+    assert_eq!(read_slots, vec![slots[0], slots[2], slots[3], slots[4]]);
+
+    // Read the eSpace accounts, skipping the storage and Core Space.
+    let (kvs, next_key) = state
+        .read_range(StorageKey::EmptyKey.with_evm_space(), &[], 3, true)
+        .unwrap();
+    let accounts: Vec<_> = kvs
+        .iter()
+        .map(|(key, _)| {
+            let key =
+                StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(key);
+            assert_eq!(key.space, Space::Ethereum);
+            match key.key {
+                StorageKey::AccountKey(address) => Address::from_slice(address),
+                key => panic!("unexpected key {:?}", key),
+            }
+        })
+        .collect();
+    assert_eq!(accounts, vec![addresses[0], addresses[1], addresses[3]]);
+    assert_eq!(
+        next_key,
+        Some(
+            StorageKey::new_account_key(&addresses[4])
+                .with_evm_space()
+                .to_key_bytes()
+        )
+    );
+
+    // Only a page and one more key are kept from the recent tries, the pages
+    // still cover all the accounts.
+    let mut accounts = vec![];
+    let mut start_key = vec![];
+    loop {
+        let (kvs, next_key) = state
+            .read_range(
+                StorageKey::EmptyKey.with_evm_space(),
+                &start_key,
+                1,
+                true,
+            )
+            .unwrap();
+        assert!(kvs.len() <= 1);
+        for (key, _) in kvs {
+            match StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(&key)
+                .key
+            {
+                StorageKey::AccountKey(address) => {
+                    accounts.push(Address::from_slice(address))
+                }
+                key => panic!("unexpected key {:?}", key),
+            }
+        }
+        match next_key {
+            Some(next_key) => start_key = next_key,
+            None => break,
+        }
+    }
+    assert_eq!(
+        accounts,
+        vec![addresses[0], addresses[1], addresses[3], addresses[4]]
+    );
+}
+
 #[test]
 fn test_set_order() {
     let mut rng = get_rng_for_test();
@@ -754,9 +899,9 @@ use crate::{
     StateRootWithAuxInfo,
 };
 use cfx_types::{
-    address_util::AddressUtil, Address, AddressSpaceUtil, H256, U256,
+    address_util::AddressUtil, Address, AddressSpaceUtil, Space, H256, U256,
 };
-use primitives::{Account, StorageKey, StorageKeyWithSpace};
+use primitives::{Account, SkipInputCheck, StorageKey, StorageKeyWithSpace};
 use rand::{
     distr::{Distribution, Uniform},
     seq::SliceRandom,
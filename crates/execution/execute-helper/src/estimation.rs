@@ -485,6 +485,12 @@ impl EstimateRequest {
         }
     }
 
+    /// The settings to execute the transaction of a virtual call once, e.g.,
+    /// in `trace_call`, with the checks of `eth_call`.
+    pub fn virtual_call_settings(self) -> TransactSettings {
+        self.transact_settings(ChargeCollateral::EstimateSender)
+    }
+
     fn first_pass_options(self) -> TransactOptions<Observer> {
         TransactOptions {
             observer: Observer::virtual_call(),
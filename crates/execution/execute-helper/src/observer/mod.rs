@@ -1,9 +1,11 @@
 pub mod exec_tracer;
 pub mod gasman;
 mod utils;
+pub mod vm_tracer;
 
 use exec_tracer::ExecTracer;
 use gasman::GasMan;
+use vm_tracer::VmTracer;
 
 use cfx_executor::{
     executive_observer::{AsTracer, DrainTrace, TracerTrait},
@@ -20,6 +22,7 @@ pub struct Observer {
     pub tracer: Option<ExecTracer>, // parity tracer
     pub gas_man: Option<GasMan>,
     pub geth_tracer: Option<GethTracer>,
+    pub vm_tracer: Option<VmTracer>, // parity vmTrace
 }
 
 impl Observer {
@@ -28,6 +31,7 @@ impl Observer {
             tracer: Some(ExecTracer::default()),
             gas_man: None,
             geth_tracer: None,
+            vm_tracer: None,
         }
     }
 
@@ -36,6 +40,7 @@ impl Observer {
             tracer: None,
             gas_man: None,
             geth_tracer: None,
+            vm_tracer: None,
         }
     }
 
@@ -44,6 +49,7 @@ impl Observer {
             tracer: Some(ExecTracer::default()),
             gas_man: Some(GasMan::default()),
             geth_tracer: None,
+            vm_tracer: None,
         }
     }
 
@@ -55,6 +61,7 @@ impl Observer {
             tracer: None,
             gas_man: None,
             geth_tracer: Some(GethTracer::new(tx_exec_context, machine, opts)),
+            vm_tracer: None,
         }
     }
 }
@@ -0,0 +1,310 @@
+use cfx_executor::{
+    observer::{
+        CallTracer, CheckpointTracer, DrainTrace, InternalTransferTracer,
+        OpcodeTracer, SetAuthTracer, StorageTracer,
+    },
+    stack::{FrameResult, FrameReturn},
+};
+use cfx_parity_trace_types::{
+    MemoryDiff, StorageDiff, VmExecutedOperation, VmOperation, VmTrace,
+};
+use cfx_types::{BigEndianHash, H256, U256};
+use cfx_vm_interpreter::instructions::{self, Instruction};
+use cfx_vm_types::{ActionParams, InterpreterInfo, Spec};
+use typemap::ShareDebugMap;
+
+/// Records the instruction-level trace of a transaction, as parity's
+/// `vmTrace`.
+///
+/// Frames without bytecode (e.g., the internal contracts) are replaced by the
+/// frame they create, so the trace of a cross-space call is nested in the
+/// `CALL` instruction of the Core Space contract.
+pub struct VmTracer {
+    spec: Spec,
+    frames: Vec<Frame>,
+    pending_step: Option<PendingStep>,
+    trace: Option<VmTrace>,
+}
+
+struct Frame {
+    trace: VmTrace,
+    /// The call or create instruction waiting for its sub frame.
+    pending_sub: Option<PendingSub>,
+}
+
+/// An instruction which has started but not ended.
+struct PendingStep {
+    instruction: Option<Instruction>,
+    gas: U256,
+    stack_len: usize,
+    mem_written: Option<(usize, usize)>,
+    store_written: Option<StorageDiff>,
+}
+
+struct PendingSub {
+    is_create: bool,
+    gas: U256,
+    gas_left: U256,
+    mem_written: Option<(usize, usize)>,
+}
+
+impl VmTracer {
+    pub fn new(spec: Spec) -> Self {
+        VmTracer {
+            spec,
+            frames: vec![],
+            pending_step: None,
+            trace: None,
+        }
+    }
+
+    pub fn drain(self) -> Option<VmTrace> { self.trace }
+
+    fn instruction_info(
+        &self, instruction: Instruction,
+    ) -> &'static instructions::InstructionInfo {
+        let cip645 = self.spec.cip645.opcode_update;
+        if self.spec.cancun_opcodes {
+            instruction.info::<true>(cip645, self.spec.eip7939)
+        } else {
+            instruction.info::<false>(cip645, self.spec.eip7939)
+        }
+    }
+
+    fn enter_frame(&mut self, params: &ActionParams) {
+        let code = params.code.as_ref().map_or(vec![], |c| (**c).clone());
+        self.frames.push(Frame {
+            trace: VmTrace { code, ops: vec![] },
+            pending_sub: None,
+        });
+    }
+
+    fn exit_frame(&mut self, result: &FrameResult) {
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        let Some(parent) = self.frames.last_mut() else {
+            self.trace = Some(frame.trace);
+            return;
+        };
+
+        let Some(sub) = parent.pending_sub.take() else {
+            if parent.trace.ops.is_empty() {
+                parent.trace = frame.trace;
+            }
+            return;
+        };
+        let Some(op) = parent.trace.ops.last_mut() else {
+            return;
+        };
+
+        let (gas_left, success, return_data, create_address) = match result {
+            Ok(FrameReturn {
+                gas_left,
+                apply_state,
+                return_data,
+                create_address,
+                ..
+            }) => (*gas_left, *apply_state, &return_data[..], *create_address),
+            Err(_) => (U256::zero(), false, &[][..], None),
+        };
+        let gas_after = sub.gas_left + gas_left;
+
+        let stack_push = if sub.is_create {
+            match create_address {
+                Some(address) if success => {
+                    vec![H256::from(address).into_uint()]
+                }
+                _ => vec![U256::zero()],
+            }
+        } else {
+            vec![U256::from(success as u8)]
+        };
+        // The return data is copied into the memory of the caller.
+        let mem_diff = sub.mem_written.and_then(|(offset, size)| {
+            let size = size.min(return_data.len());
+            (size > 0).then(|| MemoryDiff {
+                offset,
+                data: return_data[..size].to_vec(),
+            })
+        });
+
+        op.cost = sub.gas.saturating_sub(gas_after).low_u64();
+        op.executed = Some(VmExecutedOperation {
+            gas_used: gas_after.low_u64(),
+            stack_push,
+            mem_diff,
+            store_diff: None,
+        });
+        op.sub = Some(frame.trace);
+    }
+}
+
+/// Returns the memory range written by the instruction, reading the
+/// arguments from the stack before the instruction.
+fn mem_written(
+    instruction: Instruction, stack: &[U256], cancun: bool,
+) -> Option<(usize, usize)> {
+    let read = |pos: usize| {
+        stack
+            .len()
+            .checked_sub(pos + 1)
+            .map_or(0, |idx| stack[idx].low_u64() as usize)
+    };
+    match instruction {
+        instructions::MSTORE => Some((read(0), 32)),
+        instructions::MSTORE8 => Some((read(0), 1)),
+        instructions::CALLDATACOPY
+        | instructions::CODECOPY
+        | instructions::RETURNDATACOPY => Some((read(0), read(2))),
+        instructions::JUMPSUB_MCOPY if cancun => Some((read(0), read(2))),
+        instructions::EXTCODECOPY => Some((read(1), read(3))),
+        instructions::CALL | instructions::CALLCODE => Some((read(5), read(6))),
+        instructions::DELEGATECALL | instructions::STATICCALL => {
+            Some((read(4), read(5)))
+        }
+        _ => None,
+    }
+}
+
+fn is_sub_frame_instruction(instruction: Instruction) -> bool {
+    matches!(
+        instruction,
+        instructions::CALL
+            | instructions::CALLCODE
+            | instructions::DELEGATECALL
+            | instructions::STATICCALL
+            | instructions::CREATE
+            | instructions::CREATE2
+    )
+}
+
+impl DrainTrace for VmTracer {
+    fn drain_trace(self, map: &mut ShareDebugMap) {
+        if let Some(trace) = self.drain() {
+            map.insert::<VmTraceKey>(trace);
+        }
+    }
+}
+
+pub struct VmTraceKey;
+
+impl typemap::Key for VmTraceKey {
+    type Value = VmTrace;
+}
+
+impl CallTracer for VmTracer {
+    fn record_call(&mut self, params: &ActionParams) {
+        self.enter_frame(params);
+    }
+
+    fn record_call_result(&mut self, result: &FrameResult) {
+        self.exit_frame(result);
+    }
+
+    fn record_create(&mut self, params: &ActionParams) {
+        self.enter_frame(params);
+    }
+
+    fn record_create_result(&mut self, result: &FrameResult) {
+        self.exit_frame(result);
+    }
+}
+
+impl OpcodeTracer for VmTracer {
+    fn do_trace_opcode(&self, enabled: &mut bool) { *enabled |= true; }
+
+    fn step(&mut self, interp: &dyn InterpreterInfo) {
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        let instruction =
+            Instruction::from_u8_versioned(interp.current_opcode(), &self.spec);
+        let stack = interp.stack();
+
+        frame.trace.ops.push(VmOperation {
+            pc: interp.program_counter() as usize,
+            cost: 0,
+            executed: None,
+            sub: None,
+        });
+        self.pending_step = Some(PendingStep {
+            instruction,
+            gas: interp.gas_remainning(),
+            stack_len: stack.len(),
+            mem_written: instruction
+                .and_then(|i| mem_written(i, stack, self.spec.cancun_opcodes)),
+            store_written: match instruction {
+                Some(instructions::SSTORE) if stack.len() >= 2 => {
+                    Some(StorageDiff {
+                        location: stack[stack.len() - 1],
+                        value: stack[stack.len() - 2],
+                    })
+                }
+                _ => None,
+            },
+        });
+    }
+
+    fn step_end(&mut self, interp: &dyn InterpreterInfo) {
+        let Some(step) = self.pending_step.take() else {
+            return;
+        };
+        let Some(instruction) = step.instruction else {
+            return;
+        };
+        let info = self.instruction_info(instruction);
+        let Some(frame) = self.frames.last_mut() else {
+            return;
+        };
+        let Some(op) = frame.trace.ops.last_mut() else {
+            return;
+        };
+
+        let gas = interp.gas_remainning();
+        op.cost = step.gas.saturating_sub(gas).low_u64();
+
+        let stack = interp.stack();
+        let Some(popped_len) = step.stack_len.checked_sub(info.args) else {
+            return;
+        };
+        if is_sub_frame_instruction(instruction) && stack.len() == popped_len {
+            // The instruction is suspended for the sub frame, its effects
+            // are filled when the sub frame returns.
+            frame.pending_sub = Some(PendingSub {
+                is_create: matches!(
+                    instruction,
+                    instructions::CREATE | instructions::CREATE2
+                ),
+                gas: step.gas,
+                gas_left: gas,
+                mem_written: step.mem_written,
+            });
+            return;
+        }
+        if stack.len() != popped_len + info.ret {
+            // The instruction fails.
+            return;
+        }
+
+        let mem = interp.mem();
+        let mem_diff = step.mem_written.and_then(|(offset, size)| {
+            let end = offset.checked_add(size)?.min(mem.len());
+            (offset < end).then(|| MemoryDiff {
+                offset,
+                data: mem[offset..end].to_vec(),
+            })
+        });
+        op.executed = Some(VmExecutedOperation {
+            gas_used: gas.low_u64(),
+            stack_push: stack[popped_len..].to_vec(),
+            mem_diff,
+            store_diff: step.store_written,
+        });
+    }
+}
+
+impl CheckpointTracer for VmTracer {}
+impl InternalTransferTracer for VmTracer {}
+impl StorageTracer for VmTracer {}
+impl SetAuthTracer for VmTracer {}
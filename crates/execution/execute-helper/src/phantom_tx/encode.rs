@@ -0,0 +1,103 @@
+use cfx_types::Space;
+use primitives::{receipt::EVM_SPACE_SUCCESS, Receipt, SignedTransaction};
+use rlp::{Encodable, RlpStream};
+
+/// Encodes a transaction of a phantom block in the EIP-2718 format. Phantom
+/// transactions are encoded as the EIP-155 transactions they are converted
+/// to.
+pub fn encode_eth_transaction(tx: &SignedTransaction) -> Vec<u8> {
+    tx.transaction.transaction.rlp_bytes()
+}
+
+/// Encodes the receipt of a transaction of a phantom block in the EIP-2718
+/// format, i.e. `rlp([status, cumulativeGasUsed, logsBloom, logs])` prefixed
+/// with the type of a typed transaction.
+pub fn encode_eth_receipt(
+    tx: &SignedTransaction, receipt: &Receipt,
+) -> Vec<u8> {
+    let success =
+        receipt.outcome_status.in_space(Space::Ethereum) == EVM_SPACE_SUCCESS;
+    let logs: Vec<_> = receipt
+        .logs
+        .iter()
+        .filter(|log| log.space == Space::Ethereum)
+        .collect();
+
+    let mut s = RlpStream::new();
+    if tx.is_2718() {
+        s.append_raw(&[tx.type_id()], 0);
+    }
+    s.begin_list(4);
+    s.append(&(success as u8));
+    s.append(&receipt.accumulated_gas_used);
+    s.append(&receipt.log_bloom);
+    s.begin_list(logs.len());
+    for log in logs {
+        s.begin_list(3);
+        s.append(&log.address);
+        s.append_list(&log.topics);
+        s.append(&log.data);
+    }
+    s.out()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_eth_receipt;
+    use cfx_types::{AddressWithSpace, Bloom, Space, H256, U256};
+    use hex_literal::hex;
+    use primitives::{
+        transaction::{
+            Eip1559Transaction, Eip155Transaction, EthereumTransaction,
+        },
+        LogEntry, Receipt, TransactionStatus,
+    };
+
+    fn receipt(outcome: TransactionStatus) -> Receipt {
+        let log = |space| LogEntry {
+            address: hex!("0000000000000000000000000000000000000011").into(),
+            topics: vec![
+                H256::from_low_u64_be(0xdead),
+                H256::from_low_u64_be(0xbeef),
+            ],
+            data: hex!("0100ff").to_vec(),
+            space,
+        };
+        Receipt::new(
+            outcome,
+            U256::one(),
+            U256::zero(),
+            false,
+            // The logs of the Conflux space are not part of the encoding.
+            vec![log(Space::Native), log(Space::Ethereum)],
+            Bloom::zero(),
+            false,
+            vec![],
+            vec![],
+            None,
+        )
+    }
+
+    #[test]
+    fn test_encode_legacy_receipt() {
+        // The test vector of EIP-2481.
+        let tx = EthereumTransaction::Eip155(Eip155Transaction::default())
+            .fake_sign_rpc(AddressWithSpace::default());
+        assert_eq!(
+            encode_eth_receipt(&tx, &receipt(TransactionStatus::Failure)),
+            hex!("f901668001b9010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f85ff85d940000000000000000000000000000000000000011f842a0000000000000000000000000000000000000000000000000000000000000deada0000000000000000000000000000000000000000000000000000000000000beef830100ff")
+        );
+    }
+
+    #[test]
+    fn test_encode_typed_receipt() {
+        // The receipt of a typed transaction is `type || rlp(receipt)`, as
+        // encoded by geth and reth.
+        let tx = EthereumTransaction::Eip1559(Eip1559Transaction::default())
+            .fake_sign_rpc(AddressWithSpace::default());
+        assert_eq!(
+            encode_eth_receipt(&tx, &receipt(TransactionStatus::Success)),
+            hex!("02f901660101b9010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f85ff85d940000000000000000000000000000000000000011f842a0000000000000000000000000000000000000000000000000000000000000deada0000000000000000000000000000000000000000000000000000000000000beef830100ff")
+        );
+    }
+}
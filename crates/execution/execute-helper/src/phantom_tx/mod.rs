@@ -1,3 +1,4 @@
+mod encode;
 mod recover;
 
 use cfx_types::{Address, AddressSpaceUtil, Bloom, Space, U256};
@@ -6,6 +7,7 @@ use primitives::{
     SignedTransaction, TransactionStatus,
 };
 
+pub use encode::{encode_eth_receipt, encode_eth_transaction};
 pub use recover::{build_bloom_and_recover_phantom, recover_phantom};
 
 #[derive(Clone, Debug, Default)]
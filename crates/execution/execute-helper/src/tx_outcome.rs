@@ -1,6 +1,8 @@
+use cfx_bytes::Bytes;
 use cfx_executor::{
     executive::ExecutionOutcome, internal_contract::make_staking_events,
 };
+use cfx_parity_trace_types::{StateDiff, VmTrace};
 use cfx_types::{H256, U256};
 use cfx_vm_types::Spec;
 use pow_types::StakingEvent;
@@ -10,7 +12,10 @@ use alloy_rpc_types_trace::geth::GethTrace;
 use geth_tracer::GethTraceKey;
 
 use super::{
-    observer::exec_tracer::{ExecTrace, ExecTraceKey},
+    observer::{
+        exec_tracer::{ExecTrace, ExecTraceKey},
+        vm_tracer::VmTraceKey,
+    },
     phantom_tx::{recover_phantom, PhantomTransaction},
 };
 
@@ -21,7 +26,12 @@ pub struct ProcessTxOutcome {
     pub tx_staking_events: Vec<StakingEvent>,
     pub tx_exec_error_msg: String,
     pub consider_repacked: bool,
-    pub geth_trace: Option<GethTrace>,
+    pub geth_trace: Option<Result<GethTrace, String>>,
+    pub vm_trace: Option<VmTrace>,
+    /// Only collected when replaying transactions, filled by the caller since
+    /// it is read from the state before the cache is committed.
+    pub state_diff: Option<StateDiff>,
+    pub output: Bytes,
 }
 
 fn parity_traces(outcome: &ExecutionOutcome) -> Vec<ExecTrace> {
@@ -31,18 +41,30 @@ fn parity_traces(outcome: &ExecutionOutcome) -> Vec<ExecTrace> {
         .unwrap_or_default()
 }
 
-fn geth_traces(outcome: &ExecutionOutcome) -> Option<GethTrace> {
+fn geth_traces(
+    outcome: &ExecutionOutcome,
+) -> Option<Result<GethTrace, String>> {
     outcome
         .try_as_executed()
         .and_then(|executed| executed.ext_result.get::<GethTraceKey>().cloned())
 }
 
+fn vm_trace(outcome: &ExecutionOutcome) -> Option<VmTrace> {
+    outcome
+        .try_as_executed()
+        .and_then(|executed| executed.ext_result.get::<VmTraceKey>().cloned())
+}
+
 pub fn make_process_tx_outcome(
     outcome: ExecutionOutcome, accumulated_gas_used: &mut U256, tx_hash: H256,
     spec: &Spec,
 ) -> ProcessTxOutcome {
     let tx_traces = parity_traces(&outcome);
     let geth_trace = geth_traces(&outcome);
+    let vm_trace = vm_trace(&outcome);
+    let output = outcome
+        .try_as_executed()
+        .map_or(Bytes::new(), |executed| executed.output.clone());
     let tx_exec_error_msg = outcome.error_message();
     let consider_repacked = outcome.consider_repacked();
     let receipt = outcome.make_receipt(accumulated_gas_used, spec);
@@ -59,5 +81,8 @@ pub fn make_process_tx_outcome(
         tx_exec_error_msg,
         consider_repacked,
         geth_trace,
+        vm_trace,
+        state_diff: None,
+        output,
     }
 }
@@ -0,0 +1,66 @@
+//! Precompiles supplied by the binary embedding the executor, for private and
+//! consortium chains. The implementations are registered by name in a
+//! `PrecompilePlugins`, which is passed to the `Machine`, and only enabled at
+//! the addresses configured in `CommonParams::custom_precompiles`.
+
+use super::{Builtin, Precompile, PricePlan};
+use crate::spec::CustomPrecompileParams;
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fmt,
+    sync::Arc,
+};
+
+/// The pricing scheme and the implementation of a custom precompile.
+pub struct CustomPrecompile {
+    pub price_plan: Box<dyn PricePlan>,
+    pub precompile: Box<dyn Precompile>,
+}
+
+/// Creates a fresh instance of a custom precompile for each `Machine`.
+pub type PrecompileFactory = Arc<dyn Fn() -> CustomPrecompile + Send + Sync>;
+
+/// The precompile implementations supplied by the embedding binary.
+#[derive(Clone, Default)]
+pub struct PrecompilePlugins {
+    factories: BTreeMap<String, PrecompileFactory>,
+}
+
+impl PrecompilePlugins {
+    /// Registers a precompile implementation under `name`.
+    pub fn register(
+        &mut self, name: &str, factory: PrecompileFactory,
+    ) -> Result<(), String> {
+        match self.factories.entry(name.to_string()) {
+            Entry::Occupied(_) => {
+                Err(format!("Precompile {} is already registered", name))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(factory);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn builtin(
+        &self, params: &CustomPrecompileParams,
+    ) -> Result<Builtin, String> {
+        let factory = self.factories.get(&params.name).ok_or_else(|| {
+            format!(
+                "Precompile {} configured at {:?} is not registered",
+                params.name, params.address
+            )
+        })?;
+        let CustomPrecompile {
+            price_plan,
+            precompile,
+        } = factory();
+        Ok(Builtin::new(price_plan, precompile, params.activate_at))
+    }
+}
+
+impl fmt::Debug for PrecompilePlugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.factories.keys()).finish()
+    }
+}
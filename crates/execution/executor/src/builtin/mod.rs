@@ -22,14 +22,17 @@
 
 mod blake2f;
 mod bls12_381;
+mod custom;
 mod executable;
 mod interface;
 mod kzg_point_evaluations;
 mod modexp;
+mod p256_verify;
 mod price_plan;
 mod pricer;
 
 pub use bls12_381::build_bls12_builtin_map;
+pub use custom::{CustomPrecompile, PrecompileFactory, PrecompilePlugins};
 pub use executable::BuiltinExec;
 pub use interface::*;
 pub use price_plan::{IfPricer, StaticPlan};
@@ -52,7 +55,7 @@ use blake2f::compress;
 
 use bls12_381::bls12_builtin_factory;
 use modexp::ModexpImpl;
-pub(crate) use modexp::ModexpPricer;
+pub(crate) use modexp::{ModexpPricePlan, ModexpPricer};
 pub(crate) use pricer::{
     AltBn128PairingPricer, Blake2FPricer, ConstPricer, Linear,
 };
@@ -125,6 +128,7 @@ pub fn builtin_factory(name: &str) -> Box<dyn Precompile> {
         }
         "blake2_f" => Box::new(Blake2FImpl) as Box<dyn Precompile>,
         "kzg_point_eval" => Box::new(KzgPointEval) as Box<dyn Precompile>,
+        "p256_verify" => Box::new(P256Verify) as Box<dyn Precompile>,
         "bls12_g1add"
         | "bls12_g1msm"
         | "bls12_g2add"
@@ -180,6 +184,9 @@ struct Blake2FImpl;
 #[allow(dead_code)]
 struct KzgPointEval;
 
+#[derive(Debug)]
+struct P256Verify;
+
 impl Precompile for Identity {
     fn execute(
         &self, input: &[u8], output: &mut BytesRef,
@@ -531,16 +538,37 @@ impl Precompile for KzgPointEval {
         Ok(())
     }
 }
+
+impl Precompile for P256Verify {
+    fn execute(
+        &self, input: &[u8], output: &mut BytesRef,
+    ) -> Result<(), Error> {
+        // An invalid signature returns empty data instead of failing.
+        if p256_verify::verify(input) {
+            let mut result = [0u8; 32];
+            result[31] = 1;
+            output.write(0, &result);
+        }
+        Ok(())
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::{
         builtin_factory, modexp::modexp as me, price_plan::StaticPlan,
-        Blake2FPricer, Builtin, Linear, ModexpPricer,
+        Blake2FPricer, Builtin, CustomPrecompile, Linear, ModexpPricePlan,
+        ModexpPricer, PrecompileFactory, PrecompilePlugins, PricePlan,
+    };
+    use crate::{
+        machine::{Machine, VmFactory},
+        spec::{CommonParams, CustomPrecompileParams},
     };
     use cfx_bytes::BytesRef;
-    use cfx_types::U256;
+    use cfx_types::{Address, AddressSpaceUtil, U256};
+    use cfx_vm_types::Spec;
     use num::{BigUint, One, Zero};
     use rustc_hex::FromHex;
+    use std::sync::Arc;
 
     #[test]
     fn modexp_func() {
@@ -850,6 +878,408 @@ mod tests {
         }
     }
 
+    #[test]
+    fn modexp_osaka() {
+        let plan = ModexpPricePlan::new(
+            ModexpPricer::new_byzantium(20),
+            ModexpPricer::new_berlin(200),
+            ModexpPricer::new_osaka(500),
+        );
+        let mut spec = Spec::genesis_spec();
+        spec.eip7823 = true;
+        spec.eip7883 = true;
+        let cost = |input: &[u8]| plan.pricer(&spec).cost(input);
+
+        // fermat's little theorem example, 16 * 255 iterations.
+        {
+            let input: Vec<u8> = FromHex::from_hex(
+                "\
+				0000000000000000000000000000000000000000000000000000000000000001\
+				0000000000000000000000000000000000000000000000000000000000000020\
+				0000000000000000000000000000000000000000000000000000000000000020\
+				03\
+				fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+				fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            )
+            .unwrap();
+            assert_eq!(cost(&input[..]), 4080.into());
+        }
+
+        // the minimum gas.
+        {
+            let input: Vec<u8> = FromHex::from_hex(
+                "\
+				0000000000000000000000000000000000000000000000000000000000000001\
+				0000000000000000000000000000000000000000000000000000000000000002\
+				0000000000000000000000000000000000000000000000000000000000000020\
+				03\
+				ffff\
+				80",
+            )
+            .unwrap();
+            assert_eq!(cost(&input[..]), 500.into());
+        }
+
+        // long exponent and modulus, 2 * 8 * 8 * (16 * 32) gas.
+        {
+            let input: Vec<u8> = FromHex::from_hex(
+                "\
+				0000000000000000000000000000000000000000000000000000000000000001\
+				0000000000000000000000000000000000000000000000000000000000000040\
+				0000000000000000000000000000000000000000000000000000000000000040",
+            )
+            .unwrap();
+            assert_eq!(cost(&input[..]), 65536.into());
+        }
+
+        // the modulus exceeds the bound of EIP-7823.
+        {
+            let input: Vec<u8> = FromHex::from_hex(
+                "\
+				0000000000000000000000000000000000000000000000000000000000000001\
+				0000000000000000000000000000000000000000000000000000000000000001\
+				0000000000000000000000000000000000000000000000000000000000000401",
+            )
+            .unwrap();
+            assert_eq!(cost(&input[..]), U256::max_value());
+
+            spec.eip7823 = false;
+            let cost = plan.pricer(&spec).cost(&input[..]);
+            assert_eq!(cost, 33282.into());
+        }
+    }
+
+    /// Encodes a modexp input with the given lengths of the base and the
+    /// modulus. Only the lengths and the exponent affect the gas.
+    fn modexp_input(base_len: usize, exp: &[u8], mod_len: usize) -> Vec<u8> {
+        let mut input = Vec::new();
+        for len in [base_len, exp.len(), mod_len] {
+            let mut word = [0u8; 32];
+            U256::from(len).to_big_endian(&mut word);
+            input.extend_from_slice(&word);
+        }
+        input.extend(std::iter::repeat(0xff).take(base_len));
+        input.extend_from_slice(exp);
+        input.extend(std::iter::repeat(0xff).take(mod_len));
+        input
+    }
+
+    #[test]
+    fn modexp_osaka_fixtures() {
+        let plan = ModexpPricePlan::new(
+            ModexpPricer::new_byzantium(20),
+            ModexpPricer::new_berlin(200),
+            ModexpPricer::new_osaka(500),
+        );
+        let mut berlin = Spec::genesis_spec();
+        berlin.cip645.eip2565 = true;
+        let mut osaka = berlin.clone();
+        osaka.eip7823 = true;
+        osaka.eip7883 = true;
+
+        let pow_0x10001: &[u8] = &[0x01, 0x00, 0x01];
+        let mut long_exp = vec![0; 40];
+        long_exp[0] = 0x01;
+        // The nagydani cases of the EIP-2565 and EIP-7883 precompile test
+        // suites: (base and modulus length, exponent, Berlin gas, Osaka gas).
+        let cases: [(usize, &[u8], u64, u64); 11] = [
+            (64, &[0x02], 200, 500),
+            (64, &[0x03], 200, 500),
+            (64, pow_0x10001, 341, 2048),
+            (128, &[0x02], 200, 512),
+            (128, pow_0x10001, 1365, 8192),
+            (256, &[0x02], 341, 2048),
+            (256, pow_0x10001, 5461, 32768),
+            (512, &[0x02], 1365, 8192),
+            (512, pow_0x10001, 21845, 131072),
+            (1024, &[0x02], 5461, 32768),
+            (1024, pow_0x10001, 87381, 524288),
+        ];
+        for (len, exp, berlin_gas, osaka_gas) in cases {
+            let input = modexp_input(len, exp, len);
+            assert_eq!(plan.pricer(&berlin).cost(&input), berlin_gas.into());
+            assert_eq!(plan.pricer(&osaka).cost(&input), osaka_gas.into());
+        }
+
+        // EIP-7883 doubles the multiplier of the exponent over 32 bytes:
+        // 16 * (8 * 8 + 248) / 3 before and 16 * (16 * 8 + 248) after.
+        let input = modexp_input(32, &long_exp, 32);
+        assert_eq!(plan.pricer(&berlin).cost(&input), 1664.into());
+        assert_eq!(plan.pricer(&osaka).cost(&input), 6016.into());
+    }
+
+    #[test]
+    fn modexp_eip7823_bounds() {
+        let plan = ModexpPricePlan::new(
+            ModexpPricer::new_byzantium(20),
+            ModexpPricer::new_berlin(200),
+            ModexpPricer::new_osaka(500),
+        );
+        let mut spec = Spec::genesis_spec();
+        spec.cip645.eip2565 = true;
+        spec.eip7883 = true;
+        spec.eip7823 = true;
+
+        let exp = |len: usize| {
+            let mut exp = vec![0; len];
+            exp[len - 1] = 0x03;
+            exp
+        };
+        // Each of the base, the exponent and the modulus may have at most
+        // 1024 bytes.
+        for (base_len, exp_len, mod_len) in [
+            (1024, 1, 1024),
+            (32, 1024, 32),
+            (1025, 1, 32),
+            (32, 1025, 32),
+            (32, 1, 1025),
+        ] {
+            let input = modexp_input(base_len, &exp(exp_len), mod_len);
+            let bounded = plan.pricer(&spec).cost(&input);
+            let in_bounds =
+                base_len <= 1024 && exp_len <= 1024 && mod_len <= 1024;
+            assert_eq!(bounded != U256::max_value(), in_bounds);
+
+            spec.eip7823 = false;
+            let unbounded = plan.pricer(&spec).cost(&input);
+            spec.eip7823 = true;
+            assert_ne!(unbounded, U256::max_value());
+            if in_bounds {
+                assert_eq!(bounded, unbounded);
+            }
+        }
+    }
+
+    #[test]
+    fn p256_verify() {
+        let f = Builtin {
+            price_plan: Box::new(StaticPlan(Linear::new(6900, 0))),
+            native: builtin_factory("p256_verify"),
+            activate_at: 0,
+        };
+        let verify = |input: &str| {
+            let input: Vec<u8> = FromHex::from_hex(input).unwrap();
+            assert_eq!(f.cost_on_genesis(&input[..]), 6900.into());
+
+            let mut output = Vec::new();
+            f.execute(&input[..], &mut BytesRef::Flexible(&mut output))
+                .expect("Builtin should not fail");
+            output
+        };
+        let success: Vec<u8> = FromHex::from_hex(
+            "0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+
+        // valid signatures from the RIP-7212 test vectors.
+        for input in [
+            "\
+				4cee90eb86eaa050036147a12d49004b6b9c72bd725d39d4785011fe190f0b4d\
+				a73bd4903f0ce3b639bbbf6e8e80d16931ff4bcf5993d58468e8fb19086e8cac\
+				36dbcd03009df8c59286b162af3bd7fcc0450c9aa81be5d10d312af6c66b1d60\
+				4aebd3099c618202fcfe16ae7770b0c49ab5eadf74b754204a3bb6060e44eff3\
+				7618b065f9832de4ca6ca971a7a1adc826d0f7c00181a5fb2ddf79ae00b4e10e",
+            "\
+				3fec5769b5cf4e310a7d150508e82fb8e3eda1c2c94c61492d3bd8aea99e06c9\
+				e22466e928fdccef0de49e3503d2657d00494a00e764fd437bdafa05f5922b1f\
+				bbb77c6817ccf50748419477e843d5bac67e6a70e97dde5a57e0c983b777e1ad\
+				31a80482dadf89de6302b1988c82c29544c9c07bb910596158f6062517eb089a\
+				2f54c9a0f348752950094d3228d3b940258c75fe2a413cb70baa21dc2e352fc5",
+            "\
+				e775723953ead4a90411a02908fd1a629db584bc600664c609061f221ef6bf7c\
+				440066c8626b49daaa7bf2bcc0b74be4f7a1e3dcf0e869f1542fe821498cbf2d\
+				e73ad398194129f635de4424a07ca715838aefe8fe69d1a391cfa70470795a80\
+				dd056866e6e1125aff94413921880c437c9e2570a28ced7267c8beef7e9b2d8d\
+				1547d76dfcf4bee592f5fefe10ddfb6aeb0991c5b9dbbee6ec80d11b17c0eb1a",
+            "\
+				b5a77e7a90aa14e0bf5f337f06f597148676424fae26e175c6e5621c34351955\
+				289f319789da424845c9eac935245fcddd805950e2f02506d09be7e411199556\
+				d262144475b1fa46ad85250728c600c53dfd10f8b3f4adf140e27241aec3c2da\
+				3a81046703fccf468b48b145f939efdbb96c3786db712b3113bb2488ef286cdc\
+				ef8afe82d200a5bb36b5462166e8ce77f2d831a52ef2135b2af188110beaefb1",
+        ] {
+            assert_eq!(verify(input), success);
+        }
+
+        // the first vector with a modified message hash, a zero r, a public
+        // key not on the curve and a truncated input respectively.
+        for input in [
+            "\
+				4cee90eb86eaa050036147a12d49004b6a9c72bd725d39d4785011fe190f0b4d\
+				a73bd4903f0ce3b639bbbf6e8e80d16931ff4bcf5993d58468e8fb19086e8cac\
+				36dbcd03009df8c59286b162af3bd7fcc0450c9aa81be5d10d312af6c66b1d60\
+				4aebd3099c618202fcfe16ae7770b0c49ab5eadf74b754204a3bb6060e44eff3\
+				7618b065f9832de4ca6ca971a7a1adc826d0f7c00181a5fb2ddf79ae00b4e10e",
+            "\
+				4cee90eb86eaa050036147a12d49004b6b9c72bd725d39d4785011fe190f0b4d\
+				0000000000000000000000000000000000000000000000000000000000000000\
+				36dbcd03009df8c59286b162af3bd7fcc0450c9aa81be5d10d312af6c66b1d60\
+				4aebd3099c618202fcfe16ae7770b0c49ab5eadf74b754204a3bb6060e44eff3\
+				7618b065f9832de4ca6ca971a7a1adc826d0f7c00181a5fb2ddf79ae00b4e10e",
+            "\
+				4cee90eb86eaa050036147a12d49004b6b9c72bd725d39d4785011fe190f0b4d\
+				a73bd4903f0ce3b639bbbf6e8e80d16931ff4bcf5993d58468e8fb19086e8cac\
+				36dbcd03009df8c59286b162af3bd7fcc0450c9aa81be5d10d312af6c66b1d60\
+				4aebd3099c618202fcfe16ae7770b0c49ab5eadf74b754204a3bb6060e44eff3\
+				7618b065f9832de4ca6ca971a7a1adc826d0f7c00181a5fb2ddf79ae00b4e10f",
+            "\
+				4cee90eb86eaa050036147a12d49004b6b9c72bd725d39d4785011fe190f0b4d\
+				a73bd4903f0ce3b639bbbf6e8e80d16931ff4bcf5993d58468e8fb19086e8cac\
+				36dbcd03009df8c59286b162af3bd7fcc0450c9aa81be5d10d312af6c66b1d60\
+				4aebd3099c618202fcfe16ae7770b0c49ab5eadf74b754204a3bb6060e44eff3\
+				7618b065f9832de4ca6ca971a7a1adc826d0f7c00181a5fb2ddf79ae00b4e1",
+            "",
+        ] {
+            assert!(verify(input).is_empty());
+        }
+    }
+
+    #[test]
+    fn p256_verify_wycheproof() {
+        let f = Builtin {
+            price_plan: Box::new(StaticPlan(Linear::new(6900, 0))),
+            native: builtin_factory("p256_verify"),
+            activate_at: 0,
+        };
+        let verify = |parts: [&str; 5]| {
+            let input: Vec<u8> = parts.concat().from_hex().unwrap();
+            let mut output = Vec::new();
+            f.execute(&input[..], &mut BytesRef::Flexible(&mut output))
+                .expect("Builtin should not fail");
+            output
+        };
+
+        // The key and the signatures of the message "123400" from the
+        // Wycheproof ecdsa_secp256r1_sha256 test vectors.
+        let hash =
+            "bb5a52f42f9c9261ed4361f59422a1e30036e7c32b270c8807a419feca605023";
+        let x =
+            "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838";
+        let y =
+            "c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e";
+        let r =
+            "2ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18";
+        let s =
+            "4cd60b855d442f5b3c7b11eb6c4e0ae7525fe710fab9aa7c77a67f79e6fadd76";
+        let malleable_s =
+            "b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db";
+        // The order of the curve and the modulus of the field.
+        let n =
+            "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551";
+        let p =
+            "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff";
+        let zero =
+            "0000000000000000000000000000000000000000000000000000000000000000";
+        let one =
+            "0000000000000000000000000000000000000000000000000000000000000001";
+        let modified_hash =
+            "bb5a52f42f9c9261ed4361f59422a1e30036e7c32b270c8807a419feca605024";
+        let off_curve_y =
+            "c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513f";
+
+        let success: Vec<u8> = one.from_hex().unwrap();
+        let long_y = [y, "00"].concat();
+
+        // EIP-7951 does not require a low s, unlike secp256k1 transactions.
+        assert_eq!(verify([hash, r, s, x, y]), success);
+        assert_eq!(verify([hash, r, malleable_s, x, y]), success);
+
+        for parts in [
+            [modified_hash, r, s, x, y],
+            // r and s must be in [1, n).
+            [hash, zero, s, x, y],
+            [hash, r, zero, x, y],
+            [hash, n, s, x, y],
+            [hash, r, n, x, y],
+            // The coordinates must be in the field and on the curve.
+            [hash, r, s, p, y],
+            [hash, r, s, x, off_curve_y],
+            // The point at infinity.
+            [hash, r, s, zero, zero],
+            // The input must be exactly 160 bytes.
+            [hash, r, s, x, long_y.as_str()],
+        ] {
+            assert!(verify(parts).is_empty());
+        }
+    }
+
+    #[test]
+    fn custom_precompile() {
+        let factory: PrecompileFactory = Arc::new(|| CustomPrecompile {
+            price_plan: Box::new(StaticPlan(Linear::new(60, 12))),
+            precompile: builtin_factory("identity"),
+        });
+        let mut precompiles = PrecompilePlugins::default();
+        precompiles
+            .register("custom_identity", factory.clone())
+            .expect("Should register");
+        assert!(precompiles.register("custom_identity", factory).is_err());
+
+        let params = CustomPrecompileParams {
+            name: "custom_identity".into(),
+            address: Address::from_low_u64_be(0x1000),
+            activate_at: 5,
+        };
+        let f = precompiles.builtin(&params).expect("Should be registered");
+        assert!(!f.is_active(4));
+        assert!(f.is_active(5));
+
+        let input = [0xffu8; 33];
+        let mut output = vec![0u8; 33];
+        f.execute(&input[..], &mut BytesRef::Fixed(&mut output[..]))
+            .expect("Builtin should not fail");
+        assert_eq!(&output[..], &input[..]);
+        assert_eq!(f.cost_on_genesis(&input[..]), (60 + 12 * 2).into());
+
+        let mut common_params = CommonParams::default();
+        common_params.custom_precompiles = vec![params.clone()];
+        let machine = Machine::try_new_with_builtin(
+            common_params.clone(),
+            VmFactory::new(1024 * 32),
+            &Default::default(),
+            &precompiles,
+        )
+        .expect("Should create builtins");
+        let address = params.address.with_evm_space();
+        assert!(machine.builtin(&address, 4).is_none());
+        assert!(machine.builtin(&address, 5).is_some());
+
+        // The precompiles of a machine are not shared with the others.
+        assert!(Machine::try_new_with_builtin(
+            common_params.clone(),
+            VmFactory::new(1024 * 32),
+            &Default::default(),
+            &Default::default(),
+        )
+        .is_err());
+
+        // An unregistered precompile.
+        common_params.custom_precompiles = vec![CustomPrecompileParams {
+            name: "custom_unregistered".into(),
+            ..params.clone()
+        }];
+        assert!(Machine::try_new_with_builtin(
+            common_params.clone(),
+            VmFactory::new(1024 * 32),
+            &Default::default(),
+            &precompiles,
+        )
+        .is_err());
+
+        // A precompile at the address of a builtin.
+        common_params.custom_precompiles = vec![CustomPrecompileParams {
+            address: Address::from_low_u64_be(4),
+            ..params
+        }];
+        assert!(Machine::try_new_with_builtin(
+            common_params,
+            VmFactory::new(1024 * 32),
+            &Default::default(),
+            &precompiles,
+        )
+        .is_err());
+    }
+
     #[test]
     fn bn128_add() {
         let f = Builtin {
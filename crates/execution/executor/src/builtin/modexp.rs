@@ -1,7 +1,8 @@
-use super::{Error, Precompile, Pricer};
+use super::{Error, Precompile, PricePlan, Pricer};
 use byteorder::{BigEndian, ByteOrder};
 use cfx_bytes::BytesRef;
 use cfx_types::U256;
+use cfx_vm_types::Spec;
 use num::{BigUint, One, Zero};
 use std::{
     cmp::{max, min},
@@ -127,11 +128,13 @@ impl Precompile for ModexpImpl {
 }
 
 /// A special pricing model for modular exponentiation.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub(crate) enum ModexpPricer {
     Byzantium { divisor: usize },
     // CIP-645e: EIP-2565
     Berlin { base: usize },
+    // EIP-7883
+    Osaka { base: usize },
 }
 
 impl ModexpPricer {
@@ -142,28 +145,38 @@ impl ModexpPricer {
     pub(crate) fn new_berlin(base: usize) -> ModexpPricer {
         ModexpPricer::Berlin { base }
     }
+
+    pub(crate) fn new_osaka(base: usize) -> ModexpPricer {
+        ModexpPricer::Osaka { base }
+    }
+}
+
+/// Reads the lengths of the base, the exponent and the modulus as U256.
+fn read_lengths(input: &[u8]) -> (U256, U256, U256) {
+    let mut reader = input.chain(io::repeat(0));
+    let mut buf = [0; 32];
+
+    let mut read_len = || {
+        reader
+            .read_exact(&mut buf[..])
+            .expect("reading from zero-extended memory cannot fail; qed");
+        U256::from_big_endian(&buf[..])
+    };
+    let base_len = read_len();
+    let exp_len = read_len();
+    let mod_len = read_len();
+    (base_len, exp_len, mod_len)
 }
 
 impl Pricer for ModexpPricer {
     fn cost(&self, input: &[u8]) -> U256 {
-        let mut reader = input.chain(io::repeat(0));
-        let mut buf = [0; 32];
-
         // read lengths as U256 here for accurate gas calculation.
-        let mut read_len = || {
-            reader
-                .read_exact(&mut buf[..])
-                .expect("reading from zero-extended memory cannot fail; qed");
-            U256::from_big_endian(&buf[..])
-        };
-        let base_len = read_len();
-        let exp_len = read_len();
-        let mod_len = read_len();
+        let (base_len, exp_len, mod_len) = read_lengths(input);
 
         if mod_len.is_zero() && base_len.is_zero() {
             return match self {
                 Self::Byzantium { .. } => 0.into(),
-                Self::Berlin { base } => (*base).into(),
+                Self::Berlin { base } | Self::Osaka { base } => (*base).into(),
             };
         }
 
@@ -188,7 +201,13 @@ impl Pricer for ModexpPricer {
                 .expect("reading from zero-extended memory cannot fail; qed");
             U256::from_big_endian(&buf[..])
         };
-        let iter_count = max(Self::adjusted_exp_len(exp_len, exp_low), 1);
+        // EIP-7883: Doubles the multiplier for the exponent over 32 bytes.
+        let exp_multiplier = match self {
+            ModexpPricer::Osaka { .. } => 16,
+            _ => 8,
+        };
+        let iter_count =
+            max(Self::adjusted_exp_len(exp_len, exp_low, exp_multiplier), 1);
 
         match self {
             ModexpPricer::Byzantium { divisor } => Self::byzantium_gas_calc(
@@ -197,6 +216,9 @@ impl Pricer for ModexpPricer {
             ModexpPricer::Berlin { base } => {
                 Self::berlin_gas_calc(base_len, mod_len, iter_count, *base)
             }
+            ModexpPricer::Osaka { base } => {
+                Self::osaka_gas_calc(base_len, mod_len, iter_count, *base)
+            }
         }
     }
 }
@@ -214,7 +236,7 @@ impl ModexpPricer {
         (gas / divisor as u64).into()
     }
 
-    fn adjusted_exp_len(len: u64, exp_low: U256) -> u64 {
+    fn adjusted_exp_len(len: u64, exp_low: U256, multiplier: u64) -> u64 {
         let bit_index = if exp_low.is_zero() {
             0
         } else {
@@ -223,7 +245,7 @@ impl ModexpPricer {
         if len <= 32 {
             bit_index
         } else {
-            8 * (len - 32) + bit_index
+            multiplier * (len - 32) + bit_index
         }
     }
 
@@ -261,4 +283,83 @@ impl ModexpPricer {
         };
         max(base_gas as u64, gas_u64).into()
     }
+
+    pub fn osaka_gas_calc(
+        base_len: u64, mod_len: u64, iter_count: u64, base_gas: usize,
+    ) -> U256 {
+        fn calculate_multiplication_complexity(
+            base_len: u64, mod_len: u64,
+        ) -> U256 {
+            let max_len = max(base_len, mod_len);
+            if max_len <= 32 {
+                return U256::from(16);
+            }
+            let words = U256::from((max_len + 7) / 8);
+            U256::from(2) * words * words
+        }
+
+        let multiplication_complexity =
+            calculate_multiplication_complexity(base_len, mod_len);
+        let gas = multiplication_complexity * U256::from(iter_count);
+        let gas_u64 = if gas >= U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            gas.as_u64()
+        };
+        max(base_gas as u64, gas_u64).into()
+    }
+}
+
+/// The maximum length of the base, the exponent and the modulus (EIP-7823).
+const EIP7823_INPUT_LENGTH_LIMIT: u64 = 1024;
+
+/// EIP-7823: Charges all the gas for the inputs with any length exceeding the
+/// limit, so the call fails as the EIP requires.
+#[derive(Debug)]
+pub(crate) struct BoundedModexpPricer(ModexpPricer);
+
+impl Pricer for BoundedModexpPricer {
+    fn cost(&self, input: &[u8]) -> U256 {
+        let (base_len, exp_len, mod_len) = read_lengths(input);
+        let limit = U256::from(EIP7823_INPUT_LENGTH_LIMIT);
+        if base_len > limit || exp_len > limit || mod_len > limit {
+            return U256::max_value();
+        }
+        self.0.cost(input)
+    }
+}
+
+/// Selects the modexp pricing model by the activated hardforks.
+pub(crate) struct ModexpPricePlan {
+    pricers: [ModexpPricer; 3],
+    bounded_pricers: [BoundedModexpPricer; 3],
+}
+
+impl ModexpPricePlan {
+    pub(crate) fn new(
+        byzantium: ModexpPricer, berlin: ModexpPricer, osaka: ModexpPricer,
+    ) -> Self {
+        let pricers = [byzantium, berlin, osaka];
+        ModexpPricePlan {
+            pricers,
+            bounded_pricers: pricers.map(BoundedModexpPricer),
+        }
+    }
+}
+
+impl PricePlan for ModexpPricePlan {
+    fn pricer(&self, spec: &Spec) -> &dyn Pricer {
+        let index = if spec.eip7883 {
+            2
+        } else if spec.cip645.eip2565 {
+            1
+        } else {
+            0
+        };
+        if spec.eip7823 {
+            &self.bounded_pricers[index]
+        } else {
+            &self.pricers[index]
+        }
+    }
 }
@@ -0,0 +1,35 @@
+// Based on source code from the revm project (https://github.com/bluealloy/revm) under the MIT License.
+
+use p256::ecdsa::{
+    signature::hazmat::PrehashVerifier, Signature, VerifyingKey,
+};
+
+/// Verify a secp256r1 signature over a message hash.
+///
+/// The input is encoded as follows:
+/// | message hash |  r  |  s  |  qx  |  qy  |
+/// |      32      | 32  | 32  |  32  |  32  |
+/// with `(qx, qy)` being the public key in affine coordinates.
+pub fn verify(input: &[u8]) -> bool { verify_impl(input).is_some() }
+
+fn verify_impl(input: &[u8]) -> Option<()> {
+    if input.len() != 160 {
+        return None;
+    }
+
+    let message_hash = &input[..32];
+    let signature = &input[32..96];
+    let public_key = &input[96..160];
+
+    // Prepend 0x04 to the public key for the SEC1 uncompressed form.
+    let mut uncompressed_public_key = [0u8; 65];
+    uncompressed_public_key[0] = 0x04;
+    uncompressed_public_key[1..].copy_from_slice(public_key);
+
+    // Rejects r or s out of [1, n).
+    let signature = Signature::from_slice(signature).ok()?;
+    // Rejects the coordinates out of the field or the points not on the curve.
+    let public_key =
+        VerifyingKey::from_sec1_bytes(&uncompressed_public_key).ok()?;
+    public_key.verify_prehash(message_hash, &signature).ok()
+}
@@ -21,8 +21,8 @@ use cfx_parameters::staking::{
     code_collateral_units, DRIPS_PER_STORAGE_COLLATERAL_UNIT,
 };
 use cfx_types::{
-    Address, AddressSpaceUtil, AddressWithSpace, BigEndianHash, Space, H256,
-    U256,
+    address_util::AddressUtil, Address, AddressSpaceUtil, AddressWithSpace,
+    BigEndianHash, Space, H256, U256,
 };
 use cfx_vm_types::{
     self as vm, ActionParams, ActionValue, CallType, Context as ContextTrait,
@@ -538,7 +538,7 @@ impl<'a> ContextTrait for Context<'a> {
     }
 
     fn trace_step_end(&mut self, interpreter: &dyn vm::InterpreterInfo) {
-        self.tracer.step_end(interpreter);
+        self.tracer.step_end_with_state(interpreter, self.state);
     }
 
     fn opcode_trace_enabled(&self) -> bool {
@@ -567,7 +567,7 @@ impl<'a> ContextTrait for Context<'a> {
 
     fn is_warm_account(&self, account: Address) -> bool {
         let address_with_space = account.with_space(self.space);
-        let maybe_builtin = &account[..19] == &[0u8; 19];
+        let maybe_builtin = account.is_builtin_address();
         if maybe_builtin
             && self
                 .machine
@@ -702,6 +702,8 @@ mod tests {
             let machine = Machine::new_with_builtin(
                 Default::default(),
                 Default::default(),
+                &Default::default(),
+                &Default::default(),
             );
             let env = get_test_env();
             let spec = machine.spec_for_test(env.number);
@@ -46,6 +46,8 @@ fn make_byzantium_machine(max_depth: usize) -> Machine {
     let mut machine = crate::machine::Machine::new_with_builtin(
         params,
         VmFactory::new(1024 * 32),
+        &Default::default(),
+        &Default::default(),
     );
     machine.set_max_depth(max_depth);
     machine
@@ -1,16 +1,22 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use cfx_types::{Address, AddressWithSpace, Space};
 use cfx_vm_types::Spec;
 use primitives::BlockNumber;
 
-use super::{super::contracts::all_internal_contracts, InternalContractTrait};
+use super::{
+    super::{
+        contracts::all_internal_contracts, plugin::InternalContractPlugins,
+    },
+    InternalContractTrait,
+};
 use crate::spec::CommonParams;
 
 #[derive(Default)]
 pub struct InternalContractMap {
     builtin: BTreeMap<Address, Box<dyn InternalContractTrait>>,
     activation_info: BTreeMap<BlockNumber, Vec<Address>>,
+    plugins: BTreeSet<Address>,
 }
 
 impl std::ops::Deref for InternalContractMap {
@@ -20,23 +26,37 @@ impl std::ops::Deref for InternalContractMap {
 }
 
 impl InternalContractMap {
-    pub fn new(params: &CommonParams) -> Self {
+    pub fn new(
+        params: &CommonParams, plugins: &InternalContractPlugins,
+    ) -> Self {
         let mut builtin = BTreeMap::new();
         let mut activation_info = BTreeMap::new();
         // We should initialize all the internal contracts here. Even if not all
         // of them are activated at the genesis block. The activation of the
         // internal contracts are controlled by the `CommonParams` and
-        // `vm::Spec`.
+        // `vm::Spec`. The contracts registered by plugins are included.
         let mut internal_contracts = all_internal_contracts();
+        let plugin_contracts = plugins.contracts();
+        let plugins: BTreeSet<Address> = plugin_contracts
+            .iter()
+            .map(|contract| *contract.address())
+            .collect();
+        internal_contracts.extend(plugin_contracts);
 
         while let Some(contract) = internal_contracts.pop() {
             let address = *contract.address();
-            let transition_block = if params.early_set_internal_contracts_states
-            {
-                0
-            } else {
-                contract.initialize_block(params)
-            };
+            let mut transition_block =
+                if params.early_set_internal_contracts_states {
+                    0
+                } else {
+                    contract.initialize_block(params)
+                };
+            if plugins.contains(&address) {
+                // A block number is never below its height, so the plugins
+                // are not initialized before their transition height.
+                transition_block = transition_block
+                    .max(params.transition_heights.internal_contract_plugins);
+            }
 
             builtin.insert(*contract.address(), contract);
             activation_info
@@ -48,6 +68,7 @@ impl InternalContractMap {
         Self {
             builtin,
             activation_info,
+            plugins,
         }
     }
 
@@ -75,6 +96,11 @@ impl InternalContractMap {
         if address.space != Space::Native {
             return None;
         }
+        if self.plugins.contains(&address.address)
+            && !spec.internal_contract_plugins
+        {
+            return None;
+        }
         self.builtin
             .get(&address.address)
             .filter(|&contract| contract.is_active(spec))
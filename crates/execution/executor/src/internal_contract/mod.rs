@@ -5,6 +5,7 @@
 mod components;
 mod contracts;
 mod impls;
+pub mod plugin;
 mod utils;
 
 pub use self::{
@@ -32,4 +33,5 @@ pub use self::{
             make_staking_events, IndexStatus,
         },
     },
+    plugin::{InternalContractFactory, InternalContractPlugins},
 };
@@ -0,0 +1,194 @@
+//! Plugin interface for adding internal contracts without modifying the
+//! executor, e.g., governance modules on test networks.
+//!
+//! A plugin contract is built in the same way as the builtin ones:
+//! - the contract is declared by `make_solidity_contract!`, which sets its
+//!   address and activation transition (the block to initialize its account and
+//!   the `Spec` condition to enable it);
+//! - the function selectors are the keys of the table built by
+//!   `make_function_table!` from functions declared by
+//!   `make_solidity_function!` and `impl_function_type!`;
+//! - the events are declared by `make_solidity_event!`;
+//! - the storage entries are accessed through `InternalRefContext` with the
+//!   slots computed by the solidity storage layout helpers.
+//!
+//! Importing `prelude::*` brings in all the names these macros require. The
+//! contract is then registered in an `InternalContractPlugins`, which is passed
+//! to the `Machine`. The plugin contracts are initialized and enabled only
+//! after the `internal_contract_plugins` transition height, in addition to
+//! their own activation transition.
+
+use super::{
+    components::InternalContractTrait, contracts::all_internal_contracts,
+};
+use cfx_types::Address;
+use std::{fmt, sync::Arc};
+
+/// The names required by the macros to make internal contracts.
+pub mod prelude {
+    pub use super::{InternalContractFactory, InternalContractPlugins};
+    pub use crate::{
+        group_impl_is_active, impl_function_type,
+        internal_contract::components::{
+            activation::IsActive,
+            context::InternalRefContext,
+            contract::{InternalContractTrait, SolFnTable},
+            event::SolidityEventTrait,
+            function::{
+                ExecutionTrait, InterfaceTrait, PreExecCheckConfTrait,
+                SimpleExecutionTrait, SolidityFunctionTrait,
+                UpfrontPaymentTrait,
+            },
+            storage_layout::{
+                array_slot, dynamic_slot, mapping_slot, u256_to_array,
+                vector_slot,
+            },
+            trap_result::InternalTrapResult,
+        },
+        make_function_table, make_solidity_contract, make_solidity_event,
+        make_solidity_function,
+        spec::CommonParams,
+    };
+    pub use cfx_statedb::Result as DbResult;
+    pub use cfx_types::{Address, H256, U256};
+    pub use cfx_vm_types::{self as vm, ActionParams, Spec};
+    pub use primitives::BlockNumber;
+    pub use sha3_macro::keccak;
+}
+
+/// Creates an instance of a plugin contract for each `InternalContractMap`.
+pub type InternalContractFactory =
+    Arc<dyn Fn() -> Box<dyn InternalContractTrait> + Send + Sync>;
+
+/// The internal contracts supplied by the embedding binary.
+#[derive(Clone, Default)]
+pub struct InternalContractPlugins {
+    factories: Vec<InternalContractFactory>,
+}
+
+impl InternalContractPlugins {
+    /// Registers an internal contract.
+    ///
+    /// The address must be in the range of the internal contracts, i.e.,
+    /// `0x0888000000000000000000000000000000000000` to
+    /// `0x08880000000000000000000000000000000000ff`, and not taken by any
+    /// other internal contract.
+    pub fn register(
+        &mut self, factory: InternalContractFactory,
+    ) -> Result<(), String> {
+        let address = *factory().address();
+        if !is_internal_contract_address(&address) {
+            return Err(format!(
+                "{:?} is not in the range of internal contracts",
+                address
+            ));
+        }
+
+        let taken = all_internal_contracts()
+            .into_iter()
+            .chain(self.contracts())
+            .any(|contract| *contract.address() == address);
+        if taken {
+            return Err(format!(
+                "Internal contract {:?} is already registered",
+                address
+            ));
+        }
+
+        self.factories.push(factory);
+        Ok(())
+    }
+
+    pub(super) fn contracts(&self) -> Vec<Box<dyn InternalContractTrait>> {
+        self.factories.iter().map(|factory| factory()).collect()
+    }
+}
+
+impl fmt::Debug for InternalContractPlugins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.contracts().iter().map(|contract| contract.address()))
+            .finish()
+    }
+}
+
+fn is_internal_contract_address(address: &Address) -> bool {
+    &address[..2] == b"\x08\x88" && &address[2..19] == &[0u8; 17]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{prelude::*, *};
+    use crate::internal_contract::InternalContractMap;
+    use cfx_types::AddressSpaceUtil;
+
+    make_solidity_contract! {
+        struct Governance(GOVERNANCE_ADDRESS, generate_fn_table, initialize: |params: &CommonParams| params.transition_numbers.cip119, is_active: |spec: &Spec| spec.cip119);
+    }
+    fn generate_fn_table() -> SolFnTable { make_function_table!(Proposals) }
+    group_impl_is_active!(|spec: &Spec| spec.cip119, Proposals);
+
+    make_solidity_function! {
+        struct Proposals((), "proposals()", U256);
+    }
+    impl_function_type!(Proposals, "query_with_default_gas");
+
+    impl SimpleExecutionTrait for Proposals {
+        fn execute_inner(
+            &self, _input: (), params: &ActionParams,
+            context: &mut InternalRefContext,
+        ) -> vm::Result<U256> {
+            Ok(context.storage_at(params, &u256_to_array(U256::zero()))?)
+        }
+    }
+
+    make_solidity_contract! {
+        struct Shadow(ADMIN_ADDRESS, "placeholder");
+    }
+
+    const GOVERNANCE_ADDRESS: Address = Address([
+        0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80,
+    ]);
+    const ADMIN_ADDRESS: Address = Address([
+        0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ]);
+
+    #[test]
+    fn test_register_internal_contract() {
+        let mut plugins = InternalContractPlugins::default();
+        let factory: InternalContractFactory =
+            Arc::new(|| Box::new(Governance::instance()));
+        plugins.register(factory.clone()).unwrap();
+        assert!(plugins.register(factory).is_err());
+        assert!(plugins
+            .register(Arc::new(|| Box::new(Shadow::instance())))
+            .is_err());
+
+        let mut params = CommonParams::default();
+        params.transition_numbers.cip119 = 100;
+        params.transition_heights.internal_contract_plugins = 200;
+        let map = InternalContractMap::new(&params, &plugins);
+        let contract = map.get(&GOVERNANCE_ADDRESS).unwrap();
+        assert!(contract
+            .get_func_table()
+            .contains_key(&<Proposals as InterfaceTrait>::FUNC_SIG));
+        // The plugins are initialized after both their own activation and
+        // the transition height of the plugins.
+        assert!(!map.initialized_at(100).contains(&GOVERNANCE_ADDRESS));
+        assert!(map.initialized_at(200).contains(&GOVERNANCE_ADDRESS));
+
+        // The plugins are enabled after both their own activation and the
+        // transition height of the plugins.
+        let address = GOVERNANCE_ADDRESS.with_native_space();
+        assert!(map.contract(&address, &params.spec(100, 100)).is_none());
+        assert!(map.contract(&address, &params.spec(100, 200)).is_some());
+        assert!(map.contract(&address, &params.spec(99, 200)).is_none());
+
+        // The plugins of a map are not shared with the others.
+        let map = InternalContractMap::new(&params, &Default::default());
+        assert!(map.get(&GOVERNANCE_ADDRESS).is_none());
+
+        assert!(is_internal_contract_address(&ADMIN_ADDRESS));
+        assert!(!is_internal_contract_address(&Address::zero()));
+    }
+}
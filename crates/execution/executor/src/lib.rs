@@ -12,7 +12,8 @@ extern crate cfx_util_macros;
 use substrate_bn as bn;
 
 /// Ethereum Builtins: Implements Ethereum's builtin contracts, ranging from
-/// address `0x1` to `0x9`.
+/// address `0x1` to `0x9`, and the custom precompiles registered by the
+/// embedding binary.
 mod builtin;
 
 /// Execution Context: Implements the context during the execution, like
@@ -59,7 +60,14 @@ pub mod spec;
 /// meaningful database interfaces for the execution.
 pub mod state;
 
-pub use internal_contract::{InternalContractMap, InternalContractTrait};
+pub use builtin::{
+    CustomPrecompile, Error as PrecompileError, Precompile, PrecompileFactory,
+    PrecompilePlugins, PricePlan, Pricer, StaticPlan,
+};
+pub use internal_contract::{
+    InternalContractFactory, InternalContractMap, InternalContractPlugins,
+    InternalContractTrait,
+};
 pub use observer as executive_observer;
 
 /// Common tools for test
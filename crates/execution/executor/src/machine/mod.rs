@@ -8,9 +8,10 @@ use super::builtin::Builtin;
 use crate::{
     builtin::{
         build_bls12_builtin_map, builtin_factory, AltBn128PairingPricer,
-        Blake2FPricer, IfPricer, Linear, ModexpPricer, StaticPlan,
+        Blake2FPricer, IfPricer, Linear, ModexpPricePlan, ModexpPricer,
+        PrecompilePlugins, StaticPlan,
     },
-    internal_contract::InternalContractMap,
+    internal_contract::{InternalContractMap, InternalContractPlugins},
     spec::CommonParams,
 };
 use cfx_types::{Address, AddressWithSpace, Space, H256};
@@ -44,14 +45,30 @@ impl Machine {
         }
     }
 
+    /// Creates a machine with the builtins and the internal contracts,
+    /// including the ones of `plugins`, and the custom precompiles of
+    /// `params` implemented by `precompiles`.
     pub fn new_with_builtin(
         params: CommonParams, vm_factory: VmFactory,
+        plugins: &InternalContractPlugins, precompiles: &PrecompilePlugins,
     ) -> Machine {
-        let builtin = new_builtin_map(&params, Space::Native);
-        let builtin_evm = new_builtin_map(&params, Space::Ethereum);
+        Self::try_new_with_builtin(params, vm_factory, plugins, precompiles)
+            .unwrap_or_else(|e| panic!("Failed to create builtins: {}", e))
+    }
 
-        let internal_contracts = InternalContractMap::new(&params);
-        Machine {
+    /// Like `new_with_builtin`, but returns an error if the custom
+    /// precompiles in `params` are not registered or conflict with the
+    /// builtins.
+    pub fn try_new_with_builtin(
+        params: CommonParams, vm_factory: VmFactory,
+        plugins: &InternalContractPlugins, precompiles: &PrecompilePlugins,
+    ) -> Result<Machine, String> {
+        let builtin = new_builtin_map(&params, Space::Native, precompiles)?;
+        let builtin_evm =
+            new_builtin_map(&params, Space::Ethereum, precompiles)?;
+
+        let internal_contracts = InternalContractMap::new(&params, plugins);
+        Ok(Machine {
             params,
             vm_factory,
             builtins: Arc::new(builtin),
@@ -59,7 +76,7 @@ impl Machine {
             internal_contracts: Arc::new(internal_contracts),
             #[cfg(test)]
             max_depth: None,
-        }
+        })
     }
 
     pub fn builtin(
@@ -118,8 +135,8 @@ impl Machine {
 }
 
 fn new_builtin_map(
-    params: &CommonParams, space: Space,
-) -> BTreeMap<Address, Builtin> {
+    params: &CommonParams, space: Space, precompiles: &PrecompilePlugins,
+) -> Result<BTreeMap<Address, Builtin>, String> {
     let mut btree = BTreeMap::new();
 
     btree.insert(
@@ -158,11 +175,11 @@ fn new_builtin_map(
         ),
     );
 
-    // CIP-645e: EIP-2565
-    let mod_exp_pricer = IfPricer::new(
-        |spec| spec.cip645.eip2565,
-        ModexpPricer::new_berlin(200),
+    // CIP-645e: EIP-2565, EIP-7883 and EIP-7823
+    let mod_exp_pricer = ModexpPricePlan::new(
         ModexpPricer::new_byzantium(20),
+        ModexpPricer::new_berlin(200),
+        ModexpPricer::new_osaka(500),
     );
     btree.insert(
         Address::from(H256::from_low_u64_be(5)),
@@ -243,5 +260,23 @@ fn new_builtin_map(
             ),
         );
     }
-    btree
+    // EIP-7951: secp256r1 signature verification
+    btree.insert(
+        Address::from(H256::from_low_u64_be(0x100)),
+        Builtin::new(
+            Box::new(StaticPlan(Linear::new(6900, 0))),
+            builtin_factory("p256_verify"),
+            params.transition_heights.eip7951,
+        ),
+    );
+    for custom in &params.custom_precompiles {
+        if btree.contains_key(&custom.address) {
+            return Err(format!(
+                "Precompile {} conflicts with the builtin at {:?}",
+                custom.name, custom.address
+            ));
+        }
+        btree.insert(custom.address, precompiles.builtin(custom)?);
+    }
+    Ok(btree)
 }
@@ -1,10 +1,10 @@
-use crate::stack::FrameResult;
+use crate::{stack::FrameResult, state::State};
 use cfx_vm_types::ActionParams;
 
 use impl_tools::autoimpl;
 use impl_trait_for_tuples::impl_for_tuples;
 
-#[impl_for_tuples(3)]
+#[impl_for_tuples(4)]
 #[autoimpl(for<T: trait + ?Sized> &mut T)]
 #[allow(unused_variables)]
 pub trait CallTracer {
@@ -19,4 +19,20 @@ pub trait CallTracer {
 
     /// Prepares create result trace
     fn record_create_result(&mut self, result: &FrameResult) {}
+
+    /// Same as `record_call_result`, for tracers that also need to read the
+    /// state after the frame's checkpoint is discarded or reverted.
+    fn record_call_result_with_state(
+        &mut self, result: &FrameResult, state: &State,
+    ) {
+        self.record_call_result(result);
+    }
+
+    /// Same as `record_create_result`, for tracers that also need to read the
+    /// state after the frame's checkpoint is discarded or reverted.
+    fn record_create_result_with_state(
+        &mut self, result: &FrameResult, state: &State,
+    ) {
+        self.record_create_result(result);
+    }
 }
@@ -1,10 +1,11 @@
+use crate::state::State;
 use cfx_types::{Address, Space, H256, U256};
 use cfx_vm_types::InterpreterInfo;
 
 use impl_tools::autoimpl;
 use impl_trait_for_tuples::impl_for_tuples;
 
-#[impl_for_tuples(3)]
+#[impl_for_tuples(4)]
 #[autoimpl(for<T: trait + ?Sized> &mut T)]
 pub trait OpcodeTracer {
     fn do_trace_opcode(&self, _enabled: &mut bool) {}
@@ -22,6 +23,15 @@ pub trait OpcodeTracer {
     /// Called after `step` when the instruction has been executed.
     fn step_end(&mut self, interp: &dyn InterpreterInfo) { let _ = interp; }
 
+    /// Same as `step_end`, for tracers that also need to read the state
+    /// after the instruction has been executed.
+    fn step_end_with_state(
+        &mut self, interp: &dyn InterpreterInfo, state: &State,
+    ) {
+        let _ = state;
+        self.step_end(interp);
+    }
+
     /// Called when a log is emitted.
     #[inline]
     fn log(&mut self, address: &Address, topics: &Vec<H256>, data: &[u8]) {
@@ -17,7 +17,7 @@ use cfx_parameters::{
         INITIAL_BASE_MINING_REWARD_IN_UCFX,
     },
 };
-use cfx_types::{AllChainID, Space, SpaceMap, U256, U512};
+use cfx_types::{Address, AllChainID, Space, SpaceMap, U256, U512};
 use cfx_vm_types::{CIP645Spec, ConsensusGasSpec, Spec};
 use primitives::{block::BlockHeight, BlockNumber};
 use std::collections::BTreeMap;
@@ -63,6 +63,21 @@ pub struct CommonParams {
     pub transition_numbers: TransitionsBlockNumber,
     /// The upgrades activated at given block height (a.k.a. epoch number).
     pub transition_heights: TransitionsEpochHeight,
+    /// The precompiles supplied by the embedding binary, enabled in addition
+    /// to the standard builtins.
+    pub custom_precompiles: Vec<CustomPrecompileParams>,
+}
+
+/// The activation of a precompile registered in `PrecompilePlugins`.
+/// The embedding binary supplies its implementation and pricing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomPrecompileParams {
+    /// The name the implementation is registered with.
+    pub name: String,
+    /// The address of the precompile in both spaces.
+    pub address: Address,
+    /// The block number from which the precompile is enabled.
+    pub activate_at: BlockNumber,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -160,6 +175,16 @@ pub struct TransitionsEpochHeight {
     pub cip145_fix: BlockHeight,
     /// EIP-7939: Count Leading Zeros Instruction
     pub cip166: BlockHeight,
+    /// EIP-7823: Set upper bounds for MODEXP
+    pub eip7823: BlockHeight,
+    /// EIP-7825: Transaction Gas Limit Cap
+    pub eip7825: BlockHeight,
+    /// EIP-7883: ModExp Gas Cost Increase
+    pub eip7883: BlockHeight,
+    /// EIP-7951: Precompile for secp256r1 Curve Support
+    pub eip7951: BlockHeight,
+    /// Enables the internal contracts registered by plugins
+    pub internal_contract_plugins: BlockHeight,
 }
 
 impl Default for CommonParams {
@@ -183,6 +208,7 @@ impl Default for CommonParams {
             transition_numbers: Default::default(),
             transition_heights: Default::default(),
             min_base_price: SpaceMap::default(),
+            custom_precompiles: Vec::new(),
         }
     }
 }
@@ -232,6 +258,12 @@ impl CommonParams {
         spec.cancun_opcodes = number >= self.transition_numbers.cancun_opcodes;
         spec.align_evm = height >= self.transition_heights.align_evm && cip645;
         spec.eip7939 = height >= self.transition_heights.cip166;
+        spec.eip7823 = height >= self.transition_heights.eip7823;
+        spec.eip7825 = height >= self.transition_heights.eip7825;
+        spec.eip7883 = height >= self.transition_heights.eip7883;
+        spec.eip7951 = height >= self.transition_heights.eip7951;
+        spec.internal_contract_plugins =
+            height >= self.transition_heights.internal_contract_plugins;
 
         spec.overwrite_gas_plan_by_cip();
 
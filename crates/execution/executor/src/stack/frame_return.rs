@@ -24,9 +24,13 @@ pub(super) fn process_return<'a>(
     }
 
     if is_create {
-        resources.tracer.record_create_result(&frame_result);
+        resources
+            .tracer
+            .record_create_result_with_state(&frame_result, resources.state);
     } else {
-        resources.tracer.record_call_result(&frame_result);
+        resources
+            .tracer
+            .record_call_result_with_state(&frame_result, resources.state);
     }
 
     resources.callstack.pop();
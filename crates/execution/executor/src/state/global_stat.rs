@@ -108,4 +108,20 @@ impl GlobalStat {
 
     /// Get the mutable reference of a variable
     pub fn val<T: GlobalParamKey>(&mut self) -> &mut U256 { &mut self.0[T::ID] }
+
+    /// Get the owned value of a variable by its index
+    pub fn get_by_id(&self, id: usize) -> U256 { self.0[id] }
+
+    /// Apply the changes from `base` to `updated` on top of the current
+    /// values. Within a transaction, the variables are only increased or
+    /// decreased (except for the ones whose values are read), so the changes
+    /// can be replayed as deltas in wrapping arithmetic.
+    pub fn apply_delta(&mut self, base: &GlobalStat, updated: &GlobalStat) {
+        for (value, (base, updated)) in
+            self.0.iter_mut().zip(base.0.iter().zip(updated.0.iter()))
+        {
+            let delta = updated.overflowing_sub(*base).0;
+            *value = value.overflowing_add(delta).0;
+        }
+    }
 }
@@ -56,7 +56,6 @@ impl AccountEntry {
         }
     }
 
-    #[allow(unused)]
     pub fn is_dirty(&self) -> bool { matches!(self, Cached(_, true)) }
 
     pub fn is_db_absent(&self) -> bool { matches!(self, DbAbsent) }
@@ -319,6 +319,31 @@ impl OverlayAccount {
         Ok(())
     }
 
+    /// Returns the storage entries written since the last cache commit,
+    /// i.e., by the transaction in execution.
+    pub fn written_storage(&self) -> Vec<(Vec<u8>, U256)> {
+        self.storage_write_cache
+            .read()
+            .iter()
+            .filter_map(|(key, item)| match item {
+                WriteCacheItem::Write(entry) => {
+                    Some((key.clone(), entry.value))
+                }
+                WriteCacheItem::Read => None,
+            })
+            .collect()
+    }
+
+    /// Returns the storage entries committed to the cache, i.e., by the
+    /// earlier transactions of the epoch.
+    pub fn committed_storage(&self) -> Vec<(Vec<u8>, U256)> {
+        self.storage_committed_cache
+            .read()
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.value))
+            .collect()
+    }
+
     pub fn is_warm_storage_entry(&self, key: &[u8]) -> bool {
         self.storage_write_cache.read().get(key).is_some()
     }
@@ -2,7 +2,10 @@
 //! provides interfaces for reading and writing account data. It also handles
 //! the logic for loading extension fields of an account.
 
-use super::{AccountEntry, OverlayAccount, RequireFields, State};
+use super::{
+    speculation::Speculation, AccountEntry, OverlayAccount, RequireFields,
+    State,
+};
 use crate::{state::overlay_account::AccountEntryWithWarm, unwrap_or_return};
 use cfx_statedb::{
     Error as DbErrorKind, Result as DbResult, StateDb, StateDbExt,
@@ -46,6 +49,7 @@ impl State {
         let account_entry = Self::fetch_account_mut(
             &mut cache,
             &self.committed_cache,
+            self.speculation.as_ref(),
             &self.db,
             address,
             require,
@@ -173,6 +177,7 @@ impl State {
         let account_entry = Self::fetch_account_mut(
             &mut cache,
             &self.committed_cache,
+            self.speculation.as_ref(),
             &self.db,
             address,
             require,
@@ -180,6 +185,7 @@ impl State {
 
         // Save the value before modification into the checkpoint.
         self.copy_cache_entry_to_checkpoint(*address, account_entry);
+        self.record_speculative_write(address);
 
         // Set the dirty flag in cache.
         if let AccountEntry::Cached(_, dirty_bit) = &mut account_entry.entry {
@@ -203,14 +209,25 @@ impl State {
     fn fetch_account_mut<'a>(
         cache: &'a mut HashMap<AddressWithSpace, AccountEntryWithWarm>,
         committed_cache: &'a HashMap<AddressWithSpace, AccountEntry>,
-        db: &StateDb, address: &AddressWithSpace, require: RequireFields,
+        speculation: Option<&Speculation>, db: &StateDb,
+        address: &AddressWithSpace, require: RequireFields,
     ) -> DbResult<&'a mut AccountEntryWithWarm> {
+        if let Some(speculation) = speculation {
+            speculation.record_loaded_account(address);
+        }
         let account_entry = match cache.entry(*address) {
             Occupied(e) => e.into_mut(),
             Vacant(e) => {
-                let entry = match committed_cache.get(address) {
-                    Some(committed) => committed.clone_from_committed_cache(),
-                    None => {
+                let base = speculation
+                    .and_then(|speculation| speculation.base_account(address));
+                let entry = match (committed_cache.get(address), base) {
+                    (Some(committed), _) => {
+                        committed.clone_from_committed_cache()
+                    }
+                    // The base is shared by the speculative states on other
+                    // threads, so the account is deeply copied.
+                    (None, Some(base)) => base.clone_account(),
+                    (None, None) => {
                         let address = *e.key();
                         AccountEntry::new_loaded(db.get_account(&address)?)
                     }
@@ -95,6 +95,7 @@ impl State {
     /// to the checkpoint in needed.
     pub(super) fn insert_to_cache(&mut self, account: OverlayAccount) {
         let address = *account.address();
+        self.record_speculative_write(&address);
         let old_account_entry = self
             .cache
             .get_mut()
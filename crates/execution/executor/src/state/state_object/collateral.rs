@@ -66,8 +66,8 @@ impl State {
             U256::zero()
         };
 
-        *self.global_stat.val::<TotalStorage>() -= *by - storage_points_refund;
-        *self.global_stat.val::<UsedStoragePoints>() -= storage_points_refund;
+        self.sub_global_stat::<TotalStorage>(*by - storage_points_refund);
+        self.sub_global_stat::<UsedStoragePoints>(storage_points_refund);
         self.sub_total_issued(burnt);
 
         Ok(storage_points_refund)
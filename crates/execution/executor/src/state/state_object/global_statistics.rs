@@ -7,7 +7,7 @@ use cfx_types::{Address, AddressSpaceUtil, U256};
 
 impl State {
     pub fn total_issued_tokens(&self) -> U256 {
-        self.global_stat.get::<TotalIssued>()
+        self.read_global_stat::<TotalIssued>()
     }
 
     /// Maintain `total_issued_tokens`.
@@ -18,8 +18,7 @@ impl State {
     /// Maintain `total_issued_tokens`. This is only used in the extremely
     /// unlikely case that there are a lot of partial invalid blocks.
     pub fn sub_total_issued(&mut self, v: U256) {
-        *self.global_stat.val::<TotalIssued>() =
-            self.global_stat.refr::<TotalIssued>().saturating_sub(v);
+        self.saturating_sub_global_stat::<TotalIssued>(v);
     }
 
     pub fn add_total_pos_staking(&mut self, v: U256) {
@@ -31,37 +30,35 @@ impl State {
     }
 
     pub fn sub_total_evm_tokens(&mut self, v: U256) {
-        *self.global_stat.val::<TotalEvmToken>() =
-            self.global_stat.refr::<TotalEvmToken>().saturating_sub(v);
+        self.saturating_sub_global_stat::<TotalEvmToken>(v);
     }
 
     pub fn total_staking_tokens(&self) -> U256 {
-        self.global_stat.get::<TotalStaking>()
+        self.read_global_stat::<TotalStaking>()
     }
 
     pub fn total_storage_tokens(&self) -> U256 {
-        self.global_stat.get::<TotalStorage>()
+        self.read_global_stat::<TotalStorage>()
     }
 
     pub fn total_espace_tokens(&self) -> U256 {
-        self.global_stat.get::<TotalEvmToken>()
+        self.read_global_stat::<TotalEvmToken>()
     }
 
     pub fn used_storage_points(&self) -> U256 {
-        self.global_stat.get::<UsedStoragePoints>()
+        self.read_global_stat::<UsedStoragePoints>()
     }
 
     pub fn converted_storage_points(&self) -> U256 {
-        self.global_stat.get::<ConvertedStoragePoints>()
+        self.read_global_stat::<ConvertedStoragePoints>()
     }
 
     pub fn total_pos_staking_tokens(&self) -> U256 {
-        self.global_stat.get::<TotalPosStaking>()
+        self.read_global_stat::<TotalPosStaking>()
     }
 
     pub fn sub_total_pos_staking(&mut self, v: U256) {
-        *self.global_stat.val::<TotalPosStaking>() =
-            self.global_stat.refr::<TotalPosStaking>().saturating_sub(v)
+        self.saturating_sub_global_stat::<TotalPosStaking>(v)
     }
 
     pub fn total_circulating_tokens(&self) -> DbResult<U256> {
@@ -74,11 +71,56 @@ impl State {
     pub fn add_converted_storage_point(
         &mut self, from_balance: U256, from_collateral: U256,
     ) {
-        *self.global_stat.val::<TotalIssued>() -=
-            from_balance + from_collateral;
-        *self.global_stat.val::<TotalStorage>() -= from_collateral;
+        self.sub_global_stat::<TotalIssued>(from_balance + from_collateral);
+        self.sub_global_stat::<TotalStorage>(from_collateral);
         *self.global_stat.val::<UsedStoragePoints>() += from_collateral;
         *self.global_stat.val::<ConvertedStoragePoints>() +=
             from_balance + from_collateral;
     }
 }
+
+impl State {
+    /// Reads a global statistic variable. On a speculative state, the read is
+    /// recorded, so the result is dropped if the preceding transactions have
+    /// changed the variable.
+    pub(super) fn read_global_stat<T: GlobalParamKey>(&self) -> U256 {
+        if let Some(speculation) = &self.speculation {
+            speculation.record_global_stat(T::ID);
+        }
+        self.global_stat.get::<T>()
+    }
+
+    /// Subtracts from a global statistic variable, which never underflows in
+    /// a valid execution.
+    pub(super) fn sub_global_stat<T: GlobalParamKey>(&mut self, v: U256) {
+        let Some(speculation) = &self.speculation else {
+            *self.global_stat.val::<T>() -= v;
+            return;
+        };
+        let value = self.global_stat.get::<T>();
+        match value.checked_sub(v) {
+            Some(remain) => speculation.record_global_stat_slack(T::ID, remain),
+            // The value may have been increased by the preceding
+            // transactions, which is unknown here.
+            None => speculation.require_serial(),
+        }
+        *self.global_stat.val::<T>() = value.overflowing_sub(v).0;
+    }
+
+    /// Subtracts from a global statistic variable, stopping at zero.
+    pub(super) fn saturating_sub_global_stat<T: GlobalParamKey>(
+        &mut self, v: U256,
+    ) {
+        let value = self.global_stat.get::<T>();
+        if let Some(speculation) = &self.speculation {
+            match value.checked_sub(v) {
+                Some(remain) => {
+                    speculation.record_global_stat_slack(T::ID, remain)
+                }
+                // The result depends on the exact value.
+                None => speculation.record_global_stat(T::ID),
+            }
+        }
+        *self.global_stat.val::<T>() = value.saturating_sub(v);
+    }
+}
@@ -45,8 +45,16 @@ mod storage_entry;
 
 mod reward;
 
+/// Implements the diff of the accounts changed by a transaction for parity's
+/// `stateDiff` trace.
+mod state_diff;
+
 mod state_override;
 
+/// Speculation: Implements the speculative execution of transactions on a
+/// snapshot of the `State`, and the validation and replay of their results.
+mod speculation;
+
 #[cfg(test)]
 mod tests;
 
@@ -55,13 +63,14 @@ pub use self::{
     commit::StateCommitResult,
     pos::{distribute_pos_interest, update_pos_status},
     reward::initialize_cip137,
+    speculation::{SpeculativeBase, SpeculativeResult},
     sponsor::COMMISSION_PRIVILEGE_SPECIAL_KEY,
     staking::initialize_or_update_dao_voted_params,
 };
 #[cfg(test)]
 pub use tests::{get_state_by_epoch_id, get_state_for_genesis_write};
 
-use self::checkpoints::CheckpointLayer;
+use self::{checkpoints::CheckpointLayer, speculation::Speculation};
 use super::{
     checkpoints::LazyDiscardedVec,
     global_stat::GlobalStat,
@@ -99,6 +108,10 @@ pub struct State {
     // TODO: it seems `RwLock` is not necessary here. But we need to change the
     // signature of `write_account` from `&self` to `&mut self` first
     checkpoints: RwLock<LazyDiscardedVec<CheckpointLayer>>,
+
+    /// Present if the state executes transactions speculatively on a
+    /// snapshot of another state.
+    speculation: Option<Speculation>,
 }
 
 impl State {
@@ -119,6 +132,7 @@ impl State {
             checkpoints: Default::default(),
             tx_access_list: None,
             global_stat: world_stat,
+            speculation: None,
         })
     }
 
@@ -30,17 +30,17 @@ impl State {
     }
 
     pub fn pow_base_reward(&self) -> U256 {
-        let base_reward = self.global_stat.get::<PowBaseReward>();
+        let base_reward = self.read_global_stat::<PowBaseReward>();
         assert!(!base_reward.is_zero());
         base_reward
     }
 
     pub fn distributable_pos_interest(&self) -> U256 {
-        self.global_stat.get::<DistributablePoSInterest>()
+        self.read_global_stat::<DistributablePoSInterest>()
     }
 
     pub fn last_distribute_block(&self) -> u64 {
-        self.global_stat.refr::<LastDistributeBlock>().as_u64()
+        self.read_global_stat::<LastDistributeBlock>().as_u64()
     }
 
     pub fn reset_pos_distribute_info(&mut self, current_block_number: u64) {
@@ -57,7 +57,7 @@ impl State {
     }
 
     pub fn get_base_price_prop(&self) -> U256 {
-        self.global_stat.get::<BaseFeeProp>()
+        self.read_global_stat::<BaseFeeProp>()
     }
 
     pub fn set_base_fee_prop(&mut self, val: U256) {
@@ -0,0 +1,222 @@
+//! Speculation: Supports executing transactions optimistically on a frozen
+//! snapshot of a canonical `State`, recording what they have read, and
+//! replaying the results on the canonical `State` if nothing they have read
+//! was changed in between.
+
+use super::{AccountEntry, GlobalStat, State};
+use cfx_statedb::{
+    global_params::TOTAL_GLOBAL_PARAMS, StateDbGeneric as StateDb,
+};
+use cfx_types::{AddressWithSpace, U256};
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+/// A snapshot of the committed cache and the global statistic variables of a
+/// canonical `State` between two transactions. Speculative states read through
+/// it before falling back to the database.
+pub struct SpeculativeBase {
+    committed_cache: HashMap<AddressWithSpace, AccountEntry>,
+    global_stat: GlobalStat,
+}
+
+/// The state changes and the accessed items of a speculatively executed
+/// transaction.
+pub struct SpeculativeResult {
+    accounts: HashMap<AddressWithSpace, AccountEntry>,
+    base_global_stat: GlobalStat,
+    global_stat: GlobalStat,
+    accesses: AccessRecord,
+}
+
+pub(super) struct Speculation {
+    base: Arc<SpeculativeBase>,
+    accesses: Mutex<AccessRecord>,
+}
+
+#[derive(Default)]
+struct AccessRecord {
+    /// Accounts loaded in any way, including the ones only touched for their
+    /// storage entries.
+    loaded_accounts: HashSet<AddressWithSpace>,
+    /// Accounts possibly modified.
+    written_accounts: HashSet<AddressWithSpace>,
+    /// Global statistic variables whose exact value has been observed.
+    global_stat: [bool; TOTAL_GLOBAL_PARAMS],
+    /// For the global statistic variables which have only been subtracted,
+    /// the minimum remaining value after a subtraction. The subtractions stay
+    /// valid as long as the canonical value does not drop by more than it.
+    global_stat_slack: [Option<U256>; TOTAL_GLOBAL_PARAMS],
+    /// The execution hit a case that can not be reasoned about speculatively.
+    requires_serial: bool,
+}
+
+impl Speculation {
+    pub(super) fn base_account(
+        &self, address: &AddressWithSpace,
+    ) -> Option<&AccountEntry> {
+        self.base.committed_cache.get(address)
+    }
+
+    pub(super) fn record_loaded_account(&self, address: &AddressWithSpace) {
+        self.accesses.lock().loaded_accounts.insert(*address);
+    }
+
+    pub(super) fn record_written_account(&self, address: &AddressWithSpace) {
+        let mut accesses = self.accesses.lock();
+        accesses.loaded_accounts.insert(*address);
+        accesses.written_accounts.insert(*address);
+    }
+
+    pub(super) fn record_global_stat(&self, id: usize) {
+        self.accesses.lock().global_stat[id] = true;
+    }
+
+    pub(super) fn record_global_stat_slack(&self, id: usize, remain: U256) {
+        let mut accesses = self.accesses.lock();
+        let slack = &mut accesses.global_stat_slack[id];
+        *slack = Some(slack.map_or(remain, |slack| slack.min(remain)));
+    }
+
+    pub(super) fn require_serial(&self) {
+        self.accesses.lock().requires_serial = true;
+    }
+}
+
+impl State {
+    /// Creates a state which executes transactions on top of `base`. The
+    /// `db` must be a view of the same database version as the canonical
+    /// state that `base` is taken from.
+    pub fn new_speculative(db: StateDb, base: Arc<SpeculativeBase>) -> Self {
+        State {
+            db,
+            cache: Default::default(),
+            committed_cache: Default::default(),
+            checkpoints: Default::default(),
+            tx_access_list: None,
+            global_stat: base.global_stat,
+            speculation: Some(Speculation {
+                base,
+                accesses: Default::default(),
+            }),
+        }
+    }
+
+    /// Moves the committed cache into a `SpeculativeBase`. The canonical state
+    /// must not be used until the base is returned by
+    /// `restore_speculative_base`.
+    pub fn take_speculative_base(&mut self) -> Arc<SpeculativeBase> {
+        assert!(self.no_checkpoint());
+        assert!(self.cache.get_mut().is_empty());
+        Arc::new(SpeculativeBase {
+            committed_cache: std::mem::take(&mut self.committed_cache),
+            global_stat: self.global_stat,
+        })
+    }
+
+    /// Takes back the committed cache from a `SpeculativeBase`. All the
+    /// speculative states built on it must have been dropped.
+    pub fn restore_speculative_base(&mut self, base: Arc<SpeculativeBase>) {
+        let base = Arc::try_unwrap(base)
+            .ok()
+            .expect("Speculative states should have been dropped");
+        assert!(self.committed_cache.is_empty());
+        self.committed_cache = base.committed_cache;
+    }
+
+    /// Extracts the result of the last transaction executed on a speculative
+    /// state, and resets the state for the next transaction.
+    pub fn take_speculative_result(&mut self) -> SpeculativeResult {
+        assert!(self.no_checkpoint());
+        let speculation =
+            self.speculation.as_ref().expect("Not a speculative state");
+        let base_global_stat = speculation.base.global_stat;
+        let accesses = std::mem::take(&mut *speculation.accesses.lock());
+
+        self.clear_tx_access_list();
+        let accounts = self
+            .cache
+            .get_mut()
+            .drain()
+            .map(|(address, account)| (address, account.entry))
+            .collect();
+        let global_stat =
+            std::mem::replace(&mut self.global_stat, base_global_stat);
+
+        SpeculativeResult {
+            accounts,
+            base_global_stat,
+            global_stat,
+            accesses,
+        }
+    }
+
+    /// Checks whether the speculative result is still valid on this state,
+    /// given the accounts changed since the `SpeculativeBase` was taken.
+    pub fn validate_speculative_result(
+        &self, result: &SpeculativeResult,
+        changed_accounts: &HashSet<AddressWithSpace>,
+    ) -> bool {
+        let accesses = &result.accesses;
+        if accesses.requires_serial
+            || !accesses.loaded_accounts.is_disjoint(changed_accounts)
+        {
+            return false;
+        }
+
+        let base = &result.base_global_stat;
+        (0..TOTAL_GLOBAL_PARAMS).all(|id| {
+            let current = self.global_stat.get_by_id(id);
+            if accesses.global_stat[id] {
+                current == base.get_by_id(id)
+            } else if let Some(slack) = accesses.global_stat_slack[id] {
+                current.saturating_add(slack) >= base.get_by_id(id)
+            } else {
+                true
+            }
+        })
+    }
+
+    /// Installs a validated speculative result into the cache, as if the
+    /// transaction had been executed on this state, and returns the accounts
+    /// it may have changed. The changes of the global statistic variables are
+    /// applied as deltas.
+    pub fn apply_speculative_result(
+        &mut self, result: SpeculativeResult,
+    ) -> HashSet<AddressWithSpace> {
+        assert!(self.no_checkpoint());
+        let cache = self.cache.get_mut();
+        assert!(cache.is_empty());
+        cache.extend(
+            result
+                .accounts
+                .into_iter()
+                .map(|(address, entry)| (address, entry.with_warm(false))),
+        );
+        self.global_stat
+            .apply_delta(&result.base_global_stat, &result.global_stat);
+        result.accesses.written_accounts
+    }
+
+    /// Returns the accounts marked dirty in the cache, which have not been
+    /// committed yet. It includes the accounts changed by the previous blocks
+    /// and only loaded since then.
+    pub fn dirty_cached_accounts(&self) -> Vec<AddressWithSpace> {
+        self.cache
+            .read()
+            .iter()
+            .filter(|(_, account)| account.is_dirty())
+            .map(|(address, _)| *address)
+            .collect()
+    }
+}
+
+impl State {
+    pub(super) fn record_speculative_write(&self, address: &AddressWithSpace) {
+        if let Some(speculation) = &self.speculation {
+            speculation.record_written_account(address);
+        }
+    }
+}
@@ -92,7 +92,7 @@ impl State {
         return_if!(amount.is_zero());
 
         let acc_interest_rate =
-            self.global_stat.get::<AccumulateInterestRate>();
+            self.read_global_stat::<AccumulateInterestRate>();
         self.write_account_ext_lock(
             &address.with_native_space(),
             RequireFields::DepositList,
@@ -113,7 +113,7 @@ impl State {
         return_if!(amount.is_zero());
 
         let accumulated_interest_rate =
-            self.global_stat.get::<AccumulateInterestRate>();
+            self.read_global_stat::<AccumulateInterestRate>();
         let interest = self
             .write_account_ext_lock(
                 &address.with_native_space(),
@@ -123,7 +123,7 @@ impl State {
 
         // the interest will be put in balance.
         self.add_total_issued(interest);
-        *self.global_stat.val::<TotalStaking>() -= *amount;
+        self.sub_global_stat::<TotalStaking>(*amount);
         Ok(interest)
     }
 }
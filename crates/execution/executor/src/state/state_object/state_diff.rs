@@ -0,0 +1,231 @@
+use super::{AccountEntry, OverlayAccount, RequireFields, State};
+use cfx_bytes::Bytes;
+use cfx_parity_trace_types::{AccountDiff, Diff, StateDiff};
+use cfx_statedb::{Result as DbResult, StateDbExt};
+use cfx_types::{AddressWithSpace, BigEndianHash, H256, U256};
+use keccak_hash::KECCAK_EMPTY;
+use primitives::{StorageKey, StorageValue};
+use std::collections::{BTreeMap, HashMap};
+
+/// The fields of an existing account compared in the state diff.
+struct AccountBasics {
+    balance: U256,
+    nonce: U256,
+    code_hash: H256,
+}
+
+impl From<&OverlayAccount> for AccountBasics {
+    fn from(account: &OverlayAccount) -> Self {
+        AccountBasics {
+            balance: *account.balance(),
+            nonce: *account.nonce(),
+            code_hash: account.code_hash(),
+        }
+    }
+}
+
+impl State {
+    /// Returns the changes of the accounts made by the transaction in
+    /// execution.
+    ///
+    /// It must be called after the transaction is executed and before the
+    /// cache is committed by `update_state_post_tx_execution`: the cache then
+    /// holds the accounts after the transaction, while the committed cache
+    /// and the db hold them before the transaction.
+    pub fn state_diff(&mut self) -> DbResult<StateDiff> {
+        assert!(self.no_checkpoint());
+
+        let mut killed_storage = self.killed_storage_before_tx()?;
+        let mut state_diff = StateDiff::default();
+        let cache = self.cache.read();
+        for (address, entry) in cache.iter() {
+            let AccountEntry::Cached(account, true) = &entry.entry else {
+                continue;
+            };
+            let pre = self.account_before_tx(address)?;
+            let post = (!account.removed_without_update())
+                .then(|| AccountBasics::from(account));
+
+            let code = match (&pre, &post) {
+                (Some(pre), Some(post)) if pre.code_hash == post.code_hash => {
+                    Diff::Same
+                }
+                _ => Diff::new(
+                    pre.as_ref()
+                        .map(|pre| self.code_before_tx(address, &pre.code_hash))
+                        .transpose()?,
+                    post.as_ref().map(|_| Self::code_after_tx(account)),
+                ),
+            };
+            let storage = match killed_storage.remove(address) {
+                Some(storage) => storage
+                    .into_iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(key, value)| {
+                        (key, Diff::Died(H256::from_uint(&value)))
+                    })
+                    .collect(),
+                None => {
+                    self.storage_diff(account, pre.is_some(), post.is_some())?
+                }
+            };
+
+            state_diff.insert(
+                *address,
+                AccountDiff {
+                    balance: Diff::new(
+                        pre.as_ref().map(|a| a.balance),
+                        post.as_ref().map(|a| a.balance),
+                    ),
+                    nonce: Diff::new(
+                        pre.as_ref().map(|a| a.nonce),
+                        post.as_ref().map(|a| a.nonce),
+                    ),
+                    code,
+                    storage,
+                },
+            );
+        }
+        Ok(state_diff)
+    }
+
+    /// Returns the storage before the transaction of the existing accounts
+    /// killed by it, all of which is removed. Iterating the db storage needs
+    /// a mutable db, so it is read before the cache is borrowed.
+    fn killed_storage_before_tx(
+        &mut self,
+    ) -> DbResult<HashMap<AddressWithSpace, BTreeMap<H256, U256>>> {
+        let killed: Vec<AddressWithSpace> = self
+            .cache
+            .read()
+            .iter()
+            .filter_map(|(address, entry)| match &entry.entry {
+                AccountEntry::Cached(account, true)
+                    if account.removed_without_update() =>
+                {
+                    Some(*address)
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut killed_storage = HashMap::new();
+        for address in killed {
+            if self.account_before_tx(&address)?.is_none() {
+                continue;
+            }
+            let committed = match self.committed_cache.get(&address) {
+                Some(AccountEntry::Cached(account, _)) => Some(account),
+                _ => None,
+            };
+            // The db storage is invalid if the account was cleared or created
+            // by an earlier transaction of the epoch.
+            let mut storage =
+                if committed.map_or(false, |account| account.fresh_storage()) {
+                    BTreeMap::new()
+                } else {
+                    self.db.get_account_storage_entries(&address, None)?
+                };
+            for (key, value) in
+                committed.map_or(vec![], |account| account.committed_storage())
+            {
+                if key.len() == 32 {
+                    storage.insert(H256::from_slice(&key), value);
+                }
+            }
+            killed_storage.insert(address, storage);
+        }
+        Ok(killed_storage)
+    }
+
+    fn account_before_tx(
+        &self, address: &AddressWithSpace,
+    ) -> DbResult<Option<AccountBasics>> {
+        Ok(match self.committed_cache.get(address) {
+            Some(AccountEntry::Cached(account, _))
+                if !account.removed_without_update() =>
+            {
+                Some(AccountBasics::from(account))
+            }
+            Some(_) => None,
+            None => {
+                self.db.get_account(address)?.map(|account| AccountBasics {
+                    balance: account.balance,
+                    nonce: account.nonce,
+                    code_hash: account.code_hash,
+                })
+            }
+        })
+    }
+
+    fn code_before_tx(
+        &self, address: &AddressWithSpace, code_hash: &H256,
+    ) -> DbResult<Bytes> {
+        if *code_hash == KECCAK_EMPTY {
+            return Ok(Bytes::new());
+        }
+        if let Some(AccountEntry::Cached(account, _)) =
+            self.committed_cache.get(address)
+        {
+            if !account.should_load_ext_fields(RequireFields::Code) {
+                return Ok(Self::code_after_tx(account));
+            }
+        }
+        Ok(self
+            .db
+            .get_code(address, code_hash)?
+            .map(|info| (*info.code).clone())
+            .unwrap_or_default())
+    }
+
+    /// The code of an account changed by the transaction is always loaded.
+    fn code_after_tx(account: &OverlayAccount) -> Bytes {
+        account
+            .code()
+            .map(|code| (*code).clone())
+            .unwrap_or_default()
+    }
+
+    fn storage_diff(
+        &self, account: &OverlayAccount, existed: bool, exists: bool,
+    ) -> DbResult<BTreeMap<H256, Diff<H256>>> {
+        let mut storage = BTreeMap::new();
+        for (key, value) in account.written_storage() {
+            // Some internal contracts of Core Space use keys which are not
+            // storage slots, they can not be represented in the diff.
+            if key.len() != 32 {
+                continue;
+            }
+            let diff = match (existed, exists) {
+                (true, true) => Diff::new(
+                    Some(self.storage_before_tx(account, &key)?),
+                    Some(value),
+                ),
+                (false, true) if !value.is_zero() => Diff::Born(value),
+                _ => Diff::Same,
+            };
+            if !diff.is_same() {
+                storage.insert(
+                    H256::from_slice(&key),
+                    diff.map(|v| H256::from_uint(&v)),
+                );
+            }
+        }
+        Ok(storage)
+    }
+
+    fn storage_before_tx(
+        &self, account: &OverlayAccount, key: &[u8],
+    ) -> DbResult<U256> {
+        if let Some(value) = account.origin_storage_at(key) {
+            return Ok(value);
+        }
+        let address = account.address();
+        let storage_key = StorageKey::new_storage_key(&address.address, key)
+            .with_space(address.space);
+        Ok(self
+            .db
+            .get::<StorageValue>(storage_key)?
+            .map_or(U256::zero(), |entry| entry.value))
+    }
+}
@@ -2,7 +2,7 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use super::{State, Substate};
+use super::{SpeculativeResult, State, Substate};
 use crate::internal_contract::{
     initialize_internal_contract_accounts, InternalContractMap,
 };
@@ -15,6 +15,7 @@ use cfx_types::{
 };
 use keccak_hash::{keccak, KECCAK_EMPTY};
 use primitives::{EpochId, StorageKey, StorageLayout};
+use std::collections::HashSet;
 
 pub fn get_state_by_epoch_id(epoch_id: &EpochId) -> State {
     State::new(StateDb::new_for_unit_test_with_epoch(epoch_id)).unwrap()
@@ -864,6 +865,117 @@ fn create_contract_fail() {
         .unwrap();
 }
 
+#[test]
+fn state_diff_of_transaction() {
+    use cfx_parity_trace_types::Diff;
+    use cfx_types::Space;
+
+    let mut state = get_state_for_genesis_write();
+    let a = Address::from_low_u64_be(1000).with_evm_space();
+    let b = Address::from_low_u64_be(1001).with_evm_space();
+    let key = u256_to_vec(&U256::from(1));
+
+    // The first transaction of the epoch.
+    state.add_balance(&a, &U256::from(100)).unwrap();
+    state.update_state_post_tx_execution(false);
+
+    // The second transaction of the epoch.
+    state.add_balance(&a, &U256::from(5)).unwrap();
+    state
+        .set_storage(
+            &a,
+            key.clone(),
+            U256::from(3),
+            Address::zero(),
+            &mut Substate::new(),
+        )
+        .unwrap();
+    state.add_balance(&b, &U256::from(7)).unwrap();
+
+    let diff: Vec<_> = state
+        .state_diff()
+        .unwrap()
+        .into_space(Space::Ethereum)
+        .collect();
+    assert_eq!(diff.len(), 2);
+
+    let (address, a_diff) = &diff[0];
+    assert_eq!(*address, a.address);
+    assert_eq!(
+        a_diff.balance,
+        Diff::Changed {
+            from: U256::from(100),
+            to: U256::from(105)
+        }
+    );
+    assert_eq!(a_diff.nonce, Diff::Same);
+    assert_eq!(a_diff.code, Diff::Same);
+    assert_eq!(
+        a_diff
+            .storage
+            .get(&BigEndianHash::from_uint(&U256::from(1))),
+        Some(&Diff::Changed {
+            from: BigEndianHash::from_uint(&U256::zero()),
+            to: BigEndianHash::from_uint(&U256::from(3)),
+        })
+    );
+
+    let (address, b_diff) = &diff[1];
+    assert_eq!(*address, b.address);
+    assert_eq!(b_diff.balance, Diff::Born(U256::from(7)));
+    assert_eq!(b_diff.nonce, Diff::Born(U256::zero()));
+    assert_eq!(b_diff.code, Diff::Born(vec![]));
+    assert!(b_diff.storage.is_empty());
+}
+
+#[test]
+fn state_diff_of_killed_account() {
+    use cfx_parity_trace_types::Diff;
+    use cfx_types::Space;
+
+    let mut state_0 = get_state_for_genesis_write();
+    let mut a = Address::zero();
+    a.set_contract_type_bits();
+    let a_s = a.with_native_space();
+    let k = u256_to_vec(&U256::from(0));
+    state_0.checkpoint();
+    state_0
+        .new_contract_with_code(&a_s, *COLLATERAL_DRIPS_PER_STORAGE_KEY)
+        .unwrap();
+    state_0
+        .set_storage(&a_s, k.clone(), U256::one(), a, &mut Substate::new())
+        .unwrap();
+    state_0
+        .set_storage_layout(&a_s, StorageLayout::Regular(0))
+        .unwrap();
+    state_0
+        .add_sponsor_balance_for_collateral(
+            &a,
+            &COLLATERAL_DRIPS_PER_STORAGE_KEY,
+        )
+        .unwrap();
+    state_0.discard_checkpoint();
+
+    let epoch_id_1 = EpochId::from_uint(&U256::from(1));
+    state_0
+        .commit(epoch_id_1, /* debug_record = */ None)
+        .unwrap();
+
+    let mut state = get_state_by_epoch_id(&epoch_id_1);
+    state.remove_contract(&a_s).unwrap();
+
+    let diff = state.state_diff().unwrap();
+    let (_, a_diff) = diff
+        .into_space(Space::Native)
+        .find(|(address, _)| *address == a)
+        .unwrap();
+    assert_eq!(a_diff.storage.len(), 1);
+    assert_eq!(
+        a_diff.storage.get(&BigEndianHash::from_uint(&U256::zero())),
+        Some(&Diff::Died(BigEndianHash::from_uint(&U256::one())))
+    );
+}
+
 #[test]
 fn create_contract_fail_previous_storage() {
     let mut state = get_state_for_genesis_write();
@@ -1191,6 +1303,70 @@ fn test_automatic_collateral_normal_account() {
     );
 }
 
+#[test]
+fn replay_speculative_results() {
+    let mut state = get_state_for_genesis_write();
+    let mut a = Address::zero();
+    a.set_user_account_type_bits();
+    let a = a.with_native_space();
+    let mut b = Address::from_low_u64_be(1);
+    b.set_user_account_type_bits();
+    let b = b.with_native_space();
+
+    state.add_balance(&a, &U256::from(100)).unwrap();
+    state.add_balance(&b, &U256::from(100)).unwrap();
+    state.add_total_issued(U256::from(1000));
+    state.commit_cache(false);
+    let total_issued = state.total_issued_tokens();
+
+    let base = state.take_speculative_base();
+    let mut speculative =
+        State::new_speculative(StateDb::new_for_unit_test(), base.clone());
+
+    speculative.sub_balance(&a, &U256::from(10)).unwrap();
+    speculative.add_total_issued(U256::from(1));
+    let first = speculative.take_speculative_result();
+
+    speculative.add_balance(&a, &U256::from(5)).unwrap();
+    let second = speculative.take_speculative_result();
+
+    speculative.add_balance(&b, &U256::from(7)).unwrap();
+    speculative.add_total_issued(U256::from(2));
+    let third = speculative.take_speculative_result();
+
+    speculative.sub_total_issued(U256::from(3));
+    let fourth = speculative.take_speculative_result();
+
+    let _ = speculative.total_issued_tokens();
+    let fifth = speculative.take_speculative_result();
+
+    drop(speculative);
+    state.restore_speculative_base(base);
+
+    let mut changed = HashSet::new();
+    let mut replay = |state: &mut State, result: SpeculativeResult| {
+        if !state.validate_speculative_result(&result, &changed) {
+            return false;
+        }
+        changed.extend(state.apply_speculative_result(result));
+        state.commit_cache(false);
+        true
+    };
+
+    assert!(replay(&mut state, first));
+    // The balance of `a` has been changed by the first result.
+    assert!(!replay(&mut state, second));
+    assert!(replay(&mut state, third));
+    // Subtraction only requires the total issued tokens to be large enough.
+    assert!(replay(&mut state, fourth));
+    // Reading the exact value conflicts with the previous changes.
+    assert!(!replay(&mut state, fifth));
+
+    assert_eq!(state.balance(&a).unwrap(), U256::from(90));
+    assert_eq!(state.balance(&b).unwrap(), U256::from(107));
+    assert_eq!(state.total_issued_tokens(), total_issued);
+}
+
 // #[test]
 // fn test_automatic_collateral_contract_account() {
 //     let storage_manager = new_state_manager_for_unit_test();
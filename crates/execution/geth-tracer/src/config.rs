@@ -27,7 +27,9 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 use alloy_rpc_types_trace::{
-    geth::{CallConfig, GethDefaultTracingOptions, PreStateConfig},
+    geth::{
+        CallConfig, FlatCallConfig, GethDefaultTracingOptions, PreStateConfig,
+    },
     parity::TraceType,
 };
 use std::collections::HashSet;
@@ -176,6 +178,18 @@ impl TracingInspectorConfig {
         Self::none()
     }
 
+    /// Returns a config for geth's
+    /// [FlatCallTracer](alloy_rpc_trace_types::geth::FlatCallFrame).
+    ///
+    /// This returns [Self::default_parity] and includes precompile calls if
+    /// configured in the given [FlatCallConfig]
+    #[inline]
+    pub fn from_flat_call_config(config: &FlatCallConfig) -> Self {
+        Self::default_parity().set_exclude_precompile_calls(
+            !config.include_precompiles.unwrap_or_default(),
+        )
+    }
+
     /// Configure whether calls to precompiles should be ignored.
     ///
     /// If set to `true`, calls to precompiles without value transfers will be
@@ -1,8 +1,10 @@
 use crate::{
     config::TracingInspectorConfig,
     fourbyte::FourByteInspector,
+    js_tracer::{JsTracer, JsTracerError},
+    mux::MuxInspector,
     tracing_inspector::TracingInspector,
-    types::{LogCallOrder, TxExecContext},
+    types::{CallKind, LogCallOrder, TxExecContext},
     utils::{to_alloy_address, to_alloy_h256, to_alloy_u256},
 };
 use alloy_primitives::{Address, Bytes, LogData};
@@ -18,9 +20,12 @@ use cfx_executor::{
         OpcodeTracer, SetAuthTracer, StorageTracer,
     },
     stack::{FrameResult, FrameReturn},
+    state::State as CfxState,
 };
 use cfx_types::{Space, H160};
-use cfx_vm_types::{ActionParams, CallType, Error, InterpreterInfo};
+use cfx_vm_types::{
+    ActionParams, CallType, CreateType, Error, InterpreterInfo,
+};
 use revm::{database::InMemoryDB, state::EvmState as State};
 use revm_interpreter::{Gas, InstructionResult, InterpreterResult};
 
@@ -30,6 +35,11 @@ pub struct GethTracer {
     inner: TracingInspector,
     //
     fourbyte_inspector: FourByteInspector,
+    // set for `GethDebugTracerType::JsTracer`, holds the error if the js code
+    // can not be loaded
+    js_tracer: Option<Result<JsTracer, JsTracerError>>,
+    // set for `MuxTracer`, which runs the sub tracers instead of this one
+    mux_tracer: Option<MuxInspector>,
     //
     tx_gas_limit: u64, // tx level gas limit
     //
@@ -62,7 +72,14 @@ impl GethTracer {
                             .expect("should success");
                         TracingInspectorConfig::from_geth_call_config(&c)
                     }
-                    FlatCallTracer => TracingInspectorConfig::none(),
+                    FlatCallTracer => {
+                        let c = opts
+                            .tracer_config
+                            .clone()
+                            .into_flat_call_config()
+                            .expect("should success");
+                        TracingInspectorConfig::from_flat_call_config(&c)
+                    }
                     PreStateTracer => {
                         let c = opts
                             .tracer_config
@@ -79,9 +96,42 @@ impl GethTracer {
             None => TracingInspectorConfig::from_geth_config(&opts.config),
         };
 
+        let js_tracer = match &opts.tracer {
+            Some(GethDebugTracerType::JsTracer(code)) => {
+                Some(JsTracer::from_opts(code, &opts).map(|mut tracer| {
+                    tracer.set_tx_exec_context(&tx_exec_context);
+                    tracer.register_precompiles(
+                        machine
+                            .builtins_evm()
+                            .iter()
+                            .filter(|(_, builtin)| {
+                                builtin.is_active(tx_exec_context.block_number)
+                            })
+                            .map(|(address, _)| *address),
+                    );
+                    tracer
+                }))
+            }
+            _ => None,
+        };
+
+        let mux_tracer = match &opts.tracer {
+            Some(GethDebugTracerType::BuiltInTracer(MuxTracer)) => {
+                let c = opts
+                    .tracer_config
+                    .clone()
+                    .into_mux_config()
+                    .expect("should success");
+                Some(MuxInspector::new(c, &tx_exec_context, &machine, &opts))
+            }
+            _ => None,
+        };
+
         Self {
             inner: TracingInspector::new(config, machine, tx_exec_context),
             fourbyte_inspector: FourByteInspector::new(),
+            js_tracer,
+            mux_tracer,
             tx_gas_limit,
             depth: 0,
             gas_left: tx_gas_limit,
@@ -97,7 +147,7 @@ impl GethTracer {
                     Some(builtin_tracer)
                 }
                 GethDebugTracerType::JsTracer(_) => {
-                    // not supported
+                    // handled by `js_tracer`
                     Some(NoopTracer)
                 }
             },
@@ -117,9 +167,24 @@ impl GethTracer {
         self.tracer_type() == Some(FourByteTracer)
     }
 
+    fn js_tracer(&mut self) -> Option<&mut JsTracer> {
+        self.js_tracer.as_mut()?.as_mut().ok()
+    }
+
     pub fn gas_used(&self) -> u64 { self.tx_gas_limit - self.gas_left }
 
-    pub fn drain(self) -> GethTrace {
+    pub fn drain(self) -> Result<GethTrace, String> {
+        if let Some(js_tracer) = self.js_tracer {
+            return js_tracer
+                .and_then(JsTracer::into_result)
+                .map(GethTrace::JS)
+                .map_err(|err| err.to_string());
+        }
+
+        if let Some(mux_tracer) = self.mux_tracer {
+            return mux_tracer.drain();
+        }
+
         let trace = match self.tracer_type() {
             Some(t) => match t {
                 FourByteTracer => self.fourbyte_inspector.drain(),
@@ -145,7 +210,17 @@ impl GethTracer {
                         .unwrap();
                     GethTrace::PreStateTracer(frame)
                 }
-                NoopTracer | MuxTracer | FlatCallTracer => {
+                FlatCallTracer => {
+                    let gas_used = self.gas_used();
+                    let info = self.inner.tx_exec_context().transaction_info();
+                    let traces = self
+                        .inner
+                        .into_parity_builder()
+                        .with_transaction_gas_used(gas_used)
+                        .into_localized_transaction_traces(info);
+                    GethTrace::FlatCallTracer(traces)
+                }
+                NoopTracer | MuxTracer => {
                     GethTrace::NoopTracer(NoopFrame::default())
                 }
             },
@@ -166,7 +241,7 @@ impl GethTracer {
             }
         };
 
-        trace
+        Ok(trace)
     }
 }
 
@@ -179,7 +254,7 @@ impl DrainTrace for GethTracer {
 pub struct GethTraceKey;
 
 impl typemap::Key for GethTraceKey {
-    type Value = GethTrace;
+    type Value = Result<GethTrace, String>;
 }
 
 impl CheckpointTracer for GethTracer {}
@@ -192,6 +267,16 @@ impl SetAuthTracer for GethTracer {}
 
 impl CallTracer for GethTracer {
     fn record_call(&mut self, params: &ActionParams) {
+        if let Some(js_tracer) = self.js_tracer() {
+            js_tracer.record_call(params);
+            return;
+        }
+
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.record_call(params);
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             self.fourbyte_inspector.record_call(params);
             return;
@@ -243,6 +328,11 @@ impl CallTracer for GethTracer {
     }
 
     fn record_call_result(&mut self, result: &FrameResult) {
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.record_call_result(result);
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
@@ -276,6 +366,16 @@ impl CallTracer for GethTracer {
     }
 
     fn record_create(&mut self, params: &ActionParams) {
+        if let Some(js_tracer) = self.js_tracer() {
+            js_tracer.record_call(params);
+            return;
+        }
+
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.record_create(params);
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
@@ -294,11 +394,21 @@ impl CallTracer for GethTracer {
             to_alloy_u256(params.value.value())
         };
 
+        let kind = match params.create_type {
+            CreateType::CREATE2 => CallKind::Create2,
+            _ => CallKind::Create,
+        };
+
         self.inner.start_trace_on_call(
             Address::default(), // call_result will set this address
-            params.data.clone().unwrap_or_default().into(),
+            params
+                .code
+                .as_ref()
+                .map(|c| c.to_vec())
+                .unwrap_or_default()
+                .into(),
             value,
-            params.call_type.into(),
+            kind,
             to_alloy_address(params.sender),
             params.gas.as_u64(),
             Some(false),
@@ -310,6 +420,11 @@ impl CallTracer for GethTracer {
     }
 
     fn record_create_result(&mut self, result: &FrameResult) {
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.record_create_result(result);
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
@@ -349,6 +464,34 @@ impl CallTracer for GethTracer {
         self.inner
             .fill_trace_on_call_end(outcome, create_address, gas_spent);
     }
+
+    fn record_call_result_with_state(
+        &mut self, result: &FrameResult, state: &CfxState,
+    ) {
+        if let Some(js_tracer) = self.js_tracer() {
+            js_tracer.record_call_result(result, state);
+            return;
+        }
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.record_call_result_with_state(result, state);
+            return;
+        }
+        self.record_call_result(result);
+    }
+
+    fn record_create_result_with_state(
+        &mut self, result: &FrameResult, state: &CfxState,
+    ) {
+        if let Some(js_tracer) = self.js_tracer() {
+            js_tracer.record_call_result(result, state);
+            return;
+        }
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.record_create_result_with_state(result, state);
+            return;
+        }
+        self.record_create_result(result);
+    }
 }
 
 impl OpcodeTracer for GethTracer {
@@ -356,15 +499,36 @@ impl OpcodeTracer for GethTracer {
         if self.inner.config.record_steps {
             *enabled |= true;
         }
+        if let Some(Ok(js_tracer)) = &self.js_tracer {
+            *enabled |= js_tracer.do_trace_opcode();
+        }
+        if let Some(mux_tracer) = &self.mux_tracer {
+            mux_tracer.do_trace_opcode(enabled);
+        }
     }
 
     fn initialize_interp(&mut self, gas_limit: cfx_types::U256) {
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.initialize_interp(gas_limit);
+            return;
+        }
+
         self.inner
             .gas_inspector
             .set_gas_remainning(gas_limit.as_u64());
     }
 
     fn step(&mut self, interp: &dyn InterpreterInfo) {
+        if let Some(js_tracer) = self.js_tracer() {
+            js_tracer.step(interp);
+            return;
+        }
+
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.step(interp);
+            return;
+        }
+
         self.inner
             .gas_inspector
             .set_gas_remainning(interp.gas_remainning().as_u64());
@@ -375,6 +539,11 @@ impl OpcodeTracer for GethTracer {
     }
 
     fn step_end(&mut self, interp: &dyn InterpreterInfo) {
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.step_end(interp);
+            return;
+        }
+
         let remainning = interp.gas_remainning().as_u64();
         let last_gas_cost = self
             .inner
@@ -390,6 +559,20 @@ impl OpcodeTracer for GethTracer {
         }
     }
 
+    fn step_end_with_state(
+        &mut self, interp: &dyn InterpreterInfo, state: &CfxState,
+    ) {
+        if let Some(js_tracer) = self.js_tracer() {
+            js_tracer.step_end(interp, state);
+            return;
+        }
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.step_end_with_state(interp, state);
+            return;
+        }
+        self.step_end(interp);
+    }
+
     fn log(
         &mut self, _address: &cfx_types::Address,
         topics: &Vec<cfx_types::H256>, data: &[u8],
@@ -406,9 +589,19 @@ impl OpcodeTracer for GethTracer {
     }
 
     fn selfdestruct(
-        &mut self, space: Space, _contract: &cfx_types::Address,
-        target: &cfx_types::Address, _value: cfx_types::U256,
+        &mut self, space: Space, contract: &cfx_types::Address,
+        target: &cfx_types::Address, value: cfx_types::U256,
     ) {
+        if let Some(js_tracer) = self.js_tracer() {
+            js_tracer.selfdestruct(contract, target, value);
+            return;
+        }
+
+        if let Some(mux_tracer) = &mut self.mux_tracer {
+            mux_tracer.selfdestruct(space, contract, target, value);
+            return;
+        }
+
         if self.is_fourbyte_tracer() {
             return;
         }
@@ -464,3 +657,197 @@ pub fn to_instruction_result(frame_result: &FrameResult) -> InstructionResult {
     };
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rpc_types_trace::{
+        geth::{mux::MuxConfig, CallFrame, GethDebugTracerConfig},
+        parity::{Action, CreateOutput, CreationMethod, TraceOutput},
+    };
+    use cfx_executor::{machine::VmFactory, spec::CommonParams};
+    use cfx_types::H256;
+    use cfx_vm_types::ActionValue;
+    use serde_json::{json, Value};
+
+    const CALLER: H160 = H160([0x11; 20]);
+    const CONTRACT: H160 = H160([0x22; 20]);
+    const CREATED: H160 = H160([0x33; 20]);
+    const INIT_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xf3];
+
+    fn frame_return(
+        gas_left: u64, return_data: Vec<u8>, create_address: Option<H160>,
+    ) -> FrameResult {
+        Ok(FrameReturn {
+            space: Space::Ethereum,
+            gas_left: gas_left.into(),
+            apply_state: true,
+            return_data: return_data.into(),
+            create_address,
+            substate: None,
+        })
+    }
+
+    /// Traces a transaction calling `CONTRACT`, which deploys `CREATED` with
+    /// CREATE2.
+    fn trace(tracer: GethDebugBuiltInTracerType, config: Value) -> GethTrace {
+        let tx_exec_context = TxExecContext {
+            tx_gas_limit: 100_000,
+            block_number: 1,
+            block_height: 1,
+            tx_hash: H256::repeat_byte(1),
+            tx_index: 0,
+            block_hash: H256::repeat_byte(2),
+        };
+        let machine = Arc::new(Machine::new_with_builtin(
+            CommonParams::default(),
+            VmFactory::new(1024 * 32),
+            &Default::default(),
+            &Default::default(),
+        ));
+        let opts = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(tracer)),
+            tracer_config: GethDebugTracerConfig(config),
+            ..Default::default()
+        };
+        let mut tracer = GethTracer::new(tx_exec_context, machine, opts);
+
+        tracer.record_call(&ActionParams {
+            space: Space::Ethereum,
+            code_address: CONTRACT,
+            address: CONTRACT,
+            sender: CALLER,
+            gas: 90_000.into(),
+            data: Some(vec![1, 2, 3, 4]),
+            ..Default::default()
+        });
+        tracer.record_create(&ActionParams {
+            space: Space::Ethereum,
+            sender: CONTRACT,
+            gas: 50_000.into(),
+            value: ActionValue::Transfer(7.into()),
+            code: Some(Arc::new(INIT_CODE.to_vec())),
+            call_type: CallType::None,
+            create_type: CreateType::CREATE2,
+            ..Default::default()
+        });
+        tracer.record_create_result(&frame_return(
+            40_000,
+            vec![0xfe],
+            Some(CREATED),
+        ));
+        tracer.record_call_result(&frame_return(70_000, vec![], None));
+        tracer.drain().unwrap()
+    }
+
+    #[test]
+    fn test_call_tracer_create() {
+        let GethTrace::CallTracer(frame) = trace(CallTracer, Value::Null)
+        else {
+            panic!("expected a call frame");
+        };
+        assert_eq!(frame.typ, "CALL");
+        assert_eq!(frame.gas_used, alloy_primitives::U256::from(30_000));
+        assert_eq!(frame.calls.len(), 1);
+
+        // The input of a create frame is the init code.
+        let create = &frame.calls[0];
+        assert_eq!(
+            create,
+            &CallFrame {
+                typ: "CREATE2".into(),
+                from: to_alloy_address(CONTRACT),
+                to: Some(to_alloy_address(CREATED)),
+                value: Some(alloy_primitives::U256::from(7)),
+                gas: alloy_primitives::U256::from(50_000),
+                gas_used: alloy_primitives::U256::from(10_000),
+                input: Bytes::from(INIT_CODE),
+                output: Some(Bytes::from(vec![0xfe])),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_flat_call_tracer() {
+        let GethTrace::FlatCallTracer(traces) =
+            trace(FlatCallTracer, Value::Null)
+        else {
+            panic!("expected flat call traces");
+        };
+        assert_eq!(traces.len(), 2);
+        for trace in &traces {
+            assert_eq!(
+                trace.transaction_hash,
+                Some(to_alloy_h256(H256::repeat_byte(1)))
+            );
+            assert_eq!(
+                trace.block_hash,
+                Some(to_alloy_h256(H256::repeat_byte(2)))
+            );
+        }
+
+        let call = &traces[0].trace;
+        assert!(matches!(call.action, Action::Call(_)));
+        assert_eq!(call.subtraces, 1);
+        assert_eq!(call.result.as_ref().unwrap().gas_used(), 30_000);
+
+        let create = &traces[1].trace;
+        assert_eq!(create.trace_address, vec![0]);
+        let Action::Create(action) = &create.action else {
+            panic!("expected a create action");
+        };
+        assert_eq!(action.from, to_alloy_address(CONTRACT));
+        assert_eq!(action.init, Bytes::from(INIT_CODE));
+        assert_eq!(action.creation_method, CreationMethod::Create2);
+        assert_eq!(
+            create.result,
+            Some(TraceOutput::Create(CreateOutput {
+                gas_used: 10_000,
+                code: Bytes::from(vec![0xfe]),
+                address: to_alloy_address(CREATED),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_mux_tracer() {
+        let config = json!({
+            "callTracer": { "onlyTopCall": true },
+            "flatCallTracer": {},
+            "4byteTracer": null,
+        });
+        let GethTrace::MuxTracer(frame) = trace(MuxTracer, config) else {
+            panic!("expected a mux frame");
+        };
+        assert_eq!(frame.0.len(), 3);
+        assert_eq!(
+            frame.0[&CallTracer],
+            trace(CallTracer, json!({ "onlyTopCall": true }))
+        );
+        assert_eq!(
+            frame.0[&FlatCallTracer],
+            trace(FlatCallTracer, Value::Null)
+        );
+        assert_eq!(
+            frame.0[&FourByteTracer],
+            trace(FourByteTracer, Value::Null)
+        );
+
+        let nested: MuxConfig = serde_json::from_value(json!({
+            "callTracer": {},
+            "muxTracer": { "callTracer": {} },
+        }))
+        .unwrap();
+        assert!(crate::check_mux_config(&nested).is_err());
+        let prestate: MuxConfig = serde_json::from_value(json!({
+            "callTracer": {},
+            "prestateTracer": { "diffMode": true },
+        }))
+        .unwrap();
+        assert!(crate::check_mux_config(&prestate).is_err());
+        let flat: MuxConfig =
+            serde_json::from_value(json!({ "callTracer": {} })).unwrap();
+        assert!(crate::check_mux_config(&flat).is_ok());
+    }
+}
@@ -0,0 +1,968 @@
+// Copyright 2023-2024 Paradigm.xyz
+// This file is part of reth.
+// Reth is a modular, contributor-friendly and blazing-fast implementation of
+// the Ethereum protocol
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+//! Objects passed to the JS tracer functions
+
+use super::builtins::{
+    address_to_byte_array, address_to_byte_array_value, bytes_to_address,
+    bytes_to_hash, from_buf_value, to_bigint, to_byte_array,
+    to_byte_array_value,
+};
+use crate::utils::{from_alloy_address, to_alloy_u256};
+use alloy_primitives::{Address, Bytes, U256};
+use boa_engine::{
+    js_string,
+    native_function::NativeFunction,
+    object::{builtins::JsUint8Array, FunctionObjectBuilder},
+    Context, JsArgs, JsError, JsNativeError, JsObject, JsResult, JsValue,
+};
+use boa_gc::{empty_trace, Finalize, Trace};
+use cfx_executor::state::State;
+use cfx_types::{AddressSpaceUtil, AddressWithSpace, Space, U256 as CfxU256};
+use revm_bytecode::OpCode;
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+/// `memory.slice` zero-pads reads beyond the memory size up to this many
+/// bytes, like geth does.
+pub(crate) const MEMORY_PAD_LIMIT: usize = 1024 * 1024;
+
+/// Creates a native function that returns via [JsValue::from].
+macro_rules! js_value_getter {
+    ($value:ident, $ctx:ident) => {
+        FunctionObjectBuilder::new(
+            $ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, _ctx| {
+                Ok(JsValue::from($value))
+            }),
+        )
+        .length(0)
+        .build()
+    };
+}
+
+/// Creates a native function that returns a captured JsValue.
+macro_rules! js_value_capture_getter {
+    ($value:ident, $ctx:ident) => {
+        FunctionObjectBuilder::new(
+            $ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, _args, input, _ctx| {
+                    Ok(JsValue::from(input.clone()))
+                },
+                $value,
+            ),
+        )
+        .length(0)
+        .build()
+    };
+}
+
+fn js_error(msg: String) -> JsError {
+    JsError::from_native(JsNativeError::error().with_message(msg))
+}
+
+fn js_type_error(msg: String) -> JsError {
+    JsError::from_native(JsNativeError::typ().with_message(msg))
+}
+
+/// A garbage collectible handle to a value that stops giving access to the
+/// value once its [GcGuard] is dropped.
+///
+/// boa requires captured values to be `'static`, while the tracer only
+/// borrows the interpreter snapshot and the state for the duration of a
+/// callback. JS code may keep the objects it receives (e.g. `this.db = db`),
+/// so every access after the callback returns fails instead of reading a
+/// dangling reference.
+struct GuardedNullableGc<Val: 'static> {
+    /// The lifetime is a lie to make it possible to use a reference in boa
+    /// which requires 'static
+    inner: Rc<RefCell<Option<&'static Val>>>,
+}
+
+impl<Val: 'static> GuardedNullableGc<Val> {
+    /// Creates a garbage collectible handle to the given reference.
+    ///
+    /// The returned guard must be dropped before the value is.
+    fn new_ref(val: &Val) -> (Self, GcGuard<'_, Val>) {
+        let inner = Rc::new(RefCell::new(Some(val)));
+        let guard = GcGuard {
+            inner: Rc::clone(&inner),
+        };
+
+        // SAFETY: guard enforces that the value is removed from the refcell
+        // before it is dropped.
+        #[allow(clippy::missing_transmute_annotations)]
+        let this = Self {
+            inner: unsafe { std::mem::transmute(inner) },
+        };
+
+        (this, guard)
+    }
+
+    /// Executes the given closure with a reference to the inner value if it
+    /// is still present.
+    fn with_inner<F, R>(&self, f: F) -> Option<R>
+    where F: FnOnce(&Val) -> R {
+        let val = *self.inner.borrow();
+        val.map(f)
+    }
+}
+
+impl<Val: 'static> Clone for GuardedNullableGc<Val> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+impl<Val: 'static> Finalize for GuardedNullableGc<Val> {}
+
+unsafe impl<Val: 'static> Trace for GuardedNullableGc<Val> {
+    empty_trace!();
+}
+
+/// Guards the inner reference, once this value is dropped the reference is
+/// removed from every [GuardedNullableGc] handle.
+#[must_use]
+pub(crate) struct GcGuard<'a, Val> {
+    inner: Rc<RefCell<Option<&'a Val>>>,
+}
+
+impl<'a, Val> Drop for GcGuard<'a, Val> {
+    fn drop(&mut self) { self.inner.borrow_mut().take(); }
+}
+
+/// Bytes captured by a native function.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct JsBytes(pub(crate) Bytes);
+
+impl Finalize for JsBytes {}
+
+unsafe impl Trace for JsBytes {
+    empty_trace!();
+}
+
+/// The `log` object passed to `step` and `fault`.
+pub(crate) struct StepLog {
+    /// Stack before step execution
+    pub(crate) stack: StackRef,
+    /// Opcode to be executed
+    pub(crate) op: OpObj,
+    /// Memory before step execution
+    pub(crate) memory: MemoryRef,
+    /// Program counter before step execution
+    pub(crate) pc: u64,
+    /// Remaining gas before step execution
+    pub(crate) gas_remaining: u64,
+    /// Gas cost of step execution
+    pub(crate) cost: u64,
+    /// Call depth, 1 for the top level call
+    pub(crate) depth: u64,
+    /// Gas refund counter before step execution
+    pub(crate) refund: u64,
+    /// Error of the step, only set for `fault`
+    pub(crate) error: Option<String>,
+    /// The executing contract
+    pub(crate) contract: Contract,
+}
+
+impl StepLog {
+    /// Converts the step into a js object.
+    ///
+    /// Caution: this expects a global property `bigint` to be present.
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            stack,
+            op,
+            memory,
+            pc,
+            gas_remaining: gas,
+            cost,
+            depth,
+            refund,
+            error,
+            contract,
+        } = self;
+        let obj = JsObject::default();
+
+        // fields
+        let op = op.into_js_object(ctx)?;
+        let memory = memory.into_js_object(ctx)?;
+        let stack = stack.into_js_object(ctx)?;
+        let contract = contract.into_js_object(ctx)?;
+
+        obj.set(js_string!("op"), op, false, ctx)?;
+        obj.set(js_string!("memory"), memory, false, ctx)?;
+        obj.set(js_string!("stack"), stack, false, ctx)?;
+        obj.set(js_string!("contract"), contract, false, ctx)?;
+
+        // methods
+        let error = error
+            .map(|err| JsValue::from(js_string!(err)))
+            .unwrap_or_default();
+        let get_error = js_value_capture_getter!(error, ctx);
+        let get_pc = js_value_getter!(pc, ctx);
+        let get_gas = js_value_getter!(gas, ctx);
+        let get_cost = js_value_getter!(cost, ctx);
+        let get_refund = js_value_getter!(refund, ctx);
+        let get_depth = js_value_getter!(depth, ctx);
+
+        obj.set(js_string!("getPC"), get_pc, false, ctx)?;
+        obj.set(js_string!("getError"), get_error, false, ctx)?;
+        obj.set(js_string!("getGas"), get_gas, false, ctx)?;
+        obj.set(js_string!("getCost"), get_cost, false, ctx)?;
+        obj.set(js_string!("getDepth"), get_depth, false, ctx)?;
+        obj.set(js_string!("getRefund"), get_refund, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// The `log.memory` object.
+#[derive(Clone)]
+pub(crate) struct MemoryRef {
+    memory: GuardedNullableGc<Vec<u8>>,
+    /// The memory size before the step
+    len: usize,
+    /// The offset and the content before the step of the memory written by
+    /// the step
+    overwritten: Rc<(usize, Vec<u8>)>,
+}
+
+impl MemoryRef {
+    pub(crate) fn new(mem: &Vec<u8>) -> (Self, GcGuard<'_, Vec<u8>>) {
+        Self::before_step(mem, mem.len(), Default::default())
+    }
+
+    /// Creates a reference to the memory before a step from the memory after
+    /// it, which may have been expanded, and the part the step overwrote.
+    pub(crate) fn before_step(
+        mem: &Vec<u8>, len: usize, overwritten: (usize, Vec<u8>),
+    ) -> (Self, GcGuard<'_, Vec<u8>>) {
+        let (memory, guard) = GuardedNullableGc::new_ref(mem);
+        let this = Self {
+            memory,
+            len,
+            overwritten: Rc::new(overwritten),
+        };
+        (this, guard)
+    }
+
+    fn len(&self) -> usize {
+        self.memory.with_inner(|_| self.len).unwrap_or_default()
+    }
+
+    /// Returns `memory[start..end]`, zero-padded if it reads beyond the
+    /// memory size.
+    fn slice(&self, start: f64, end: f64) -> JsResult<Vec<u8>> {
+        if end < start || start < 0. {
+            return Err(js_type_error(format!(
+                "tracer accessed out of bound memory: offset {start}, end \
+                 {end}"
+            )));
+        }
+        let (start, end) = (start as usize, end as usize);
+        let len = self.len();
+        if end > len && end - len > MEMORY_PAD_LIMIT {
+            return Err(js_type_error(format!(
+                "tracer reached limit for padding memory slice: end {end}, \
+                 memorySize {len}"
+            )));
+        }
+        let mut slice = vec![0u8; end - start];
+        self.memory.with_inner(|mem| {
+            let available = len.min(mem.len()).min(end);
+            if start < available {
+                slice[..available - start]
+                    .copy_from_slice(&mem[start..available]);
+            }
+            let (offset, data) = &*self.overwritten;
+            let (from, to) = (start.max(*offset), end.min(offset + data.len()));
+            if from < to {
+                slice[from - start..to - start]
+                    .copy_from_slice(&data[from - offset..to - offset]);
+            }
+        });
+        Ok(slice)
+    }
+
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let len = self.len();
+
+        let length = js_value_getter!(len, ctx);
+
+        // slice returns the requested range of memory as a byte slice.
+        let slice = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, memory, ctx| {
+                    let start = args.get_or_undefined(0).to_number(ctx)?;
+                    let end = args.get_or_undefined(1).to_number(ctx)?;
+                    to_byte_array_value(memory.slice(start, end)?, ctx)
+                },
+                self.clone(),
+            ),
+        )
+        .length(2)
+        .build();
+
+        // getUint returns the 32 bytes at the given offset as a bigint.
+        let get_uint = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, memory, ctx| {
+                    let offset_f64 = args.get_or_undefined(0).to_number(ctx)?;
+                    let len = memory.len();
+                    let offset = offset_f64 as usize;
+                    if offset_f64 < 0. || len < offset + 32 {
+                        return Err(js_type_error(format!(
+                            "tracer accessed out of bound memory: available \
+                             {len}, offset {offset}, size 32"
+                        )));
+                    }
+                    let word = memory.slice(offset_f64, offset_f64 + 32.)?;
+                    to_bigint(U256::from_be_slice(&word), ctx)
+                },
+                self,
+            ),
+        )
+        .length(1)
+        .build();
+
+        obj.set(js_string!("slice"), slice, false, ctx)?;
+        obj.set(js_string!("getUint"), get_uint, false, ctx)?;
+        obj.set(js_string!("length"), length, false, ctx)?;
+        Ok(obj)
+    }
+}
+
+impl Finalize for MemoryRef {}
+
+unsafe impl Trace for MemoryRef {
+    empty_trace!();
+}
+
+/// The `log.stack` object.
+#[derive(Clone)]
+pub(crate) struct StackRef {
+    stack: GuardedNullableGc<Vec<CfxU256>>,
+    /// The stack size before the step
+    len: usize,
+    /// The top of the stack before the step, popped by the step
+    popped: Rc<Vec<CfxU256>>,
+}
+
+impl StackRef {
+    /// Creates a reference to the interpreter stack, whose top is the last
+    /// element.
+    pub(crate) fn new(
+        stack: &Vec<CfxU256>,
+    ) -> (Self, GcGuard<'_, Vec<CfxU256>>) {
+        Self::before_step(stack, stack.len(), Vec::new())
+    }
+
+    /// Creates a reference to the stack before a step from the stack after
+    /// it and the items the step popped.
+    pub(crate) fn before_step(
+        stack: &Vec<CfxU256>, len: usize, popped: Vec<CfxU256>,
+    ) -> (Self, GcGuard<'_, Vec<CfxU256>>) {
+        let (inner, guard) = GuardedNullableGc::new_ref(stack);
+        let this = Self {
+            stack: inner,
+            len,
+            popped: Rc::new(popped),
+        };
+        (this, guard)
+    }
+
+    fn len(&self) -> usize {
+        self.stack.with_inner(|_| self.len).unwrap_or_default()
+    }
+
+    fn peek(&self, idx: usize) -> Option<CfxU256> {
+        let pos = self.len.checked_sub(idx + 1)?;
+        let kept = self.len - self.popped.len();
+        self.stack
+            .with_inner(|stack| match pos.checked_sub(kept) {
+                Some(popped_pos) => self.popped.get(popped_pos).copied(),
+                None => stack.get(pos).copied(),
+            })
+            .flatten()
+    }
+
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let len = self.len();
+        let length = js_value_getter!(len, ctx);
+
+        // peek returns the nth-from-the-top element of the stack.
+        let peek = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, stack, ctx| {
+                    let idx_f64 = args.get_or_undefined(0).to_number(ctx)?;
+                    let value = (idx_f64 >= 0.)
+                        .then(|| stack.peek(idx_f64 as usize))
+                        .flatten()
+                        .ok_or_else(|| {
+                            js_type_error(format!(
+                                "tracer accessed out of bound stack: size \
+                                 {}, index {idx_f64}",
+                                stack.len()
+                            ))
+                        })?;
+                    to_bigint(to_alloy_u256(value), ctx)
+                },
+                self,
+            ),
+        )
+        .length(1)
+        .build();
+
+        obj.set(js_string!("length"), length, false, ctx)?;
+        obj.set(js_string!("peek"), peek, false, ctx)?;
+        Ok(obj)
+    }
+}
+
+impl Finalize for StackRef {}
+
+unsafe impl Trace for StackRef {
+    empty_trace!();
+}
+
+/// The `log.op` object.
+#[derive(Debug)]
+pub(crate) struct OpObj(pub(crate) u8);
+
+impl OpObj {
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let value = self.0;
+        let is_push = OpCode::new(value).map_or(false, |op| op.is_push());
+        let name = js_string!(OpCode::new(value).map_or_else(
+            || format!("opcode {value:#x} not defined"),
+            |op| op.as_str().to_string()
+        ));
+
+        let to_number = js_value_getter!(value, ctx);
+        let is_push = js_value_getter!(is_push, ctx);
+        let to_string = js_value_capture_getter!(name, ctx);
+
+        obj.set(js_string!("toNumber"), to_number, false, ctx)?;
+        obj.set(js_string!("toString"), to_string, false, ctx)?;
+        obj.set(js_string!("isPush"), is_push, false, ctx)?;
+        Ok(obj)
+    }
+}
+
+/// The `log.contract` object.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Contract {
+    pub(crate) caller: Address,
+    pub(crate) address: Address,
+    pub(crate) value: U256,
+    pub(crate) input: Bytes,
+}
+
+impl Contract {
+    /// Converts the contract into a js object.
+    ///
+    /// Caution: this expects a global property `bigint` to be present.
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            caller,
+            address,
+            value,
+            input,
+        } = self;
+        let obj = JsObject::default();
+
+        let get_caller = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(caller, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_address = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(address, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_value = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                to_bigint(value, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        // The input is only copied into the JS heap when it is requested.
+        let get_input = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, _args, input, ctx| {
+                    to_byte_array_value(input.0.iter().copied(), ctx)
+                },
+                JsBytes(input),
+            ),
+        )
+        .length(0)
+        .build();
+
+        obj.set(js_string!("getCaller"), get_caller, false, ctx)?;
+        obj.set(js_string!("getAddress"), get_address, false, ctx)?;
+        obj.set(js_string!("getValue"), get_value, false, ctx)?;
+        obj.set(js_string!("getInput"), get_input, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// The `frame` object passed to `enter`.
+#[derive(Debug)]
+pub(crate) struct CallFrame {
+    pub(crate) contract: Contract,
+    /// `CALL`, `STATICCALL`, `DELEGATECALL`, `CALLCODE`, `CREATE`,
+    /// `CREATE2` or `SELFDESTRUCT`
+    pub(crate) kind: String,
+    pub(crate) gas: u64,
+}
+
+impl CallFrame {
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            contract:
+                Contract {
+                    caller,
+                    address,
+                    value,
+                    input,
+                },
+            kind,
+            gas,
+        } = self;
+        let obj = JsObject::default();
+
+        let get_from = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(caller, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_to = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                address_to_byte_array_value(address, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let get_value = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure(move |_this, _args, ctx| {
+                to_bigint(value, ctx)
+            }),
+        )
+        .length(0)
+        .build();
+
+        let input = to_byte_array_value(input, ctx)?;
+        let get_input = js_value_capture_getter!(input, ctx);
+        let get_gas = js_value_getter!(gas, ctx);
+        let ty = js_string!(kind);
+        let get_type = js_value_capture_getter!(ty, ctx);
+
+        obj.set(js_string!("getFrom"), get_from, false, ctx)?;
+        obj.set(js_string!("getTo"), get_to, false, ctx)?;
+        obj.set(js_string!("getValue"), get_value, false, ctx)?;
+        obj.set(js_string!("getInput"), get_input, false, ctx)?;
+        obj.set(js_string!("getGas"), get_gas, false, ctx)?;
+        obj.set(js_string!("getType"), get_type, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// The `frameResult` object passed to `exit`.
+#[derive(Debug, Default)]
+pub(crate) struct CallFrameResult {
+    pub(crate) gas_used: u64,
+    pub(crate) output: Bytes,
+    pub(crate) error: Option<String>,
+}
+
+impl CallFrameResult {
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            gas_used,
+            output,
+            error,
+        } = self;
+        let obj = JsObject::default();
+
+        let output = to_byte_array_value(output, ctx)?;
+        let get_output = js_value_capture_getter!(output, ctx);
+        let error = error
+            .map(|err| JsValue::from(js_string!(err)))
+            .unwrap_or_default();
+        let get_error = js_value_capture_getter!(error, ctx);
+        let get_gas_used = js_value_getter!(gas_used, ctx);
+
+        obj.set(js_string!("getGasUsed"), get_gas_used, false, ctx)?;
+        obj.set(js_string!("getOutput"), get_output, false, ctx)?;
+        obj.set(js_string!("getError"), get_error, false, ctx)?;
+
+        Ok(obj)
+    }
+}
+
+/// The `ctx` object passed to `result`.
+#[derive(Debug, Default)]
+pub(crate) struct JsEvmContext {
+    /// `CALL` or `CREATE`
+    pub(crate) r#type: String,
+    /// Sender of the transaction
+    pub(crate) from: Address,
+    /// Target of the transaction, the created contract for `CREATE`
+    pub(crate) to: Option<Address>,
+    pub(crate) input: Bytes,
+    /// Gas limit
+    pub(crate) gas: u64,
+    /// Amount of gas used by the transaction
+    pub(crate) gas_used: u64,
+    pub(crate) gas_price: U256,
+    /// Intrinsic gas of the transaction
+    pub(crate) intrinsic_gas: u64,
+    /// Amount to be transferred in drip
+    pub(crate) value: U256,
+    /// Block number
+    pub(crate) block: u64,
+    pub(crate) output: Bytes,
+    /// Execution time of the transaction
+    pub(crate) time: String,
+    /// Error of the top level call, if any
+    pub(crate) error: Option<String>,
+}
+
+impl JsEvmContext {
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let Self {
+            r#type,
+            from,
+            to,
+            input,
+            gas,
+            gas_used,
+            gas_price,
+            intrinsic_gas,
+            value,
+            block,
+            output,
+            time,
+            error,
+        } = self;
+        let obj = JsObject::default();
+
+        obj.set(js_string!("type"), js_string!(r#type), false, ctx)?;
+        obj.set(
+            js_string!("from"),
+            address_to_byte_array(from, ctx)?,
+            false,
+            ctx,
+        )?;
+        let to = match to {
+            Some(to) => address_to_byte_array(to, ctx)?.into(),
+            None => JsValue::null(),
+        };
+        obj.set(js_string!("to"), to, false, ctx)?;
+        obj.set(js_string!("input"), to_byte_array(input, ctx)?, false, ctx)?;
+        obj.set(js_string!("gas"), gas, false, ctx)?;
+        obj.set(js_string!("gasUsed"), gas_used, false, ctx)?;
+        obj.set(
+            js_string!("gasPrice"),
+            to_bigint(gas_price, ctx)?,
+            false,
+            ctx,
+        )?;
+        obj.set(js_string!("intrinsicGas"), intrinsic_gas, false, ctx)?;
+        obj.set(js_string!("value"), to_bigint(value, ctx)?, false, ctx)?;
+        obj.set(js_string!("block"), block, false, ctx)?;
+        obj.set(
+            js_string!("output"),
+            to_byte_array(output, ctx)?,
+            false,
+            ctx,
+        )?;
+        obj.set(js_string!("time"), js_string!(time), false, ctx)?;
+        if let Some(error) = error {
+            obj.set(js_string!("error"), js_string!(error), false, ctx)?;
+        }
+
+        Ok(obj)
+    }
+}
+
+/// The `db` object, reading the state of the space being executed.
+#[derive(Clone)]
+pub(crate) struct EvmDbRef {
+    state: GuardedNullableGc<State>,
+    space: Space,
+}
+
+impl EvmDbRef {
+    pub(crate) fn new(
+        state: &State, space: Space,
+    ) -> (Self, GcGuard<'_, State>) {
+        let (state, guard) = GuardedNullableGc::new_ref(state);
+        (Self { state, space }, guard)
+    }
+
+    fn address(&self, val: JsValue, ctx: &mut Context) -> JsResult<Address> {
+        Ok(bytes_to_address(from_buf_value(val, ctx)?))
+    }
+
+    fn read<R, E: Display>(
+        &self, address: Address,
+        f: impl FnOnce(&State, &AddressWithSpace) -> Result<R, E>,
+    ) -> JsResult<R> {
+        let address_with_space =
+            from_alloy_address(address).with_space(self.space);
+        match self.state.with_inner(|state| f(state, &address_with_space)) {
+            Some(Ok(value)) => Ok(value),
+            Some(Err(err)) => Err(js_error(format!(
+                "failed to read {address:?} from database: {err}"
+            ))),
+            None => Err(js_error(
+                "tracer accessed the database outside of a callback".into(),
+            )),
+        }
+    }
+
+    pub(crate) fn into_js_object(
+        self, ctx: &mut Context,
+    ) -> JsResult<JsObject> {
+        let obj = JsObject::default();
+        let exists = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let address =
+                        db.address(args.get_or_undefined(0).clone(), ctx)?;
+                    let exists =
+                        db.read(address, |state, addr| state.exists(addr))?;
+                    Ok(JsValue::from(exists))
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_balance = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let address =
+                        db.address(args.get_or_undefined(0).clone(), ctx)?;
+                    let balance =
+                        db.read(address, |state, addr| state.balance(addr))?;
+                    to_bigint(to_alloy_u256(balance), ctx)
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_nonce = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let address =
+                        db.address(args.get_or_undefined(0).clone(), ctx)?;
+                    let nonce =
+                        db.read(address, |state, addr| state.nonce(addr))?;
+                    Ok(JsValue::from(nonce.low_u64()))
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_code = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let address =
+                        db.address(args.get_or_undefined(0).clone(), ctx)?;
+                    let code =
+                        db.read(address, |state, addr| state.code(addr))?;
+                    let code = code.as_deref().map_or(&[][..], |c| &c[..]);
+                    to_byte_array_value(code.iter().copied(), ctx)
+                },
+                self.clone(),
+            ),
+        )
+        .length(1)
+        .build();
+
+        let get_state = FunctionObjectBuilder::new(
+            ctx.realm(),
+            NativeFunction::from_copy_closure_with_captures(
+                move |_this, args, db, ctx| {
+                    let address =
+                        db.address(args.get_or_undefined(0).clone(), ctx)?;
+                    let slot = bytes_to_hash(from_buf_value(
+                        args.get_or_undefined(1).clone(),
+                        ctx,
+                    )?);
+                    let value = db.read(address, |state, addr| {
+                        state.storage_at(addr, slot.as_slice())
+                    })?;
+                    to_byte_array_value(
+                        to_alloy_u256(value).to_be_bytes::<32>(),
+                        ctx,
+                    )
+                },
+                self,
+            ),
+        )
+        .length(2)
+        .build();
+
+        obj.set(js_string!("getBalance"), get_balance, false, ctx)?;
+        obj.set(js_string!("getNonce"), get_nonce, false, ctx)?;
+        obj.set(js_string!("getCode"), get_code, false, ctx)?;
+        obj.set(js_string!("getState"), get_state, false, ctx)?;
+        obj.set(js_string!("exists"), exists, false, ctx)?;
+        Ok(obj)
+    }
+}
+
+impl Finalize for EvmDbRef {}
+
+unsafe impl Trace for EvmDbRef {
+    empty_trace!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::js_tracer::{builtins::to_serde_value, test_context};
+    use boa_engine::Source;
+
+    fn call(
+        ctx: &mut Context, code: &str, args: &[JsValue],
+    ) -> JsResult<JsValue> {
+        let f = ctx.eval(Source::from_bytes(code)).unwrap();
+        f.as_callable()
+            .unwrap()
+            .call(&JsValue::undefined(), args, ctx)
+    }
+
+    #[test]
+    fn test_stack_peek() {
+        let mut ctx = test_context();
+
+        let stack = vec![CfxU256::from(1), CfxU256::from(35000)];
+        let (stack_ref, guard) = StackRef::new(&stack);
+        let stack_obj: JsValue =
+            stack_ref.into_js_object(&mut ctx).unwrap().into();
+
+        let code = "(function(stack) { return [stack.length(), \
+                    stack.peek(0), stack.peek(1)]; })";
+        let res = call(&mut ctx, code, &[stack_obj.clone()]).unwrap();
+        let res = to_serde_value(res, usize::MAX, &mut ctx).unwrap();
+        assert_eq!(res.to_string(), r#"[2,"35000","1"]"#);
+
+        let res = call(
+            &mut ctx,
+            "(function(s) { s.peek(2) })",
+            &[stack_obj.clone()],
+        );
+        assert!(res.is_err());
+
+        // the stack is not accessible once the guard is dropped
+        drop(guard);
+        let res = call(&mut ctx, "(function(s) { s.peek(0) })", &[stack_obj]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_memory_slice() {
+        let mut ctx = test_context();
+
+        let mut mem = vec![0u8; 32];
+        mem[31] = 0x2a;
+        let (mem_ref, _guard) = MemoryRef::new(&mem);
+        let mem_obj: JsValue = mem_ref.into_js_object(&mut ctx).unwrap().into();
+
+        let code = "(function(m) { return [m.length(), m.getUint(0), \
+                    toHex(m.slice(30, 34))]; })";
+        let res = call(&mut ctx, code, &[mem_obj.clone()]).unwrap();
+        let res = to_serde_value(res, usize::MAX, &mut ctx).unwrap();
+        assert_eq!(res.to_string(), r#"[32,"42","002a0000"]"#);
+
+        let res = call(&mut ctx, "(function(m) { m.slice(2, 1) })", &[mem_obj]);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_op_obj() {
+        let mut ctx = test_context();
+        let op: JsValue = OpObj(0x60).into_js_object(&mut ctx).unwrap().into();
+        let code = "(function(op) { return [op.toNumber(), op.toString(), \
+                    op.isPush()]; })";
+        let res = call(&mut ctx, code, &[op]).unwrap();
+        let res = to_serde_value(res, usize::MAX, &mut ctx).unwrap();
+        assert_eq!(res.to_string(), r#"[96,"PUSH1",true]"#);
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright 2023-2024 Paradigm.xyz
+// This file is part of reth.
+// Reth is a modular, contributor-friendly and blazing-fast implementation of
+// the Ethereum protocol
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+//! Builtin functions available to JS tracers
+
+use alloy_primitives::{hex, Address, B256, U256};
+use boa_engine::{
+    builtins::{array_buffer::ArrayBuffer, typed_array::TypedArray},
+    js_string,
+    object::builtins::{JsArray, JsArrayBuffer, JsTypedArray, JsUint8Array},
+    property::Attribute,
+    Context, JsArgs, JsError, JsNativeError, JsResult, JsString, JsValue,
+    NativeFunction, Source,
+};
+use boa_gc::{empty_trace, Finalize, Trace};
+use std::{borrow::Borrow, collections::HashSet};
+
+/// Minified version of <https://github.com/peterolson/BigInteger.js>, the
+/// `bigint` implementation geth exposes to JS tracers.
+pub(crate) const BIG_INT_JS: &str = include_str!("bigint.js");
+
+/// Converts the given `JsValue` to a `serde_json::Value`, failing if its
+/// JSON encoding is longer than `max_len` bytes.
+///
+/// `JSON.stringify` is tried first so that `toJSON` properties are honored,
+/// which makes `bigint` values serialize as decimal strings. Falls back to
+/// boa's own conversion.
+pub(crate) fn to_serde_value(
+    val: JsValue, max_len: usize, ctx: &mut Context,
+) -> JsResult<serde_json::Value> {
+    let Ok(json) = json_stringify(val.clone(), ctx) else {
+        let value = val.to_json(ctx)?;
+        if value.to_string().len() > max_len {
+            return Err(result_too_large(max_len));
+        }
+        return Ok(value);
+    };
+    let json =
+        json.to_std_string().map_err(|err| {
+            JsError::from_native(JsNativeError::error().with_message(format!(
+                "failed to convert JSON to string: {}",
+                err
+            )))
+        })?;
+    if json.len() > max_len {
+        return Err(result_too_large(max_len));
+    }
+    serde_json::from_str(&json).map_err(|err| {
+        JsError::from_native(
+            JsNativeError::error()
+                .with_message(format!("failed to parse JSON: {}", err)),
+        )
+    })
+}
+
+fn result_too_large(max_len: usize) -> JsError {
+    JsError::from_native(JsNativeError::range().with_message(format!(
+        "tracer result exceeds the size limit of {max_len} bytes"
+    )))
+}
+
+/// Stringifies the given value with the global `JSON` object.
+pub(crate) fn json_stringify(
+    val: JsValue, ctx: &mut Context,
+) -> JsResult<JsString> {
+    let json = ctx.global_object().get(js_string!("JSON"), ctx)?;
+    let json_obj = json.as_object().ok_or_else(|| {
+        JsError::from_native(
+            JsNativeError::typ().with_message("JSON is not an object"),
+        )
+    })?;
+
+    let stringify = json_obj.get(js_string!("stringify"), ctx)?;
+
+    let stringify = stringify.as_callable().ok_or_else(|| {
+        JsError::from_native(
+            JsNativeError::typ().with_message("JSON.stringify is not callable"),
+        )
+    })?;
+    let res = stringify.call(&json, &[val], ctx)?;
+    res.to_string(ctx)
+}
+
+/// Registers the global `bigint` property and the geth builtin helpers
+/// `toHex`, `toWord`, `toAddress`, `toContract` and `toContract2`.
+///
+/// `isPrecompiled` is registered separately by
+/// [`PrecompileList::register_callable`].
+pub(crate) fn register_builtins(ctx: &mut Context) -> JsResult<()> {
+    let big_int = ctx.eval(Source::from_bytes(BIG_INT_JS.as_bytes()))?;
+    ctx.register_global_property(
+        js_string!("bigint"),
+        big_int,
+        Attribute::all(),
+    )?;
+    ctx.register_global_builtin_callable(
+        js_string!("toHex"),
+        1,
+        NativeFunction::from_fn_ptr(to_hex),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toWord"),
+        1,
+        NativeFunction::from_fn_ptr(to_word),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toAddress"),
+        1,
+        NativeFunction::from_fn_ptr(to_address),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toContract"),
+        2,
+        NativeFunction::from_fn_ptr(to_contract),
+    )?;
+    ctx.register_global_callable(
+        js_string!("toContract2"),
+        3,
+        NativeFunction::from_fn_ptr(to_contract2),
+    )?;
+
+    Ok(())
+}
+
+/// Converts an array, hex string or Uint8Array to bytes.
+pub(crate) fn from_buf_value(
+    val: JsValue, context: &mut Context,
+) -> JsResult<Vec<u8>> {
+    if let Some(obj) = val.as_object().cloned() {
+        if obj.is::<TypedArray>() {
+            let array: JsTypedArray = JsTypedArray::from_object(obj)?;
+            let len = array.length(context)?;
+            let mut buf = Vec::with_capacity(len);
+            for i in 0..len {
+                let val = array.get(i, context)?;
+                buf.push(val.to_number(context)? as u8);
+            }
+            return Ok(buf);
+        } else if obj.is::<ArrayBuffer>() {
+            let buf = JsArrayBuffer::from_object(obj)?;
+            let buf =
+                buf.data().map(|data| data.to_vec()).ok_or_else(|| {
+                    JsNativeError::typ()
+                        .with_message("ArrayBuffer was already detached")
+                })?;
+            return Ok(buf);
+        } else if obj.is::<JsString>() {
+            let js_string =
+                obj.downcast_ref::<JsString>().ok_or_else(|| {
+                    JsNativeError::typ().with_message("invalid string type")
+                })?;
+            return hex_decode_js_string(js_string.borrow());
+        } else if obj.is_array() {
+            let array = JsArray::from_object(obj)?;
+            let len = array.length(context)?;
+            let mut buf = Vec::with_capacity(len as usize);
+            for i in 0..len {
+                let val = array.get(i, context)?;
+                buf.push(val.to_number(context)? as u8);
+            }
+            return Ok(buf);
+        }
+    }
+
+    if let Some(js_string) = val.as_string() {
+        return hex_decode_js_string(js_string);
+    }
+
+    Err(JsError::from_native(JsNativeError::typ().with_message(
+        format!("invalid buffer type: {}", val.type_of()),
+    )))
+}
+
+/// Creates a new [JsUint8Array] from the address' bytes.
+pub(crate) fn address_to_byte_array(
+    addr: Address, context: &mut Context,
+) -> JsResult<JsUint8Array> {
+    JsUint8Array::from_iter(addr.0, context)
+}
+
+/// Creates a new [JsUint8Array] value from the address' bytes.
+pub(crate) fn address_to_byte_array_value(
+    addr: Address, context: &mut Context,
+) -> JsResult<JsValue> {
+    Ok(address_to_byte_array(addr, context)?.into())
+}
+
+/// Creates a new [JsUint8Array] from a byte block.
+pub(crate) fn to_byte_array<I>(
+    bytes: I, context: &mut Context,
+) -> JsResult<JsUint8Array>
+where I: IntoIterator<Item = u8> {
+    JsUint8Array::from_iter(bytes, context)
+}
+
+/// Creates a new [JsUint8Array] value from a byte block.
+pub(crate) fn to_byte_array_value<I>(
+    bytes: I, context: &mut Context,
+) -> JsResult<JsValue>
+where I: IntoIterator<Item = u8> {
+    Ok(to_byte_array(bytes, context)?.into())
+}
+
+/// Converts a buffer to an address, cropping it from the left if it is
+/// longer than 20 bytes.
+pub(crate) fn bytes_to_address(buf: Vec<u8>) -> Address {
+    let mut address = Address::default();
+    let mut buf = &buf[..];
+    let address_len = address.0.len();
+    if buf.len() > address_len {
+        buf = &buf[buf.len() - address_len..];
+    }
+    address.0[address_len - buf.len()..].copy_from_slice(buf);
+    address
+}
+
+/// Converts a buffer to a hash, cropping it from the left if it is longer
+/// than 32 bytes.
+pub(crate) fn bytes_to_hash(buf: Vec<u8>) -> B256 {
+    let mut hash = B256::default();
+    let mut buf = &buf[..];
+    let hash_len = hash.0.len();
+    if buf.len() > hash_len {
+        buf = &buf[buf.len() - hash_len..];
+    }
+    hash.0[hash_len - buf.len()..].copy_from_slice(buf);
+    hash
+}
+
+/// Converts a U256 to a JS `bigint` using the global `bigint` property.
+pub(crate) fn to_bigint(value: U256, ctx: &mut Context) -> JsResult<JsValue> {
+    let bigint = ctx.global_object().get(js_string!("bigint"), ctx)?;
+    let Some(bigint) = bigint.as_callable() else {
+        return Ok(JsValue::undefined());
+    };
+    bigint.call(
+        &JsValue::undefined(),
+        &[JsValue::from(js_string!(value.to_string()))],
+        ctx,
+    )
+}
+
+/// `toContract2(from, salt, initcode)`: computes the CREATE2 address of a
+/// contract deployed by `from`.
+pub(crate) fn to_contract2(
+    _: &JsValue, args: &[JsValue], ctx: &mut Context,
+) -> JsResult<JsValue> {
+    let from = args.get_or_undefined(0).clone();
+    let salt = match args.get_or_undefined(1).to_string(ctx) {
+        Ok(js_string) => bytes_to_hash(hex_decode_js_string(&js_string)?),
+        Err(_) => {
+            return Err(JsError::from_native(
+                JsNativeError::typ().with_message("invalid salt type"),
+            ))
+        }
+    };
+    let initcode = args.get_or_undefined(2).clone();
+
+    let addr = bytes_to_address(from_buf_value(from, ctx)?);
+    let code = from_buf_value(initcode, ctx)?;
+
+    address_to_byte_array_value(addr.create2_from_code(salt, code), ctx)
+}
+
+/// `toContract(from, nonce)`: computes the CREATE address of a contract
+/// deployed by `from`.
+pub(crate) fn to_contract(
+    _: &JsValue, args: &[JsValue], ctx: &mut Context,
+) -> JsResult<JsValue> {
+    let from = args.get_or_undefined(0).clone();
+    let nonce = args.get_or_undefined(1).to_number(ctx)? as u64;
+
+    let addr = bytes_to_address(from_buf_value(from, ctx)?);
+
+    address_to_byte_array_value(addr.create(nonce), ctx)
+}
+
+/// `toAddress(buf)`: converts a buffer to an address.
+pub(crate) fn to_address(
+    _: &JsValue, args: &[JsValue], ctx: &mut Context,
+) -> JsResult<JsValue> {
+    let val = args.get_or_undefined(0).clone();
+    let address = bytes_to_address(from_buf_value(val, ctx)?);
+    address_to_byte_array_value(address, ctx)
+}
+
+/// `toWord(buf)`: converts a buffer to a 32-byte word.
+pub(crate) fn to_word(
+    _: &JsValue, args: &[JsValue], ctx: &mut Context,
+) -> JsResult<JsValue> {
+    let val = args.get_or_undefined(0).clone();
+    let hash = bytes_to_hash(from_buf_value(val, ctx)?);
+    to_byte_array_value(hash.0, ctx)
+}
+
+/// `toHex(buf)`: converts a buffer to a hex string.
+pub(crate) fn to_hex(
+    _: &JsValue, args: &[JsValue], ctx: &mut Context,
+) -> JsResult<JsValue> {
+    let val = args.get_or_undefined(0).clone();
+    let buf = from_buf_value(val, ctx)?;
+    Ok(JsValue::from(js_string!(hex::encode(buf))))
+}
+
+fn hex_decode_js_string(js_string: &JsString) -> JsResult<Vec<u8>> {
+    match js_string.to_std_string() {
+        Ok(s) => hex::decode(s.as_str()).map_err(|err| {
+            JsError::from_native(
+                JsNativeError::error()
+                    .with_message(format!("invalid hex string {s}: {err}")),
+            )
+        }),
+        Err(err) => {
+            Err(JsError::from_native(JsNativeError::error().with_message(
+                format!("invalid utf8 string {js_string:?}: {err}"),
+            )))
+        }
+    }
+}
+
+/// The precompile addresses backing the `isPrecompiled` global callable.
+#[derive(Clone, Debug)]
+pub(crate) struct PrecompileList(pub(crate) HashSet<Address>);
+
+impl PrecompileList {
+    /// Registers the global callable `isPrecompiled`.
+    pub(crate) fn register_callable(self, ctx: &mut Context) -> JsResult<()> {
+        let is_precompiled = NativeFunction::from_copy_closure_with_captures(
+            move |_this, args, precompiles, ctx| {
+                let val = args.get_or_undefined(0).clone();
+                let addr = bytes_to_address(from_buf_value(val, ctx)?);
+                Ok(precompiles.0.contains(&addr).into())
+            },
+            self,
+        );
+
+        ctx.register_global_callable(
+            js_string!("isPrecompiled"),
+            1,
+            is_precompiled,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Finalize for PrecompileList {}
+
+unsafe impl Trace for PrecompileList {
+    empty_trace!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::js_tracer::test_context;
+
+    #[test]
+    fn test_install_bigint() {
+        let mut ctx = test_context();
+        let big_int =
+            ctx.eval(Source::from_bytes(BIG_INT_JS.as_bytes())).unwrap();
+        let value = JsValue::from(100);
+        let result = big_int
+            .as_callable()
+            .unwrap()
+            .call(&JsValue::undefined(), &[value], &mut ctx)
+            .unwrap();
+        assert_eq!(
+            result.to_string(&mut ctx).unwrap().to_std_string().unwrap(),
+            "100"
+        );
+    }
+
+    #[test]
+    fn test_to_serde_value_size_limit() {
+        let mut ctx = test_context();
+        let val = ctx.eval(Source::from_bytes("[1, 2, 3]")).unwrap();
+        assert_eq!(
+            to_serde_value(val.clone(), 7, &mut ctx).unwrap(),
+            serde_json::json!([1, 2, 3])
+        );
+        assert!(to_serde_value(val, 6, &mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_bytes_to_address() {
+        let addr = bytes_to_address(vec![0xff; 32]);
+        assert_eq!(addr, Address::repeat_byte(0xff));
+
+        let addr = bytes_to_address(vec![0x01]);
+        assert_eq!(addr.0[19], 0x01);
+        assert!(addr.0[..19].iter().all(|b| *b == 0));
+    }
+}
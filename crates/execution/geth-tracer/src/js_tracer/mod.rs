@@ -0,0 +1,1197 @@
+//! JavaScript tracer
+//!
+//! Runs geth style custom tracers (see
+//! <https://geth.ethereum.org/docs/developers/evm-tracing/custom-tracer>)
+//! inside a sandboxed [boa](https://boajs.dev) engine. The tracer code must
+//! evaluate to an object exposing `result(ctx, db)` and `fault(log, db)`, and
+//! may expose `setup(config)`, `step(log, db)`, `enter(frame)` and
+//! `exit(frameResult)`.
+//!
+//! The JS code has no access to the host besides the objects passed to these
+//! functions. Every callback is bounded by the loop iteration, recursion and
+//! stack size limits of the engine. The whole tracing, from the evaluation of
+//! the code to `result`, is bounded by the `timeout` tracing option, which
+//! also sets the number of JS instructions the tracer may execute, see
+//! [`INSTRUCTIONS_PER_SECOND`]. The memory is bounded inside the engine: each
+//! `ArrayBuffer` by [`MAX_BUFFER_SIZE`], the JS stack by [`STACK_SIZE_LIMIT`]
+//! and the returned result by [`MAX_RESULT_SIZE`].
+
+mod bindings;
+mod builtins;
+
+use crate::{
+    config::TraceStyle,
+    geth_tracer::to_instruction_result,
+    types::CallKind,
+    utils::{fmt_error_msg, to_alloy_address, to_alloy_u256},
+    TxExecContext,
+};
+use alloy_primitives::{Bytes, U256};
+use alloy_rpc_types_trace::geth::GethDebugTracingOptions;
+use bindings::{
+    CallFrame, CallFrameResult, Contract, EvmDbRef, JsEvmContext, MemoryRef,
+    OpObj, StackRef, StepLog,
+};
+use boa_engine::{
+    builtins::function::BoundFunction,
+    context::{ContextBuilder, HostHooks},
+    js_string,
+    object::{builtins::JsArray, ObjectInitializer},
+    property::Attribute,
+    Context, JsError, JsObject, JsValue, Script, Source,
+};
+use builtins::{register_builtins, to_serde_value, PrecompileList};
+use cfx_executor::{stack::FrameResult, state::State};
+use cfx_types::{Space, H160, U256 as CfxU256};
+use cfx_vm_interpreter::instructions::{
+    self, Instruction, INSTRUCTIONS, INSTRUCTIONS_CANCUN, INSTRUCTIONS_CIP645,
+    INSTRUCTIONS_EIP7939,
+};
+use cfx_vm_types::{ActionParams, CallType, CreateType, InterpreterInfo};
+use std::{
+    future::Future,
+    pin::pin,
+    task::{self, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+/// The maximum number of iterations of the loops in one call of a JS
+/// function.
+pub const LOOP_ITERATION_LIMIT: u64 = 200_000;
+
+/// The maximum depth of nested JS function calls.
+///
+/// Functions called back by builtins, e.g. by `Array.prototype.map`, nest on
+/// the native stack, so the limit must fit in the stack of a worker thread.
+pub const RECURSION_LIMIT: usize = 128;
+
+/// The maximum number of values on the stack of the JS engine.
+pub const STACK_SIZE_LIMIT: usize = 64 * 1024;
+
+/// The maximum size of an `ArrayBuffer`, which backs the byte arrays.
+pub const MAX_BUFFER_SIZE: u64 = 16 * 1024 * 1024;
+
+/// The number of JS instructions a tracer may execute per second of its
+/// `timeout`, about the speed of the engine.
+///
+/// The deadline is only checked between the instructions of the tracer
+/// functions, while the functions invoked by builtins, e.g. the callback of
+/// `Array.prototype.map`, run to completion on a nested interpreter loop. The
+/// instruction budget applies to both, so that such callbacks stop within a
+/// small multiple of the timeout.
+pub const INSTRUCTIONS_PER_SECOND: u64 = 10_000_000;
+
+/// The number of "clock cycles" the JS engine runs between two checks of the
+/// deadline.
+const EXECUTION_BUDGET: u32 = 1_000;
+
+/// The maximum size of the JSON encoded tracer result.
+pub const MAX_RESULT_SIZE: usize = 64 * 1024 * 1024;
+
+/// The time budget of a tracer if the `timeout` tracing option is not set,
+/// same as geth.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, thiserror::Error)]
+pub enum JsTracerError {
+    #[error("failed to evaluate JS code: {0}")]
+    EvalCode(JsError),
+
+    #[error("the evaluated code is not a JS object")]
+    ExpectedJsObject,
+
+    #[error("trace object must expose a function result()")]
+    ResultFunctionMissing,
+
+    #[error("trace object must expose a function fault()")]
+    FaultFunctionMissing,
+
+    #[error("trace object's setup must be a function")]
+    SetupFunctionNotCallable,
+
+    #[error("invalid JSON config: {0}")]
+    InvalidJsonConfig(JsError),
+
+    #[error("invalid timeout: {0}")]
+    InvalidTimeout(String),
+
+    #[error("{err} in server-side tracer function '{function}'")]
+    CallFailed {
+        function: &'static str,
+        err: JsError,
+    },
+
+    #[error("execution timeout")]
+    Timeout,
+
+    #[error(transparent)]
+    JsError(JsError),
+}
+
+impl From<JsError> for JsTracerError {
+    fn from(err: JsError) -> Self {
+        // The instruction budget is derived from the timeout.
+        match err.as_native() {
+            Some(native) if native.is_no_instructions_remain() => Self::Timeout,
+            _ => Self::JsError(err),
+        }
+    }
+}
+
+/// A call frame entered by the transaction.
+struct CallStackItem {
+    contract: Contract,
+    kind: CallKind,
+    gas_limit: u64,
+    space: Space,
+    /// The last executed step of this frame, reported to `fault` if the frame
+    /// fails
+    last_step: Option<StepSnapshot>,
+}
+
+/// The interpreter state before an instruction is executed.
+///
+/// Steps are reported to `step` once the instruction has been executed so
+/// that its gas cost is known. Instead of copying the stack and memory, only
+/// the parts overwritten by the instruction are saved, and `log.stack` and
+/// `log.memory` read the state before the instruction through the state after
+/// it. `fault` is called once the frame has exited, so it sees an empty stack
+/// and memory.
+struct StepSnapshot {
+    pc: u64,
+    op: u8,
+    gas_remaining: u64,
+    cost: u64,
+    depth: u64,
+    /// Only saved for tracers with `step`
+    overwritten: Option<Overwritten>,
+}
+
+/// The parts of the stack and memory an instruction may overwrite, saved
+/// before it is executed.
+struct Overwritten {
+    stack_len: usize,
+    /// The top of the stack popped by the instruction
+    popped: Vec<CfxU256>,
+    memory_len: usize,
+    /// The offset and the content of the memory written by the instruction,
+    /// within `memory_len`
+    memory: (usize, Vec<u8>),
+}
+
+impl Overwritten {
+    fn new(interp: &dyn InterpreterInfo) -> Self {
+        let (stack, memory) = (interp.stack(), interp.mem());
+        let op = interp.current_opcode();
+        let popped = stack_pop_count(op).min(stack.len());
+        let written = memory_written(op, stack)
+            .and_then(|(offset, size)| {
+                let end = offset.saturating_add(size).min(memory.len());
+                (offset < end).then(|| (offset, memory[offset..end].to_vec()))
+            })
+            .unwrap_or_default();
+        Self {
+            stack_len: stack.len(),
+            popped: stack[stack.len() - popped..].to_vec(),
+            memory_len: memory.len(),
+            memory: written,
+        }
+    }
+}
+
+/// Context of the transaction reported to `result`.
+struct TxInfo {
+    contract: Contract,
+    kind: CallKind,
+    gas_price: U256,
+    intrinsic_gas: u64,
+}
+
+/// Host hooks of the JS engine, limiting the size of buffers.
+struct TracerHooks;
+
+impl HostHooks for TracerHooks {
+    fn max_buffer_size(&self, _context: &mut Context) -> u64 { MAX_BUFFER_SIZE }
+}
+
+static TRACER_HOOKS: TracerHooks = TracerHooks;
+
+/// Creates a JS context with the limits of a tracer and the geth builtins.
+///
+/// The context may execute [`INSTRUCTIONS_PER_SECOND`] instructions per
+/// second of `timeout`, after which every evaluation fails with
+/// [`JsTracerError::Timeout`].
+fn new_context(timeout: Duration) -> Result<Context, JsTracerError> {
+    let instructions = timeout.as_secs_f64() * INSTRUCTIONS_PER_SECOND as f64;
+    let mut ctx = ContextBuilder::new()
+        .host_hooks(&TRACER_HOOKS)
+        .instructions_remaining(instructions as usize)
+        .build()?;
+    let limits = ctx.runtime_limits_mut();
+    limits.set_loop_iteration_limit(LOOP_ITERATION_LIMIT);
+    limits.set_recursion_limit(RECURSION_LIMIT);
+    limits.set_stack_size_limit(STACK_SIZE_LIMIT);
+
+    register_builtins(&mut ctx)?;
+    Ok(ctx)
+}
+
+/// Creates a JS context without instruction budget and with the geth
+/// builtins.
+#[cfg(test)]
+pub(crate) fn test_context() -> Context {
+    let mut ctx = ContextBuilder::new()
+        .instructions_remaining(usize::MAX)
+        .build()
+        .unwrap();
+    register_builtins(&mut ctx).unwrap();
+    ctx
+}
+
+/// The deadline of a tracer.
+#[derive(Clone, Copy)]
+struct Budget {
+    deadline: Instant,
+}
+
+impl Budget {
+    fn new(timeout: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + timeout,
+        }
+    }
+
+    fn check(&self) -> Result<(), JsTracerError> {
+        if Instant::now() > self.deadline {
+            return Err(JsTracerError::Timeout);
+        }
+        Ok(())
+    }
+
+    /// Evaluates `script` on boa's budgeted interpreter loop, which yields
+    /// every [`EXECUTION_BUDGET`] cycles so that the deadline is checked in
+    /// the middle of long running code.
+    ///
+    /// JS functions invoked by builtins run to completion on a nested loop
+    /// and are bounded by the instruction budget of the context instead. If
+    /// the budget runs out the evaluation is abandoned, leaving `ctx`
+    /// unusable.
+    fn run(
+        &self, script: &Script, ctx: &mut Context,
+    ) -> Result<JsValue, JsTracerError> {
+        let mut eval =
+            pin!(script.evaluate_async_with_budget(ctx, EXECUTION_BUDGET));
+        let mut cx = task::Context::from_waker(Waker::noop());
+        loop {
+            match eval.as_mut().poll(&mut cx) {
+                Poll::Ready(res) => return Ok(res?),
+                Poll::Pending => self.check()?,
+            }
+        }
+    }
+}
+
+pub struct JsTracer {
+    ctx: Context,
+    /// The global `__call` object holding the function and the arguments of
+    /// the callback being invoked by `call_script`
+    call_env: JsObject,
+    /// Invokes `__call.fn(...__call.args)`
+    call_script: Script,
+    budget: Budget,
+
+    /// The functions of the tracer object, bound to it
+    result_fn: JsObject,
+    fault_fn: JsObject,
+    /// Invoked when entering a call frame other than the top level one
+    enter_fn: Option<JsObject>,
+    /// Invoked when exiting a call frame other than the top level one
+    exit_fn: Option<JsObject>,
+    /// Invoked for each executed instruction
+    step_fn: Option<JsObject>,
+
+    call_stack: Vec<CallStackItem>,
+    pending_step: Option<StepSnapshot>,
+    tx_gas_limit: u64,
+    block_number: u64,
+    tx_info: Option<TxInfo>,
+
+    started_at: Instant,
+    /// The value returned by `result`, computed when the top level call
+    /// returns while the state is still available
+    result: Option<serde_json::Value>,
+    /// The first error raised by the tracer, which aborts the tracing
+    error: Option<JsTracerError>,
+}
+
+impl JsTracer {
+    /// Evaluates the tracer code and calls its `setup` function with the
+    /// given config.
+    ///
+    /// The evaluation and `setup` count towards the `timeout`.
+    pub fn new(
+        code: &str, config: serde_json::Value, timeout: Duration,
+    ) -> Result<Self, JsTracerError> {
+        let started_at = Instant::now();
+        let budget = Budget::new(timeout);
+        let mut ctx = new_context(timeout)?;
+
+        let call_env = ObjectInitializer::new(&mut ctx)
+            .property(js_string!("fn"), JsValue::undefined(), Attribute::all())
+            .property(
+                js_string!("args"),
+                JsValue::undefined(),
+                Attribute::all(),
+            )
+            .build();
+        ctx.register_global_property(
+            js_string!("__call"),
+            call_env.clone(),
+            Attribute::empty(),
+        )?;
+        let call_script = Script::parse(
+            Source::from_bytes("__call.fn(...__call.args)"),
+            None,
+            &mut ctx,
+        )?;
+
+        let code = format!("({})", code);
+        let obj =
+            Script::parse(Source::from_bytes(code.as_bytes()), None, &mut ctx)
+                .map_err(JsTracerError::EvalCode)
+                .and_then(|script| match budget.run(&script, &mut ctx) {
+                    Err(JsTracerError::JsError(err)) => {
+                        Err(JsTracerError::EvalCode(err))
+                    }
+                    res => res,
+                })?;
+        let obj = obj
+            .as_object()
+            .cloned()
+            .ok_or(JsTracerError::ExpectedJsObject)?;
+
+        let get_fn = |name, ctx: &mut Context| -> Result<_, JsTracerError> {
+            let Some(f) = obj
+                .get(js_string!(name), ctx)?
+                .as_object()
+                .cloned()
+                .filter(|o| o.is_callable())
+            else {
+                return Ok(None);
+            };
+            // Bound functions are called on the budgeted interpreter loop,
+            // unlike `Function.prototype.call`.
+            Ok(Some(BoundFunction::create(
+                f,
+                obj.clone().into(),
+                vec![],
+                ctx,
+            )?))
+        };
+        let result_fn = get_fn("result", &mut ctx)?
+            .ok_or(JsTracerError::ResultFunctionMissing)?;
+        let fault_fn = get_fn("fault", &mut ctx)?
+            .ok_or(JsTracerError::FaultFunctionMissing)?;
+        let enter_fn = get_fn("enter", &mut ctx)?;
+        let exit_fn = get_fn("exit", &mut ctx)?;
+        let step_fn = get_fn("step", &mut ctx)?;
+
+        let setup_fn = if obj.get(js_string!("setup"), &mut ctx)?.is_undefined()
+        {
+            None
+        } else {
+            Some(
+                get_fn("setup", &mut ctx)?
+                    .ok_or(JsTracerError::SetupFunctionNotCallable)?,
+            )
+        };
+        let mut tracer = Self {
+            ctx,
+            call_env,
+            call_script,
+            budget,
+            result_fn,
+            fault_fn,
+            enter_fn,
+            exit_fn,
+            step_fn,
+            call_stack: Vec::new(),
+            pending_step: None,
+            tx_gas_limit: 0,
+            block_number: 0,
+            tx_info: None,
+            started_at,
+            result: None,
+            error: None,
+        };
+        if let Some(setup_fn) = setup_fn {
+            let config = JsValue::from_json(&config, &mut tracer.ctx)
+                .map_err(JsTracerError::InvalidJsonConfig)?;
+            tracer
+                .call(&setup_fn, &[config])
+                .map_err(|err| with_function("setup", err))?;
+        }
+        Ok(tracer)
+    }
+
+    /// Creates the tracer for `GethDebugTracerType::JsTracer(code)` with the
+    /// `tracerConfig` and `timeout` of the tracing options.
+    pub fn from_opts(
+        code: &str, opts: &GethDebugTracingOptions,
+    ) -> Result<Self, JsTracerError> {
+        Self::new(code, opts.tracer_config.0.clone(), timeout(opts)?)
+    }
+
+    /// Checks the `timeout` tracing option and the syntax of the tracer code
+    /// without running it.
+    pub fn check_opts(
+        code: &str, opts: &GethDebugTracingOptions,
+    ) -> Result<(), JsTracerError> {
+        timeout(opts)?;
+        let mut ctx = ContextBuilder::new().build()?;
+        let code = format!("({})", code);
+        Script::parse(Source::from_bytes(code.as_bytes()), None, &mut ctx)
+            .map_err(JsTracerError::EvalCode)?;
+        Ok(())
+    }
+
+    pub(crate) fn set_tx_exec_context(&mut self, context: &TxExecContext) {
+        self.tx_gas_limit = context.tx_gas_limit;
+        self.block_number = context.block_height;
+    }
+
+    /// Registers the global `isPrecompiled` function.
+    pub(crate) fn register_precompiles(
+        &mut self, precompiles: impl IntoIterator<Item = H160>,
+    ) {
+        let list = PrecompileList(
+            precompiles.into_iter().map(to_alloy_address).collect(),
+        );
+        if let Err(err) = list.register_callable(&mut self.ctx) {
+            self.error = Some(err.into());
+        }
+    }
+
+    /// Returns the value returned by `result`, or the first error raised by
+    /// the tracer.
+    pub fn into_result(mut self) -> Result<serde_json::Value, JsTracerError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        if let Some(result) = self.result {
+            return Ok(result);
+        }
+        // The transaction did not execute any frame, `result` is still
+        // expected to produce an output.
+        let ctx = JsEvmContext {
+            r#type: "CALL".into(),
+            gas: self.tx_gas_limit,
+            block: self.block_number,
+            time: format!("{:?}", self.started_at.elapsed()),
+            ..Default::default()
+        };
+        let ctx = ctx.into_js_object(&mut self.ctx)?;
+        let result_fn = self.result_fn.clone();
+        let result = self
+            .call(&result_fn, &[ctx.into(), JsValue::undefined()])
+            .map_err(|err| with_function("result", err))?;
+        Ok(to_serde_value(result, MAX_RESULT_SIZE, &mut self.ctx)?)
+    }
+
+    pub(crate) fn do_trace_opcode(&self) -> bool { self.error.is_none() }
+
+    pub(crate) fn step(&mut self, interp: &dyn InterpreterInfo) {
+        if !self.check_running() {
+            return;
+        }
+        self.pending_step = Some(StepSnapshot {
+            pc: interp.program_counter(),
+            op: interp.current_opcode(),
+            gas_remaining: interp.gas_remainning().as_u64(),
+            cost: 0,
+            depth: self.call_stack.len() as u64,
+            overwritten: self
+                .step_fn
+                .is_some()
+                .then(|| Overwritten::new(interp)),
+        });
+    }
+
+    pub(crate) fn step_end(
+        &mut self, interp: &dyn InterpreterInfo, state: &State,
+    ) {
+        let Some(mut step) = self.pending_step.take() else {
+            return;
+        };
+        if !self.check_running() {
+            return;
+        }
+        step.cost = step
+            .gas_remaining
+            .saturating_sub(interp.gas_remainning().as_u64());
+
+        if let (Some(step_fn), Some(overwritten)) =
+            (self.step_fn.clone(), step.overwritten.take())
+        {
+            let (stack, _stack_guard) = StackRef::before_step(
+                interp.stack(),
+                overwritten.stack_len,
+                overwritten.popped,
+            );
+            let (memory, _memory_guard) = MemoryRef::before_step(
+                interp.mem(),
+                overwritten.memory_len,
+                overwritten.memory,
+            );
+            let res = self
+                .call_with_step(&step_fn, &step, None, stack, memory, state);
+            self.check_call("step", res);
+        }
+
+        if let Some(call) = self.call_stack.last_mut() {
+            call.last_step = Some(step);
+        }
+    }
+
+    pub(crate) fn record_call(&mut self, params: &ActionParams) {
+        if !self.check_running() {
+            return;
+        }
+        let kind = match params.call_type {
+            CallType::None => match params.create_type {
+                CreateType::CREATE2 => CallKind::Create2,
+                _ => CallKind::Create,
+            },
+            call_type => call_type.into(),
+        };
+        let input: Bytes = params
+            .data
+            .clone()
+            .or_else(|| params.code.as_ref().map(|code| code.to_vec()))
+            .unwrap_or_default()
+            .into();
+        let contract = Contract {
+            caller: to_alloy_address(params.sender),
+            address: to_alloy_address(params.address),
+            value: to_alloy_u256(params.value.value()),
+            input,
+        };
+        let gas_limit = params.gas.as_u64();
+
+        if self.call_stack.is_empty() {
+            self.tx_info = Some(TxInfo {
+                contract: contract.clone(),
+                kind,
+                gas_price: to_alloy_u256(params.gas_price),
+                intrinsic_gas: self.tx_gas_limit.saturating_sub(gas_limit),
+            });
+        } else if let Some(enter_fn) = self.enter_fn.clone() {
+            // determine correct `from` and `to` based on the call scheme
+            let (from, to) = match params.call_type {
+                CallType::DelegateCall | CallType::CallCode => {
+                    (params.address, params.code_address)
+                }
+                _ => (params.sender, params.address),
+            };
+            let frame = CallFrame {
+                contract: Contract {
+                    caller: to_alloy_address(from),
+                    address: to_alloy_address(to),
+                    ..contract.clone()
+                },
+                kind: kind.to_string(),
+                gas: gas_limit,
+            };
+            let res = self.call_with_frame(&enter_fn, frame);
+            self.check_call("enter", res);
+        }
+
+        self.call_stack.push(CallStackItem {
+            contract,
+            kind,
+            gas_limit,
+            space: params.space,
+            last_step: None,
+        });
+    }
+
+    pub(crate) fn record_call_result(
+        &mut self, result: &FrameResult, state: &State,
+    ) {
+        if !self.check_running() {
+            return;
+        }
+        let Some(call) = self.call_stack.pop() else {
+            return;
+        };
+
+        let error =
+            fmt_error_msg(to_instruction_result(result), TraceStyle::Geth);
+        let gas_left = result.as_ref().map_or(0, |r| r.gas_left.as_u64());
+        let output: Bytes = result
+            .as_ref()
+            .map(|r| r.return_data.to_vec())
+            .unwrap_or_default()
+            .into();
+
+        if let (Some(err), Some(step)) = (&error, &call.last_step) {
+            let fault_fn = self.fault_fn.clone();
+            let (stack, _stack_guard) = StackRef::new(&EMPTY_STACK);
+            let (memory, _memory_guard) = MemoryRef::new(&EMPTY_MEMORY);
+            let res = self.call_with_step(
+                &fault_fn,
+                step,
+                Some(err.clone()),
+                stack,
+                memory,
+                state,
+            );
+            self.check_call("fault", res);
+            if !self.check_running() {
+                return;
+            }
+        }
+
+        if !self.call_stack.is_empty() {
+            if let Some(exit_fn) = self.exit_fn.clone() {
+                let frame_result = CallFrameResult {
+                    gas_used: call.gas_limit.saturating_sub(gas_left),
+                    output,
+                    error,
+                };
+                let res = self.call_with_frame_result(&exit_fn, frame_result);
+                self.check_call("exit", res);
+            }
+            return;
+        }
+
+        // The top level call returned, produce the result while the state
+        // of the transaction is still available to `db`.
+        let Some(TxInfo {
+            contract,
+            kind,
+            gas_price,
+            intrinsic_gas,
+        }) = self.tx_info.take()
+        else {
+            return;
+        };
+        let is_create = kind.is_any_create();
+        let ctx = JsEvmContext {
+            r#type: if is_create { "CREATE" } else { "CALL" }.into(),
+            from: contract.caller,
+            to: (!is_create || error.is_none()).then_some(contract.address),
+            input: contract.input,
+            gas: self.tx_gas_limit,
+            gas_used: self.tx_gas_limit.saturating_sub(gas_left),
+            gas_price,
+            intrinsic_gas,
+            value: contract.value,
+            block: self.block_number,
+            output,
+            time: format!("{:?}", self.started_at.elapsed()),
+            error,
+        };
+        let res = self.call_result(ctx, call.space, state);
+        match res {
+            Ok(result) => self.result = Some(result),
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    pub(crate) fn selfdestruct(
+        &mut self, contract: &H160, target: &H160, value: CfxU256,
+    ) {
+        if !self.check_running() {
+            return;
+        }
+        // A selfdestruct is reported as a frame that is entered and exited
+        // immediately.
+        if let Some(enter_fn) = self.enter_fn.clone() {
+            let frame = CallFrame {
+                contract: Contract {
+                    caller: to_alloy_address(*contract),
+                    address: to_alloy_address(*target),
+                    value: to_alloy_u256(value),
+                    input: Bytes::new(),
+                },
+                kind: "SELFDESTRUCT".into(),
+                gas: 0,
+            };
+            let res = self.call_with_frame(&enter_fn, frame);
+            self.check_call("enter", res);
+        }
+        if let Some(exit_fn) = self.exit_fn.clone() {
+            if !self.check_running() {
+                return;
+            }
+            let res = self
+                .call_with_frame_result(&exit_fn, CallFrameResult::default());
+            self.check_call("exit", res);
+        }
+    }
+
+    /// Returns whether the tracer can still run, recording an error once the
+    /// budget has run out.
+    fn check_running(&mut self) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        if let Err(err) = self.budget.check() {
+            self.error = Some(err);
+            return false;
+        }
+        true
+    }
+
+    fn check_call(
+        &mut self, function: &'static str, res: Result<(), JsTracerError>,
+    ) {
+        if let (Err(err), None) = (res, &self.error) {
+            self.error = Some(with_function(function, err));
+        }
+    }
+
+    /// Calls a function of the tracer within the budget.
+    fn call(
+        &mut self, f: &JsObject, args: &[JsValue],
+    ) -> Result<JsValue, JsTracerError> {
+        let args = JsArray::from_iter(args.iter().cloned(), &mut self.ctx);
+        self.call_env
+            .set(js_string!("fn"), f.clone(), true, &mut self.ctx)?;
+        self.call_env
+            .set(js_string!("args"), args, true, &mut self.ctx)?;
+        self.budget.run(&self.call_script, &mut self.ctx)
+    }
+
+    fn call_with_step(
+        &mut self, f: &JsObject, step: &StepSnapshot, error: Option<String>,
+        stack: StackRef, memory: MemoryRef, state: &State,
+    ) -> Result<(), JsTracerError> {
+        let Some(call) = self.call_stack.last() else {
+            return Ok(());
+        };
+        let (db, _db_guard) = EvmDbRef::new(state, call.space);
+        let log = StepLog {
+            stack,
+            op: OpObj(step.op),
+            memory,
+            pc: step.pc,
+            gas_remaining: step.gas_remaining,
+            cost: step.cost,
+            depth: step.depth,
+            // the refund counter is not exposed to tracers
+            refund: 0,
+            error,
+            contract: call.contract.clone(),
+        };
+        let log = log.into_js_object(&mut self.ctx)?;
+        let db = db.into_js_object(&mut self.ctx)?;
+        self.call(f, &[log.into(), db.into()])?;
+        Ok(())
+    }
+
+    fn call_with_frame(
+        &mut self, f: &JsObject, frame: CallFrame,
+    ) -> Result<(), JsTracerError> {
+        let frame = frame.into_js_object(&mut self.ctx)?;
+        self.call(f, &[frame.into()])?;
+        Ok(())
+    }
+
+    fn call_with_frame_result(
+        &mut self, f: &JsObject, frame_result: CallFrameResult,
+    ) -> Result<(), JsTracerError> {
+        let frame_result = frame_result.into_js_object(&mut self.ctx)?;
+        self.call(f, &[frame_result.into()])?;
+        Ok(())
+    }
+
+    fn call_result(
+        &mut self, ctx: JsEvmContext, space: Space, state: &State,
+    ) -> Result<serde_json::Value, JsTracerError> {
+        let (db, _db_guard) = EvmDbRef::new(state, space);
+        let ctx = ctx.into_js_object(&mut self.ctx)?;
+        let db = db.into_js_object(&mut self.ctx)?;
+        let result_fn = self.result_fn.clone();
+        let result = self
+            .call(&result_fn, &[ctx.into(), db.into()])
+            .map_err(|err| with_function("result", err))?;
+        Ok(to_serde_value(result, MAX_RESULT_SIZE, &mut self.ctx)?)
+    }
+}
+
+static EMPTY_STACK: Vec<CfxU256> = Vec::new();
+static EMPTY_MEMORY: Vec<u8> = Vec::new();
+
+/// Returns the `timeout` tracing option.
+fn timeout(opts: &GethDebugTracingOptions) -> Result<Duration, JsTracerError> {
+    match &opts.timeout {
+        Some(timeout) => parse_duration(timeout)
+            .ok_or_else(|| JsTracerError::InvalidTimeout(timeout.clone())),
+        None => Ok(DEFAULT_TIMEOUT),
+    }
+}
+
+/// Returns the largest number of stack items popped by the opcode in any
+/// instruction set.
+fn stack_pop_count(op: u8) -> usize {
+    [
+        &*INSTRUCTIONS,
+        &*INSTRUCTIONS_CANCUN,
+        &*INSTRUCTIONS_CIP645,
+        &*INSTRUCTIONS_EIP7939,
+    ]
+    .iter()
+    .filter_map(|instructions| instructions[op as usize].as_ref())
+    .map(|info| info.args)
+    .max()
+    .unwrap_or(0)
+}
+
+/// Returns the offset and the size of the memory the opcode may write before
+/// the next step, read from the stack before it is executed.
+///
+/// The output of a call is written when the call returns, unless it is
+/// executed synchronously, e.g. a precompile.
+fn memory_written(op: u8, stack: &[CfxU256]) -> Option<(usize, usize)> {
+    let read = |pos: usize| {
+        let value = stack[stack.len().checked_sub(pos + 1)?];
+        Some(
+            if value > CfxU256::from(usize::MAX) {
+                usize::MAX
+            } else {
+                value.as_usize()
+            },
+        )
+    };
+    match Instruction::from_u8(op)? {
+        instructions::MSTORE => Some((read(0)?, 32)),
+        instructions::MSTORE8 => Some((read(0)?, 1)),
+        instructions::CALLDATACOPY
+        | instructions::CODECOPY
+        | instructions::RETURNDATACOPY
+        | instructions::JUMPSUB_MCOPY => Some((read(0)?, read(2)?)),
+        instructions::EXTCODECOPY => Some((read(1)?, read(3)?)),
+        instructions::CALL | instructions::CALLCODE => {
+            Some((read(5)?, read(6)?))
+        }
+        instructions::DELEGATECALL | instructions::STATICCALL => {
+            Some((read(4)?, read(5)?))
+        }
+        _ => None,
+    }
+}
+
+/// Attributes an error raised by JS code to the tracer function `function`.
+fn with_function(function: &'static str, err: JsTracerError) -> JsTracerError {
+    match err {
+        JsTracerError::JsError(err) => {
+            JsTracerError::CallFailed { function, err }
+        }
+        err => err,
+    }
+}
+
+/// Parses a duration in the format of Go's `time.ParseDuration`, e.g. `5s`,
+/// `300ms` or `1m30s`.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    if s == "0" {
+        return Some(Duration::ZERO);
+    }
+    let mut rest = s;
+    let mut total = Duration::ZERO;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let nanos_per_unit = match &rest[..unit_len] {
+            "ns" => 1.,
+            "us" | "µs" => 1e3,
+            "ms" => 1e6,
+            "s" => 1e9,
+            "m" => 60e9,
+            "h" => 3600e9,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+
+        total += Duration::from_nanos((number * nanos_per_unit) as u64);
+    }
+    (!s.is_empty()).then_some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loops too long for the timeout of the tests, though every loop is
+    /// within the loop iteration limit.
+    const NESTED_LOOPS: &str =
+        "for (var i = 0; i < 100000; i++) for (var j = 0; j < 100000; j++) {}";
+
+    struct MockInterpreter {
+        op: u8,
+        stack: Vec<CfxU256>,
+        memory: Vec<u8>,
+    }
+
+    impl InterpreterInfo for MockInterpreter {
+        fn gas_remainning(&self) -> CfxU256 { 21000.into() }
+
+        fn program_counter(&self) -> u64 { 1 }
+
+        fn current_opcode(&self) -> u8 { self.op }
+
+        fn opcode(&self, _pc: u64) -> Option<u8> { Some(self.op) }
+
+        fn mem(&self) -> &Vec<u8> { &self.memory }
+
+        fn stack(&self) -> &Vec<CfxU256> { &self.stack }
+
+        fn return_stack(&self) -> &Vec<usize> { unimplemented!() }
+
+        fn contract_address(&self) -> H160 { H160::zero() }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("5s"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_duration("300ms"), Some(Duration::from_millis(300)));
+        assert_eq!(parse_duration("1m30s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1.5h"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("0"), Some(Duration::ZERO));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("10"), None);
+        assert_eq!(parse_duration("10x"), None);
+    }
+
+    #[test]
+    fn test_new_tracer() {
+        let code = "{data: [], fault: function() {}, result: function() { \
+                    return this.data; }, setup: function(cfg) { \
+                    this.data.push(cfg.x); }}";
+        let config = serde_json::json!({ "x": 1 });
+        let tracer = JsTracer::new(code, config, DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(tracer.into_result().unwrap(), serde_json::json!([1]));
+
+        let err = JsTracer::new(
+            "{fault: function() {}}",
+            Default::default(),
+            DEFAULT_TIMEOUT,
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, JsTracerError::ResultFunctionMissing));
+
+        let err = JsTracer::new("{", Default::default(), DEFAULT_TIMEOUT)
+            .err()
+            .unwrap();
+        assert!(matches!(err, JsTracerError::EvalCode(_)));
+    }
+
+    #[test]
+    fn test_loop_iteration_limit() {
+        let code = "{fault: function() {}, result: function() { \
+                    while (true) {} }}";
+        let tracer =
+            JsTracer::new(code, Default::default(), DEFAULT_TIMEOUT).unwrap();
+        let err = tracer.into_result().unwrap_err();
+        assert!(matches!(
+            err,
+            JsTracerError::CallFailed {
+                function: "result",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let timeout = Duration::from_millis(100);
+        let code = format!(
+            "{{fault: function() {{}}, result: function() {{}}, \
+             setup: function() {{ {NESTED_LOOPS} }}}}"
+        );
+        let err = JsTracer::new(&code, Default::default(), timeout)
+            .err()
+            .unwrap();
+        assert!(matches!(err, JsTracerError::Timeout));
+
+        let code = format!("(function() {{ {NESTED_LOOPS} }})()");
+        let err = JsTracer::new(&code, Default::default(), timeout)
+            .err()
+            .unwrap();
+        assert!(matches!(err, JsTracerError::Timeout));
+
+        let code = format!(
+            "{{fault: function() {{}}, result: function() {{ {NESTED_LOOPS} \
+             }}}}"
+        );
+        let tracer = JsTracer::new(&code, Default::default(), timeout).unwrap();
+        assert!(matches!(
+            tracer.into_result().unwrap_err(),
+            JsTracerError::Timeout
+        ));
+    }
+
+    #[test]
+    fn test_recursion_limit() {
+        // Recursing through a builtin nests on the native stack.
+        let code = "{fault: function() {}, result: function() { \
+                    function f() { return [1].map(f); } return f(); }}";
+        let tracer =
+            JsTracer::new(code, Default::default(), DEFAULT_TIMEOUT).unwrap();
+        assert!(matches!(
+            tracer.into_result().unwrap_err(),
+            JsTracerError::CallFailed {
+                function: "result",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_timeout_in_builtin_callbacks() {
+        // The callbacks run on the nested interpreter loop of `map`.
+        let code = "{fault: function() {}, result: function() { \
+                    var a = new Array(1000).fill(0); \
+                    return a.map(function() { return a.map(function() { \
+                    for (var i = 0; i < 100000; i++) {} }); }); }}";
+        let timeout = Duration::from_millis(100);
+        let tracer = JsTracer::new(code, Default::default(), timeout).unwrap();
+        assert!(matches!(
+            tracer.into_result().unwrap_err(),
+            JsTracerError::Timeout
+        ));
+    }
+
+    #[test]
+    fn test_buffer_size_limit() {
+        let code = format!(
+            "{{fault: function() {{}}, result: function() {{ \
+             return new ArrayBuffer({}).byteLength; }}}}",
+            MAX_BUFFER_SIZE + 1
+        );
+        let tracer =
+            JsTracer::new(&code, Default::default(), DEFAULT_TIMEOUT).unwrap();
+        assert!(matches!(
+            tracer.into_result().unwrap_err(),
+            JsTracerError::CallFailed {
+                function: "result",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_check_opts() {
+        // `setup` is not run
+        let code = "{fault: function() {}, result: function() {}, \
+                    setup: function() { throw 'setup'; }}";
+        JsTracer::check_opts(code, &Default::default()).unwrap();
+
+        let err = JsTracer::check_opts("{", &Default::default()).unwrap_err();
+        assert!(matches!(err, JsTracerError::EvalCode(_)));
+
+        let opts = GethDebugTracingOptions {
+            timeout: Some("10x".into()),
+            ..Default::default()
+        };
+        let err = JsTracer::check_opts(code, &opts).unwrap_err();
+        assert!(matches!(err, JsTracerError::InvalidTimeout(_)));
+    }
+
+    #[test]
+    fn test_tracer_without_step() {
+        let interp = MockInterpreter {
+            op: 0x01,
+            stack: vec![1.into(), 2.into()],
+            memory: vec![0; 64],
+        };
+
+        let code = "{fault: function() {}, result: function() { return 1; }}";
+        let mut tracer =
+            JsTracer::new(code, Default::default(), DEFAULT_TIMEOUT).unwrap();
+        tracer.step(&interp);
+        let step = tracer.pending_step.as_ref().unwrap();
+        assert_eq!((step.pc, step.op), (1, 0x01));
+        assert!(step.overwritten.is_none());
+        assert_eq!(tracer.into_result().unwrap(), serde_json::json!(1));
+
+        let code = "{fault: function() {}, result: function() {}, \
+                    step: function() {}}";
+        let mut tracer =
+            JsTracer::new(code, Default::default(), DEFAULT_TIMEOUT).unwrap();
+        tracer.step(&interp);
+        let overwritten = tracer
+            .pending_step
+            .as_ref()
+            .unwrap()
+            .overwritten
+            .as_ref()
+            .unwrap();
+        // ADD pops two items and writes no memory
+        assert_eq!(overwritten.popped, interp.stack);
+        assert_eq!(overwritten.memory, (0, vec![]));
+    }
+
+    #[test]
+    fn test_step_before_execution() {
+        // MSTORE of 0x2a at offset 16, which expands the memory to 64 bytes
+        let before = MockInterpreter {
+            op: instructions::MSTORE as u8,
+            stack: vec![7.into(), 0x2a.into(), 16.into()],
+            memory: vec![0xff; 32],
+        };
+        let overwritten = Overwritten::new(&before);
+        assert_eq!(overwritten.memory, (16, vec![0xff; 16]));
+
+        let mut memory_after = vec![0xff; 16];
+        memory_after.extend([0; 31]);
+        memory_after.push(0x2a);
+        memory_after.extend([0; 16]);
+        let stack_after = vec![7.into()];
+
+        let mut ctx = test_context();
+        let (stack, _stack_guard) = StackRef::before_step(
+            &stack_after,
+            overwritten.stack_len,
+            overwritten.popped,
+        );
+        let (memory, _memory_guard) = MemoryRef::before_step(
+            &memory_after,
+            overwritten.memory_len,
+            overwritten.memory,
+        );
+        let stack = stack.into_js_object(&mut ctx).unwrap();
+        let memory = memory.into_js_object(&mut ctx).unwrap();
+        let f = ctx
+            .eval(Source::from_bytes(
+                "(function(s, m) { return [s.length(), s.peek(0), s.peek(1), \
+                 s.peek(2), m.length(), toHex(m.slice(0, 48))]; })",
+            ))
+            .unwrap();
+        let res = f
+            .as_callable()
+            .unwrap()
+            .call(
+                &JsValue::undefined(),
+                &[stack.into(), memory.into()],
+                &mut ctx,
+            )
+            .unwrap();
+        let res = to_serde_value(res, usize::MAX, &mut ctx).unwrap();
+        let memory_before = format!("{}{}", "ff".repeat(32), "00".repeat(16));
+        assert_eq!(
+            res,
+            serde_json::json!([3, "16", "42", "7", 32, memory_before])
+        );
+    }
+}
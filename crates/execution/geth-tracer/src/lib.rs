@@ -5,6 +5,9 @@ mod fourbyte;
 mod gas;
 mod geth_builder;
 mod geth_tracer;
+mod js_tracer;
+mod mux;
+mod parity_builder;
 mod tracing_inspector;
 mod types;
 mod utils;
@@ -12,8 +15,11 @@ mod utils;
 use arena::CallTraceArena;
 use config::TracingInspectorConfig;
 use geth_builder::GethTraceBuilder;
+use parity_builder::ParityTraceBuilder;
 
 pub use geth_tracer::{GethTraceKey, GethTracer};
+pub use js_tracer::{JsTracer, JsTracerError};
+pub use mux::check_mux_config;
 pub use types::{GethTraceWithHash, TxExecContext};
 pub use utils::{
     from_alloy_address, to_alloy_address, to_alloy_h256, to_alloy_u256,
@@ -0,0 +1,152 @@
+use crate::{geth_tracer::GethTracer, types::TxExecContext};
+use alloy_rpc_types_trace::geth::{
+    mux::{MuxConfig, MuxFrame},
+    GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingOptions,
+    GethTrace,
+};
+use cfx_executor::{
+    machine::Machine,
+    observer::{CallTracer, OpcodeTracer},
+    stack::FrameResult,
+    state::State,
+};
+use cfx_types::{Address, Space, H256, U256};
+use cfx_vm_types::{ActionParams, InterpreterInfo};
+use std::{collections::HashMap, sync::Arc};
+
+/// Runs several built-in tracers over the same transaction execution, like
+/// geth's `muxTracer`.
+///
+/// Every tracer of the [MuxConfig] is a standalone [GethTracer] receiving
+/// all the hooks, so the result of each tracer is the same as running it
+/// alone.
+pub struct MuxInspector(Vec<(GethDebugBuiltInTracerType, GethTracer)>);
+
+/// Checks that `config` can be run by [MuxInspector]: a `muxTracer` can not
+/// be nested in another one, and the `prestateTracer` is not supported since
+/// the sub tracers are not given the state before the transaction.
+pub fn check_mux_config(config: &MuxConfig) -> Result<(), String> {
+    if config
+        .0
+        .contains_key(&GethDebugBuiltInTracerType::MuxTracer)
+    {
+        return Err("muxTracer can not be nested in a muxTracer".into());
+    }
+    if config
+        .0
+        .contains_key(&GethDebugBuiltInTracerType::PreStateTracer)
+    {
+        return Err("prestateTracer is not supported in a muxTracer".into());
+    }
+    Ok(())
+}
+
+impl MuxInspector {
+    pub fn new(
+        config: MuxConfig, tx_exec_context: &TxExecContext,
+        machine: &Arc<Machine>, opts: &GethDebugTracingOptions,
+    ) -> Self {
+        let tracers = config
+            .0
+            .into_iter()
+            .map(|(tracer_type, tracer_config)| {
+                let opts = GethDebugTracingOptions {
+                    config: opts.config.clone(),
+                    tracer: Some(GethDebugTracerType::BuiltInTracer(
+                        tracer_type,
+                    )),
+                    tracer_config: tracer_config.unwrap_or_default(),
+                    ..Default::default()
+                };
+                let tracer = GethTracer::new(
+                    tx_exec_context.clone(),
+                    Arc::clone(machine),
+                    opts,
+                );
+                (tracer_type, tracer)
+            })
+            .collect();
+        Self(tracers)
+    }
+
+    pub fn drain(self) -> Result<GethTrace, String> {
+        let mut frame = HashMap::with_capacity(self.0.len());
+        for (tracer_type, tracer) in self.0 {
+            frame.insert(tracer_type, tracer.drain()?);
+        }
+        Ok(GethTrace::MuxTracer(MuxFrame(frame)))
+    }
+
+    fn tracers(&mut self) -> impl Iterator<Item = &mut GethTracer> {
+        self.0.iter_mut().map(|(_, tracer)| tracer)
+    }
+}
+
+impl CallTracer for MuxInspector {
+    fn record_call(&mut self, params: &ActionParams) {
+        self.tracers().for_each(|t| t.record_call(params));
+    }
+
+    fn record_call_result(&mut self, result: &FrameResult) {
+        self.tracers().for_each(|t| t.record_call_result(result));
+    }
+
+    fn record_create(&mut self, params: &ActionParams) {
+        self.tracers().for_each(|t| t.record_create(params));
+    }
+
+    fn record_create_result(&mut self, result: &FrameResult) {
+        self.tracers().for_each(|t| t.record_create_result(result));
+    }
+
+    fn record_call_result_with_state(
+        &mut self, result: &FrameResult, state: &State,
+    ) {
+        self.tracers()
+            .for_each(|t| t.record_call_result_with_state(result, state));
+    }
+
+    fn record_create_result_with_state(
+        &mut self, result: &FrameResult, state: &State,
+    ) {
+        self.tracers()
+            .for_each(|t| t.record_create_result_with_state(result, state));
+    }
+}
+
+impl OpcodeTracer for MuxInspector {
+    fn do_trace_opcode(&self, enabled: &mut bool) {
+        self.0.iter().for_each(|(_, t)| t.do_trace_opcode(enabled));
+    }
+
+    fn initialize_interp(&mut self, gas_limit: U256) {
+        self.tracers().for_each(|t| t.initialize_interp(gas_limit));
+    }
+
+    fn step(&mut self, interp: &dyn InterpreterInfo) {
+        self.tracers().for_each(|t| t.step(interp));
+    }
+
+    fn step_end(&mut self, interp: &dyn InterpreterInfo) {
+        self.tracers().for_each(|t| t.step_end(interp));
+    }
+
+    fn step_end_with_state(
+        &mut self, interp: &dyn InterpreterInfo, state: &State,
+    ) {
+        self.tracers()
+            .for_each(|t| t.step_end_with_state(interp, state));
+    }
+
+    fn log(&mut self, address: &Address, topics: &Vec<H256>, data: &[u8]) {
+        self.tracers().for_each(|t| t.log(address, topics, data));
+    }
+
+    fn selfdestruct(
+        &mut self, space: Space, contract: &Address, target: &Address,
+        value: U256,
+    ) {
+        self.tracers()
+            .for_each(|t| t.selfdestruct(space, contract, target, value));
+    }
+}
@@ -0,0 +1,275 @@
+// Copyright 2023-2024 Paradigm.xyz
+// This file is part of reth.
+// Reth is a modular, contributor-friendly and blazing-fast implementation of
+// the Ethereum protocol
+
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+use crate::{types::CallTraceNode, TracingInspectorConfig};
+use alloy_primitives::Address;
+use alloy_rpc_types::TransactionInfo;
+use alloy_rpc_types_trace::parity::{
+    LocalizedTransactionTrace, TransactionTrace,
+};
+use std::collections::HashSet;
+
+/// A type for creating parity style traces
+///
+/// Note: Parity style traces always ignore calls to precompiles.
+#[derive(Clone, Debug)]
+pub struct ParityTraceBuilder {
+    /// Recorded trace nodes
+    nodes: Vec<CallTraceNode>,
+    /// How the traces were recorded
+    _config: TracingInspectorConfig,
+}
+
+impl ParityTraceBuilder {
+    /// Returns a new instance of the builder
+    pub fn new(
+        nodes: Vec<CallTraceNode>, _config: TracingInspectorConfig,
+    ) -> Self {
+        Self { nodes, _config }
+    }
+
+    /// Returns a list of all addresses that appeared as callers.
+    pub fn callers(&self) -> HashSet<Address> {
+        self.nodes.iter().map(|node| node.trace.caller).collect()
+    }
+
+    /// Manually the gas used of the root trace.
+    ///
+    /// The root trace's gasUsed should mirror the actual gas used by the
+    /// transaction.
+    #[inline]
+    pub fn set_transaction_gas_used(&mut self, gas_used: u64) {
+        if let Some(node) = self.nodes.first_mut() {
+            node.trace.gas_used = gas_used;
+        }
+    }
+
+    /// Convenience function for
+    /// [ParityTraceBuilder::set_transaction_gas_used] that consumes the
+    /// type.
+    #[inline]
+    pub fn with_transaction_gas_used(mut self, gas_used: u64) -> Self {
+        self.set_transaction_gas_used(gas_used);
+        self
+    }
+
+    /// Returns the trace addresses of all call nodes in the set
+    ///
+    /// Each entry in the returned vector represents the
+    /// [Self::trace_address] of the corresponding node in the nodes set.
+    ///
+    /// CAUTION: This also includes precompiles, which have an empty trace
+    /// address.
+    fn trace_addresses(&self) -> Vec<Vec<usize>> {
+        let mut all_addresses = Vec::with_capacity(self.nodes.len());
+        for idx in 0..self.nodes.len() {
+            all_addresses.push(self.trace_address(idx));
+        }
+        all_addresses
+    }
+
+    /// Returns the `traceAddress` of the node in the arena
+    ///
+    /// The `traceAddress` field of all returned traces, gives the exact
+    /// location in the call trace [index in root, index in first CALL,
+    /// index in second CALL, …].
+    ///
+    /// # Panics
+    ///
+    /// if the `idx` does not belong to a node
+    ///
+    /// Note: if the call node of `idx` is a precompile, the returned trace
+    /// address will be empty.
+    fn trace_address(&self, idx: usize) -> Vec<usize> {
+        if idx == 0 {
+            // root call has empty traceAddress
+            return vec![];
+        }
+        let mut graph = vec![];
+        let mut node = &self.nodes[idx];
+        if node.is_precompile() {
+            return graph;
+        }
+        while let Some(parent) = node.parent {
+            // the index of the child call in the arena
+            let child_idx = node.idx;
+            node = &self.nodes[parent];
+            // find the index of the child call in the parent node
+            let call_idx = node
+                .children
+                .iter()
+                .position(|child| *child == child_idx)
+                .expect("non precompile child call exists in parent");
+            graph.push(call_idx);
+        }
+        graph.reverse();
+        graph
+    }
+
+    /// Returns an iterator over all recorded traces for `trace_transaction`
+    pub fn into_localized_transaction_traces_iter(
+        self, info: TransactionInfo,
+    ) -> impl Iterator<Item = LocalizedTransactionTrace> {
+        self.into_transaction_traces_iter().map(move |trace| {
+            let TransactionInfo {
+                hash,
+                index,
+                block_hash,
+                block_number,
+                ..
+            } = info;
+            LocalizedTransactionTrace {
+                trace,
+                transaction_position: index,
+                transaction_hash: hash,
+                block_number,
+                block_hash,
+            }
+        })
+    }
+
+    /// Returns all recorded traces for `trace_transaction`
+    pub fn into_localized_transaction_traces(
+        self, info: TransactionInfo,
+    ) -> Vec<LocalizedTransactionTrace> {
+        self.into_localized_transaction_traces_iter(info).collect()
+    }
+
+    /// Returns an iterator over all recorded traces for `trace_transaction`
+    pub fn into_transaction_traces_iter(
+        self,
+    ) -> impl Iterator<Item = TransactionTrace> {
+        let trace_addresses = self.trace_addresses();
+        TransactionTraceIter {
+            next_selfdestruct: None,
+            iter: self
+                .nodes
+                .into_iter()
+                .zip(trace_addresses)
+                .filter(|(node, _)| !node.is_precompile())
+                .map(|(node, trace_address)| {
+                    (node.parity_transaction_trace(trace_address), node)
+                }),
+        }
+    }
+
+    /// Returns the raw traces of the transaction
+    pub fn into_transaction_traces(self) -> Vec<TransactionTrace> {
+        self.into_transaction_traces_iter().collect()
+    }
+}
+
+/// An iterator for [TransactionTrace]s
+struct TransactionTraceIter<Iter> {
+    iter: Iter,
+    next_selfdestruct: Option<TransactionTrace>,
+}
+
+impl<Iter> Iterator for TransactionTraceIter<Iter>
+where Iter: Iterator<Item = (TransactionTrace, CallTraceNode)>
+{
+    type Item = TransactionTrace;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(selfdestruct) = self.next_selfdestruct.take() {
+            return Some(selfdestruct);
+        }
+        let (mut trace, node) = self.iter.next()?;
+        if node.is_selfdestruct() {
+            // since selfdestructs are emitted as additional trace, increase
+            // the trace count
+            let mut addr = trace.trace_address.clone();
+            addr.push(trace.subtraces);
+            // need to account for the additional selfdestruct trace
+            trace.subtraces += 1;
+            self.next_selfdestruct = node.parity_selfdestruct_trace(addr);
+        }
+        Some(trace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{CallKind, CallTrace};
+    use alloy_rpc_types_trace::parity::Action;
+
+    fn node(
+        idx: usize, parent: Option<usize>, children: Vec<usize>, kind: CallKind,
+    ) -> CallTraceNode {
+        CallTraceNode {
+            parent,
+            children,
+            idx,
+            trace: CallTrace {
+                kind,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_trace_addresses() {
+        // root -> [call -> [create], call]
+        let nodes = vec![
+            node(0, None, vec![1, 3], CallKind::Call),
+            node(1, Some(0), vec![2], CallKind::DelegateCall),
+            node(2, Some(1), vec![], CallKind::Create2),
+            node(3, Some(0), vec![], CallKind::StaticCall),
+        ];
+        let traces =
+            ParityTraceBuilder::new(nodes, TracingInspectorConfig::none())
+                .into_transaction_traces();
+
+        let addresses: Vec<_> =
+            traces.iter().map(|t| t.trace_address.clone()).collect();
+        assert_eq!(addresses, vec![vec![], vec![0], vec![0, 0], vec![1]]);
+        assert_eq!(traces[0].subtraces, 2);
+        assert!(matches!(traces[2].action, Action::Create(_)));
+    }
+
+    #[test]
+    fn test_skip_precompile_and_selfdestruct() {
+        let mut precompile = node(1, Some(0), vec![], CallKind::StaticCall);
+        precompile.trace.maybe_precompile = Some(true);
+        let mut root = node(0, None, vec![], CallKind::Call);
+        root.trace.selfdestruct_refund_target = Some(Address::repeat_byte(1));
+
+        let traces = ParityTraceBuilder::new(
+            vec![root, precompile],
+            TracingInspectorConfig::none(),
+        )
+        .into_transaction_traces();
+
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].subtraces, 1);
+        assert_eq!(traces[1].trace_address, vec![0]);
+        assert!(matches!(traces[1].action, Action::Selfdestruct(_)));
+    }
+}
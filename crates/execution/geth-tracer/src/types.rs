@@ -28,10 +28,22 @@
 // DEALINGS IN THE SOFTWARE.
 
 //! Types for representing call trace items.
-use crate::{config::TraceStyle, utils, utils::convert_memory};
+use crate::{
+    config::TraceStyle,
+    utils,
+    utils::{convert_memory, to_alloy_h256},
+};
 use alloy_primitives::{Address, Bytes, LogData, U256};
-use alloy_rpc_types_trace::geth::{
-    CallFrame, CallLogFrame, GethDefaultTracingOptions, GethTrace, StructLog,
+use alloy_rpc_types::TransactionInfo;
+use alloy_rpc_types_trace::{
+    geth::{
+        CallFrame, CallLogFrame, GethDefaultTracingOptions, GethTrace,
+        StructLog,
+    },
+    parity::{
+        Action, CallAction, CallOutput, CallType, CreateAction, CreateOutput,
+        CreationMethod, SelfdestructAction, TraceOutput, TransactionTrace,
+    },
 };
 use cfx_types::{Space, H256};
 use cfx_vm_types::CallType as CfxCallType;
@@ -210,6 +222,101 @@ impl CallTraceNode {
         self.trace.selfdestruct_refund_target.is_some()
     }
 
+    /// Converts this node into a parity `TransactionTrace`
+    pub fn parity_transaction_trace(
+        &self, trace_address: Vec<usize>,
+    ) -> TransactionTrace {
+        let action = self.parity_action();
+        let result = if self.trace.is_error() && !self.trace.is_revert() {
+            // if the trace is an error that is not a revert, the result is
+            // None
+            None
+        } else {
+            Some(self.parity_trace_output())
+        };
+        let error = self.trace.as_error_msg(TraceStyle::Parity);
+        TransactionTrace {
+            action,
+            error,
+            result,
+            trace_address,
+            subtraces: self.children.len(),
+        }
+    }
+
+    /// Returns the `Output` for a parity trace
+    pub fn parity_trace_output(&self) -> TraceOutput {
+        if self.kind().is_any_create() {
+            TraceOutput::Create(CreateOutput {
+                gas_used: self.trace.gas_used,
+                code: self.trace.output.clone(),
+                address: self.trace.address,
+            })
+        } else {
+            TraceOutput::Call(CallOutput {
+                gas_used: self.trace.gas_used,
+                output: self.trace.output.clone(),
+            })
+        }
+    }
+
+    /// If the trace is a selfdestruct, returns the `Action` for a parity trace.
+    pub fn parity_selfdestruct_action(&self) -> Option<Action> {
+        if self.is_selfdestruct() {
+            Some(Action::Selfdestruct(SelfdestructAction {
+                address: self.trace.address,
+                refund_address: self
+                    .trace
+                    .selfdestruct_refund_target
+                    .unwrap_or_default(),
+                balance: self.trace.value,
+            }))
+        } else {
+            None
+        }
+    }
+
+    /// If the trace is a selfdestruct, returns the `TransactionTrace` for a
+    /// parity trace.
+    pub fn parity_selfdestruct_trace(
+        &self, trace_address: Vec<usize>,
+    ) -> Option<TransactionTrace> {
+        let trace = self.parity_selfdestruct_action()?;
+        Some(TransactionTrace {
+            action: trace,
+            error: None,
+            result: None,
+            trace_address,
+            subtraces: 0,
+        })
+    }
+
+    /// Returns the `Action` for a parity trace.
+    ///
+    /// Caution: This does not include the selfdestruct action, if the trace
+    /// is a selfdestruct, since those are handled in addition to the call
+    /// action.
+    pub fn parity_action(&self) -> Action {
+        if self.kind().is_any_create() {
+            Action::Create(CreateAction {
+                from: self.trace.caller,
+                value: self.trace.value,
+                gas: self.trace.gas_limit,
+                init: self.trace.data.clone(),
+                creation_method: self.kind().into(),
+            })
+        } else {
+            Action::Call(CallAction {
+                from: self.trace.caller,
+                to: self.trace.address,
+                value: self.trace.value,
+                gas: self.trace.gas_limit,
+                input: self.trace.data.clone(),
+                call_type: self.kind().into(),
+            })
+        }
+    }
+
     /// If the trace is a selfdestruct, returns the `CallFrame` for a geth call
     /// trace
     pub fn geth_selfdestruct_call_trace(&self) -> Option<CallFrame> {
@@ -276,6 +383,27 @@ impl CallTraceNode {
     }
 }
 
+impl From<CallKind> for CallType {
+    fn from(kind: CallKind) -> Self {
+        match kind {
+            CallKind::Call => Self::Call,
+            CallKind::StaticCall => Self::StaticCall,
+            CallKind::CallCode => Self::CallCode,
+            CallKind::DelegateCall => Self::DelegateCall,
+            CallKind::Create | CallKind::Create2 => Self::None,
+        }
+    }
+}
+
+impl From<CallKind> for CreationMethod {
+    fn from(kind: CallKind) -> Self {
+        match kind {
+            CallKind::Create2 => Self::Create2,
+            _ => Self::Create,
+        }
+    }
+}
+
 /// A unified representation of a call.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -585,7 +713,8 @@ impl AsRef<[u8]> for RecordedMemory {
 }
 
 pub struct GethTraceWithHash {
-    pub trace: GethTrace,
+    /// The trace, or the error message of a tracer that failed
+    pub trace: Result<GethTrace, String>,
     pub tx_hash: H256,
     pub space: Space,
 }
@@ -595,6 +724,24 @@ pub struct TxExecContext {
     pub tx_gas_limit: u64,
     pub block_number: BlockNumber,
     pub block_height: BlockHeight,
+    pub tx_hash: H256,
+    // index of the transaction in the eSpace block
+    pub tx_index: usize,
+    // hash of the eSpace block, which is the hash of the pivot block
+    pub block_hash: H256,
+}
+
+impl TxExecContext {
+    /// Returns the transaction info used to localize parity style traces.
+    pub fn transaction_info(&self) -> TransactionInfo {
+        TransactionInfo {
+            hash: Some(to_alloy_h256(self.tx_hash)),
+            index: Some(self.tx_index as u64),
+            block_hash: Some(to_alloy_h256(self.block_hash)),
+            block_number: Some(self.block_height),
+            ..Default::default()
+        }
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -1,7 +1,10 @@
 pub mod action_types;
 pub mod address_pocket;
 pub mod filter;
+pub mod replay_types;
+pub mod state_diff;
 pub mod trace_types;
+pub mod vm_trace;
 
 #[cfg(test)]
 mod tests;
@@ -9,4 +12,7 @@ mod tests;
 pub use action_types::*;
 pub use address_pocket::AddressPocket;
 pub use filter::*;
+pub use replay_types::*;
+pub use state_diff::*;
 pub use trace_types::*;
+pub use vm_trace::*;
@@ -0,0 +1,47 @@
+use crate::{state_diff::StateDiff, trace_types::ExecTrace, vm_trace::VmTrace};
+use cfx_bytes::Bytes;
+use cfx_types::{Space, H256};
+use serde::Deserialize;
+
+/// The outputs of parity's `trace_call` and `trace_replay*` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceType {
+    /// The call and create traces.
+    Trace,
+    /// The instruction-level traces.
+    VmTrace,
+    /// The account changes.
+    StateDiff,
+}
+
+/// Which outputs should be collected when replaying a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TraceOptions {
+    pub trace: bool,
+    pub vm_trace: bool,
+    pub state_diff: bool,
+}
+
+impl TraceOptions {
+    pub fn from_trace_types(types: &[TraceType]) -> Self {
+        TraceOptions {
+            trace: types.contains(&TraceType::Trace),
+            vm_trace: types.contains(&TraceType::VmTrace),
+            state_diff: types.contains(&TraceType::StateDiff),
+        }
+    }
+}
+
+/// The outputs of a replayed transaction. The fields not requested in
+/// [`TraceOptions`] are `None`.
+#[derive(Debug, Clone)]
+pub struct TransactionReplayTraces {
+    pub tx_hash: H256,
+    pub space: Space,
+    /// The return data of the transaction.
+    pub output: Bytes,
+    pub trace: Option<Vec<ExecTrace>>,
+    pub vm_trace: Option<VmTrace>,
+    pub state_diff: Option<StateDiff>,
+}
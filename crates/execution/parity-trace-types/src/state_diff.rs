@@ -0,0 +1,141 @@
+use cfx_bytes::Bytes;
+use cfx_types::{Address, AddressWithSpace, Space, H256, U256};
+use std::collections::BTreeMap;
+
+/// Change of a value from the beginning to the end of a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diff<T> {
+    /// The value is unchanged.
+    Same,
+    /// The value did not exist before the transaction.
+    Born(T),
+    /// The value does not exist after the transaction.
+    Died(T),
+    /// The value is changed.
+    Changed { from: T, to: T },
+}
+
+impl<T: Eq> Diff<T> {
+    /// Makes the diff of the value before (`pre`) and after (`post`) the
+    /// transaction, `None` means the value does not exist.
+    pub fn new(pre: Option<T>, post: Option<T>) -> Self {
+        match (pre, post) {
+            (None, Some(to)) => Diff::Born(to),
+            (Some(from), None) => Diff::Died(from),
+            (Some(from), Some(to)) if from != to => Diff::Changed { from, to },
+            _ => Diff::Same,
+        }
+    }
+
+    pub fn is_same(&self) -> bool { matches!(self, Diff::Same) }
+
+    /// Combines the diff with the `later` diff of the same value, the result
+    /// is the change from before this diff to after the `later` one.
+    pub fn then(self, later: Diff<T>) -> Self {
+        match (self, later) {
+            (diff, Diff::Same) | (Diff::Same, diff) => diff,
+            (Diff::Born(_), Diff::Died(_)) => Diff::Same,
+            (Diff::Born(_), Diff::Born(to) | Diff::Changed { to, .. }) => {
+                Diff::Born(to)
+            }
+            (Diff::Died(from) | Diff::Changed { from, .. }, Diff::Died(_)) => {
+                Diff::Died(from)
+            }
+            (
+                Diff::Died(from) | Diff::Changed { from, .. },
+                Diff::Born(to) | Diff::Changed { to, .. },
+            ) => Diff::new(Some(from), Some(to)),
+        }
+    }
+}
+
+impl<T> Diff<T> {
+    pub fn map<U, F: Fn(T) -> U>(self, f: F) -> Diff<U> {
+        match self {
+            Diff::Same => Diff::Same,
+            Diff::Born(to) => Diff::Born(f(to)),
+            Diff::Died(from) => Diff::Died(f(from)),
+            Diff::Changed { from, to } => Diff::Changed {
+                from: f(from),
+                to: f(to),
+            },
+        }
+    }
+}
+
+/// Changes of an account made by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub balance: Diff<U256>,
+    pub nonce: Diff<U256>,
+    pub code: Diff<Bytes>,
+    /// Changed storage entries, keyed by the storage slot.
+    pub storage: BTreeMap<H256, Diff<H256>>,
+}
+
+impl AccountDiff {
+    pub fn is_same(&self) -> bool {
+        self.balance.is_same()
+            && self.nonce.is_same()
+            && self.code.is_same()
+            && self.storage.is_empty()
+    }
+
+    /// Combines the changes with the `later` changes of the same account.
+    pub fn then(self, later: AccountDiff) -> Self {
+        let mut storage = self.storage;
+        for (key, diff) in later.storage {
+            let diff = match storage.remove(&key) {
+                Some(prev) => prev.then(diff),
+                None => diff,
+            };
+            if !diff.is_same() {
+                storage.insert(key, diff);
+            }
+        }
+        AccountDiff {
+            balance: self.balance.then(later.balance),
+            nonce: self.nonce.then(later.nonce),
+            code: self.code.then(later.code),
+            storage,
+        }
+    }
+}
+
+/// Changes of all the accounts touched by a transaction, in both spaces.
+///
+/// A Core Space transaction may change eSpace accounts through the
+/// cross-space call internal contract, so the accounts are keyed with their
+/// space.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff(pub BTreeMap<AddressWithSpace, AccountDiff>);
+
+impl StateDiff {
+    pub fn insert(&mut self, address: AddressWithSpace, diff: AccountDiff) {
+        if !diff.is_same() {
+            self.0.insert(address, diff);
+        }
+    }
+
+    /// Accumulates the changes of a later transaction, so the diff covers the
+    /// changes of both transactions.
+    pub fn merge(&mut self, later: StateDiff) {
+        for (address, diff) in later.0 {
+            let diff = match self.0.remove(&address) {
+                Some(prev) => prev.then(diff),
+                None => diff,
+            };
+            self.insert(address, diff);
+        }
+    }
+
+    /// Returns the account changes in the given space.
+    pub fn into_space(
+        self, space: Space,
+    ) -> impl Iterator<Item = (Address, AccountDiff)> {
+        self.0
+            .into_iter()
+            .filter(move |(address, _)| address.space == space)
+            .map(|(address, diff)| (address.address, diff))
+    }
+}
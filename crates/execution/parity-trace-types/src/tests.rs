@@ -1,7 +1,10 @@
 use super::{
     action_types::{Action, Call},
+    replay_types::{TraceOptions, TraceType},
+    state_diff::{AccountDiff, Diff, StateDiff},
     trace_types::{BlockExecTraces, ExecTrace, TransactionExecTraces},
 };
+use cfx_types::{Address, AddressSpaceUtil, Space, H256};
 use cfx_vm_types::CallType;
 use rlp::*;
 
@@ -68,3 +71,114 @@ fn test_trace_serialization() {
     let decoded = ::rlp::decode(&encoded).expect("error decoding block traces");
     assert_eq!(block_traces, decoded);
 }
+
+#[test]
+fn test_diff() {
+    assert_eq!(Diff::<u64>::new(None, None), Diff::Same);
+    assert_eq!(Diff::new(Some(1), Some(1)), Diff::Same);
+    assert_eq!(Diff::new(None, Some(1)), Diff::Born(1));
+    assert_eq!(Diff::new(Some(1), None), Diff::Died(1));
+    assert_eq!(
+        Diff::new(Some(1), Some(2)),
+        Diff::Changed { from: 1, to: 2 }
+    );
+}
+
+#[test]
+fn test_diff_then() {
+    let changed = |from, to| Diff::Changed { from, to };
+    assert_eq!(Diff::Same.then(Diff::Born(1)), Diff::Born(1));
+    assert_eq!(changed(1, 2).then(Diff::Same), changed(1, 2));
+    assert_eq!(changed(1, 2).then(changed(2, 3)), changed(1, 3));
+    assert_eq!(changed(1, 2).then(changed(2, 1)), Diff::Same);
+    assert_eq!(Diff::Born(1).then(changed(1, 2)), Diff::Born(2));
+    assert_eq!(Diff::Born(1).then(Diff::Died(1)), Diff::Same);
+    assert_eq!(changed(1, 2).then(Diff::Died(2)), Diff::Died(1));
+    assert_eq!(Diff::Died(1).then(Diff::Born(2)), changed(1, 2));
+}
+
+#[test]
+fn test_state_diff_merge() {
+    let address = Address::repeat_byte(1).with_evm_space();
+    let diff = |balance, storage: &[(u64, Diff<H256>)]| AccountDiff {
+        balance,
+        nonce: Diff::Same,
+        code: Diff::Same,
+        storage: storage
+            .iter()
+            .map(|(key, diff)| (H256::from_low_u64_be(*key), diff.clone()))
+            .collect(),
+    };
+    let value = H256::from_low_u64_be;
+
+    let mut state_diff = StateDiff::default();
+    state_diff.insert(
+        address,
+        diff(
+            Diff::new(Some(1.into()), Some(2.into())),
+            &[(1, Diff::Born(value(1))), (2, Diff::Born(value(2)))],
+        ),
+    );
+
+    let mut later = StateDiff::default();
+    later.insert(
+        address,
+        diff(
+            Diff::new(Some(2.into()), Some(3.into())),
+            &[(1, Diff::Died(value(1)))],
+        ),
+    );
+    state_diff.merge(later);
+    assert_eq!(
+        state_diff.0[&address],
+        diff(
+            Diff::new(Some(1.into()), Some(3.into())),
+            &[(2, Diff::Born(value(2)))],
+        )
+    );
+
+    // The account is removed if the changes are reverted.
+    let mut later = StateDiff::default();
+    later.insert(
+        address,
+        diff(
+            Diff::new(Some(3.into()), Some(1.into())),
+            &[(2, Diff::Died(value(2)))],
+        ),
+    );
+    state_diff.merge(later);
+    assert!(state_diff.0.is_empty());
+}
+
+#[test]
+fn test_state_diff_skip_unchanged_accounts() {
+    let unchanged = AccountDiff {
+        balance: Diff::Same,
+        nonce: Diff::Same,
+        code: Diff::Same,
+        storage: Default::default(),
+    };
+    let changed = AccountDiff {
+        balance: Diff::new(Some(1.into()), Some(2.into())),
+        ..unchanged.clone()
+    };
+
+    let mut state_diff = StateDiff::default();
+    state_diff.insert(Address::repeat_byte(1).with_evm_space(), unchanged);
+    state_diff
+        .insert(Address::repeat_byte(2).with_evm_space(), changed.clone());
+    state_diff
+        .insert(Address::repeat_byte(3).with_native_space(), changed.clone());
+
+    let evm_diff: Vec<_> = state_diff.into_space(Space::Ethereum).collect();
+    assert_eq!(evm_diff, vec![(Address::repeat_byte(2), changed)]);
+}
+
+#[test]
+fn test_trace_options() {
+    let options = TraceOptions::from_trace_types(&[
+        TraceType::StateDiff,
+        TraceType::Trace,
+    ]);
+    assert!(options.trace && options.state_diff && !options.vm_trace);
+}
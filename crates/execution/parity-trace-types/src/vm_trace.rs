@@ -0,0 +1,51 @@
+use cfx_bytes::Bytes;
+use cfx_types::U256;
+
+/// Instruction-level trace of the bytecode executed in a call or create
+/// frame, in the layout of parity's `vmTrace`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VmTrace {
+    /// The code executed by the frame.
+    pub code: Bytes,
+    /// The executed instructions, in order.
+    pub ops: Vec<VmOperation>,
+}
+
+/// An executed instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VmOperation {
+    /// The program counter.
+    pub pc: usize,
+    /// The gas cost of the instruction, including the gas passed to a sub
+    /// call or create.
+    pub cost: u64,
+    /// The effects of the instruction, `None` if the instruction fails.
+    pub executed: Option<VmExecutedOperation>,
+    /// The trace of the frame created by the instruction.
+    pub sub: Option<VmTrace>,
+}
+
+/// The effects of an executed instruction.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VmExecutedOperation {
+    /// The gas left after the instruction.
+    pub gas_used: u64,
+    /// The values pushed to the stack.
+    pub stack_push: Vec<U256>,
+    /// The memory written by the instruction.
+    pub mem_diff: Option<MemoryDiff>,
+    /// The storage entry written by the instruction.
+    pub store_diff: Option<StorageDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryDiff {
+    pub offset: usize,
+    pub data: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageDiff {
+    pub location: U256,
+    pub value: U256,
+}
@@ -206,6 +206,16 @@ pub struct Spec {
     pub cip_c2_fix: bool,
     /// EIP-7939: Count Leading Zeros Instruction
     pub eip7939: bool,
+    /// EIP-7823: Set upper bounds for MODEXP
+    pub eip7823: bool,
+    /// EIP-7825: Transaction Gas Limit Cap
+    pub eip7825: bool,
+    /// EIP-7883: ModExp Gas Cost Increase
+    pub eip7883: bool,
+    /// EIP-7951: Precompile for secp256r1 Curve Support
+    pub eip7951: bool,
+    /// Enables the internal contracts registered by plugins
+    pub internal_contract_plugins: bool,
 }
 
 /// Represents the feature flags for CIP-645 implementation.
@@ -418,6 +428,11 @@ impl Spec {
             cip_c2_fix: false,
             align_evm: false,
             eip7939: false,
+            eip7823: false,
+            eip7825: false,
+            eip7883: false,
+            eip7951: false,
+            internal_contract_plugins: false,
         }
     }
 
@@ -242,6 +242,19 @@ impl NetworkService {
         }
     }
 
+    /// Add a P2P peer to the client as a reserved node, which is connected
+    /// before the sampled trusted nodes.
+    pub fn add_reserved_peer(&self, node: NodeEntry) -> Result<(), Error> {
+        if let Some(ref x) = self.inner {
+            let id = node.id;
+            x.node_db.write().insert_trusted(node);
+            x.reserved_nodes.write().insert(id);
+            Ok(())
+        } else {
+            Err("Network service not started yet!".into())
+        }
+    }
+
     /// Drop a P2P peer from the client
     pub fn drop_peer(&self, node: NodeEntry) -> Result<(), Error> {
         if let Some(ref x) = self.inner {
@@ -256,6 +269,13 @@ impl NetworkService {
         self.inner.as_ref().map(|inner_ref| inner_ref.local_addr())
     }
 
+    /// Get the public endpoint of the client, which is advertised to peers
+    pub fn public_endpoint(&self) -> Option<NodeEndpoint> {
+        self.inner
+            .as_ref()
+            .map(|inner_ref| inner_ref.metadata.public_endpoint.clone())
+    }
+
     /// Register a new protocol handler
     pub fn register_protocol(
         &self, handler: Arc<dyn NetworkProtocolHandler + Sync>,
@@ -760,6 +780,7 @@ impl NetworkServiceInner {
     pub fn local_addr(&self) -> SocketAddr { self.metadata.local_address }
 
     fn drop_node(&self, local_id: NodeId) -> Result<(), Error> {
+        self.reserved_nodes.write().remove(&local_id);
         let removed_node = self.node_db.write().remove(&local_id);
 
         if let Some(node) = removed_node {
@@ -138,6 +138,13 @@ pub mod rpc {
     pub const TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_LOW: usize = 100;
     pub const TRANSACTION_COUNT_PER_BLOCK_WATER_LINE_MEDIUM: usize = 600;
     pub const GAS_PRICE_DEFAULT_VALUE: usize = 1_000_000_000;
+    /// The default and the maximum page sizes of the transactions returned
+    /// by `cfx_getTransactionsByAddress` and `eth_getTransactionsByAddress`.
+    pub const TRANSACTIONS_BY_ADDRESS_DEFAULT_LIMIT: usize = 100;
+    pub const TRANSACTIONS_BY_ADDRESS_MAX_LIMIT: usize = 1000;
+    /// The maximum number of addresses queried by `cfx_getAccounts` and
+    /// `eth_getAccounts` in one call.
+    pub const GET_ACCOUNTS_MAX_ADDRESSES: usize = 1000;
 }
 
 pub mod sync {
@@ -247,6 +254,9 @@ pub mod block {
     // space in the cross space call. Setting it to N means that only 1/N of gas
     // left can be passed to the cross space call.
     pub const CROSS_SPACE_GAS_RATIO: u64 = 10;
+    // The maximum gas limit of an EVM space transaction after EIP-7825 is
+    // enabled.
+    pub const EIP7825_TRANSACTION_GAS_LIMIT_CAP: u64 = 1 << 24;
     // The following parameter controls the ratio of block gas limit for the
     // core space after CIP1559 is enabled. Setting it to N means that only N/10
     // of the block gas limit can be used for core space transactions.
@@ -60,6 +60,13 @@ pub enum FilterError {
         max_gap: u64,
     },
 
+    /// More epochs than `max_epochs` have indexed logs of the filter.
+    TooManyIndexedEpochs {
+        from_epoch: u64,
+        to_epoch: u64,
+        max_epochs: u64,
+    },
+
     /// Roots for verifying the requested epochs are unavailable.
     UnableToVerify {
         epoch: u64,
@@ -160,6 +167,18 @@ impl fmt::Display for FilterError {
                     from_block, to_block, max_gap
                 }
             }
+            TooManyIndexedEpochs {
+                from_epoch,
+                to_epoch,
+                max_epochs,
+            } => {
+                format! {
+                    "More than get_logs_filter_max_epoch_range epochs between \
+                    from_epoch and to_epoch have logs matching the filter \
+                    (from: {}, to: {}, get_logs_filter_max_epoch_range: {})",
+                    from_epoch, to_epoch, max_epochs
+                }
+            }
             UnableToVerify {
                 epoch,
                 latest_verifiable,
@@ -16,6 +16,7 @@ pub mod is_default;
 pub mod log_entry;
 pub mod pos;
 pub mod receipt;
+pub mod receipt_proof;
 pub mod state_root;
 pub mod static_bool;
 pub mod storage;
@@ -35,6 +36,7 @@ pub use crate::{
     epoch::{BlockHashOrEpochNumber, EpochId, EpochNumber, NULL_EPOCH},
     log_entry::LogEntry,
     receipt::{BlockReceipts, Receipt, TransactionStatus},
+    receipt_proof::verify_epoch_receipt_proof,
     state_root::*,
     static_bool::StaticBool,
     storage::{
@@ -0,0 +1,261 @@
+//! Standalone verification of Core Space receipt proofs.
+//!
+//! The epoch receipts root (the `deferred_receipts_root` header field) is the
+//! root of a simple MPT keyed by block index whose values are the receipts
+//! roots of each block, which are in turn simple MPTs keyed by transaction
+//! index whose values are RLP encoded receipts. Proof nodes are the RLP
+//! encoded trie nodes along the key path, root first, as produced by
+//! `TrieProofNode`'s `Encodable` implementation in `cfx-storage`.
+//!
+//! This module only depends on `keccak` and `rlp` so that it can be reused
+//! by light clients and relayers: the merkle stored inside every proof node is
+//! ignored and recomputed from the node content.
+
+use crate::{
+    hash::keccak,
+    state_root::{MerkleHash, MERKLE_NULL_NODE},
+};
+use cfx_types::H256;
+use rlp::Rlp;
+
+const CHILDREN_COUNT: usize = 16;
+
+/// A trie node decoded from a proof, without its (untrusted) merkle.
+struct ProofNode {
+    children: Option<[MerkleHash; CHILDREN_COUNT]>,
+    value: Option<Vec<u8>>,
+    path_mask: u8,
+    path_slice: Vec<u8>,
+}
+
+impl ProofNode {
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let rlp = Rlp::new(bytes);
+        let item_count = rlp.item_count().ok()?;
+        if item_count != 3 && item_count != 4 {
+            return None;
+        }
+
+        let children_rlp = rlp.at(1).ok()?;
+        let children = if children_rlp.is_empty() {
+            None
+        } else {
+            if children_rlp.item_count().ok()? != CHILDREN_COUNT {
+                return None;
+            }
+            let mut table = [MERKLE_NULL_NODE; CHILDREN_COUNT];
+            for (i, child) in table.iter_mut().enumerate() {
+                *child = children_rlp.val_at(i).ok()?;
+            }
+            Some(table)
+        };
+
+        let value = rlp.val_at::<Option<Vec<u8>>>(2).ok()?;
+
+        let (path_mask, path_slice) = if item_count == 4 {
+            let path = rlp.at(3).ok()?;
+            (path.val_at(0).ok()?, path.val_at(1).ok()?)
+        } else {
+            (0, vec![])
+        };
+
+        Some(ProofNode {
+            children,
+            value,
+            path_mask,
+            path_slice,
+        })
+    }
+
+    /// The path excludes the first nibble of `path_slice`.
+    fn without_first_nibble(&self) -> bool { self.path_mask & 0x0f != 0 }
+
+    /// The path excludes the last nibble of `path_slice`.
+    fn without_last_nibble(&self) -> bool { self.path_mask & 0xf0 != 0 }
+
+    fn path_nibbles(&self) -> Vec<u8> {
+        let mut nibbles: Vec<u8> = self
+            .path_slice
+            .iter()
+            .flat_map(|byte| [byte >> 4, byte & 0x0f])
+            .collect();
+        if self.without_last_nibble() {
+            nibbles.pop();
+        }
+        if self.without_first_nibble() && !nibbles.is_empty() {
+            nibbles.remove(0);
+        }
+        nibbles
+    }
+
+    fn node_merkle(&self) -> MerkleHash {
+        let mut buffer = Vec::with_capacity(
+            1 + CHILDREN_COUNT * 32
+                + self.value.as_ref().map_or(0, |v| 1 + v.len()),
+        );
+        buffer.push(b'n');
+        match &self.children {
+            Some(children) => {
+                for child in children {
+                    buffer.extend_from_slice(child.as_bytes());
+                }
+            }
+            None => {
+                for _ in 0..CHILDREN_COUNT {
+                    buffer.extend_from_slice(MERKLE_NULL_NODE.as_bytes());
+                }
+            }
+        }
+        if let Some(value) = &self.value {
+            buffer.push(b'v');
+            buffer.extend_from_slice(value);
+        }
+        keccak(&buffer)
+    }
+
+    /// The merkle referenced by the parent node, i.e. the node merkle
+    /// combined with the compressed path.
+    fn path_merkle(&self, steps: usize) -> MerkleHash {
+        let node_merkle = self.node_merkle();
+        if steps == 0 {
+            return node_merkle;
+        }
+
+        let without_first_nibble = self.without_first_nibble();
+        let mut buffer = Vec::with_capacity(1 + self.path_slice.len() + 32);
+        buffer.push(
+            128u8 + 64u8 * (without_first_nibble as u8) + (steps % 63) as u8,
+        );
+        buffer.extend_from_slice(&self.path_slice);
+        if without_first_nibble {
+            buffer[1] &= 0x0f;
+        }
+        buffer.extend_from_slice(node_merkle.as_bytes());
+        keccak(&buffer)
+    }
+}
+
+/// The key of the `index`-th item in a simple MPT with `num_keys` items.
+pub fn simple_mpt_key(index: usize, num_keys: usize) -> Vec<u8> {
+    let mut key_length = 0;
+    let mut largest = match num_keys {
+        0 => 0,
+        1 => 1,
+        n => n - 1,
+    };
+    while largest != 0 {
+        key_length += 1;
+        largest >>= 8;
+    }
+
+    let mut key = vec![0u8; key_length];
+    let mut index = index;
+    for byte in key.iter_mut().rev() {
+        *byte = index as u8;
+        index >>= 8;
+    }
+    key
+}
+
+/// Looks up the value of the `index`-th item of a simple MPT with `num_keys`
+/// items and merkle root `root`, using the RLP encoded proof `nodes`.
+///
+/// Returns `None` if the proof is invalid or incomplete for the key, and
+/// `Some(None)` if the proof shows that the key is absent.
+pub fn simple_mpt_proof_value<N: AsRef<[u8]>>(
+    root: &MerkleHash, index: usize, num_keys: usize, nodes: &[N],
+) -> Option<Option<Vec<u8>>> {
+    if index >= num_keys {
+        return None;
+    }
+    if root == &MERKLE_NULL_NODE {
+        return Some(None);
+    }
+
+    let nodes = nodes
+        .iter()
+        .map(|node| ProofNode::decode(node.as_ref()))
+        .collect::<Option<Vec<_>>>()?;
+
+    let key_nibbles: Vec<u8> = simple_mpt_key(index, num_keys)
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect();
+
+    // When all keys share the first nibble (always 0), the root node has a
+    // single child and `simple_mpt_merkle_root` uses that child as the root.
+    let largest_key = simple_mpt_key(num_keys - 1, num_keys);
+    let mut position = if largest_key[0] >> 4 == 0 { 1 } else { 0 };
+
+    let mut expected = *root;
+    loop {
+        let (node, path) = nodes.iter().find_map(|node| {
+            let path = node.path_nibbles();
+            // A non-empty path starts in the middle of a byte exactly when
+            // the node sits at an odd nibble position.
+            if !path.is_empty()
+                && node.without_first_nibble() != (position % 2 == 1)
+            {
+                return None;
+            }
+            if node.path_merkle(path.len()) == expected {
+                Some((node, path))
+            } else {
+                None
+            }
+        })?;
+
+        let remaining = &key_nibbles[position..];
+        if !remaining.starts_with(&path) {
+            return Some(None);
+        }
+        position += path.len();
+
+        if position == key_nibbles.len() {
+            return Some(node.value.clone());
+        }
+
+        let child_index = key_nibbles[position] as usize;
+        position += 1;
+        expected = match &node.children {
+            Some(children) if children[child_index] != MERKLE_NULL_NODE => {
+                children[child_index]
+            }
+            _ => return Some(None),
+        };
+    }
+}
+
+/// Verify that `receipt_rlp` is the RLP encoded receipt of the
+/// `transaction_index`-th transaction (out of `receipt_count`) in the
+/// `block_index`-th block (out of `block_count`) of an epoch whose receipts
+/// root is `epoch_receipts_root`.
+///
+/// `block_index_proof` proves the receipts root of the block against the
+/// epoch receipts root, `block_receipt_proof` proves the receipt against
+/// that block receipts root.
+pub fn verify_epoch_receipt_proof<N: AsRef<[u8]>>(
+    epoch_receipts_root: &MerkleHash, block_index: usize, block_count: usize,
+    block_index_proof: &[N], transaction_index: usize, receipt_count: usize,
+    receipt_rlp: &[u8], block_receipt_proof: &[N],
+) -> bool {
+    let block_receipts_root = match simple_mpt_proof_value(
+        epoch_receipts_root,
+        block_index,
+        block_count,
+        block_index_proof,
+    ) {
+        Some(Some(root)) if root.len() == 32 => H256::from_slice(&root),
+        _ => return false,
+    };
+
+    match simple_mpt_proof_value(
+        &block_receipts_root,
+        transaction_index,
+        receipt_count,
+        block_receipt_proof,
+    ) {
+        Some(Some(receipt)) => receipt == receipt_rlp,
+        _ => false,
+    }
+}
@@ -1,2 +1,3 @@
 pub const DEFAULT_HTTP_PORT: u16 = 18545;
 pub const DEFAULT_WS_PORT: u16 = 18546;
+pub const DEFAULT_IPC_ENDPOINT: &str = "/tmp/conflux.ipc";
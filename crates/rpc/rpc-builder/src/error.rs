@@ -27,6 +27,7 @@
 // IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
 // DEALINGS IN THE SOFTWARE.
 use crate::EthRpcModule;
+use cfx_rpc_ipc::IpcServerStartError;
 use std::{
     collections::HashSet,
     io::{self, ErrorKind},
@@ -44,6 +45,8 @@ pub enum ServerKind {
     WsHttp(SocketAddr),
     /// Auth.
     Auth(SocketAddr),
+    /// GraphQL.
+    GraphQL(SocketAddr),
 }
 
 impl ServerKind {
@@ -54,6 +57,7 @@ impl ServerKind {
             Self::WS(_) => "--ws.port",
             Self::WsHttp(_) => "--ws.port and --http.port",
             Self::Auth(_) => "--authrpc.port",
+            Self::GraphQL(_) => "jsonrpc_graphql_port",
         }
     }
 }
@@ -65,6 +69,7 @@ impl std::fmt::Display for ServerKind {
             Self::WS(addr) => write!(f, "{addr} (WS-RPC server)"),
             Self::WsHttp(addr) => write!(f, "{addr} (WS-HTTP-RPC server)"),
             Self::Auth(addr) => write!(f, "{addr} (AUTH server)"),
+            Self::GraphQL(addr) => write!(f, "{addr} (GraphQL server)"),
         }
     }
 }
@@ -96,8 +101,8 @@ pub enum RpcError {
     #[error(transparent)]
     WsHttpSamePortError(#[from] WsHttpSamePortError),
     /// Thrown when IPC server fails to start.
-    // #[error(transparent)]
-    // IpcServerError(#[from] IpcServerStartError),
+    #[error(transparent)]
+    IpcServerError(#[from] IpcServerStartError),
     /// Custom error.
     #[error("{0}")]
     Custom(String),
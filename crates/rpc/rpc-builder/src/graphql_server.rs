@@ -0,0 +1,267 @@
+use crate::{RpcError, ServerKind, ACCEPT_ERROR_BACKOFF};
+use async_graphql::{
+    http::GraphiQLSource, BatchRequest, BatchResponse, Request, Response,
+    ServerError,
+};
+use axum::{
+    extract::{DefaultBodyLimit, State},
+    http::{HeaderValue, Method, StatusCode},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use cfx_rpc_graphql::CfxSchema;
+use cfx_rpc_middlewares::{
+    ApiKey, ApiKeyLayer, AuthLayer, Authenticated, JwtSecret, RemoteAddr,
+    ThrottleManager,
+};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto,
+};
+use jsonrpsee::server::{stop_channel, ServerHandle};
+use log::debug;
+use std::{
+    fmt::{self, Debug},
+    net::SocketAddr,
+    sync::Arc,
+};
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// The path which queries are posted to.
+const GRAPHQL_PATH: &str = "/graphql";
+
+/// The path of the GraphiQL page for exploring the schema.
+const GRAPHIQL_PATH: &str = "/graphql/ui";
+
+/// The method name GraphQL queries are throttled as.
+const GRAPHQL_METHOD: &str = "graphql";
+
+/// Configs for the Core Space GraphQL server.
+#[derive(Clone)]
+pub struct GraphQLServerConfig {
+    addr: SocketAddr,
+    schema: CfxSchema,
+    cors_domains: Option<Vec<String>>,
+    max_request_body_size: usize,
+    keep_alive: bool,
+    require_jwt: bool,
+}
+
+impl GraphQLServerConfig {
+    pub fn new(addr: SocketAddr, schema: CfxSchema) -> Self {
+        Self {
+            addr,
+            schema,
+            cors_domains: None,
+            max_request_body_size: 10 * 1024 * 1024,
+            keep_alive: true,
+            require_jwt: false,
+        }
+    }
+
+    /// Allows cross-origin requests from the domains, `*` allows any. No
+    /// cross-origin request is allowed by default.
+    pub fn with_cors_domains(mut self, domains: Vec<String>) -> Self {
+        self.cors_domains = Some(domains);
+        self
+    }
+
+    pub fn with_max_request_body_size(mut self, size: usize) -> Self {
+        self.max_request_body_size = size;
+        self
+    }
+
+    pub fn with_keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Rejects the requests without a valid JWT, see
+    /// [`crate::RpcServerConfig::with_jwt_secret`].
+    pub fn with_require_jwt(mut self, require_jwt: bool) -> Self {
+        self.require_jwt = require_jwt;
+        self
+    }
+}
+
+impl Debug for GraphQLServerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GraphQLServerConfig")
+            .field("addr", &self.addr)
+            .field("cors_domains", &self.cors_domains)
+            .field("max_request_body_size", &self.max_request_body_size)
+            .field("keep_alive", &self.keep_alive)
+            .field("require_jwt", &self.require_jwt)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+struct GraphQLState {
+    schema: CfxSchema,
+    throttle: Arc<ThrottleManager>,
+    require_jwt: bool,
+}
+
+/// Starts the GraphQL server. Like geth, queries are posted to `/graphql`
+/// and `/graphql/ui` serves GraphiQL.
+///
+/// The requests go through the same http middlewares as the json-rpc
+/// servers, and each query is throttled as a call of the `graphql` method.
+pub(crate) async fn start_graphql_server(
+    config: GraphQLServerConfig, jwt_secret: Option<JwtSecret>,
+    throttle: Arc<ThrottleManager>,
+) -> Result<(SocketAddr, ServerHandle), RpcError> {
+    let GraphQLServerConfig {
+        addr,
+        schema,
+        cors_domains,
+        max_request_body_size,
+        keep_alive,
+        require_jwt,
+    } = config;
+    let kind = ServerKind::GraphQL(addr);
+    if require_jwt && jwt_secret.is_none() {
+        return Err(RpcError::Custom(
+            "GraphQL server requires a JWT but no secret is set".into(),
+        ));
+    }
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|err| RpcError::server_error(err, kind))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|err| RpcError::server_error(err, kind))?;
+
+    let api_key_layer = ApiKeyLayer::new(throttle.api_key_header());
+    let state = GraphQLState {
+        schema,
+        throttle,
+        require_jwt,
+    };
+    let mut app = Router::new()
+        .route(GRAPHQL_PATH, post(graphql))
+        .route(GRAPHIQL_PATH, get(graphiql))
+        .with_state(state)
+        .layer(DefaultBodyLimit::max(max_request_body_size))
+        .layer(AuthLayer::new(jwt_secret))
+        .layer(api_key_layer);
+    if let Some(domains) = &cors_domains {
+        app = app.layer(cors_layer(domains));
+    }
+
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    builder.http1().keep_alive(keep_alive);
+
+    let (stop_handle, server_handle) = stop_channel();
+    tokio::spawn(async move {
+        loop {
+            let (socket, remote_addr) = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        debug!("Failed to accept GraphQL connection: {}", e);
+                        tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                        continue;
+                    }
+                },
+                _ = stop_handle.clone().shutdown() => break,
+            };
+
+            let app = app.clone();
+            let service = hyper::service::service_fn(
+                move |mut req: hyper::Request<hyper::body::Incoming>| {
+                    req.extensions_mut().insert(RemoteAddr(remote_addr));
+                    app.clone().oneshot(req)
+                },
+            );
+            let builder = builder.clone();
+            let stop_handle = stop_handle.clone();
+            tokio::spawn(async move {
+                let conn =
+                    builder.serve_connection(TokioIo::new(socket), service);
+                tokio::pin!(conn);
+                let result = tokio::select! {
+                    result = conn.as_mut() => result,
+                    _ = stop_handle.shutdown() => {
+                        conn.as_mut().graceful_shutdown();
+                        conn.await
+                    }
+                };
+                if let Err(e) = result {
+                    debug!("GraphQL connection {} closed: {}", remote_addr, e);
+                }
+            });
+        }
+    });
+
+    Ok((local_addr, server_handle))
+}
+
+fn cors_layer(domains: &[String]) -> CorsLayer {
+    let layer = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers(Any);
+    if domains.iter().any(|domain| domain == "*") {
+        layer.allow_origin(Any)
+    } else {
+        layer.allow_origin(AllowOrigin::list(
+            domains
+                .iter()
+                .filter_map(|domain| HeaderValue::from_str(domain).ok()),
+        ))
+    }
+}
+
+/// Executes a query, or a batch of queries, posted as JSON.
+async fn graphql(
+    State(state): State<GraphQLState>,
+    authenticated: Option<Extension<Authenticated>>,
+    api_key: Option<Extension<ApiKey>>,
+    remote_addr: Option<Extension<RemoteAddr>>,
+    Json(request): Json<BatchRequest>,
+) -> axum::response::Response {
+    if state.require_jwt && authenticated.is_none() {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    let api_key = api_key.as_ref().map(|Extension(key)| key);
+    let remote_addr = remote_addr.as_ref().map(|Extension(addr)| addr);
+    let response = match request {
+        BatchRequest::Single(request) => BatchResponse::Single(
+            execute(&state, api_key, remote_addr, request).await,
+        ),
+        BatchRequest::Batch(requests) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses
+                    .push(execute(&state, api_key, remote_addr, request).await);
+            }
+            BatchResponse::Batch(responses)
+        }
+    };
+    Json(response).into_response()
+}
+
+/// Executes a query unless the client is throttled.
+async fn execute(
+    state: &GraphQLState, api_key: Option<&ApiKey>,
+    remote_addr: Option<&RemoteAddr>, request: Request,
+) -> Response {
+    match state
+        .throttle
+        .throttle_call(api_key, remote_addr, GRAPHQL_METHOD)
+    {
+        Ok(()) => state.schema.execute(request).await,
+        Err(e) => {
+            Response::from_errors(vec![ServerError::new(e.message(), None)])
+        }
+    }
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
@@ -0,0 +1,110 @@
+use crate::{
+    constants, start_http_server, EthSubscriptionIdProvider, ServerMiddlewares,
+};
+use async_trait::async_trait;
+use cfx_rpc_eth_impl::traits::HttpServerControl;
+use jsonrpsee::{
+    server::{ServerConfigBuilder, ServerHandle},
+    RpcModule,
+};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    fmt::{self, Debug},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+};
+
+/// Starts and stops the http server at runtime, used by `admin_startHTTP` and
+/// `admin_stopHTTP`.
+///
+/// The started server serves the same modules as the http server configured
+/// on startup, or the ws server if http is not configured.
+pub struct HttpServerController {
+    server_config: ServerConfigBuilder,
+    default_addr: SocketAddr,
+    module: RwLock<Option<(RpcModule<()>, ServerMiddlewares)>>,
+    running: Mutex<Option<(SocketAddr, ServerHandle)>>,
+}
+
+impl HttpServerController {
+    pub fn new(
+        server_config: ServerConfigBuilder, default_addr: Option<SocketAddr>,
+    ) -> Self {
+        Self {
+            server_config: server_config
+                .set_id_provider(EthSubscriptionIdProvider::default()),
+            default_addr: default_addr.unwrap_or(SocketAddr::V4(
+                SocketAddrV4::new(
+                    Ipv4Addr::LOCALHOST,
+                    constants::DEFAULT_HTTP_PORT,
+                ),
+            )),
+            module: RwLock::new(None),
+            running: Mutex::new(None),
+        }
+    }
+
+    /// Sets the module served by the http server, and its middlewares.
+    pub(crate) fn set_module(
+        &self, module: RpcModule<()>, middlewares: ServerMiddlewares,
+    ) {
+        *self.module.write() = Some((module, middlewares));
+    }
+
+    /// Records the http server started on startup, so that it can be stopped.
+    pub(crate) fn set_running(&self, addr: SocketAddr, handle: ServerHandle) {
+        *self.running.lock() = Some((addr, handle));
+    }
+}
+
+#[async_trait]
+impl HttpServerControl for HttpServerController {
+    fn http_address(&self) -> Option<SocketAddr> {
+        self.running.lock().as_ref().map(|(addr, _)| *addr)
+    }
+
+    fn default_http_address(&self) -> SocketAddr { self.default_addr }
+
+    async fn start_http(&self, addr: SocketAddr) -> Result<SocketAddr, String> {
+        if let Some(addr) = self.http_address() {
+            return Err(format!("HTTP server already running on {}", addr));
+        }
+        let (module, middlewares) =
+            self.module.read().clone().ok_or("No RPC module to serve")?;
+
+        let (local_addr, handle) = start_http_server(
+            self.server_config.clone(),
+            addr,
+            module,
+            middlewares,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut running = self.running.lock();
+        if let Some((addr, _)) = running.as_ref() {
+            // Started concurrently by another request.
+            let _ = handle.stop();
+            return Err(format!("HTTP server already running on {}", addr));
+        }
+        *running = Some((local_addr, handle));
+        Ok(local_addr)
+    }
+
+    fn stop_http(&self) -> Result<(), String> {
+        let (_, handle) = self
+            .running
+            .lock()
+            .take()
+            .ok_or("HTTP server is not running")?;
+        handle.stop().map_err(|e| e.to_string())
+    }
+}
+
+impl Debug for HttpServerController {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpServerController")
+            .field("default_addr", &self.default_addr)
+            .field("running", &self.http_address())
+            .finish_non_exhaustive()
+    }
+}
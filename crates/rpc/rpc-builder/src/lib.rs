@@ -28,37 +28,60 @@
 // DEALINGS IN THE SOFTWARE.
 mod constants;
 mod error;
+mod graphql_server;
+mod http_server;
 mod id_provider;
 mod module;
 
-use cfx_rpc_middlewares::{Logger, Metrics, Throttle};
+use axum::{
+    body::Body,
+    extract::Request,
+    http::StatusCode,
+    response::IntoResponse,
+    serve::{IncomingStream, ListenerExt},
+};
+use cfx_rpc_ipc::IpcServerBuilder;
+pub use cfx_rpc_middlewares::JwtSecret;
+use cfx_rpc_middlewares::{
+    ApiKeyLayer, Auth, AuthLayer, Logger, Metrics, RemoteAddr, Throttle,
+    ThrottleManager,
+};
 pub use error::*;
+use graphql_server::start_graphql_server;
+pub use graphql_server::GraphQLServerConfig;
+pub use http_server::HttpServerController;
 pub use id_provider::EthSubscriptionIdProvider;
 use log::debug;
 pub use module::{EthRpcModule, RpcModuleSelection};
 
 use cfx_rpc_cfx_types::RpcImplConfiguration;
 use cfx_rpc_eth_api::*;
-use cfx_rpc_eth_impl::{helpers::ChainInfo, *};
+use cfx_rpc_eth_impl::{helpers::ChainInfo, traits::HttpServerControl, *};
 use cfx_tasks::TaskExecutor;
 use cfxcore::{
     Notifications, SharedConsensusGraph, SharedSynchronizationService,
     SharedTransactionPool,
 };
+use cfxcore_accounts::AccountProvider;
 pub use jsonrpsee::server::ServerBuilder;
 use jsonrpsee::{
     core::RegisterMethodError,
     server::{
-        middleware::rpc::RpcServiceBuilder, AlreadyStoppedError, IdProvider,
-        ServerConfigBuilder, ServerHandle,
+        middleware::rpc::RpcServiceBuilder, stop_channel, AlreadyStoppedError,
+        IdProvider, ServerConfig, ServerConfigBuilder, ServerHandle,
     },
     Methods, RpcModule,
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    future::IntoFuture,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     sync::Arc,
+    time::Duration,
 };
+use tokio::net::TcpListener;
+use tower::ServiceExt;
 
 /// A builder type to configure the RPC module: See [`RpcModule`]
 ///
@@ -71,6 +94,8 @@ pub struct RpcModuleBuilder {
     tx_pool: SharedTransactionPool,
     executor: TaskExecutor,
     notifications: Arc<Notifications>,
+    http_controller: Option<Arc<HttpServerController>>,
+    accounts: Option<Arc<AccountProvider>>,
 }
 
 impl RpcModuleBuilder {
@@ -86,9 +111,25 @@ impl RpcModuleBuilder {
             tx_pool,
             executor,
             notifications,
+            http_controller: None,
+            accounts: None,
         }
     }
 
+    /// Enables `admin_startHTTP` and `admin_stopHTTP` with the controller.
+    pub fn with_http_controller(
+        mut self, http_controller: Arc<HttpServerController>,
+    ) -> Self {
+        self.http_controller = Some(http_controller);
+        self
+    }
+
+    /// Enables `eth_signTypedData` with the local accounts.
+    pub fn with_accounts(mut self, accounts: Arc<AccountProvider>) -> Self {
+        self.accounts = Some(accounts);
+        self
+    }
+
     /// Configures all [`RpcModule`]s specific to the given
     /// [`TransportRpcModuleConfig`] which can be used to start the
     /// transport server(s).
@@ -98,7 +139,12 @@ impl RpcModuleBuilder {
         let mut modules = TransportRpcModules::default();
 
         if !module_config.is_empty() {
-            let TransportRpcModuleConfig { http, ws } = module_config.clone();
+            let TransportRpcModuleConfig {
+                http,
+                ws,
+                ipc,
+                auth,
+            } = module_config.clone();
 
             let Self {
                 config,
@@ -107,6 +153,8 @@ impl RpcModuleBuilder {
                 tx_pool,
                 executor,
                 notifications,
+                http_controller,
+                accounts,
             } = self;
 
             let mut registry = RpcRegistryInner::new(
@@ -117,10 +165,23 @@ impl RpcModuleBuilder {
                 executor,
                 notifications,
             );
+            registry.http_controller = http_controller;
+            registry.accounts = accounts;
 
             modules.config = module_config;
             modules.http = registry.maybe_module(http.as_ref());
             modules.ws = registry.maybe_module(ws.as_ref());
+            modules.ipc = registry.maybe_module(ipc.as_ref());
+            if let Some(auth) = auth {
+                modules.protected_methods = Arc::new(
+                    registry
+                        .eth_methods(auth.iter_selection())
+                        .iter()
+                        .flat_map(|methods| methods.method_names())
+                        .map(String::from)
+                        .collect(),
+                );
+            }
         }
 
         modules
@@ -137,6 +198,8 @@ pub struct RpcRegistryInner {
     modules: HashMap<EthRpcModule, Methods>,
     executor: TaskExecutor,
     notifications: Arc<Notifications>,
+    http_controller: Option<Arc<HttpServerController>>,
+    accounts: Option<Arc<AccountProvider>>,
 }
 
 impl RpcRegistryInner {
@@ -153,6 +216,8 @@ impl RpcRegistryInner {
             modules: Default::default(),
             executor,
             notifications,
+            http_controller: None,
+            accounts: None,
         }
     }
 
@@ -185,6 +250,7 @@ impl RpcRegistryInner {
         TraceApi::new(
             self.consensus.clone(),
             self.sync.network.get_network_type().clone(),
+            self.config.max_estimation_gas_limit,
         )
     }
 
@@ -230,6 +296,16 @@ impl RpcRegistryInner {
             self.modules
                 .entry(namespace)
                 .or_insert_with(|| match namespace {
+                    EthRpcModule::Admin => AdminApi::new(
+                        self.consensus.clone(),
+                        self.sync.clone(),
+                        self.http_controller.clone().map(|controller| {
+                            controller as Arc<dyn HttpServerControl>
+                        }),
+                        self.config.admin_chain_data_dir.clone(),
+                    )
+                    .into_rpc()
+                    .into(),
                     EthRpcModule::Debug => DebugApi::new(
                         self.consensus.clone(),
                         self.config.max_estimation_gas_limit,
@@ -237,14 +313,17 @@ impl RpcRegistryInner {
                     .into_rpc()
                     .into(),
                     EthRpcModule::Eth => {
-                        let mut module = EthApi::new(
+                        let mut eth_api = EthApi::new(
                             self.config.clone(),
                             self.consensus.clone(),
                             self.sync.clone(),
                             self.tx_pool.clone(),
                             self.executor.clone(),
-                        )
-                        .into_rpc();
+                        );
+                        if let Some(accounts) = &self.accounts {
+                            eth_api = eth_api.with_accounts(accounts.clone());
+                        }
+                        let mut module = eth_api.into_rpc();
                         if self.config.poll_lifetime_in_seconds.is_some() {
                             let filter_module = EthFilterApi::new(
                                 self.consensus.clone(),
@@ -267,6 +346,7 @@ impl RpcRegistryInner {
                     EthRpcModule::Trace => TraceApi::new(
                         self.consensus.clone(),
                         self.sync.network.get_network_type().clone(),
+                        self.config.max_estimation_gas_limit,
                     )
                     .into_rpc()
                     .into(),
@@ -312,8 +392,10 @@ impl RpcRegistryInner {
 /// Supported server transports are:
 ///    - http
 ///    - ws
+///    - ipc
 ///
-/// Http and WS share the same settings: [`ServerBuilder`].
+/// Http and WS share the same settings: [`ServerBuilder`]. The IPC server is
+/// configured with an [`IpcServerBuilder`].
 ///
 /// Once the [`RpcModule`] is built via [`RpcModuleBuilder`] the servers can be
 /// started, See also [`ServerBuilder::build`] and
@@ -332,6 +414,16 @@ pub struct RpcServerConfig {
     ws_cors_domains: Option<String>,
     /// Address where to bind the ws server to
     ws_addr: Option<SocketAddr>,
+    /// Configs for JSON-RPC IPC server
+    ipc_server_config: Option<IpcServerBuilder>,
+    /// The path of the IPC socket
+    ipc_endpoint: Option<String>,
+    /// The secret to validate the JWTs sent to the http and ws servers
+    jwt_secret: Option<JwtSecret>,
+    /// Controller of the http server for the admin namespace
+    http_controller: Option<Arc<HttpServerController>>,
+    /// Configs for the Core Space GraphQL server
+    graphql_server_config: Option<GraphQLServerConfig>,
 }
 
 impl Default for RpcServerConfig {
@@ -343,6 +435,11 @@ impl Default for RpcServerConfig {
             ws_server_config: None,
             ws_cors_domains: None,
             ws_addr: None,
+            ipc_server_config: None,
+            ipc_endpoint: None,
+            jwt_secret: None,
+            http_controller: None,
+            graphql_server_config: None,
             // rpc_middleware: RpcServiceBuilder::new(),
         }
     }
@@ -359,6 +456,11 @@ impl RpcServerConfig {
         Self::default().with_ws(config)
     }
 
+    /// Creates a new config with only ipc set
+    pub fn ipc(config: IpcServerBuilder) -> Self {
+        Self::default().with_ipc(config)
+    }
+
     /// Configures the http server
     ///
     /// Note: this always configures an [`EthSubscriptionIdProvider`]
@@ -380,6 +482,17 @@ impl RpcServerConfig {
             Some(config.set_id_provider(EthSubscriptionIdProvider::default()));
         self
     }
+
+    /// Configures the ipc server
+    ///
+    /// Note: this always configures an [`EthSubscriptionIdProvider`]
+    /// [`IdProvider`] for convenience. To set a custom [`IdProvider`],
+    /// please use [`Self::with_id_provider`].
+    pub fn with_ipc(mut self, config: IpcServerBuilder) -> Self {
+        self.ipc_server_config =
+            Some(config.set_id_provider(EthSubscriptionIdProvider::default()));
+        self
+    }
 }
 
 impl RpcServerConfig {
@@ -417,6 +530,34 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the path of the ipc socket
+    pub fn with_ipc_endpoint(mut self, path: impl Into<String>) -> Self {
+        self.ipc_endpoint = Some(path.into());
+        self
+    }
+
+    /// Configures the [`JwtSecret`] required by the modules of
+    /// [`TransportRpcModuleConfig::with_auth`] on the http and ws servers.
+    pub fn with_jwt_secret(mut self, secret: JwtSecret) -> Self {
+        self.jwt_secret = Some(secret);
+        self
+    }
+
+    /// Configures the [`HttpServerController`] which takes over the started
+    /// http server.
+    pub fn with_http_controller(
+        mut self, http_controller: Arc<HttpServerController>,
+    ) -> Self {
+        self.http_controller = Some(http_controller);
+        self
+    }
+
+    /// Configures the Core Space GraphQL server.
+    pub fn with_graphql(mut self, config: GraphQLServerConfig) -> Self {
+        self.graphql_server_config = Some(config);
+        self
+    }
+
     /// Sets a custom [`IdProvider`] for all configured transports.
     ///
     /// By default all transports use [`EthSubscriptionIdProvider`]
@@ -430,6 +571,9 @@ impl RpcServerConfig {
             self.ws_server_config =
                 Some(ws.set_id_provider(id_provider.clone()));
         }
+        if let Some(ipc) = self.ipc_server_config {
+            self.ipc_server_config = Some(ipc.set_id_provider(id_provider));
+        }
 
         self
     }
@@ -439,7 +583,10 @@ impl RpcServerConfig {
     /// If no server is configured, no server will be launched on
     /// [`RpcServerConfig::start`].
     pub const fn has_server(&self) -> bool {
-        self.http_server_config.is_some() || self.ws_server_config.is_some()
+        self.http_server_config.is_some()
+            || self.ws_server_config.is_some()
+            || self.ipc_server_config.is_some()
+            || self.graphql_server_config.is_some()
     }
 
     /// Returns the [`SocketAddr`] of the http server
@@ -448,6 +595,9 @@ impl RpcServerConfig {
     /// Returns the [`SocketAddr`] of the ws server
     pub const fn ws_address(&self) -> Option<SocketAddr> { self.ws_addr }
 
+    /// Returns the path of the ipc socket
+    pub fn ipc_endpoint(&self) -> Option<&String> { self.ipc_endpoint.as_ref() }
+
     // Builds and starts the configured server(s): http, ws, ipc.
     //
     // If both http and ws are on the same port, they are combined into one
@@ -461,16 +611,23 @@ impl RpcServerConfig {
         // TODO: handle enable metrics
         debug!("enable metrics: {}", enable_metrics);
 
-        let rpc_middleware = RpcServiceBuilder::new()
-            .layer_fn(move |s| {
-                Throttle::new(
-                    throttling_conf_file.as_ref().map(|s| s.as_str()),
-                    "rpc",
-                    s,
-                )
-            })
-            .layer_fn(|s| Metrics::new(s))
-            .layer_fn(|s| Logger::new(s));
+        let throttle = Arc::new(match &throttling_conf_file {
+            Some(file) => {
+                ThrottleManager::load(file, "rpc").map_err(RpcError::Custom)?
+            }
+            None => ThrottleManager::default(),
+        });
+        let middlewares = ServerMiddlewares {
+            jwt_secret: self.jwt_secret.clone(),
+            protected_methods: modules.protected_methods.clone(),
+            throttle: throttle.clone(),
+        };
+        if let Some(controller) = &self.http_controller {
+            if let Some(module) = modules.http.as_ref().or(modules.ws.as_ref())
+            {
+                controller.set_module(module.clone(), middlewares.clone());
+            }
+        }
 
         let http_socket_addr =
             self.http_addr.unwrap_or(SocketAddr::V4(SocketAddrV4::new(
@@ -482,6 +639,46 @@ impl RpcServerConfig {
             SocketAddrV4::new(Ipv4Addr::LOCALHOST, constants::DEFAULT_WS_PORT),
         ));
 
+        let mut result = RpcServerHandle {
+            http_local_addr: None,
+            ws_local_addr: None,
+            graphql_local_addr: None,
+            ipc_endpoint: None,
+            http: None,
+            ws: None,
+            ipc: None,
+            graphql: None,
+        };
+        if let Some(config) = self.graphql_server_config {
+            let (addr, handle) = start_graphql_server(
+                config,
+                self.jwt_secret.clone(),
+                throttle.clone(),
+            )
+            .await?;
+
+            result.graphql = Some(handle);
+            result.graphql_local_addr = Some(addr);
+        }
+        if let Some(config) = self.ipc_server_config {
+            let endpoint = self
+                .ipc_endpoint
+                .unwrap_or_else(|| constants::DEFAULT_IPC_ENDPOINT.to_string());
+            // ipc clients have no remote address, they share the buckets
+            let rpc_middleware = RpcServiceBuilder::new()
+                .layer_fn(move |s| Throttle::new(throttle.clone(), s))
+                .layer_fn(|s| Metrics::new(s))
+                .layer_fn(|s| Logger::new(s));
+            let handle = config
+                .set_rpc_middleware(rpc_middleware)
+                .build(endpoint.clone())
+                .start(modules.ipc.clone().expect("ipc server error"))
+                .await?;
+
+            result.ipc = Some(handle);
+            result.ipc_endpoint = Some(endpoint);
+        }
+
         // If both are configured on the same port, we combine them into one
         // server.
         if self.http_addr == self.ws_addr
@@ -492,36 +689,25 @@ impl RpcServerConfig {
             modules.config.ensure_ws_http_identical()?;
 
             if let Some(config) = self.http_server_config {
-                let server = ServerBuilder::new()
-                    .set_rpc_middleware(rpc_middleware)
-                    .set_config(config.build())
-                    .build(http_socket_addr)
-                    .await
-                    .map_err(|err| {
-                        RpcError::server_error(
-                            err,
-                            ServerKind::WsHttp(http_socket_addr),
-                        )
-                    })?;
-                let addr = server.local_addr().map_err(|err| {
-                    RpcError::server_error(
-                        err,
-                        ServerKind::WsHttp(http_socket_addr),
-                    )
-                })?;
                 if let Some(module) =
                     modules.http.as_ref().or(modules.ws.as_ref())
                 {
-                    let handle = server.start(module.clone());
-                    let http_handle = Some(handle.clone());
-                    let ws_handle = Some(handle);
-
-                    return Ok(RpcServerHandle {
-                        http_local_addr: Some(addr),
-                        ws_local_addr: Some(addr),
-                        http: http_handle,
-                        ws: ws_handle,
-                    });
+                    let (addr, handle) = start_server(
+                        config.build(),
+                        ServerKind::WsHttp(http_socket_addr),
+                        module.clone(),
+                        middlewares,
+                    )
+                    .await?;
+                    if let Some(controller) = &self.http_controller {
+                        controller.set_running(addr, handle.clone());
+                    }
+                    result.http = Some(handle.clone());
+                    result.ws = Some(handle);
+                    result.http_local_addr = Some(addr);
+                    result.ws_local_addr = Some(addr);
+
+                    return Ok(result);
                 }
 
                 return Err(RpcError::Custom(
@@ -530,66 +716,152 @@ impl RpcServerConfig {
             }
         }
 
-        let mut result = RpcServerHandle {
-            http_local_addr: None,
-            ws_local_addr: None,
-            http: None,
-            ws: None,
-        };
         if let Some(config) = self.ws_server_config {
-            let server = ServerBuilder::new()
-                .set_config(config.ws_only().build())
-                .set_rpc_middleware(rpc_middleware.clone())
-                .build(ws_socket_addr)
-                .await
-                .map_err(|err| {
-                    RpcError::server_error(err, ServerKind::WS(ws_socket_addr))
-                })?;
-
-            let addr = server.local_addr().map_err(|err| {
-                RpcError::server_error(err, ServerKind::WS(ws_socket_addr))
-            })?;
-
-            let ws_local_addr = Some(addr);
-            let ws_server = Some(server);
-            let ws_handle = ws_server.map(|ws_server| {
-                ws_server.start(modules.ws.clone().expect("ws server error"))
-            });
-
-            result.ws = ws_handle;
-            result.ws_local_addr = ws_local_addr;
+            let (addr, handle) = start_server(
+                config.ws_only().build(),
+                ServerKind::WS(ws_socket_addr),
+                modules.ws.clone().expect("ws server error"),
+                middlewares.clone(),
+            )
+            .await?;
+
+            result.ws = Some(handle);
+            result.ws_local_addr = Some(addr);
         }
 
         if let Some(config) = self.http_server_config {
-            let server = ServerBuilder::new()
-                .set_config(config.http_only().build())
-                .set_rpc_middleware(rpc_middleware)
-                .build(http_socket_addr)
-                .await
-                .map_err(|err| {
-                    RpcError::server_error(
-                        err,
-                        ServerKind::Http(http_socket_addr),
-                    )
-                })?;
-            let local_addr = server.local_addr().map_err(|err| {
-                RpcError::server_error(err, ServerKind::Http(http_socket_addr))
-            })?;
-            let http_local_addr = Some(local_addr);
-            let http_server = Some(server);
-            let http_handle = http_server.map(|http_server| {
-                http_server
-                    .start(modules.http.clone().expect("http server error"))
-            });
-
-            result.http = http_handle;
-            result.http_local_addr = http_local_addr;
+            let (local_addr, http_handle) = start_http_server(
+                config,
+                http_socket_addr,
+                modules.http.clone().expect("http server error"),
+                middlewares,
+            )
+            .await?;
+            if let Some(controller) = &self.http_controller {
+                controller.set_running(local_addr, http_handle.clone());
+            }
+
+            result.http = Some(http_handle);
+            result.http_local_addr = Some(local_addr);
         }
 
         Ok(result)
     }
 }
 
+/// The middleware settings shared by the http and ws servers.
+#[derive(Clone)]
+pub(crate) struct ServerMiddlewares {
+    /// The secret to validate the JWTs
+    jwt_secret: Option<JwtSecret>,
+    /// The methods which can only be called with a valid JWT
+    protected_methods: Arc<HashSet<String>>,
+    /// The token buckets of the clients, shared by all servers
+    throttle: Arc<ThrottleManager>,
+}
+
+/// Builds and starts a http only server serving the module.
+pub(crate) async fn start_http_server(
+    config: ServerConfigBuilder, addr: SocketAddr, module: RpcModule<()>,
+    middlewares: ServerMiddlewares,
+) -> Result<(SocketAddr, ServerHandle), RpcError> {
+    start_server(
+        config.http_only().build(),
+        ServerKind::Http(addr),
+        module,
+        middlewares,
+    )
+    .await
+}
+
+/// The time to wait before accepting connections again after an error.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Builds and starts a server serving the module on the address of `kind`.
+///
+/// The [`Throttle`] middleware keys the clients by the [`RemoteAddr`] of
+/// their requests, which [`jsonrpsee::server::Server::start`] does not pass to
+/// the middlewares. So the connections are served by [`axum::serve`], whose
+/// per-connection hook builds a service of
+/// [`jsonrpsee::server::Builder::to_service_builder`] knowing the peer
+/// address. The services still enforce the limits of `config`, e.g. the
+/// maximum number of connections, as they share the connection guard of the
+/// builder.
+async fn start_server(
+    config: ServerConfig, kind: ServerKind, module: RpcModule<()>,
+    middlewares: ServerMiddlewares,
+) -> Result<(SocketAddr, ServerHandle), RpcError> {
+    let addr = match kind {
+        ServerKind::Http(addr)
+        | ServerKind::WS(addr)
+        | ServerKind::WsHttp(addr)
+        | ServerKind::Auth(addr) => addr,
+    };
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|err| RpcError::server_error(err, kind))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|err| RpcError::server_error(err, kind))?;
+    // as `Server::start` does with the default `tcp_no_delay`
+    let listener = listener.tap_io(|socket| {
+        if let Err(e) = socket.set_nodelay(true) {
+            debug!("Failed to set TCP_NODELAY on rpc connection: {}", e);
+        }
+    });
+
+    let ServerMiddlewares {
+        jwt_secret,
+        protected_methods,
+        throttle,
+    } = middlewares;
+    let http_middleware = tower::ServiceBuilder::new()
+        .layer(AuthLayer::new(jwt_secret))
+        .layer(ApiKeyLayer::new(throttle.api_key_header()));
+    let rpc_middleware = RpcServiceBuilder::new()
+        .layer_fn(move |s| Auth::new(protected_methods.clone(), s))
+        .layer_fn(move |s| Throttle::new(throttle.clone(), s))
+        .layer_fn(|s| Metrics::new(s))
+        .layer_fn(|s| Logger::new(s));
+    let service_builder = ServerBuilder::new()
+        .set_config(config)
+        .set_http_middleware(http_middleware)
+        .set_rpc_middleware(rpc_middleware)
+        .to_service_builder();
+
+    let methods: Methods = module.into();
+    let (stop_handle, server_handle) = stop_channel();
+    let stopped = stop_handle.clone().shutdown();
+    let make_service = tower::service_fn(move |conn: IncomingStream<'_, _>| {
+        let remote_addr = RemoteAddr(*conn.remote_addr());
+        let service = service_builder
+            .clone()
+            .build(methods.clone(), stop_handle.clone());
+        let service = tower::service_fn(move |mut req: Request| {
+            req.extensions_mut().insert(remote_addr);
+            let service = service.clone();
+            async move {
+                let response = match service.oneshot(req).await {
+                    Ok(response) => response.map(Body::new),
+                    Err(e) => {
+                        debug!("Failed to serve rpc request: {}", e);
+                        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                    }
+                };
+                Ok::<_, Infallible>(response)
+            }
+        });
+        async move { Ok::<_, Infallible>(service) }
+    });
+    tokio::spawn(
+        axum::serve(listener, make_service)
+            .with_graceful_shutdown(stopped)
+            .into_future(),
+    );
+
+    Ok((local_addr, server_handle))
+}
+
 /// Holds modules to be installed per transport type
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct TransportRpcModuleConfig {
@@ -597,6 +869,10 @@ pub struct TransportRpcModuleConfig {
     http: Option<RpcModuleSelection>,
     /// ws module configuration
     ws: Option<RpcModuleSelection>,
+    /// ipc module configuration
+    ipc: Option<RpcModuleSelection>,
+    /// modules which require a JWT on http and ws
+    auth: Option<RpcModuleSelection>,
 }
 
 impl TransportRpcModuleConfig {
@@ -610,6 +886,11 @@ impl TransportRpcModuleConfig {
         Self::default().with_ws(ws)
     }
 
+    /// Creates a new config with only ipc set
+    pub fn set_ipc(ipc: impl Into<RpcModuleSelection>) -> Self {
+        Self::default().with_ipc(ipc)
+    }
+
     /// Sets the [`RpcModuleSelection`] for the http transport.
     pub fn with_http(mut self, http: impl Into<RpcModuleSelection>) -> Self {
         self.http = Some(http.into());
@@ -622,6 +903,20 @@ impl TransportRpcModuleConfig {
         self
     }
 
+    /// Sets the [`RpcModuleSelection`] for the ipc transport.
+    pub fn with_ipc(mut self, ipc: impl Into<RpcModuleSelection>) -> Self {
+        self.ipc = Some(ipc.into());
+        self
+    }
+
+    /// Sets the [`RpcModuleSelection`] which can only be called with a valid
+    /// JWT on the http and ws transports. The modules still need to be
+    /// configured for the transports to be served.
+    pub fn with_auth(mut self, auth: impl Into<RpcModuleSelection>) -> Self {
+        self.auth = Some(auth.into());
+        self
+    }
+
     /// Get a mutable reference to the
     pub fn http_mut(&mut self) -> &mut Option<RpcModuleSelection> {
         &mut self.http
@@ -630,9 +925,14 @@ impl TransportRpcModuleConfig {
     /// Get a mutable reference to the
     pub fn ws_mut(&mut self) -> &mut Option<RpcModuleSelection> { &mut self.ws }
 
+    /// Get a mutable reference to the
+    pub fn ipc_mut(&mut self) -> &mut Option<RpcModuleSelection> {
+        &mut self.ipc
+    }
+
     /// Returns true if no transports are configured
     pub const fn is_empty(&self) -> bool {
-        self.http.is_none() && self.ws.is_none()
+        self.http.is_none() && self.ws.is_none() && self.ipc.is_none()
     }
 
     /// Returns the [`RpcModuleSelection`] for the http transport
@@ -643,6 +943,14 @@ impl TransportRpcModuleConfig {
     /// Returns the [`RpcModuleSelection`] for the ws transport
     pub const fn ws(&self) -> Option<&RpcModuleSelection> { self.ws.as_ref() }
 
+    /// Returns the [`RpcModuleSelection`] for the ipc transport
+    pub const fn ipc(&self) -> Option<&RpcModuleSelection> { self.ipc.as_ref() }
+
+    /// Returns the [`RpcModuleSelection`] which requires a JWT
+    pub const fn auth(&self) -> Option<&RpcModuleSelection> {
+        self.auth.as_ref()
+    }
+
     /// Ensures that both http and ws are configured and that they are
     /// configured to use the same port.
     fn ensure_ws_http_identical(&self) -> Result<(), WsHttpSamePortError> {
@@ -690,6 +998,10 @@ pub struct TransportRpcModules<Context = ()> {
     http: Option<RpcModule<Context>>,
     /// rpcs module for ws
     ws: Option<RpcModule<Context>>,
+    /// rpcs module for ipc
+    ipc: Option<RpcModule<Context>>,
+    /// the methods which require a JWT on http and ws
+    protected_methods: Arc<HashSet<String>>,
 }
 
 // === impl TransportRpcModules ===
@@ -729,6 +1041,20 @@ impl TransportRpcModules {
         Ok(false)
     }
 
+    /// Merge the given [Methods] in the configured ipc methods.
+    ///
+    /// Fails if any of the methods in other is present already.
+    ///
+    /// Returns [Ok(false)] if no ipc transport is configured.
+    pub fn merge_ipc(
+        &mut self, other: impl Into<Methods>,
+    ) -> Result<bool, RegisterMethodError> {
+        if let Some(ref mut ipc) = self.ipc {
+            return ipc.merge(other.into()).map(|_| true);
+        }
+        Ok(false)
+    }
+
     /// Merge the given [Methods] in all configured methods.
     ///
     /// Fails if any of the methods in other is present already.
@@ -738,6 +1064,7 @@ impl TransportRpcModules {
         let other = other.into();
         self.merge_http(other.clone())?;
         self.merge_ws(other.clone())?;
+        self.merge_ipc(other)?;
         Ok(())
     }
 
@@ -771,6 +1098,21 @@ impl TransportRpcModules {
         }
     }
 
+    /// Removes the method with the given name from the configured ipc methods.
+    ///
+    /// Returns `true` if the method was found and removed, `false` otherwise.
+    ///
+    /// Be aware that a subscription consist of two methods, `subscribe` and
+    /// `unsubscribe` and it's the caller responsibility to remove both
+    /// `subscribe` and `unsubscribe` methods for subscriptions.
+    pub fn remove_ipc_method(&mut self, method_name: &'static str) -> bool {
+        if let Some(ipc_module) = &mut self.ipc {
+            ipc_module.remove_method(method_name).is_some()
+        } else {
+            false
+        }
+    }
+
     /// Removes the method with the given name from all configured transports.
     ///
     /// Returns `true` if the method was found and removed, `false` otherwise.
@@ -779,8 +1121,9 @@ impl TransportRpcModules {
     ) -> bool {
         let http_removed = self.remove_http_method(method_name);
         let ws_removed = self.remove_ws_method(method_name);
+        let ipc_removed = self.remove_ipc_method(method_name);
 
-        http_removed || ws_removed
+        http_removed || ws_removed || ipc_removed
     }
 }
 
@@ -794,8 +1137,14 @@ pub struct RpcServerHandle {
     /// The address of the http/ws server
     http_local_addr: Option<SocketAddr>,
     ws_local_addr: Option<SocketAddr>,
+    /// The address of the GraphQL server
+    graphql_local_addr: Option<SocketAddr>,
+    /// The path of the ipc socket
+    ipc_endpoint: Option<String>,
     http: Option<ServerHandle>,
     ws: Option<ServerHandle>,
+    ipc: Option<ServerHandle>,
+    graphql: Option<ServerHandle>,
 }
 
 impl RpcServerHandle {
@@ -809,6 +1158,14 @@ impl RpcServerHandle {
         self.ws_local_addr
     }
 
+    /// Returns the [`SocketAddr`] of the GraphQL server if started.
+    pub const fn graphql_local_addr(&self) -> Option<SocketAddr> {
+        self.graphql_local_addr
+    }
+
+    /// Returns the path of the ipc socket if started.
+    pub fn ipc_endpoint(&self) -> Option<String> { self.ipc_endpoint.clone() }
+
     /// Tell the server to stop without waiting for the server to stop.
     pub fn stop(self) -> Result<(), AlreadyStoppedError> {
         if let Some(handle) = self.http {
@@ -819,6 +1176,14 @@ impl RpcServerHandle {
             handle.stop()?
         }
 
+        if let Some(handle) = self.ipc {
+            handle.stop()?
+        }
+
+        if let Some(handle) = self.graphql {
+            handle.stop()?
+        }
+
         Ok(())
     }
 
@@ -831,4 +1196,10 @@ impl RpcServerHandle {
     pub fn ws_url(&self) -> Option<String> {
         self.ws_local_addr.map(|addr| format!("ws://{addr}"))
     }
+
+    /// Returns the url which GraphQL queries are posted to
+    pub fn graphql_url(&self) -> Option<String> {
+        self.graphql_local_addr
+            .map(|addr| format!("http://{addr}/graphql"))
+    }
 }
@@ -257,7 +257,7 @@ impl fmt::Display for RpcModuleSelection {
 #[strum(serialize_all = "kebab-case")]
 pub enum EthRpcModule {
     /// `admin_` module
-    // Admin,
+    Admin,
     /// `debug_` module
     Debug,
     /// `eth_` module
@@ -310,7 +310,7 @@ impl FromStr for EthRpcModule {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
-            // "admin" => Self::Admin,
+            "admin" => Self::Admin,
             "debug" | "ethdebug" => Self::Debug,
             "eth" => Self::Eth,
             "net" => Self::Net,
@@ -6,6 +6,7 @@ use cfx_rpc_cfx_types::{
     AccountPendingInfo, AccountPendingTransactions, BlockHashOrEpochNumber,
     Bytes as RpcBytes, Receipt as RpcReceipt, RpcAddress, TransactionRequest,
 };
+use cfx_typed_data::TypedData;
 use cfx_types::{H256, H520, U128, U256, U64};
 use jsonrpsee::{core::RpcResult as JsonRpcResult, proc_macros::rpc};
 
@@ -39,6 +40,13 @@ pub trait CfxDebugRpc {
         &self, data: RpcBytes, address: RpcAddress, password: Option<String>,
     ) -> JsonRpcResult<H520>;
 
+    /// Signs typed structured data via
+    /// [CIP-23](https://github.com/Conflux-Chain/CIPs/blob/master/CIPs/cip-23.md).
+    #[method(name = "signTypedData")]
+    fn sign_typed_data(
+        &self, address: RpcAddress, data: TypedData, password: Option<String>,
+    ) -> JsonRpcResult<H520>;
+
     #[method(name = "signTransaction")]
     fn sign_transaction(
         &self, tx: TransactionRequest, password: Option<String>,
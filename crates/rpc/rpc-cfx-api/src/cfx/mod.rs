@@ -9,7 +9,7 @@ use cfx_rpc_cfx_types::{
     EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
     Receipt as RpcReceipt, RewardInfo as RpcRewardInfo, RpcAddress,
     SponsorInfo, Status as RpcStatus, StorageCollateralInfo, TokenSupplyInfo,
-    Transaction, TransactionRequest, VoteParamsInfo,
+    Transaction, TransactionRequest, TransactionsByAddress, VoteParamsInfo,
 };
 use cfx_rpc_primitives::U64 as HexU64;
 use cfx_types::{H256, U256, U64};
@@ -189,6 +189,14 @@ pub trait CfxRpc {
         &self, tx_hash: H256,
     ) -> JsonRpcResult<Option<Transaction>>;
 
+    /// Get the transactions sent from or to an address, from the newest one.
+    /// `cursor` is the `nextCursor` of the previous page. It requires
+    /// `persist_address_tx_index` to be enabled.
+    #[method(name = "getTransactionsByAddress")]
+    async fn transactions_by_address(
+        &self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>,
+    ) -> JsonRpcResult<TransactionsByAddress>;
+
     /// Return estimated gas and collateral usage.
     #[method(name = "estimateGasAndCollateral")]
     async fn estimate_gas_and_collateral(
@@ -230,6 +238,13 @@ pub trait CfxRpc {
         &self, address: RpcAddress, epoch_num: Option<EpochNumber>,
     ) -> JsonRpcResult<RpcAccount>;
 
+    /// Return the accounts of the given addresses, in the same order. All of
+    /// them are read from the state of the same epoch.
+    #[method(name = "getAccounts")]
+    async fn accounts(
+        &self, addresses: Vec<RpcAddress>, epoch_num: Option<EpochNumber>,
+    ) -> JsonRpcResult<Vec<RpcAccount>>;
+
     /// Returns interest rate of the given epoch
     #[method(name = "getInterestRate")]
     async fn interest_rate(
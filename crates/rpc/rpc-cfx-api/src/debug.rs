@@ -3,12 +3,11 @@
 // See http://www.gnu.org/licenses/
 
 use cfx_rpc_cfx_types::{
-    ConsensusGraphStates, EpochNumber, RpcAddress, StatOnGasLoad,
-    SyncGraphStates, Transaction as RpcTransaction,
+    ConsensusGraphStates, EpochNumber, EpochReceiptProof, RpcAddress,
+    StatOnGasLoad, SyncGraphStates, Transaction as RpcTransaction,
 };
 use cfx_rpc_eth_types::WrapTransaction;
 use cfx_types::{H256, U64};
-// use cfxcore::verification::EpochReceiptProof;
 use jsonrpsee::{core::RpcResult as JsonRpcResult, proc_macros::rpc};
 use network::{
     node_table::{Node, NodeId},
@@ -77,10 +76,10 @@ pub trait DebugRpc {
         &self, last_epoch: EpochNumber, time_window: U64,
     ) -> JsonRpcResult<Option<StatOnGasLoad>>;
 
-    // #[method(name = "getEpochReceiptProofByTransaction")]
-    // fn epoch_receipt_proof_by_transaction(
-    //     &self, tx_hash: H256,
-    // ) -> JsonRpcResult<Option<EpochReceiptProof>>;
+    #[method(name = "getEpochReceiptProofByTransaction")]
+    fn epoch_receipt_proof_by_transaction(
+        &self, tx_hash: H256,
+    ) -> JsonRpcResult<Option<EpochReceiptProof>>;
 
     #[method(name = "getTransactionsByEpoch")]
     fn transactions_by_epoch(
@@ -3,7 +3,10 @@
 // See http://www.gnu.org/licenses/
 
 use cfx_rpc_cfx_types::{
+    trace::{TraceResults, TraceResultsWithTransactionHash},
+    trace_replay::TraceType,
     EpochNumber, LocalizedBlockTrace, LocalizedTrace, TraceFilter,
+    TransactionRequest,
 };
 use cfx_rpc_eth_types::trace::EpochTrace;
 use cfx_types::H256;
@@ -34,4 +37,25 @@ pub trait Trace {
     #[method(name = "epoch")]
     fn epoch_traces(&self, epoch: EpochNumber)
         -> RpcResult<Option<EpochTrace>>;
+
+    /// Executes a transaction on the state of the epoch and returns the
+    /// requested traces.
+    #[method(name = "call")]
+    fn call(
+        &self, request: TransactionRequest, trace_types: Vec<TraceType>,
+        epoch: Option<EpochNumber>,
+    ) -> RpcResult<TraceResults>;
+
+    /// Replays a transaction and returns the requested traces.
+    #[method(name = "replayTransaction")]
+    fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Option<TraceResults>>;
+
+    /// Replays the Core Space transactions in an epoch and returns the
+    /// requested traces.
+    #[method(name = "replayBlockTransactions")]
+    fn replay_block_transactions(
+        &self, epoch: EpochNumber, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Vec<TraceResultsWithTransactionHash>>;
 }
@@ -21,7 +21,10 @@ use cfx_parameters::{
     genesis::{
         genesis_contract_address_four_year, genesis_contract_address_two_year,
     },
-    rpc::GAS_PRICE_DEFAULT_VALUE,
+    rpc::{
+        GAS_PRICE_DEFAULT_VALUE, TRANSACTIONS_BY_ADDRESS_DEFAULT_LIMIT,
+        TRANSACTIONS_BY_ADDRESS_MAX_LIMIT,
+    },
     staking::{BLOCKS_PER_YEAR, DRIPS_PER_STORAGE_COLLATERAL_UNIT},
 };
 use cfx_rpc_cfx_api::{CfxDebugRpcServer, CfxRpcServer};
@@ -36,14 +39,15 @@ use cfx_rpc_cfx_types::{
     EstimateGasAndCollateralResponse, Log as RpcLog, PoSEconomics,
     RewardInfo as RpcRewardInfo, RpcAddress, RpcImplConfiguration, SponsorInfo,
     Status as RpcStatus, StorageCollateralInfo, TokenSupplyInfo,
-    Transaction as RpcTransaction, TransactionRequest, VoteParamsInfo,
+    Transaction as RpcTransaction, TransactionRequest, TransactionsByAddress,
+    VoteParamsInfo,
 };
 use cfx_rpc_eth_types::FeeHistory;
 use cfx_rpc_primitives::U64 as HexU64;
 use cfx_rpc_utils::error::jsonrpsee_error_helpers::{
-    call_execution_error, internal_error, internal_error_with_data,
-    invalid_params, invalid_params_check, invalid_params_msg,
-    invalid_params_rpc_err, pivot_assumption_failed,
+    account_error, call_execution_error, encoding_error, internal_error,
+    internal_error_with_data, invalid_params, invalid_params_check,
+    invalid_params_msg, invalid_params_rpc_err, pivot_assumption_failed,
     request_rejected_in_catch_up_mode,
 };
 use cfx_statedb::{
@@ -55,6 +59,7 @@ use cfx_statedb::{
     StateDbExt,
 };
 use cfx_storage::state::StateDbGetOriginalMethods;
+use cfx_typed_data::{Standard, TypedData};
 use cfx_types::{
     Address, AddressSpaceUtil, BigEndianHash, Space, H160, H256, H520, U128,
     U256, U64,
@@ -67,9 +72,7 @@ use cfxcore::{
         pos_handler::PosVerifier, MaybeExecutedTxExtraInfo, TransactionInfo,
     },
     consensus_parameters::DEFERRED_STATE_EPOCH_COUNT,
-    errors::{
-        account_result_to_rpc_result, Error as CoreError, Result as CoreResult,
-    },
+    errors::{Error as CoreError, Result as CoreResult},
     ConsensusGraph, SharedConsensusGraph, SharedSynchronizationService,
     SharedTransactionPool,
 };
@@ -89,7 +92,8 @@ use rustc_hex::ToHex;
 use storage_interface::DBReaderForPoW;
 
 use crate::{
-    eth_data_hash, helpers::build_block,
+    eth_data_hash,
+    helpers::{build_block, check_accounts_limit, read_accounts},
     pos_handler::convert_to_pos_epoch_reward,
 };
 
@@ -1286,6 +1290,50 @@ impl CfxRpcServer for CfxHandler {
         Ok(None)
     }
 
+    async fn transactions_by_address(
+        &self, address: RpcAddress, cursor: Option<U64>, limit: Option<U64>,
+    ) -> RpcResult<TransactionsByAddress> {
+        self.check_address_network(address.network)?;
+        info!(
+            "RPC Request: cfx_getTransactionsByAddress address={:?} cursor={:?} limit={:?}",
+            address, cursor, limit
+        );
+
+        let limit = limit
+            .map_or(TRANSACTIONS_BY_ADDRESS_DEFAULT_LIMIT, |limit| {
+                limit.as_usize()
+            });
+        if limit > TRANSACTIONS_BY_ADDRESS_MAX_LIMIT {
+            bail!(invalid_params(
+                "limit",
+                Some(format!(
+                    "limit should not exceed {}",
+                    TRANSACTIONS_BY_ADDRESS_MAX_LIMIT
+                ))
+            ));
+        }
+
+        let (tx_hashes, next_cursor) = self
+            .consensus
+            .get_transactions_by_address(
+                &address.hex_address.with_native_space(),
+                cursor.map(|cursor| cursor.as_u64()),
+                limit,
+            )
+            .map_err(into_rpc_err)?;
+        let mut transactions = Vec::with_capacity(tx_hashes.len());
+        for hash in tx_hashes {
+            if let Some(tx) = self.transaction_by_hash(hash).await? {
+                transactions.push(tx);
+            }
+        }
+
+        Ok(TransactionsByAddress {
+            transactions,
+            next_cursor: next_cursor.map(Into::into),
+        })
+    }
+
     async fn estimate_gas_and_collateral(
         &self, request: TransactionRequest, epoch_number: Option<EpochNumber>,
     ) -> RpcResult<EstimateGasAndCollateralResponse> {
@@ -1490,30 +1538,53 @@ impl CfxRpcServer for CfxHandler {
             "RPC Request: cfx_getAccount address={:?} epoch_num={:?}",
             address, epoch_num
         );
-        let addr = &address.hex_address;
         let state_db = self
             .consensus
             .get_state_db_by_epoch_number(epoch_num, "epoch_num")
             .map_err(into_rpc_err)?;
-        let account = match state_db
-            .get_account(&addr.with_native_space())
-            .map_err(into_rpc_err)?
-        {
-            Some(t) => t,
-            None => account_result_to_rpc_result(
-                "address",
-                Ok(Account::new_empty_with_balance(
-                    &addr.with_native_space(),
-                    &U256::zero(),
-                    &U256::zero(),
-                )),
-            )
-            .map_err(into_rpc_err)?,
-        };
+        let account = read_accounts(
+            &state_db,
+            &[address.hex_address.with_native_space()],
+        )
+        .map_err(into_rpc_err)?
+        .remove(0);
         RpcAccount::try_from(account, network)
             .map_err(|e| invalid_params_rpc_err(e, None::<bool>))
     }
 
+    async fn accounts(
+        &self, addresses: Vec<RpcAddress>, epoch_num: Option<EpochNumber>,
+    ) -> RpcResult<Vec<RpcAccount>> {
+        check_accounts_limit(addresses.len()).map_err(into_rpc_err)?;
+        for address in &addresses {
+            self.check_address_network(address.network)?;
+        }
+        let epoch_num = epoch_num.unwrap_or(EpochNumber::LatestState).into();
+        info!(
+            "RPC Request: cfx_getAccounts addresses={} epoch_num={:?}",
+            addresses.len(),
+            epoch_num
+        );
+
+        let state_db = self
+            .consensus
+            .get_state_db_by_epoch_number(epoch_num, "epoch_num")
+            .map_err(into_rpc_err)?;
+        let native_addresses: Vec<_> = addresses
+            .iter()
+            .map(|address| address.hex_address.with_native_space())
+            .collect();
+        read_accounts(&state_db, &native_addresses)
+            .map_err(into_rpc_err)?
+            .into_iter()
+            .zip(&addresses)
+            .map(|(account, address)| {
+                RpcAccount::try_from(account, address.network)
+                    .map_err(|e| invalid_params_rpc_err(e, None::<bool>))
+            })
+            .collect()
+    }
+
     async fn interest_rate(
         &self, epoch_number: Option<EpochNumber>,
     ) -> RpcResult<U256> {
@@ -2073,6 +2144,20 @@ impl CfxDebugRpcServer for CfxHandler {
         Ok(H520(signature.into()))
     }
 
+    fn sign_typed_data(
+        &self, address: RpcAddress, data: TypedData, password: Option<String>,
+    ) -> RpcResult<H520> {
+        self.check_address_network(address.network)?;
+        let message =
+            data.signing_hash(Standard::Cip23).map_err(encoding_error)?;
+        let password = password.map(Password::from);
+        let signature = self
+            .accounts
+            .sign(address.into(), password, message)
+            .map_err(account_error)?;
+        Ok(H520(signature.into()))
+    }
+
     fn sign_transaction(
         &self, tx: TransactionRequest, password: Option<String>,
     ) -> RpcResult<String> {
@@ -12,8 +12,8 @@ use cfx_rpc_cfx_types::{
         ConsensusGraphBlockExecutionState, ConsensusGraphBlockState,
     },
     sync_graph_states::SyncGraphBlockState,
-    ConsensusGraphStates, EpochNumber, RpcAddress, StatOnGasLoad,
-    SyncGraphStates, Transaction as RpcTransaction,
+    ConsensusGraphStates, EpochNumber, EpochReceiptProof, RpcAddress,
+    StatOnGasLoad, SyncGraphStates, Transaction as RpcTransaction,
 };
 use cfx_rpc_eth_types::{Transaction as EthTransaction, WrapTransaction};
 use cfx_rpc_utils::error::jsonrpsee_error_helpers::{
@@ -43,6 +43,8 @@ use cfx_rpc_cfx_types::{
     receipt::Receipt as RpcReceipt, transaction::PackedOrExecuted,
 };
 
+use crate::helpers::build_epoch_receipt_proof;
+
 pub struct DebugHandler {
     tx_pool: SharedTransactionPool,
     consensus: SharedConsensusGraph,
@@ -597,6 +599,14 @@ impl DebugRpcServer for DebugHandler {
         Ok(Some(stat))
     }
 
+    fn epoch_receipt_proof_by_transaction(
+        &self, tx_hash: H256,
+    ) -> RpcResult<Option<EpochReceiptProof>> {
+        build_epoch_receipt_proof(self.consensus_graph(), &tx_hash)
+            .map(Some)
+            .map_err(|e| invalid_params_rpc_err(e, None::<bool>))
+    }
+
     fn transactions_by_epoch(
         &self, epoch_number: U64,
     ) -> RpcResult<Vec<WrapTransaction>> {
@@ -0,0 +1,151 @@
+use cfx_parameters::rpc::GET_ACCOUNTS_MAX_ADDRESSES;
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::invalid_params;
+use cfx_statedb::{StateDb, StateDbExt};
+use cfx_types::{AddressWithSpace, U256};
+use cfx_util_macros::bail;
+use cfxcore::errors::{Error as CoreError, Result as CoreResult};
+use primitives::Account;
+
+/// Checks that at most [`GET_ACCOUNTS_MAX_ADDRESSES`] accounts are requested
+/// by one call.
+pub fn check_accounts_limit(count: usize) -> CoreResult<()> {
+    if count > GET_ACCOUNTS_MAX_ADDRESSES {
+        bail!(invalid_params(
+            "addresses",
+            Some(format!(
+                "at most {} addresses are allowed",
+                GET_ACCOUNTS_MAX_ADDRESSES
+            ))
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the accounts of `addresses`, in the same order, from one snapshot
+/// of the state. An address without an account gets an empty account.
+pub fn read_accounts(
+    state_db: &StateDb, addresses: &[AddressWithSpace],
+) -> CoreResult<Vec<Account>> {
+    addresses
+        .iter()
+        .map(|address| {
+            Ok(state_db
+                .get_account(address)
+                .map_err(CoreError::from)?
+                .unwrap_or_else(|| {
+                    Account::new_empty_with_balance(
+                        address,
+                        &U256::zero(), /* balance */
+                        &U256::zero(), /* nonce */
+                    )
+                }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_types::{Address, AddressSpaceUtil};
+    use primitives::StorageKey;
+
+    fn address(n: u8) -> Address {
+        let mut address = Address::zero();
+        // A user address in the Core Space.
+        address.0[0] = 0x10;
+        address.0[19] = n;
+        address
+    }
+
+    fn set_balance(
+        state_db: &mut StateDb, address: &AddressWithSpace, balance: u64,
+    ) {
+        let account = Account::new_empty_with_balance(
+            address,
+            &balance.into(),
+            &1.into(),
+        );
+        state_db
+            .set(
+                StorageKey::new_account_key(&address.address)
+                    .with_space(address.space),
+                &account,
+                None,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_accounts_limit() {
+        assert!(check_accounts_limit(0).is_ok());
+        assert!(check_accounts_limit(GET_ACCOUNTS_MAX_ADDRESSES).is_ok());
+        assert!(check_accounts_limit(GET_ACCOUNTS_MAX_ADDRESSES + 1).is_err());
+    }
+
+    #[test]
+    fn test_read_accounts_in_order() {
+        let mut state_db = StateDb::new_for_unit_test();
+        let addresses: Vec<_> =
+            (1..=3).map(|n| address(n).with_native_space()).collect();
+        for (i, address) in addresses.iter().enumerate() {
+            set_balance(&mut state_db, address, 100 * (i as u64 + 1));
+        }
+
+        let mut requested = addresses.clone();
+        requested.reverse();
+        requested.push(addresses[1]);
+        let accounts = read_accounts(&state_db, &requested).unwrap();
+
+        assert_eq!(accounts.len(), requested.len());
+        for (account, address) in accounts.iter().zip(&requested) {
+            assert_eq!(account.address(), address);
+        }
+        let balances: Vec<_> =
+            accounts.iter().map(|account| account.balance).collect();
+        assert_eq!(
+            balances,
+            vec![300.into(), 200.into(), 100.into(), 200.into()]
+        );
+    }
+
+    #[test]
+    fn test_read_missing_accounts() {
+        let mut state_db = StateDb::new_for_unit_test();
+        let existing = address(1).with_native_space();
+        set_balance(&mut state_db, &existing, 100);
+
+        // The same address in the other space is another account.
+        let requested = [
+            address(2).with_native_space(),
+            existing,
+            address(1).with_evm_space(),
+        ];
+        let accounts = read_accounts(&state_db, &requested).unwrap();
+
+        assert_eq!(accounts[0], Account::new_empty(&requested[0]));
+        assert_eq!(accounts[1].balance, 100.into());
+        assert_eq!(accounts[2], Account::new_empty(&requested[2]));
+    }
+
+    #[test]
+    fn test_read_accounts_from_one_state() {
+        let addresses =
+            [address(1).with_native_space(), address(2).with_evm_space()];
+        let mut old_state = StateDb::new_for_unit_test();
+        let mut new_state = StateDb::new_for_unit_test();
+        for address in &addresses {
+            set_balance(&mut old_state, address, 100);
+            set_balance(&mut new_state, address, 200);
+        }
+
+        let old_accounts = read_accounts(&old_state, &addresses).unwrap();
+        let new_accounts = read_accounts(&new_state, &addresses).unwrap();
+
+        assert!(old_accounts
+            .iter()
+            .all(|account| account.balance == 100.into()));
+        assert!(new_accounts
+            .iter()
+            .all(|account| account.balance == 200.into()));
+    }
+}
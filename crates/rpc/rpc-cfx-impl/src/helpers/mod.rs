@@ -1,14 +1,18 @@
+mod account;
 pub mod block_provider;
 mod epoch_queue;
 pub mod poll_filter;
 pub mod poll_manager;
+pub mod receipt_proof;
 pub mod subscribers;
 
+pub use account::{check_accounts_limit, read_accounts};
 pub use block_provider::{build_block, build_header};
 pub use epoch_queue::EpochQueue;
 pub use poll_filter::{
     limit_logs, PollFilter, SyncPollFilter, MAX_BLOCK_HISTORY_SIZE,
 };
 pub use poll_manager::PollManager;
+pub use receipt_proof::build_epoch_receipt_proof;
 
 pub const MAX_FEE_HISTORY_CACHE_BLOCK_COUNT: u64 = 1024;
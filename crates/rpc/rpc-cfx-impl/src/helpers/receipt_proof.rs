@@ -0,0 +1,94 @@
+use cfx_rpc_cfx_types::EpochReceiptProof;
+use cfx_storage::TrieProof;
+use cfx_types::H256;
+use cfxcore::{
+    consensus::TransactionInfo, verification::compute_epoch_receipt_proof,
+    ConsensusGraph,
+};
+use primitives::EpochNumber;
+use rlp::Encodable;
+use std::sync::Arc;
+
+/// Build the receipt proof of the executed transaction `tx_hash` from the
+/// stored receipts of its epoch.
+pub fn build_epoch_receipt_proof(
+    consensus: &ConsensusGraph, tx_hash: &H256,
+) -> Result<EpochReceiptProof, String> {
+    let (block_hash, tx_index_in_block) =
+        match consensus.get_signed_tx_and_tx_info(tx_hash) {
+            Some((_, TransactionInfo { tx_index, .. })) => {
+                (tx_index.block_hash, tx_index.real_index)
+            }
+            None => {
+                return Err(format!(
+                    "Unable to get transaction info for hash {:?}",
+                    tx_hash
+                ))
+            }
+        };
+
+    let epoch =
+        consensus
+            .get_block_epoch_number(&block_hash)
+            .ok_or_else(|| {
+                format!("Unable to get epoch number for block {:?}", block_hash)
+            })?;
+
+    let epoch_hashes = consensus
+        .get_block_hashes_by_epoch(EpochNumber::Number(epoch))
+        .map_err(|e| {
+            format!("Unable to find epoch hashes for {}: {}", epoch, e)
+        })?;
+
+    let block_index_in_epoch = epoch_hashes
+        .iter()
+        .position(|h| *h == block_hash)
+        .ok_or_else(|| {
+            format!("Unable to find block {:?} in epoch {}", block_hash, epoch)
+        })?;
+
+    let pivot = *epoch_hashes
+        .last()
+        .expect("epoch hashes should be not empty");
+
+    let epoch_receipts = epoch_hashes
+        .iter()
+        .map(|h| {
+            consensus
+                .data_manager()
+                .block_execution_result_by_hash_with_epoch(
+                    h, &pivot, false, /* update_pivot_assumption */
+                    false, /* update_cache */
+                )
+                .map(|res| Arc::new((*res.block_receipts).clone()))
+                .ok_or_else(|| format!("Unable to find receipts for {:?}", h))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let proof = compute_epoch_receipt_proof(
+        &epoch_receipts,
+        block_index_in_epoch,
+        tx_index_in_block,
+    );
+
+    let block_receipts = &epoch_receipts[block_index_in_epoch].receipts;
+
+    Ok(EpochReceiptProof {
+        epoch_number: epoch.into(),
+        block_index: block_index_in_epoch.into(),
+        block_count: epoch_receipts.len().into(),
+        transaction_index: tx_index_in_block.into(),
+        receipt_count: block_receipts.len().into(),
+        receipt: block_receipts[tx_index_in_block].rlp_bytes().into(),
+        block_index_proof: encode_proof_nodes(proof.block_index_proof),
+        block_receipt_proof: encode_proof_nodes(proof.block_receipt_proof),
+    })
+}
+
+fn encode_proof_nodes(proof: TrieProof) -> Vec<cfx_rpc_cfx_types::Bytes> {
+    proof
+        .into_proof_nodes()
+        .iter()
+        .map(|node| rlp::encode(node).into())
+        .collect()
+}
@@ -3,13 +3,21 @@
 // See http://www.gnu.org/licenses/
 
 use cfx_addr::Network;
-use cfx_execute_helper::exec_tracer::TraceFilter as PrimitiveTraceFilter;
+use cfx_execute_helper::{
+    estimation::EstimateRequest,
+    exec_tracer::TraceFilter as PrimitiveTraceFilter,
+};
 use cfx_rpc_cfx_api::TraceServer;
 use cfx_rpc_cfx_types::{
-    EpochNumber as RpcEpochNumber, TraceFilter as RpcTraceFilter,
+    address::{check_rpc_address_network, check_two_rpc_address_network_match},
+    trace_replay::{StateDiff, VmTrace},
+    EpochNumber as RpcEpochNumber, RpcAddress, TraceFilter as RpcTraceFilter,
+    TransactionRequest,
+};
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::{
+    internal_error, invalid_params_check,
 };
-use cfx_rpc_utils::error::jsonrpsee_error_helpers::internal_error;
-use cfx_types::{Space, H256};
+use cfx_types::{Address, AddressSpaceUtil, Space, H256, U256};
 use cfx_util_macros::bail;
 use cfxcore::{
     block_data_manager::DataVersionTuple, errors::Result as CoreResult,
@@ -18,13 +26,17 @@ use cfxcore::{
 use cfxcore_errors::ProviderBlockError;
 use jsonrpsee::core::RpcResult;
 use log::warn;
-use primitives::EpochNumber;
+use primitives::{BlockHashOrEpochNumber, EpochNumber};
 use std::sync::Arc;
 
-use cfx_parity_trace_types::LocalizedTrace as PrimitiveLocalizedTrace;
+use cfx_parity_trace_types::{
+    LocalizedTrace as PrimitiveLocalizedTrace, TraceOptions, TraceType,
+    TransactionExecTraces, TransactionReplayTraces,
+};
 use cfx_rpc_cfx_types::trace::{
     Action as RpcAction, LocalizedBlockTrace,
-    LocalizedTrace as RpcLocalizedTrace, Trace,
+    LocalizedTrace as RpcLocalizedTrace, Trace, TraceResults,
+    TraceResultsWithTransactionHash,
 };
 use cfx_rpc_common_impl::trace::primitive_traces_to_eth_localized_traces;
 use cfx_rpc_eth_types::EpochTrace;
@@ -34,14 +46,19 @@ pub struct TraceHandler {
     pub data_man: Arc<BlockDataManager>,
     pub consensus: SharedConsensusGraph,
     pub network: Network,
+    max_estimation_gas_limit: Option<U256>,
 }
 
 impl TraceHandler {
-    pub fn new(network: Network, consensus: SharedConsensusGraph) -> Self {
+    pub fn new(
+        network: Network, consensus: SharedConsensusGraph,
+        max_estimation_gas_limit: Option<U256>,
+    ) -> Self {
         TraceHandler {
             data_man: consensus.data_manager().clone(),
             consensus,
             network,
+            max_estimation_gas_limit,
         }
     }
 
@@ -215,6 +232,171 @@ impl TraceHandler {
 
         Ok(Some(EpochTrace::new(cfx_traces, eth_traces)))
     }
+
+    /// Executes a Core Space transaction on the state of the epoch and
+    /// returns the parity traces.
+    pub fn call_impl(
+        &self, mut request: TransactionRequest, trace_types: Vec<TraceType>,
+        epoch: Option<RpcEpochNumber>,
+    ) -> CoreResult<TraceResults> {
+        let rpc_request_network = invalid_params_check(
+            "request",
+            check_two_rpc_address_network_match(
+                request.from.as_ref(),
+                request.to.as_ref(),
+            ),
+        )?;
+        invalid_params_check(
+            "request",
+            check_rpc_address_network(rpc_request_network, &self.network),
+        )?;
+        // Like `sign_call`, the sender defaults to the zero address.
+        let from = request
+            .from
+            .as_ref()
+            .map_or_else(Address::zero, |from| from.hex_address);
+
+        let consensus_graph = self.consensus_graph();
+        let epoch = epoch.unwrap_or(RpcEpochNumber::LatestState);
+        let epoch_height =
+            consensus_graph.get_height_from_epoch_number(epoch.into())?;
+
+        // The transaction is executed with the checks of `cfx_call`, and a
+        // default sender is not charged for gas.
+        let estimate_request = EstimateRequest {
+            has_sender: request.from.is_some(),
+            has_gas_limit: request.gas.is_some(),
+            has_gas_price: request.has_gas_price(),
+            has_nonce: request.nonce.is_some(),
+            has_storage_limit: request.storage_limit.is_some(),
+        };
+        if request.nonce.is_none() {
+            request.nonce = Some(consensus_graph.next_nonce(
+                from.with_native_space(),
+                BlockHashOrEpochNumber::EpochNumber(EpochNumber::Number(
+                    epoch_height,
+                )),
+                "epoch",
+            )?);
+        }
+
+        let chain_id = consensus_graph.best_chain_id();
+        let signed_tx = request.sign_call(
+            epoch_height,
+            chain_id.in_native_space(),
+            self.max_estimation_gas_limit,
+        )?;
+        let traces = consensus_graph.collect_call_parity_trace(
+            epoch_height,
+            signed_tx,
+            estimate_request,
+            TraceOptions::from_trace_types(&trace_types),
+        )?;
+        self.to_rpc_trace_results(traces)
+    }
+
+    pub fn replay_transaction_impl(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> CoreResult<Option<TraceResults>> {
+        match self.replay_transaction_primitive_impl(
+            tx_hash,
+            Space::Native,
+            TraceOptions::from_trace_types(&trace_types),
+        )? {
+            Some(traces) => Ok(Some(self.to_rpc_trace_results(traces)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn replay_epoch_transactions_impl(
+        &self, epoch: RpcEpochNumber, trace_types: Vec<TraceType>,
+    ) -> CoreResult<Vec<TraceResultsWithTransactionHash>> {
+        let epoch_num = self
+            .consensus_graph()
+            .get_height_from_epoch_number(epoch.into())?;
+        self.replay_epoch_primitive_impl(
+            epoch_num,
+            Space::Native,
+            TraceOptions::from_trace_types(&trace_types),
+        )?
+        .into_iter()
+        .map(|traces| {
+            let transaction_hash = traces.tx_hash;
+            Ok(TraceResultsWithTransactionHash {
+                results: self.to_rpc_trace_results(traces)?,
+                transaction_hash,
+            })
+        })
+        .collect()
+    }
+
+    /// Replays the transaction in its epoch, returns `None` if the
+    /// transaction is not executed or not in `space`.
+    pub fn replay_transaction_primitive_impl(
+        &self, tx_hash: H256, space: Space, options: TraceOptions,
+    ) -> CoreResult<Option<TransactionReplayTraces>> {
+        let Some(tx_index) = self
+            .data_man
+            .transaction_index_by_hash(&tx_hash, false /* update_cache */)
+        else {
+            return Ok(None);
+        };
+        if tx_index.is_phantom {
+            return Ok(None);
+        }
+        let Some(epoch_num) =
+            self.consensus.get_block_epoch_number(&tx_index.block_hash)
+        else {
+            return Ok(None);
+        };
+
+        let traces = self.consensus_graph().collect_epoch_parity_trace(
+            epoch_num,
+            Some(tx_hash),
+            options,
+        )?;
+        Ok(traces.into_iter().find(|traces| traces.space == space))
+    }
+
+    /// Replays the transactions in the epoch and returns the traces of
+    /// those in `space`.
+    pub fn replay_epoch_primitive_impl(
+        &self, epoch_num: u64, space: Space, options: TraceOptions,
+    ) -> CoreResult<Vec<TransactionReplayTraces>> {
+        Ok(self
+            .consensus_graph()
+            .collect_epoch_parity_trace(epoch_num, None, options)?
+            .into_iter()
+            .filter(|traces| traces.space == space)
+            .collect())
+    }
+
+    fn to_rpc_trace_results(
+        &self, traces: TransactionReplayTraces,
+    ) -> CoreResult<TraceResults> {
+        let network = self.network;
+        let trace = match traces.trace {
+            Some(trace) => Trace::from_exec_traces(
+                TransactionExecTraces(trace).filter_space(Space::Native).0,
+                network,
+            )?,
+            None => vec![],
+        };
+        let state_diff = match traces.state_diff {
+            Some(diff) => {
+                Some(StateDiff::try_from(diff, Space::Native, |address| {
+                    RpcAddress::try_from_h160(address, network)
+                })?)
+            }
+            None => None,
+        };
+        Ok(TraceResults {
+            output: traces.output.into(),
+            trace,
+            vm_trace: traces.vm_trace.map(VmTrace::from),
+            state_diff,
+        })
+    }
 }
 
 impl TraceServer for TraceHandler {
@@ -244,6 +426,28 @@ impl TraceServer for TraceHandler {
         self.epoch_trace_impl(epoch.into_primitive())
             .map_err(Into::into)
     }
+
+    fn call(
+        &self, request: TransactionRequest, trace_types: Vec<TraceType>,
+        epoch: Option<RpcEpochNumber>,
+    ) -> RpcResult<TraceResults> {
+        self.call_impl(request, trace_types, epoch)
+            .map_err(Into::into)
+    }
+
+    fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Option<TraceResults>> {
+        self.replay_transaction_impl(tx_hash, trace_types)
+            .map_err(Into::into)
+    }
+
+    fn replay_block_transactions(
+        &self, epoch: RpcEpochNumber, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Vec<TraceResultsWithTransactionHash>> {
+        self.replay_epoch_transactions_impl(epoch, trace_types)
+            .map_err(Into::into)
+    }
 }
 
 impl TraceHandler {
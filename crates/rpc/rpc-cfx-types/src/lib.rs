@@ -17,6 +17,7 @@ pub mod pos_economics;
 pub mod provenance;
 pub mod pubsub;
 pub mod receipt;
+pub mod receipt_proof;
 pub mod reward_info;
 mod rpc_impl_configuration;
 pub mod sponsor_info;
@@ -28,6 +29,7 @@ pub mod sync_graph_states;
 pub mod token_supply_info;
 pub mod trace;
 pub mod trace_filter;
+pub mod trace_replay;
 pub mod traits;
 pub mod transaction;
 pub mod transaction_request;
@@ -49,6 +51,7 @@ pub use log::Log;
 pub use phantom_block::PhantomBlock;
 pub use pos_economics::PoSEconomics;
 pub use receipt::Receipt;
+pub use receipt_proof::EpochReceiptProof;
 pub use reward_info::RewardInfo;
 pub use rpc_impl_configuration::RpcImplConfiguration;
 pub use sponsor_info::SponsorInfo;
@@ -59,7 +62,7 @@ pub use sync_graph_states::*;
 pub use token_supply_info::TokenSupplyInfo;
 pub use trace::{LocalizedBlockTrace, LocalizedTrace};
 pub use trace_filter::TraceFilter;
-pub use transaction::Transaction;
+pub use transaction::{Transaction, TransactionsByAddress};
 pub use transaction_request::*;
 pub use transaction_status::{PendingReason, TransactionStatus};
 pub use tx_pool::*;
@@ -20,14 +20,14 @@
 
 //! Pub-Sub types.
 
-use crate::{CfxRpcLogFilter, Header, Log};
+use crate::{pos, CfxRpcLogFilter, Header, Log};
 use cfx_types::{H256, U256};
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged, rename_all = "camelCase")]
 // NOTE: rename_all does not apply to enum member fields
 // see: https://github.com/serde-rs/serde/issues/1061
@@ -51,6 +51,16 @@ pub enum Result {
     /// Chain reorg
     #[serde(rename_all = "camelCase")]
     ChainReorg { revert_to: U256 },
+
+    /// Committed PoS block
+    PosBlock(pos::Block),
+
+    /// Committee of the PoS epoch starting with the next PoS block
+    #[serde(rename_all = "camelCase")]
+    PosCommitteeChange { committee: pos::RpcCommittee },
+
+    /// PoS account status
+    PosAccount(pos::Account),
 }
 
 /// Subscription kind.
@@ -68,6 +78,13 @@ pub enum Kind {
     Syncing,
     /// Epoch
     Epochs,
+    /// Epochs finalized by PoS, in order.
+    FinalizedEpochs,
+    /// Committed PoS blocks, in order. The first block of each new PoS epoch
+    /// is preceded by the committee of the epoch.
+    PosBlocks,
+    /// Changes of the status of a PoS account.
+    PosAccount,
 }
 
 /// Subscription epoch.
@@ -90,6 +107,8 @@ pub enum Params {
     Logs(CfxRpcLogFilter),
     /// Epoch parameters.
     Epochs(SubscriptionEpoch),
+    /// PoS account address.
+    PosAccount(H256),
 }
 
 impl Default for Params {
@@ -112,9 +131,40 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(v);
         }
 
-        // otherwise, interpret as epoch
-        from_value(v).map(Params::Epochs).map_err(|e| {
+        // try to interpret as epoch
+        if let Ok(v) = from_value(v.clone()).map(Params::Epochs) {
+            return Ok(v);
+        }
+
+        // otherwise, interpret as PoS account address
+        from_value(v).map(Params::PosAccount).map_err(|e| {
             D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e))
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Params, SubscriptionEpoch};
+    use cfx_types::H256;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_params() {
+        let params: Params = serde_json::from_value(json!(null)).unwrap();
+        assert_eq!(params, Params::None);
+
+        let params: Params =
+            serde_json::from_value(json!("latest_state")).unwrap();
+        assert_eq!(params, Params::Epochs(SubscriptionEpoch::LatestState));
+
+        let address = H256::from_low_u64_be(1);
+        let params: Params = serde_json::from_value(json!(address)).unwrap();
+        assert_eq!(params, Params::PosAccount(address));
+
+        let params: Params = serde_json::from_value(json!({})).unwrap();
+        assert!(matches!(params, Params::Logs(_)));
+
+        assert!(serde_json::from_value::<Params>(json!("0x1")).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use cfx_rpc_primitives::Bytes;
+use cfx_types::{H256, U64};
+use primitives::verify_epoch_receipt_proof;
+use serde_derive::{Deserialize, Serialize};
+
+/// Proof that a receipt is included in the receipts of an epoch.
+///
+/// The proof is checked against the `deferredReceiptsRoot` of the pivot block
+/// header `DEFERRED_STATE_EPOCH_COUNT` epochs after `epochNumber`. Proof nodes
+/// are RLP encoded trie nodes from the root down to the proved value.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochReceiptProof {
+    pub epoch_number: U64,
+    pub block_index: U64,
+    pub block_count: U64,
+    pub transaction_index: U64,
+    pub receipt_count: U64,
+    /// The RLP encoded receipt.
+    pub receipt: Bytes,
+    /// Proves the receipts root of the block against the epoch receipts root.
+    pub block_index_proof: Vec<Bytes>,
+    /// Proves the receipt against the receipts root of the block.
+    pub block_receipt_proof: Vec<Bytes>,
+}
+
+impl EpochReceiptProof {
+    /// Verify the proof against `deferred_receipts_root`, without trusting
+    /// the node that produced it.
+    pub fn verify(&self, deferred_receipts_root: &H256) -> bool {
+        verify_epoch_receipt_proof(
+            deferred_receipts_root,
+            self.block_index.as_usize(),
+            self.block_count.as_usize(),
+            &self.block_index_proof,
+            self.transaction_index.as_usize(),
+            self.receipt_count.as_usize(),
+            &self.receipt,
+            &self.block_receipt_proof,
+        )
+    }
+}
@@ -1,4 +1,5 @@
 use cfx_types::U256;
+use std::path::PathBuf;
 
 #[derive(Clone, Default, Debug)]
 pub struct RpcImplConfiguration {
@@ -18,4 +19,8 @@ pub struct RpcImplConfiguration {
     pub enable_metrics: bool,
 
     pub poll_lifetime_in_seconds: Option<u32>,
+
+    /// The directory of the files exported and imported by
+    /// `admin_exportChain` and `admin_importChain`.
+    pub admin_chain_data_dir: Option<PathBuf>,
 }
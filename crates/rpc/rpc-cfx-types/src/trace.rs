@@ -2,7 +2,7 @@
 // Conflux is free software and distributed under GNU General Public License.
 // See http://www.gnu.org/licenses/
 
-use crate::RpcAddress;
+use crate::{trace_replay, RpcAddress};
 use cfx_addr::Network;
 use cfx_parity_trace_types::{
     Action as VmAction, ActionType as VmActionType, BlockExecTraces,
@@ -343,6 +343,29 @@ impl Serialize for Trace {
     }
 }
 
+impl Trace {
+    /// Converts the traces of a Core Space transaction.
+    pub fn from_exec_traces(
+        traces: Vec<ExecTrace>, network: Network,
+    ) -> Result<Vec<Self>, String> {
+        traces
+            .into_iter()
+            .map(|t| {
+                let valid = t.valid;
+                Action::try_from(t.action, network)
+                    .map(|action| Trace { action, valid })
+            })
+            .collect()
+    }
+}
+
+/// The result of `trace_call` and `trace_replayTransaction`.
+pub type TraceResults = trace_replay::TraceResults<Trace, RpcAddress>;
+
+/// The result of `trace_replayBlockTransactions`.
+pub type TraceResultsWithTransactionHash =
+    trace_replay::TraceResultsWithTransactionHash<Trace, RpcAddress>;
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LocalizedTrace {
@@ -386,14 +409,7 @@ impl LocalizedTransactionTrace {
         let traces: Vec<ExecTrace> = traces.into();
 
         Ok(LocalizedTransactionTrace {
-            traces: traces
-                .into_iter()
-                .map(|t| {
-                    let valid = t.valid;
-                    Action::try_from(t.action, network)
-                        .map(|action| Trace { action, valid })
-                })
-                .collect::<Result<_, _>>()?,
+            traces: Trace::from_exec_traces(traces, network)?,
             transaction_position: transaction_position.into(),
             transaction_hash,
         })
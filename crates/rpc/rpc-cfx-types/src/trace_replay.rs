@@ -0,0 +1,256 @@
+//! The outputs of `trace_call` and `trace_replay*`, in the layout of parity.
+//!
+//! They are shared by Core Space and eSpace, which differ in the call traces
+//! and the address format.
+
+use cfx_parity_trace_types::{
+    AccountDiff as PrimitiveAccountDiff, Diff as PrimitiveDiff,
+    MemoryDiff as PrimitiveMemoryDiff, StateDiff as PrimitiveStateDiff,
+    StorageDiff as PrimitiveStorageDiff,
+    VmExecutedOperation as PrimitiveVmExecutedOperation,
+    VmOperation as PrimitiveVmOperation, VmTrace as PrimitiveVmTrace,
+};
+use cfx_rpc_primitives::Bytes;
+use cfx_types::{Address, Space, H256, U256};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+pub use cfx_parity_trace_types::TraceType;
+
+/// The result of a traced transaction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults<T, A> {
+    /// The return data of the transaction.
+    pub output: Bytes,
+    /// The call traces, empty if not requested.
+    pub trace: Vec<T>,
+    /// The instruction-level traces.
+    pub vm_trace: Option<VmTrace>,
+    /// The account changes.
+    pub state_diff: Option<StateDiff<A>>,
+}
+
+/// The result of a traced transaction in a replayed block.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResultsWithTransactionHash<T, A> {
+    #[serde(flatten)]
+    pub results: TraceResults<T, A>,
+    pub transaction_hash: H256,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VmTrace {
+    pub code: Bytes,
+    pub ops: Vec<VmOperation>,
+}
+
+impl From<PrimitiveVmTrace> for VmTrace {
+    fn from(trace: PrimitiveVmTrace) -> Self {
+        VmTrace {
+            code: trace.code.into(),
+            ops: trace.ops.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VmOperation {
+    pub pc: usize,
+    pub cost: u64,
+    pub ex: Option<VmExecutedOperation>,
+    pub sub: Option<VmTrace>,
+}
+
+impl From<PrimitiveVmOperation> for VmOperation {
+    fn from(op: PrimitiveVmOperation) -> Self {
+        VmOperation {
+            pc: op.pc,
+            cost: op.cost,
+            ex: op.executed.map(Into::into),
+            sub: op.sub.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct VmExecutedOperation {
+    /// The gas left after the instruction.
+    pub used: u64,
+    pub push: Vec<U256>,
+    pub mem: Option<MemoryDiff>,
+    pub store: Option<StorageDiff>,
+}
+
+impl From<PrimitiveVmExecutedOperation> for VmExecutedOperation {
+    fn from(ex: PrimitiveVmExecutedOperation) -> Self {
+        VmExecutedOperation {
+            used: ex.gas_used,
+            push: ex.stack_push,
+            mem: ex.mem_diff.map(Into::into),
+            store: ex.store_diff.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryDiff {
+    pub off: usize,
+    pub data: Bytes,
+}
+
+impl From<PrimitiveMemoryDiff> for MemoryDiff {
+    fn from(diff: PrimitiveMemoryDiff) -> Self {
+        MemoryDiff {
+            off: diff.offset,
+            data: diff.data.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageDiff {
+    pub key: U256,
+    pub val: U256,
+}
+
+impl From<PrimitiveStorageDiff> for StorageDiff {
+    fn from(diff: PrimitiveStorageDiff) -> Self {
+        StorageDiff {
+            key: diff.location,
+            val: diff.value,
+        }
+    }
+}
+
+/// Change of a value, serialized as `"="`, `{"+": to}`, `{"-": from}` or
+/// `{"*": {"from": from, "to": to}}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diff<T>(pub PrimitiveDiff<T>);
+
+#[derive(Serialize)]
+struct ChangedValue<'a, T> {
+    from: &'a T,
+    to: &'a T,
+}
+
+impl<T: Serialize> Serialize for Diff<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let (tag, value) = match &self.0 {
+            PrimitiveDiff::Same => return serializer.serialize_str("="),
+            PrimitiveDiff::Born(to) => ("+", to),
+            PrimitiveDiff::Died(from) => ("-", from),
+            PrimitiveDiff::Changed { from, to } => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("*", &ChangedValue { from, to })?;
+                return map.end();
+            }
+        };
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(tag, value)?;
+        map.end()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountDiff {
+    pub balance: Diff<U256>,
+    pub nonce: Diff<U256>,
+    pub code: Diff<Bytes>,
+    pub storage: BTreeMap<H256, Diff<H256>>,
+}
+
+impl From<PrimitiveAccountDiff> for AccountDiff {
+    fn from(diff: PrimitiveAccountDiff) -> Self {
+        AccountDiff {
+            balance: Diff(diff.balance),
+            nonce: Diff(diff.nonce),
+            code: Diff(diff.code.map(Into::into)),
+            storage: diff
+                .storage
+                .into_iter()
+                .map(|(key, value)| (key, Diff(value)))
+                .collect(),
+        }
+    }
+}
+
+/// The account changes, serialized as a map from the address to the account
+/// diff, in the order of the addresses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateDiff<A>(pub Vec<(A, AccountDiff)>);
+
+impl<A> StateDiff<A> {
+    /// Converts the account changes in the space, with the addresses
+    /// formatted by `format_address`.
+    pub fn try_from<F, E>(
+        diff: PrimitiveStateDiff, space: Space, format_address: F,
+    ) -> Result<Self, E>
+    where F: Fn(Address) -> Result<A, E> {
+        Ok(StateDiff(
+            diff.into_space(space)
+                .map(|(address, diff)| {
+                    Ok((format_address(address)?, diff.into()))
+                })
+                .collect::<Result<_, E>>()?,
+        ))
+    }
+}
+
+impl<A: Serialize> Serialize for StateDiff<A> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (address, diff) in &self.0 {
+            map.serialize_entry(address, diff)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_diff_serialization() {
+        let mut storage = BTreeMap::new();
+        storage.insert(
+            H256::from_low_u64_be(1),
+            Diff(PrimitiveDiff::Changed {
+                from: H256::zero(),
+                to: H256::from_low_u64_be(3),
+            }),
+        );
+        let diff = AccountDiff {
+            balance: Diff(PrimitiveDiff::Born(U256::from(5))),
+            nonce: Diff(PrimitiveDiff::Same),
+            code: Diff(PrimitiveDiff::Died(vec![0x60].into())),
+            storage,
+        };
+
+        let serialized = serde_json::to_string_pretty(&diff).unwrap();
+
+        let expected = r#"{
+  "balance": {
+    "+": "0x5"
+  },
+  "nonce": "=",
+  "code": {
+    "-": "0x60"
+  },
+  "storage": {
+    "0x0000000000000000000000000000000000000000000000000000000000000001": {
+      "*": {
+        "from": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "to": "0x0000000000000000000000000000000000000000000000000000000000000003"
+      }
+    }
+  }
+}"#;
+        assert_eq!(serialized, expected);
+    }
+}
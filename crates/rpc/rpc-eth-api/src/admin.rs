@@ -0,0 +1,55 @@
+use cfx_rpc_eth_types::{NodeInfo, PeerInfo};
+use cfx_types::U64;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// Admin rpc interface, for managing the node.
+#[rpc(server, namespace = "admin")]
+pub trait AdminApi {
+    /// Returns the information of the local node.
+    #[method(name = "nodeInfo")]
+    fn node_info(&self) -> RpcResult<NodeInfo>;
+
+    /// Returns the currently connected peers.
+    #[method(name = "peers")]
+    fn peers(&self) -> RpcResult<Vec<PeerInfo>>;
+
+    /// Adds the node given by the `cfxnode://ID@IP:PORT` url to the trusted
+    /// node table, so that the node will try to connect to it.
+    #[method(name = "addPeer")]
+    fn add_peer(&self, url: String) -> RpcResult<bool>;
+
+    /// Disconnects from the node given by the url and removes it from the
+    /// node table.
+    #[method(name = "removePeer")]
+    fn remove_peer(&self, url: String) -> RpcResult<bool>;
+
+    /// Adds the node given by the url as a reserved node, which is always
+    /// tried first when connecting peers.
+    #[method(name = "addTrustedPeer")]
+    fn add_trusted_peer(&self, url: String) -> RpcResult<bool>;
+
+    /// Starts the HTTP RPC server. The configured address is used if `host`
+    /// or `port` is not provided.
+    #[method(name = "startHTTP")]
+    async fn start_http(
+        &self, host: Option<String>, port: Option<u16>,
+    ) -> RpcResult<bool>;
+
+    /// Stops the HTTP RPC server.
+    #[method(name = "stopHTTP")]
+    async fn stop_http(&self) -> RpcResult<bool>;
+
+    /// Exports the blocks of the epochs in `[first, last]` to the file as
+    /// concatenated RLP. All executed epochs are exported by default. The
+    /// file is a relative path in `admin_chain_data_dir`.
+    #[method(name = "exportChain")]
+    async fn export_chain(
+        &self, file: String, first: Option<U64>, last: Option<U64>,
+    ) -> RpcResult<bool>;
+
+    /// Imports the blocks from a file written by `admin_exportChain`, given as
+    /// a relative path in `admin_chain_data_dir`. Blocks already in the local
+    /// chain are skipped.
+    #[method(name = "importChain")]
+    async fn import_chain(&self, file: String) -> RpcResult<bool>;
+}
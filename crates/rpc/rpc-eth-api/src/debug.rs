@@ -2,8 +2,11 @@ use alloy_rpc_types_trace::geth::{
     GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
     TraceResult,
 };
-use cfx_rpc_eth_types::{BlockId, BlockProperties, TransactionRequest};
-use cfx_types::H256;
+use cfx_rpc_eth_types::{
+    BlockId, BlockProperties, Bytes, StateDump, StorageRangeResult,
+    TransactionRequest,
+};
+use cfx_types::{Address, H256};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 #[rpc(server, namespace = "debug")]
@@ -37,6 +40,49 @@ pub trait DebugApi {
         opts: Option<GethDebugTracingCallOptions>,
     ) -> RpcResult<GethTrace>;
 
+    /// Returns the RLP encoded header of the block. The header is encoded in
+    /// the Ethereum format, with the fields returned by `eth_getBlockByNumber`.
+    #[method(name = "getRawHeader")]
+    async fn debug_get_raw_header(&self, block: BlockId) -> RpcResult<Bytes>;
+
+    /// Returns the RLP encoded block, holding the header, the eSpace
+    /// transactions (including phantom transactions) and no uncles.
+    #[method(name = "getRawBlock")]
+    async fn debug_get_raw_block(&self, block: BlockId) -> RpcResult<Bytes>;
+
+    /// Returns the EIP-2718 encoded receipts of the transactions in the block.
+    #[method(name = "getRawReceipts")]
+    async fn debug_get_raw_receipts(
+        &self, block: BlockId,
+    ) -> RpcResult<Vec<Bytes>>;
+
+    /// Returns the EIP-2718 encoded transaction, or null if the transaction
+    /// is not executed.
+    #[method(name = "getRawTransaction")]
+    async fn debug_get_raw_transaction(
+        &self, tx_hash: H256,
+    ) -> RpcResult<Option<Bytes>>;
+
+    /// Returns a page of the storage of `address`, starting from the slot
+    /// `key_start`. Only the states before the first transaction
+    /// (`tx_index` 0) and after the last transaction of the block are
+    /// available.
+    #[method(name = "storageRangeAt")]
+    async fn debug_storage_range_at(
+        &self, block_hash: H256, tx_index: usize, address: Address,
+        key_start: H256, max_result: usize,
+    ) -> RpcResult<StorageRangeResult>;
+
+    /// Returns a page of the eSpace accounts at the block, starting from the
+    /// address `start`. At most 256 accounts and 10000 storage slots are
+    /// returned. `incompletes` has no effect, as the addresses of all the
+    /// accounts are known.
+    #[method(name = "accountRange")]
+    async fn debug_account_range(
+        &self, block: BlockId, start: Bytes, max_results: usize, no_code: bool,
+        no_storage: bool, incompletes: bool,
+    ) -> RpcResult<StateDump>;
+
     /// Returns block properties needed for validate transaction execution
     /// This method will not return properties for phantom transactions
     #[method(name = "blockProperties")]
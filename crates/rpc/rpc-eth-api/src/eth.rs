@@ -1,10 +1,12 @@
 use cfx_rpc_eth_types::{
-    AccessListResult, AccountPendingTransactions, Block, BlockId,
-    BlockOverrides, Bundle, EthCallResponse, EthRpcLogFilter as Filter,
-    FeeHistory, Header, Log, Receipt, RpcStateOverride, SimulatePayload,
-    SimulatedBlock, StateContext, SyncStatus, Transaction, TransactionRequest,
+    AccessListResult, Account, AccountPendingTransactions, AccountProof, Block,
+    BlockId, BlockOverrides, Bundle, EthCallResponse,
+    EthRpcLogFilter as Filter, FeeHistory, Header, Log, Receipt,
+    RpcStateOverride, SimulatePayload, SimulatedBlock, StateContext,
+    SyncStatus, Transaction, TransactionRequest, TransactionsByAddress,
 };
 use cfx_rpc_primitives::{Bytes, Index};
+use cfx_typed_data::TypedData;
 use cfx_types::{Address, H256, H64, U256, U64};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
@@ -111,6 +113,14 @@ pub trait EthApi {
         &self, hash: H256,
     ) -> RpcResult<Option<Transaction>>;
 
+    /// Returns the transactions sent from or to an address, including phantom
+    /// transactions, from the newest one. `cursor` is the `nextCursor` of the
+    /// previous page. It requires `persist_address_tx_index` to be enabled.
+    #[method(name = "getTransactionsByAddress")]
+    async fn transactions_by_address(
+        &self, address: Address, cursor: Option<U64>, limit: Option<U64>,
+    ) -> RpcResult<TransactionsByAddress>;
+
     /// Returns information about a raw transaction by block hash and
     /// transaction index position.
     #[method(name = "getRawTransactionByBlockHashAndIndex")]
@@ -247,12 +257,17 @@ pub trait EthApi {
 
     /// Returns the account details by specifying an address and a block
     /// number/tag
-    // #[method(name = "getAccount")]
-    // async fn get_account(
-    //     &self,
-    //     address: Address,
-    //     block: BlockId,
-    // ) -> RpcResult<Option<reth_rpc_types::Account>>;
+    #[method(name = "getAccount")]
+    async fn get_account(
+        &self, address: Address, block_number: Option<BlockId>,
+    ) -> RpcResult<Account>;
+
+    /// Returns the account details of the given addresses, in the same
+    /// order. All of them are read from the same state.
+    #[method(name = "getAccounts")]
+    async fn get_accounts(
+        &self, addresses: Vec<Address>, block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<Account>>;
 
     /// Introduced in EIP-1559, returns suggestion for the priority for dynamic
     /// fee transactions.
@@ -336,22 +351,24 @@ pub trait EthApi {
         &self, transaction: TransactionRequest,
     ) -> RpcResult<Bytes>;
 
-    // Signs data via [EIP-712](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md).
-    // #[method(name = "signTypedData")]
-    // async fn sign_typed_data(&self, address: Address, data: TypedData) ->
-    // RpcResult<Bytes>;
-
-    // Returns the account and storage values of the specified account including
-    // the Merkle-proof. This call can be used to verify that the data you
-    // are pulling from is not tampered with.
-
-    // #[method(name = "getProof")]
-    // async fn get_proof(
-    //     &self,
-    //     address: Address,
-    //     keys: Vec<JsonStorageKey>,
-    //     block_number: Option<BlockId>,
-    // ) -> RpcResult<EIP1186AccountProofResponse>;
+    /// Signs data via [EIP-712](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md).
+    /// The account must be unlocked.
+    #[method(name = "signTypedData")]
+    async fn sign_typed_data(
+        &self, address: Address, data: TypedData,
+    ) -> RpcResult<Bytes>;
+
+    /// Returns the account and storage values of the specified account
+    /// including the Merkle-proof. This call can be used to verify that the
+    /// data you are pulling from is not tampered with.
+    ///
+    /// The proof follows the Conflux three-layer state format, see
+    /// [`AccountProof`].
+    #[method(name = "getProof")]
+    async fn get_proof(
+        &self, address: Address, keys: Vec<JsonStorageKey>,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<AccountProof>;
 
     /// Returns logs matching given filter object.
     #[method(name = "getLogs")]
@@ -1,3 +1,4 @@
+mod admin;
 mod debug;
 mod eth;
 mod filter;
@@ -9,6 +10,7 @@ mod trace;
 mod txpool;
 mod web3;
 
+pub use admin::AdminApiServer;
 pub use debug::DebugApiServer;
 pub use eth::EthApiServer;
 pub use filter::EthFilterApiServer;
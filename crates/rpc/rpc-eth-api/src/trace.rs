@@ -1,5 +1,7 @@
 use cfx_rpc_eth_types::{
     BlockId, Index, LocalizedSetAuthTrace, LocalizedTrace, TraceFilter,
+    TraceResults, TraceResultsWithTransactionHash, TraceType,
+    TransactionRequest,
 };
 use cfx_types::H256;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
@@ -34,4 +36,25 @@ pub trait TraceApi {
     async fn transaction_traces(
         &self, tx_hash: H256,
     ) -> RpcResult<Option<Vec<LocalizedTrace>>>;
+
+    /// Executes a transaction on the state of the given block and returns
+    /// the requested traces.
+    #[method(name = "call")]
+    async fn trace_call(
+        &self, request: TransactionRequest, trace_types: Vec<TraceType>,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<TraceResults>;
+
+    /// Replays a transaction and returns the requested traces.
+    #[method(name = "replayTransaction")]
+    async fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Option<TraceResults>>;
+
+    /// Replays all transactions in a block and returns the requested
+    /// traces.
+    #[method(name = "replayBlockTransactions")]
+    async fn replay_block_transactions(
+        &self, block_number: BlockId, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Vec<TraceResultsWithTransactionHash>>;
 }
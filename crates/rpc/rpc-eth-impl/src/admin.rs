@@ -0,0 +1,450 @@
+use crate::traits::HttpServerControl;
+use async_trait::async_trait;
+use cfx_rpc_cfx_types::ChainStaticMeta;
+use cfx_rpc_eth_api::AdminApiServer;
+use cfx_rpc_eth_types::{
+    EthProtocolInfo, NodeInfo, NodeProtocols, PeerInfo, PeerNetworkInfo,
+    PeerProtocol, Ports,
+};
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::{
+    internal_error_with_data, invalid_params_msg,
+};
+use cfx_types::U64;
+use cfx_util_macros::bail;
+use cfxcore::{
+    errors::Result as CoreResult, SharedConsensusGraph,
+    SharedSynchronizationService,
+};
+use jsonrpsee::core::RpcResult;
+use log::info;
+use network::node_table::{Node, NodeEndpoint, NodeEntry};
+use primitives::{Block, EpochNumber};
+use rlp::{Encodable, Rlp};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    net::IpAddr,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+/// An upper bound of the encoded size of a block read by
+/// `admin_importChain`, far above the block size limit, so that a corrupted
+/// length prefix can not make the node allocate arbitrary memory.
+const MAX_BLOCK_RLP_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Clone)]
+pub struct AdminApi {
+    consensus: SharedConsensusGraph,
+    sync: SharedSynchronizationService,
+    http_server: Option<Arc<dyn HttpServerControl>>,
+    // the directory of the files of `admin_exportChain` and
+    // `admin_importChain`, which are disabled if it is not set
+    chain_data_dir: Option<PathBuf>,
+}
+
+impl AdminApi {
+    pub fn new(
+        consensus: SharedConsensusGraph, sync: SharedSynchronizationService,
+        http_server: Option<Arc<dyn HttpServerControl>>,
+        chain_data_dir: Option<PathBuf>,
+    ) -> Self {
+        AdminApi {
+            consensus,
+            sync,
+            http_server,
+            chain_data_dir,
+        }
+    }
+
+    fn parse_node_url(url: &str) -> RpcResult<NodeEntry> {
+        let node = Node::from_str(url).map_err(|e| {
+            invalid_params_msg(&format!("node url {}: {}", url, e))
+        })?;
+        Ok(NodeEntry {
+            id: node.id,
+            endpoint: node.endpoint,
+        })
+    }
+
+    fn http_server(&self) -> RpcResult<&Arc<dyn HttpServerControl>> {
+        self.http_server.as_ref().ok_or_else(|| {
+            internal_error_with_data("HTTP server control is not available")
+        })
+    }
+
+    pub fn node_info_impl(&self) -> CoreResult<NodeInfo> {
+        let network = &self.sync.network;
+        let id = *network.net_key_pair().map_err(|e| e.to_string())?.public();
+        let listen_addr = network
+            .local_addr()
+            .ok_or("Network service not started yet!")?;
+        let endpoint = network
+            .public_endpoint()
+            .ok_or("Network service not started yet!")?;
+
+        Ok(NodeInfo {
+            id,
+            name: ChainStaticMeta::client_version(),
+            enode: Node::new(id, endpoint.clone()).to_string(),
+            ip: endpoint.address.ip().to_string(),
+            listen_addr,
+            ports: Ports {
+                discovery: endpoint.udp_port,
+                listener: endpoint.address.port(),
+            },
+            protocols: NodeProtocols {
+                eth: EthProtocolInfo {
+                    network: network.network_id(),
+                    chain_id: U64::from(
+                        self.consensus.best_chain_id().in_evm_space(),
+                    ),
+                    genesis: self.consensus.data_manager().true_genesis.hash(),
+                    head: self.consensus.best_block_hash(),
+                },
+            },
+        })
+    }
+
+    pub fn peers_impl(&self) -> Vec<PeerInfo> {
+        let network = &self.sync.network;
+        let peers = network.get_peer_info().unwrap_or_default();
+
+        peers
+            .into_iter()
+            .map(|peer| {
+                let (trusted, endpoint) = match network.get_node(&peer.nodeid) {
+                    Some((trusted, node)) => (trusted, node.endpoint),
+                    None => (
+                        false,
+                        NodeEndpoint {
+                            address: peer.addr,
+                            udp_port: peer.addr.port(),
+                        },
+                    ),
+                };
+                let inbound = network
+                    .get_detailed_sessions(Some(peer.nodeid))
+                    .and_then(|sessions| {
+                        sessions.first().map(|session| !session.originated)
+                    })
+                    .unwrap_or(false);
+
+                PeerInfo {
+                    id: peer.nodeid,
+                    enode: Node::new(peer.nodeid, endpoint).to_string(),
+                    network: PeerNetworkInfo {
+                        remote_address: peer.addr,
+                        inbound,
+                        trusted,
+                    },
+                    protocols: peer
+                        .protocols
+                        .iter()
+                        .map(|protocol| PeerProtocol {
+                            name: String::from_utf8_lossy(&protocol.protocol)
+                                .into_owned(),
+                            version: protocol.version.0,
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Blocks the current thread until all the blocks are written, so it
+    /// should be called in a blocking task.
+    pub fn export_chain_impl(
+        &self, file: &str, first: Option<U64>, last: Option<U64>,
+    ) -> CoreResult<()> {
+        let path = chain_file_path(self.chain_data_dir.as_deref(), file)?;
+        // The genesis block is known to every node, so it is skipped by
+        // default.
+        let first = first.map_or(1, |n| n.as_u64());
+        let last = last
+            .map_or_else(|| self.consensus.best_epoch_number(), |n| n.as_u64());
+        if first > last {
+            bail!(invalid_params_msg(&format!(
+                "first epoch {} is after the last epoch {}",
+                first, last
+            )));
+        }
+
+        let data_man = self.consensus.data_manager();
+        let mut writer = BufWriter::new(
+            File::create(&path)
+                .map_err(|e| format!("failed to create {}: {}", file, e))?,
+        );
+        let mut exported = 0;
+        for epoch in first..=last {
+            let hashes = self
+                .consensus
+                .get_block_hashes_by_epoch(EpochNumber::Number(epoch))?;
+            for hash in hashes {
+                let block = data_man
+                    .block_by_hash(&hash, false /* update_cache */)
+                    .ok_or_else(|| {
+                        format!("body of block {:?} is not available", hash)
+                    })?;
+                writer
+                    .write_all(&block.rlp_bytes())
+                    .map_err(|e| format!("failed to write {}: {}", file, e))?;
+                exported += 1;
+            }
+        }
+        writer
+            .flush()
+            .map_err(|e| format!("failed to write {}: {}", file, e))?;
+
+        info!(
+            "Exported {} blocks of epochs [{}, {}] to {}",
+            exported, first, last, file
+        );
+        Ok(())
+    }
+
+    /// Blocks the current thread until all the blocks are imported, so it
+    /// should be called in a blocking task.
+    pub fn import_chain_impl(&self, file: &str) -> CoreResult<()> {
+        let path = chain_file_path(self.chain_data_dir.as_deref(), file)?;
+        let mut reader = BufReader::new(
+            File::open(&path)
+                .map_err(|e| format!("failed to open {}: {}", file, e))?,
+        );
+        let graph = self.sync.get_synchronization_graph();
+
+        let (mut imported, mut skipped) = (0, 0);
+        let mut offset = 0;
+        while let Some(data) = read_block_rlp(&mut reader)
+            .map_err(|e| format!("invalid block at {}: {}", offset, e))?
+        {
+            let block: Block = Rlp::new(&data)
+                .as_val()
+                .map_err(|e| format!("invalid block at {}: {}", offset, e))?;
+            offset += data.len();
+
+            if graph.contains_block(&block.hash()) {
+                skipped += 1;
+                continue;
+            }
+            self.sync
+                .import_block(block)
+                .map_err(|e| format!("failed to import block: {}", e))?;
+            imported += 1;
+        }
+
+        info!(
+            "Imported {} blocks from {}, {} known blocks skipped",
+            imported, file, skipped
+        );
+        Ok(())
+    }
+}
+
+/// Resolves the `file` of `admin_exportChain` and `admin_importChain` in
+/// `dir`. It must be a relative path without `..`, so that the methods can
+/// not access the files out of the directory.
+fn chain_file_path(dir: Option<&Path>, file: &str) -> CoreResult<PathBuf> {
+    let Some(dir) = dir else {
+        bail!(invalid_params_msg(
+            "chain import and export are disabled: admin_chain_data_dir is \
+             not configured"
+        ));
+    };
+    let path = Path::new(file);
+    if file.is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        bail!(invalid_params_msg(&format!(
+            "file {} is not a relative path in admin_chain_data_dir",
+            file
+        )));
+    }
+    Ok(dir.join(path))
+}
+
+/// Reads the next RLP-encoded block from `reader` without decoding it, or
+/// returns `None` at the end of the file.
+fn read_block_rlp(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(None);
+    }
+
+    let mut prefix = [0u8; 9];
+    reader.read_exact(&mut prefix[..1])?;
+    let (header_size, payload_size) = match prefix[0] {
+        size @ 0xc0..=0xf7 => (1, (size - 0xc0) as usize),
+        size_of_size @ 0xf8..=0xff => {
+            let header_size = 1 + (size_of_size - 0xf7) as usize;
+            reader.read_exact(&mut prefix[1..header_size])?;
+            let payload_size =
+                prefix[1..header_size].iter().fold(0usize, |size, byte| {
+                    size.saturating_mul(256).saturating_add(*byte as usize)
+                });
+            (header_size, payload_size)
+        }
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "a block must be an RLP list",
+            ))
+        }
+    };
+
+    let size = header_size.saturating_add(payload_size);
+    if size > MAX_BLOCK_RLP_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("block of {} bytes is too large", size),
+        ));
+    }
+    let mut data = prefix[..header_size].to_vec();
+    data.resize(size, 0);
+    reader.read_exact(&mut data[header_size..])?;
+    Ok(Some(data))
+}
+
+#[async_trait]
+impl AdminApiServer for AdminApi {
+    fn node_info(&self) -> RpcResult<NodeInfo> {
+        self.node_info_impl().map_err(|err| err.into())
+    }
+
+    fn peers(&self) -> RpcResult<Vec<PeerInfo>> { Ok(self.peers_impl()) }
+
+    fn add_peer(&self, url: String) -> RpcResult<bool> {
+        let node = Self::parse_node_url(&url)?;
+        self.sync
+            .network
+            .add_peer(node)
+            .map_err(|e| internal_error_with_data(e.to_string()))?;
+        Ok(true)
+    }
+
+    fn remove_peer(&self, url: String) -> RpcResult<bool> {
+        let node = Self::parse_node_url(&url)?;
+        self.sync
+            .network
+            .drop_peer(node)
+            .map_err(|e| internal_error_with_data(e.to_string()))?;
+        Ok(true)
+    }
+
+    fn add_trusted_peer(&self, url: String) -> RpcResult<bool> {
+        let node = Self::parse_node_url(&url)?;
+        self.sync
+            .network
+            .add_reserved_peer(node)
+            .map_err(|e| internal_error_with_data(e.to_string()))?;
+        Ok(true)
+    }
+
+    async fn start_http(
+        &self, host: Option<String>, port: Option<u16>,
+    ) -> RpcResult<bool> {
+        let http_server = self.http_server()?;
+        let mut addr = http_server.default_http_address();
+        if let Some(host) = host {
+            let ip = IpAddr::from_str(&host).map_err(|e| {
+                invalid_params_msg(&format!("host {}: {}", host, e))
+            })?;
+            addr.set_ip(ip);
+        }
+        if let Some(port) = port {
+            addr.set_port(port);
+        }
+
+        let addr = http_server
+            .start_http(addr)
+            .await
+            .map_err(internal_error_with_data)?;
+        info!("HTTP server started on {}", addr);
+        Ok(true)
+    }
+
+    async fn stop_http(&self) -> RpcResult<bool> {
+        self.http_server()?
+            .stop_http()
+            .map_err(internal_error_with_data)?;
+        info!("HTTP server stopped");
+        Ok(true)
+    }
+
+    async fn export_chain(
+        &self, file: String, first: Option<U64>, last: Option<U64>,
+    ) -> RpcResult<bool> {
+        let api = self.clone();
+        tokio::task::spawn_blocking(move || {
+            api.export_chain_impl(&file, first, last)
+        })
+        .await
+        .map_err(|e| internal_error_with_data(e.to_string()))?
+        .map(|_| true)
+        .map_err(|err| err.into())
+    }
+
+    async fn import_chain(&self, file: String) -> RpcResult<bool> {
+        let api = self.clone();
+        tokio::task::spawn_blocking(move || api.import_chain_impl(&file))
+            .await
+            .map_err(|e| internal_error_with_data(e.to_string()))?
+            .map(|_| true)
+            .map_err(|err| err.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chain_file_path, read_block_rlp, MAX_BLOCK_RLP_SIZE};
+    use primitives::{Block, BlockHeaderBuilder};
+    use rlp::Encodable;
+    use std::{io::Cursor, path::Path};
+
+    #[test]
+    fn test_chain_file_path() {
+        let dir = Path::new("/data/chain");
+        assert_eq!(
+            chain_file_path(Some(dir), "backup/blocks.rlp").unwrap(),
+            dir.join("backup/blocks.rlp")
+        );
+        for file in ["", "/etc/passwd", "../blocks.rlp", "backup/../../x", "."]
+        {
+            assert!(chain_file_path(Some(dir), file).is_err(), "{}", file);
+        }
+        assert!(chain_file_path(None, "blocks.rlp").is_err());
+    }
+
+    #[test]
+    fn test_read_block_rlp() {
+        let blocks: Vec<_> = (0..3)
+            .map(|height| {
+                let header = BlockHeaderBuilder::new()
+                    .with_height(height)
+                    .with_referee_hashes(vec![Default::default(); 3])
+                    .build();
+                Block::new(header, vec![]).rlp_bytes().to_vec()
+            })
+            .collect();
+        let mut reader = Cursor::new(blocks.concat());
+        for block in &blocks {
+            assert_eq!(
+                read_block_rlp(&mut reader).unwrap().as_ref(),
+                Some(block)
+            );
+        }
+        assert!(read_block_rlp(&mut reader).unwrap().is_none());
+
+        // A truncated block.
+        let data = &blocks[0][..blocks[0].len() - 1];
+        assert!(read_block_rlp(&mut Cursor::new(data)).is_err());
+        // Not a list.
+        assert!(read_block_rlp(&mut Cursor::new([0x80])).is_err());
+        // The length prefix exceeds the limit.
+        let size = (MAX_BLOCK_RLP_SIZE as u32).to_be_bytes();
+        let data = [&[0xfb][..], &size].concat();
+        assert!(read_block_rlp(&mut Cursor::new(data)).is_err());
+    }
+}
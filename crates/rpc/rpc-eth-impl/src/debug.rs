@@ -1,23 +1,45 @@
 use alloy_rpc_types_trace::geth::{
-    GethDebugBuiltInTracerType,
+    GethDebugBuiltInTracerType, GethDebugTracerConfig,
     GethDebugTracerType::{BuiltInTracer, JsTracer},
     GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame,
     TraceResult,
 };
 use async_trait::async_trait;
+use cfx_execute_helper::phantom_tx::{
+    encode_eth_receipt, encode_eth_transaction,
+};
+use cfx_rpc_cfx_types::PhantomBlock;
 use cfx_rpc_eth_api::DebugApiServer;
-use cfx_rpc_eth_types::{BlockId, BlockProperties, TransactionRequest};
+use cfx_rpc_eth_types::{
+    AccountState, BlockId, BlockProperties, Bytes, Header, StateDump,
+    StorageEntry, StorageRangeResult, TransactionRequest,
+    EOA_STORAGE_ROOT_H256,
+};
 use cfx_rpc_utils::error::jsonrpsee_error_helpers::invalid_params_msg;
-use cfx_types::{AddressSpaceUtil, Space, H256, U256};
+use cfx_statedb::{StateDb, StateDbExt};
+use cfx_types::{Address, AddressSpaceUtil, Space, H256, U256};
 use cfxcore::{
     errors::Error as CoreError, ConsensusGraph, SharedConsensusGraph,
 };
-use geth_tracer::to_alloy_h256;
+use geth_tracer::{to_alloy_h256, GethTraceWithHash};
 use jsonrpsee::core::RpcResult;
+use keccak_hash::{keccak, KECCAK_EMPTY};
 use primitives::{
-    Block, BlockHashOrEpochNumber, BlockHeaderBuilder, EpochNumber,
+    Account, Block, BlockHashOrEpochNumber, BlockHeaderBuilder, EpochNumber,
+    SkipInputCheck, StorageKey, StorageKeyWithSpace, StorageValue,
+    TransactionStatus,
 };
-use std::{sync::Arc, vec};
+use rlp::{Encodable, Rlp, RlpStream};
+use std::{collections::BTreeMap, iter::zip, sync::Arc, vec};
+
+/// The maximum number of accounts returned by `debug_accountRange`, the same
+/// as geth.
+const ACCOUNT_RANGE_MAX_RESULTS: usize = 256;
+/// The maximum number of storage slots returned by `debug_storageRangeAt`.
+const STORAGE_RANGE_MAX_RESULTS: usize = 256;
+/// The maximum number of storage slots returned by a `debug_accountRange`
+/// call. A page stops before the account whose storage exceeds the rest.
+const ACCOUNT_RANGE_MAX_STORAGE_SLOTS: usize = 10_000;
 
 pub struct DebugApi {
     consensus: SharedConsensusGraph,
@@ -71,7 +93,11 @@ impl DebugApi {
         }
 
         let opts = opts.unwrap_or_default();
+        check_tracer_opts(&opts.tracing_options)?;
         let block_num = block_number.unwrap_or_default();
+        if block_num == BlockId::Pending {
+            return self.trace_pending_call(request, opts.tracing_options);
+        }
 
         let epoch_num = self
             .get_block_epoch_num(block_num)
@@ -143,26 +169,88 @@ impl DebugApi {
             .first()
             .ok_or(CoreError::Msg("trace generation failed".to_string()))?;
 
-        Ok(res.trace.clone())
+        res.trace.clone().map_err(CoreError::Msg)
     }
 
     pub fn trace_block_by_num(
         &self, block_num: u64, opts: Option<GethDebugTracingOptions>,
     ) -> Result<Vec<TraceResult>, CoreError> {
         let opts = opts.unwrap_or_default();
+        check_tracer_opts(&opts)?;
         let epoch_traces = self
             .consensus_graph()
             .collect_epoch_geth_trace(block_num, None, opts)?;
 
-        let result = epoch_traces
+        Ok(espace_trace_results(epoch_traces))
+    }
+
+    /// Executes the call as the last transaction of the pending block.
+    fn trace_pending_call(
+        &self, mut request: TransactionRequest, opts: GethDebugTracingOptions,
+    ) -> Result<GethTrace, CoreError> {
+        let pending = self.consensus_graph().pending_block()?;
+        let epoch_num = pending.height() - 1;
+
+        // nonce auto fill
+        if request.nonce.is_none() {
+            let from = request.from.unwrap().with_evm_space();
+            let nonce = match pending.nonce(&from) {
+                Some(nonce) => nonce,
+                None => self.consensus_graph().next_nonce(
+                    from,
+                    BlockHashOrEpochNumber::EpochNumber(EpochNumber::Number(
+                        epoch_num,
+                    )),
+                    "num",
+                )?,
+            };
+            request.nonce = Some(nonce);
+        }
+
+        let chain_id = self.consensus.best_chain_id();
+        let signed_tx = request.sign_call(
+            chain_id.in_evm_space(),
+            self.max_estimation_gas_limit,
+        )?;
+        let tx_hash = signed_tx.hash();
+
+        let mut transactions = pending.block.transactions.clone();
+        transactions.push(Arc::new(signed_tx));
+        let block =
+            Block::new(pending.block.block_header.clone(), transactions);
+
+        let traces = self.consensus_graph().collect_blocks_geth_trace(
+            *pending.block.block_header.parent_hash(),
+            epoch_num,
+            &vec![Arc::new(block)],
+            opts,
+            Some(tx_hash),
+        )?;
+
+        traces
             .into_iter()
-            .filter(|val| val.space == Space::Ethereum)
-            .map(|val| TraceResult::Success {
-                result: val.trace,
-                tx_hash: Some(to_alloy_h256(val.tx_hash)),
-            })
-            .collect();
-        Ok(result)
+            .find(|val| val.tx_hash == tx_hash)
+            .ok_or(CoreError::Msg("trace generation failed".to_string()))?
+            .trace
+            .map_err(CoreError::Msg)
+    }
+
+    pub fn trace_pending_block(
+        &self, opts: Option<GethDebugTracingOptions>,
+    ) -> Result<Vec<TraceResult>, CoreError> {
+        let opts = opts.unwrap_or_default();
+        check_tracer_opts(&opts)?;
+        let pending = self.consensus_graph().pending_block()?;
+
+        let traces = self.consensus_graph().collect_blocks_geth_trace(
+            *pending.block.block_header.parent_hash(),
+            pending.height() - 1,
+            &vec![pending.block.clone()],
+            opts,
+            None,
+        )?;
+
+        Ok(espace_trace_results(traces))
     }
 
     pub fn trace_transaction(
@@ -170,46 +258,13 @@ impl DebugApi {
     ) -> Result<GethTrace, CoreError> {
         let opts = opts.unwrap_or_default();
 
-        // early return if tracer is not supported or NoopTracer is requested
-        if let Some(tracer_type) = &opts.tracer {
-            match tracer_type {
-                BuiltInTracer(builtin_tracer) => match builtin_tracer {
-                    GethDebugBuiltInTracerType::FourByteTracer => (),
-                    GethDebugBuiltInTracerType::CallTracer => {
-                        // pre check config
-                        let _ = opts
-                            .tracer_config
-                            .clone()
-                            .into_call_config()
-                            .map_err(|err| {
-                            CoreError::Msg(err.to_string())
-                        })?;
-                        ()
-                    }
-                    GethDebugBuiltInTracerType::PreStateTracer => {
-                        // pre check config
-                        let _ = opts
-                            .tracer_config
-                            .clone()
-                            .into_pre_state_config()
-                            .map_err(|err| CoreError::Msg(err.to_string()))?;
-                        ()
-                    }
-                    GethDebugBuiltInTracerType::NoopTracer => {
-                        return Ok(GethTrace::NoopTracer(NoopFrame::default()))
-                    }
-                    GethDebugBuiltInTracerType::MuxTracer => {
-                        return Err(CoreError::Msg("not supported".to_string()))
-                    }
-                    GethDebugBuiltInTracerType::FlatCallTracer => {
-                        return Err(CoreError::Msg("not supported".to_string()))
-                    }
-                },
-                JsTracer(_) => {
-                    return Err(CoreError::Msg("not supported".to_string()))
-                }
-            }
+        // early return if NoopTracer is requested
+        if let Some(BuiltInTracer(GethDebugBuiltInTracerType::NoopTracer)) =
+            &opts.tracer
+        {
+            return Ok(GethTrace::NoopTracer(NoopFrame::default()));
         }
+        check_tracer_opts(&opts)?;
 
         let tx_index = self
             .consensus
@@ -235,10 +290,361 @@ impl DebugApi {
             .map(|val| val.trace)
             .ok_or(CoreError::Msg("trace generation failed".to_string()))?;
 
-        Ok(trace)
+        trace.map_err(CoreError::Msg)
+    }
+}
+
+fn espace_trace_results(traces: Vec<GethTraceWithHash>) -> Vec<TraceResult> {
+    traces
+        .into_iter()
+        .filter(|val| val.space == Space::Ethereum)
+        .map(|val| {
+            let tx_hash = Some(to_alloy_h256(val.tx_hash));
+            match val.trace {
+                Ok(result) => TraceResult::Success { result, tx_hash },
+                Err(error) => TraceResult::Error { error, tx_hash },
+            }
+        })
+        .collect()
+}
+
+impl DebugApi {
+    /// Applies `f` on the phantom block of `block`, which may be the pending
+    /// block.
+    fn with_phantom_block<T>(
+        &self, block: BlockId, f: impl FnOnce(&PhantomBlock) -> T,
+    ) -> Result<T, CoreError> {
+        let phantom_block = match block {
+            BlockId::Pending => {
+                let pending = self.consensus_graph().pending_block()?;
+                return Ok(f(&pending.phantom_block));
+            }
+            BlockId::Hash { hash, .. } => {
+                self.consensus_graph().get_phantom_block_by_hash(
+                    &hash, false, /* include_traces */
+                )?
+            }
+            _ => {
+                let epoch_num = self
+                    .get_block_epoch_num(block)
+                    .map_err(|err| CoreError::Msg(err))?;
+                self.consensus_graph().get_phantom_block_by_number(
+                    EpochNumber::Number(epoch_num),
+                    None,
+                    false, /* include_traces */
+                )?
+            }
+        };
+
+        match phantom_block {
+            Some(pb) => Ok(f(&pb)),
+            None => Err(CoreError::Msg("block not found".to_string())),
+        }
+    }
+
+    pub fn raw_block(&self, block: BlockId) -> Result<Bytes, CoreError> {
+        self.with_phantom_block(block, |pb| {
+            let mut s = RlpStream::new_list(3);
+            s.append(&Header::from_phantom(pb));
+            s.begin_list(pb.transactions.len());
+            for tx in &pb.transactions {
+                // Typed transactions are wrapped as RLP strings.
+                s.append(&tx.transaction);
+            }
+            s.begin_list(0);
+            s.out().into()
+        })
+    }
+
+    pub fn raw_receipts(
+        &self, block: BlockId,
+    ) -> Result<Vec<Bytes>, CoreError> {
+        self.with_phantom_block(block, |pb| {
+            zip(&pb.transactions, &pb.receipts)
+                .map(|(tx, receipt)| encode_eth_receipt(tx, receipt).into())
+                .collect()
+        })
+    }
+
+    pub fn raw_transaction(
+        &self, hash: H256,
+    ) -> Result<Option<Bytes>, CoreError> {
+        let tx_index = match self
+            .consensus
+            .data_manager()
+            .transaction_index_by_hash(&hash, false /* update_cache */)
+        {
+            Some(tx_index) => tx_index,
+            None => return Ok(None),
+        };
+        let epoch_num =
+            match self.consensus.get_block_epoch_number(&tx_index.block_hash) {
+                Some(epoch_num) => epoch_num,
+                None => return Ok(None),
+            };
+
+        self.with_phantom_block(BlockId::Num(epoch_num), |pb| {
+            let (tx, receipt) = zip(&pb.transactions, &pb.receipts)
+                .find(|(tx, _)| tx.hash() == hash)?;
+            // A skipped transaction is not available to clients if accessed
+            // by its hash.
+            if receipt.outcome_status == TransactionStatus::Skipped {
+                return None;
+            }
+            Some(encode_eth_transaction(tx).into())
+        })
+    }
+
+    pub fn storage_range_at(
+        &self, block_hash: H256, tx_index: usize, address: Address,
+        key_start: H256, max_result: usize,
+    ) -> Result<StorageRangeResult, CoreError> {
+        let epoch_num = self
+            .consensus_graph()
+            .get_block_epoch_number_with_pivot_check(&block_hash, true)?;
+        // The states in the middle of a block are not kept, so only the
+        // states before and after the block can be read.
+        let epoch_num = if tx_index == 0 {
+            epoch_num
+                .checked_sub(1)
+                .ok_or("genesis has no parent state")?
+        } else {
+            let tx_count = self
+                .with_phantom_block(BlockId::Num(epoch_num), |pb| {
+                    pb.transactions.len()
+                })?;
+            if tx_index != tx_count {
+                return Err(CoreError::InvalidParam(
+                    "txIndex".into(),
+                    format!("only 0 and {} are supported", tx_count).into(),
+                ));
+            }
+            epoch_num
+        };
+
+        let mut state_db =
+            self.consensus_graph().get_eth_state_db_by_epoch_number(
+                EpochNumber::Number(epoch_num),
+                "blockHash",
+            )?;
+        let start_key =
+            StorageKey::new_storage_key(&address, key_start.as_ref())
+                .with_evm_space()
+                .to_key_bytes();
+        let (kvs, next_key) = state_db.read_range(
+            StorageKey::new_storage_root_key(&address).with_evm_space(),
+            &start_key,
+            max_result.min(STORAGE_RANGE_MAX_RESULTS),
+            false, /* only_account_key */
+        )?;
+
+        let mut storage = BTreeMap::new();
+        for (key, value) in kvs {
+            let Some(key) = storage_slot(&key) else {
+                continue;
+            };
+            let value: StorageValue = rlp::decode(&value)
+                .map_err(|err| CoreError::Msg(err.to_string()))?;
+            storage.insert(
+                keccak(key),
+                StorageEntry {
+                    key: Some(key),
+                    value: H256::from_uint(&value.value),
+                },
+            );
+        }
+
+        Ok(StorageRangeResult {
+            storage,
+            next_key: next_key.and_then(|key| storage_slot(&key)),
+        })
+    }
+
+    pub fn account_range(
+        &self, block: BlockId, start: Bytes, max_results: usize, no_code: bool,
+        no_storage: bool,
+    ) -> Result<StateDump, CoreError> {
+        let epoch_num = self
+            .get_block_epoch_num(block)
+            .map_err(|err| CoreError::Msg(err))?;
+        let pivot_hash = self
+            .consensus_graph()
+            .get_hash_from_epoch_number(EpochNumber::Number(epoch_num))?;
+        let root = *self
+            .consensus
+            .data_manager()
+            .block_header_by_hash(&pivot_hash)
+            .ok_or("block not found")?
+            .deferred_state_root();
+
+        let mut state_db =
+            self.consensus_graph().get_eth_state_db_by_epoch_number(
+                EpochNumber::Number(epoch_num),
+                "block",
+            )?;
+        let max_results = if max_results == 0 {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results.min(ACCOUNT_RANGE_MAX_RESULTS)
+        };
+        let (kvs, mut next) = state_db.read_range(
+            StorageKey::EmptyKey.with_evm_space(),
+            &start.0,
+            max_results,
+            true, /* only_account_key */
+        )?;
+
+        let mut accounts = BTreeMap::new();
+        let mut storage_slots_left = ACCOUNT_RANGE_MAX_STORAGE_SLOTS;
+        for (key, value) in kvs {
+            let address = Address::from_slice(&key[..Address::len_bytes()]);
+            let account = Account::new_from_rlp(address, &Rlp::new(&value))
+                .map_err(|err| CoreError::Msg(err.to_string()))?;
+            let max_storage_slots = (!no_storage).then_some(storage_slots_left);
+            let Some(state) = account_state(
+                &mut state_db,
+                &account,
+                no_code,
+                max_storage_slots,
+            )?
+            else {
+                if accounts.is_empty() {
+                    return Err(CoreError::Msg(format!(
+                        "the storage of {:?} has more than {} slots, read it \
+                         by debug_storageRangeAt",
+                        address, ACCOUNT_RANGE_MAX_STORAGE_SLOTS
+                    )));
+                }
+                next = Some(key);
+                break;
+            };
+            storage_slots_left -= state.storage.as_ref().map_or(0, |s| s.len());
+            accounts.insert(address, state);
+        }
+
+        Ok(StateDump {
+            root,
+            accounts,
+            next: next.map(|key| key[..Address::len_bytes()].to_vec().into()),
+        })
+    }
+}
+
+/// Returns the slot of an eSpace storage key, or `None` for other keys.
+fn storage_slot(key: &[u8]) -> Option<H256> {
+    match StorageKeyWithSpace::from_key_bytes::<SkipInputCheck>(key).key {
+        StorageKey::StorageKey { storage_key, .. } => {
+            Some(H256::from_slice(storage_key))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the state of the account with at most `max_storage_slots` storage
+/// slots, or `None` if the account has more slots. The storage is omitted if
+/// `max_storage_slots` is `None`.
+fn account_state(
+    state_db: &mut StateDb, account: &Account, no_code: bool,
+    max_storage_slots: Option<usize>,
+) -> Result<Option<AccountState>, CoreError> {
+    let address = account.address();
+    let is_contract = account.code_hash != KECCAK_EMPTY;
+
+    let code = if is_contract && !no_code {
+        state_db
+            .get_code(address, &account.code_hash)?
+            .map(|code_info| code_info.code.to_vec().into())
+    } else {
+        None
+    };
+
+    let storage = if let Some(max_storage_slots) =
+        max_storage_slots.filter(|_| is_contract)
+    {
+        let (kvs, next_key) = state_db.read_range(
+            StorageKey::new_storage_root_key(&address.address).with_evm_space(),
+            &[],
+            max_storage_slots,
+            false, /* only_account_key */
+        )?;
+        if next_key.is_some() {
+            return Ok(None);
+        }
+        let mut storage = BTreeMap::new();
+        for (key, value) in kvs {
+            let Some(key) = storage_slot(&key) else {
+                continue;
+            };
+            let value: StorageValue = rlp::decode(&value)
+                .map_err(|err| CoreError::Msg(err.to_string()))?;
+            storage.insert(key, value.value);
+        }
+        Some(storage)
+    } else {
+        None
+    };
+
+    let nonce = u64::try_from(account.nonce).map_err(|_| {
+        CoreError::Msg(format!(
+            "the nonce of {:?} does not fit in 64 bits",
+            address.address
+        ))
+    })?;
+
+    Ok(Some(AccountState {
+        balance: account.balance,
+        nonce,
+        // The state tree has no storage root, so a fixed value is used.
+        root: EOA_STORAGE_ROOT_H256,
+        code_hash: account.code_hash,
+        code,
+        storage,
+        address: Some(address.address),
+        address_hash: Some(keccak(address.address)),
+    }))
+}
+
+/// Checks the tracer config before replaying the transactions, so invalid
+/// options are reported as errors instead of failing in the executor.
+fn check_tracer_opts(opts: &GethDebugTracingOptions) -> Result<(), CoreError> {
+    match &opts.tracer {
+        Some(BuiltInTracer(tracer)) => {
+            check_builtin_tracer_config(*tracer, opts.tracer_config.clone())
+        }
+        Some(JsTracer(code)) => geth_tracer::JsTracer::check_opts(code, opts)
+            .map_err(|err| CoreError::Msg(err.to_string())),
+        None => Ok(()),
     }
 }
 
+fn check_builtin_tracer_config(
+    tracer: GethDebugBuiltInTracerType, config: GethDebugTracerConfig,
+) -> Result<(), CoreError> {
+    use GethDebugBuiltInTracerType::*;
+
+    let res = match tracer {
+        FourByteTracer | NoopTracer => Ok(()),
+        CallTracer => config.into_call_config().map(|_| ()),
+        PreStateTracer => config.into_pre_state_config().map(|_| ()),
+        FlatCallTracer => config.into_flat_call_config().map(|_| ()),
+        MuxTracer => {
+            let mux_config = config
+                .into_mux_config()
+                .map_err(|err| CoreError::Msg(err.to_string()))?;
+            geth_tracer::check_mux_config(&mux_config)
+                .map_err(CoreError::Msg)?;
+            for (tracer, config) in mux_config.0 {
+                check_builtin_tracer_config(
+                    tracer,
+                    config.unwrap_or_default(),
+                )?;
+            }
+            Ok(())
+        }
+    };
+    res.map_err(|err| CoreError::Msg(err.to_string()))
+}
+
 #[async_trait]
 impl DebugApiServer for DebugApi {
     async fn db_get(&self, _key: String) -> RpcResult<Option<String>> {
@@ -265,6 +671,10 @@ impl DebugApiServer for DebugApi {
     async fn debug_trace_block_by_number(
         &self, block: BlockId, opts: Option<GethDebugTracingOptions>,
     ) -> RpcResult<Vec<TraceResult>> {
+        if block == BlockId::Pending {
+            return self.trace_pending_block(opts).map_err(|e| e.into());
+        }
+
         let num = self
             .get_block_epoch_num(block)
             .map_err(|e| invalid_params_msg(&e))?;
@@ -280,6 +690,49 @@ impl DebugApiServer for DebugApi {
             .map_err(|e| e.into())
     }
 
+    async fn debug_get_raw_header(&self, block: BlockId) -> RpcResult<Bytes> {
+        self.with_phantom_block(block, |pb| {
+            Header::from_phantom(pb).rlp_bytes().into()
+        })
+        .map_err(|e| e.into())
+    }
+
+    async fn debug_get_raw_block(&self, block: BlockId) -> RpcResult<Bytes> {
+        self.raw_block(block).map_err(|e| e.into())
+    }
+
+    async fn debug_get_raw_receipts(
+        &self, block: BlockId,
+    ) -> RpcResult<Vec<Bytes>> {
+        self.raw_receipts(block).map_err(|e| e.into())
+    }
+
+    async fn debug_get_raw_transaction(
+        &self, tx_hash: H256,
+    ) -> RpcResult<Option<Bytes>> {
+        self.raw_transaction(tx_hash).map_err(|e| e.into())
+    }
+
+    async fn debug_storage_range_at(
+        &self, block_hash: H256, tx_index: usize, address: Address,
+        key_start: H256, max_result: usize,
+    ) -> RpcResult<StorageRangeResult> {
+        self.storage_range_at(
+            block_hash, tx_index, address, key_start, max_result,
+        )
+        .map_err(|e| e.into())
+    }
+
+    async fn debug_account_range(
+        &self, block: BlockId, start: Bytes, max_results: usize, no_code: bool,
+        no_storage: bool, _incompletes: bool,
+    ) -> RpcResult<StateDump> {
+        // The state is keyed by the addresses instead of their hashes, so
+        // there are no accounts with unknown addresses to include or skip.
+        self.account_range(block, start, max_results, no_code, no_storage)
+            .map_err(|e| e.into())
+    }
+
     async fn debug_block_properties(
         &self, block_number: BlockId,
     ) -> RpcResult<Option<Vec<BlockProperties>>> {
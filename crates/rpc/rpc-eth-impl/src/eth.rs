@@ -1,20 +1,29 @@
-use crate::helpers::{FeeHistoryCache, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT};
+use crate::helpers::{
+    pending_block::{pending_logs, pending_state_override},
+    FeeHistoryCache, MAX_FEE_HISTORY_CACHE_BLOCK_COUNT,
+};
 use async_trait::async_trait;
 use cfx_execute_helper::estimation::EstimateRequest;
 use cfx_executor::executive::{
     Executed, ExecutionError, ExecutionOutcome, ToRepackError, TxDropError,
 };
-use cfx_parameters::rpc::GAS_PRICE_DEFAULT_VALUE;
+use cfx_parameters::rpc::{
+    GAS_PRICE_DEFAULT_VALUE, TRANSACTIONS_BY_ADDRESS_DEFAULT_LIMIT,
+    TRANSACTIONS_BY_ADDRESS_MAX_LIMIT,
+};
+use cfx_rpc_cfx_impl::helpers::{check_accounts_limit, read_accounts};
 use cfx_rpc_cfx_types::{
     traits::BlockProvider, PhantomBlock, RpcImplConfiguration,
 };
 use cfx_rpc_eth_api::EthApiServer;
 use cfx_rpc_eth_types::{
-    AccessListResult, AccountOverride, AccountPendingTransactions, Block,
-    BlockId, BlockOverrides, Bundle, Error, EthCallResponse, EthRpcLogFilter,
-    EthRpcLogFilter as Filter, EvmOverrides, FeeHistory, Header, Log, LogData,
-    Receipt, RpcStateOverride, SimulatePayload, SimulatedBlock, StateContext,
-    SyncInfo, SyncStatus, Transaction, TransactionRequest,
+    AccessListResult, Account as RpcAccount, AccountOverride,
+    AccountPendingTransactions, AccountProof, Block, BlockId, BlockOverrides,
+    Bundle, Error, EthCallResponse, EthRpcLogFilter, EthRpcLogFilter as Filter,
+    EvmOverrides, FeeHistory, Header, Log, LogData, Receipt, RpcStateOverride,
+    SimulatePayload, SimulatedBlock, StateContext, StorageProof, SyncInfo,
+    SyncStatus, Transaction, TransactionRequest, TransactionsByAddress,
+    EOA_STORAGE_ROOT_H256,
 };
 use cfx_rpc_primitives::{Bytes, Index, U64 as HexU64};
 use cfx_rpc_utils::{
@@ -25,16 +34,20 @@ use cfx_rpc_utils::{
             unknown_block,
         },
         jsonrpsee_error_helpers::{
-            internal_error, internal_error_with_data, invalid_input_rpc_err,
-            invalid_params, invalid_params_rpc_err,
+            account_error, encoding_error, internal_error,
+            internal_error_with_data, invalid_input_rpc_err, invalid_params,
+            invalid_params_rpc_err,
         },
     },
     helpers::SpawnBlocking,
 };
 use cfx_statedb::StateDbExt;
+use cfx_storage::state::{StateDbGetOriginalMethods, StateTrait};
 use cfx_tasks::{TaskExecutor, TaskSpawner};
+use cfx_typed_data::{Standard, TypedData};
 use cfx_types::{
-    Address, AddressSpaceUtil, BigEndianHash, Space, H160, H256, H64, U256, U64,
+    Address, AddressSpaceUtil, BigEndianHash, Space, H160, H256, H520, H64,
+    U256, U64,
 };
 use cfx_util_macros::bail;
 use cfx_vm_types::Error as VmError;
@@ -43,15 +56,19 @@ use cfxcore::{
     ConsensusGraph, SharedConsensusGraph, SharedSynchronizationService,
     SharedTransactionPool,
 };
+use cfxcore_accounts::AccountProvider;
 use cfxcore_errors::ProviderBlockError;
 use jsonrpsee::{core::RpcResult, types::ErrorObjectOwned as RpcError};
+use keccak_hash::{keccak, KECCAK_EMPTY};
 use primitives::{
-    filter::LogFilter, receipt::EVM_SPACE_SUCCESS, Action, EpochNumber,
-    StorageKey, StorageValue, TransactionStatus, TransactionWithSignature,
+    filter::LogFilter, receipt::EVM_SPACE_SUCCESS, Account, Action,
+    EpochNumber, MptValue, StorageKey, StorageRoot, StorageValue,
+    TransactionStatus, TransactionWithSignature,
 };
+use rlp::Rlp;
 use rustc_hex::ToHex;
 use solidity_abi::string_revert_reason_decode;
-use std::{collections::HashMap, future::Future};
+use std::{collections::HashMap, future::Future, iter::zip, sync::Arc};
 
 type BlockNumber = BlockId;
 type BlockNumberOrTag = BlockId;
@@ -67,6 +84,7 @@ pub struct EthApi {
     tx_pool: SharedTransactionPool,
     fee_history_cache: FeeHistoryCache,
     executor: TaskExecutor,
+    accounts: Option<Arc<AccountProvider>>,
 }
 
 impl EthApi {
@@ -82,9 +100,16 @@ impl EthApi {
             tx_pool,
             fee_history_cache: FeeHistoryCache::new(),
             executor,
+            accounts: None,
         }
     }
 
+    /// Enables signing with the local accounts.
+    pub fn with_accounts(mut self, accounts: Arc<AccountProvider>) -> Self {
+        self.accounts = Some(accounts);
+        self
+    }
+
     pub fn consensus_graph(&self) -> &ConsensusGraph { &self.consensus }
 
     pub fn tx_pool(&self) -> &SharedTransactionPool { &self.tx_pool }
@@ -189,12 +214,29 @@ impl EthApi {
             }
             None => None,
         };
+        // A call on the pending block is executed on the best executed state
+        // with the changes of the pending block applied.
+        let (epoch, state_overrides) = match block_number_or_hash
+            .unwrap_or_default()
+        {
+            BlockNumber::Pending => {
+                let pending = consensus_graph.pending_block()?;
+                let state_overrides =
+                    pending_state_override(&pending, state_overrides).map_err(
+                        |err| CoreError::InvalidParam(err, Default::default()),
+                    )?;
+                (
+                    EpochNumber::Number(pending.height() - 1),
+                    Some(state_overrides),
+                )
+            }
+            block_number => (
+                self.convert_block_number_to_epoch_number(block_number)?,
+                state_overrides,
+            ),
+        };
         let evm_overrides = EvmOverrides::new(state_overrides, block_overrides);
 
-        let epoch = self.convert_block_number_to_epoch_number(
-            block_number_or_hash.unwrap_or_default(),
-        )?;
-
         // if gas_price and gas is zero, it is considered as not set
         request.unset_zero_gas_and_price();
 
@@ -565,8 +607,18 @@ impl EthApi {
     pub fn user_balance(
         &self, address: H160, num: Option<BlockNumber>,
     ) -> CoreResult<U256> {
-        let epoch_num =
-            self.convert_block_number_to_epoch_number(num.unwrap_or_default())?;
+        let epoch_num = match num.unwrap_or_default() {
+            BlockNumber::Pending => {
+                let pending = self.consensus.pending_block()?;
+                if let Some(balance) =
+                    pending.balance(&address.with_evm_space())
+                {
+                    return Ok(balance);
+                }
+                EpochNumber::Number(pending.height() - 1)
+            }
+            num => self.convert_block_number_to_epoch_number(num)?,
+        };
         let state_db = self
             .consensus
             .get_eth_state_db_by_epoch_number(epoch_num, "num")?;
@@ -577,6 +629,42 @@ impl EthApi {
         Ok(acc.map_or(U256::zero(), |acc| acc.balance).into())
     }
 
+    pub fn accounts_by_address(
+        &self, addresses: &[H160], block_num: Option<BlockNumber>,
+    ) -> CoreResult<Vec<RpcAccount>> {
+        check_accounts_limit(addresses.len())?;
+        let epoch_num = self.convert_block_number_to_epoch_number(
+            block_num.unwrap_or_default(),
+        )?;
+        // Fix the height first so that the accounts and their storage roots
+        // are read from the same state.
+        let height = self.consensus.get_height_from_epoch_number(epoch_num)?;
+        let state_db = self.consensus.get_eth_state_db_by_epoch_number(
+            EpochNumber::Number(height),
+            "num",
+        )?;
+        let state = self.consensus.get_storage_state_by_epoch_number(
+            EpochNumber::Number(height),
+            "num",
+        )?;
+        let evm_addresses: Vec<_> = addresses
+            .iter()
+            .map(|address| address.with_evm_space())
+            .collect();
+
+        zip(read_accounts(&state_db, &evm_addresses)?, &evm_addresses)
+            .map(|(account, address)| -> CoreResult<RpcAccount> {
+                let storage_root = state.get_original_storage_root(address)?;
+                Ok(RpcAccount {
+                    balance: account.balance,
+                    nonce: account.nonce,
+                    code_hash: account.code_hash,
+                    storage_root: storage_root_hash(&storage_root),
+                })
+            })
+            .collect()
+    }
+
     pub fn storage_at(
         &self, address: H160, position: U256, block_num: Option<BlockNumber>,
     ) -> CoreResult<H256> {
@@ -604,6 +692,88 @@ impl EthApi {
         )
     }
 
+    pub fn account_proof(
+        &self, address: H160, keys: Vec<JsonStorageKey>,
+        block_num: Option<BlockNumber>,
+    ) -> CoreResult<AccountProof> {
+        let epoch_num = self.convert_block_number_to_epoch_number(
+            block_num.unwrap_or_default(),
+        )?;
+        // Fix the height first so that the current and previous snapshot
+        // state roots belong to the same pivot chain view.
+        let height = self.consensus.get_height_from_epoch_number(epoch_num)?;
+
+        let state = self.consensus.get_storage_state_by_epoch_number(
+            EpochNumber::Number(height),
+            "block_number",
+        )?;
+        let state_root = state.get_state_root()?.state_root;
+
+        let snapshot_epoch_count =
+            self.consensus.data_manager().get_snapshot_epoch_count() as u64;
+        let prev_snapshot_state_root = if height > snapshot_epoch_count {
+            let prev_pivot = self
+                .consensus
+                .get_block_hashes_by_epoch(EpochNumber::Number(
+                    height - snapshot_epoch_count,
+                ))?
+                .last()
+                .cloned()
+                .ok_or("Pivot block of previous snapshot period not found")?;
+            let commitment = self
+                .consensus
+                .data_manager()
+                .get_epoch_execution_commitment_with_db(&prev_pivot)
+                .ok_or("State root of previous snapshot period not found")?;
+            Some(commitment.state_root_with_aux_info.state_root)
+        } else {
+            None
+        };
+
+        let (maybe_account, account_proof) = state
+            .get_original_raw_with_proof(
+                StorageKey::new_account_key(&address).with_evm_space(),
+            )?;
+        let account = match maybe_account {
+            Some(raw) => {
+                Some(Account::new_from_rlp(address, &Rlp::new(&raw)).map_err(
+                    |e| CoreError::from(format!("Invalid account: {:?}", e)),
+                )?)
+            }
+            None => None,
+        };
+
+        let mut storage_proof = Vec::with_capacity(keys.len());
+        for key in keys {
+            let position: H256 = H256::from_uint(&key);
+            let (maybe_value, proof) = state.get_original_raw_with_proof(
+                StorageKey::new_storage_key(&address, position.as_ref())
+                    .with_evm_space(),
+            )?;
+            let value = match maybe_value {
+                Some(raw) => Rlp::new(&raw).as_val::<StorageValue>()?.value,
+                None => U256::zero(),
+            };
+            storage_proof.push(StorageProof {
+                key,
+                value,
+                proof: proof.into(),
+            });
+        }
+
+        Ok(AccountProof {
+            address,
+            balance: account.as_ref().map_or(U256::zero(), |a| a.balance),
+            nonce: account.as_ref().map_or(U256::zero(), |a| a.nonce),
+            code_hash: account.as_ref().map_or(KECCAK_EMPTY, |a| a.code_hash),
+            account_proof: account_proof.into(),
+            storage_proof,
+            epoch_number: height.into(),
+            state_root,
+            prev_snapshot_state_root,
+        })
+    }
+
     pub fn phantom_block_by_hash(
         &self, hash: H256,
     ) -> CoreResult<Option<PhantomBlock>> {
@@ -1004,12 +1174,28 @@ impl EthApi {
     }
 
     pub fn logs(&self, filter: EthRpcLogFilter) -> CoreResult<Vec<Log>> {
+        // The logs of the pending block are appended if the range ends at
+        // the pending block, the executed epochs are skipped if it also
+        // starts there.
+        let include_pending = filter.to_block == Some(BlockNumber::Pending);
+        let only_pending =
+            include_pending && filter.from_block == Some(BlockNumber::Pending);
         let filter: LogFilter = filter.into_primitive(self)?;
 
-        let logs = self
-            .consensus_graph()
-            .logs(filter)
-            .map_err(|err| CoreError::from(err))?;
+        let mut logs: Vec<Log> = if only_pending {
+            vec![]
+        } else {
+            self.consensus_graph()
+                .logs(filter.clone())
+                .map_err(|err| CoreError::from(err))?
+                .into_iter()
+                .map(|l| Log::try_from_localized(l, self, false))
+                .collect::<Result<_, _>>()?
+        };
+        if include_pending {
+            let pending = self.consensus_graph().pending_block()?;
+            logs.extend(pending_logs(&pending, &filter));
+        }
 
         // If the results does not fit into `max_limit`, report an error
         if let Some(max_limit) = self.config.get_logs_filter_max_limit {
@@ -1018,11 +1204,7 @@ impl EthApi {
             }
         }
 
-        Ok(logs
-            .iter()
-            .cloned()
-            .map(|l| Log::try_from_localized(l, self, false))
-            .collect::<Result<_, _>>()?)
+        Ok(logs)
     }
 
     pub fn max_priority_fee_per_gas(&self) -> CoreResult<U256> {
@@ -1073,6 +1255,62 @@ impl EthApi {
             pending_count: pending_count.into(),
         })
     }
+
+    pub fn transactions_by_address(
+        &self, address: Address, cursor: Option<U64>, limit: Option<U64>,
+    ) -> CoreResult<TransactionsByAddress> {
+        let limit = limit
+            .map_or(TRANSACTIONS_BY_ADDRESS_DEFAULT_LIMIT, |limit| {
+                limit.as_usize()
+            });
+        if limit > TRANSACTIONS_BY_ADDRESS_MAX_LIMIT {
+            bail!(invalid_params(
+                "limit",
+                Some(format!(
+                    "limit should not exceed {}",
+                    TRANSACTIONS_BY_ADDRESS_MAX_LIMIT
+                ))
+            ));
+        }
+
+        let (tx_hashes, next_cursor) =
+            self.consensus_graph().get_transactions_by_address(
+                &address.with_evm_space(),
+                cursor.map(|cursor| cursor.as_u64()),
+                limit,
+            )?;
+        let mut transactions = Vec::with_capacity(tx_hashes.len());
+        for hash in tx_hashes {
+            if let Some(tx) = self.transaction_by_hash(hash)? {
+                transactions.push(tx);
+            }
+        }
+
+        Ok(TransactionsByAddress {
+            transactions,
+            next_cursor: next_cursor.map(Into::into),
+        })
+    }
+}
+
+/// The `storageRoot` of an account. The storage of an account is kept in up
+/// to three tries, whose roots are returned by `cfx_getStorageRoot`, so the
+/// hash of these roots is used. An account without storage gets the root of
+/// the empty trie, like in geth.
+fn storage_root_hash(root: &StorageRoot) -> H256 {
+    let has_storage = [&root.delta, &root.intermediate]
+        .into_iter()
+        .find_map(|value| match value {
+            MptValue::None => None,
+            MptValue::TombStone => Some(false),
+            MptValue::Some(_) => Some(true),
+        })
+        .unwrap_or(root.snapshot.is_some());
+    if has_storage {
+        keccak(rlp::encode(root))
+    } else {
+        EOA_STORAGE_ROOT_H256
+    }
 }
 
 impl SpawnBlocking for EthApi {
@@ -1227,6 +1465,17 @@ impl EthApiServer for EthApi {
         self.async_transaction_by_hash(hash).await
     }
 
+    async fn transactions_by_address(
+        &self, address: Address, cursor: Option<U64>, limit: Option<U64>,
+    ) -> RpcResult<TransactionsByAddress> {
+        self.clone()
+            .spawn_blocking_io(move |this| {
+                this.transactions_by_address(address, cursor, limit)
+                    .map_err(|err| err.into())
+            })
+            .await
+    }
+
     /// Returns information about a raw transaction by block hash and
     /// transaction index position.
     async fn raw_transaction_by_block_hash_and_index(
@@ -1413,11 +1662,21 @@ impl EthApiServer for EthApi {
 
     /// Returns the account details by specifying an address and a block
     /// number/tag
-    // async fn get_account(
-    //     &self,
-    //     address: Address,
-    //     block: BlockId,
-    // ) -> RpcResult<Option<reth_rpc_types::Account>>;
+    async fn get_account(
+        &self, address: Address, block_number: Option<BlockId>,
+    ) -> RpcResult<RpcAccount> {
+        let mut accounts = self
+            .accounts_by_address(&[address], block_number)
+            .map_err(RpcError::from)?;
+        Ok(accounts.remove(0))
+    }
+
+    async fn get_accounts(
+        &self, addresses: Vec<Address>, block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<RpcAccount>> {
+        self.accounts_by_address(&addresses, block_number)
+            .map_err(|err| err.into())
+    }
 
     /// Introduced in EIP-1559, returns suggestion for the priority for dynamic
     /// fee transactions.
@@ -1534,6 +1793,36 @@ impl EthApiServer for EthApi {
         Err(internal_error_with_data("Not implemented"))
     }
 
+    async fn sign_typed_data(
+        &self, address: Address, data: TypedData,
+    ) -> RpcResult<Bytes> {
+        let accounts = self
+            .accounts
+            .as_ref()
+            .ok_or_else(|| account_error("local accounts are not enabled"))?;
+        let hash = data
+            .signing_hash(Standard::Eip712)
+            .map_err(encoding_error)?;
+        let signature = accounts
+            .sign_evm(address, None, hash)
+            .map_err(account_error)?;
+
+        // The recovery id is offset by 27 in Ethereum signatures.
+        let mut signature = H520::from(signature);
+        signature.0[64] += 27;
+        Ok(Bytes::new(signature.0.to_vec()))
+    }
+
+    /// Returns the account and storage values of the specified account
+    /// including the Merkle-proof.
+    async fn get_proof(
+        &self, address: Address, keys: Vec<JsonStorageKey>,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<AccountProof> {
+        self.account_proof(address, keys, block_number)
+            .map_err(|err| err.into())
+    }
+
     async fn logs(&self, filter: Filter) -> RpcResult<Vec<Log>> {
         self.logs(filter).map_err(|err| err.into())
     }
@@ -18,7 +18,10 @@ use cfx_util_macros::bail;
 use cfxcore::{channel::Channel, SharedConsensusGraph, SharedTransactionPool};
 use jsonrpsee::core::RpcResult;
 use primitives::filter::LogFilter;
-use std::{collections::VecDeque, sync::Arc};
+use std::{
+    collections::{BTreeSet, VecDeque},
+    sync::Arc,
+};
 
 type PendingTransactionFilterKind = ();
 
@@ -50,12 +53,7 @@ impl EthFilterApiServer for EthFilterApi {
         let mut polls = self.inner.polls().lock();
         let epoch_number = self.inner.best_executed_epoch_number();
 
-        if filter.to_block == Some(BlockId::Pending) {
-            bail!(invalid_request_msg(
-                "Filter logs from pending blocks is not supported"
-            ))
-        }
-
+        let include_pending = filter.to_block == Some(BlockId::Pending);
         let filter: LogFilter = self.inner.into_primitive_filter(filter)?;
 
         let id = polls.create_poll(SyncPollFilter::new(PollFilter::Logs {
@@ -65,7 +63,8 @@ impl EthFilterApiServer for EthFilterApi {
                 epoch_number - 1
             },
             filter,
-            include_pending: false,
+            include_pending,
+            reported_pending_logs: BTreeSet::new(),
             previous_logs: VecDeque::with_capacity(MAX_BLOCK_HISTORY_SIZE),
             recent_reported_epochs: VecDeque::with_capacity(
                 MAX_BLOCK_HISTORY_SIZE,
@@ -164,7 +163,8 @@ impl EthFilterApiServer for EthFilterApi {
                 ref mut recent_reported_epochs,
                 ref mut previous_logs,
                 ref filter,
-                include_pending: _,
+                include_pending,
+                ref mut reported_pending_logs,
             } => {
                 let (reorg_len, epochs) =
                     self.inner.epochs_since_last_request(
@@ -212,6 +212,23 @@ impl EthFilterApiServer for EthFilterApi {
                     previous_logs.push_front(log);
                 }
 
+                // logs of the pending block not reported yet, the pending
+                // block is rebuilt on new best information
+                if include_pending {
+                    let key = |l: &Log| {
+                        let index = l.transaction_log_index.unwrap_or_default();
+                        (l.transaction_hash, index.as_u64())
+                    };
+                    let pending_logs = self.inner.pending_logs(filter)?;
+                    let keys = pending_logs.iter().map(key).collect();
+                    logs.extend(
+                        pending_logs.into_iter().filter(|l| {
+                            !reported_pending_logs.contains(&key(l))
+                        }),
+                    );
+                    *reported_pending_logs = keys;
+                }
+
                 Ok(FilterChanges::Logs(limit_logs(
                     logs,
                     self.inner.get_logs_filter_max_limit(),
@@ -221,7 +238,7 @@ impl EthFilterApiServer for EthFilterApi {
     }
 
     async fn filter_logs(&self, id: FilterId) -> RpcResult<Vec<Log>> {
-        let (filter, _) = {
+        let (filter, include_pending) = {
             let mut polls = self.inner.polls().lock();
 
             match polls.poll(&id).and_then(|f| {
@@ -240,10 +257,11 @@ impl EthFilterApiServer for EthFilterApi {
         };
 
         // retrieve logs
-        Ok(limit_logs(
-            self.inner.logs(filter)?,
-            self.inner.get_logs_filter_max_limit(),
-        ))
+        let mut logs = self.inner.logs(filter.clone())?;
+        if include_pending {
+            logs.extend(self.inner.pending_logs(&filter)?);
+        }
+        Ok(limit_logs(logs, self.inner.get_logs_filter_max_limit()))
     }
 
     async fn uninstall_filter(&self, id: FilterId) -> RpcResult<bool> {
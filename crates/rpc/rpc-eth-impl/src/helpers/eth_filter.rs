@@ -5,7 +5,10 @@ use std::{
 };
 
 use crate::{
-    helpers::{poll_filter::SyncPollFilter, poll_manager::PollManager},
+    helpers::{
+        pending_block::pending_logs, poll_filter::SyncPollFilter,
+        poll_manager::PollManager,
+    },
     traits::Filterable,
 };
 use cfx_rpc_cfx_types::traits::BlockProvider;
@@ -215,6 +218,14 @@ impl Filterable for EthFilterHelper {
             .map_err(RpcError::from)?)
     }
 
+    fn pending_logs(&self, filter: &LogFilter) -> RpcResult<Vec<Log>> {
+        let pending = self
+            .consensus_graph()
+            .pending_block()
+            .map_err(|err| CfxRpcError::from(err))?;
+        Ok(pending_logs(&pending, filter))
+    }
+
     fn logs_for_epoch(
         &self, filter: &LogFilter, epoch: (u64, Vec<H256>), removed: bool,
     ) -> RpcResult<Vec<Log>> {
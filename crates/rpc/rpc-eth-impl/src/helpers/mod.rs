@@ -1,6 +1,7 @@
 mod chain_info;
 pub mod eth_filter;
 mod fee_history_cache;
+pub mod pending_block;
 pub mod poll_filter;
 pub mod poll_manager;
 
@@ -0,0 +1,140 @@
+use cfx_parity_trace_types::{AccountDiff, Diff};
+use cfx_rpc_eth_types::{
+    AccountOverride, AccountStateOverrideMode, Log, LogData, StateOverride,
+};
+use cfx_types::{Address, Space, H256, U256};
+use cfxcore::consensus::PendingBlock;
+use primitives::filter::LogFilter;
+use std::{collections::hash_map::Entry, iter::zip};
+
+/// Returns the eSpace logs of the pending block which match the filter.
+pub fn pending_logs(pending: &PendingBlock, filter: &LogFilter) -> Vec<Log> {
+    let pb = &pending.phantom_block;
+    let mut logs = vec![];
+    let mut log_index = 0;
+
+    for (tx_index, (receipt, tx)) in
+        zip(&pb.receipts, &pb.transactions).enumerate()
+    {
+        let eth_logs =
+            receipt.logs.iter().filter(|l| l.space == Space::Ethereum);
+
+        for (tx_log_index, entry) in eth_logs.enumerate() {
+            if filter.matches(entry) {
+                logs.push(Log {
+                    inner: LogData {
+                        address: entry.address,
+                        topics: entry.topics.clone(),
+                        data: entry.data.clone().into(),
+                    },
+                    block_hash: pending.hash(),
+                    block_number: pending.height().into(),
+                    block_timestamp: Some(pending.timestamp().into()),
+                    transaction_hash: tx.hash(),
+                    transaction_index: tx_index.into(),
+                    log_index: Some(log_index.into()),
+                    transaction_log_index: Some(tx_log_index.into()),
+                    removed: false,
+                });
+            }
+            log_index += 1;
+        }
+    }
+
+    logs
+}
+
+/// Converts the eSpace account changes of the pending block to state
+/// overrides, so a call on the best executed state sees the pending state.
+/// The fields set in `overrides` take priority over the pending changes.
+///
+/// Fails if a pending nonce does not fit in a state override.
+pub fn pending_state_override(
+    pending: &PendingBlock, overrides: Option<StateOverride>,
+) -> Result<StateOverride, String> {
+    let mut state_override: StateOverride = pending
+        .state_diff
+        .clone()
+        .into_space(Space::Ethereum)
+        .map(|(address, diff)| Ok((address, account_override(address, diff)?)))
+        .collect::<Result<_, String>>()?;
+
+    for (address, account) in overrides.unwrap_or_default() {
+        match state_override.entry(address) {
+            Entry::Vacant(entry) => {
+                entry.insert(account);
+            }
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                existing.balance = account.balance.or(existing.balance);
+                existing.nonce = account.nonce.or(existing.nonce);
+                existing.code = account.code.or(existing.code.take());
+                existing.move_precompile_to = account.move_precompile_to;
+                match account.state {
+                    AccountStateOverrideMode::None => {}
+                    AccountStateOverrideMode::Diff(diff) => {
+                        match existing.state {
+                            AccountStateOverrideMode::Diff(ref mut slots)
+                            | AccountStateOverrideMode::State(ref mut slots) => {
+                                slots.extend(diff)
+                            }
+                            AccountStateOverrideMode::None => {
+                                existing.state =
+                                    AccountStateOverrideMode::Diff(diff)
+                            }
+                        }
+                    }
+                    state => existing.state = state,
+                }
+            }
+        }
+    }
+
+    Ok(state_override)
+}
+
+fn account_override(
+    address: Address, diff: AccountDiff,
+) -> Result<AccountOverride, String> {
+    // The storage of a destructed account is cleared.
+    let destructed = matches!(diff.nonce, Diff::Died(_))
+        || matches!(diff.balance, Diff::Died(_));
+
+    let storage = diff
+        .storage
+        .into_iter()
+        .filter_map(|(key, diff)| Some((key, value_after(diff)?)))
+        .map(|(key, value)| (key, value.unwrap_or_else(H256::zero)))
+        .collect();
+
+    let nonce = value_after(diff.nonce)
+        .map(|nonce| {
+            u64::try_from(nonce.unwrap_or_else(U256::zero)).map_err(|_| {
+                format!("pending nonce of {:?} exceeds u64", address)
+            })
+        })
+        .transpose()?;
+
+    Ok(AccountOverride {
+        balance: value_after(diff.balance)
+            .map(|balance| balance.unwrap_or_else(U256::zero)),
+        nonce: nonce.map(Into::into),
+        code: value_after(diff.code).map(Option::unwrap_or_default),
+        state: if destructed {
+            AccountStateOverrideMode::State(storage)
+        } else {
+            AccountStateOverrideMode::Diff(storage)
+        },
+        move_precompile_to: None,
+    })
+}
+
+/// The value after the change: `None` if unchanged, `Some(None)` if the value
+/// is removed.
+fn value_after<T>(diff: Diff<T>) -> Option<Option<T>> {
+    match diff {
+        Diff::Same => None,
+        Diff::Born(to) | Diff::Changed { to, .. } => Some(Some(to)),
+        Diff::Died(_) => Some(None),
+    }
+}
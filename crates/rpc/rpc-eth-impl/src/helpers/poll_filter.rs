@@ -63,6 +63,9 @@ pub enum PollFilter<T> {
         previous_logs: VecDeque<Vec<T>>,
         filter: LogFilter,
         include_pending: bool,
+        /// Transaction hash and log index of the pending logs already
+        /// reported.
+        reported_pending_logs: BTreeSet<(H256, u64)>,
     },
 }
 
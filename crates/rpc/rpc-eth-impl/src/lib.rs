@@ -1,3 +1,4 @@
+mod admin;
 mod debug;
 mod eth;
 mod filter;
@@ -11,6 +12,7 @@ pub mod traits;
 mod txpool;
 mod web3;
 
+pub use admin::AdminApi;
 pub use debug::DebugApi;
 pub use eth::EthApi;
 pub use filter::EthFilterApi;
@@ -8,7 +8,7 @@ use cfx_rpc_cfx_types::{traits::BlockProvider, PhantomBlock};
 use cfx_rpc_eth_api::EthPubSubApiServer;
 use cfx_rpc_eth_types::{
     eth_pubsub::{Kind as SubscriptionKind, Params, Result as PubSubResult},
-    Header, Log,
+    Header, Log, Transaction,
 };
 use cfx_tasks::TaskExecutor;
 use cfx_types::{Space, H256};
@@ -20,7 +20,8 @@ use jsonrpsee::{core::SubscriptionResult, PendingSubscriptionSink};
 use log::{debug, error, info, trace, warn};
 use parking_lot::RwLock;
 use primitives::{
-    filter::LogFilter, log_entry::LocalizedLogEntry, BlockReceipts, EpochNumber,
+    filter::LogFilter, log_entry::LocalizedLogEntry, BlockReceipts,
+    EpochNumber, SignedTransaction,
 };
 use std::{
     collections::{HashMap, VecDeque},
@@ -42,6 +43,8 @@ pub struct PubSubApi {
     head_sender: Arc<broadcast::Sender<Header>>,
     log_loop_started: Arc<RwLock<HashMap<LogFilter, bool>>>,
     log_senders: Arc<RwLock<HashMap<LogFilter, broadcast::Sender<Log>>>>,
+    pending_txs_loop_started: Arc<RwLock<bool>>,
+    pending_tx_sender: Arc<broadcast::Sender<Arc<SignedTransaction>>>,
 }
 
 impl PubSubApi {
@@ -50,6 +53,7 @@ impl PubSubApi {
         executor: TaskExecutor,
     ) -> PubSubApi {
         let (head_sender, _) = broadcast::channel(BROADCAST_CHANNEL_SIZE);
+        let (pending_tx_sender, _) = broadcast::channel(BROADCAST_CHANNEL_SIZE);
         let log_senders = Arc::new(RwLock::new(HashMap::new()));
         let chain_data_provider =
             Arc::new(ChainDataProvider::new(consensus.clone()));
@@ -62,6 +66,8 @@ impl PubSubApi {
             log_senders,
             chain_data_provider,
             log_loop_started: Arc::new(RwLock::new(HashMap::new())),
+            pending_txs_loop_started: Arc::new(RwLock::new(false)),
+            pending_tx_sender: Arc::new(pending_tx_sender),
         }
     }
 
@@ -103,6 +109,49 @@ impl PubSubApi {
             .map(|item| item.expect("should not be an error"))
     }
 
+    fn new_pending_transactions_stream(
+        &self,
+    ) -> impl Stream<Item = Arc<SignedTransaction>> {
+        let receiver = self.pending_tx_sender.subscribe();
+        BroadcastStream::new(receiver)
+            .filter(|item| futures::future::ready(item.is_ok()))
+            .map(|item| item.expect("should not be an error"))
+    }
+
+    fn start_pending_transactions_loop(&self) {
+        let mut loop_started = self.pending_txs_loop_started.write();
+        if *loop_started {
+            return;
+        }
+        *loop_started = true;
+
+        debug!("async start_pending_transactions_loop");
+
+        // subscribe to the `new_pending_transactions` channel
+        let notifications = self.notifications.clone();
+        let mut receiver = notifications.new_pending_transactions.subscribe();
+        let pending_tx_sender = self.pending_tx_sender.clone();
+        let pending_txs_loop_started = self.pending_txs_loop_started.clone();
+
+        let fut = async move {
+            while let Some(txs) = receiver.recv().await {
+                for tx in txs.iter() {
+                    if pending_tx_sender.send(tx.clone()).is_err() {
+                        // all the subscriptions are closed, stop the loop
+                        let mut loop_started = pending_txs_loop_started.write();
+                        *loop_started = false;
+                        notifications
+                            .new_pending_transactions
+                            .unsubscribe(receiver.id);
+                        return;
+                    }
+                }
+            }
+        };
+
+        self.executor.spawn(fut);
+    }
+
     fn start_heads_loop(&self) {
         let mut loop_started = self.heads_loop_started.write();
         if *loop_started {
@@ -193,25 +242,13 @@ impl PubSubApi {
                     debug!("pivot chain reorg: {} -> {}", last_epoch, epoch.0);
                     assert!(epoch.0 > 0, "Unexpected epoch number received.");
 
-                    let mut reverted = vec![];
-                    while let Some(e) = epochs.back() {
-                        if e.0 >= epoch.0 {
-                            reverted.push(epochs.pop_back().unwrap());
-                        } else {
-                            break;
-                        }
-                    }
-
-                    for (_, _, logs) in reverted.into_iter() {
-                        for mut log in logs.into_iter() {
-                            log.removed = true;
-                            // send removed logs
-                            let send_res = tx.send(log);
-                            if send_res.is_err() {
-                                let mut loop_started = loop_started.write();
-                                loop_started.remove(&filter);
-                                return;
-                            }
+                    for log in revert_epochs(&mut epochs, epoch.0) {
+                        // send removed logs
+                        let send_res = tx.send(log);
+                        if send_res.is_err() {
+                            let mut loop_started = loop_started.write();
+                            loop_started.remove(&filter);
+                            return;
                         }
                     }
                 }
@@ -309,6 +346,30 @@ impl EthPubSubApiServer for PubSubApi {
                 self.start_logs_loop(filter);
                 Ok(())
             }
+            (SubscriptionKind::NewPendingTransactions, params) => {
+                let full_transactions = match params {
+                    None | Some(Params::None) => false,
+                    Some(Params::FullTransactions(full)) => full,
+                    Some(Params::Logs(_)) => {
+                        return Err("Invalid params, expected a boolean \
+                            for fullTransactions"
+                            .into())
+                    }
+                };
+
+                let sink = pending.accept().await?;
+                let stream =
+                    self.new_pending_transactions_stream().map(move |tx| {
+                        pending_transaction_result(&tx, full_transactions)
+                    });
+                self.executor.spawn(async move {
+                    let _ = pipe_from_stream(sink, stream).await;
+                });
+
+                // start the pending transaction loop
+                self.start_pending_transactions_loop();
+                Ok(())
+            }
             (_, _) => {
                 // reject
                 Err("Not supported".into())
@@ -317,6 +378,42 @@ impl EthPubSubApiServer for PubSubApi {
     }
 }
 
+/// Removes the epochs from `epoch` on and returns their logs marked as
+/// removed, in the reverse order of emission so that subscribers can unwind
+/// them like a stack.
+fn revert_epochs(
+    epochs: &mut VecDeque<(u64, Vec<H256>, Vec<Log>)>, epoch: u64,
+) -> Vec<Log> {
+    let mut removed = vec![];
+    while let Some(e) = epochs.back() {
+        if e.0 < epoch {
+            break;
+        }
+        let (_, _, logs) = epochs.pop_back().unwrap();
+        removed.extend(logs.into_iter().rev().map(|mut log| {
+            log.removed = true;
+            log
+        }));
+    }
+    removed
+}
+
+/// The notification of a pending transaction, which is the full transaction
+/// if `full_transactions` is set, otherwise its hash.
+fn pending_transaction_result(
+    tx: &SignedTransaction, full_transactions: bool,
+) -> PubSubResult {
+    if full_transactions {
+        PubSubResult::FullTransaction(Transaction::from_signed(
+            tx,
+            (None, None, None),
+            (None, None),
+        ))
+    } else {
+        PubSubResult::TransactionHash(tx.hash())
+    }
+}
+
 pub struct ChainDataProvider {
     consensus: SharedConsensusGraph,
     data_man: Arc<BlockDataManager>,
@@ -543,3 +640,95 @@ impl BlockProvider for &ChainDataProvider {
             .map_err(|e| e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_rpc_eth_types::LogData;
+    use cfx_types::{Address, AddressSpaceUtil, U256};
+    use primitives::{transaction::Eip155Transaction, Action};
+
+    fn log(epoch: u64, index: u64) -> Log {
+        Log {
+            inner: LogData {
+                address: Address::zero(),
+                topics: vec![],
+                data: Default::default(),
+            },
+            block_hash: H256::from_low_u64_be(epoch),
+            block_number: epoch.into(),
+            transaction_hash: H256::zero(),
+            transaction_index: U256::zero(),
+            log_index: Some(index.into()),
+            transaction_log_index: Some(index.into()),
+            block_timestamp: None,
+            removed: false,
+        }
+    }
+
+    fn epochs(logs: &[(u64, u64)]) -> VecDeque<(u64, Vec<H256>, Vec<Log>)> {
+        logs.iter()
+            .map(|&(epoch, count)| {
+                let hashes = vec![H256::from_low_u64_be(epoch)];
+                (epoch, hashes, (0..count).map(|i| log(epoch, i)).collect())
+            })
+            .collect()
+    }
+
+    fn positions(logs: &[Log]) -> Vec<(u64, u64)> {
+        logs.iter()
+            .map(|log| {
+                (log.block_number.as_u64(), log.log_index.unwrap().as_u64())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_revert_epochs() {
+        let mut kept = epochs(&[(1, 1), (2, 2), (3, 0), (4, 2)]);
+
+        let removed = revert_epochs(&mut kept, 2);
+        // the latest epoch first, and the latest log of each epoch first
+        assert_eq!(positions(&removed), [(4, 1), (4, 0), (2, 1), (2, 0)]);
+        assert!(removed.iter().all(|log| log.removed));
+        assert_eq!(kept.len(), 1);
+        assert!(!kept[0].2[0].removed);
+
+        // epochs after the last one are not reverted
+        assert!(revert_epochs(&mut kept, 2).is_empty());
+        assert_eq!(kept.len(), 1);
+    }
+
+    #[test]
+    fn test_pending_transaction_result() {
+        let from = Address::from_low_u64_be(1);
+        let tx = Eip155Transaction {
+            nonce: 3.into(),
+            gas_price: 1.into(),
+            gas: 21000.into(),
+            action: Action::Call(Address::from_low_u64_be(2)),
+            value: 10.into(),
+            chain_id: Some(1),
+            data: vec![],
+        }
+        .fake_sign_rpc(from.with_evm_space());
+
+        assert_eq!(
+            pending_transaction_result(&tx, false),
+            PubSubResult::TransactionHash(tx.hash())
+        );
+        match pending_transaction_result(&tx, true) {
+            PubSubResult::FullTransaction(full) => {
+                assert_eq!(full.hash, tx.hash());
+                assert_eq!(full.from, from);
+                assert_eq!(full.nonce, 3.into());
+                assert_eq!(full.value, 10.into());
+                // pending transactions are not in a block yet
+                assert_eq!(full.block_hash, None);
+                assert_eq!(full.block_number, None);
+                assert_eq!(full.transaction_index, None);
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+}
@@ -1,35 +1,50 @@
 use std::vec;
 
 use cfx_addr::Network;
-use cfx_parity_trace_types::Action;
+use cfx_execute_helper::estimation::EstimateRequest;
+use cfx_parity_trace_types::{
+    Action, TraceOptions, TransactionExecTraces, TransactionReplayTraces,
+};
 use cfx_rpc_cfx_impl::TraceHandler;
-use cfx_rpc_cfx_types::PhantomBlock;
+use cfx_rpc_cfx_types::{
+    trace_replay::{StateDiff, VmTrace},
+    PhantomBlock,
+};
 use cfx_rpc_common_impl::trace::{
     into_eth_localized_traces, primitive_traces_to_eth_localized_traces,
 };
 use cfx_rpc_eth_api::TraceApiServer;
 use cfx_rpc_eth_types::{
     trace::{LocalizedSetAuthTrace, LocalizedTrace as EthLocalizedTrace},
-    BlockId, Index, LocalizedTrace, TraceFilter,
+    BlockId, Index, LocalizedTrace, Trace, TraceFilter, TraceResults,
+    TraceResultsWithTransactionHash, TraceType, TransactionRequest,
 };
 use cfx_rpc_utils::error::{
     jsonrpc_error_helpers::error_object_owned_to_jsonrpc_error,
     jsonrpsee_error_helpers::{internal_error, invalid_params_rpc_err},
 };
-use cfx_types::H256;
+use cfx_types::{AddressSpaceUtil, Space, H256, U256};
 use cfx_util_macros::unwrap_option_or_return_result_none as unwrap_or_return;
 use cfxcore::{errors::Result as CoreResult, SharedConsensusGraph};
 use jsonrpsee::{core::RpcResult, types::ErrorObjectOwned};
 use log::warn;
-use primitives::EpochNumber;
+use primitives::{BlockHashOrEpochNumber, EpochNumber};
 pub struct TraceApi {
     trace_handler: TraceHandler,
+    max_estimation_gas_limit: Option<U256>,
 }
 
 impl TraceApi {
-    pub fn new(consensus: SharedConsensusGraph, network: Network) -> TraceApi {
-        let trace_handler = TraceHandler::new(network, consensus);
-        TraceApi { trace_handler }
+    pub fn new(
+        consensus: SharedConsensusGraph, network: Network,
+        max_estimation_gas_limit: Option<U256>,
+    ) -> TraceApi {
+        let trace_handler =
+            TraceHandler::new(network, consensus, max_estimation_gas_limit);
+        TraceApi {
+            trace_handler,
+            max_estimation_gas_limit,
+        }
     }
 
     pub fn get_block(
@@ -202,6 +217,134 @@ impl TraceApi {
 
         Ok(Some(eth_traces))
     }
+
+    pub fn trace_call(
+        &self, mut request: TransactionRequest, trace_types: Vec<TraceType>,
+        block_number: Option<BlockId>,
+    ) -> CoreResult<TraceResults> {
+        let consensus_graph = self.trace_handler.consensus_graph();
+        let epoch_num =
+            self.get_block_epoch_num(block_number.unwrap_or_default())?;
+
+        // The transaction is executed with the checks of `eth_call`. Like
+        // parity, the sender defaults to the zero address, which is not
+        // charged for gas.
+        let from = request.from.unwrap_or_default();
+        request.unset_zero_gas_and_price();
+        let estimate_request = EstimateRequest {
+            has_sender: request.from.is_some(),
+            has_gas_limit: request.gas.is_some(),
+            has_gas_price: request.has_gas_price(),
+            has_nonce: request.nonce.is_some(),
+            has_storage_limit: false,
+        };
+        if request.nonce.is_none() {
+            request.nonce = Some(consensus_graph.next_nonce(
+                from.with_evm_space(),
+                BlockHashOrEpochNumber::EpochNumber(EpochNumber::Number(
+                    epoch_num,
+                )),
+                "num",
+            )?);
+        }
+
+        let chain_id = consensus_graph.best_chain_id();
+        let signed_tx = request.sign_call(
+            chain_id.in_evm_space(),
+            self.max_estimation_gas_limit,
+        )?;
+        let traces = consensus_graph.collect_call_parity_trace(
+            epoch_num,
+            signed_tx,
+            estimate_request,
+            TraceOptions::from_trace_types(&trace_types),
+        )?;
+        Self::to_eth_trace_results(traces)
+    }
+
+    pub fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> CoreResult<Option<TraceResults>> {
+        let traces = self.trace_handler.replay_transaction_primitive_impl(
+            tx_hash,
+            Space::Ethereum,
+            TraceOptions::from_trace_types(&trace_types),
+        )?;
+
+        unwrap_or_return!(traces);
+
+        Ok(Some(Self::to_eth_trace_results(traces)?))
+    }
+
+    pub fn replay_block_transactions(
+        &self, block_number: BlockId, trace_types: Vec<TraceType>,
+    ) -> CoreResult<Vec<TraceResultsWithTransactionHash>> {
+        let epoch_num = self.get_block_epoch_num(block_number)?;
+        self.trace_handler
+            .replay_epoch_primitive_impl(
+                epoch_num,
+                Space::Ethereum,
+                TraceOptions::from_trace_types(&trace_types),
+            )?
+            .into_iter()
+            .map(|traces| {
+                let transaction_hash = traces.tx_hash;
+                Ok(TraceResultsWithTransactionHash {
+                    results: Self::to_eth_trace_results(traces)?,
+                    transaction_hash,
+                })
+            })
+            .collect()
+    }
+
+    fn get_block_epoch_num(&self, block: BlockId) -> CoreResult<u64> {
+        let consensus_graph = self.trace_handler.consensus_graph();
+        match block {
+            BlockId::Hash { hash, .. } => consensus_graph
+                .get_block_epoch_number_with_pivot_check(
+                    &hash, true, /* require_pivot */
+                ),
+            _ => Ok(consensus_graph
+                .get_height_from_epoch_number(block.try_into()?)?),
+        }
+    }
+
+    fn to_eth_trace_results(
+        traces: TransactionReplayTraces,
+    ) -> CoreResult<TraceResults> {
+        let trace = match traces.trace {
+            // The traces are not localized, so the block fields are unused.
+            Some(trace) => into_eth_localized_traces(
+                &TransactionExecTraces(trace).filter_space(Space::Ethereum).0,
+                0,
+                H256::zero(),
+                traces.tx_hash,
+                0,
+            )
+            .map_err(|e| {
+                warn!("Internal error on trace reconstruction: {}", e);
+                internal_error()
+            })?
+            .into_iter()
+            .map(Trace::from)
+            .collect(),
+            None => vec![],
+        };
+        let state_diff = match traces.state_diff {
+            Some(diff) => Some(StateDiff::try_from(
+                diff,
+                Space::Ethereum,
+                Ok::<_, String>,
+            )?),
+            None => None,
+        };
+        Ok(TraceResults {
+            output: traces.output.into(),
+            trace,
+            vm_trace: traces.vm_trace.map(VmTrace::from),
+            state_diff,
+        })
+    }
 }
 
 #[async_trait::async_trait]
@@ -231,6 +374,28 @@ impl TraceApiServer for TraceApi {
             .map_err(|err| err.into())
     }
 
+    async fn trace_call(
+        &self, request: TransactionRequest, trace_types: Vec<TraceType>,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<TraceResults> {
+        self.trace_call(request, trace_types, block_number)
+            .map_err(|err| err.into())
+    }
+
+    async fn replay_transaction(
+        &self, tx_hash: H256, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Option<TraceResults>> {
+        self.replay_transaction(tx_hash, trace_types)
+            .map_err(|err| err.into())
+    }
+
+    async fn replay_block_transactions(
+        &self, block_number: BlockId, trace_types: Vec<TraceType>,
+    ) -> RpcResult<Vec<TraceResultsWithTransactionHash>> {
+        self.replay_block_transactions(block_number, trace_types)
+            .map_err(|err| err.into())
+    }
+
     async fn trace_get(
         &self, tx_hash: H256, indices: Vec<Index>,
     ) -> RpcResult<Option<LocalizedTrace>> {
@@ -20,6 +20,9 @@ pub trait Filterable {
     /// Get logs that match the given filter.
     fn logs(&self, filter: LogFilter) -> RpcResult<Vec<Log>>;
 
+    /// Get logs of the pending block that match the given filter.
+    fn pending_logs(&self, filter: &LogFilter) -> RpcResult<Vec<Log>>;
+
     /// Get logs that match the given filter for specific epoch
     fn logs_for_epoch(
         &self, filter: &LogFilter, epoch: (u64, Vec<H256>), removed: bool,
@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use std::net::SocketAddr;
+
+/// Starts and stops the HTTP RPC server while the node is running.
+#[async_trait]
+pub trait HttpServerControl: Send + Sync {
+    /// Returns the address of the running HTTP server, if any.
+    fn http_address(&self) -> Option<SocketAddr>;
+
+    /// Returns the address the HTTP server is configured to listen on.
+    fn default_http_address(&self) -> SocketAddr;
+
+    /// Starts the HTTP server on `addr`, and returns the local address it
+    /// listens on.
+    async fn start_http(&self, addr: SocketAddr) -> Result<SocketAddr, String>;
+
+    /// Stops the running HTTP server.
+    fn stop_http(&self) -> Result<(), String>;
+}
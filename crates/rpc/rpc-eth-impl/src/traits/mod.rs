@@ -1,3 +1,5 @@
 mod filterable;
+mod http_server_control;
 
 pub use filterable::Filterable;
+pub use http_server_control::HttpServerControl;
@@ -0,0 +1,19 @@
+use cfx_types::{H256, U256};
+use serde::{Deserialize, Serialize};
+
+/// The account fields returned by `eth_getAccount`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    /// The balance of the account
+    pub balance: U256,
+    /// The nonce of the account
+    pub nonce: U256,
+    /// The code hash of the account, the hash of the empty code for
+    /// accounts without code
+    pub code_hash: H256,
+    /// The hash of the storage roots of the account, see
+    /// `cfx_getStorageRoot`, or the empty trie root for accounts without
+    /// storage
+    pub storage_root: H256,
+}
@@ -0,0 +1,439 @@
+use crate::Bytes;
+use cfx_storage::{StateProof, TrieProof, TrieProofNode};
+use cfx_types::{Address, BigEndianHash, H256, U256, U64};
+use keccak_hash::KECCAK_EMPTY;
+use primitives::{
+    account::EthereumAccount, StateRoot, StorageKey, StorageKeyWithSpace,
+    StorageValue,
+};
+use rlp::Rlp;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Merkle proof of one key in the Conflux state.
+///
+/// Unlike Ethereum, the Conflux state is split into three Merkle Patricia
+/// Tries (see [`StateRoot`]):
+/// - `snapshot`: the snapshot MPT, keyed by the raw storage key;
+/// - `intermediate`: the delta MPT of the previous snapshot period, keyed by
+///   the storage key padded with the previous state root;
+/// - `delta`: the delta MPT of the current snapshot period, keyed by the
+///   storage key padded with the current snapshot and intermediate roots.
+///
+/// A value is looked up in `delta` first, then `intermediate`, then
+/// `snapshot`, so each layer either proves the value or proves that the key
+/// is absent from it. Every layer is a list of RLP-encoded trie nodes, root
+/// first. A layer is `null` when its trie is empty or when an upper layer
+/// already proves the value.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateProofLayers {
+    pub snapshot: Option<Vec<Bytes>>,
+    pub intermediate: Option<Vec<Bytes>>,
+    pub delta: Option<Vec<Bytes>>,
+}
+
+impl StateProofLayers {
+    fn encode_layer(proof: Option<TrieProof>) -> Option<Vec<Bytes>> {
+        proof.map(|p| {
+            p.into_proof_nodes()
+                .iter()
+                .map(|node| rlp::encode(node).into())
+                .collect()
+        })
+    }
+
+    fn decode_layer(
+        layer: &Option<Vec<Bytes>>,
+    ) -> Result<Option<TrieProof>, ProofVerificationError> {
+        let nodes = match layer {
+            None => return Ok(None),
+            Some(nodes) => nodes,
+        };
+        if nodes.len() > TrieProof::MAX_NODES {
+            return Err(ProofVerificationError::MalformedProof(
+                "too many trie nodes".into(),
+            ));
+        }
+        let nodes = nodes
+            .iter()
+            .map(|n| Rlp::new(n).as_val::<TrieProofNode>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                ProofVerificationError::MalformedProof(e.to_string())
+            })?;
+        TrieProof::new(nodes)
+            .map(Some)
+            .map_err(|e| ProofVerificationError::MalformedProof(e.to_string()))
+    }
+
+    pub fn to_state_proof(&self) -> Result<StateProof, ProofVerificationError> {
+        Ok(StateProof {
+            delta_proof: Self::decode_layer(&self.delta)?,
+            intermediate_proof: Self::decode_layer(&self.intermediate)?,
+            snapshot_proof: Self::decode_layer(&self.snapshot)?,
+        })
+    }
+}
+
+impl From<StateProof> for StateProofLayers {
+    fn from(proof: StateProof) -> Self {
+        Self {
+            snapshot: Self::encode_layer(proof.snapshot_proof),
+            intermediate: Self::encode_layer(proof.intermediate_proof),
+            delta: Self::encode_layer(proof.delta_proof),
+        }
+    }
+}
+
+/// Proof of one storage slot, as returned in [`AccountProof`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProof {
+    /// The requested storage slot
+    pub key: U256,
+    /// The value stored in the slot, zero for empty slots
+    pub value: U256,
+    /// The proof of the storage entry
+    pub proof: StateProofLayers,
+}
+
+/// Response of `eth_getProof`, the Conflux variant of the
+/// [EIP-1186](https://eips.ethereum.org/EIPS/eip-1186) account proof.
+///
+/// Conflux keeps accounts and storage entries in the same tries, so every
+/// storage slot is proven directly against the state root instead of against
+/// a per-account storage root, and no `storageHash` is returned.
+///
+/// The state of epoch `epochNumber` is committed in the `deferredStateRoot`
+/// of the pivot block `DEFERRED_STATE_EPOCH_COUNT` epochs later, which equals
+/// `keccak(snapshotRoot ++ intermediateDeltaRoot ++ deltaRoot)` of
+/// `stateRoot`. When `prevSnapshotStateRoot` is present, it is the state root
+/// of epoch `epochNumber - snapshotEpochCount` and is needed to derive the
+/// keys of the intermediate layer. Use [`AccountProof::verify`] to check the
+/// proof.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: Address,
+    pub balance: U256,
+    pub nonce: U256,
+    pub code_hash: H256,
+    /// The proof of the account entry
+    pub account_proof: StateProofLayers,
+    pub storage_proof: Vec<StorageProof>,
+    /// The epoch whose state is proven
+    pub epoch_number: U64,
+    pub state_root: StateRoot,
+    pub prev_snapshot_state_root: Option<StateRoot>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ProofVerificationError {
+    #[error("state root mismatch: expected {expected:?}, got {received:?}")]
+    StateRootMismatch { expected: H256, received: H256 },
+    #[error("previous snapshot state root is required for verification")]
+    MissingPrevSnapshotStateRoot,
+    #[error("malformed proof: {0}")]
+    MalformedProof(String),
+    #[error("invalid account proof for {0:?}")]
+    InvalidAccountProof(Address),
+    #[error("invalid storage proof for {address:?} at slot {key:?}")]
+    InvalidStorageProof { address: Address, key: U256 },
+}
+
+impl AccountProof {
+    /// Verifies the account and all storage proofs.
+    ///
+    /// `deferred_state_root` is the `deferredStateRoot` of the pivot block
+    /// that commits the state of `epochNumber`. If the response carries a
+    /// `prevSnapshotStateRoot`, `prev_deferred_state_root` must be the
+    /// `deferredStateRoot` committing the state of epoch
+    /// `epochNumber - snapshotEpochCount`.
+    pub fn verify(
+        &self, deferred_state_root: &H256,
+        prev_deferred_state_root: Option<&H256>,
+    ) -> Result<(), ProofVerificationError> {
+        check_state_root(&self.state_root, deferred_state_root)?;
+
+        let maybe_intermediate_padding = match &self.prev_snapshot_state_root {
+            None => None,
+            Some(prev_root) => {
+                let expected = prev_deferred_state_root.ok_or(
+                    ProofVerificationError::MissingPrevSnapshotStateRoot,
+                )?;
+                check_state_root(prev_root, expected)?;
+                Some(StorageKeyWithSpace::delta_mpt_padding(
+                    &prev_root.snapshot_root,
+                    &prev_root.intermediate_delta_root,
+                ))
+            }
+        };
+
+        let account_key = StorageKey::new_account_key(&self.address)
+            .with_evm_space()
+            .to_key_bytes();
+        let account = rlp::encode(&EthereumAccount {
+            balance: self.balance,
+            nonce: self.nonce,
+            code_hash: self.code_hash,
+        });
+        let account_proof = self.account_proof.to_state_proof()?;
+        let proves_account = account_proof.is_valid_kv(
+            &account_key,
+            Some(&account[..]),
+            self.state_root.clone(),
+            maybe_intermediate_padding.clone(),
+        );
+        // A non-existent account is reported as an empty one.
+        let proves_absence = self.is_empty()
+            && account_proof.is_valid_kv(
+                &account_key,
+                None,
+                self.state_root.clone(),
+                maybe_intermediate_padding.clone(),
+            );
+        if !proves_account && !proves_absence {
+            return Err(ProofVerificationError::InvalidAccountProof(
+                self.address,
+            ));
+        }
+
+        for entry in &self.storage_proof {
+            let slot = H256::from_uint(&entry.key);
+            let key = StorageKey::new_storage_key(&self.address, slot.as_ref())
+                .with_evm_space()
+                .to_key_bytes();
+            // Zero values are never stored in the state.
+            let value = (!entry.value.is_zero()).then(|| {
+                rlp::encode(&StorageValue {
+                    value: entry.value,
+                    owner: None,
+                })
+            });
+            if !entry.proof.to_state_proof()?.is_valid_kv(
+                &key,
+                value.as_ref().map(|v| &v[..]),
+                self.state_root.clone(),
+                maybe_intermediate_padding.clone(),
+            ) {
+                return Err(ProofVerificationError::InvalidStorageProof {
+                    address: self.address,
+                    key: entry.key,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_empty(&self) -> bool {
+        self.balance.is_zero()
+            && self.nonce.is_zero()
+            && self.code_hash == KECCAK_EMPTY
+    }
+}
+
+fn check_state_root(
+    state_root: &StateRoot, expected: &H256,
+) -> Result<(), ProofVerificationError> {
+    let received = state_root.compute_state_root_hash();
+    if received != *expected {
+        return Err(ProofVerificationError::StateRootMismatch {
+            expected: *expected,
+            received,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cfx_storage::{
+        tests::new_state_manager_for_unit_test_with_snapshot_epoch_count,
+        StateIndex, StorageManagerTrait, StorageStateTrait,
+        StorageStateTraitExt,
+    };
+    use primitives::MERKLE_NULL_NODE;
+
+    #[test]
+    fn empty_layers_roundtrip() {
+        let layers = StateProofLayers::from(StateProof::default());
+        assert_eq!(layers, StateProofLayers::default());
+        assert_eq!(layers.to_state_proof().unwrap(), StateProof::default());
+        assert_eq!(
+            serde_json::to_string(&layers).unwrap(),
+            r#"{"snapshot":null,"intermediate":null,"delta":null}"#
+        );
+    }
+
+    #[test]
+    fn reject_wrong_state_root() {
+        let proof = AccountProof {
+            code_hash: KECCAK_EMPTY,
+            ..Default::default()
+        };
+        let err = proof.verify(&H256::zero(), None).unwrap_err();
+        assert_eq!(
+            err,
+            ProofVerificationError::StateRootMismatch {
+                expected: H256::zero(),
+                received: StateRoot::default().compute_state_root_hash(),
+            }
+        );
+    }
+
+    #[test]
+    fn require_prev_snapshot_state_root() {
+        let proof = AccountProof {
+            code_hash: KECCAK_EMPTY,
+            prev_snapshot_state_root: Some(StateRoot::default()),
+            ..Default::default()
+        };
+        let root = StateRoot::default().compute_state_root_hash();
+        assert_eq!(
+            proof.verify(&root, None).unwrap_err(),
+            ProofVerificationError::MissingPrevSnapshotStateRoot
+        );
+    }
+
+    #[test]
+    fn reject_empty_proof_for_non_empty_trie() {
+        let state_root = StateRoot {
+            snapshot_root: MERKLE_NULL_NODE,
+            intermediate_delta_root: MERKLE_NULL_NODE,
+            delta_root: H256::repeat_byte(1),
+        };
+        let proof = AccountProof {
+            code_hash: KECCAK_EMPTY,
+            state_root: state_root.clone(),
+            ..Default::default()
+        };
+        assert_eq!(
+            proof
+                .verify(&state_root.compute_state_root_hash(), None)
+                .unwrap_err(),
+            ProofVerificationError::InvalidAccountProof(Address::zero())
+        );
+    }
+
+    #[test]
+    fn verify_proof_from_state() {
+        let snapshot_epoch_count = 10;
+        let address = Address::repeat_byte(0x12);
+        let (balance, nonce) = (U256::from(100), U256::one());
+        let (slot, empty_slot) =
+            (H256::from_low_u64_be(3), H256::from_low_u64_be(4));
+        let value = U256::from(7);
+        let account_key =
+            StorageKey::new_account_key(&address).with_evm_space();
+
+        let state_manager =
+            new_state_manager_for_unit_test_with_snapshot_epoch_count(
+                snapshot_epoch_count,
+            );
+        let mut genesis = state_manager.get_state_for_genesis_write();
+        genesis
+            .set(
+                account_key,
+                rlp::encode(&EthereumAccount {
+                    balance,
+                    nonce,
+                    code_hash: KECCAK_EMPTY,
+                })
+                .into(),
+            )
+            .unwrap();
+        genesis
+            .set(
+                StorageKey::new_storage_key(&address, slot.as_ref())
+                    .with_evm_space(),
+                rlp::encode(&StorageValue { value, owner: None }).into(),
+            )
+            .unwrap();
+        let epoch_id = H256::repeat_byte(1);
+        let root = genesis.compute_state_root().unwrap();
+        genesis.commit(epoch_id).unwrap();
+
+        let state = state_manager
+            .get_state_for_next_epoch_inner(
+                StateIndex::new_for_next_epoch(
+                    &epoch_id,
+                    &root,
+                    1,
+                    snapshot_epoch_count,
+                ),
+                true,
+                false,
+            )
+            .unwrap()
+            .unwrap();
+        let state_root = state.get_state_root().unwrap().state_root;
+        let (_, account_proof) = state.get_with_proof(account_key).unwrap();
+        let storage_proof = [(slot, value), (empty_slot, U256::zero())]
+            .iter()
+            .map(|(slot, value)| {
+                let key = StorageKey::new_storage_key(&address, slot.as_ref())
+                    .with_evm_space();
+                StorageProof {
+                    key: slot.into_uint(),
+                    value: *value,
+                    proof: state.get_with_proof(key).unwrap().1.into(),
+                }
+            })
+            .collect();
+        let proof = AccountProof {
+            address,
+            balance,
+            nonce,
+            code_hash: KECCAK_EMPTY,
+            account_proof: account_proof.into(),
+            storage_proof,
+            epoch_number: U64::one(),
+            state_root: state_root.clone(),
+            prev_snapshot_state_root: None,
+        };
+        let deferred_state_root = state_root.compute_state_root_hash();
+        assert_eq!(proof.verify(&deferred_state_root, None), Ok(()));
+
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: AccountProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(decoded.verify(&deferred_state_root, None), Ok(()));
+
+        let mut tampered = proof.clone();
+        tampered.balance = U256::from(101);
+        assert_eq!(
+            tampered.verify(&deferred_state_root, None).unwrap_err(),
+            ProofVerificationError::InvalidAccountProof(address)
+        );
+
+        let mut tampered = proof.clone();
+        tampered.storage_proof[0].value = U256::from(8);
+        assert_eq!(
+            tampered.verify(&deferred_state_root, None).unwrap_err(),
+            ProofVerificationError::InvalidStorageProof {
+                address,
+                key: slot.into_uint(),
+            }
+        );
+
+        // A non-existent account is proven as an empty one.
+        let absent = Address::repeat_byte(0x34);
+        let (_, absent_proof) = state
+            .get_with_proof(
+                StorageKey::new_account_key(&absent).with_evm_space(),
+            )
+            .unwrap();
+        let proof = AccountProof {
+            address: absent,
+            balance: U256::zero(),
+            nonce: U256::zero(),
+            account_proof: absent_proof.into(),
+            storage_proof: vec![],
+            ..proof
+        };
+        assert_eq!(proof.verify(&deferred_state_root, None), Ok(()));
+    }
+}
@@ -0,0 +1,70 @@
+use cfx_types::{H256, H512, U64};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+
+/// The local node information returned by `admin_nodeInfo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeInfo {
+    /// The node id, i.e. the public key of the node.
+    pub id: H512,
+    /// The client version.
+    pub name: String,
+    /// The node url in the format of `cfxnode://ID@IP:PORT`.
+    pub enode: String,
+    pub ip: String,
+    pub listen_addr: SocketAddr,
+    pub ports: Ports,
+    pub protocols: NodeProtocols,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ports {
+    /// The UDP port for node discovery.
+    pub discovery: u16,
+    /// The TCP port for peer connections.
+    pub listener: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeProtocols {
+    pub eth: EthProtocolInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthProtocolInfo {
+    /// The p2p network id.
+    pub network: u64,
+    pub chain_id: U64,
+    pub genesis: H256,
+    /// The hash of the latest pivot block.
+    pub head: H256,
+}
+
+/// A connected peer returned by `admin_peers`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerInfo {
+    pub id: H512,
+    /// The node url in the format of `cfxnode://ID@IP:PORT`.
+    pub enode: String,
+    pub network: PeerNetworkInfo,
+    pub protocols: Vec<PeerProtocol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerNetworkInfo {
+    pub remote_address: SocketAddr,
+    /// Whether the connection is initiated by the peer.
+    pub inbound: bool,
+    /// Whether the peer is in the trusted node table.
+    pub trusted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerProtocol {
+    pub name: String,
+    pub version: u8,
+}
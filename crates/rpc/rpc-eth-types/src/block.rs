@@ -24,6 +24,7 @@ use cfx_types::{
     hexstr_to_h256, Address, Bloom as H2048, Space, H160, H256, H64, U256,
 };
 use primitives::receipt::EVM_SPACE_SUCCESS;
+use rlp::{Encodable, RlpStream};
 use serde::{Deserialize, Serialize, Serializer};
 use std::collections::BTreeMap;
 
@@ -167,6 +168,38 @@ impl Block {
     }
 }
 
+/// The Ethereum encoding of the header. The hash of the encoding differs from
+/// `hash`, which is the hash of the pivot block.
+impl Encodable for Header {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(
+            if self.base_fee_per_gas.is_some() {
+                16
+            } else {
+                15
+            },
+        );
+        s.append(&self.parent_hash);
+        s.append(&self.uncles_hash);
+        s.append(&self.author);
+        s.append(&self.state_root);
+        s.append(&self.transactions_root);
+        s.append(&self.receipts_root);
+        s.append(&self.logs_bloom);
+        s.append(&self.difficulty);
+        s.append(&self.number);
+        s.append(&self.gas_limit);
+        s.append(&self.gas_used);
+        s.append(&self.timestamp);
+        s.append(&self.extra_data.0);
+        s.append(&self.mix_hash);
+        s.append(&self.nonce);
+        if let Some(base_fee_per_gas) = &self.base_fee_per_gas {
+            s.append(base_fee_per_gas);
+        }
+    }
+}
+
 impl Header {
     pub fn from_phantom(pb: &PhantomBlock) -> Self {
         // If there are no transactions, we use the empty hash for txRoot and
@@ -324,6 +357,8 @@ mod tests {
     use super::{Block, BlockTransactions, Header};
     use crate::Bytes;
     use cfx_types::{Bloom as H2048, H160, H256, H64, U256};
+    use hex_literal::hex;
+    use keccak_hash::keccak;
 
     #[test]
     fn test_serialize_block() {
@@ -361,4 +396,45 @@ mod tests {
             r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000000","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","sha3Uncles":"0x0000000000000000000000000000000000000000000000000000000000000000","author":"0x0000000000000000000000000000000000000000","miner":"0x0000000000000000000000000000000000000000","stateRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","receiptsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","number":"0x0","gasUsed":"0x0","gasLimit":"0x0","espaceGasLimit":"0x0","extraData":"0x","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","timestamp":"0x0","difficulty":"0x0","totalDifficulty":"0x0","size":"0x45","nonce":"0x0000000000000000","mixHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactions":[],"uncles":[]}"#
         );
     }
+
+    #[test]
+    fn test_encode_header() {
+        // The expected encoding is the one of reth (`alloy_consensus::Header`)
+        // for the same fields.
+        let mut header = Header {
+            hash: H256::default(),
+            parent_hash: hex!("3de6bb3849a138e6ab0b83a3a00dc7433f1e83f7fd488e4bba78f2fe2631a633").into(),
+            uncles_hash: hex!("1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347").into(),
+            author: hex!("7777788200b672a42421017f65ede4fc759564c8").into(),
+            miner: H160::default(),
+            state_root: hex!("41cf6e8e60fd087d2b00360dc29e5bfb21959bce1f4c242fd1ad7c4da968eb87").into(),
+            transactions_root: hex!("dfcb68d3a3c41096f4a77569db7956e0a0e750fad185948e54789ea0e51779cb").into(),
+            receipts_root: hex!("8a8865cd785e2e9dfce7da83aca010b10b9af2abbd367114b236f149534c821d").into(),
+            number: 12965000.into(),
+            gas_used: 30025257.into(),
+            gas_limit: 30029122.into(),
+            espace_gas_limit: U256::default(),
+            extra_data: Bytes::new(b"https://www.kryptex.org".to_vec()),
+            logs_bloom: H2048::repeat_byte(0x24),
+            timestamp: 1628166822.into(),
+            difficulty: 0x1aedf59a4bc180u64.into(),
+            total_difficulty: 0.into(),
+            base_fee_per_gas: Some(1_000_000_000.into()),
+            size: 0.into(),
+            nonce: hex!("b223da049adf2216").into(),
+            mix_hash: hex!("9620b46a81a4795cf4449d48e3270419f58b09293a5421205f88179b563f815a").into(),
+        };
+        let expected = hex!("f9021fa03de6bb3849a138e6ab0b83a3a00dc7433f1e83f7fd488e4bba78f2fe2631a633a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347947777788200b672a42421017f65ede4fc759564c8a041cf6e8e60fd087d2b00360dc29e5bfb21959bce1f4c242fd1ad7c4da968eb87a0dfcb68d3a3c41096f4a77569db7956e0a0e750fad185948e54789ea0e51779cba08a8865cd785e2e9dfce7da83aca010b10b9af2abbd367114b236f149534c821db9010024242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424242424871aedf59a4bc18083c5d4888401ca35428401ca262984610bdaa69768747470733a2f2f7777772e6b7279707465782e6f7267a09620b46a81a4795cf4449d48e3270419f58b09293a5421205f88179b563f815a88b223da049adf2216843b9aca00");
+        assert_eq!(rlp::encode(&header).to_vec(), expected);
+        assert_eq!(
+            keccak(&expected),
+            H256(hex!("4df749012fdd423adad995a82a783e4866c54ff0f690540490a4d9c0e8bbfa69"))
+        );
+
+        // Without the base fee, the header has the 15 fields before London.
+        header.base_fee_per_gas = None;
+        let encoded = rlp::encode(&header);
+        assert_eq!(encoded[..3], hex!("f9021a"));
+        assert_eq!(encoded[3..], expected[3..expected.len() - 5]);
+    }
 }
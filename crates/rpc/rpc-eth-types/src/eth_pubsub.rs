@@ -4,14 +4,14 @@
 
 //! Pub-Sub types.
 
-use super::{EthRpcLogFilter, Header, Log};
+use super::{EthRpcLogFilter, Header, Log, Transaction};
 use cfx_types::H256;
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 use serde_json::{from_value, Value};
 
 /// Subscription result.
 #[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(untagged, rename_all = "camelCase")]
 // NOTE: rename_all does not apply to enum member fields
 // see: https://github.com/serde-rs/serde/issues/1061
@@ -24,6 +24,9 @@ pub enum Result {
 
     /// Transaction hash
     TransactionHash(H256),
+
+    /// Full transaction
+    FullTransaction(Transaction),
 }
 
 /// Subscription kind.
@@ -33,9 +36,11 @@ pub enum Result {
 pub enum Kind {
     /// New block headers subscription.
     NewHeads,
-    /// Logs subscription.
+    /// Logs subscription. When the pivot chain reverts epochs, the logs
+    /// emitted for them are sent again with `removed: true`, the latest first.
     Logs,
-    /// New Pending Transactions subscription.
+    /// New Pending Transactions subscription, streaming the hashes or, with
+    /// the `fullTransactions` flag, the full objects of the transactions.
     NewPendingTransactions,
     /// Node syncing status subscription.
     Syncing,
@@ -48,6 +53,8 @@ pub enum Params {
     None,
     /// Log parameters.
     Logs(EthRpcLogFilter),
+    /// Whether to stream full transaction objects.
+    FullTransactions(bool),
 }
 
 impl Default for Params {
@@ -65,6 +72,10 @@ impl<'a> Deserialize<'a> for Params {
             return Ok(Params::None);
         }
 
+        if let Some(full) = v.as_bool() {
+            return Ok(Params::FullTransactions(full));
+        }
+
         // try to interpret as a log filter
         from_value(v.clone()).map(Params::Logs).map_err(|e| {
             D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e))
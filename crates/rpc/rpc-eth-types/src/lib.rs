@@ -1,4 +1,7 @@
 mod access_list;
+mod account;
+mod account_proof;
+mod admin;
 mod authorization;
 mod block;
 mod block_number;
@@ -21,6 +24,11 @@ mod transaction_request;
 mod tx_pool;
 
 pub use access_list::*;
+pub use account::Account;
+pub use account_proof::{
+    AccountProof, ProofVerificationError, StateProofLayers, StorageProof,
+};
+pub use admin::*;
 pub use authorization::{Authorization, SignedAuthorization};
 pub use block::{Block, BlockOverrides, Header};
 pub use block_number::BlockId;
@@ -42,7 +50,7 @@ pub use state_dump::*;
 pub use sync::{SyncInfo, SyncStatus};
 pub use trace::*;
 pub use trace_filter::TraceFilter;
-pub use transaction::{Transaction, WrapTransaction};
+pub use transaction::{Transaction, TransactionsByAddress, WrapTransaction};
 pub use transaction_request::{
     TransactionRequest, DEFAULT_ETH_GAS_CALL_REQUEST,
 };
@@ -63,6 +63,25 @@ pub struct StateDump {
     pub next: Option<Bytes>,
 }
 
+/// A page of the storage of an account, returned by `debug_storageRangeAt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots, indexed by the keccak hash of their keys as in geth
+    pub storage: BTreeMap<H256, StorageEntry>,
+    /// The key of the next slot if there are more. The slots are ordered by
+    /// their keys instead of the hashes, as the state tree is not keyed by
+    /// the hashes.
+    pub next_key: Option<H256>,
+}
+
+/// A storage slot in [`StorageRangeResult`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageEntry {
+    pub key: Option<H256>,
+    pub value: H256,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,7 +5,7 @@ use cfx_parity_trace_types::{
 };
 use cfx_rpc_cfx_types::{
     trace::{Action as CfxRpcAction, LocalizedTrace as CfxLocalizedTrace},
-    RpcAddress,
+    trace_replay, RpcAddress,
 };
 use cfx_rpc_primitives::Bytes;
 use cfx_rpc_utils::error::jsonrpsee_error_helpers::internal_error;
@@ -16,6 +16,8 @@ use jsonrpsee::types::ErrorObjectOwned;
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use std::{collections::HashMap, convert::TryFrom, fmt};
 
+pub use cfx_rpc_cfx_types::trace_replay::TraceType;
+
 /// Create response
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -354,6 +356,25 @@ impl Serialize for Trace {
     }
 }
 
+impl From<LocalizedTrace> for Trace {
+    fn from(trace: LocalizedTrace) -> Self {
+        Trace {
+            trace_address: trace.trace_address,
+            subtraces: trace.subtraces,
+            action: trace.action,
+            result: trace.result,
+            error: trace.error,
+        }
+    }
+}
+
+/// The result of `trace_call` and `trace_replayTransaction`.
+pub type TraceResults = trace_replay::TraceResults<Trace, Address>;
+
+/// The result of `trace_replayBlockTransactions`.
+pub type TraceResultsWithTransactionHash =
+    trace_replay::TraceResultsWithTransactionHash<Trace, Address>;
+
 #[derive(Debug, Clone)]
 pub enum TraceError {
     /// Execution has been reverted with REVERT instruction.
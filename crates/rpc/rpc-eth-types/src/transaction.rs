@@ -197,3 +197,12 @@ pub enum WrapTransaction {
     NativeTransaction(CfxTransaction),
     EthTransaction(Transaction),
 }
+
+/// A page of the transactions sent from or to an address, from the newest one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsByAddress {
+    pub transactions: Vec<Transaction>,
+    /// The cursor of the next page, or `None` on the last page.
+    pub next_cursor: Option<U64>,
+}
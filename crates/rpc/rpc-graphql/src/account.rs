@@ -0,0 +1,195 @@
+use crate::{
+    cfx,
+    scalars::{Address, BigInt, Bytes, Bytes32},
+};
+use async_graphql::{Context, Object, Result, SimpleObject};
+use cfx_rpc_cfx_types::{
+    Account as RpcAccount, BlockHashOrEpochNumber, EpochNumber, RpcAddress,
+    SponsorInfo as RpcSponsorInfo,
+};
+use primitives::{DepositInfo, VoteStakeInfo};
+use tokio::sync::OnceCell;
+
+/// An account in the state of an epoch, the latest state if no epoch is
+/// given.
+pub struct Account {
+    address: RpcAddress,
+    epoch: Option<EpochNumber>,
+    /// The basic fields, retrieved once for all of them.
+    basic: OnceCell<RpcAccount>,
+}
+
+impl Account {
+    pub(crate) fn new(address: RpcAddress, epoch: Option<EpochNumber>) -> Self {
+        Account {
+            address,
+            epoch,
+            basic: OnceCell::new(),
+        }
+    }
+
+    async fn basic(&self, ctx: &Context<'_>) -> Result<&RpcAccount> {
+        Ok(self
+            .basic
+            .get_or_try_init(|| {
+                cfx(ctx).account(self.address.clone(), self.epoch.clone())
+            })
+            .await?)
+    }
+
+    fn block_hash_or_epoch_number(&self) -> Option<BlockHashOrEpochNumber> {
+        self.epoch
+            .clone()
+            .map(|epoch| BlockHashOrEpochNumber::EpochNumber(epoch.into()))
+    }
+}
+
+#[Object]
+impl Account {
+    async fn address(&self) -> Address { self.address.clone().into() }
+
+    async fn balance(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        Ok(self.basic(ctx).await?.balance.into())
+    }
+
+    async fn nonce(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        Ok(self.basic(ctx).await?.nonce.into())
+    }
+
+    async fn code_hash(&self, ctx: &Context<'_>) -> Result<Bytes32> {
+        Ok(self.basic(ctx).await?.code_hash.into())
+    }
+
+    async fn staking_balance(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        Ok(self.basic(ctx).await?.staking_balance.into())
+    }
+
+    /// The storage collateral of the account, including the storage points
+    /// used by a sponsored contract.
+    async fn collateral_for_storage(
+        &self, ctx: &Context<'_>,
+    ) -> Result<BigInt> {
+        Ok(self.basic(ctx).await?.collateral_for_storage.into())
+    }
+
+    async fn accumulated_interest_return(
+        &self, ctx: &Context<'_>,
+    ) -> Result<BigInt> {
+        Ok(self.basic(ctx).await?.accumulated_interest_return.into())
+    }
+
+    /// The admin of a contract, the zero address for other accounts.
+    async fn admin(&self, ctx: &Context<'_>) -> Result<Address> {
+        Ok(self.basic(ctx).await?.admin.clone().into())
+    }
+
+    async fn code(&self, ctx: &Context<'_>) -> Result<Bytes> {
+        Ok(cfx(ctx)
+            .code(self.address.clone(), self.block_hash_or_epoch_number())
+            .await?
+            .into())
+    }
+
+    async fn storage(
+        &self, ctx: &Context<'_>, slot: BigInt,
+    ) -> Result<Option<Bytes32>> {
+        Ok(cfx(ctx)
+            .storage_at(
+                self.address.clone(),
+                slot.0,
+                self.block_hash_or_epoch_number(),
+            )
+            .await?
+            .map(Into::into))
+    }
+
+    async fn sponsor_info(&self, ctx: &Context<'_>) -> Result<SponsorInfo> {
+        Ok(cfx(ctx)
+            .sponsor_info(self.address.clone(), self.epoch.clone())
+            .await?
+            .into())
+    }
+
+    /// The staking deposits of the account.
+    async fn deposits(&self, ctx: &Context<'_>) -> Result<Vec<Deposit>> {
+        Ok(cfx(ctx)
+            .deposit_list(self.address.clone(), self.epoch.clone())
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// The locked staking votes of the account.
+    async fn votes(&self, ctx: &Context<'_>) -> Result<Vec<Vote>> {
+        Ok(cfx(ctx)
+            .vote_list(self.address.clone(), self.epoch.clone())
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
+/// The sponsors of a contract.
+#[derive(SimpleObject)]
+pub struct SponsorInfo {
+    sponsor_for_gas: Address,
+    sponsor_for_collateral: Address,
+    sponsor_gas_bound: BigInt,
+    sponsor_balance_for_gas: BigInt,
+    sponsor_balance_for_collateral: BigInt,
+    available_storage_points: BigInt,
+    used_storage_points: BigInt,
+}
+
+impl From<RpcSponsorInfo> for SponsorInfo {
+    fn from(info: RpcSponsorInfo) -> Self {
+        SponsorInfo {
+            sponsor_for_gas: info.sponsor_for_gas.into(),
+            sponsor_for_collateral: info.sponsor_for_collateral.into(),
+            sponsor_gas_bound: info.sponsor_gas_bound.into(),
+            sponsor_balance_for_gas: info.sponsor_balance_for_gas.into(),
+            sponsor_balance_for_collateral: info
+                .sponsor_balance_for_collateral
+                .into(),
+            available_storage_points: info.available_storage_points.into(),
+            used_storage_points: info.used_storage_points.into(),
+        }
+    }
+}
+
+/// A staking deposit.
+#[derive(SimpleObject)]
+pub struct Deposit {
+    amount: BigInt,
+    /// The block number of the deposit.
+    deposit_time: BigInt,
+    accumulated_interest_rate: BigInt,
+}
+
+impl From<DepositInfo> for Deposit {
+    fn from(deposit: DepositInfo) -> Self {
+        Deposit {
+            amount: deposit.amount.into(),
+            deposit_time: deposit.deposit_time.into(),
+            accumulated_interest_rate: deposit.accumulated_interest_rate.into(),
+        }
+    }
+}
+
+/// Staking balance locked for voting.
+#[derive(SimpleObject)]
+pub struct Vote {
+    amount: BigInt,
+    unlock_block_number: BigInt,
+}
+
+impl From<VoteStakeInfo> for Vote {
+    fn from(vote: VoteStakeInfo) -> Self {
+        Vote {
+            amount: vote.amount.into(),
+            unlock_block_number: vote.unlock_block_number.into(),
+        }
+    }
+}
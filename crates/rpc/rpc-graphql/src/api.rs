@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use cfx_rpc_cfx_api::{CfxRpcServer, PosRpcServer};
+use cfx_rpc_cfx_types::{
+    pos::Status as RpcPosStatus, Account as RpcAccount, Block as RpcBlock,
+    BlockHashOrEpochNumber, Bytes, CfxRpcLogFilter, EpochNumber, Log as RpcLog,
+    Receipt as RpcReceipt, RpcAddress, SponsorInfo as RpcSponsorInfo,
+    Status as RpcStatus, Transaction as RpcTransaction,
+};
+use cfx_types::{H256, U256, U64};
+use jsonrpsee_types::ErrorObjectOwned;
+use primitives::{DepositInfo, VoteStakeInfo};
+
+pub type RpcResult<T> = Result<T, ErrorObjectOwned>;
+
+/// The `cfx_*` methods which the resolvers are built on.
+///
+/// The jsonrpsee server traits can not be made into trait objects, so the
+/// schema holds its handlers through this trait, which is implemented for
+/// every [`CfxRpcServer`].
+#[async_trait]
+pub trait CfxApi: Send + Sync {
+    async fn block_by_hash(
+        &self, hash: H256, include_txs: bool,
+    ) -> RpcResult<Option<RpcBlock>>;
+
+    async fn block_by_epoch_number(
+        &self, epoch: EpochNumber, include_txs: bool,
+    ) -> RpcResult<Option<RpcBlock>>;
+
+    async fn block_by_block_number(
+        &self, number: U64, include_txs: bool,
+    ) -> RpcResult<Option<RpcBlock>>;
+
+    async fn blocks_by_epoch(&self, epoch: EpochNumber)
+        -> RpcResult<Vec<H256>>;
+
+    async fn skipped_blocks_by_epoch(
+        &self, epoch: EpochNumber,
+    ) -> RpcResult<Vec<H256>>;
+
+    async fn epoch_number(&self, epoch: Option<EpochNumber>)
+        -> RpcResult<U256>;
+
+    async fn transaction_by_hash(
+        &self, hash: H256,
+    ) -> RpcResult<Option<RpcTransaction>>;
+
+    async fn transaction_receipt(
+        &self, hash: H256,
+    ) -> RpcResult<Option<RpcReceipt>>;
+
+    async fn get_logs(&self, filter: CfxRpcLogFilter)
+        -> RpcResult<Vec<RpcLog>>;
+
+    async fn account(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<RpcAccount>;
+
+    async fn code(
+        &self, address: RpcAddress, epoch: Option<BlockHashOrEpochNumber>,
+    ) -> RpcResult<Bytes>;
+
+    async fn storage_at(
+        &self, address: RpcAddress, position: U256,
+        epoch: Option<BlockHashOrEpochNumber>,
+    ) -> RpcResult<Option<H256>>;
+
+    async fn sponsor_info(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<RpcSponsorInfo>;
+
+    async fn deposit_list(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<Vec<DepositInfo>>;
+
+    async fn vote_list(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<Vec<VoteStakeInfo>>;
+
+    async fn gas_price(&self) -> RpcResult<U256>;
+
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256>;
+
+    async fn get_status(&self) -> RpcResult<RpcStatus>;
+
+    async fn send_raw_transaction(&self, raw: Bytes) -> RpcResult<H256>;
+}
+
+#[async_trait]
+impl<T: CfxRpcServer> CfxApi for T {
+    async fn block_by_hash(
+        &self, hash: H256, include_txs: bool,
+    ) -> RpcResult<Option<RpcBlock>> {
+        CfxRpcServer::block_by_hash(self, hash, include_txs).await
+    }
+
+    async fn block_by_epoch_number(
+        &self, epoch: EpochNumber, include_txs: bool,
+    ) -> RpcResult<Option<RpcBlock>> {
+        CfxRpcServer::block_by_epoch_number(self, epoch, include_txs).await
+    }
+
+    async fn block_by_block_number(
+        &self, number: U64, include_txs: bool,
+    ) -> RpcResult<Option<RpcBlock>> {
+        CfxRpcServer::block_by_block_number(self, number, include_txs).await
+    }
+
+    async fn blocks_by_epoch(
+        &self, epoch: EpochNumber,
+    ) -> RpcResult<Vec<H256>> {
+        CfxRpcServer::blocks_by_epoch(self, epoch).await
+    }
+
+    async fn skipped_blocks_by_epoch(
+        &self, epoch: EpochNumber,
+    ) -> RpcResult<Vec<H256>> {
+        CfxRpcServer::skipped_blocks_by_epoch(self, epoch).await
+    }
+
+    async fn epoch_number(
+        &self, epoch: Option<EpochNumber>,
+    ) -> RpcResult<U256> {
+        CfxRpcServer::epoch_number(self, epoch).await
+    }
+
+    async fn transaction_by_hash(
+        &self, hash: H256,
+    ) -> RpcResult<Option<RpcTransaction>> {
+        CfxRpcServer::transaction_by_hash(self, hash).await
+    }
+
+    async fn transaction_receipt(
+        &self, hash: H256,
+    ) -> RpcResult<Option<RpcReceipt>> {
+        CfxRpcServer::transaction_receipt(self, hash).await
+    }
+
+    async fn get_logs(
+        &self, filter: CfxRpcLogFilter,
+    ) -> RpcResult<Vec<RpcLog>> {
+        CfxRpcServer::get_logs(self, filter).await
+    }
+
+    async fn account(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<RpcAccount> {
+        CfxRpcServer::account(self, address, epoch).await
+    }
+
+    async fn code(
+        &self, address: RpcAddress, epoch: Option<BlockHashOrEpochNumber>,
+    ) -> RpcResult<Bytes> {
+        CfxRpcServer::code(self, address, epoch).await
+    }
+
+    async fn storage_at(
+        &self, address: RpcAddress, position: U256,
+        epoch: Option<BlockHashOrEpochNumber>,
+    ) -> RpcResult<Option<H256>> {
+        CfxRpcServer::storage_at(self, address, position, epoch).await
+    }
+
+    async fn sponsor_info(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<RpcSponsorInfo> {
+        CfxRpcServer::sponsor_info(self, address, epoch).await
+    }
+
+    async fn deposit_list(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<Vec<DepositInfo>> {
+        CfxRpcServer::deposit_list(self, address, epoch).await
+    }
+
+    async fn vote_list(
+        &self, address: RpcAddress, epoch: Option<EpochNumber>,
+    ) -> RpcResult<Vec<VoteStakeInfo>> {
+        CfxRpcServer::vote_list(self, address, epoch).await
+    }
+
+    async fn gas_price(&self) -> RpcResult<U256> {
+        CfxRpcServer::gas_price(self).await
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+        CfxRpcServer::max_priority_fee_per_gas(self).await
+    }
+
+    async fn get_status(&self) -> RpcResult<RpcStatus> {
+        CfxRpcServer::get_status(self).await
+    }
+
+    async fn send_raw_transaction(&self, raw: Bytes) -> RpcResult<H256> {
+        CfxRpcServer::send_raw_transaction(self, raw).await
+    }
+}
+
+/// The `pos_*` methods which the resolvers are built on, see [`CfxApi`].
+pub trait PosApi: Send + Sync {
+    fn pos_status(&self) -> RpcResult<RpcPosStatus>;
+}
+
+impl<T: PosRpcServer> PosApi for T {
+    fn pos_status(&self) -> RpcResult<RpcPosStatus> {
+        PosRpcServer::pos_status(self)
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    account::Account,
+    cfx, page,
+    scalars::{Address, BigInt, Bytes, Bytes32, EpochTag, Long},
+    transaction::{Log, Transaction},
+    MAX_LIST_SIZE,
+};
+use async_graphql::{Context, Object, Result};
+use cfx_rpc_cfx_types::{
+    Block as RpcBlock, BlockTransactions, CfxRpcLogFilter, EpochNumber,
+};
+use cfx_types::{H256, U256, U64};
+
+/// A Core Space block.
+pub struct Block(RpcBlock);
+
+impl Block {
+    /// Retrieves the block with its transactions.
+    pub(crate) async fn by_hash(
+        ctx: &Context<'_>, hash: H256,
+    ) -> Result<Option<Block>> {
+        Ok(cfx(ctx).block_by_hash(hash, true).await?.map(Block))
+    }
+
+    async fn by_hashes(
+        ctx: &Context<'_>, hashes: Vec<H256>,
+    ) -> Result<Vec<Block>> {
+        let mut blocks = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            if let Some(block) = Self::by_hash(ctx, hash).await? {
+                blocks.push(block);
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+#[Object]
+impl Block {
+    async fn hash(&self) -> Bytes32 { self.0.hash.into() }
+
+    async fn parent_hash(&self) -> Bytes32 { self.0.parent_hash.into() }
+
+    async fn parent(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        Self::by_hash(ctx, self.0.parent_hash).await
+    }
+
+    /// The distance to the genesis block along the parent edges.
+    async fn height(&self) -> BigInt { self.0.height.into() }
+
+    async fn miner_address(&self) -> Address { self.0.miner.clone().into() }
+
+    /// The account of the miner, in the state of `epoch` or the latest state.
+    async fn miner(&self, epoch: Option<EpochTag>) -> Account {
+        Account::new(self.0.miner.clone(), epoch.map(|epoch| epoch.0))
+    }
+
+    async fn deferred_state_root(&self) -> Bytes32 {
+        self.0.deferred_state_root.into()
+    }
+
+    async fn deferred_receipts_root(&self) -> Bytes32 {
+        self.0.deferred_receipts_root.into()
+    }
+
+    async fn deferred_logs_bloom_hash(&self) -> Bytes32 {
+        self.0.deferred_logs_bloom_hash.into()
+    }
+
+    /// The number of ancestors whose deferred roots or blame are incorrect
+    /// according to this block.
+    async fn blame(&self) -> Long { self.0.blame.into() }
+
+    async fn transactions_root(&self) -> Bytes32 {
+        self.0.transactions_root.into()
+    }
+
+    /// The epoch which the block is executed in, null if it's not executed
+    /// yet.
+    async fn epoch_number(&self) -> Option<BigInt> {
+        self.0.epoch_number.map(Into::into)
+    }
+
+    async fn epoch(&self) -> Option<Epoch> {
+        self.0.epoch_number.map(|number| Epoch { number })
+    }
+
+    async fn block_number(&self) -> Option<BigInt> {
+        self.0.block_number.map(Into::into)
+    }
+
+    async fn gas_limit(&self) -> BigInt { self.0.gas_limit.into() }
+
+    async fn gas_used(&self) -> Option<BigInt> {
+        self.0.gas_used.map(Into::into)
+    }
+
+    async fn base_fee_per_gas(&self) -> Option<BigInt> {
+        self.0.base_fee_per_gas.map(Into::into)
+    }
+
+    async fn timestamp(&self) -> BigInt { self.0.timestamp.into() }
+
+    async fn difficulty(&self) -> BigInt { self.0.difficulty.into() }
+
+    async fn pow_quality(&self) -> Option<BigInt> {
+        self.0.pow_quality.map(Into::into)
+    }
+
+    async fn referee_hashes(&self) -> Vec<Bytes32> {
+        self.0.referee_hashes.iter().map(|h| (*h).into()).collect()
+    }
+
+    #[graphql(complexity = "first.min(MAX_LIST_SIZE) * child_complexity")]
+    async fn referees(
+        &self, ctx: &Context<'_>, #[graphql(default)] skip: usize,
+        #[graphql(default_with = "MAX_LIST_SIZE")] first: usize,
+    ) -> Result<Vec<Block>> {
+        let hashes = page(self.0.referee_hashes.iter().copied(), skip, first);
+        Self::by_hashes(ctx, hashes).await
+    }
+
+    async fn adaptive(&self) -> bool { self.0.adaptive }
+
+    async fn nonce(&self) -> BigInt { self.0.nonce.into() }
+
+    async fn size(&self) -> Option<BigInt> { self.0.size.map(Into::into) }
+
+    async fn custom(&self) -> Vec<Bytes> {
+        self.0.custom.iter().cloned().map(Into::into).collect()
+    }
+
+    async fn pos_reference(&self) -> Option<Bytes32> {
+        self.0.pos_reference.map(Into::into)
+    }
+
+    async fn transaction_count(&self) -> Long {
+        let count = match &self.0.transactions {
+            BlockTransactions::Hashes(hashes) => hashes.len(),
+            BlockTransactions::Full(txs) => txs.len(),
+        };
+        U64::from(count).into()
+    }
+
+    #[graphql(complexity = "first.min(MAX_LIST_SIZE) * child_complexity")]
+    async fn transactions(
+        &self, ctx: &Context<'_>, #[graphql(default)] skip: usize,
+        #[graphql(default_with = "MAX_LIST_SIZE")] first: usize,
+    ) -> Result<Vec<Transaction>> {
+        match &self.0.transactions {
+            BlockTransactions::Full(txs) => {
+                Ok(page(txs.iter().cloned().map(Transaction::new), skip, first))
+            }
+            BlockTransactions::Hashes(hashes) => {
+                let hashes = page(hashes.iter().copied(), skip, first);
+                let mut txs = Vec::with_capacity(hashes.len());
+                for hash in hashes {
+                    if let Some(tx) = Transaction::by_hash(ctx, hash).await? {
+                        txs.push(tx);
+                    }
+                }
+                Ok(txs)
+            }
+        }
+    }
+
+    /// The logs produced by the transactions of the block.
+    #[graphql(complexity = "first.min(MAX_LIST_SIZE) * child_complexity")]
+    async fn logs(
+        &self, ctx: &Context<'_>, #[graphql(default)] skip: usize,
+        #[graphql(default_with = "MAX_LIST_SIZE")] first: usize,
+    ) -> Result<Vec<Log>> {
+        let filter = CfxRpcLogFilter {
+            from_epoch: None,
+            to_epoch: None,
+            block_hashes: Some(vec![self.0.hash]),
+            ..Default::default()
+        };
+        Ok(page(Log::by_filter(ctx, filter).await?, skip, first))
+    }
+}
+
+/// An epoch of the pivot chain, i.e. a pivot block and the blocks it
+/// executes.
+pub struct Epoch {
+    number: U256,
+}
+
+impl Epoch {
+    /// Resolves an epoch tag to the epoch number it refers to now, so that
+    /// all fields of the epoch are consistent.
+    pub(crate) async fn resolve(
+        ctx: &Context<'_>, epoch: EpochNumber,
+    ) -> Result<Epoch> {
+        let number = cfx(ctx).epoch_number(Some(epoch)).await?;
+        Ok(Epoch { number })
+    }
+
+    fn epoch_number(&self) -> EpochNumber {
+        EpochNumber::Num(U64::from(self.number.low_u64()))
+    }
+}
+
+#[Object]
+impl Epoch {
+    async fn number(&self) -> BigInt { self.number.into() }
+
+    async fn pivot_block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        Ok(cfx(ctx)
+            .block_by_epoch_number(self.epoch_number(), true)
+            .await?
+            .map(Block))
+    }
+
+    /// The hashes of the blocks in the epoch, in execution order. The last
+    /// one is the pivot block.
+    async fn block_hashes(&self, ctx: &Context<'_>) -> Result<Vec<Bytes32>> {
+        Ok(cfx(ctx)
+            .blocks_by_epoch(self.epoch_number())
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// The blocks in the epoch, in execution order. The last one is the
+    /// pivot block.
+    #[graphql(complexity = "first.min(MAX_LIST_SIZE) * child_complexity")]
+    async fn blocks(
+        &self, ctx: &Context<'_>, #[graphql(default)] skip: usize,
+        #[graphql(default_with = "MAX_LIST_SIZE")] first: usize,
+    ) -> Result<Vec<Block>> {
+        let hashes = cfx(ctx).blocks_by_epoch(self.epoch_number()).await?;
+        Block::by_hashes(ctx, page(hashes, skip, first)).await
+    }
+
+    /// The hashes of the blocks which are in the past set of the pivot block
+    /// but skipped from execution.
+    async fn skipped_block_hashes(
+        &self, ctx: &Context<'_>,
+    ) -> Result<Vec<Bytes32>> {
+        Ok(cfx(ctx)
+            .skipped_blocks_by_epoch(self.epoch_number())
+            .await?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    /// An account in the state after executing the epoch.
+    async fn account(&self, address: Address) -> Account {
+        Account::new(address.0, Some(self.epoch_number()))
+    }
+
+    /// The logs produced in the epoch.
+    #[graphql(complexity = "first.min(MAX_LIST_SIZE) * child_complexity")]
+    async fn logs(
+        &self, ctx: &Context<'_>, #[graphql(default)] skip: usize,
+        #[graphql(default_with = "MAX_LIST_SIZE")] first: usize,
+    ) -> Result<Vec<Log>> {
+        let filter = CfxRpcLogFilter {
+            from_epoch: Some(self.epoch_number()),
+            to_epoch: Some(self.epoch_number()),
+            ..Default::default()
+        };
+        Ok(page(Log::by_filter(ctx, filter).await?, skip, first))
+    }
+}
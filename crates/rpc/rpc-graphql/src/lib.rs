@@ -0,0 +1,406 @@
+//! GraphQL schema of Core Space.
+//!
+//! The schema follows geth's GraphQL schema where the concepts match, with
+//! epochs in place of block numbers. Every field is resolved by the handlers
+//! of the `cfx_*` and `pos_*` JSON-RPC methods, so it returns the same data
+//! and errors as the corresponding method. A query can therefore assemble a
+//! block with its referees, transactions, receipts and the accounts involved
+//! in a single round trip.
+
+mod account;
+mod api;
+mod block;
+mod query;
+mod scalars;
+mod status;
+mod transaction;
+
+pub use account::{Account, Deposit, SponsorInfo, Vote};
+pub use api::{CfxApi, PosApi};
+pub use block::{Block, Epoch};
+pub use query::{LogFilter, Mutation, Query};
+pub use scalars::{Address, BigInt, Bytes, Bytes32, EpochTag, Long};
+pub use status::{PosStatus, Status};
+pub use transaction::{Log, Receipt, StorageChange, Transaction};
+
+use async_graphql::{Context, EmptySubscription, Schema};
+use std::sync::Arc;
+
+/// Queries nested deeper than this are rejected, so that a query can not
+/// walk the referees of referees without bound.
+const MAX_QUERY_DEPTH: usize = 16;
+
+/// Queries resolving more fields than this are rejected before execution.
+/// The fields of a list count once for every entry the list can return,
+/// see [`MAX_LIST_SIZE`].
+const MAX_QUERY_COMPLEXITY: usize = 10_000;
+
+/// The maximum number of entries returned by a list of objects, whose
+/// fields may each take further requests, e.g. the referees of a block. The
+/// lists take the `skip` and `first` arguments to page through longer ones.
+pub(crate) const MAX_LIST_SIZE: usize = 100;
+
+/// The Core Space GraphQL schema.
+pub type CfxSchema = Schema<Query, Mutation, EmptySubscription>;
+
+/// Builds the schema resolving its fields with the given handlers.
+///
+/// The `sendRawTransaction` mutation is only served if
+/// `send_raw_transaction` is set.
+pub fn build_schema(
+    cfx: Arc<dyn CfxApi>, pos: Arc<dyn PosApi>, send_raw_transaction: bool,
+) -> CfxSchema {
+    Schema::build(Query, Mutation, EmptySubscription)
+        .data(Handlers {
+            cfx,
+            pos,
+            send_raw_transaction,
+        })
+        .limit_depth(MAX_QUERY_DEPTH)
+        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .finish()
+}
+
+/// The handlers shared by all resolvers.
+struct Handlers {
+    cfx: Arc<dyn CfxApi>,
+    pos: Arc<dyn PosApi>,
+    send_raw_transaction: bool,
+}
+
+fn cfx<'a>(ctx: &Context<'a>) -> &'a dyn CfxApi {
+    &*ctx.data_unchecked::<Handlers>().cfx
+}
+
+fn pos<'a>(ctx: &Context<'a>) -> &'a dyn PosApi {
+    &*ctx.data_unchecked::<Handlers>().pos
+}
+
+fn send_raw_transaction_enabled(ctx: &Context<'_>) -> bool {
+    ctx.data_unchecked::<Handlers>().send_raw_transaction
+}
+
+/// The page of `items` after skipping `skip` entries, with at most `first`
+/// and at most [`MAX_LIST_SIZE`] entries.
+fn page<T>(
+    items: impl IntoIterator<Item = T>, skip: usize, first: usize,
+) -> Vec<T> {
+    items
+        .into_iter()
+        .skip(skip)
+        .take(first.min(MAX_LIST_SIZE))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::RpcResult;
+    use async_trait::async_trait;
+    use cfx_rpc_cfx_types::{
+        pos::Status as RpcPosStatus, Account as RpcAccount, Block as RpcBlock,
+        BlockHashOrEpochNumber, Bytes as RpcBytes, CfxRpcLogFilter,
+        EpochNumber, Log as RpcLog, Receipt as RpcReceipt, RpcAddress,
+        SponsorInfo as RpcSponsorInfo, Status as RpcStatus,
+        Transaction as RpcTransaction,
+    };
+    use cfx_types::{H256, U256, U64};
+    use primitives::{DepositInfo, VoteStakeInfo};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    /// Serves `blocks`, the blocks of every epoch being `epoch`.
+    #[derive(Default)]
+    struct MockCfx {
+        blocks: HashMap<H256, RpcBlock>,
+        epoch: Vec<H256>,
+    }
+
+    impl MockCfx {
+        fn insert_block(&mut self, hash: H256, referees: Vec<H256>) {
+            let block = serde_json::from_value(json!({
+                "hash": hash,
+                "parentHash": H256::zero(),
+                "height": "0x0",
+                "miner": "CFX:TYPE.NULL:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA0SFBNJM2",
+                "deferredStateRoot": H256::zero(),
+                "deferredReceiptsRoot": H256::zero(),
+                "deferredLogsBloomHash": H256::zero(),
+                "blame": "0x0",
+                "transactionsRoot": H256::zero(),
+                "gasLimit": "0x0",
+                "timestamp": "0x0",
+                "difficulty": "0x0",
+                "refereeHashes": referees,
+                "adaptive": false,
+                "nonce": "0x0",
+                "transactions": [],
+                "custom": [],
+            }))
+            .unwrap();
+            self.blocks.insert(hash, block);
+        }
+    }
+
+    #[async_trait]
+    impl CfxApi for MockCfx {
+        async fn block_by_hash(
+            &self, hash: H256, _include_txs: bool,
+        ) -> RpcResult<Option<RpcBlock>> {
+            Ok(self.blocks.get(&hash).cloned())
+        }
+
+        async fn block_by_epoch_number(
+            &self, _epoch: EpochNumber, _include_txs: bool,
+        ) -> RpcResult<Option<RpcBlock>> {
+            unimplemented!()
+        }
+
+        async fn block_by_block_number(
+            &self, _number: U64, _include_txs: bool,
+        ) -> RpcResult<Option<RpcBlock>> {
+            unimplemented!()
+        }
+
+        async fn blocks_by_epoch(
+            &self, _epoch: EpochNumber,
+        ) -> RpcResult<Vec<H256>> {
+            Ok(self.epoch.clone())
+        }
+
+        async fn skipped_blocks_by_epoch(
+            &self, _epoch: EpochNumber,
+        ) -> RpcResult<Vec<H256>> {
+            unimplemented!()
+        }
+
+        async fn epoch_number(
+            &self, _epoch: Option<EpochNumber>,
+        ) -> RpcResult<U256> {
+            Ok(U256::from(7))
+        }
+
+        async fn transaction_by_hash(
+            &self, _hash: H256,
+        ) -> RpcResult<Option<RpcTransaction>> {
+            unimplemented!()
+        }
+
+        async fn transaction_receipt(
+            &self, _hash: H256,
+        ) -> RpcResult<Option<RpcReceipt>> {
+            unimplemented!()
+        }
+
+        async fn get_logs(
+            &self, _filter: CfxRpcLogFilter,
+        ) -> RpcResult<Vec<RpcLog>> {
+            unimplemented!()
+        }
+
+        async fn account(
+            &self, _address: RpcAddress, _epoch: Option<EpochNumber>,
+        ) -> RpcResult<RpcAccount> {
+            unimplemented!()
+        }
+
+        async fn code(
+            &self, _address: RpcAddress, _epoch: Option<BlockHashOrEpochNumber>,
+        ) -> RpcResult<RpcBytes> {
+            unimplemented!()
+        }
+
+        async fn storage_at(
+            &self, _address: RpcAddress, _position: U256,
+            _epoch: Option<BlockHashOrEpochNumber>,
+        ) -> RpcResult<Option<H256>> {
+            unimplemented!()
+        }
+
+        async fn sponsor_info(
+            &self, _address: RpcAddress, _epoch: Option<EpochNumber>,
+        ) -> RpcResult<RpcSponsorInfo> {
+            unimplemented!()
+        }
+
+        async fn deposit_list(
+            &self, _address: RpcAddress, _epoch: Option<EpochNumber>,
+        ) -> RpcResult<Vec<DepositInfo>> {
+            unimplemented!()
+        }
+
+        async fn vote_list(
+            &self, _address: RpcAddress, _epoch: Option<EpochNumber>,
+        ) -> RpcResult<Vec<VoteStakeInfo>> {
+            unimplemented!()
+        }
+
+        async fn gas_price(&self) -> RpcResult<U256> { Ok(U256::from(1)) }
+
+        async fn max_priority_fee_per_gas(&self) -> RpcResult<U256> {
+            unimplemented!()
+        }
+
+        async fn get_status(&self) -> RpcResult<RpcStatus> { unimplemented!() }
+
+        async fn send_raw_transaction(&self, raw: RpcBytes) -> RpcResult<H256> {
+            Ok(H256::from_low_u64_be(raw.0.len() as u64))
+        }
+    }
+
+    struct MockPos;
+
+    impl PosApi for MockPos {
+        fn pos_status(&self) -> RpcResult<RpcPosStatus> { unimplemented!() }
+    }
+
+    fn schema(cfx: MockCfx, send_raw_transaction: bool) -> CfxSchema {
+        build_schema(Arc::new(cfx), Arc::new(MockPos), send_raw_transaction)
+    }
+
+    async fn execute(schema: &CfxSchema, query: &str) -> Value {
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    async fn execute_err(schema: &CfxSchema, query: &str) -> String {
+        let response = schema.execute(query).await;
+        assert_eq!(response.errors.len(), 1);
+        response.errors[0].message.clone()
+    }
+
+    fn hashes(range: std::ops::RangeInclusive<u64>) -> Vec<H256> {
+        range.map(H256::from_low_u64_be).collect()
+    }
+
+    #[test]
+    fn test_schema_sdl() {
+        let sdl = Schema::build(Query, Mutation, EmptySubscription)
+            .finish()
+            .sdl();
+
+        for definition in [
+            "type Query",
+            "type Mutation",
+            "type Epoch",
+            "type Block",
+            "type Transaction",
+            "type Receipt",
+            "type Log",
+            "type Account",
+            "type PosStatus",
+            "input LogFilter",
+            "scalar EpochNumber",
+            "scalar BigInt",
+        ] {
+            assert!(sdl.contains(definition), "missing {}", definition);
+        }
+        assert!(sdl.contains(
+            "referees(skip: Int! = 0, first: Int! = 100): [Block!]!"
+        ));
+        assert!(sdl.contains("sponsorInfo: SponsorInfo!"));
+    }
+
+    #[tokio::test]
+    async fn test_block() {
+        let mut cfx = MockCfx::default();
+        let root = H256::repeat_byte(0xaa);
+        cfx.insert_block(root, hashes(1..=150));
+        for hash in hashes(1..=150) {
+            cfx.insert_block(hash, vec![]);
+        }
+        let schema = schema(cfx, false);
+
+        let query = format!(
+            r#"{{
+                block(hash: "{:?}") {{
+                    hash
+                    refereeHashes
+                    referees {{ hash }}
+                    page: referees(skip: 140, first: 20) {{ hash }}
+                }}
+                missing: block(hash: "{:?}") {{ hash }}
+            }}"#,
+            root,
+            H256::repeat_byte(0xbb)
+        );
+        let data = execute(&schema, &query).await;
+        let block = &data["block"];
+        assert_eq!(block["hash"], json!(root));
+        assert_eq!(block["refereeHashes"], json!(hashes(1..=150)));
+        // The referees are capped, and paged with `skip` and `first`.
+        let referees = block["referees"].as_array().unwrap();
+        assert_eq!(referees.len(), MAX_LIST_SIZE);
+        assert_eq!(referees[0]["hash"], json!(H256::from_low_u64_be(1)));
+        let page: Vec<_> = block["page"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|referee| referee["hash"].clone())
+            .collect();
+        assert_eq!(json!(page), json!(hashes(141..=150)));
+        assert_eq!(data["missing"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_epoch() {
+        let mut cfx = MockCfx::default();
+        cfx.epoch = hashes(1..=3);
+        for hash in hashes(1..=3) {
+            cfx.insert_block(hash, vec![]);
+        }
+        let schema = schema(cfx, false);
+
+        let data = execute(
+            &schema,
+            "{ epoch { number blockHashes blocks(skip: 1) { hash } } }",
+        )
+        .await;
+        assert_eq!(
+            data["epoch"],
+            json!({
+                "number": U256::from(7),
+                "blockHashes": hashes(1..=3),
+                "blocks": [
+                    { "hash": H256::from_low_u64_be(2) },
+                    { "hash": H256::from_low_u64_be(3) },
+                ],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_complexity() {
+        let schema = schema(MockCfx::default(), false);
+
+        // Every referee of every block of the epoch could be resolved.
+        let err =
+            execute_err(&schema, "{ epoch { blocks { referees { hash } } } }")
+                .await;
+        assert_eq!(err, "Query is too complex.");
+        // Smaller pages are accepted.
+        execute(
+            &schema,
+            "{ epoch { blocks(first: 10) { referees(first: 10) { hash } } } }",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_transaction() {
+        let query = r#"mutation { sendRawTransaction(data: "0x010203") }"#;
+
+        let disabled = schema(MockCfx::default(), false);
+        assert_eq!(
+            execute_err(&disabled, query).await,
+            "sendRawTransaction is disabled"
+        );
+
+        let enabled = schema(MockCfx::default(), true);
+        assert_eq!(
+            execute(&enabled, query).await,
+            json!({ "sendRawTransaction": H256::from_low_u64_be(3) })
+        );
+    }
+}
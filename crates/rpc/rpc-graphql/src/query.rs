@@ -0,0 +1,148 @@
+use crate::{
+    account::Account,
+    block::{Block, Epoch},
+    cfx, page, pos,
+    scalars::{Address, BigInt, Bytes, Bytes32, EpochTag, Long},
+    send_raw_transaction_enabled,
+    status::{PosStatus, Status},
+    transaction::{Log, Transaction},
+    MAX_LIST_SIZE,
+};
+use async_graphql::{Context, InputObject, Object, Result};
+use cfx_rpc_cfx_types::{CfxRpcLogFilter, EpochNumber};
+use cfx_rpc_primitives::VariadicValue;
+
+/// The root of the queries.
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// The epoch of the given number or tag, `latest_state` by default.
+    async fn epoch(
+        &self, ctx: &Context<'_>, number: Option<EpochTag>,
+    ) -> Result<Epoch> {
+        let epoch = number.map_or(EpochNumber::LatestState, |tag| tag.0);
+        Epoch::resolve(ctx, epoch).await
+    }
+
+    async fn block(
+        &self, ctx: &Context<'_>, hash: Bytes32,
+    ) -> Result<Option<Block>> {
+        Block::by_hash(ctx, hash.0).await
+    }
+
+    /// The block of the given number in the total order of blocks.
+    async fn block_by_number(
+        &self, ctx: &Context<'_>, number: Long,
+    ) -> Result<Option<Block>> {
+        match cfx(ctx).block_by_block_number(number.0, false).await? {
+            Some(block) => Block::by_hash(ctx, block.hash).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn transaction(
+        &self, ctx: &Context<'_>, hash: Bytes32,
+    ) -> Result<Option<Transaction>> {
+        Transaction::by_hash(ctx, hash.0).await
+    }
+
+    /// The logs matching `filter`, see `cfx_getLogs`.
+    #[graphql(complexity = "first.min(MAX_LIST_SIZE) * child_complexity")]
+    async fn logs(
+        &self, ctx: &Context<'_>, filter: LogFilter,
+        #[graphql(default)] skip: usize,
+        #[graphql(default_with = "MAX_LIST_SIZE")] first: usize,
+    ) -> Result<Vec<Log>> {
+        Ok(page(Log::by_filter(ctx, filter.into()).await?, skip, first))
+    }
+
+    /// An account in the state of `epoch`, the latest state by default.
+    async fn account(
+        &self, address: Address, epoch: Option<EpochTag>,
+    ) -> Account {
+        Account::new(address.0, epoch.map(|epoch| epoch.0))
+    }
+
+    async fn gas_price(&self, ctx: &Context<'_>) -> Result<BigInt> {
+        Ok(cfx(ctx).gas_price().await?.into())
+    }
+
+    async fn max_priority_fee_per_gas(
+        &self, ctx: &Context<'_>,
+    ) -> Result<BigInt> {
+        Ok(cfx(ctx).max_priority_fee_per_gas().await?.into())
+    }
+
+    async fn status(&self, ctx: &Context<'_>) -> Result<Status> {
+        Ok(cfx(ctx).get_status().await?.into())
+    }
+
+    async fn pos_status(&self, ctx: &Context<'_>) -> Result<PosStatus> {
+        Ok(pos(ctx).pos_status()?.into())
+    }
+}
+
+/// The root of the mutations.
+pub struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Sends a signed transaction, returning its hash. Only served if
+    /// enabled in the node's configuration.
+    #[graphql(visible = "send_raw_transaction_enabled")]
+    async fn send_raw_transaction(
+        &self, ctx: &Context<'_>, data: Bytes,
+    ) -> Result<Bytes32> {
+        if !send_raw_transaction_enabled(ctx) {
+            return Err("sendRawTransaction is disabled".into());
+        }
+        Ok(cfx(ctx).send_raw_transaction(data.0).await?.into())
+    }
+}
+
+/// The criteria of the logs to return, see `cfx_getLogs`.
+#[derive(InputObject)]
+pub struct LogFilter {
+    from_epoch: Option<EpochTag>,
+    to_epoch: Option<EpochTag>,
+    from_block: Option<Long>,
+    to_block: Option<Long>,
+    /// Overrides the epoch and block ranges if given.
+    block_hashes: Option<Vec<Bytes32>>,
+    /// The logs must be produced by one of the addresses if given.
+    addresses: Option<Vec<Address>>,
+    /// The n-th entry lists the accepted values of the n-th topic, null
+    /// accepts any value.
+    topics: Option<Vec<Option<Vec<Bytes32>>>>,
+}
+
+impl From<LogFilter> for CfxRpcLogFilter {
+    fn from(filter: LogFilter) -> Self {
+        CfxRpcLogFilter {
+            from_epoch: filter.from_epoch.map(|epoch| epoch.0),
+            to_epoch: filter.to_epoch.map(|epoch| epoch.0),
+            from_block: filter.from_block.map(|number| number.0),
+            to_block: filter.to_block.map(|number| number.0),
+            block_hashes: filter
+                .block_hashes
+                .map(|hashes| hashes.into_iter().map(|h| h.0).collect()),
+            address: filter.addresses.map(|addresses| {
+                VariadicValue::Multiple(
+                    addresses.into_iter().map(|a| a.0).collect(),
+                )
+            }),
+            topics: filter.topics.map(|topics| {
+                topics
+                    .into_iter()
+                    .map(|topic| match topic {
+                        Some(values) => VariadicValue::Multiple(
+                            values.into_iter().map(|v| v.0).collect(),
+                        ),
+                        None => VariadicValue::Null,
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+//! Scalars of the schema. They are (de)serialized exactly like the
+//! corresponding JSON-RPC types.
+
+use async_graphql::scalar;
+use cfx_rpc_cfx_types::{EpochNumber, RpcAddress};
+use cfx_types::{H256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// A 32 byte value, e.g. a hash.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bytes32(pub H256);
+
+scalar!(
+    Bytes32,
+    "Bytes32",
+    "A 32 byte value, hex encoded with the 0x prefix."
+);
+
+/// A Core Space address.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Address(pub RpcAddress);
+
+scalar!(Address, "Address", "A base32 encoded Core Space address.");
+
+/// Arbitrary length binary data.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Bytes(pub cfx_rpc_primitives::Bytes);
+
+scalar!(
+    Bytes,
+    "Bytes",
+    "Arbitrary length binary data, hex encoded with the 0x prefix."
+);
+
+/// A 256 bit unsigned integer.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BigInt(pub U256);
+
+scalar!(
+    BigInt,
+    "BigInt",
+    "A 256 bit unsigned integer, hex encoded with the 0x prefix."
+);
+
+/// A 64 bit unsigned integer.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Long(pub U64);
+
+scalar!(
+    Long,
+    "Long",
+    "A 64 bit unsigned integer, hex encoded with the 0x prefix."
+);
+
+/// An epoch number or tag, as accepted by the `cfx_*` methods.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EpochTag(pub EpochNumber);
+
+scalar!(
+    EpochTag,
+    "EpochNumber",
+    "A hex encoded epoch number or one of the tags `earliest`, \
+     `latest_checkpoint`, `latest_finalized`, `latest_confirmed`, \
+     `latest_state` and `latest_mined`."
+);
+
+impl From<H256> for Bytes32 {
+    fn from(value: H256) -> Self { Bytes32(value) }
+}
+
+impl From<RpcAddress> for Address {
+    fn from(value: RpcAddress) -> Self { Address(value) }
+}
+
+impl From<cfx_rpc_primitives::Bytes> for Bytes {
+    fn from(value: cfx_rpc_primitives::Bytes) -> Self { Bytes(value) }
+}
+
+impl From<U256> for BigInt {
+    fn from(value: U256) -> Self { BigInt(value) }
+}
+
+impl From<U64> for Long {
+    fn from(value: U64) -> Self { Long(value) }
+}
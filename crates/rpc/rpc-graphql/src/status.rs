@@ -0,0 +1,63 @@
+use crate::scalars::{Bytes32, Long};
+use async_graphql::SimpleObject;
+use cfx_rpc_cfx_types::{pos::Status as RpcPosStatus, Status as RpcStatus};
+
+/// The status of the node, as returned by `cfx_getStatus`.
+#[derive(SimpleObject)]
+pub struct Status {
+    best_hash: Bytes32,
+    chain_id: Long,
+    ethereum_space_chain_id: Long,
+    network_id: Long,
+    epoch_number: Long,
+    block_number: Long,
+    pending_tx_number: Long,
+    latest_checkpoint: Long,
+    latest_confirmed: Long,
+    latest_state: Long,
+    latest_finalized: Long,
+}
+
+impl From<RpcStatus> for Status {
+    fn from(status: RpcStatus) -> Self {
+        Status {
+            best_hash: status.best_hash.into(),
+            chain_id: status.chain_id.into(),
+            ethereum_space_chain_id: status.ethereum_space_chain_id.into(),
+            network_id: status.network_id.into(),
+            epoch_number: status.epoch_number.into(),
+            block_number: status.block_number.into(),
+            pending_tx_number: status.pending_tx_number.into(),
+            latest_checkpoint: status.latest_checkpoint.into(),
+            latest_confirmed: status.latest_confirmed.into(),
+            latest_state: status.latest_state.into(),
+            latest_finalized: status.latest_finalized.into(),
+        }
+    }
+}
+
+/// The status of the PoS chain, as returned by `pos_getStatus`.
+#[derive(SimpleObject)]
+pub struct PosStatus {
+    latest_committed: Long,
+    epoch: Long,
+    /// The height of the latest PoW block decided by the PoS chain.
+    pivot_decision_height: Long,
+    /// The hash of the latest PoW block decided by the PoS chain.
+    pivot_decision_hash: Bytes32,
+    latest_voted: Option<Long>,
+    latest_tx_number: Long,
+}
+
+impl From<RpcPosStatus> for PosStatus {
+    fn from(status: RpcPosStatus) -> Self {
+        PosStatus {
+            latest_committed: status.latest_committed.into(),
+            epoch: status.epoch.into(),
+            pivot_decision_height: status.pivot_decision.height.into(),
+            pivot_decision_hash: status.pivot_decision.block_hash.into(),
+            latest_voted: status.latest_voted.map(Into::into),
+            latest_tx_number: status.latest_tx_number.into(),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{
+    account::Account,
+    block::Block,
+    cfx, page,
+    scalars::{Address, BigInt, Bytes, Bytes32, EpochTag, Long},
+    MAX_LIST_SIZE,
+};
+use async_graphql::{Context, Object, Result, SimpleObject};
+use cfx_rpc_cfx_types::{
+    CfxRpcLogFilter, Log as RpcLog, Receipt as RpcReceipt,
+    StorageChange as RpcStorageChange, Transaction as RpcTransaction,
+};
+use cfx_types::H256;
+
+/// A Core Space transaction.
+pub struct Transaction(RpcTransaction);
+
+impl Transaction {
+    pub(crate) fn new(tx: RpcTransaction) -> Self { Transaction(tx) }
+
+    pub(crate) async fn by_hash(
+        ctx: &Context<'_>, hash: H256,
+    ) -> Result<Option<Transaction>> {
+        Ok(cfx(ctx).transaction_by_hash(hash).await?.map(Transaction))
+    }
+}
+
+#[Object]
+impl Transaction {
+    async fn hash(&self) -> Bytes32 { self.0.hash.into() }
+
+    #[graphql(name = "type")]
+    async fn transaction_type(&self) -> Option<Long> {
+        self.0.transaction_type.map(Into::into)
+    }
+
+    async fn nonce(&self) -> BigInt { self.0.nonce.into() }
+
+    /// The hash of the block packing the transaction, null if it's pending.
+    async fn block_hash(&self) -> Option<Bytes32> {
+        self.0.block_hash.map(Into::into)
+    }
+
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        match self.0.block_hash {
+            Some(hash) => Block::by_hash(ctx, hash).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn transaction_index(&self) -> Option<Long> {
+        self.0.transaction_index.map(Into::into)
+    }
+
+    async fn from_address(&self) -> Address { self.0.from.clone().into() }
+
+    /// The sender account, in the state of `epoch` or the latest state.
+    async fn from(&self, epoch: Option<EpochTag>) -> Account {
+        Account::new(self.0.from.clone(), epoch.map(|epoch| epoch.0))
+    }
+
+    async fn to_address(&self) -> Option<Address> {
+        self.0.to.clone().map(Into::into)
+    }
+
+    /// The receiver account, in the state of `epoch` or the latest state.
+    /// Null for contract creations.
+    async fn to(&self, epoch: Option<EpochTag>) -> Option<Account> {
+        self.0
+            .to
+            .clone()
+            .map(|to| Account::new(to, epoch.map(|epoch| epoch.0)))
+    }
+
+    async fn value(&self) -> BigInt { self.0.value.into() }
+
+    async fn gas_price(&self) -> BigInt { self.0.gas_price.into() }
+
+    async fn gas(&self) -> BigInt { self.0.gas.into() }
+
+    async fn max_priority_fee_per_gas(&self) -> Option<BigInt> {
+        self.0.max_priority_fee_per_gas.map(Into::into)
+    }
+
+    async fn max_fee_per_gas(&self) -> Option<BigInt> {
+        self.0.max_fee_per_gas.map(Into::into)
+    }
+
+    async fn contract_created(&self) -> Option<Address> {
+        self.0.contract_created.clone().map(Into::into)
+    }
+
+    async fn data(&self) -> Bytes { self.0.data.clone().into() }
+
+    async fn storage_limit(&self) -> BigInt { self.0.storage_limit.into() }
+
+    async fn epoch_height(&self) -> BigInt { self.0.epoch_height.into() }
+
+    async fn chain_id(&self) -> Option<BigInt> {
+        self.0.chain_id.map(Into::into)
+    }
+
+    /// The outcome status, null if the transaction isn't executed yet.
+    async fn status(&self) -> Option<Long> { self.0.status.map(Into::into) }
+
+    async fn v(&self) -> BigInt { self.0.v.into() }
+
+    async fn r(&self) -> BigInt { self.0.r.into() }
+
+    async fn s(&self) -> BigInt { self.0.s.into() }
+
+    async fn y_parity(&self) -> Option<Long> { self.0.y_parity.map(Into::into) }
+
+    /// The receipt, null if the transaction isn't executed yet.
+    async fn receipt(&self, ctx: &Context<'_>) -> Result<Option<Receipt>> {
+        Ok(cfx(ctx)
+            .transaction_receipt(self.0.hash)
+            .await?
+            .map(Receipt))
+    }
+}
+
+/// The receipt of an executed transaction.
+pub struct Receipt(RpcReceipt);
+
+#[Object]
+impl Receipt {
+    async fn transaction_hash(&self) -> Bytes32 {
+        self.0.transaction_hash.into()
+    }
+
+    async fn transaction(
+        &self, ctx: &Context<'_>,
+    ) -> Result<Option<Transaction>> {
+        Transaction::by_hash(ctx, self.0.transaction_hash).await
+    }
+
+    /// The index of the transaction in its block.
+    async fn index(&self) -> Long { self.0.index.into() }
+
+    async fn block_hash(&self) -> Bytes32 { self.0.block_hash.into() }
+
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        Block::by_hash(ctx, self.0.block_hash).await
+    }
+
+    async fn epoch_number(&self) -> Option<Long> {
+        self.0.epoch_number.map(Into::into)
+    }
+
+    async fn from(&self) -> Address { self.0.from.clone().into() }
+
+    async fn to(&self) -> Option<Address> { self.0.to.clone().map(Into::into) }
+
+    async fn gas_used(&self) -> BigInt { self.0.gas_used.into() }
+
+    async fn accumulated_gas_used(&self) -> Option<BigInt> {
+        self.0.accumulated_gas_used.map(Into::into)
+    }
+
+    async fn gas_fee(&self) -> BigInt { self.0.gas_fee.into() }
+
+    async fn effective_gas_price(&self) -> BigInt {
+        self.0.effective_gas_price.into()
+    }
+
+    async fn burnt_gas_fee(&self) -> Option<BigInt> {
+        self.0.burnt_gas_fee.map(Into::into)
+    }
+
+    async fn contract_created(&self) -> Option<Address> {
+        self.0.contract_created.clone().map(Into::into)
+    }
+
+    #[graphql(complexity = "first.min(MAX_LIST_SIZE) * child_complexity")]
+    async fn logs(
+        &self, #[graphql(default)] skip: usize,
+        #[graphql(default_with = "MAX_LIST_SIZE")] first: usize,
+    ) -> Vec<Log> {
+        page(self.0.logs.iter().cloned().map(Log), skip, first)
+    }
+
+    async fn logs_bloom(&self) -> Bytes {
+        cfx_rpc_primitives::Bytes::new(self.0.logs_bloom.as_bytes().to_vec())
+            .into()
+    }
+
+    async fn state_root(&self) -> Bytes32 { self.0.state_root.into() }
+
+    /// 0 for success, 1 for failure and 2 for skipped execution.
+    async fn outcome_status(&self) -> Long { self.0.outcome_status.into() }
+
+    async fn tx_exec_error_msg(&self) -> Option<String> {
+        self.0.tx_exec_error_msg.clone()
+    }
+
+    async fn gas_covered_by_sponsor(&self) -> bool {
+        self.0.gas_covered_by_sponsor
+    }
+
+    async fn storage_covered_by_sponsor(&self) -> bool {
+        self.0.storage_covered_by_sponsor
+    }
+
+    async fn storage_collateralized(&self) -> Long {
+        self.0.storage_collateralized.into()
+    }
+
+    async fn storage_released(&self) -> Vec<StorageChange> {
+        self.0
+            .storage_released
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect()
+    }
+}
+
+/// Storage collateral released from an account.
+#[derive(SimpleObject)]
+pub struct StorageChange {
+    address: Address,
+    collaterals: Long,
+}
+
+impl From<RpcStorageChange> for StorageChange {
+    fn from(change: RpcStorageChange) -> Self {
+        StorageChange {
+            address: change.address.into(),
+            collaterals: change.collaterals.into(),
+        }
+    }
+}
+
+/// A log produced by a transaction.
+pub struct Log(RpcLog);
+
+impl Log {
+    pub(crate) async fn by_filter(
+        ctx: &Context<'_>, filter: CfxRpcLogFilter,
+    ) -> Result<Vec<Log>> {
+        Ok(cfx(ctx)
+            .get_logs(filter)
+            .await?
+            .into_iter()
+            .map(Log)
+            .collect())
+    }
+}
+
+#[Object]
+impl Log {
+    async fn address(&self) -> Address { self.0.address.clone().into() }
+
+    async fn topics(&self) -> Vec<Bytes32> {
+        self.0.topics.iter().map(|t| (*t).into()).collect()
+    }
+
+    async fn data(&self) -> Bytes { self.0.data.clone().into() }
+
+    async fn block_hash(&self) -> Option<Bytes32> {
+        self.0.block_hash.map(Into::into)
+    }
+
+    async fn block(&self, ctx: &Context<'_>) -> Result<Option<Block>> {
+        match self.0.block_hash {
+            Some(hash) => Block::by_hash(ctx, hash).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn epoch_number(&self) -> Option<BigInt> {
+        self.0.epoch_number.map(Into::into)
+    }
+
+    async fn transaction_hash(&self) -> Option<Bytes32> {
+        self.0.transaction_hash.map(Into::into)
+    }
+
+    async fn transaction(
+        &self, ctx: &Context<'_>,
+    ) -> Result<Option<Transaction>> {
+        match self.0.transaction_hash {
+            Some(hash) => Transaction::by_hash(ctx, hash).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn transaction_index(&self) -> Option<BigInt> {
+        self.0.transaction_index.map(Into::into)
+    }
+
+    /// The index of the log in its block.
+    async fn log_index(&self) -> Option<BigInt> {
+        self.0.log_index.map(Into::into)
+    }
+
+    /// The index of the log in its transaction.
+    async fn transaction_log_index(&self) -> Option<BigInt> {
+        self.0.transaction_log_index.map(Into::into)
+    }
+
+    async fn block_timestamp(&self) -> Option<BigInt> {
+        self.0.block_timestamp.map(Into::into)
+    }
+}
@@ -0,0 +1,135 @@
+/// Splits the bytes read from a socket into JSON messages.
+///
+/// A message is a JSON object or array, found by matching the brackets
+/// outside of strings. The scan state is kept between calls, so a large
+/// message arriving in many reads is only scanned once.
+#[derive(Debug, Default)]
+pub(crate) struct StreamCodec {
+    buf: Vec<u8>,
+    /// The position up to which `buf` has been scanned.
+    pos: usize,
+    depth: usize,
+    in_str: bool,
+    escaped: bool,
+}
+
+impl StreamCodec {
+    pub fn extend(&mut self, data: &[u8]) { self.buf.extend_from_slice(data); }
+
+    /// The number of buffered bytes not returned as messages yet.
+    pub fn buffered_len(&self) -> usize { self.buf.len() }
+
+    /// Returns the next complete message, if any.
+    ///
+    /// Bytes that do not start with `{` or `[` are returned as a message up to
+    /// the next whitespace, so that they are answered with a parse error.
+    pub fn next_message(&mut self) -> Option<String> {
+        if self.pos == 0 {
+            let start = self
+                .buf
+                .iter()
+                .position(|b| !b.is_ascii_whitespace())
+                .unwrap_or(self.buf.len());
+            self.buf.drain(..start);
+        }
+
+        let first = *self.buf.first()?;
+        if first != b'{' && first != b'[' {
+            let end = self.buf.iter().position(|b| b.is_ascii_whitespace())?;
+            return Some(self.take(end));
+        }
+
+        while self.pos < self.buf.len() {
+            let b = self.buf[self.pos];
+            self.pos += 1;
+
+            if self.in_str {
+                if self.escaped {
+                    self.escaped = false;
+                } else if b == b'\\' {
+                    self.escaped = true;
+                } else if b == b'"' {
+                    self.in_str = false;
+                }
+                continue;
+            }
+
+            match b {
+                b'"' => self.in_str = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        let end = self.pos;
+                        return Some(self.take(end));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn take(&mut self, end: usize) -> String {
+        let message: Vec<u8> = self.buf.drain(..end).collect();
+        self.pos = 0;
+        self.depth = 0;
+        self.in_str = false;
+        self.escaped = false;
+        String::from_utf8_lossy(&message).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamCodec;
+
+    #[test]
+    fn test_split_messages() {
+        let mut codec = StreamCodec::default();
+        codec.extend(
+            br#" {"id":1,"method":"a"}
+[{"id":2},{"id":3}]{"id":4,"#,
+        );
+
+        assert_eq!(
+            codec.next_message().as_deref(),
+            Some(r#"{"id":1,"method":"a"}"#)
+        );
+        assert_eq!(
+            codec.next_message().as_deref(),
+            Some(r#"[{"id":2},{"id":3}]"#)
+        );
+        assert_eq!(codec.next_message(), None);
+
+        codec.extend(br#""method":"b"}"#);
+        assert_eq!(
+            codec.next_message().as_deref(),
+            Some(r#"{"id":4,"method":"b"}"#)
+        );
+        assert_eq!(codec.buffered_len(), 0);
+    }
+
+    #[test]
+    fn test_brackets_in_strings() {
+        let mut codec = StreamCodec::default();
+        codec.extend(br#"{"params":["}\"]{",""]}"#);
+
+        assert_eq!(
+            codec.next_message().as_deref(),
+            Some(r#"{"params":["}\"]{",""]}"#)
+        );
+    }
+
+    #[test]
+    fn test_invalid_message() {
+        let mut codec = StreamCodec::default();
+        codec.extend(b"hello");
+        assert_eq!(codec.next_message(), None);
+
+        codec.extend(b"\n{}");
+        assert_eq!(codec.next_message().as_deref(), Some("hello"));
+        assert_eq!(codec.next_message().as_deref(), Some("{}"));
+    }
+}
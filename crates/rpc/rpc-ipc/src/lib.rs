@@ -0,0 +1,12 @@
+//! JSON-RPC server over a Unix domain socket.
+//!
+//! Messages are JSON objects or arrays sent back to back on the socket,
+//! without any framing. Responses and subscription notifications are written
+//! to the socket one per line.
+
+mod codec;
+mod rpc_service;
+mod server;
+
+pub use rpc_service::RpcService;
+pub use server::{IpcServer, IpcServerBuilder, IpcServerStartError};
@@ -0,0 +1,161 @@
+use futures::future::{self, join_all, BoxFuture, Either};
+use jsonrpsee::{
+    core::middleware::BatchEntry,
+    server::{
+        middleware::rpc::{Batch, Notification, RpcServiceT},
+        BoundedSubscriptions, ConnectionId, IdProvider, MethodCallback,
+        MethodResponse, MethodSink, Methods, SubscriptionState,
+    },
+    types::{
+        error::{reject_too_many_subscriptions, ErrorCode},
+        ErrorObject, Id, Request,
+    },
+};
+use std::{future::Future, sync::Arc};
+
+/// The innermost service of an IPC connection, which dispatches the requests
+/// to the registered methods. The rpc middlewares are layered on top of it.
+#[derive(Clone)]
+pub struct RpcService {
+    conn_id: ConnectionId,
+    methods: Methods,
+    max_response_body_size: usize,
+    bounded_subscriptions: BoundedSubscriptions,
+    sink: MethodSink,
+    id_provider: Arc<dyn IdProvider>,
+}
+
+impl RpcService {
+    pub(crate) fn new(
+        conn_id: ConnectionId, methods: Methods, max_response_body_size: usize,
+        bounded_subscriptions: BoundedSubscriptions, sink: MethodSink,
+        id_provider: Arc<dyn IdProvider>,
+    ) -> Self {
+        Self {
+            conn_id,
+            methods,
+            max_response_body_size,
+            bounded_subscriptions,
+            sink,
+            id_provider,
+        }
+    }
+}
+
+impl RpcServiceT for RpcService {
+    /// The responses of the calls in the batch, in order. Notifications have
+    /// no response.
+    type BatchResponse = Vec<MethodResponse>;
+    type MethodResponse = MethodResponse;
+    type NotificationResponse = ();
+
+    fn call<'a>(
+        &self, req: Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let conn_id = self.conn_id;
+        let max_response_body_size = self.max_response_body_size;
+        let params = req.params();
+        let name = req.method_name();
+        let id = req.id().clone();
+        let extensions = req.extensions.clone();
+
+        let fut: BoxFuture<'a, MethodResponse> =
+            match self.methods.method_with_name(name) {
+                None => Box::pin(future::ready(MethodResponse::error(
+                    id,
+                    ErrorObject::from(ErrorCode::MethodNotFound),
+                ))),
+                Some((_, MethodCallback::Sync(callback))) => {
+                    Box::pin(future::ready(callback(
+                        id,
+                        params,
+                        max_response_body_size,
+                        extensions,
+                    )))
+                }
+                Some((_, MethodCallback::Async(callback))) => callback(
+                    id.into_owned(),
+                    params.into_owned(),
+                    conn_id,
+                    max_response_body_size,
+                    extensions,
+                ),
+                Some((_, MethodCallback::Subscription(callback))) => {
+                    match self.bounded_subscriptions.acquire() {
+                        Some(permit) => {
+                            let conn_state = SubscriptionState {
+                                conn_id,
+                                id_provider: &*self.id_provider,
+                                subscription_permit: permit,
+                            };
+                            callback(
+                                id,
+                                params,
+                                self.sink.clone(),
+                                conn_state,
+                                extensions,
+                            )
+                        }
+                        None => Box::pin(future::ready(MethodResponse::error(
+                            id,
+                            reject_too_many_subscriptions(
+                                self.bounded_subscriptions.max(),
+                            ),
+                        ))),
+                    }
+                }
+                // Unsubscribing is always allowed.
+                Some((_, MethodCallback::Unsubscription(callback))) => {
+                    Box::pin(future::ready(callback(
+                        id,
+                        params,
+                        conn_id,
+                        max_response_body_size,
+                        extensions,
+                    )))
+                }
+            };
+        fut
+    }
+
+    fn batch<'a>(
+        &self, batch: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        let calls: Vec<_> = batch
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Ok(BatchEntry::Call(req)) => Some(Either::Left(self.call(req))),
+                Ok(BatchEntry::Notification(_)) => None,
+                Err(_) => {
+                    Some(Either::Right(future::ready(MethodResponse::error(
+                        Id::Null,
+                        ErrorObject::from(ErrorCode::InvalidRequest),
+                    ))))
+                }
+            })
+            .collect();
+        join_all(calls)
+    }
+
+    fn notification<'a>(
+        &self, _n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        future::ready(())
+    }
+}
+
+/// Returns the id of a request that fails to parse, or `Id::Null` if the id
+/// can not be found either.
+pub(crate) fn request_id(request: &str) -> Id<'static> {
+    #[derive(serde::Deserialize)]
+    struct WithId<'a> {
+        #[serde(borrow)]
+        id: Option<Id<'a>>,
+    }
+
+    serde_json::from_str::<WithId>(request)
+        .ok()
+        .and_then(|r| r.id)
+        .map(Id::into_owned)
+        .unwrap_or(Id::Null)
+}
@@ -0,0 +1,578 @@
+use crate::{
+    codec::StreamCodec,
+    rpc_service::{request_id, RpcService},
+};
+use futures::{stream::FuturesUnordered, StreamExt};
+use jsonrpsee::{
+    core::middleware::BatchEntry,
+    server::{
+        middleware::rpc::{Batch, RpcServiceBuilder, RpcServiceT},
+        stop_channel, BoundedSubscriptions, IdProvider, MethodResponse,
+        MethodSink, Methods, RandomIntegerIdProvider, ServerHandle, StopHandle,
+    },
+    types::{
+        error::{reject_too_big_request, ErrorCode},
+        ErrorObject, Id, Notification, Request,
+    },
+};
+use log::{debug, warn};
+use serde_json::value::RawValue;
+use std::{fs, io, sync::Arc};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    sync::{mpsc, Semaphore},
+};
+use tower::{layer::util::Identity, Layer};
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Errors when starting the IPC server.
+#[derive(Debug, thiserror::Error)]
+pub enum IpcServerStartError {
+    #[error("failed to bind IPC socket {path}: {source}")]
+    Bind { path: String, source: io::Error },
+    #[error("failed to set permissions of IPC socket {path}: {source}")]
+    Permissions { path: String, source: io::Error },
+    #[error("IPC is only supported on unix platforms")]
+    Unsupported,
+}
+
+#[derive(Debug, Clone)]
+struct Settings {
+    max_request_body_size: u32,
+    max_response_body_size: u32,
+    max_connections: u32,
+    max_subscriptions_per_connection: u32,
+    message_buffer_capacity: u32,
+    permissions: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            max_request_body_size: 10 * 1024 * 1024,
+            max_response_body_size: 10 * 1024 * 1024,
+            max_connections: 100,
+            max_subscriptions_per_connection: 1024,
+            message_buffer_capacity: 1024,
+            permissions: 0o600,
+        }
+    }
+}
+
+/// Builder to configure and create an [`IpcServer`].
+///
+/// The limits mirror the ones of the jsonrpsee http and ws servers.
+pub struct IpcServerBuilder<RpcMiddleware = Identity> {
+    settings: Settings,
+    id_provider: Arc<dyn IdProvider>,
+    rpc_middleware: RpcServiceBuilder<RpcMiddleware>,
+}
+
+impl Default for IpcServerBuilder {
+    fn default() -> Self {
+        Self {
+            settings: Settings::default(),
+            id_provider: Arc::new(RandomIntegerIdProvider),
+            rpc_middleware: RpcServiceBuilder::new(),
+        }
+    }
+}
+
+impl<RpcMiddleware> IpcServerBuilder<RpcMiddleware> {
+    pub fn max_request_body_size(mut self, size: u32) -> Self {
+        self.settings.max_request_body_size = size;
+        self
+    }
+
+    pub fn max_response_body_size(mut self, size: u32) -> Self {
+        self.settings.max_response_body_size = size;
+        self
+    }
+
+    pub fn max_connections(mut self, max: u32) -> Self {
+        self.settings.max_connections = max;
+        self
+    }
+
+    pub fn max_subscriptions_per_connection(mut self, max: u32) -> Self {
+        self.settings.max_subscriptions_per_connection = max;
+        self
+    }
+
+    /// The number of responses and notifications buffered for a connection
+    /// before the server stops reading from it.
+    pub fn set_message_buffer_capacity(mut self, capacity: u32) -> Self {
+        self.settings.message_buffer_capacity = capacity;
+        self
+    }
+
+    /// Sets the unix file mode of the socket, `0o600` by default so that only
+    /// the owner of the node can connect.
+    pub fn permissions(mut self, mode: u32) -> Self {
+        self.settings.permissions = mode;
+        self
+    }
+
+    pub fn set_id_provider<I: IdProvider + 'static>(
+        mut self, id_provider: I,
+    ) -> Self {
+        self.id_provider = Arc::new(id_provider);
+        self
+    }
+
+    /// Configures the rpc middlewares, which wrap the [`RpcService`] of each
+    /// connection.
+    pub fn set_rpc_middleware<T>(
+        self, rpc_middleware: RpcServiceBuilder<T>,
+    ) -> IpcServerBuilder<T> {
+        IpcServerBuilder {
+            settings: self.settings,
+            id_provider: self.id_provider,
+            rpc_middleware,
+        }
+    }
+
+    /// Creates the server listening on the socket at `endpoint`.
+    pub fn build(
+        self, endpoint: impl Into<String>,
+    ) -> IpcServer<RpcMiddleware> {
+        IpcServer {
+            endpoint: endpoint.into(),
+            settings: self.settings,
+            id_provider: self.id_provider,
+            rpc_middleware: self.rpc_middleware,
+        }
+    }
+}
+
+/// JSON-RPC server listening on a Unix domain socket.
+pub struct IpcServer<RpcMiddleware = Identity> {
+    endpoint: String,
+    settings: Settings,
+    id_provider: Arc<dyn IdProvider>,
+    rpc_middleware: RpcServiceBuilder<RpcMiddleware>,
+}
+
+impl<RpcMiddleware> IpcServer<RpcMiddleware>
+where
+    RpcMiddleware: Layer<RpcService> + Send + Sync + 'static,
+    RpcMiddleware::Service: RpcServiceT<
+            MethodResponse = MethodResponse,
+            BatchResponse = Vec<MethodResponse>,
+            NotificationResponse = (),
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    /// Returns the path of the socket.
+    pub fn endpoint(&self) -> &str { &self.endpoint }
+
+    /// Binds the socket and serves `methods` in the background until the
+    /// returned handle is stopped or dropped.
+    ///
+    /// A stale socket file at the endpoint, e.g. left by a crashed node, is
+    /// replaced.
+    pub async fn start(
+        self, methods: impl Into<Methods>,
+    ) -> Result<ServerHandle, IpcServerStartError> {
+        let listener = bind(&self.endpoint, self.settings.permissions)?;
+        let (stop_handle, server_handle) = stop_channel();
+        tokio::spawn(self.accept_connections(
+            listener,
+            methods.into(),
+            stop_handle,
+        ));
+        Ok(server_handle)
+    }
+
+    #[cfg(unix)]
+    async fn accept_connections(
+        self, listener: tokio::net::UnixListener, methods: Methods,
+        stop_handle: StopHandle,
+    ) {
+        let settings = &self.settings;
+        let connection_guard =
+            Arc::new(Semaphore::new(settings.max_connections as usize));
+        let stopped = stop_handle.clone().shutdown();
+        tokio::pin!(stopped);
+        let mut next_conn_id = 0usize;
+
+        loop {
+            let stream = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        warn!("Failed to accept IPC connection: {}", e);
+                        continue;
+                    }
+                },
+                _ = &mut stopped => break,
+            };
+
+            let Ok(permit) = connection_guard.clone().try_acquire_owned()
+            else {
+                warn!(
+                    "IPC connection rejected, the limit {} is reached",
+                    settings.max_connections
+                );
+                continue;
+            };
+
+            let (tx, rx) =
+                mpsc::channel(settings.message_buffer_capacity as usize);
+            let service = self.rpc_middleware.service(RpcService::new(
+                next_conn_id.into(),
+                methods.clone(),
+                settings.max_response_body_size as usize,
+                BoundedSubscriptions::new(
+                    settings.max_subscriptions_per_connection,
+                ),
+                MethodSink::new_with_limit(tx, settings.max_response_body_size),
+                self.id_provider.clone(),
+            ));
+            next_conn_id = next_conn_id.wrapping_add(1);
+
+            let (reader, writer) = stream.into_split();
+            let stop_handle = stop_handle.clone();
+            let max_request_body_size = settings.max_request_body_size;
+            tokio::spawn(async move {
+                serve_connection(
+                    reader,
+                    writer,
+                    service,
+                    rx,
+                    stop_handle,
+                    max_request_body_size,
+                )
+                .await;
+                drop(permit);
+            });
+        }
+
+        if let Err(e) = fs::remove_file(&self.endpoint) {
+            debug!("Failed to remove IPC socket {}: {}", self.endpoint, e);
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn accept_connections(
+        self, _listener: (), _methods: Methods, _stop_handle: StopHandle,
+    ) {
+    }
+}
+
+/// Binds the socket at `path` with the file mode `permissions`.
+///
+/// The socket is bound in a private directory and linked to `path` once its
+/// permissions are set, so no other user can connect to it in between. An
+/// existing file at `path` other than a socket is never replaced.
+#[cfg(unix)]
+fn bind(
+    path: &str, permissions: u32,
+) -> Result<tokio::net::UnixListener, IpcServerStartError> {
+    use std::{
+        ffi::OsString,
+        os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+        path::Path,
+    };
+
+    let bind_error = |source| IpcServerStartError::Bind {
+        path: path.to_string(),
+        source,
+    };
+    let permissions_error = |source| IpcServerStartError::Permissions {
+        path: path.to_string(),
+        source,
+    };
+
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if metadata.file_type().is_socket() {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    let endpoint = Path::new(path);
+    let file_name = endpoint.file_name().ok_or_else(|| {
+        bind_error(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the path is not a file",
+        ))
+    })?;
+    let mut staging_name = OsString::from(".");
+    staging_name.push(file_name);
+    staging_name.push(format!(".{}", std::process::id()));
+    let staging_dir = endpoint.with_file_name(staging_name);
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging_dir)
+        .map_err(bind_error)?;
+
+    let staging_path = staging_dir.join(file_name);
+    let bound = tokio::net::UnixListener::bind(&staging_path)
+        .map_err(bind_error)
+        .and_then(|listener| {
+            fs::set_permissions(
+                &staging_path,
+                fs::Permissions::from_mode(permissions),
+            )
+            .map_err(permissions_error)?;
+            fs::hard_link(&staging_path, endpoint).map_err(bind_error)?;
+            Ok(listener)
+        });
+    let _ = fs::remove_file(&staging_path);
+    let _ = fs::remove_dir(&staging_dir);
+    bound
+}
+
+#[cfg(not(unix))]
+fn bind(_path: &str, _permissions: u32) -> Result<(), IpcServerStartError> {
+    Err(IpcServerStartError::Unsupported)
+}
+
+/// Reads the messages of a connection and writes back the responses, in the
+/// order they complete, and the subscription notifications.
+async fn serve_connection<R, W, S>(
+    mut reader: R, mut writer: W, service: S,
+    mut notifications: mpsc::Receiver<Box<RawValue>>, stop_handle: StopHandle,
+    max_request_body_size: u32,
+) where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+    S: RpcServiceT<
+            MethodResponse = MethodResponse,
+            BatchResponse = Vec<MethodResponse>,
+            NotificationResponse = (),
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    let max_size = max_request_body_size as usize;
+    let mut codec = StreamCodec::default();
+    let mut read_buf = vec![0u8; READ_BUFFER_SIZE];
+    let mut pending = FuturesUnordered::new();
+    let stopped = stop_handle.shutdown();
+    tokio::pin!(stopped);
+
+    loop {
+        let to_write = tokio::select! {
+            read = reader.read(&mut read_buf) => {
+                let n = match read {
+                    Ok(0) => break,
+                    Ok(n) => n,
+                    Err(e) => {
+                        debug!("Failed to read IPC connection: {}", e);
+                        break;
+                    }
+                };
+                codec.extend(&read_buf[..n]);
+
+                let mut too_big = codec.buffered_len() > max_size;
+                while let Some(message) = codec.next_message() {
+                    if message.len() > max_size {
+                        too_big = true;
+                        break;
+                    }
+                    pending.push(process_message(message, service.clone()));
+                }
+                if too_big {
+                    // The rest of the stream can not be split reliably.
+                    let response = MethodResponse::error(
+                        Id::Null,
+                        reject_too_big_request(max_request_body_size),
+                    );
+                    let _ = write_message(
+                        &mut writer,
+                        response.as_json().get(),
+                    )
+                    .await;
+                    break;
+                }
+                continue;
+            }
+            Some(response) = pending.next(), if !pending.is_empty() => {
+                match response {
+                    Some(response) => response,
+                    None => continue,
+                }
+            }
+            Some(notification) = notifications.recv() => {
+                notification.get().to_string()
+            }
+            _ = &mut stopped => break,
+        };
+
+        if let Err(e) = write_message(&mut writer, &to_write).await {
+            debug!("Failed to write IPC connection: {}", e);
+            break;
+        }
+    }
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(
+    writer: &mut W, message: &str,
+) -> io::Result<()> {
+    writer.write_all(message.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await
+}
+
+/// Handles a single request or a batch, and returns the response to write.
+///
+/// Notifications, i.e. requests without an id, get no response.
+async fn process_message<S>(message: String, service: S) -> Option<String>
+where S: RpcServiceT<
+        MethodResponse = MethodResponse,
+        BatchResponse = Vec<MethodResponse>,
+        NotificationResponse = (),
+    > {
+    if !message.starts_with('[') {
+        return match serde_json::from_str::<Request>(&message) {
+            Ok(request) => Some(service.call(request).await),
+            Err(_) if is_notification(&message) => None,
+            Err(_) => Some(invalid_request(&message)),
+        }
+        .map(|response| response.as_json().get().to_string());
+    }
+
+    let entries = match serde_json::from_str::<Vec<&RawValue>>(&message) {
+        Ok(entries) if !entries.is_empty() => entries,
+        Ok(_) => {
+            return Some(to_json(&invalid_request(&message)));
+        }
+        Err(_) => {
+            let response = MethodResponse::error(
+                Id::Null,
+                ErrorObject::from(ErrorCode::ParseError),
+            );
+            return Some(to_json(&response));
+        }
+    };
+
+    let mut calls = Vec::new();
+    let mut responses = Vec::new();
+    for entry in entries {
+        match serde_json::from_str::<Request>(entry.get()) {
+            Ok(request) => calls.push(Ok(BatchEntry::Call(request))),
+            Err(_) if is_notification(entry.get()) => {}
+            Err(_) => responses.push(invalid_request(entry.get())),
+        }
+    }
+    if !calls.is_empty() {
+        let mut call_responses = service.batch(Batch::from(calls)).await;
+        call_responses.append(&mut responses);
+        responses = call_responses;
+    }
+
+    if responses.is_empty() {
+        return None;
+    }
+    let responses: Vec<_> =
+        responses.iter().map(|r| r.as_json().get()).collect();
+    Some(format!("[{}]", responses.join(",")))
+}
+
+fn is_notification(message: &str) -> bool {
+    serde_json::from_str::<Notification<Option<&RawValue>>>(message).is_ok()
+}
+
+fn invalid_request(message: &str) -> MethodResponse {
+    let code = if serde_json::from_str::<&RawValue>(message).is_ok() {
+        ErrorCode::InvalidRequest
+    } else {
+        ErrorCode::ParseError
+    };
+    MethodResponse::error(request_id(message), ErrorObject::from(code))
+}
+
+fn to_json(response: &MethodResponse) -> String {
+    response.as_json().get().to_string()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::IpcServerBuilder;
+    use jsonrpsee::{types::ErrorObjectOwned, RpcModule};
+    use serde_json::{json, Value};
+    use std::{
+        fs,
+        os::unix::fs::{FileTypeExt, PermissionsExt},
+    };
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::UnixStream,
+    };
+
+    fn methods() -> RpcModule<()> {
+        let mut module = RpcModule::new(());
+        module
+            .register_method("test_echo", |params, _, _| {
+                params.one::<u64>().map_err(ErrorObjectOwned::from)
+            })
+            .unwrap();
+        module
+    }
+
+    #[tokio::test]
+    async fn test_ipc_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.ipc");
+        // A stale socket left by a crashed node is replaced.
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let handle = IpcServerBuilder::default()
+            .build(path.to_str().unwrap())
+            .start(methods())
+            .await
+            .unwrap();
+        let metadata = fs::metadata(&path).unwrap();
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        // The directory to bind the socket is removed.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let (reader, mut writer) =
+            UnixStream::connect(&path).await.unwrap().into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let request =
+            r#"{"jsonrpc":"2.0","id":1,"method":"test_echo","params":[7]}"#;
+        writer.write_all(request.as_bytes()).await.unwrap();
+        let response: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap())
+                .unwrap();
+        assert_eq!(response, json!({"jsonrpc": "2.0", "id": 1, "result": 7}));
+
+        // The notification in the batch gets no response.
+        let request = r#"[
+            {"jsonrpc":"2.0","id":2,"method":"test_echo","params":[8]},
+            {"jsonrpc":"2.0","method":"test_echo","params":[9]}
+        ]"#;
+        writer.write_all(request.as_bytes()).await.unwrap();
+        let response: Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap())
+                .unwrap();
+        assert_eq!(response, json!([{"jsonrpc": "2.0", "id": 2, "result": 8}]));
+
+        handle.stop().unwrap();
+        handle.stopped().await;
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_ipc_server_keeps_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.ipc");
+        fs::write(&path, b"data").unwrap();
+
+        let result = IpcServerBuilder::default()
+            .permissions(0o660)
+            .build(path.to_str().unwrap())
+            .start(methods())
+            .await;
+        assert!(result.is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"data");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -39,9 +39,9 @@
 //! may be handy in some scenarios such CORS but if you want to access
 //! to the actual JSON-RPC details this is the middleware to use.
 
-use std::{future::Future, net::SocketAddr};
+use std::{future::Future, net::SocketAddr, sync::Arc};
 
-use cfx_rpc_middlewares::{Metrics, Throttle};
+use cfx_rpc_middlewares::{Metrics, Throttle, ThrottleManager};
 use jsonrpsee::{
     core::client::ClientT,
     rpc_params,
@@ -118,10 +118,12 @@ async fn run_server() -> anyhow::Result<SocketAddr> {
 
     debug!("throttling config path: {:?}", config_path);
 
+    let throttle = Arc::new(
+        ThrottleManager::load(config_path.to_str().unwrap(), "test")
+            .map_err(anyhow::Error::msg)?,
+    );
     let rpc_middleware = RpcServiceBuilder::new()
-        .layer_fn(move |s| {
-            Throttle::new(Some(config_path.to_str().unwrap()), "test", s)
-        })
+        .layer_fn(move |s| Throttle::new(throttle.clone(), s))
         .layer_fn(|s| Metrics::new(s));
 
     let server = Server::builder()
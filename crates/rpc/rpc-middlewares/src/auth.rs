@@ -0,0 +1,419 @@
+use cfx_rpc_utils::error::jsonrpsee_error_helpers::unauthorized;
+use futures::future::{self, Either};
+use jsonrpsee::{
+    core::middleware::{BatchEntry, BatchEntryErr},
+    server::{
+        middleware::rpc::{Batch, Notification, RpcServiceT},
+        HttpRequest, MethodResponse,
+    },
+};
+use jsonrpsee_types::Request;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use log::debug;
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::{File, OpenOptions},
+    future::Future,
+    io::{self, Read, Write},
+    path::Path,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tower::{Layer, Service};
+
+const JWT_SECRET_LEN: usize = 32;
+
+/// The maximum difference in seconds between the `iat` claim of a token and
+/// the local time.
+const JWT_IAT_LEEWAY: u64 = 60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum JwtError {
+    #[error("failed to access JWT secret file {path}: {source}")]
+    Io { path: String, source: io::Error },
+    #[error("JWT secret must be {JWT_SECRET_LEN} hex encoded bytes")]
+    InvalidSecret,
+    #[error(
+        "JWT secret file {path} is accessible by other users (mode {mode:o}), \
+         it should only be readable by the owner"
+    )]
+    InsecurePermissions { path: String, mode: u32 },
+    #[error("invalid JWT: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+    #[error("JWT issued at {0} is more than {JWT_IAT_LEEWAY}s away from now")]
+    InvalidIssuedAt(u64),
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    iat: u64,
+}
+
+/// The secret shared with the clients to sign and validate HS256 JWTs.
+#[derive(Clone)]
+pub struct JwtSecret([u8; JWT_SECRET_LEN]);
+
+impl JwtSecret {
+    pub fn random() -> Self { Self(rand::random()) }
+
+    /// Parses the hex encoded secret, with or without the `0x` prefix.
+    pub fn from_hex(hex: &str) -> Result<Self, JwtError> {
+        let hex = hex.trim();
+        let bytes = hex::decode(hex.strip_prefix("0x").unwrap_or(hex))
+            .map_err(|_| JwtError::InvalidSecret)?;
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| JwtError::InvalidSecret)
+    }
+
+    /// Reads the hex encoded secret from the file. If the file does not
+    /// exist, it is created with a random secret, readable only by the owner.
+    ///
+    /// On unix, a file accessible by the group or other users is refused.
+    pub fn from_file(path: &Path) -> Result<Self, JwtError> {
+        let io_error = |source| JwtError::Io {
+            path: path.display().to_string(),
+            source,
+        };
+
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        match options.open(path) {
+            Ok(mut file) => {
+                let secret = Self::random();
+                file.write_all(hex::encode(secret.0).as_bytes())
+                    .map_err(io_error)?;
+                return Ok(secret);
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(io_error(e)),
+        }
+
+        let mut file = File::open(path).map_err(io_error)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode =
+                file.metadata().map_err(io_error)?.permissions().mode() & 0o777;
+            if mode & 0o077 != 0 {
+                return Err(JwtError::InsecurePermissions {
+                    path: path.display().to_string(),
+                    mode,
+                });
+            }
+        }
+        let mut hex = String::new();
+        file.read_to_string(&mut hex).map_err(io_error)?;
+        Self::from_hex(&hex)
+    }
+
+    /// Validates the signature of the token, and that it is issued within
+    /// [`JWT_IAT_LEEWAY`] seconds of the local time. The `exp` claim is
+    /// optional, but checked if present.
+    pub fn validate(&self, token: &str) -> Result<(), JwtError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_required_spec_claims(&["iat"]);
+        let claims = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(&self.0),
+            &validation,
+        )?
+        .claims;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if now.abs_diff(claims.iat) > JWT_IAT_LEEWAY {
+            return Err(JwtError::InvalidIssuedAt(claims.iat));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for JwtSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("JwtSecret(..)")
+    }
+}
+
+/// Set in the extensions of the requests carrying a valid JWT.
+#[derive(Clone, Copy, Debug)]
+pub struct Authenticated;
+
+/// Http middleware which validates the bearer token in the `Authorization`
+/// header and marks the request as [`Authenticated`].
+///
+/// Requests without a valid token are passed on unmarked, the [`Auth`] rpc
+/// middleware decides which methods they can call. For ws, the token of the
+/// upgrade request applies to the whole connection.
+#[derive(Clone, Debug)]
+pub struct AuthLayer {
+    secret: Option<Arc<JwtSecret>>,
+}
+
+impl AuthLayer {
+    pub fn new(secret: Option<JwtSecret>) -> Self {
+        Self {
+            secret: secret.map(Arc::new),
+        }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService {
+            secret: self.secret.clone(),
+            inner,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AuthService<S> {
+    secret: Option<Arc<JwtSecret>>,
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for AuthService<S>
+where S: Service<HttpRequest<B>>
+{
+    type Error = S::Error;
+    type Future = S::Future;
+    type Response = S::Response;
+
+    fn poll_ready(
+        &mut self, cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<B>) -> Self::Future {
+        if let Some(secret) = &self.secret {
+            let token = req
+                .headers()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "));
+            if let Some(token) = token {
+                match secret.validate(token.trim()) {
+                    Ok(()) => {
+                        req.extensions_mut().insert(Authenticated);
+                    }
+                    Err(e) => debug!("auth middleware: {}", e),
+                }
+            }
+        }
+        self.inner.call(req)
+    }
+}
+
+/// Rejects the calls to the protected methods from requests which are not
+/// [`Authenticated`].
+#[derive(Clone)]
+pub struct Auth<S> {
+    service: S,
+    protected_methods: Arc<HashSet<String>>,
+}
+
+impl<S> Auth<S> {
+    pub fn new(protected_methods: Arc<HashSet<String>>, service: S) -> Self {
+        Self {
+            service,
+            protected_methods,
+        }
+    }
+
+    fn is_allowed(&self, req: &Request) -> bool {
+        !self.protected_methods.contains(req.method_name())
+            || req.extensions().get::<Authenticated>().is_some()
+    }
+}
+
+impl<S> RpcServiceT for Auth<S>
+where S: RpcServiceT<MethodResponse = MethodResponse>
+        + Send
+        + Sync
+        + Clone
+        + 'static
+{
+    type BatchResponse = S::BatchResponse;
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+
+    fn call<'a>(
+        &self, req: Request<'a>,
+    ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        if self.is_allowed(&req) {
+            Either::Left(self.service.call(req))
+        } else {
+            debug!("auth interceptor: method `{}` unauthorized", req.method);
+            let err = unauthorized(req.method_name());
+            Either::Right(future::ready(MethodResponse::error(req.id, err)))
+        }
+    }
+
+    fn batch<'a>(
+        &self, mut batch: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        for entry in batch.iter_mut() {
+            let err = match entry {
+                Ok(BatchEntry::Call(req)) if !self.is_allowed(req) => {
+                    BatchEntryErr::new(
+                        req.id.clone(),
+                        unauthorized(req.method_name()),
+                    )
+                }
+                _ => continue,
+            };
+            *entry = Err(err);
+        }
+        self.service.batch(batch)
+    }
+
+    fn notification<'a>(
+        &self, n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        // notifications have no response and are not executed
+        self.service.notification(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+    use std::{convert::Infallible, fs};
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    fn token(secret: &JwtSecret, claims: serde_json::Value) -> String {
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(&secret.0),
+        )
+        .unwrap()
+    }
+
+    /// Returns whether the request is marked as [`Authenticated`].
+    #[derive(Clone)]
+    struct IsAuthenticated;
+
+    impl Service<HttpRequest<()>> for IsAuthenticated {
+        type Error = Infallible;
+        type Future = future::Ready<Result<bool, Infallible>>;
+        type Response = bool;
+
+        fn poll_ready(
+            &mut self, _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, req: HttpRequest<()>) -> Self::Future {
+            future::ready(Ok(req.extensions().get::<Authenticated>().is_some()))
+        }
+    }
+
+    fn is_authenticated(secret: &JwtSecret, header: Option<&str>) -> bool {
+        let mut service =
+            AuthLayer::new(Some(secret.clone())).layer(IsAuthenticated);
+        let mut req = HttpRequest::new(());
+        if let Some(header) = header {
+            req.headers_mut()
+                .insert("authorization", header.parse().unwrap());
+        }
+        futures::executor::block_on(service.call(req)).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let secret = JwtSecret::random();
+        let valid = token(&secret, json!({ "iat": now() }));
+        assert!(secret.validate(&valid).is_ok());
+        let with_exp =
+            token(&secret, json!({ "iat": now(), "exp": now() + 60 }));
+        assert!(secret.validate(&with_exp).is_ok());
+
+        // The `iat` claim is required.
+        let missing_iat = token(&secret, json!({ "exp": now() + 60 }));
+        assert!(matches!(
+            secret.validate(&missing_iat),
+            Err(JwtError::InvalidToken(_))
+        ));
+        // The token is issued too long ago, or in the future.
+        for iat in [now() - 2 * JWT_IAT_LEEWAY, now() + 2 * JWT_IAT_LEEWAY] {
+            let token = token(&secret, json!({ "iat": iat }));
+            assert!(matches!(
+                secret.validate(&token),
+                Err(JwtError::InvalidIssuedAt(_))
+            ));
+        }
+        let expired =
+            token(&secret, json!({ "iat": now(), "exp": now() - 120 }));
+        assert!(secret.validate(&expired).is_err());
+        // The token is signed by another secret.
+        let bad_signature =
+            token(&JwtSecret::random(), json!({ "iat": now() }));
+        assert!(matches!(
+            secret.validate(&bad_signature),
+            Err(JwtError::InvalidToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_auth_layer() {
+        let secret = JwtSecret::random();
+        let valid = token(&secret, json!({ "iat": now() }));
+        assert!(is_authenticated(
+            &secret,
+            Some(&format!("Bearer {}", valid))
+        ));
+
+        assert!(!is_authenticated(&secret, None));
+        assert!(!is_authenticated(&secret, Some(&valid)));
+        let bad_signature =
+            token(&JwtSecret::random(), json!({ "iat": now() }));
+        assert!(!is_authenticated(
+            &secret,
+            Some(&format!("Bearer {}", bad_signature))
+        ));
+    }
+
+    #[test]
+    fn test_secret_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+
+        let secret = JwtSecret::from_file(&path).unwrap();
+        assert_eq!(JwtSecret::from_file(&path).unwrap().0, secret.0);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o644))
+                .unwrap();
+            assert!(matches!(
+                JwtSecret::from_file(&path),
+                Err(JwtError::InsecurePermissions { mode: 0o644, .. })
+            ));
+        }
+    }
+}
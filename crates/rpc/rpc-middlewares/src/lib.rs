@@ -1,7 +1,13 @@
+mod auth;
 mod log;
 mod metrics;
 mod throttle;
 
+pub use auth::{
+    Auth, AuthLayer, AuthService, Authenticated, JwtError, JwtSecret,
+};
 pub use log::Logger;
 pub use metrics::Metrics;
-pub use throttle::Throttle;
+pub use throttle::{
+    ApiKey, ApiKeyLayer, ApiKeyService, RemoteAddr, Throttle, ThrottleManager,
+};
@@ -8,53 +8,247 @@ use jsonrpsee::{
     core::RpcResult,
     server::{
         middleware::rpc::{Batch, Notification, RpcServiceT},
-        MethodResponse,
+        HttpRequest, MethodResponse,
     },
 };
 use jsonrpsee_types::Request;
 use log::debug;
-use std::future::Future;
-use throttling::token_bucket::{ThrottleResult, TokenBucketManager};
+use lru_time_cache::LruCache;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, HashSet},
+    fs::read_to_string,
+    future::Future,
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use throttling::token_bucket::{
+    ThrottleResult, TokenBucket, TokenBucketManager,
+};
+use tower::{Layer, Service};
 
-#[derive(Clone)]
-pub struct Throttle<S> {
-    service: S,
-    manager: TokenBucketManager,
+const DEFAULT_MAX_CLIENTS: usize = 10_000;
+const DEFAULT_IDLE_SECS: u64 = 600;
+
+/// The remote address of the connection of a request, set in the request
+/// extensions by the server.
+#[derive(Clone, Copy, Debug)]
+pub struct RemoteAddr(pub SocketAddr);
+
+/// The api key sent in the header configured by `api_key_header`, set in the
+/// request extensions by [`ApiKeyLayer`].
+#[derive(Clone, Debug)]
+pub struct ApiKey(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum ClientKey {
+    ApiKey(String),
+    Ip(IpAddr),
+    Unknown,
 }
 
-impl<S> Throttle<S> {
-    pub fn new(file: Option<&str>, section: &str, s: S) -> Self {
-        let manager = match file {
-            Some(file) => TokenBucketManager::load(file, Some(section))
-                .expect("invalid throttling configuration file"),
-            None => TokenBucketManager::default(),
-        };
+impl ClientKey {
+    /// Identifies a client by its ip address. The IPv6 clients are
+    /// identified by their /64 prefix, usually assigned to a single host.
+    fn ip(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+                Some(ip) => ClientKey::Ip(IpAddr::V4(ip)),
+                None => ClientKey::Ip(IpAddr::V6(Ipv6Addr::from(
+                    u128::from(ip) & !(u64::MAX as u128),
+                ))),
+            },
+            ip => ClientKey::Ip(ip),
+        }
+    }
+}
 
-        Throttle {
-            service: s,
-            manager,
+struct ClientBuckets {
+    methods: TokenBucketManager,
+    total: Option<Mutex<TokenBucket>>,
+}
+
+/// The token buckets of the rpc clients, shared by all connections of a
+/// server.
+///
+/// A client is identified by its api key if `api_key_header` is configured
+/// and the header is sent with one of the `api_keys`, otherwise by its ip
+/// address, or its /64 prefix for IPv6. The buckets are configured in a
+/// throttling TOML section, e.g. `[rpc]`:
+///   - `<method>="<bucket>"`: every client has its own bucket for the method.
+///   - `[rpc.clients]` holds the options:
+///     - `api_key_header`: the header with the api key of the client.
+///     - `api_keys`: the list of the known api keys. The clients sending
+///       another key are identified by their ip address.
+///     - `max_clients` and `idle_secs`: the buckets of the least recently seen
+///       clients are dropped when there are more than `max_clients` clients, or
+///       after being idle for `idle_secs` seconds.
+///     - `total="<bucket>"`: a bucket shared by all methods of a client.
+///   - `[rpc.clients.costs]`: `<method>=<cost>`, the number of tokens taken
+///     from the `total` bucket per call of the method, 1 by default.
+pub struct ThrottleManager {
+    templates: TokenBucketManager,
+    total: Option<TokenBucket>,
+    costs: HashMap<String, u64>,
+    api_key_header: Option<String>,
+    api_keys: HashSet<String>,
+    clients: Mutex<LruCache<ClientKey, Arc<ClientBuckets>>>,
+}
+
+impl Default for ThrottleManager {
+    fn default() -> Self {
+        Self::new(
+            TokenBucketManager::default(),
+            DEFAULT_MAX_CLIENTS,
+            DEFAULT_IDLE_SECS,
+        )
+    }
+}
+
+impl ThrottleManager {
+    fn new(
+        templates: TokenBucketManager, max_clients: usize, idle_secs: u64,
+    ) -> Self {
+        ThrottleManager {
+            templates,
+            total: None,
+            costs: HashMap::new(),
+            api_key_header: None,
+            api_keys: HashSet::new(),
+            clients: Mutex::new(LruCache::with_expiry_duration_and_capacity(
+                Duration::from_secs(idle_secs),
+                max_clients,
+            )),
         }
     }
 
-    pub fn before(&self, name: &String) -> RpcResult<()> {
-        let bucket = match self.manager.get(name) {
-            Some(bucket) => bucket,
-            None => return Ok(()),
+    /// Loads the buckets of the section from the throttling TOML file.
+    pub fn load(file: &str, section: &str) -> Result<Self, String> {
+        let templates = TokenBucketManager::load(file, Some(section))?;
+
+        let content = read_to_string(file)
+            .map_err(|e| format!("failed to read toml file: {:?}", e))?;
+        let toml_val = content
+            .parse::<toml::Value>()
+            .map_err(|e| format!("failed to parse toml file: {:?}", e))?;
+        let options = match toml_val.get(section).and_then(|v| v.get("clients"))
+        {
+            Some(options) => options,
+            None => {
+                return Ok(Self::new(
+                    templates,
+                    DEFAULT_MAX_CLIENTS,
+                    DEFAULT_IDLE_SECS,
+                ))
+            }
+        };
+
+        let integer = |key: &str, default: u64| match options.get(key) {
+            Some(v) => v
+                .as_integer()
+                .filter(|n| *n > 0)
+                .map(|n| n as u64)
+                .ok_or_else(|| format!("invalid {}.clients.{}", section, key)),
+            None => Ok(default),
+        };
+        let string = |key: &str| match options.get(key) {
+            Some(v) => v
+                .as_str()
+                .map(|s| Some(s.to_string()))
+                .ok_or_else(|| format!("invalid {}.clients.{}", section, key)),
+            None => Ok(None),
         };
 
-        let result = bucket.lock().throttle_default();
+        let mut manager = Self::new(
+            templates,
+            integer("max_clients", DEFAULT_MAX_CLIENTS as u64)? as usize,
+            integer("idle_secs", DEFAULT_IDLE_SECS)?,
+        );
+        manager.api_key_header = string("api_key_header")?;
+        if let Some(api_keys) = options.get("api_keys") {
+            let invalid = || format!("invalid {}.clients.api_keys", section);
+            for key in api_keys.as_array().ok_or_else(invalid)? {
+                let key = key.as_str().ok_or_else(invalid)?;
+                manager.api_keys.insert(key.to_string());
+            }
+        }
+        manager.total = string("total")?
+            .map(|bucket| TokenBucket::from_str(&bucket))
+            .transpose()?;
+        if let Some(costs) = options.get("costs") {
+            let costs = costs
+                .as_table()
+                .ok_or_else(|| format!("invalid {}.clients.costs", section))?;
+            for (method, cost) in costs {
+                let cost = cost
+                    .as_integer()
+                    .filter(|n| *n >= 0)
+                    .ok_or_else(|| format!("invalid cost of {}", method))?;
+                manager.costs.insert(method.clone(), cost as u64);
+            }
+        }
+
+        Ok(manager)
+    }
+
+    /// The header with the api key of the clients, if configured.
+    pub fn api_key_header(&self) -> Option<&str> {
+        self.api_key_header.as_deref()
+    }
+
+    fn client_buckets(&self, client: ClientKey) -> Arc<ClientBuckets> {
+        let mut clients = self.clients.lock();
+        if let Some(buckets) = clients.get(&client) {
+            return buckets.clone();
+        }
+
+        let buckets = Arc::new(ClientBuckets {
+            methods: self.templates.renew(),
+            total: self.total.as_ref().map(|t| Mutex::new(t.renew())),
+        });
+        clients.insert(client, buckets.clone());
+        buckets
+    }
+
+    fn client_key(
+        &self, api_key: Option<&ApiKey>, remote_addr: Option<&RemoteAddr>,
+    ) -> ClientKey {
+        match (api_key, remote_addr) {
+            (Some(ApiKey(key)), _) if self.api_keys.contains(key) => {
+                ClientKey::ApiKey(key.clone())
+            }
+            (_, Some(RemoteAddr(addr))) => ClientKey::ip(addr.ip()),
+            (_, None) => ClientKey::Unknown,
+        }
+    }
+
+    /// Throttles a call of `method` by the client identified by its known
+    /// [`ApiKey`], or else by its [`RemoteAddr`]. The calls of the JSON-RPC
+    /// servers are throttled by the [`Throttle`] middleware, this is for the
+    /// other servers sharing the buckets, e.g. GraphQL.
+    pub fn throttle_call(
+        &self, api_key: Option<&ApiKey>, remote_addr: Option<&RemoteAddr>,
+        method: &str,
+    ) -> RpcResult<()> {
+        let client = self.client_key(api_key, remote_addr);
 
-        match result {
+        match self.throttle(client.clone(), method) {
             ThrottleResult::Success => Ok(()),
             ThrottleResult::Throttled(wait_time) => {
-                debug!("RPC {} throttled in {:?}", name, wait_time);
+                debug!(
+                    "RPC {} of {:?} throttled in {:?}",
+                    method, client, wait_time
+                );
                 let err = request_rejected_too_many_request_error(Some(
                     format!("throttled in {:?}", wait_time),
                 ));
                 bail!(jsonrpc_error_to_error_object_owned(err))
             }
             ThrottleResult::AlreadyThrottled => {
-                debug!("RPC {} already throttled", name);
+                debug!("RPC {} of {:?} already throttled", method, client);
                 let err = request_rejected_too_many_request_error(Some(
                     "already throttled, please try again later".into(),
                 ));
@@ -62,6 +256,65 @@ impl<S> Throttle<S> {
             }
         }
     }
+
+    fn throttle(&self, client: ClientKey, method: &str) -> ThrottleResult {
+        let method_bucket = self.templates.get(method).is_some();
+        if !method_bucket && self.total.is_none() {
+            return ThrottleResult::Success;
+        }
+
+        // Both buckets are checked before taking the tokens from either, so
+        // a call rejected by one bucket does not consume the other. The
+        // buckets of a client are always locked in this order.
+        let buckets = self.client_buckets(client);
+        let cost = self.costs.get(method).copied().unwrap_or(1);
+        let mut total = buckets.total.as_ref().map(|total| total.lock());
+        let method_bucket = buckets.methods.get(method);
+        let mut method_bucket =
+            method_bucket.as_ref().map(|bucket| bucket.lock());
+
+        if let Some(total) = &mut total {
+            if !total.can_acquire(cost, 0) {
+                return total.throttle(cost, 0);
+            }
+        }
+        if let Some(bucket) = &mut method_bucket {
+            if !bucket.can_acquire_default() {
+                return bucket.throttle_default();
+            }
+        }
+
+        if let Some(total) = &mut total {
+            total.throttle(cost, 0);
+        }
+        if let Some(bucket) = &mut method_bucket {
+            bucket.throttle_default();
+        }
+        ThrottleResult::Success
+    }
+}
+
+#[derive(Clone)]
+pub struct Throttle<S> {
+    service: S,
+    manager: Arc<ThrottleManager>,
+}
+
+impl<S> Throttle<S> {
+    pub fn new(manager: Arc<ThrottleManager>, s: S) -> Self {
+        Throttle {
+            service: s,
+            manager,
+        }
+    }
+
+    pub fn before(&self, req: &Request) -> RpcResult<()> {
+        self.manager.throttle_call(
+            req.extensions().get::<ApiKey>(),
+            req.extensions().get::<RemoteAddr>(),
+            req.method_name(),
+        )
+    }
 }
 
 impl<S> RpcServiceT for Throttle<S>
@@ -79,7 +332,7 @@ where S: RpcServiceT<MethodResponse = MethodResponse>
         &self, req: Request<'a>,
     ) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
         let service = self.service.clone();
-        let throlltle_result = self.before(&req.method_name().to_string());
+        let throlltle_result = self.before(&req);
         match throlltle_result {
             Ok(_) => {
                 debug!("throttle interceptor: method `{}` success", req.method);
@@ -107,3 +360,222 @@ where S: RpcServiceT<MethodResponse = MethodResponse>
         self.service.notification(n)
     }
 }
+
+/// Http middleware which sets the [`ApiKey`] of the requests from the
+/// configured header.
+#[derive(Clone, Debug)]
+pub struct ApiKeyLayer {
+    header: Option<Arc<str>>,
+}
+
+impl ApiKeyLayer {
+    pub fn new(header: Option<&str>) -> Self {
+        Self {
+            header: header.map(Arc::from),
+        }
+    }
+}
+
+impl<S> Layer<S> for ApiKeyLayer {
+    type Service = ApiKeyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyService {
+            header: self.header.clone(),
+            inner,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ApiKeyService<S> {
+    header: Option<Arc<str>>,
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for ApiKeyService<S>
+where S: Service<HttpRequest<B>>
+{
+    type Error = S::Error;
+    type Future = S::Future;
+    type Response = S::Response;
+
+    fn poll_ready(
+        &mut self, cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<B>) -> Self::Future {
+        if let Some(header) = &self.header {
+            let key = req
+                .headers()
+                .get(&**header)
+                .and_then(|value| value.to_str().ok())
+                .filter(|key| !key.is_empty())
+                .map(|key| ApiKey(key.to_string()));
+            if let Some(key) = key {
+                req.extensions_mut().insert(key);
+            }
+        }
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const CONFIG: &str = r#"
+[rpc]
+eth_call="1,1,1,1,0"
+eth_getLogs="5,5,1,1,0"
+
+[rpc.clients]
+api_key_header="x-api-key"
+api_keys=["key"]
+max_clients=2
+total="4,4,1,1,0"
+
+[rpc.clients.costs]
+eth_getLogs=5
+"#;
+
+    fn manager() -> ThrottleManager {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("throttling.toml");
+        std::fs::write(&file, CONFIG).unwrap();
+        ThrottleManager::load(file.to_str().unwrap(), "rpc").unwrap()
+    }
+
+    fn ip(n: u8) -> ClientKey {
+        ClientKey::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, n)))
+    }
+
+    fn is_success(result: ThrottleResult) -> bool {
+        result == ThrottleResult::Success
+    }
+
+    #[test]
+    fn test_load() {
+        let manager = manager();
+        assert_eq!(manager.api_key_header(), Some("x-api-key"));
+        assert!(manager.api_keys.contains("key"));
+        assert_eq!(manager.costs.get("eth_getLogs"), Some(&5));
+        assert!(manager.total.is_some());
+        assert!(manager.templates.get("eth_call").is_some());
+    }
+
+    #[test]
+    fn test_throttle_checks_both_buckets() {
+        let manager = manager();
+
+        assert!(is_success(manager.throttle(ip(1), "eth_call")));
+        // Rejected by the method bucket, the total bucket is not consumed.
+        assert!(!is_success(manager.throttle(ip(1), "eth_call")));
+        for _ in 0..3 {
+            assert!(is_success(manager.throttle(ip(1), "eth_chainId")));
+        }
+        assert!(!is_success(manager.throttle(ip(1), "eth_chainId")));
+
+        // Rejected by the total bucket, the method bucket is not consumed.
+        assert!(!is_success(manager.throttle(ip(2), "eth_getLogs")));
+        let buckets = manager.client_buckets(ip(2));
+        let method_bucket = buckets.methods.get("eth_getLogs").unwrap();
+        assert!(method_bucket.lock().can_acquire(5, 0));
+    }
+
+    #[test]
+    fn test_throttle_per_client() {
+        let manager = manager();
+        let api_key = ClientKey::ApiKey("key".into());
+
+        assert!(is_success(manager.throttle(ip(1), "eth_call")));
+        assert!(!is_success(manager.throttle(ip(1), "eth_call")));
+        assert!(is_success(manager.throttle(api_key.clone(), "eth_call")));
+        assert!(!is_success(manager.throttle(api_key, "eth_call")));
+
+        // At most 2 clients are kept, the least recently seen one is dropped
+        // and gets new buckets.
+        assert!(is_success(manager.throttle(ip(3), "eth_call")));
+        assert!(is_success(manager.throttle(ip(1), "eth_call")));
+    }
+
+    #[test]
+    fn test_throttle_call() {
+        let manager = manager();
+        let addr = RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 8545)));
+        let api_key = ApiKey("key".into());
+
+        assert!(manager.throttle_call(None, Some(&addr), "eth_call").is_ok());
+        assert!(manager
+            .throttle_call(None, Some(&addr), "eth_call")
+            .is_err());
+        // The client is identified by the api key if sent.
+        assert!(manager
+            .throttle_call(Some(&api_key), Some(&addr), "eth_call")
+            .is_ok());
+        assert!(manager
+            .throttle_call(Some(&api_key), None, "eth_call")
+            .is_err());
+        // The calls share the buckets of the clients of the rpc servers.
+        assert!(!is_success(manager.throttle(ip(1), "eth_call")));
+    }
+
+    #[test]
+    fn test_client_key() {
+        let manager = manager();
+        let addr = RemoteAddr(SocketAddr::from(([10, 0, 0, 1], 8545)));
+
+        let known = ApiKey("key".into());
+        assert_eq!(
+            manager.client_key(Some(&known), Some(&addr)),
+            ClientKey::ApiKey("key".into())
+        );
+        // An unknown api key does not get its own buckets.
+        let unknown = ApiKey("other".into());
+        assert_eq!(manager.client_key(Some(&unknown), Some(&addr)), ip(1));
+        assert_eq!(
+            manager.client_key(Some(&unknown), None),
+            ClientKey::Unknown
+        );
+        assert!(manager
+            .throttle_call(Some(&unknown), Some(&addr), "eth_call")
+            .is_ok());
+        assert!(manager
+            .throttle_call(Some(&known), Some(&addr), "eth_call")
+            .is_ok());
+        assert!(manager
+            .throttle_call(None, Some(&addr), "eth_call")
+            .is_err());
+
+        // The IPv6 clients are identified by their /64 prefix.
+        let v6 = |addr: &str| {
+            let ip: IpAddr = addr.parse().unwrap();
+            manager.client_key(None, Some(&RemoteAddr((ip, 8545).into())))
+        };
+        assert_eq!(v6("2001:db8:1:2::1"), v6("2001:db8:1:2:ffff::2"));
+        assert_ne!(v6("2001:db8:1:2::1"), v6("2001:db8:1:3::1"));
+        assert_eq!(v6("::ffff:10.0.0.1"), ip(1));
+    }
+
+    #[test]
+    fn test_unthrottled_methods() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("throttling.toml");
+        std::fs::write(&file, "[rpc]\neth_call=\"1,1,1,1,0\"\n").unwrap();
+        let manager =
+            ThrottleManager::load(file.to_str().unwrap(), "rpc").unwrap();
+
+        for _ in 0..10 {
+            assert!(is_success(manager.throttle(ip(1), "eth_chainId")));
+        }
+        // No buckets are kept for the calls without any bucket.
+        assert!(manager.clients.lock().is_empty());
+        assert!(is_success(manager.throttle(ClientKey::Unknown, "eth_call")));
+        assert!(!is_success(
+            manager.throttle(ClientKey::Unknown, "eth_call")
+        ));
+    }
+}
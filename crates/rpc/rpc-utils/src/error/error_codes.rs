@@ -67,6 +67,9 @@ pub const REQUEST_REJECTED_TOO_MANY_REQUESTS: i64 = -32072;
 /// This is mostly an application error but it's generic enough to define it
 /// here.
 pub const REQUEST_REJECTED_LIMIT_DATA: i64 = -32041;
+/// The method requires a valid JWT in the `Authorization` header of the
+/// request.
+pub const UNAUTHORIZED: i64 = -32079;
 
 /* Conflux node status related error codes
  *
@@ -115,7 +118,6 @@ pub const ACCOUNT_LOCKED: i64 = -32020;
 // FIXME: why didn't we use this error code?
 #[cfg(any(test, feature = "accounts"))]
 pub const PASSWORD_INVALID: i64 = -32021;
-// FIXME: why didn't we use this error code?
 pub const ACCOUNT_ERROR: i64 = -32023;
 /// Encoding error happened in signing structured data. Related to EIP712.
 pub const ENCODING_ERROR: i64 = -32058;
@@ -156,6 +156,24 @@ pub fn pivot_assumption_failed(expected: H256, got: H256) -> Error {
     }
 }
 
+/// Constructs the error of failing to encode the typed data to sign.
+pub fn encoding_error(details: impl fmt::Display) -> Error {
+    Error {
+        code: ErrorCode::ServerError(codes::ENCODING_ERROR),
+        message: "Encoding error".into(),
+        data: Some(Value::String(details.to_string())),
+    }
+}
+
+/// Constructs the error of failing to sign with a local account.
+pub fn account_error(details: impl fmt::Display) -> Error {
+    Error {
+        code: ErrorCode::ServerError(codes::ACCOUNT_ERROR),
+        message: "Account error".into(),
+        data: Some(Value::String(details.to_string())),
+    }
+}
+
 pub fn error_object_owned_to_jsonrpc_error(e: ErrorObjectOwned) -> Error {
     Error {
         code: ErrorCode::from(e.code() as i64),
@@ -6,6 +6,7 @@ use jsonrpsee::types::error::{
     INVALID_PARAMS_CODE, INVALID_REQUEST_CODE,
 };
 use serde::Serialize;
+use std::fmt;
 
 pub fn invalid_params_msg(param: &str) -> ErrorObjectOwned {
     let data: Option<bool> = None;
@@ -83,6 +84,32 @@ pub fn request_rejected_in_catch_up_mode(
     )
 }
 
+pub fn unauthorized(method: &str) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(
+        codes::UNAUTHORIZED as i32,
+        "Unauthorized",
+        Some(format!("method {} requires a valid JWT", method)),
+    )
+}
+
+/// Constructs the error of failing to encode the typed data to sign.
+pub fn encoding_error(details: impl fmt::Display) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(
+        codes::ENCODING_ERROR as i32,
+        "Encoding error",
+        Some(details.to_string()),
+    )
+}
+
+/// Constructs the error of failing to sign with a local account.
+pub fn account_error(details: impl fmt::Display) -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(
+        codes::ACCOUNT_ERROR as i32,
+        "Account error",
+        Some(details.to_string()),
+    )
+}
+
 pub fn pivot_assumption_failed(expected: H256, got: H256) -> ErrorObjectOwned {
     ErrorObjectOwned::owned(
         codes::CONFLUX_PIVOT_CHAIN_UNSTABLE as i32,
@@ -22,7 +22,7 @@ pub enum ThrottleResult {
     AlreadyThrottled,
 }
 
-#[derive(DeriveMallocSizeOf)]
+#[derive(DeriveMallocSizeOf, Clone)]
 pub struct ThrottleTokens {
     max_tokens: u64,    // maximum tokens allowed in bucket
     cur_tokens: u64,    // current tokens in bucket
@@ -43,7 +43,7 @@ impl ThrottleTokens {
     }
 }
 
-#[derive(DeriveMallocSizeOf)]
+#[derive(DeriveMallocSizeOf, Clone)]
 pub struct TokenBucket {
     cpu_tokens: ThrottleTokens,
     message_size_tokens: ThrottleTokens,
@@ -125,6 +125,17 @@ impl TokenBucket {
         self.max_throttled_counter = max_throttled_counter;
     }
 
+    /// Creates a bucket with the same settings and tokens, which starts
+    /// recharging from now.
+    pub fn renew(&self) -> Self {
+        TokenBucket {
+            last_update: Instant::now(),
+            throttled_until: None,
+            throttled_counter: 0,
+            ..self.clone()
+        }
+    }
+
     fn refresh(&mut self, now: Instant) {
         let elapsed_secs = (now - self.last_update).as_secs();
         if elapsed_secs == 0 {
@@ -185,6 +196,28 @@ impl TokenBucket {
         Err(self.last_update + Duration::from_secs(recharge_secs) - now)
     }
 
+    /// Returns whether [`Self::throttle`] would acquire the tokens now,
+    /// without acquiring them.
+    pub fn can_acquire(
+        &mut self, cpu_cost: u64, message_size_cost: u64,
+    ) -> bool {
+        let now = Instant::now();
+        if self.throttled_until.map_or(false, |until| now < until) {
+            return false;
+        }
+
+        self.refresh(now);
+        cpu_cost <= self.cpu_tokens.cur_tokens
+            && message_size_cost <= self.message_size_tokens.cur_tokens
+    }
+
+    pub fn can_acquire_default(&mut self) -> bool {
+        self.can_acquire(
+            self.cpu_tokens.default_cost,
+            self.message_size_tokens.default_cost,
+        )
+    }
+
     pub fn throttle_default(&mut self) -> ThrottleResult {
         self.throttle(
             self.cpu_tokens.default_cost,
@@ -271,6 +304,20 @@ impl TokenBucketManager {
         self.buckets.get(name).cloned()
     }
 
+    /// Creates a manager with a renewed copy of every bucket, e.g. to throttle
+    /// another client with the same settings.
+    pub fn renew(&self) -> Self {
+        TokenBucketManager {
+            buckets: self
+                .buckets
+                .iter()
+                .map(|(name, bucket)| {
+                    (name.clone(), Arc::new(Mutex::new(bucket.lock().renew())))
+                })
+                .collect(),
+        }
+    }
+
     pub fn load(
         toml_file: &str, section: Option<&str>,
     ) -> Result<Self, String> {
@@ -292,6 +339,12 @@ impl TokenBucketManager {
         let mut manager = TokenBucketManager::default();
 
         for (k, v) in table.iter() {
+            // Sub tables hold the options of the section user, e.g. the per
+            // client throttling of rpc.
+            if v.is_table() {
+                continue;
+            }
+
             let v = match v.as_str() {
                 Some(v) => v,
                 None => {
@@ -424,6 +477,32 @@ mod tests {
         assert_eq!(bucket.throttled_counter, 0);
     }
 
+    #[test]
+    fn test_renew() {
+        let mut bucket = TokenBucket::full(3, 1, 1, 3, 1, 1);
+        let template = bucket.renew();
+        assert_eq!(bucket.try_acquire_cost(3, 3), Ok(()));
+        assert!(bucket.try_acquire_cost(1, 1).is_err());
+
+        // the renewed bucket is not affected
+        let mut renewed = template.renew();
+        assert_eq!(renewed.try_acquire_cost(3, 3), Ok(()));
+    }
+
+    #[test]
+    fn test_can_acquire() {
+        let mut bucket = TokenBucket::full(2, 1, 1, 2, 1, 1);
+        assert!(bucket.can_acquire(2, 2));
+        assert!(bucket.can_acquire(2, 2));
+        assert!(!bucket.can_acquire(3, 1));
+
+        assert_eq!(bucket.throttle(2, 2), ThrottleResult::Success);
+        assert!(!bucket.can_acquire_default());
+        assert!(bucket.throttle_default() != ThrottleResult::Success);
+        // still not allowed when throttled
+        assert!(!bucket.can_acquire(0, 0));
+    }
+
     #[test]
     fn test_tolerate_throttling() {
         // empty bucket
@@ -0,0 +1,534 @@
+//! Hashing of typed structured data for signing, as specified by
+//! [EIP-712](https://eips.ethereum.org/EIPS/eip-712) for eSpace and by
+//! [CIP-23](https://github.com/Conflux-Chain/CIPs/blob/master/CIPs/cip-23.md)
+//! for Core Space.
+
+use cfx_addr::cfx_addr_decode;
+use cfx_types::{Address, H256, U256};
+use keccak_hash::keccak;
+use rustc_hex::FromHex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+/// The standard of the typed data, which decides the name of the domain type
+/// and the accepted address format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Standard {
+    /// EIP-712, addresses are hex encoded.
+    Eip712,
+    /// CIP-23, addresses are base32 encoded, hex is accepted as well.
+    Cip23,
+}
+
+impl Standard {
+    /// The name of the struct type of the domain.
+    pub fn domain_type(&self) -> &'static str {
+        match self {
+            Standard::Eip712 => "EIP712Domain",
+            Standard::Cip23 => "CIP23Domain",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    #[error("unknown type {0}")]
+    UnknownType(String),
+    #[error("invalid type {0}")]
+    InvalidType(String),
+    #[error("missing field {field} of {struct_type}")]
+    MissingField { struct_type: String, field: String },
+    #[error("invalid value of {field}, expected {expected}")]
+    InvalidValue {
+        field: String,
+        expected: &'static str,
+    },
+    #[error("array {field} has {actual} elements, expected {expected}")]
+    InvalidArrayLength {
+        field: String,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+/// A member of a struct type.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemberType {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+}
+
+/// The typed data to sign, in the JSON format of `eth_signTypedData_v4`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    /// The struct types, including the domain type.
+    pub types: BTreeMap<String, Vec<MemberType>>,
+    pub primary_type: String,
+    pub domain: Value,
+    #[serde(default)]
+    pub message: Value,
+}
+
+/// A parsed member type.
+enum Kind<'a> {
+    Bool,
+    Address,
+    Uint(usize),
+    Int(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<Kind<'a>>, Option<usize>),
+    Struct(&'a str),
+}
+
+impl TypedData {
+    /// Returns the hash to sign, `keccak256("\x19\x01" ‖ domainSeparator ‖
+    /// hashStruct(message))`. The message is omitted if the primary type is
+    /// the domain type.
+    pub fn signing_hash(&self, standard: Standard) -> Result<H256, Error> {
+        let domain_type = standard.domain_type();
+        let mut encoded = vec![0x19, 0x01];
+        encoded.extend_from_slice(
+            self.hash_struct(domain_type, &self.domain, standard)?
+                .as_bytes(),
+        );
+        if self.primary_type != domain_type {
+            encoded.extend_from_slice(
+                self.hash_struct(&self.primary_type, &self.message, standard)?
+                    .as_bytes(),
+            );
+        }
+        Ok(keccak(encoded))
+    }
+
+    /// Returns `keccak256(typeHash ‖ encodeData(data))` of the struct type.
+    pub fn hash_struct(
+        &self, struct_type: &str, data: &Value, standard: Standard,
+    ) -> Result<H256, Error> {
+        let mut encoded = self.type_hash(struct_type)?.as_bytes().to_vec();
+        let object = data.as_object().ok_or_else(|| Error::InvalidValue {
+            field: struct_type.to_string(),
+            expected: "object",
+        })?;
+        for member in &self.types[struct_type] {
+            let value = object.get(&member.name).ok_or_else(|| {
+                Error::MissingField {
+                    struct_type: struct_type.to_string(),
+                    field: member.name.clone(),
+                }
+            })?;
+            let kind = self.parse_type(&member.type_)?;
+            encoded.extend_from_slice(
+                self.encode_value(&kind, &member.name, value, standard)?
+                    .as_bytes(),
+            );
+        }
+        Ok(keccak(encoded))
+    }
+
+    /// Returns `keccak256(encodeType(struct_type))`.
+    pub fn type_hash(&self, struct_type: &str) -> Result<H256, Error> {
+        Ok(keccak(self.encode_type(struct_type)?))
+    }
+
+    /// Returns the struct type followed by the struct types it references,
+    /// sorted by name, e.g. `Mail(Person from,Person to,string
+    /// contents)Person(string name,address wallet)`.
+    pub fn encode_type(&self, struct_type: &str) -> Result<String, Error> {
+        let mut deps = BTreeSet::new();
+        self.collect_deps(struct_type, &mut deps)?;
+        deps.remove(struct_type);
+
+        let mut encoded = String::new();
+        for name in std::iter::once(struct_type).chain(deps) {
+            let members: Vec<_> = self.types[name]
+                .iter()
+                .map(|member| format!("{} {}", member.type_, member.name))
+                .collect();
+            encoded.push_str(&format!("{}({})", name, members.join(",")));
+        }
+        Ok(encoded)
+    }
+
+    fn collect_deps<'a>(
+        &'a self, struct_type: &'a str, deps: &mut BTreeSet<&'a str>,
+    ) -> Result<(), Error> {
+        if !deps.insert(struct_type) {
+            return Ok(());
+        }
+        let members = self
+            .types
+            .get(struct_type)
+            .ok_or_else(|| Error::UnknownType(struct_type.to_string()))?;
+        for member in members {
+            let mut kind = self.parse_type(&member.type_)?;
+            while let Kind::Array(inner, _) = kind {
+                kind = *inner;
+            }
+            if let Kind::Struct(name) = kind {
+                self.collect_deps(name, deps)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_type<'a>(&self, type_: &'a str) -> Result<Kind<'a>, Error> {
+        let invalid = || Error::InvalidType(type_.to_string());
+        if let Some(array) = type_.strip_suffix(']') {
+            let open = array.rfind('[').ok_or_else(invalid)?;
+            let len = match &array[open + 1..] {
+                "" => None,
+                len => Some(len.parse().map_err(|_| invalid())?),
+            };
+            let inner = self.parse_type(&array[..open])?;
+            return Ok(Kind::Array(Box::new(inner), len));
+        }
+
+        let kind = match type_ {
+            "bool" => Kind::Bool,
+            "address" => Kind::Address,
+            "bytes" => Kind::Bytes,
+            "string" => Kind::String,
+            _ if self.types.contains_key(type_) => Kind::Struct(type_),
+            _ => {
+                let size = |prefix: &str, step: usize, max: usize| {
+                    let size = type_.strip_prefix(prefix)?;
+                    match size.parse::<usize>() {
+                        Ok(n)
+                            if !size.starts_with('0')
+                                && n % step == 0
+                                && n <= max =>
+                        {
+                            Some(n)
+                        }
+                        _ => None,
+                    }
+                };
+                if let Some(bits) = size("uint", 8, 256) {
+                    Kind::Uint(bits)
+                } else if let Some(bits) = size("int", 8, 256) {
+                    Kind::Int(bits)
+                } else if let Some(len) = size("bytes", 1, 32) {
+                    Kind::FixedBytes(len)
+                } else {
+                    return Err(Error::UnknownType(type_.to_string()));
+                }
+            }
+        };
+        Ok(kind)
+    }
+
+    /// Encodes a member value into 32 bytes: atomic values are padded,
+    /// dynamic values, arrays and structs are hashed.
+    fn encode_value(
+        &self, kind: &Kind, field: &str, value: &Value, standard: Standard,
+    ) -> Result<H256, Error> {
+        let invalid = |expected| Error::InvalidValue {
+            field: field.to_string(),
+            expected,
+        };
+        let encoded = match kind {
+            Kind::Bool => {
+                let value = value.as_bool().ok_or_else(|| invalid("bool"))?;
+                H256::from_low_u64_be(value as u64)
+            }
+            Kind::Address => {
+                let address = parse_address(value, standard)
+                    .ok_or_else(|| invalid("address"))?;
+                let mut encoded = H256::zero();
+                encoded.0[12..].copy_from_slice(address.as_bytes());
+                encoded
+            }
+            Kind::Uint(bits) => {
+                let (negative, n) =
+                    parse_integer(value).ok_or_else(|| invalid("integer"))?;
+                if negative || (*bits < 256 && n >> *bits != U256::zero()) {
+                    return Err(invalid("unsigned integer in range"));
+                }
+                u256_to_h256(n)
+            }
+            Kind::Int(bits) => {
+                let (negative, n) =
+                    parse_integer(value).ok_or_else(|| invalid("integer"))?;
+                let limit = U256::one() << (*bits - 1);
+                if (negative && n > limit) || (!negative && n >= limit) {
+                    return Err(invalid("signed integer in range"));
+                }
+                if negative {
+                    u256_to_h256((!n).overflowing_add(U256::one()).0)
+                } else {
+                    u256_to_h256(n)
+                }
+            }
+            Kind::FixedBytes(len) => {
+                let bytes = value
+                    .as_str()
+                    .and_then(parse_hex)
+                    .filter(|bytes| bytes.len() <= *len)
+                    .ok_or_else(|| invalid("hex bytes"))?;
+                let mut encoded = H256::zero();
+                encoded.0[..bytes.len()].copy_from_slice(&bytes);
+                encoded
+            }
+            Kind::Bytes => {
+                let bytes = value
+                    .as_str()
+                    .and_then(parse_hex)
+                    .ok_or_else(|| invalid("hex bytes"))?;
+                keccak(bytes)
+            }
+            Kind::String => {
+                let value = value.as_str().ok_or_else(|| invalid("string"))?;
+                keccak(value.as_bytes())
+            }
+            Kind::Array(inner, len) => {
+                let values =
+                    value.as_array().ok_or_else(|| invalid("array"))?;
+                if let Some(len) = len {
+                    if values.len() != *len {
+                        return Err(Error::InvalidArrayLength {
+                            field: field.to_string(),
+                            expected: *len,
+                            actual: values.len(),
+                        });
+                    }
+                }
+                let mut encoded = Vec::with_capacity(32 * values.len());
+                for value in values {
+                    encoded.extend_from_slice(
+                        self.encode_value(inner, field, value, standard)?
+                            .as_bytes(),
+                    );
+                }
+                keccak(encoded)
+            }
+            Kind::Struct(struct_type) => {
+                self.hash_struct(struct_type, value, standard)?
+            }
+        };
+        Ok(encoded)
+    }
+}
+
+fn u256_to_h256(n: U256) -> H256 {
+    let mut encoded = H256::zero();
+    n.to_big_endian(&mut encoded.0);
+    encoded
+}
+
+fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    value.strip_prefix("0x").unwrap_or(value).from_hex().ok()
+}
+
+/// Parses a hex or base32 (CIP-23 only) address.
+fn parse_address(value: &Value, standard: Standard) -> Option<Address> {
+    let value = value.as_str()?;
+    if standard == Standard::Cip23 && value.contains(':') {
+        return cfx_addr_decode(value).ok()?.hex_address;
+    }
+    parse_hex(value)
+        .filter(|bytes| bytes.len() == Address::len_bytes())
+        .map(|bytes| Address::from_slice(&bytes))
+}
+
+/// Parses a JSON number, or a decimal or `0x` prefixed hex string, into its
+/// sign and magnitude.
+fn parse_integer(value: &Value) -> Option<(bool, U256)> {
+    match value {
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => Some((false, n.into())),
+            (None, Some(n)) => Some((true, n.unsigned_abs().into())),
+            _ => None,
+        },
+        Value::String(s) => {
+            let (negative, s) = match s.strip_prefix('-') {
+                Some(s) => (true, s),
+                None => (false, s.as_str()),
+            };
+            let n = match s.strip_prefix("0x") {
+                Some(hex) if !hex.is_empty() => U256::from_str(hex).ok()?,
+                Some(_) => return None,
+                None => U256::from_dec_str(s).ok()?,
+            };
+            Some((negative && !n.is_zero(), n))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mail() -> TypedData {
+        serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {
+                    "name": "Cow",
+                    "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+                },
+                "to": {
+                    "name": "Bob",
+                    "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"
+                },
+                "contents": "Hello, Bob!"
+            }
+        }))
+        .unwrap()
+    }
+
+    fn h256(hex: &str) -> H256 { H256::from_str(hex).unwrap() }
+
+    #[test]
+    fn test_eip712_example() {
+        let data = mail();
+        assert_eq!(
+            data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string \
+             name,address wallet)"
+        );
+        assert_eq!(
+            data.type_hash("Mail").unwrap(),
+            h256("a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2")
+        );
+        assert_eq!(
+            data.hash_struct("EIP712Domain", &data.domain, Standard::Eip712)
+                .unwrap(),
+            h256("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f")
+        );
+        assert_eq!(
+            data.hash_struct("Mail", &data.message, Standard::Eip712)
+                .unwrap(),
+            h256("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e")
+        );
+        assert_eq!(
+            data.signing_hash(Standard::Eip712).unwrap(),
+            h256("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+        );
+    }
+
+    #[test]
+    fn test_cip23_domain() {
+        let mut data = mail();
+        // the domain type must be named `CIP23Domain`
+        assert_eq!(
+            data.signing_hash(Standard::Cip23),
+            Err(Error::UnknownType("CIP23Domain".into()))
+        );
+
+        let domain = data.types.remove("EIP712Domain").unwrap();
+        data.types.insert("CIP23Domain".into(), domain);
+        let hex_hash = data.signing_hash(Standard::Cip23).unwrap();
+
+        let wallet = Address::from_str(
+            "CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+                .to_lowercase()
+                .as_str(),
+        )
+        .unwrap();
+        data.message["from"]["wallet"] = json!(cfx_addr::cfx_addr_encode(
+            wallet.as_bytes(),
+            cfx_addr::Network::Main,
+            cfx_addr::EncodingOptions::Simple,
+        )
+        .unwrap());
+        assert_eq!(data.signing_hash(Standard::Cip23).unwrap(), hex_hash);
+        assert!(data.signing_hash(Standard::Eip712).is_err());
+    }
+
+    #[test]
+    fn test_arrays() {
+        let data: TypedData = serde_json::from_value(json!({
+            "types": {
+                "EIP712Domain": [{ "name": "name", "type": "string" }],
+                "Group": [
+                    { "name": "members", "type": "address[]" },
+                    { "name": "scores", "type": "int8[2][]" }
+                ]
+            },
+            "primaryType": "Group",
+            "domain": { "name": "Groups" },
+            "message": {
+                "members": ["0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"],
+                "scores": [[-128, "127"], [0, "0x10"]]
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            data.encode_type("Group").unwrap(),
+            "Group(address[] members,int8[2][] scores)"
+        );
+        assert!(data.signing_hash(Standard::Eip712).is_ok());
+
+        let mut invalid = data.clone();
+        invalid.message["scores"] = json!([[-129, 0]]);
+        assert!(matches!(
+            invalid.signing_hash(Standard::Eip712),
+            Err(Error::InvalidValue { .. })
+        ));
+        invalid.message["scores"] = json!([[0]]);
+        assert!(matches!(
+            invalid.signing_hash(Standard::Eip712),
+            Err(Error::InvalidArrayLength { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_types() {
+        let mut data = mail();
+        data.types.get_mut("Mail").unwrap()[2].type_ = "Letter".into();
+        assert_eq!(
+            data.signing_hash(Standard::Eip712),
+            Err(Error::UnknownType("Letter".into()))
+        );
+
+        let mut data = mail();
+        data.types.get_mut("Mail").unwrap()[2].type_ = "uint7".into();
+        assert_eq!(
+            data.signing_hash(Standard::Eip712),
+            Err(Error::UnknownType("uint7".into()))
+        );
+
+        let mut data = mail();
+        data.message["to"].as_object_mut().unwrap().remove("wallet");
+        assert_eq!(
+            data.signing_hash(Standard::Eip712),
+            Err(Error::MissingField {
+                struct_type: "Person".into(),
+                field: "wallet".into(),
+            })
+        );
+    }
+}
@@ -362,6 +362,8 @@ fn main() {
             era_epoch_count,
             pow,
             sync.machine().vm_factory(),
+            &Default::default(),
+            &Default::default(),
         );
 
     println!("Checkpoint generated in the process. Going to test the last checkpoint recovery, genesis hash {} stable hash {}.", genesis_hash, stable_hash);
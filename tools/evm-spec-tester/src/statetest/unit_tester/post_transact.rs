@@ -101,6 +101,10 @@ fn match_fail_single_reason(reason: &str, outcome: TestOutcome<'_>) -> bool {
     use ExecutionOutcome::*;
     use TestOutcome::*;
     match reason {
+        "TransactionException.GAS_LIMIT_EXCEEDS_MAXIMUM" => matches!(
+            outcome,
+            Consensus(TransactionError::GasLimitExceeded { .. })
+        ),
         "TransactionException.INITCODE_SIZE_EXCEEDED" => matches!(
             outcome,
             Consensus(TransactionError::CreateInitCodeSizeLimit)
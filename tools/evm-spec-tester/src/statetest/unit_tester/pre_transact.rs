@@ -45,6 +45,8 @@ pub fn make_machine_verify_conf(
         Arc::new(Machine::new_with_builtin(
             config.common_params(),
             vm_factory,
+            &config.internal_contract_plugins,
+            &config.precompile_plugins,
         ))
     };
     let verification = config.verification_config(machine.clone());
@@ -62,10 +62,12 @@ pub(crate) fn default_raw_configuration() -> RawConfiguration {
 pub(crate) fn set_cips_according_to_spec(
     conf: &mut Configuration, spec: &SpecName,
 ) {
-    // set osaka opcode transition height according to spec
-    if spec >= &SpecName::Osaka {
-        conf.raw_conf.osaka_opcode_transition_height = Some(1);
+    // set osaka transition heights according to spec
+    let osaka_height = if spec >= &SpecName::Osaka {
+        Some(1)
     } else {
-        conf.raw_conf.osaka_opcode_transition_height = Some(u64::MAX);
-    }
+        Some(u64::MAX)
+    };
+    conf.raw_conf.osaka_opcode_transition_height = osaka_height;
+    conf.raw_conf.osaka_transition_height = osaka_height;
 }